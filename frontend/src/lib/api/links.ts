@@ -126,9 +126,11 @@ export const linksApi = {
    * @returns Analytics response with clicks over time, referrers, countries, user agents
    */
   async getAnalytics(id: string, days: number): Promise<LinkAnalyticsResponse> {
-    // Backend now calculates timestamps to eliminate clock skew issues
+    // Backend now calculates timestamps to eliminate clock skew issues.
+    // Send the local UTC offset so daily buckets match the viewer's days.
+    const tzOffsetMinutes = -new Date().getTimezoneOffset();
     return apiClient.get<LinkAnalyticsResponse>(
-      `/api/links/${id}/analytics?days=${days}`
+      `/api/links/${id}/analytics?days=${days}&tz_offset_minutes=${tzOffsetMinutes}`
     );
   },
 
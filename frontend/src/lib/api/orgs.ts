@@ -91,11 +91,12 @@ export const orgsApi = {
   async deleteOrg(
     org_id: string,
     action: "delete" | "migrate",
-    target_org_id?: string
+    target_org_id?: string,
+    confirm_link_count?: number
   ): Promise<{ success: boolean; switched_to_org: OrgWithRole }> {
     return apiClient.delete<{ success: boolean; switched_to_org: OrgWithRole }>(
       `/api/orgs/${org_id}`,
-      { action, target_org_id }
+      { action, target_org_id, confirm_link_count }
     );
   },
 
@@ -180,6 +180,7 @@ export interface LinkAnalyticsResponse {
   top_user_agents: UserAgentCount[];
   analytics_gated?: boolean;
   gated_reason?: string;
+  sample_rate?: number;
 }
 
 export interface TopLinkCount {
@@ -217,6 +218,7 @@ export interface UsageResponse {
   usage: {
     links_created_this_month: number;
     tags_count: number;
+    custom_domains_count: number;
   };
   next_reset?: {
     utc: string;
@@ -0,0 +1,16 @@
+-- Migration 0042: Org-level destination rewrite rules
+-- Each rule rewrites link destinations that start with `from_pattern` by replacing
+-- that prefix with `to_pattern` at redirect time (e.g. after moving docs to a new domain).
+-- Rules are cached per org in KV under `org_rewrite_rules:{org_id}` for the redirect hot path.
+
+CREATE TABLE org_rewrite_rules (
+  id TEXT PRIMARY KEY NOT NULL,
+  org_id TEXT NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  from_pattern TEXT NOT NULL,
+  to_pattern TEXT NOT NULL,
+  created_by TEXT NOT NULL,
+  created_at INTEGER NOT NULL,
+  UNIQUE (org_id, from_pattern)
+) STRICT;
+
+CREATE INDEX idx_org_rewrite_rules_org ON org_rewrite_rules(org_id, created_at);
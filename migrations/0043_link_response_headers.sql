@@ -0,0 +1,4 @@
+-- Migration 0043: Per-link custom response headers
+-- JSON object of header name -> value applied to the redirect response
+-- (e.g. {"link": "<https://cdn.example.com>; rel=preconnect"}). NULL = none.
+ALTER TABLE links ADD COLUMN response_headers TEXT;
@@ -0,0 +1,4 @@
+-- Migration 0044: Per-org branded interstitial page
+-- When > 0, redirects for the org's links show a short "You're leaving via <org>"
+-- page and continue to the destination after this many seconds. 0 = immediate redirect.
+ALTER TABLE organizations ADD COLUMN interstitial_delay_seconds INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,37 @@
+-- Migration 0045: Domain -> tag suggestion mapping
+-- Used by GET /api/links/suggest-tags to propose tags from a destination's domain.
+-- A domain also matches its subdomains (gist.github.com -> github.com).
+-- Edit this table to change the global suggestions.
+CREATE TABLE IF NOT EXISTS domain_tag_suggestions (
+  domain TEXT NOT NULL,
+  tag_name TEXT NOT NULL,
+  PRIMARY KEY (domain, tag_name)
+) STRICT;
+
+INSERT OR IGNORE INTO domain_tag_suggestions (domain, tag_name) VALUES
+  ('github.com', 'dev'),
+  ('gitlab.com', 'dev'),
+  ('bitbucket.org', 'dev'),
+  ('stackoverflow.com', 'dev'),
+  ('npmjs.com', 'dev'),
+  ('crates.io', 'dev'),
+  ('youtube.com', 'video'),
+  ('youtu.be', 'video'),
+  ('vimeo.com', 'video'),
+  ('twitch.tv', 'video'),
+  ('x.com', 'social'),
+  ('twitter.com', 'social'),
+  ('linkedin.com', 'social'),
+  ('facebook.com', 'social'),
+  ('instagram.com', 'social'),
+  ('reddit.com', 'social'),
+  ('medium.com', 'blog'),
+  ('substack.com', 'blog'),
+  ('docs.google.com', 'docs'),
+  ('notion.so', 'docs'),
+  ('figma.com', 'design'),
+  ('dribbble.com', 'design'),
+  ('amazon.com', 'shopping'),
+  ('etsy.com', 'shopping'),
+  ('spotify.com', 'music'),
+  ('soundcloud.com', 'music');
@@ -0,0 +1,5 @@
+-- Migration 0046: Allowed destination URL schemes
+-- Comma-separated list of schemes link destinations may use.
+-- Supported values: http, https, mailto, tel. Defaults to web URLs only.
+INSERT OR IGNORE INTO settings (key, value, updated_at)
+VALUES ('allowed_destination_schemes', 'http,https', 0);
@@ -0,0 +1,5 @@
+-- Migration 0047: Optional org name uniqueness per billing account
+-- When 'true', creating or renaming an org to a name already used by another
+-- org in the same billing account (case-insensitive) is rejected with 409.
+INSERT OR IGNORE INTO settings (key, value, updated_at)
+VALUES ('unique_org_names_per_billing_account', 'false', 0);
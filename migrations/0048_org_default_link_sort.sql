@@ -0,0 +1,4 @@
+-- Migration 0048: Per-org default sort for the links list
+-- Used by GET /api/links when the request has no `sort` parameter.
+-- One of: created, updated, clicks, title, code.
+ALTER TABLE organizations ADD COLUMN default_link_sort TEXT NOT NULL DEFAULT 'created';
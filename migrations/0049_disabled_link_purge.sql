@@ -0,0 +1,9 @@
+-- Migration 0049: Optional purge of long-disabled links
+-- disabled_link_purge_days: hard-delete links disabled (and unchanged) for at
+-- least this many days. '0' = off, so nothing is deleted unless configured.
+-- disabled_link_purge_require_zero_clicks: only purge links with no clicks
+-- recorded during that period.
+INSERT OR IGNORE INTO settings (key, value, updated_at)
+VALUES ('disabled_link_purge_days', '0', 0);
+INSERT OR IGNORE INTO settings (key, value, updated_at)
+VALUES ('disabled_link_purge_require_zero_clicks', 'true', 0);
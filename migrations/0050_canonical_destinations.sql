@@ -0,0 +1,9 @@
+-- Migration 0050: Optional destination canonicalization on link creation
+-- raw_destination keeps the URL as submitted when POST /api/links is called
+-- with canonicalize=true (destination_url then holds the canonical form).
+ALTER TABLE links ADD COLUMN raw_destination TEXT;
+
+-- Comma-separated tracking parameters stripped during canonicalization.
+-- A trailing '*' matches any parameter with that prefix.
+INSERT OR IGNORE INTO settings (key, value, updated_at)
+VALUES ('canonicalize_strip_params', 'utm_*,fbclid', 0);
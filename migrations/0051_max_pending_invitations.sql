@@ -0,0 +1,5 @@
+-- Migration 0051: Cap on pending invitations per organization
+-- max_pending_invitations_per_org: invitations that are neither accepted nor
+-- expired count towards the cap; new invitations are rejected once reached.
+INSERT OR IGNORE INTO settings (key, value, updated_at)
+VALUES ('max_pending_invitations_per_org', '100', 0);
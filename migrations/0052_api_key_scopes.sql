@@ -0,0 +1,4 @@
+-- Migration 0052: Scopes for API keys
+-- Comma-separated scope names (links:read, links:write, analytics:read, admin).
+-- NULL means the key predates scopes and keeps full (admin) access.
+ALTER TABLE api_keys ADD COLUMN scopes TEXT;
@@ -0,0 +1,5 @@
+-- Migration 0053: Optional revocation of inactive API keys
+-- api_key_inactive_revoke_days: revoke active keys not used (or, if never
+-- used, not created) within this many days. '0' = off.
+INSERT OR IGNORE INTO settings (key, value, updated_at)
+VALUES ('api_key_inactive_revoke_days', '0', 0);
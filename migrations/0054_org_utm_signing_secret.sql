@@ -0,0 +1,3 @@
+-- Migration 0054: Per-org secret for signed UTM override tokens
+-- NULL = signed UTM overrides disabled (the `u` query param is ignored)
+ALTER TABLE organizations ADD COLUMN utm_signing_secret TEXT;
@@ -0,0 +1,2 @@
+-- Migration 0055: Remember the OAuth provider of each user's latest login
+ALTER TABLE users ADD COLUMN last_login_provider TEXT;
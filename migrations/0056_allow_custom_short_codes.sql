@@ -0,0 +1,5 @@
+-- Migration 0056: Instance-wide switch for custom short codes
+-- allow_custom_short_codes: 'false' rejects any caller-chosen short_code on
+-- link creation, regardless of tier. Default 'true' keeps tier-based access.
+INSERT OR IGNORE INTO settings (key, value, updated_at)
+VALUES ('allow_custom_short_codes', 'true', 0);
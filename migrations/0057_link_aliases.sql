@@ -0,0 +1,17 @@
+-- Migration 0057: Alias codes
+-- Extra short codes that redirect to an existing link and share its
+-- analytics. Each alias is also written to KV as a pointer to the link's
+-- primary short code, so status and destination changes apply to it too.
+CREATE TABLE link_aliases (
+  alias_code TEXT PRIMARY KEY NOT NULL,
+  link_id TEXT NOT NULL REFERENCES links(id) ON DELETE CASCADE,
+  org_id TEXT NOT NULL,
+  created_by TEXT NOT NULL,
+  created_at INTEGER NOT NULL
+) STRICT;
+
+CREATE INDEX idx_link_aliases_link ON link_aliases(link_id, created_at);
+CREATE INDEX idx_link_aliases_org ON link_aliases(org_id);
+
+-- Which alias a click came through; NULL = the link's primary short code
+ALTER TABLE analytics_events ADD COLUMN alias_code TEXT;
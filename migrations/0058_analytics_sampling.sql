@@ -0,0 +1,12 @@
+-- Migration 0058: Soft cap on raw analytics events per link
+-- Beyond analytics_event_soft_cap clicks on a link, only 1 in
+-- analytics_sample_rate clicks is stored as a raw event. sample_rate records
+-- how many clicks each stored event stands for (1 = not sampled), so
+-- aggregates can be scaled back up. click_count stays exact.
+ALTER TABLE analytics_events ADD COLUMN sample_rate INTEGER NOT NULL DEFAULT 1;
+
+INSERT OR IGNORE INTO settings (key, value, updated_at)
+VALUES ('analytics_event_soft_cap', '1000000', 0);
+
+INSERT OR IGNORE INTO settings (key, value, updated_at)
+VALUES ('analytics_sample_rate', '10', 0);
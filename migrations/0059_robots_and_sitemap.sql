@@ -0,0 +1,8 @@
+-- Migration 0059: robots.txt and opt-in sitemap on the redirect domain
+-- robots_txt overrides the default body served at /robots.txt on the
+-- redirect domain (empty = built-in default that disallows crawling).
+-- public_sitemap lets an org list its active links in /sitemap.xml.
+ALTER TABLE organizations ADD COLUMN public_sitemap INTEGER NOT NULL DEFAULT 0;
+
+INSERT OR IGNORE INTO settings (key, value, updated_at)
+VALUES ('robots_txt', '', 0);
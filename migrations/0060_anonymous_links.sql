@@ -0,0 +1,21 @@
+-- Migration 0060: Anonymous link creation and claiming
+-- When anonymous_link_creation_enabled is on, POST /api/links/anonymous
+-- creates links without an account. They are held by a placeholder org and
+-- user (links.org_id / created_by are foreign keys) until someone signs in and
+-- claims them with the returned token via POST /api/links/claim.
+-- claim_token_hash is the SHA-256 of that token; it is kept after claiming so
+-- a reused token is reported as already claimed.
+ALTER TABLE links ADD COLUMN claim_token_hash TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_links_claim_token_hash
+  ON links(claim_token_hash)
+  WHERE claim_token_hash IS NOT NULL;
+
+INSERT OR IGNORE INTO organizations (id, name, slug, created_at, created_by)
+VALUES ('anonymous', 'Unclaimed links', '__anonymous__', 0, 'anonymous');
+
+INSERT OR IGNORE INTO users (id, email, name, oauth_provider, oauth_id, org_id, role, created_at)
+VALUES ('anonymous', 'anonymous@rushomon.invalid', 'Anonymous', 'system', 'anonymous', 'anonymous', 'member', 0);
+
+INSERT OR IGNORE INTO settings (key, value, updated_at)
+VALUES ('anonymous_link_creation_enabled', 'false', 0);
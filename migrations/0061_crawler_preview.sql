@@ -0,0 +1,5 @@
+-- Migration 0061: opt-in OpenGraph page for link-preview crawlers
+-- When set, requests from link-preview crawlers (Slack, Twitter, Facebook, ...)
+-- get a 200 HTML page with OpenGraph/Twitter tags for the destination instead
+-- of the redirect. Browsers keep getting the redirect.
+ALTER TABLE organizations ADD COLUMN crawler_preview INTEGER NOT NULL DEFAULT 0;
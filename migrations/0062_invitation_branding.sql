@@ -0,0 +1,5 @@
+-- Migration 0062: per-org branding for invitation emails
+-- invite_subject replaces the default invitation subject; invite_message is
+-- shown as a note from the org in the email. NULL = default wording.
+ALTER TABLE organizations ADD COLUMN invite_subject TEXT;
+ALTER TABLE organizations ADD COLUMN invite_message TEXT;
@@ -0,0 +1,6 @@
+-- Migration 0063: optional click deduplication window
+-- When click_dedup_window_seconds > 0, repeat clicks by the same visitor
+-- (IP + User-Agent) on a link within that many seconds are not recorded as
+-- events and do not increment the click counters. 0 = count every click.
+INSERT OR IGNORE INTO settings (key, value, updated_at)
+VALUES ('click_dedup_window_seconds', '0', 0);
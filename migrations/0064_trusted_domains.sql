@@ -0,0 +1,7 @@
+-- Migration 0064: interstitial for untrusted destinations
+-- When untrusted_interstitial is set, links whose destination host is not in
+-- trusted_domains (a JSON array of hostnames; subdomains match too) show the
+-- interstitial with an external-site warning. Trusted destinations redirect
+-- directly.
+ALTER TABLE organizations ADD COLUMN untrusted_interstitial INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE organizations ADD COLUMN trusted_domains TEXT;
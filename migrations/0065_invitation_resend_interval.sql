@@ -0,0 +1,8 @@
+-- Migration 0065: minimum interval between invitation resends
+-- last_resent_at records the latest resend of an invitation email; a resend
+-- within invitation_resend_interval_seconds of it is rejected with 429.
+-- 0 = no cooldown.
+ALTER TABLE org_invitations ADD COLUMN last_resent_at INTEGER;
+
+INSERT OR IGNORE INTO settings (key, value, updated_at)
+VALUES ('invitation_resend_interval_seconds', '300', 0);
@@ -0,0 +1,4 @@
+-- Migration 0066: optional per-organization monthly link quota
+-- When set, an org may create at most org_link_quota links per calendar month
+-- (UTC), on top of the billing account's tier limit. NULL = no org-level cap.
+ALTER TABLE organizations ADD COLUMN org_link_quota INTEGER;
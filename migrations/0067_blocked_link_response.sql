@@ -0,0 +1,7 @@
+-- Migration 0067: configurable response for blocked links
+-- Blocked links now keep a tombstone mapping in KV so the redirect path can
+-- tell them apart from missing codes. blocked_link_response picks what is served:
+-- 'not_found' (redirect to /404, previous behaviour), 'gone' (410 page) or
+-- 'unavailable' (451 page).
+INSERT OR IGNORE INTO settings (key, value, updated_at)
+VALUES ('blocked_link_response', 'not_found', 0);
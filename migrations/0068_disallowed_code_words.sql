@@ -0,0 +1,13 @@
+-- Migration 0068: admin-managed disallowed words in custom short codes
+-- 'deny' words may not appear (case-insensitive, leetspeak-folded substring)
+-- in user-chosen codes; 'allow' words exempt benign codes that contain one.
+CREATE TABLE disallowed_code_words (
+  id TEXT PRIMARY KEY,
+  word TEXT NOT NULL,
+  kind TEXT NOT NULL DEFAULT 'deny',  -- 'deny' or 'allow'
+  created_by TEXT NOT NULL,  -- User ID of admin who added this
+  created_at INTEGER NOT NULL,
+  FOREIGN KEY (created_by) REFERENCES users(id)
+);
+
+CREATE UNIQUE INDEX idx_disallowed_code_words_word_kind ON disallowed_code_words(word, kind);
@@ -0,0 +1,6 @@
+-- Migration 0069: per-IP budget for anonymous abuse reports
+-- POST /api/reports/links only rejected duplicate reports of the same link,
+-- so one client could flood the queue by reporting many different links.
+-- reports_per_ip_per_hour caps anonymous submissions per IP (0 = no limit).
+INSERT OR IGNORE INTO settings (key, value, updated_at)
+VALUES ('reports_per_ip_per_hour', '10', 0);
@@ -0,0 +1,7 @@
+-- Migration 0070: restrict signups to an allowlist of email domains
+-- signup_email_domains is a comma-separated list (e.g. 'example.com,example.org').
+-- When set, new OAuth signups whose email is outside these domains are refused
+-- with ?error=email_domain_not_allowed. Existing users can still sign in.
+-- Empty (the default) allows any domain.
+INSERT OR IGNORE INTO settings (key, value, updated_at)
+VALUES ('signup_email_domains', '', 0);
@@ -0,0 +1,6 @@
+-- Migration 0071: per-org budget for link creation
+-- POST /api/links was only limited per user, so a shared org could create
+-- links as fast as its members combined. org_links_per_hour caps creation
+-- across all members of an org (0 = no limit).
+INSERT OR IGNORE INTO settings (key, value, updated_at)
+VALUES ('org_links_per_hour', '1000', 0);
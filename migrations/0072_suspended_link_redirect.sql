@@ -0,0 +1,10 @@
+-- Migration 0072: configurable redirect for suspended users' links
+-- Suspending a user disables their org's links, which then bounce to /404
+-- like links a user disabled. disabled_reason tells the two apart
+-- ('suspension' when set by the suspend cascade, NULL otherwise), and
+-- suspended_link_redirect_url sends suspension-disabled links to an
+-- account-suspended notice instead (empty = /404 as before).
+ALTER TABLE links ADD COLUMN disabled_reason TEXT;
+
+INSERT OR IGNORE INTO settings (key, value, updated_at)
+VALUES ('suspended_link_redirect_url', '', 0);
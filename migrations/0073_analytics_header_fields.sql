@@ -0,0 +1,6 @@
+-- Migration 0073: record allowlisted request headers with click events
+-- analytics_header_fields is the org's JSON array of lowercase header names
+-- (e.g. ["x-tenant"]) whose values are kept per click; extra holds those
+-- values as a JSON object keyed by header name (NULL when none were sent).
+ALTER TABLE organizations ADD COLUMN analytics_header_fields TEXT;
+ALTER TABLE analytics_events ADD COLUMN extra TEXT;
@@ -0,0 +1,4 @@
+-- Migration 0074: org-level default redirect status
+-- New links created without an explicit redirect_type use the org's
+-- default_redirect_type (301, 302, 307 or 308) instead of always 301.
+ALTER TABLE organizations ADD COLUMN default_redirect_type TEXT NOT NULL DEFAULT '301';
@@ -0,0 +1,4 @@
+-- Migration 0075: expire links by click count
+-- max_clicks caps how many clicks a link redirects before it serves the
+-- 404 page like an expired link (NULL = unlimited).
+ALTER TABLE links ADD COLUMN max_clicks INTEGER;
@@ -0,0 +1,4 @@
+-- Migration 0076: per-link fallback destination
+-- fallback_url replaces the 404 page when this link is disabled, expired or
+-- over its max_clicks (NULL = 404 page). Missing short codes still 404.
+ALTER TABLE links ADD COLUMN fallback_url TEXT;
@@ -0,0 +1,41 @@
+-- Migration 0077: keep analytics of admin-deleted links for investigations
+-- With deleted_link_retention_days > 0, admin deletion moves the link into a
+-- deleted_links tombstone (status 'deleted') and its click events into
+-- deleted_link_events until retain_until, when the daily cleanup job purges
+-- both. 0 (the default) purges everything immediately, as before.
+CREATE TABLE deleted_links (
+  id TEXT PRIMARY KEY,
+  org_id TEXT NOT NULL,
+  short_code TEXT NOT NULL,
+  destination_url TEXT NOT NULL,
+  title TEXT,
+  created_by TEXT NOT NULL,
+  created_at INTEGER NOT NULL,
+  click_count INTEGER NOT NULL DEFAULT 0,
+  status TEXT NOT NULL DEFAULT 'deleted',
+  deleted_by TEXT NOT NULL,
+  deleted_at INTEGER NOT NULL,
+  retain_until INTEGER NOT NULL
+) STRICT;
+
+CREATE INDEX idx_deleted_links_retain_until ON deleted_links(retain_until);
+
+CREATE TABLE deleted_link_events (
+  id INTEGER PRIMARY KEY,
+  link_id TEXT NOT NULL,
+  org_id TEXT NOT NULL,
+  timestamp INTEGER NOT NULL,
+  referrer TEXT,
+  user_agent TEXT,
+  country TEXT,
+  city TEXT,
+  alias_code TEXT,
+  sample_rate INTEGER NOT NULL DEFAULT 1,
+  extra TEXT,
+  FOREIGN KEY (link_id) REFERENCES deleted_links(id) ON DELETE CASCADE
+) STRICT;
+
+CREATE INDEX idx_deleted_link_events_link ON deleted_link_events(link_id, timestamp DESC);
+
+INSERT OR IGNORE INTO settings (key, value, updated_at)
+VALUES ('deleted_link_retention_days', '0', 0);
@@ -0,0 +1,5 @@
+-- Migration 0078: Index the public sitemap opt-in
+-- /sitemap.xml starts from the orgs that enabled public_sitemap and reads
+-- their links through idx_links_org_status, instead of scanning every link.
+CREATE INDEX IF NOT EXISTS idx_organizations_public_sitemap
+ON organizations(public_sitemap) WHERE public_sitemap = 1;
@@ -0,0 +1,8 @@
+-- Migration 0079: record when each link row was inserted
+-- Imports may backdate created_at, so the per-org monthly quota
+-- (org_link_quota) counts links by insertion time instead. Rows created
+-- before this migration fall back to created_at.
+ALTER TABLE links ADD COLUMN inserted_at INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_links_org_inserted
+ON links(org_id, COALESCE(inserted_at, created_at));
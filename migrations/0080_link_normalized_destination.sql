@@ -0,0 +1,9 @@
+-- Migration 0080: store each link's normalized destination
+-- Create-time dedupe (dedupe=true) looks links up by this column instead of
+-- scanning the org's links. The normalization runs in the Worker, so rows
+-- created before this migration start out NULL; the dedupe lookup falls back
+-- to normalizing them in batches and backfills the column as it goes.
+ALTER TABLE links ADD COLUMN normalized_destination TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_links_org_normalized_destination
+ON links(org_id, normalized_destination);
@@ -0,0 +1,78 @@
+/// Admin analytics handlers
+///
+/// GET /api/admin/analytics/by-status — instance-wide clicks split by link status
+use crate::auth;
+use crate::models::analytics::{ClicksByStatusResponse, complete_status_breakdown};
+use crate::repositories::AnalyticsRepository;
+use crate::utils::{AppError, QueryParams};
+use worker::d1::D1Database;
+use worker::*;
+
+/// Longest window the breakdown scans, since it reads events across all orgs
+const MAX_BREAKDOWN_WINDOW_SECS: i64 = 366 * 24 * 60 * 60;
+
+/// Parse a required Unix-timestamp query parameter.
+fn parse_timestamp(params: &QueryParams, name: &str) -> Result<i64, AppError> {
+    params
+        .get(name)
+        .ok_or_else(|| AppError::BadRequest(format!("Missing '{}' parameter", name)))?
+        .parse::<i64>()
+        .map_err(|_| AppError::BadRequest(format!("'{}' must be a Unix timestamp", name)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/analytics/by-status",
+    tag = "Admin",
+    summary = "Clicks by link status",
+    description = "Clicks across all orgs with `start <= timestamp <= end`, grouped by each link's current status (active, disabled, blocked), e.g. to see how much traffic blocked links got before they were blocked. Every status is listed, with zeros when it had no clicks. Clicks on deleted links are not counted. The window may be at most 366 days",
+    params(
+        ("start" = i64, Query, description = "Window start (Unix seconds)"),
+        ("end" = i64, Query, description = "Window end (Unix seconds)"),
+    ),
+    responses(
+        (status = 200, description = "Breakdown", body = ClicksByStatusResponse),
+        (status = 400, description = "Missing or invalid window"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_admin_clicks_by_status(
+    req: Request,
+    ctx: RouteContext<()>,
+) -> Result<Response> {
+    Ok(inner(req, ctx).await.unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    auth::require_admin(&user_ctx)?;
+
+    let params = QueryParams::from_request(&req)?;
+    let start = parse_timestamp(&params, "start")?;
+    let end = parse_timestamp(&params, "end")?;
+    if end < start {
+        return Err(AppError::BadRequest(
+            "'end' must not be before 'start'".to_string(),
+        ));
+    }
+    if end - start > MAX_BREAKDOWN_WINDOW_SECS {
+        return Err(AppError::BadRequest(
+            "The window may be at most 366 days".to_string(),
+        ));
+    }
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let counts = AnalyticsRepository::new()
+        .get_clicks_by_link_status(&db, start, end)
+        .await?;
+    let statuses = complete_status_breakdown(counts);
+
+    Ok(Response::from_json(&ClicksByStatusResponse {
+        start,
+        end,
+        total_clicks: statuses.iter().map(|s| s.clicks).sum(),
+        statuses,
+    })?)
+}
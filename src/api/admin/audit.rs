@@ -0,0 +1,119 @@
+/// Admin consistency audits
+///
+/// GET /api/admin/audit/kv-consistency — compare D1 link status with KV
+///                                      redirect entries (or, with
+///                                      orphans=true, KV keys with D1),
+///                                      optionally repairing
+use crate::auth;
+use crate::services::LinkService;
+use crate::utils::{AppError, QueryParams};
+use worker::d1::D1Database;
+use worker::*;
+
+/// Links (or KV keys) checked when `limit` is not given
+const KV_AUDIT_DEFAULT_LIMIT: i64 = 50;
+/// Each checked link costs up to two KV reads (alias codes take a hop) and a
+/// repair about four more D1/KV subrequests, so a fully repaired page of 100
+/// stays inside the Workers budget of 1,000 subrequests per invocation.
+const KV_AUDIT_MAX_LIMIT: i64 = 100;
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/audit/kv-consistency",
+    tag = "Admin",
+    summary = "Audit KV consistency",
+    description = "Checks links against their KV redirect entries and returns the ones out of sync: active links that are missing from KV or mapped to another link or destination (`missing`/`mismatched`), and disabled or blocked links still served from KV (`mismatched`). With org_id the newest links of that org are checked, otherwise a random sample across all orgs. With repair=true every reported link is re-synced from D1.\n\nWith orphans=true the check runs the other way: one page of KV keys is read and link keys that no D1 link or alias owns are returned (and deleted with repair=true). Pass the returned cursor to continue the scan",
+    params(
+        ("org_id" = Option<String>, Query, description = "Only audit this organization's links"),
+        ("limit" = Option<i64>, Query, description = "Links or KV keys to check (default 50, max 100)"),
+        ("active_only" = Option<bool>, Query, description = "Only check active links"),
+        ("repair" = Option<bool>, Query, description = "Re-sync every reported link from D1, or delete orphaned keys"),
+        ("orphans" = Option<bool>, Query, description = "Scan KV keys for entries with no link in D1"),
+        ("cursor" = Option<String>, Query, description = "Continue an orphan scan from a previous response"),
+    ),
+    responses(
+        (status = 200, description = "{checked, mismatches, repaired}, or {checked, orphans, repaired, cursor} with orphans=true"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_admin_audit_kv_consistency(
+    req: Request,
+    ctx: RouteContext<()>,
+) -> Result<Response> {
+    Ok(inner_audit_kv_consistency(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_audit_kv_consistency(
+    req: Request,
+    ctx: RouteContext<()>,
+) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    auth::require_admin(&user_ctx)?;
+
+    let params = QueryParams::from_request(&req)?;
+    let org_id = params.get("org_id").filter(|o| !o.trim().is_empty());
+    let limit = params
+        .get_i64("limit")
+        .unwrap_or(KV_AUDIT_DEFAULT_LIMIT)
+        .clamp(1, KV_AUDIT_MAX_LIMIT);
+    let repair = params.flag("repair");
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let kv = ctx.kv("URL_MAPPINGS")?;
+
+    if params.flag("orphans") {
+        let report = LinkService::new()
+            .admin_audit_kv_orphans(
+                &db,
+                &kv,
+                params.get("cursor").filter(|c| !c.is_empty()),
+                limit as u64,
+                repair,
+            )
+            .await?;
+        if !report.orphans.is_empty() {
+            console_log!(
+                "{}",
+                serde_json::json!({
+                    "event": "admin_kv_audit_orphans",
+                    "checked": report.checked,
+                    "orphans": report.orphans.len(),
+                    "repaired": report.repaired,
+                    "level": "warn"
+                })
+            );
+        }
+        return Ok(Response::from_json(&report)?);
+    }
+
+    let report = LinkService::new()
+        .admin_audit_kv_consistency(
+            &db,
+            &kv,
+            org_id.as_deref(),
+            params.flag("active_only"),
+            limit,
+            repair,
+        )
+        .await?;
+
+    if !report.mismatches.is_empty() {
+        console_log!(
+            "{}",
+            serde_json::json!({
+                "event": "admin_kv_audit_mismatches",
+                "org_id": org_id,
+                "checked": report.checked,
+                "mismatches": report.mismatches.len(),
+                "repaired": report.repaired,
+                "level": "warn"
+            })
+        );
+    }
+
+    Ok(Response::from_json(&report)?)
+}
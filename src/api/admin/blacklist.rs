@@ -2,14 +2,29 @@
 ///
 /// POST   /api/admin/blacklist       — block a destination URL
 /// GET    /api/admin/blacklist       — list all blacklist entries
+/// GET    /api/admin/blacklist/preview — links a new entry would block
 /// DELETE /api/admin/blacklist/:id   — remove a blacklist entry
 use crate::auth;
 use crate::repositories::BlacklistRepository;
 use crate::services::BlacklistService;
-use crate::utils::AppError;
+use crate::utils::{AppError, QueryParams};
 use worker::d1::D1Database;
 use worker::*;
 
+/// Most links returned by the blacklist preview
+const BLACKLIST_PREVIEW_LIMIT: usize = 100;
+
+/// Parse a blacklist `match_type`, defaulting to `exact`.
+fn parse_match_type(value: Option<&str>) -> Result<String, AppError> {
+    match value {
+        Some(m) if m == "exact" || m == "domain" => Ok(m.to_string()),
+        Some(_) => Err(AppError::BadRequest(
+            "Invalid match_type. Must be 'exact' or 'domain'".to_string(),
+        )),
+        None => Ok("exact".to_string()),
+    }
+}
+
 #[utoipa::path(
     post,
     path = "/api/admin/blacklist",
@@ -47,55 +62,8 @@ async fn inner_block(mut req: Request, ctx: RouteContext<()>) -> Result<Response
         .ok_or_else(|| AppError::BadRequest("Missing 'destination' field".to_string()))?
         .to_string();
 
-    let match_type = match body.get("match_type").and_then(|m| m.as_str()) {
-        Some(m) if m == "exact" || m == "domain" => m.to_string(),
-        Some(_) => {
-            return Err(AppError::BadRequest(
-                "Invalid match_type. Must be 'exact' or 'domain'".to_string(),
-            ));
-        }
-        None => "exact".to_string(),
-    };
-
-    let normalized_destination = if match_type == "exact" {
-        match crate::utils::normalize_url_for_blacklist(&destination) {
-            Ok(url) => url,
-            Err(e) => {
-                console_log!(
-                    "{}",
-                    serde_json::json!({
-                        "event": "url_normalize_failed",
-                        "url": destination,
-                        "error": e.to_string(),
-                        "level": "warn"
-                    })
-                );
-                destination.clone()
-            }
-        }
-    } else {
-        match url::Url::parse(&destination) {
-            Ok(url) => url.host_str().unwrap_or(&destination).to_string(),
-            Err(_) => {
-                console_log!(
-                    "{}",
-                    serde_json::json!({
-                        "event": "domain_extract_failed",
-                        "url": destination,
-                        "level": "warn"
-                    })
-                );
-                let without_protocol = destination
-                    .trim_start_matches("http://")
-                    .trim_start_matches("https://");
-                without_protocol
-                    .split('/')
-                    .next()
-                    .unwrap_or(without_protocol)
-                    .to_string()
-            }
-        }
-    };
+    let match_type = parse_match_type(body.get("match_type").and_then(|m| m.as_str()))?;
+    let normalized_destination = BlacklistService::normalize_entry(&destination, &match_type);
 
     let reason = body
         .get("reason")
@@ -167,6 +135,64 @@ async fn inner_list(req: Request, ctx: RouteContext<()>) -> Result<Response, App
     Ok(Response::from_json(&entries)?)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/admin/blacklist/preview",
+    tag = "Admin",
+    summary = "Preview a blacklist entry",
+    description = "Returns the currently active links that blocking `destination` with `match_type` would block, without blocking anything. At most 100 links are returned. matched_count counts matches among the 5,000 newest candidate links; `truncated` is true when there were more candidates, making it a lower bound",
+    params(
+        ("destination" = String, Query, description = "URL (exact) or URL/host (domain) to test"),
+        ("match_type" = Option<String>, Query, description = "exact (default) or domain"),
+    ),
+    responses(
+        (status = 200, description = "{destination, match_type, matched_count, truncated, links}"),
+        (status = 400, description = "Missing destination or invalid match_type"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_admin_preview_blacklist(
+    req: Request,
+    ctx: RouteContext<()>,
+) -> Result<Response> {
+    Ok(inner_preview(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_preview(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    auth::require_admin(&user_ctx)?;
+
+    let params = QueryParams::from_request(&req)?;
+    let destination = params
+        .get("destination")
+        .filter(|d| !d.trim().is_empty())
+        .ok_or_else(|| AppError::BadRequest("Missing 'destination' parameter".to_string()))?;
+    let match_type = parse_match_type(params.get("match_type").as_deref())?;
+    let normalized_destination = BlacklistService::normalize_entry(&destination, &match_type);
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let (links, matched_count, truncated) = BlacklistService::new()
+        .preview_matching_links(
+            &db,
+            &match_type,
+            &normalized_destination,
+            BLACKLIST_PREVIEW_LIMIT,
+        )
+        .await?;
+
+    Ok(Response::from_json(&serde_json::json!({
+        "destination": normalized_destination,
+        "match_type": match_type,
+        "matched_count": matched_count,
+        "truncated": truncated,
+        "links": links,
+    }))?)
+}
+
 #[utoipa::path(
     delete,
     path = "/api/admin/blacklist/{id}",
@@ -0,0 +1,156 @@
+/// Admin disallowed-words handlers
+///
+/// GET    /api/admin/code-words     — list deny/allow words
+/// POST   /api/admin/code-words     — add a word
+/// DELETE /api/admin/code-words/:id — remove a word
+use crate::auth;
+use crate::repositories::CodeWordRepository;
+use crate::utils::AppError;
+use crate::utils::code_words::{CODE_WORD_KINDS, MAX_CODE_WORD_LENGTH};
+use worker::d1::D1Database;
+use worker::*;
+
+/// Validate and lowercase a submitted word. Same charset as short codes,
+/// minus slashes, so every word can actually occur in a code.
+fn parse_word(value: Option<&str>) -> Result<String, AppError> {
+    let word = value
+        .map(str::trim)
+        .filter(|w| !w.is_empty())
+        .ok_or_else(|| AppError::BadRequest("Missing 'word' field".to_string()))?;
+    if word.len() > MAX_CODE_WORD_LENGTH || !word.chars().all(|c| c.is_alphanumeric() || c == '-') {
+        return Err(AppError::BadRequest(format!(
+            "Invalid word. Must be 1-{} letters, numbers or hyphens",
+            MAX_CODE_WORD_LENGTH
+        )));
+    }
+    Ok(word.to_lowercase())
+}
+
+/// Parse an entry `kind`, defaulting to `deny`.
+fn parse_kind(value: Option<&str>) -> Result<String, AppError> {
+    match value {
+        Some(k) if CODE_WORD_KINDS.contains(&k) => Ok(k.to_string()),
+        Some(_) => Err(AppError::BadRequest(
+            "Invalid kind. Must be 'deny' or 'allow'".to_string(),
+        )),
+        None => Ok("deny".to_string()),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/code-words",
+    tag = "Admin",
+    summary = "List disallowed code words",
+    description = "Words that may not appear in custom short codes (`deny`), and words that exempt codes containing one (`allow`)",
+    responses(
+        (status = 200, description = "Array of {id, word, kind, created_by, created_at}"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_admin_list_code_words(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_list(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_list(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    auth::require_admin(&user_ctx)?;
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let entries = CodeWordRepository::new().list_all(&db).await?;
+
+    Ok(Response::from_json(&entries)?)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/code-words",
+    tag = "Admin",
+    summary = "Add a disallowed code word",
+    description = "Body: {word, kind?}. `kind` is deny (default) or allow. Matching is case-insensitive, ignores hyphens and slashes, and folds common leetspeak digits (0=o, 1=i, 3=e, 4=a, 5=s, 7=t, 8=b, 9=g). Existing links are not affected",
+    responses(
+        (status = 200, description = "The created entry"),
+        (status = 400, description = "Invalid word or kind"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 409, description = "Word already listed with this kind"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_admin_add_code_word(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_add(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_add(mut req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    auth::require_admin(&user_ctx)?;
+
+    let body: serde_json::Value = req
+        .json()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Invalid JSON: {}", e)))?;
+
+    let word = parse_word(body.get("word").and_then(|w| w.as_str()))?;
+    let kind = parse_kind(body.get("kind").and_then(|k| k.as_str()))?;
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let repo = CodeWordRepository::new();
+    if repo.is_duplicate(&db, &word, &kind).await? {
+        return Err(AppError::Conflict(format!(
+            "Word is already on the {} list",
+            kind
+        )));
+    }
+    let entry = repo.add(&db, &word, &kind, &user_ctx.user_id).await?;
+
+    Ok(Response::from_json(&entry)?)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/code-words/{id}",
+    tag = "Admin",
+    summary = "Remove a disallowed code word",
+    params(("id" = String, Path, description = "Entry ID")),
+    responses(
+        (status = 200, description = "Entry removed"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 404, description = "Entry not found"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_admin_remove_code_word(
+    req: Request,
+    ctx: RouteContext<()>,
+) -> Result<Response> {
+    Ok(inner_remove(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_remove(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    auth::require_admin(&user_ctx)?;
+
+    let id = ctx
+        .param("id")
+        .ok_or_else(|| AppError::BadRequest("Missing code word ID".to_string()))?
+        .to_string();
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    if !CodeWordRepository::new().remove(&db, &id).await? {
+        return Err(AppError::NotFound("Code word not found".to_string()));
+    }
+
+    Ok(Response::from_json(&serde_json::json!({
+        "success": true,
+        "message": "Code word removed successfully"
+    }))?)
+}
@@ -0,0 +1,91 @@
+/// Admin config bundle handlers
+///
+/// GET  /api/admin/config/export — portable settings and blacklist as JSON
+/// POST /api/admin/config/import — apply a bundle (merge or replace)
+use crate::auth;
+use crate::models::config_bundle::{ConfigBundle, ConfigImportMode};
+use crate::services::ConfigBundleService;
+use crate::utils::{AppError, QueryParams};
+use worker::d1::D1Database;
+use worker::*;
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/config/export",
+    tag = "Admin",
+    summary = "Export instance configuration",
+    description = "Returns a JSON bundle {version, exported_at, settings, blacklist} for backups or moving to another instance. Billing product and discount ids and the code length watermark are instance-specific and left out. Reserved short codes are built in and not part of the bundle",
+    responses(
+        (status = 200, description = "Config bundle download"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_admin_export_config(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_export(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_export(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    auth::require_admin(&user_ctx)?;
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let bundle = ConfigBundleService::new().export(&db).await?;
+
+    let date_str = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let mut response = Response::from_json(&bundle)?;
+    response.headers_mut().set(
+        "Content-Disposition",
+        &format!("attachment; filename=\"rushomon-config-{}.json\"", date_str),
+    )?;
+    Ok(response)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/config/import",
+    tag = "Admin",
+    summary = "Import instance configuration",
+    description = "Applies a bundle from GET /api/admin/config/export. Every setting and blacklist entry is validated first and the changes are written in one batch, so an invalid bundle changes nothing. mode=merge (default) sets the bundle's settings and adds its missing blacklist entries; mode=replace also removes blacklist entries not in the bundle. Existing links matching newly added entries are blocked",
+    params(
+        ("mode" = Option<String>, Query, description = "merge (default) or replace"),
+    ),
+    request_body(content = serde_json::Value, description = "Config bundle"),
+    responses(
+        (status = 200, description = "{settings_applied, blacklist_added, blacklist_removed, links_blocked}"),
+        (status = 400, description = "Invalid bundle, mode, setting or blacklist entry"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_admin_import_config(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_import(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_import(mut req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    auth::require_admin(&user_ctx)?;
+
+    let params = QueryParams::from_request(&req)?;
+    let mode =
+        ConfigImportMode::parse(params.get("mode").as_deref()).map_err(AppError::BadRequest)?;
+
+    let bundle: ConfigBundle = req
+        .json()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Invalid config bundle: {}", e)))?;
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let kv = ctx.kv("URL_MAPPINGS")?;
+    let summary = ConfigBundleService::new()
+        .import(&db, &kv, &bundle, mode, &user_ctx.user_id)
+        .await?;
+
+    Ok(Response::from_json(&summary)?)
+}
@@ -1,6 +1,11 @@
+pub mod analytics;
 pub mod api_keys;
+pub mod audit;
 pub mod billing;
 pub mod blacklist;
+pub mod code_words;
+pub mod config;
 pub mod counters;
 pub mod domains;
+pub mod short_codes;
 pub mod users;
@@ -0,0 +1,81 @@
+/// Admin short code handlers
+///
+/// GET /api/admin/short-code/preview — sample codes for a proposed alphabet and length
+use crate::auth;
+use crate::services::SettingsService;
+use crate::utils::short_code::{
+    MAX_SHORT_CODE_LENGTH, code_alphabet_chars, generate_short_code_with_charset,
+    parse_code_alphabet,
+};
+use crate::utils::{AppError, QueryParams};
+use worker::d1::D1Database;
+use worker::*;
+
+/// Number of sample codes returned by the preview
+const PREVIEW_SAMPLE_COUNT: usize = 10;
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/short-code/preview",
+    tag = "Admin",
+    summary = "Preview generated short codes",
+    description = "Generates sample random codes with a proposed alphabet and length, without saving anything, so admins can check the result before changing code generation settings. Returns {alphabet, length, characters, samples}",
+    params(
+        ("alphabet" = Option<String>, Query, description = "base62 (default) or base58, which leaves out the ambiguous 0, O, I and l"),
+        ("length" = Option<usize>, Query, description = "Code length, between the system minimum and 100. Defaults to the current min_random_code_length"),
+    ),
+    responses(
+        (status = 200, description = "Sample codes"),
+        (status = 400, description = "Invalid alphabet or length"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_admin_preview_short_codes(
+    req: Request,
+    ctx: RouteContext<()>,
+) -> Result<Response> {
+    Ok(inner(req, ctx).await.unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    auth::require_admin(&user_ctx)?;
+
+    let params = QueryParams::from_request(&req)?;
+    let alphabet = params
+        .get("alphabet")
+        .unwrap_or_else(|| "base62".to_string());
+    let exclude_ambiguous = parse_code_alphabet(&alphabet).ok_or_else(|| {
+        AppError::BadRequest("Invalid alphabet. Must be 'base62' or 'base58'".to_string())
+    })?;
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let lengths = SettingsService::new().get_code_length_settings(&db).await?;
+    let system_min = lengths.system_min_length;
+    let length = match params.get("length") {
+        Some(value) => value
+            .parse::<usize>()
+            .ok()
+            .filter(|len| (system_min..=MAX_SHORT_CODE_LENGTH).contains(len))
+            .ok_or_else(|| {
+                AppError::BadRequest(format!(
+                    "Invalid length. Must be between {} and {}",
+                    system_min, MAX_SHORT_CODE_LENGTH
+                ))
+            })?,
+        None => lengths.min_random_length.max(system_min),
+    };
+
+    let samples: Vec<String> = (0..PREVIEW_SAMPLE_COUNT)
+        .map(|_| generate_short_code_with_charset(length, exclude_ambiguous))
+        .collect();
+
+    Ok(Response::from_json(&serde_json::json!({
+        "alphabet": alphabet,
+        "length": length,
+        "characters": String::from_utf8_lossy(code_alphabet_chars(exclude_ambiguous)),
+        "samples": samples,
+    }))?)
+}
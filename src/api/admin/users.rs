@@ -2,17 +2,19 @@
 ///
 /// GET    /api/admin/users           — list all users (paginated)
 /// GET    /api/admin/users/:id       — get one user
+/// GET    /api/admin/users/:id/links — links the user created in any org
 /// PUT    /api/admin/users/:id       — update user role
 /// PUT    /api/admin/users/:id/suspend   — suspend user
 /// PUT    /api/admin/users/:id/unsuspend — unsuspend user
 /// DELETE /api/admin/users/:id       — delete user + data
+/// POST   /api/admin/users/merge     — merge a duplicate account into another
 use crate::api::links::sync_link_mapping_from_link;
 use crate::auth;
 use crate::kv;
-use crate::models::link::LinkStatus;
+use crate::models::link::{DISABLED_REASON_SUSPENSION, LinkStatus};
 use crate::repositories::UserRepository;
 use crate::services::AdminService;
-use crate::utils::AppError;
+use crate::utils::{AppError, QueryParams};
 use worker::d1::D1Database;
 use worker::*;
 
@@ -24,9 +26,10 @@ use worker::*;
     params(
         ("page" = Option<i64>, Query, description = "Page number"),
         ("limit" = Option<i64>, Query, description = "Items per page (max 100)"),
+        ("count_only" = Option<bool>, Query, description = "Return only `{\"total\": n}`, skipping the user rows"),
     ),
     responses(
-        (status = 200, description = "Paginated list of users"),
+        (status = 200, description = "Paginated list of users, or the total when count_only=true"),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Admin required"),
     ),
@@ -66,6 +69,11 @@ async fn inner_list_users(req: Request, ctx: RouteContext<()>) -> Result<Respons
 
     let db = ctx.env.get_binding::<D1Database>("rushomon")?;
 
+    if QueryParams::from_request(&req)?.flag("count_only") {
+        let total = AdminService::new().count_users(&db).await?;
+        return Ok(Response::from_json(&serde_json::json!({ "total": total }))?);
+    }
+
     let (users, total) = AdminService::new()
         .list_users(&db, page, limit)
         .await
@@ -114,11 +122,74 @@ async fn inner_get_user(req: Request, ctx: RouteContext<()>) -> Result<Response,
     Ok(Response::from_json(&user)?)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/admin/users/{id}/links",
+    tag = "Admin",
+    summary = "List a user's links across orgs",
+    description = "Returns every link the user created, in any organization, newest first, with the org name. Useful when investigating abuse",
+    params(
+        ("id" = String, Path, description = "User ID"),
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("limit" = Option<i64>, Query, description = "Items per page (max 100)"),
+        ("status" = Option<String>, Query, description = "Only links with this status: active, disabled or blocked"),
+    ),
+    responses(
+        (status = 200, description = "Paginated list of the user's links"),
+        (status = 400, description = "Invalid status"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 404, description = "User not found"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_admin_list_user_links(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_list_user_links(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_list_user_links(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    auth::require_admin(&user_ctx)?;
+
+    let user_id = ctx
+        .param("id")
+        .ok_or_else(|| AppError::BadRequest("Missing user ID".to_string()))?
+        .to_string();
+
+    let params = QueryParams::from_request(&req)?;
+    let page = params.get_i64("page").unwrap_or(1).max(1);
+    let limit = params.get_i64("limit").unwrap_or(50).clamp(1, 100);
+    let status = params.get("status");
+    if status
+        .as_deref()
+        .is_some_and(|s| !matches!(s, "active" | "disabled" | "blocked"))
+    {
+        return Err(AppError::BadRequest(
+            "Invalid status. Must be 'active', 'disabled' or 'blocked'".to_string(),
+        ));
+    }
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let (links, total) = AdminService::new()
+        .list_user_links(&db, &user_id, status.as_deref(), page, limit)
+        .await?;
+
+    Ok(Response::from_json(&serde_json::json!({
+        "links": links,
+        "total": total,
+        "page": page,
+        "limit": limit,
+    }))?)
+}
+
 #[utoipa::path(
     put,
     path = "/api/admin/users/{id}/suspend",
     tag = "Admin",
     summary = "Suspend a user",
+    description = "Body: {reason}. Disables the active links of the user's org with disabled_reason `suspension`. Those links redirect to the `suspended_link_redirect_url` setting when it is set, and to /404 otherwise",
     params(("id" = String, Path, description = "User ID")),
     responses(
         (status = 200, description = "User suspended"),
@@ -179,6 +250,10 @@ async fn inner_suspend_user(mut req: Request, ctx: RouteContext<()>) -> Result<R
             let mut disabled_link =
                 serde_json::from_value::<crate::models::link::Link>(link.clone())
                     .map_err(|e| AppError::Internal(format!("Failed to parse link: {}", e)))?;
+            // Only links the cascade just disabled carry the suspension reason
+            if disabled_link.status == LinkStatus::Active {
+                disabled_link.disabled_reason = Some(DISABLED_REASON_SUSPENSION.to_string());
+            }
             disabled_link.status = LinkStatus::Disabled;
             sync_link_mapping_from_link(&db, &kv, &disabled_link).await?;
         }
@@ -196,6 +271,7 @@ async fn inner_suspend_user(mut req: Request, ctx: RouteContext<()>) -> Result<R
     path = "/api/admin/users/{id}/unsuspend",
     tag = "Admin",
     summary = "Unsuspend a user",
+    description = "Links disabled by the suspension stay disabled, but lose their suspension reason and redirect to /404 like other disabled links",
     params(("id" = String, Path, description = "User ID")),
     responses(
         (status = 200, description = "User unsuspended"),
@@ -221,11 +297,18 @@ async fn inner_unsuspend_user(req: Request, ctx: RouteContext<()>) -> Result<Res
 
     let db = ctx.env.get_binding::<D1Database>("rushomon")?;
 
-    AdminService::new()
+    let unsuspended_links = AdminService::new()
         .unsuspend_user(&db, &target_user_id)
         .await
         .map_err(|e| AppError::Internal(format!("Failed to unsuspend user: {}", e)))?;
 
+    // Drop the suspension reason from KV too, so the links 404 like any
+    // other disabled link
+    let kv = ctx.kv("URL_MAPPINGS")?;
+    for link in &unsuspended_links {
+        sync_link_mapping_from_link(&db, &kv, link).await?;
+    }
+
     Ok(Response::from_json(&serde_json::json!({
         "success": true,
         "message": "User unsuspended successfully"
@@ -391,3 +474,67 @@ async fn inner_update_user_role(
         "suspended_by": updated_user.suspended_by,
     }))?)
 }
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/merge",
+    tag = "Admin",
+    summary = "Merge duplicate users",
+    description = "Body: {primary_id, duplicate_id}. Moves the duplicate's org memberships (keeping the higher role where both belong to an org), links, billing account ownership, reports, invitations and API keys to the primary, then deletes the duplicate. The primary becomes an admin if the duplicate was one. Runs as a single batch: on failure neither account changes",
+    responses(
+        (status = 200, description = "{success, memberships_moved, links_moved}"),
+        (status = 400, description = "Same user twice, merging away your own account, or a suspended primary"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 404, description = "User not found"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_admin_merge_users(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_merge_users(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_merge_users(mut req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    auth::require_admin(&user_ctx)?;
+
+    let body: serde_json::Value = req
+        .json()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Invalid JSON: {}", e)))?;
+
+    let primary_id = body
+        .get("primary_id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| AppError::BadRequest("Missing 'primary_id' field".to_string()))?;
+    let duplicate_id = body
+        .get("duplicate_id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| AppError::BadRequest("Missing 'duplicate_id' field".to_string()))?;
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let (memberships_moved, links_moved) = AdminService::new()
+        .merge_users(&db, primary_id, duplicate_id, &user_ctx.user_id)
+        .await?;
+
+    console_log!(
+        "{}",
+        serde_json::json!({
+            "event": "admin_users_merged",
+            "primary_id": primary_id,
+            "duplicate_id": duplicate_id,
+            "admin_id": user_ctx.user_id,
+            "memberships_moved": memberships_moved,
+            "links_moved": links_moved,
+            "level": "info"
+        })
+    );
+
+    Ok(Response::from_json(&serde_json::json!({
+        "success": true,
+        "memberships_moved": memberships_moved,
+        "links_moved": links_moved
+    }))?)
+}
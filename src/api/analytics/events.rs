@@ -0,0 +1,70 @@
+/// Raw link events handler
+///
+/// GET /api/links/:id/events — paginated raw click events for a single link,
+/// for custom dashboards that don't want a bulk CSV export.
+use crate::auth;
+use crate::models::pagination::{PaginatedResponse, PaginationMeta};
+use crate::services::analytics_service::get_link_events;
+use crate::utils::{AppError, QueryParams};
+use worker::d1::D1Database;
+use worker::*;
+
+/// Events per page when `limit` is not given
+const LINK_EVENTS_DEFAULT_LIMIT: i64 = 50;
+/// Largest accepted `limit`
+const LINK_EVENTS_MAX_LIMIT: i64 = 500;
+
+#[utoipa::path(
+    get,
+    path = "/api/links/{id}/events",
+    tag = "Links",
+    summary = "List raw link events",
+    description = "Returns the link's raw click events (timestamp, referrer, country, city, user_agent), newest first, paginated. Events older than the organization's tier retention window are not returned; the X-Analytics-Gated header is set when that window applies",
+    params(
+        ("id" = String, Path, description = "Link ID"),
+        ("page" = Option<i64>, Query, description = "Page number (default: 1)"),
+        ("limit" = Option<i64>, Query, description = "Events per page (default: 50, max 500)"),
+    ),
+    responses(
+        (status = 200, description = "Paginated raw events: {data, pagination}"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Link not found"),
+    ),
+    security(
+        ("Bearer" = []),
+        ("session_cookie" = [])
+    )
+)]
+pub async fn handle_get_link_events(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner(req, ctx).await.unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+
+    let link_id = ctx
+        .param("id")
+        .ok_or_else(|| AppError::BadRequest("Missing link ID".to_string()))?;
+
+    let params = QueryParams::from_request(&req)?;
+    let page = params.get_i64("page").unwrap_or(1).max(1);
+    let limit = params
+        .get_i64("limit")
+        .unwrap_or(LINK_EVENTS_DEFAULT_LIMIT)
+        .clamp(1, LINK_EVENTS_MAX_LIMIT);
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let (events, total, gated) =
+        get_link_events(&db, link_id, &user_ctx.org_id, page, limit).await?;
+
+    let mut response = Response::from_json(&PaginatedResponse::new(
+        events,
+        PaginationMeta::new(page, limit, total),
+    ))?;
+    if gated {
+        response
+            .headers_mut()
+            .set("X-Analytics-Gated", "retention_limited")?;
+    }
+    Ok(response)
+}
@@ -0,0 +1,347 @@
+/// Analytics export handlers
+///
+/// GET /api/orgs/{id}/analytics/export — raw click events for every link in
+/// the org as CSV, for data portability.
+/// GET /api/links/{id}/analytics/export — raw click events of one link as CSV.
+///
+/// Bodies are streamed page by page so large exports never sit in memory at once.
+use crate::api::analytics::link::parse_link_time_range;
+use crate::api::links::export::csv_escape;
+use crate::auth;
+use crate::models::analytics::{ExportedAnalyticsEvent, LinkEvent};
+use crate::repositories::AnalyticsRepository;
+use crate::services::OrgService;
+use crate::services::analytics_service::{
+    get_link_export_window, get_org_export_window, parse_time_range_from_query,
+};
+use crate::utils::AppError;
+use futures_util::{StreamExt, stream};
+use worker::d1::D1Database;
+use worker::*;
+
+/// Events fetched from D1 per streamed chunk
+const ANALYTICS_EXPORT_PAGE_SIZE: i64 = 1000;
+/// Maximum events in one export; narrow the window to export more
+pub const MAX_ANALYTICS_EXPORT_ROWS: i64 = 100_000;
+
+/// Maximum events in one link export
+pub const MAX_LINK_ANALYTICS_EXPORT_ROWS: i64 = 50_000;
+
+const CSV_HEADER: &str =
+    "event_id,timestamp,link_short_code,link_id,referrer,user_agent,country,city\n";
+const LINK_CSV_HEADER: &str = "timestamp,referrer,user_agent,country,city\n";
+
+fn format_timestamp(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|dt: chrono::DateTime<chrono::Utc>| dt.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        .unwrap_or_default()
+}
+
+fn event_csv_row(event: &ExportedAnalyticsEvent) -> String {
+    format!(
+        "{},{},{},{},{},{},{},{}\n",
+        event.id,
+        format_timestamp(event.timestamp),
+        csv_escape(&event.link_short_code),
+        csv_escape(&event.link_id),
+        csv_escape(event.referrer.as_deref().unwrap_or("")),
+        csv_escape(event.user_agent.as_deref().unwrap_or("")),
+        csv_escape(event.country.as_deref().unwrap_or("")),
+        csv_escape(event.city.as_deref().unwrap_or("")),
+    )
+}
+
+fn link_event_csv_row(event: &LinkEvent) -> String {
+    format!(
+        "{},{},{},{},{}\n",
+        format_timestamp(event.timestamp),
+        csv_escape(event.referrer.as_deref().unwrap_or("")),
+        csv_escape(event.user_agent.as_deref().unwrap_or("")),
+        csv_escape(event.country.as_deref().unwrap_or("")),
+        csv_escape(event.city.as_deref().unwrap_or("")),
+    )
+}
+
+/// Cursor carried between streamed pages
+struct ExportCursor {
+    db: D1Database,
+    org_id: String,
+    start: i64,
+    end: i64,
+    after_id: i64,
+    remaining: i64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/orgs/{id}/analytics/export",
+    tag = "Analytics",
+    summary = "Export org analytics as CSV",
+    description = "Streams the raw click events of every link in the organization as CSV (event_id, timestamp, link_short_code, link_id, referrer, user_agent, country, city), oldest first. The window defaults to the last 7 days and is capped by tier retention; at most 100000 events are exported, so narrow the window with start/end for more. Caller must be owner or admin",
+    params(
+        ("id" = String, Path, description = "Organization ID"),
+        ("days" = Option<i64>, Query, description = "Number of days to look back (default: 7)"),
+        ("start" = Option<i64>, Query, description = "Unix timestamp range start (alternative to days)"),
+        ("end" = Option<i64>, Query, description = "Unix timestamp range end"),
+    ),
+    responses(
+        (status = 200, description = "CSV file download"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Owner or admin required"),
+        (status = 404, description = "Organization not found"),
+    ),
+    security(
+        ("Bearer" = []),
+        ("session_cookie" = [])
+    )
+)]
+pub async fn handle_export_org_analytics(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_export(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_export(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+
+    let org_id = ctx
+        .param("id")
+        .ok_or_else(|| AppError::BadRequest("Missing org id".to_string()))?
+        .to_string();
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let org_service = OrgService::new();
+    org_service
+        .require_owner_or_admin(
+            &db,
+            &org_id,
+            &user_ctx.user_id,
+            "Only org owners and admins can export analytics",
+        )
+        .await?;
+    let (org, _) = org_service
+        .get_org_as_member(&db, &org_id, &user_ctx.user_id)
+        .await?;
+
+    let url = req.url()?;
+    let (start, end) =
+        parse_time_range_from_query(url.query().unwrap_or("")).calculate_timestamps();
+    let (start, end, gated) = get_org_export_window(&db, &org, start, end).await;
+
+    let cursor = ExportCursor {
+        db,
+        org_id,
+        start,
+        end,
+        after_id: 0,
+        remaining: MAX_ANALYTICS_EXPORT_ROWS,
+    };
+
+    let header = stream::once(async { Ok::<Vec<u8>, Error>(CSV_HEADER.as_bytes().to_vec()) });
+    let pages = stream::unfold(Some(cursor), |cursor| async move {
+        let mut cursor = cursor?;
+        if cursor.remaining <= 0 {
+            return None;
+        }
+        let page = match AnalyticsRepository::new()
+            .list_org_events_page(
+                &cursor.db,
+                &cursor.org_id,
+                cursor.start,
+                cursor.end,
+                cursor.after_id,
+                ANALYTICS_EXPORT_PAGE_SIZE.min(cursor.remaining),
+            )
+            .await
+        {
+            Ok(page) => page,
+            // Surface the error to the stream, then stop
+            Err(e) => return Some((Err(e), None)),
+        };
+        let last = page.last()?;
+        cursor.after_id = last.id;
+        cursor.remaining -= page.len() as i64;
+
+        let chunk: String = page.iter().map(event_csv_row).collect();
+        let next = (page.len() as i64 == ANALYTICS_EXPORT_PAGE_SIZE).then_some(cursor);
+        Some((Ok(chunk.into_bytes()), next))
+    });
+
+    let date_str = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let filename = format!("rushomon-analytics-{}.csv", date_str);
+    let mut response = Response::from_stream(Box::pin(header.chain(pages)))?;
+    let headers = response.headers_mut();
+    headers.set("Content-Type", "text/csv; charset=utf-8")?;
+    headers.set(
+        "Content-Disposition",
+        &format!("attachment; filename=\"{}\"", filename),
+    )?;
+    headers.set("X-Export-Row-Limit", &MAX_ANALYTICS_EXPORT_ROWS.to_string())?;
+    if gated {
+        headers.set("X-Analytics-Gated", "retention_limited")?;
+    }
+    Ok(response)
+}
+
+/// Cursor carried between streamed pages of a link export
+struct LinkExportCursor {
+    db: D1Database,
+    link_id: String,
+    org_id: String,
+    start: i64,
+    end: i64,
+    after_id: i64,
+    remaining: i64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/links/{id}/analytics/export",
+    tag = "Links",
+    summary = "Export link clicks as CSV",
+    description = "Streams the link's raw click events as CSV (timestamp, referrer, user_agent, country, city), oldest first. Accepts the same time range parameters as GET /api/links/{id}/analytics, and the start is raised to the tier's retention window in the same way (X-Analytics-Gated is then set). At most 50000 events are exported; when the window holds more, X-Export-Truncated is set to true",
+    params(
+        ("id" = String, Path, description = "Link ID"),
+        ("days" = Option<i64>, Query, description = "Number of days to look back (default: 7)"),
+        ("start" = Option<i64>, Query, description = "Unix timestamp range start (alternative to days)"),
+        ("end" = Option<i64>, Query, description = "Unix timestamp range end"),
+    ),
+    responses(
+        (status = 200, description = "CSV file download"),
+        (status = 400, description = "Invalid time_range parameter"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Link not found"),
+    ),
+    security(
+        ("Bearer" = []),
+        ("session_cookie" = [])
+    )
+)]
+pub async fn handle_export_link_analytics(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_export_link(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_export_link(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+
+    let link_id = ctx
+        .param("id")
+        .ok_or_else(|| AppError::BadRequest("Missing link ID".to_string()))?
+        .to_string();
+
+    let url = req.url()?;
+    let time_range = parse_link_time_range(url.query().unwrap_or(""))?;
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let (link, start, end, gated) =
+        get_link_export_window(&db, &link_id, &user_ctx.org_id, time_range).await?;
+    let total = AnalyticsRepository::new()
+        .count_link_events_in_range(&db, &link_id, &user_ctx.org_id, start, end)
+        .await?;
+
+    let cursor = LinkExportCursor {
+        db,
+        link_id,
+        org_id: user_ctx.org_id,
+        start,
+        end,
+        after_id: 0,
+        remaining: MAX_LINK_ANALYTICS_EXPORT_ROWS,
+    };
+
+    let header = stream::once(async { Ok::<Vec<u8>, Error>(LINK_CSV_HEADER.as_bytes().to_vec()) });
+    let pages = stream::unfold(Some(cursor), |cursor| async move {
+        let mut cursor = cursor?;
+        if cursor.remaining <= 0 {
+            return None;
+        }
+        let page = match AnalyticsRepository::new()
+            .get_link_analytics_events_in_range(
+                &cursor.db,
+                &cursor.link_id,
+                &cursor.org_id,
+                cursor.start,
+                cursor.end,
+                cursor.after_id,
+                ANALYTICS_EXPORT_PAGE_SIZE.min(cursor.remaining),
+            )
+            .await
+        {
+            Ok(page) => page,
+            // Surface the error to the stream, then stop
+            Err(e) => return Some((Err(e), None)),
+        };
+        let last = page.last()?;
+        cursor.after_id = last.id;
+        cursor.remaining -= page.len() as i64;
+
+        let chunk: String = page.iter().map(link_event_csv_row).collect();
+        let next = (page.len() as i64 == ANALYTICS_EXPORT_PAGE_SIZE).then_some(cursor);
+        Some((Ok(chunk.into_bytes()), next))
+    });
+
+    let date_str = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let filename = format!("rushomon-clicks-{}-{}.csv", link.short_code, date_str);
+    let mut response = Response::from_stream(Box::pin(header.chain(pages)))?;
+    let headers = response.headers_mut();
+    headers.set("Content-Type", "text/csv; charset=utf-8")?;
+    headers.set(
+        "Content-Disposition",
+        &format!("attachment; filename=\"{}\"", filename),
+    )?;
+    headers.set(
+        "X-Export-Row-Limit",
+        &MAX_LINK_ANALYTICS_EXPORT_ROWS.to_string(),
+    )?;
+    headers.set("X-Export-Total-Rows", &total.to_string())?;
+    if total > MAX_LINK_ANALYTICS_EXPORT_ROWS {
+        headers.set("X-Export-Truncated", "true")?;
+    }
+    if gated {
+        headers.set("X-Analytics-Gated", "retention_limited")?;
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_csv_row_escapes_fields() {
+        let event = ExportedAnalyticsEvent {
+            id: 42,
+            timestamp: 1_700_000_000,
+            link_id: "link-1".to_string(),
+            link_short_code: "promo".to_string(),
+            referrer: Some("https://example.com/?a=1,b=2".to_string()),
+            user_agent: Some("Agent \"X\"".to_string()),
+            country: Some("US".to_string()),
+            city: None,
+        };
+        assert_eq!(
+            event_csv_row(&event),
+            "42,2023-11-14T22:13:20Z,promo,link-1,\"https://example.com/?a=1,b=2\",\"Agent \"\"X\"\"\",US,\n"
+        );
+        assert_eq!(CSV_HEADER.trim_end().split(',').count(), 8);
+    }
+
+    #[test]
+    fn test_link_event_csv_row_matches_header() {
+        let event = LinkEvent {
+            id: 7,
+            timestamp: 1_700_000_000,
+            referrer: None,
+            country: Some("DE".to_string()),
+            city: Some("Köln, NRW".to_string()),
+            user_agent: Some("curl/8.0".to_string()),
+        };
+        assert_eq!(
+            link_event_csv_row(&event),
+            "2023-11-14T22:13:20Z,,curl/8.0,DE,\"Köln, NRW\"\n"
+        );
+        assert_eq!(LINK_CSV_HEADER.trim_end().split(',').count(), 5);
+    }
+}
@@ -3,7 +3,10 @@
 /// GET /api/links/:id/analytics — click analytics for a single link.
 use crate::auth;
 use crate::models::{LinkAnalyticsResponse, TimeRange};
-use crate::services::analytics_service::get_link_analytics;
+use crate::services::analytics_service::{
+    get_link_analytics, parse_compare_param, parse_granularity_param, parse_max_points_param,
+    parse_tz_offset_param,
+};
 use crate::utils::AppError;
 use worker::d1::D1Database;
 use worker::*;
@@ -24,6 +27,46 @@ fn extract_query_param(query: &str, name: &str) -> Result<String> {
         .ok_or_else(|| Error::RustError(format!("Missing {} parameter", name)))
 }
 
+/// Parse the time range of a per-link analytics request. Supports the
+/// `time_range` JSON object, `days`, and legacy `start`/`end` timestamps
+/// (defaulting to the last 7 days).
+pub(crate) fn parse_link_time_range(query: &str) -> Result<TimeRange, AppError> {
+    // Try to parse as new TimeRange format first
+    let time_range = if let Ok(time_range_str) = extract_query_param(query, "time_range") {
+        // New format: JSON TimeRange object
+        serde_json::from_str::<TimeRange>(&time_range_str)
+            .map_err(|e| AppError::BadRequest(format!("Invalid time_range parameter: {}", e)))?
+    } else if let Ok(days_str) = extract_query_param(query, "days") {
+        // Simple days parameter (e.g., ?days=7)
+        let days = days_str.parse::<i64>().unwrap_or(7);
+        TimeRange::Days { value: days }
+    } else {
+        // Legacy format: start/end timestamps for backward compatibility
+        let now = crate::models::analytics::now_timestamp();
+
+        let start_legacy = query
+            .split('&')
+            .find(|s| s.starts_with("start="))
+            .and_then(|s| s.split('=').nth(1))
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_else(|| now - 7 * 24 * 60 * 60); // Default: 7 days ago
+
+        let end_legacy = query
+            .split('&')
+            .find(|s| s.starts_with("end="))
+            .and_then(|s| s.split('=').nth(1))
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(now);
+
+        TimeRange::Custom {
+            start: start_legacy,
+            end: end_legacy,
+        }
+    };
+
+    Ok(time_range)
+}
+
 #[utoipa::path(
     get,
     path = "/api/links/{id}/analytics",
@@ -35,9 +78,14 @@ fn extract_query_param(query: &str, name: &str) -> Result<String> {
         ("days" = Option<i64>, Query, description = "Number of days to look back (default: 7)"),
         ("start" = Option<i64>, Query, description = "Unix timestamp range start (alternative to days)"),
         ("end" = Option<i64>, Query, description = "Unix timestamp range end"),
+        ("compare" = Option<String>, Query, description = "Set to 'previous' to also return total clicks for the preceding window of equal length, with the percentage change"),
+        ("tz_offset_minutes" = Option<i64>, Query, description = "Viewer's UTC offset in minutes (-720 to 840, e.g. 540 for UTC+9). clicks_over_time is bucketed by local day; default UTC"),
+        ("granularity" = Option<String>, Query, description = "Bucket size for clicks_over_time: hour, day (default), week (starting Monday) or month. Hourly buckets are limited to ranges of 31 days"),
+        ("max_points" = Option<u32>, Query, description = "Most buckets clicks_over_time may return (1-1000). When the window spans more buckets than this, clicks are summed into at most max_points evenly-spaced intervals starting at the window start, and bucket_seconds gives their length. Full resolution otherwise"),
     ),
     responses(
         (status = 200, description = "Analytics data for the link"),
+        (status = 400, description = "Invalid compare, tz_offset_minutes, granularity or max_points value, or a range too wide for hourly buckets"),
         (status = 401, description = "Unauthorized"),
         (status = 404, description = "Link not found"),
     ),
@@ -60,45 +108,37 @@ async fn inner(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError
 
     let db = ctx.env.get_binding::<D1Database>("rushomon")?;
 
-    // Parse time range from query parameters
-    // Support both new format (TimeRange enum) and legacy format (start/end timestamps)
     let url = req.url()?;
     let query = url.query().unwrap_or("");
 
-    // Try to parse as new TimeRange format first
-    let time_range = if let Ok(time_range_str) = extract_query_param(query, "time_range") {
-        // New format: JSON TimeRange object
-        serde_json::from_str::<TimeRange>(&time_range_str)
-            .map_err(|e| AppError::BadRequest(format!("Invalid time_range parameter: {}", e)))?
-    } else if let Ok(days_str) = extract_query_param(query, "days") {
-        // Simple days parameter (e.g., ?days=7)
-        let days = days_str.parse::<i64>().unwrap_or(7);
-        TimeRange::Days { value: days }
-    } else {
-        // Legacy format: start/end timestamps for backward compatibility
-        let now = crate::models::analytics::now_timestamp();
+    let time_range = parse_link_time_range(query)?;
 
-        let start_legacy = query
-            .split('&')
-            .find(|s| s.starts_with("start="))
-            .and_then(|s| s.split('=').nth(1))
-            .and_then(|s| s.parse().ok())
-            .unwrap_or_else(|| now - 7 * 24 * 60 * 60); // Default: 7 days ago
+    let compare_previous =
+        parse_compare_param(extract_query_param(query, "compare").ok().as_deref())?;
 
-        let end_legacy = query
-            .split('&')
-            .find(|s| s.starts_with("end="))
-            .and_then(|s| s.split('=').nth(1))
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(now);
+    let tz_offset_minutes = parse_tz_offset_param(
+        extract_query_param(query, "tz_offset_minutes")
+            .ok()
+            .as_deref(),
+    )?;
 
-        TimeRange::Custom {
-            start: start_legacy,
-            end: end_legacy,
-        }
-    };
+    let granularity =
+        parse_granularity_param(extract_query_param(query, "granularity").ok().as_deref())?;
 
-    let analytics_result = get_link_analytics(&db, link_id, org_id, time_range).await?;
+    let max_points =
+        parse_max_points_param(extract_query_param(query, "max_points").ok().as_deref())?;
+
+    let analytics_result = get_link_analytics(
+        &db,
+        link_id,
+        org_id,
+        time_range,
+        compare_previous,
+        tz_offset_minutes,
+        granularity,
+        max_points,
+    )
+    .await?;
 
     let response = LinkAnalyticsResponse {
         link: analytics_result.link,
@@ -113,6 +153,10 @@ async fn inner(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError
             None
         },
         gated_reason: analytics_result.gated_reason,
+        comparison: analytics_result.comparison,
+        sample_rate: analytics_result.sample_rate,
+        sampled: analytics_result.sample_rate > 1,
+        bucket_seconds: analytics_result.bucket_seconds,
     };
 
     Ok(Response::from_json(&response)?)
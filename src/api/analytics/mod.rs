@@ -1,6 +1,9 @@
 /// Analytics API handlers
 ///
-/// Org-level analytics, per-link analytics, and usage endpoints.
+/// Org-level analytics, per-link analytics and raw events, org CSV export, and
+/// usage endpoints.
+pub mod events;
+pub mod export;
 pub mod link;
 pub mod org;
 pub mod usage;
@@ -1,9 +1,17 @@
 /// Org-level analytics handler
 ///
 /// GET /api/analytics/org — aggregate click analytics for the entire organization.
+/// GET /api/orgs/{id}/analytics/top-countries — most active countries for an org.
+/// GET /api/orgs/{id}/analytics/header-fields — clicks by recorded header value.
 use crate::auth;
-use crate::services::analytics_service::{get_org_analytics, parse_time_range_from_query};
-use crate::utils::AppError;
+use crate::models::org_redirect_config::validate_analytics_header_fields;
+use crate::services::OrgService;
+use crate::services::analytics_service::{
+    get_org_analytics, get_org_header_field_breakdown, get_org_top_countries, parse_compare_param,
+    parse_time_range_from_query,
+};
+use crate::utils::{AppError, QueryParams};
+use chrono::{Datelike, TimeZone};
 use worker::d1::D1Database;
 use worker::*;
 
@@ -17,9 +25,11 @@ use worker::*;
         ("days" = Option<i64>, Query, description = "Number of days to look back (default: 7)"),
         ("start" = Option<i64>, Query, description = "Unix timestamp range start (alternative to days)"),
         ("end" = Option<i64>, Query, description = "Unix timestamp range end"),
+        ("compare" = Option<String>, Query, description = "Set to 'previous' to also return total clicks for the preceding window of equal length, with the percentage change"),
     ),
     responses(
         (status = 200, description = "Org analytics response with clicks, top links, referrers, countries, and user agents"),
+        (status = 400, description = "Invalid compare value"),
         (status = 401, description = "Unauthorized"),
         (status = 404, description = "Organization not found"),
     ),
@@ -41,10 +51,12 @@ async fn inner(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError
 
     // Parse time range: ?days=N, ?start=UNIX&end=UNIX
     let time_range = parse_time_range_from_query(query);
+    let compare_previous =
+        parse_compare_param(QueryParams::from_request(&req)?.get("compare").as_deref())?;
 
     let db = ctx.env.get_binding::<D1Database>("rushomon")?;
 
-    let analytics_result = get_org_analytics(&db, org_id, time_range).await?;
+    let analytics_result = get_org_analytics(&db, org_id, time_range, compare_previous).await?;
 
     let response = crate::models::analytics::OrgAnalyticsResponse {
         total_clicks: analytics_result.total_clicks,
@@ -60,7 +72,162 @@ async fn inner(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError
             None
         },
         gated_reason: analytics_result.gated_reason,
+        comparison: analytics_result.comparison,
+        sampled: analytics_result.sample_rate > 1,
+        sample_rate: analytics_result.sample_rate,
     };
 
     Ok(Response::from_json(&response)?)
 }
+
+/// Default number of countries returned by the top-countries endpoint
+const DEFAULT_TOP_COUNTRIES_LIMIT: u32 = 10;
+/// Maximum number of countries returned by the top-countries endpoint
+const MAX_TOP_COUNTRIES_LIMIT: u32 = 50;
+
+#[utoipa::path(
+    get,
+    path = "/api/orgs/{id}/analytics/top-countries",
+    tag = "Analytics",
+    summary = "Get an org's top countries",
+    description = "Returns the organization's most active countries by clicks across all of its links. Defaults to the current calendar month (UTC); pass days or start/end to change the window. The window is capped by tier retention. Missing countries are reported as \"Unknown\"",
+    params(
+        ("id" = String, Path, description = "Organization ID"),
+        ("days" = Option<i64>, Query, description = "Number of days to look back (default: current month)"),
+        ("start" = Option<i64>, Query, description = "Unix timestamp range start (alternative to days)"),
+        ("end" = Option<i64>, Query, description = "Unix timestamp range end"),
+        ("limit" = Option<u32>, Query, description = "Number of countries (default: 10, max: 50)"),
+    ),
+    responses(
+        (status = 200, description = "Top countries ordered by clicks", body = crate::models::analytics::OrgTopCountriesResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Organization not found"),
+    ),
+    security(
+        ("Bearer" = []),
+        ("session_cookie" = [])
+    )
+)]
+pub async fn handle_get_org_top_countries(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_top_countries(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_top_countries(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+
+    let org_id = ctx
+        .param("id")
+        .ok_or_else(|| AppError::BadRequest("Missing org id".to_string()))?
+        .to_string();
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    OrgService::new()
+        .get_org_as_member(&db, &org_id, &user_ctx.user_id)
+        .await?;
+
+    let params = QueryParams::from_request(&req)?;
+    let limit = params
+        .get_u32("limit")
+        .unwrap_or(DEFAULT_TOP_COUNTRIES_LIMIT)
+        .clamp(1, MAX_TOP_COUNTRIES_LIMIT);
+
+    let (start, end) = org_breakdown_window(&req, &params)?;
+
+    let response = get_org_top_countries(&db, &org_id, start, end, limit as i64).await?;
+
+    Ok(Response::from_json(&response)?)
+}
+
+/// Default number of values returned by the header-fields endpoint
+const DEFAULT_HEADER_FIELD_VALUES_LIMIT: u32 = 10;
+/// Maximum number of values returned by the header-fields endpoint
+const MAX_HEADER_FIELD_VALUES_LIMIT: u32 = 100;
+
+#[utoipa::path(
+    get,
+    path = "/api/orgs/{id}/analytics/header-fields",
+    tag = "Analytics",
+    summary = "Get an org's clicks by recorded header value",
+    description = "Returns the organization's clicks grouped by the value of a request header recorded through the analytics_header_fields org setting. Clicks that did not record the header are skipped. Defaults to the current calendar month (UTC); pass days or start/end to change the window. The window is capped by tier retention",
+    params(
+        ("id" = String, Path, description = "Organization ID"),
+        ("field" = String, Query, description = "Header name, e.g. x-tenant (case-insensitive)"),
+        ("days" = Option<i64>, Query, description = "Number of days to look back (default: current month)"),
+        ("start" = Option<i64>, Query, description = "Unix timestamp range start (alternative to days)"),
+        ("end" = Option<i64>, Query, description = "Unix timestamp range end"),
+        ("limit" = Option<u32>, Query, description = "Number of values (default: 10, max: 100)"),
+    ),
+    responses(
+        (status = 200, description = "Header values ordered by clicks", body = crate::models::analytics::OrgHeaderFieldBreakdownResponse),
+        (status = 400, description = "Missing or invalid field parameter"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Organization not found"),
+    ),
+    security(
+        ("Bearer" = []),
+        ("session_cookie" = [])
+    )
+)]
+pub async fn handle_get_org_header_field_breakdown(
+    req: Request,
+    ctx: RouteContext<()>,
+) -> Result<Response> {
+    Ok(inner_header_field_breakdown(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_header_field_breakdown(
+    req: Request,
+    ctx: RouteContext<()>,
+) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+
+    let org_id = ctx
+        .param("id")
+        .ok_or_else(|| AppError::BadRequest("Missing org id".to_string()))?
+        .to_string();
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    OrgService::new()
+        .get_org_as_member(&db, &org_id, &user_ctx.user_id)
+        .await?;
+
+    let params = QueryParams::from_request(&req)?;
+    let raw_field = params
+        .get("field")
+        .ok_or_else(|| AppError::BadRequest("Missing 'field' parameter".to_string()))?;
+    let field = validate_analytics_header_fields(&[raw_field])
+        .map_err(AppError::BadRequest)?
+        .pop()
+        .ok_or_else(|| AppError::BadRequest("Missing 'field' parameter".to_string()))?;
+    let limit = params
+        .get_u32("limit")
+        .unwrap_or(DEFAULT_HEADER_FIELD_VALUES_LIMIT)
+        .clamp(1, MAX_HEADER_FIELD_VALUES_LIMIT);
+
+    let (start, end) = org_breakdown_window(&req, &params)?;
+
+    let response =
+        get_org_header_field_breakdown(&db, &org_id, &field, start, end, limit as i64).await?;
+
+    Ok(Response::from_json(&response)?)
+}
+
+/// Window for the org breakdown endpoints: `days` or `start`/`end` when
+/// given, otherwise the current calendar month (UTC).
+fn org_breakdown_window(req: &Request, params: &QueryParams) -> Result<(i64, i64), AppError> {
+    if params.get("days").is_some() || params.get("start").is_some() {
+        let url = req.url()?;
+        return Ok(parse_time_range_from_query(url.query().unwrap_or("")).calculate_timestamps());
+    }
+    let now = chrono::Utc::now();
+    let month_start = chrono::Utc
+        .with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+        .single()
+        .map(|d| d.timestamp())
+        .unwrap_or(0);
+    Ok((month_start, now.timestamp()))
+}
@@ -2,7 +2,7 @@
 ///
 /// GET /api/usage — returns tier, limits, current monthly usage, and next reset.
 use crate::auth;
-use crate::services::analytics_service::get_usage;
+use crate::services::analytics_service::{UsageInfo, get_usage};
 use crate::utils::AppError;
 use worker::d1::D1Database;
 use worker::*;
@@ -12,7 +12,7 @@ use worker::*;
     path = "/api/usage",
     tag = "Usage",
     summary = "Get current usage",
-    description = "Returns the authenticated organization's tier, feature limits, current monthly link usage, tag count, and the date/time of the next monthly counter reset",
+    description = "Returns the authenticated organization's tier, feature limits, current monthly link usage, tag count, custom domain count, and the date/time of the next monthly counter reset",
     responses(
         (status = 200, description = "Usage and limits for the current org"),
         (status = 401, description = "Unauthorized"),
@@ -34,7 +34,12 @@ async fn inner(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError
 
     let usage_info = get_usage(&db, org_id).await?;
 
-    let usage = serde_json::json!({
+    Ok(Response::from_json(&usage_json(&usage_info))?)
+}
+
+/// JSON body of /api/usage, shared with the dashboard bootstrap endpoint
+pub fn usage_json(usage_info: &UsageInfo) -> serde_json::Value {
+    serde_json::json!({
         "tier": usage_info.tier,
         "limits": {
             "max_links_per_month": usage_info.limits.max_links_per_month,
@@ -49,12 +54,11 @@ async fn inner(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError
         "usage": {
             "links_created_this_month": usage_info.links_created_this_month,
             "tags_count": usage_info.tags_count,
+            "custom_domains_count": usage_info.custom_domains_count,
         },
         "next_reset": {
             "utc": usage_info.next_reset_utc,
             "timestamp": usage_info.next_reset_timestamp,
         }
-    });
-
-    Ok(Response::from_json(&usage)?)
+    })
 }
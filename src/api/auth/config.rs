@@ -0,0 +1,61 @@
+/// Public auth config handler
+///
+/// GET /api/auth/config — tells the login page whether new accounts can be
+/// created and which OAuth providers are available, so it can hide the
+/// sign-up option instead of letting the OAuth flow fail with
+/// SIGNUPS_DISABLED.
+use crate::auth;
+use crate::middleware::{RateLimitConfig, RateLimitSettings, RateLimiter};
+use crate::services::SettingsService;
+use crate::utils::get_client_ip;
+use worker::*;
+
+#[utoipa::path(
+    get,
+    path = "/api/auth/config",
+    tag = "Authentication",
+    summary = "Get public auth config",
+    description = "Returns `{signups_enabled, providers}`: whether new users may sign up (the `signups_enabled` setting; always true while the instance has no users) and the names of the enabled OAuth providers. Public, rate-limited per IP",
+    responses(
+        (status = 200, description = "Auth config"),
+        (status = 429, description = "Rate limit exceeded"),
+    )
+)]
+pub async fn handle_get_auth_config(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let kv = ctx.kv("URL_MAPPINGS")?;
+
+    let client_ip = get_client_ip(&req);
+    let rate_limit_key = RateLimiter::ip_key("auth_config", &client_ip);
+    if let Err(err) = RateLimiter::check(
+        &kv,
+        &rate_limit_key,
+        &RateLimitConfig::auth_config(),
+        &RateLimitSettings::from_env(&ctx.env),
+        &client_ip,
+    )
+    .await
+    {
+        let mut response = Response::error(err.to_error_response(), 429)?;
+        if let Some(retry_after) = err.retry_after() {
+            response
+                .headers_mut()
+                .set("Retry-After", &retry_after.to_string())?;
+        }
+        return Ok(response);
+    }
+
+    let db = ctx.env.get_binding::<worker::d1::D1Database>("rushomon")?;
+    let signups_enabled = SettingsService::new().are_signups_enabled(&db).await?;
+
+    let providers: Vec<&str> = auth::providers::available_oauth_providers(&ctx.env)
+        .into_iter()
+        .map(|p| p.name)
+        .collect();
+
+    let response = Response::from_json(&serde_json::json!({
+        "signups_enabled": signups_enabled,
+        "providers": providers,
+    }))?;
+    let origin = req.headers().get("Origin").ok().flatten();
+    Ok(crate::add_cors_headers(response, origin, &ctx.env))
+}
@@ -1,6 +1,8 @@
 /// Auth API handlers
 ///
-/// Endpoints for OAuth providers listing, current user, token refresh, and logout.
+/// Endpoints for OAuth providers listing, public auth config, current user,
+/// token refresh, and logout.
+pub mod config;
 pub mod oauth;
 pub mod providers;
 pub mod session;
@@ -4,7 +4,7 @@
 /// GET /api/auth/google    - Initiate Google OAuth
 /// GET /api/auth/callback  - OAuth provider callback
 use crate::auth;
-use crate::middleware::{RateLimitConfig, RateLimiter, is_kv_rate_limiting_enabled};
+use crate::middleware::{RateLimitConfig, RateLimitSettings, RateLimiter};
 use crate::services::OAuthService;
 use crate::utils::{get_client_ip, get_frontend_url, hash_ip};
 use worker::*;
@@ -50,7 +50,8 @@ pub async fn handle_github_login(req: Request, ctx: RouteContext<()>) -> Result<
         &kv,
         &rate_limit_key,
         &rate_limit_config,
-        is_kv_rate_limiting_enabled(&ctx.env),
+        &RateLimitSettings::from_env(&ctx.env),
+        &client_ip,
     )
     .await
     {
@@ -102,7 +103,8 @@ pub async fn handle_google_login(req: Request, ctx: RouteContext<()>) -> Result<
         &kv,
         &rate_limit_key,
         &rate_limit_config,
-        is_kv_rate_limiting_enabled(&ctx.env),
+        &RateLimitSettings::from_env(&ctx.env),
+        &client_ip,
     )
     .await
     {
@@ -159,7 +161,8 @@ pub async fn handle_oauth_callback(req: Request, ctx: RouteContext<()>) -> Resul
         &kv,
         &rate_limit_key,
         &rate_limit_config,
-        is_kv_rate_limiting_enabled(&ctx.env),
+        &RateLimitSettings::from_env(&ctx.env),
+        &client_ip,
     )
     .await
     {
@@ -206,6 +209,15 @@ pub async fn handle_oauth_callback(req: Request, ctx: RouteContext<()>) -> Resul
                 return Ok(Response::empty()?.with_status(302).with_headers(headers));
             }
 
+            // Check if the email domain is outside the signup allowlist
+            if error_msg.contains("EMAIL_DOMAIN_NOT_ALLOWED") {
+                let frontend_url = get_frontend_url(&ctx.env);
+                let redirect_url = format!("{}/?error=email_domain_not_allowed", frontend_url);
+                let headers = Headers::new();
+                headers.set("Location", &redirect_url)?;
+                return Ok(Response::empty()?.with_status(302).with_headers(headers));
+            }
+
             // Check if email is already used by different provider
             if error_msg.contains("EMAIL_ALREADY_USED") {
                 let frontend_url = get_frontend_url(&ctx.env);
@@ -229,7 +241,11 @@ pub async fn handle_oauth_callback(req: Request, ctx: RouteContext<()>) -> Resul
 
     // Extract session ID from access token claims and store session in KV
     let jwt_secret = ctx.env.secret("JWT_SECRET")?.to_string();
-    let claims = auth::session::validate_jwt(&result.tokens.access_token, &jwt_secret)?;
+    let claims = auth::session::validate_jwt(
+        &result.tokens.access_token,
+        &jwt_secret,
+        &auth::session::JwtClaimsConfig::from_env(&ctx.env),
+    )?;
 
     // Store session in KV
     auth::session::store_session(
@@ -240,25 +256,19 @@ pub async fn handle_oauth_callback(req: Request, ctx: RouteContext<()>) -> Resul
     )
     .await?;
 
-    // Get frontend URL and determine scheme
+    // Get frontend URL and cookie attributes (Secure/SameSite/Domain)
     let frontend_url = get_frontend_url(&ctx.env);
-    let domain = ctx
-        .env
-        .var("DOMAIN")
-        .map(|v| v.to_string())
-        .unwrap_or_else(|_| "localhost:8787".to_string());
-
-    let scheme = if domain.starts_with("localhost") {
-        "http"
-    } else {
-        "https"
-    };
+    let cookie_config = auth::session::CookieConfig::from_env(&ctx.env)?;
 
     // Set both access and refresh tokens as httpOnly cookies (no token in URL)
-    let access_cookie =
-        auth::session::create_access_cookie_with_scheme(&result.tokens.access_token, scheme);
-    let refresh_cookie =
-        auth::session::create_refresh_cookie_with_scheme(&result.tokens.refresh_token, scheme);
+    let access_cookie = auth::session::create_access_cookie_with_config(
+        &result.tokens.access_token,
+        &cookie_config,
+    );
+    let refresh_cookie = auth::session::create_refresh_cookie_with_config(
+        &result.tokens.refresh_token,
+        &cookie_config,
+    );
 
     // Redirect to frontend WITHOUT token in URL (cookies set automatically)
     // Use stored redirect from OAuth state if present, otherwise default to auth callback
@@ -274,6 +284,12 @@ pub async fn handle_oauth_callback(req: Request, ctx: RouteContext<()>) -> Resul
     // Note: Multiple Set-Cookie headers need to be appended separately
     headers.append("Set-Cookie", &access_cookie)?;
     headers.append("Set-Cookie", &refresh_cookie)?;
+    if let Some(provider_cookie) = auth::session::create_last_provider_cookie_with_config(
+        &result.user.oauth_provider,
+        &cookie_config,
+    ) {
+        headers.append("Set-Cookie", &provider_cookie)?;
+    }
 
     Ok(Response::empty()?.with_status(302).with_headers(headers))
 }
@@ -9,7 +9,7 @@ use worker::*;
     path = "/api/auth/providers",
     tag = "Authentication",
     summary = "List enabled OAuth providers",
-    description = "Returns the list of OAuth providers configured and enabled on this instance",
+    description = "Returns the OAuth providers configured on this instance (client ID and secret both set), so the login page only renders working buttons",
     responses(
         (status = 200, description = "List of enabled providers"),
     )
@@ -22,14 +22,10 @@ async fn inner(req: Request, ctx: RouteContext<()>) -> Response {
     use serde_json::json;
 
     let env = &ctx.env;
-    let mut providers = Vec::new();
-
-    if auth::providers::GITHUB.is_enabled(env) {
-        providers.push(json!({ "name": "github", "label": "GitHub" }));
-    }
-    if auth::providers::GOOGLE.is_enabled(env) {
-        providers.push(json!({ "name": "google", "label": "Google" }));
-    }
+    let providers: Vec<_> = auth::providers::available_oauth_providers(env)
+        .into_iter()
+        .map(|p| json!({ "name": p.name, "label": p.label }))
+        .collect();
 
     let origin = req.headers().get("Origin").ok().flatten();
     match Response::from_json(&json!({ "providers": providers })) {
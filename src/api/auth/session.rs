@@ -4,9 +4,9 @@
 /// POST /api/auth/refresh — refresh access token
 /// POST /api/auth/logout  — logout and clear cookies
 use crate::auth;
-use crate::middleware::{RateLimitConfig, RateLimiter, is_kv_rate_limiting_enabled};
+use crate::middleware::{RateLimitConfig, RateLimitSettings, RateLimiter};
 use crate::services::AuthService;
-use crate::utils::AppError;
+use crate::utils::{AppError, get_client_ip};
 use worker::d1::D1Database;
 use worker::*;
 
@@ -46,7 +46,8 @@ async fn inner_get_current_user(req: Request, ctx: RouteContext<()>) -> Result<R
         &kv,
         &rate_limit_key,
         &rate_limit_config,
-        is_kv_rate_limiting_enabled(&ctx.env),
+        &RateLimitSettings::from_env(&ctx.env),
+        &get_client_ip(&req),
     )
     .await
     {
@@ -113,7 +114,11 @@ pub async fn handle_token_refresh(req: Request, ctx: RouteContext<()>) -> Result
 
     // Validate refresh token JWT
     let jwt_secret = ctx.env.secret("JWT_SECRET")?.to_string();
-    let claims = match auth::session::validate_jwt(&refresh_token, &jwt_secret) {
+    let claims = match auth::session::validate_jwt(
+        &refresh_token,
+        &jwt_secret,
+        &auth::session::JwtClaimsConfig::from_env(&ctx.env),
+    ) {
         Ok(claims) => claims,
         Err(_) => return Response::error("Invalid or expired refresh token", 401),
     };
@@ -131,7 +136,8 @@ pub async fn handle_token_refresh(req: Request, ctx: RouteContext<()>) -> Result
         &kv,
         &rate_limit_key,
         &rate_limit_config,
-        is_kv_rate_limiting_enabled(&ctx.env),
+        &RateLimitSettings::from_env(&ctx.env),
+        &get_client_ip(&req),
     )
     .await
     {
@@ -193,22 +199,13 @@ pub async fn handle_token_refresh(req: Request, ctx: RouteContext<()>) -> Result
         &claims.session_id,
         &user.role,
         &jwt_secret,
+        &auth::session::JwtClaimsConfig::from_env(&ctx.env),
     )?;
 
-    // Determine scheme for cookie (secure flag)
-    let domain = ctx
-        .env
-        .var("DOMAIN")
-        .map(|v| v.to_string())
-        .unwrap_or_else(|_| "localhost:8787".to_string());
-
-    let scheme = if domain.starts_with("localhost") {
-        "http"
-    } else {
-        "https"
-    };
+    let cookie_config = auth::session::CookieConfig::from_env(&ctx.env)?;
 
-    let access_cookie = auth::session::create_access_cookie_with_scheme(&new_access_token, scheme);
+    let access_cookie =
+        auth::session::create_access_cookie_with_config(&new_access_token, &cookie_config);
 
     let mut response = Response::ok("Token refreshed successfully")?;
     response.headers_mut().set("Set-Cookie", &access_cookie)?;
@@ -246,10 +243,12 @@ async fn inner_logout(req: Request, ctx: RouteContext<()>) -> Result<Response, A
     let auth_service = AuthService::new();
     auth_service.logout(&kv, &user_ctx.session_id).await?;
 
-    // Clear all three cookies: access token, refresh token, and legacy session
-    let access_cookie = auth::session::create_access_logout_cookie();
-    let refresh_cookie = auth::session::create_refresh_logout_cookie();
-    let session_cookie = auth::session::create_logout_cookie();
+    // Clear all three cookies: access token, refresh token, and legacy session.
+    // Domain/SameSite must match the original cookies or the browser keeps them.
+    let cookie_config = auth::session::CookieConfig::from_env(&ctx.env)?;
+    let access_cookie = auth::session::create_access_logout_cookie_with_config(&cookie_config);
+    let refresh_cookie = auth::session::create_refresh_logout_cookie_with_config(&cookie_config);
+    let session_cookie = auth::session::create_logout_cookie_with_config(&cookie_config);
 
     let mut response = Response::ok("Logged out successfully")?;
     response.headers_mut().set("Set-Cookie", &access_cookie)?;
@@ -0,0 +1,86 @@
+/// Dashboard bootstrap handler
+///
+/// GET /api/dashboard — everything the SPA needs on first load (current user,
+/// their orgs, current-org usage and the first page of links) in one response,
+/// instead of four separate round-trips. The individual endpoints remain.
+use crate::api::analytics::usage::usage_json;
+use crate::auth;
+use crate::models::link::{DEFAULT_LINK_SORT, LINK_SORT_OPTIONS};
+use crate::models::{PaginatedResponse, PaginationMeta};
+use crate::repositories::OrgRepository;
+use crate::services::analytics_service::get_usage;
+use crate::services::{AuthService, LinkService, OrgService};
+use crate::utils::{AppError, QueryParams};
+use worker::d1::D1Database;
+use worker::*;
+
+/// Links returned when no `limit` is given, matching GET /api/links
+const DASHBOARD_DEFAULT_LINKS_LIMIT: i64 = 20;
+const DASHBOARD_MAX_LINKS_LIMIT: i64 = 100;
+
+#[utoipa::path(
+    get,
+    path = "/api/dashboard",
+    tag = "Dashboard",
+    summary = "Get dashboard bootstrap data",
+    description = "Returns the current user (as /api/auth/me), their organizations and the active org_id (as /api/orgs), current-org usage (as /api/usage) and the first page of links in the org's default sort order (as /api/links) in a single response",
+    params(
+        ("limit" = Option<i64>, Query, description = "Links in the first page (default: 20, max: 100)"),
+    ),
+    responses(
+        (status = 200, description = "User, orgs, usage and first page of links"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "User not found"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_get_dashboard(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner(req, ctx).await.unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    let org_id = &user_ctx.org_id;
+
+    let limit = QueryParams::from_request(&req)?
+        .get("limit")
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(DASHBOARD_DEFAULT_LINKS_LIMIT)
+        .clamp(1, DASHBOARD_MAX_LINKS_LIMIT);
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+
+    // Sequential on purpose: D1 serializes queries on one connection anyway
+    let user = AuthService::new()
+        .get_user_by_id(&db, &user_ctx.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    let orgs = OrgService::new()
+        .list_user_orgs_with_tier(&db, &user_ctx.user_id, false)
+        .await?;
+
+    let usage_info = get_usage(&db, org_id).await?;
+
+    let org_default_sort = OrgRepository::new()
+        .get_default_link_sort(&db, org_id)
+        .await?;
+    let sort = if LINK_SORT_OPTIONS.contains(&org_default_sort.as_str()) {
+        org_default_sort.as_str()
+    } else {
+        DEFAULT_LINK_SORT
+    };
+    let (links, total, stats_json) = LinkService::new()
+        .list_links(&db, org_id, None, None, sort, limit, 0, None, None, None)
+        .await?;
+    let links_page =
+        PaginatedResponse::with_stats(links, PaginationMeta::new(1, limit, total), stats_json);
+
+    Ok(Response::from_json(&serde_json::json!({
+        "user": user,
+        "orgs": orgs,
+        "current_org_id": user_ctx.org_id,
+        "usage": usage_json(&usage_info),
+        "links": links_page,
+    }))?)
+}
@@ -73,8 +73,10 @@ async fn inner(mut req: Request, ctx: RouteContext<()>) -> Result<Response, AppE
             ));
         }
         Some(max) => {
+            // Counted across the whole billing account so extra orgs can't
+            // be used to sidestep the plan limit
             let current = CustomDomainRepository::new()
-                .count_non_failed_for_org(&db, &org_id)
+                .count_non_failed_for_billing_account(&db, &billing_account.id)
                 .await
                 .map_err(AppError::from)?;
             if current >= max {
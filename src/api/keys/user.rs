@@ -1,4 +1,5 @@
 use crate::auth::authenticate_request;
+use crate::models::ApiKeyScope;
 use crate::services::ApiKeyService;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
@@ -14,6 +15,11 @@ pub struct CreateApiKeyRequest {
     /// Defaults to all orgs the user belongs to when omitted or empty.
     #[serde(default)]
     pub org_ids: Vec<String>,
+    /// Optional list of scopes: `links:read`, `links:write`, `analytics:read`, `admin`.
+    /// Defaults to `admin` (full access) when omitted or empty.
+    #[serde(default)]
+    #[schema(example = json!(["links:read", "analytics:read"]))]
+    pub scopes: Vec<String>,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -33,6 +39,8 @@ pub struct CreateApiKeyResponse {
     pub expires_at: Option<i64>,
     /// The org IDs this key is authorized to act on behalf of.
     pub org_ids: Vec<String>,
+    /// What the key may do. Requests outside these scopes get 403 `insufficient_scope`.
+    pub scopes: Vec<ApiKeyScope>,
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -46,11 +54,11 @@ pub struct UpdateApiKeyOrgsRequest {
     path = "/api/settings/api-keys",
     tag = "API Keys",
     summary = "Create an API key",
-    description = "Generates a new personal access token (PAT) for programmatic API access. The raw token is returned only once — copy it immediately. Requires Pro tier or higher. Supply `org_ids` to restrict the key to specific organizations; omit to allow all orgs.",
+    description = "Generates a new personal access token (PAT) for programmatic API access. The raw token is returned only once — copy it immediately. Requires Pro tier or higher. Supply `org_ids` to restrict the key to specific organizations; omit to allow all orgs. Supply `scopes` (`links:read`, `links:write`, `analytics:read`, `admin`) to limit what the key may do; omit for full access.",
     request_body(content = CreateApiKeyRequest, description = "API key creation payload"),
     responses(
         (status = 200, description = "API key created with raw token", body = CreateApiKeyResponse),
-        (status = 400, description = "Empty name or invalid org IDs"),
+        (status = 400, description = "Empty name, invalid org IDs, or unknown scope"),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Plan does not support API keys"),
         (status = 404, description = "Organization not found"),
@@ -77,26 +85,31 @@ pub async fn handle_create_api_key(mut req: Request, ctx: RouteContext<()>) -> R
 
     let db = ctx.env.get_binding::<worker::d1::D1Database>("rushomon")?;
 
-    let (key_id, raw_token, hint, created_at, expires_at, org_ids) = match ApiKeyService::new()
-        .create(
-            &db,
-            &user_ctx.user_id,
-            &user_ctx.org_id,
-            &body.name,
-            body.expires_in_days,
-            body.org_ids,
-        )
-        .await
-    {
-        Ok(result) => result,
-        Err(worker::Error::RustError(msg)) if msg.contains("Upgrade to Pro") => {
-            return Response::error(msg, 403);
-        }
-        Err(worker::Error::RustError(msg)) if msg.contains("not in your membership list") => {
-            return Response::error(msg, 400);
-        }
-        Err(e) => return Err(e),
-    };
+    let (key_id, raw_token, hint, created_at, expires_at, org_ids, scopes) =
+        match ApiKeyService::new()
+            .create(
+                &db,
+                &user_ctx.user_id,
+                &user_ctx.org_id,
+                &body.name,
+                body.expires_in_days,
+                body.org_ids,
+                body.scopes,
+            )
+            .await
+        {
+            Ok(result) => result,
+            Err(worker::Error::RustError(msg)) if msg.contains("Upgrade to Pro") => {
+                return Response::error(msg, 403);
+            }
+            Err(worker::Error::RustError(msg)) if msg.contains("not in your membership list") => {
+                return Response::error(msg, 400);
+            }
+            Err(worker::Error::RustError(msg)) if msg.contains("Unknown API key scope") => {
+                return Response::error(msg, 400);
+            }
+            Err(e) => return Err(e),
+        };
 
     // Return the raw token EXACTLY ONCE
     Response::from_json(&serde_json::json!({
@@ -106,7 +119,8 @@ pub async fn handle_create_api_key(mut req: Request, ctx: RouteContext<()>) -> R
         "raw_token": raw_token,
         "created_at": created_at,
         "expires_at": expires_at,
-        "org_ids": org_ids
+        "org_ids": org_ids,
+        "scopes": scopes
     }))
 }
 
@@ -136,6 +150,25 @@ pub async fn handle_list_api_keys(req: Request, ctx: RouteContext<()>) -> Result
     Response::from_json(&keys)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/keys",
+    tag = "API Keys",
+    summary = "List API keys with last use",
+    description = "Same as `GET /api/settings/api-keys`: the authenticated user's active API keys, each with `last_used_at` (updated at most once a minute per key; null if never used). Keys unused for longer than the `api_key_inactive_revoke_days` setting are revoked automatically.",
+    responses(
+        (status = 200, description = "Array of active API keys"),
+        (status = 401, description = "Unauthorized"),
+    ),
+    security(
+        ("Bearer" = []),
+        ("session_cookie" = [])
+    )
+)]
+pub async fn handle_list_keys(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    handle_list_api_keys(req, ctx).await
+}
+
 #[utoipa::path(
     delete,
     path = "/api/settings/api-keys/{id}",
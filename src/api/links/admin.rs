@@ -15,9 +15,10 @@ use worker::*;
         ("org" = Option<String>, Query, description = "Filter by org ID"),
         ("email" = Option<String>, Query, description = "Filter by creator email"),
         ("domain" = Option<String>, Query, description = "Filter by destination domain"),
+        ("count_only" = Option<bool>, Query, description = "Return only `{\"total\": n}` for the filters, skipping rows and KV checks"),
     ),
     responses(
-        (status = 200, description = "Paginated list of all links with KV sync status"),
+        (status = 200, description = "Paginated list of all links with KV sync status, or the total when count_only=true"),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Admin required"),
     ),
@@ -81,9 +82,17 @@ async fn inner_admin_list_links(
     });
 
     let db = ctx.env.get_binding::<D1Database>("rushomon")?;
-    let kv = ctx.kv("URL_MAPPINGS")?;
     let service = LinkService::new();
 
+    if crate::utils::QueryParams::from_request(&req)?.flag("count_only") {
+        let total = service
+            .admin_count_links(&db, org_filter, email_filter, domain_filter)
+            .await?;
+        return Response::from_json(&serde_json::json!({ "total": total }))
+            .map_err(|e| crate::utils::AppError::Internal(format!("JSON error: {}", e)));
+    }
+
+    let kv = ctx.kv("URL_MAPPINGS")?;
     let (links, total) = service
         .admin_list_links(
             &db,
@@ -191,9 +200,10 @@ async fn inner_admin_update_link_status(
     path = "/api/admin/links/{id}",
     tag = "Admin",
     summary = "Hard delete a link",
+    description = "Deletes the link, its tags, reports and aliases. When the `deleted_link_retention_days` setting is above 0, the link's analytics are kept under a tombstone until `analytics_retained_until` (see `GET /api/admin/deleted-links/{id}/analytics`); otherwise they are purged too",
     params(("id" = String, Path, description = "Link ID")),
     responses(
-        (status = 200, description = "Link deleted"),
+        (status = 200, description = "Link deleted. Body: {success, message, analytics_retained_until}"),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Admin required"),
         (status = 404, description = "Link not found"),
@@ -222,15 +232,61 @@ async fn inner_admin_delete_link(
     let kv = ctx.kv("URL_MAPPINGS")?;
     let service = LinkService::new();
 
-    service.admin_delete_link(&db, &kv, &link_id).await?;
+    let retained_until = service
+        .admin_delete_link(&db, &kv, &link_id, &user_ctx.user_id)
+        .await?;
 
     Response::from_json(&serde_json::json!({
         "success": true,
-        "message": "Link deleted successfully"
+        "message": "Link deleted successfully",
+        "analytics_retained_until": retained_until
     }))
     .map_err(|e| crate::utils::AppError::Internal(format!("JSON error: {}", e)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/admin/deleted-links/{id}/analytics",
+    tag = "Admin",
+    summary = "Analytics of a deleted link",
+    description = "Analytics kept for an admin-deleted link while its retention period (the `deleted_link_retention_days` setting at deletion time) lasts: the tombstone record (status `deleted`), total clicks, top referrers and countries, and the most recent raw events",
+    params(("id" = String, Path, description = "ID of the deleted link")),
+    responses(
+        (status = 200, description = "Retained analytics", body = crate::models::deleted_link::DeletedLinkAnalyticsResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 404, description = "No retained analytics for this link"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_admin_get_deleted_link_analytics(
+    req: Request,
+    ctx: RouteContext<()>,
+) -> Result<Response> {
+    Ok(inner_admin_get_deleted_link_analytics(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_admin_get_deleted_link_analytics(
+    req: Request,
+    ctx: RouteContext<()>,
+) -> Result<Response, crate::utils::AppError> {
+    let user_ctx = crate::auth::authenticate_request(&req, &ctx).await?;
+    crate::auth::require_admin(&user_ctx)?;
+
+    let link_id = ctx
+        .param("id")
+        .ok_or_else(|| crate::utils::AppError::BadRequest("Missing link ID".to_string()))?
+        .to_string();
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let analytics =
+        crate::services::analytics_service::get_deleted_link_analytics(&db, &link_id).await?;
+
+    Ok(Response::from_json(&analytics)?)
+}
+
 #[utoipa::path(
     post,
     path = "/api/admin/links/{id}/sync-kv",
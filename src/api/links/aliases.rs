@@ -0,0 +1,151 @@
+/// Link alias handlers
+///
+/// GET    /api/links/{id}/aliases          - List a link's alias codes
+/// POST   /api/links/{id}/aliases          - Add an alias code
+/// DELETE /api/links/{id}/aliases/{code}   - Remove an alias code
+///
+/// Aliases redirect to the same destination as the link's own short code and
+/// share its analytics. They resolve on the default short domain only.
+use crate::auth;
+use crate::services::LinkAliasService;
+use crate::utils::AppError;
+use worker::d1::D1Database;
+use worker::*;
+
+#[utoipa::path(
+    get,
+    path = "/api/links/{id}/aliases",
+    tag = "Links",
+    summary = "List link aliases",
+    description = "Returns the alias codes of a link, oldest first",
+    params(
+        ("id" = String, Path, description = "Link ID"),
+    ),
+    responses(
+        (status = 200, description = "Alias list", body = Vec<crate::models::LinkAlias>),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Link not found"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_list_link_aliases(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_list(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_list(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    let link_id = link_id_param(&ctx)?;
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+
+    let aliases = LinkAliasService::new()
+        .list_aliases(&db, &user_ctx.org_id, &link_id)
+        .await?;
+    Ok(Response::from_json(&aliases)?)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/links/{id}/aliases",
+    tag = "Links",
+    summary = "Add a link alias",
+    description = "Adds an extra short code (`{\"alias_code\": \"...\"}`) that redirects to the same link and counts towards its analytics. Aliases follow the custom short code rules: Pro tier or above, the instance must allow custom codes, and the code must be valid, not reserved, and not already in use. A link can have at most 5 aliases",
+    params(
+        ("id" = String, Path, description = "Link ID"),
+    ),
+    responses(
+        (status = 201, description = "Alias created", body = crate::models::LinkAlias),
+        (status = 400, description = "Invalid alias code or alias limit reached"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Custom codes not available on this tier or instance"),
+        (status = 404, description = "Link not found"),
+        (status = 409, description = "Code already in use"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_create_link_alias(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_create(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_create(mut req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    let link_id = link_id_param(&ctx)?;
+
+    let body: serde_json::Value = req
+        .json()
+        .await
+        .map_err(|_| AppError::BadRequest("Invalid JSON body".to_string()))?;
+    let alias_code = body["alias_code"]
+        .as_str()
+        .map(|c| c.trim())
+        .filter(|c| !c.is_empty())
+        .ok_or_else(|| AppError::BadRequest("alias_code is required".to_string()))?;
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let kv = ctx.kv("URL_MAPPINGS")?;
+
+    let alias = LinkAliasService::new()
+        .create_alias(
+            &db,
+            &kv,
+            &user_ctx.org_id,
+            &user_ctx.user_id,
+            &link_id,
+            alias_code,
+        )
+        .await?;
+
+    Ok(Response::from_json(&alias)?.with_status(201))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/links/{id}/aliases/{code}",
+    tag = "Links",
+    summary = "Remove a link alias",
+    description = "Removes an alias code from a link. The code stops redirecting and becomes available again",
+    params(
+        ("id" = String, Path, description = "Link ID"),
+        ("code" = String, Path, description = "Alias code"),
+    ),
+    responses(
+        (status = 200, description = "Alias removed"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Link or alias not found"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_delete_link_alias(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_delete(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_delete(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    let link_id = link_id_param(&ctx)?;
+    let alias_code = ctx
+        .param("code")
+        .ok_or_else(|| AppError::BadRequest("Missing alias code".to_string()))?
+        .to_string();
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let kv = ctx.kv("URL_MAPPINGS")?;
+
+    LinkAliasService::new()
+        .delete_alias(&db, &kv, &user_ctx.org_id, &link_id, &alias_code)
+        .await?;
+
+    Ok(Response::from_json(
+        &serde_json::json!({ "deleted": true }),
+    )?)
+}
+
+fn link_id_param(ctx: &RouteContext<()>) -> Result<String, AppError> {
+    ctx.param("id")
+        .map(|s| s.to_string())
+        .ok_or_else(|| AppError::BadRequest("Missing link ID".to_string()))
+}
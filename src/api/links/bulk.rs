@@ -0,0 +1,488 @@
+/// POST /api/links/bulk
+///
+/// Creates up to MAX_BULK_CREATE_LINKS links in one call. Items are validated
+/// like POST /api/links and fail individually; the monthly quota is reserved
+/// for all valid items up front, so a batch never goes over the limit. Each
+/// item is likewise charged against the link creation rate limits.
+/// With `?mode=atomic` the batch is all-or-nothing: the first invalid item
+/// fails the request and no link is created.
+use super::create::check_link_creation_rate_limits;
+use crate::auth;
+use crate::kv;
+use crate::models::link::{
+    BulkCreateLinkItem, BulkCreateLinkResult, BulkCreateLinksRequest, BulkCreateLinksResponse,
+    Link, LinkStatus, MAX_BULK_CREATE_LINKS,
+};
+use crate::repositories::{CodeWordRepository, OrgRepository};
+use crate::services::settings_service::CodeLengthSettings;
+use crate::services::{LinkService, SettingsService};
+use crate::utils::code_words::DisallowedWords;
+use crate::utils::{
+    AppError, QueryParams, get_client_ip, now_timestamp, validate_and_normalize_tags,
+    validate_custom_short_code, validate_url_with_schemes,
+};
+use std::collections::HashSet;
+use worker::d1::D1Database;
+use worker::kv::KvStore;
+use worker::*;
+
+/// Instance settings every item is validated against, loaded once per batch
+struct ItemRules {
+    allowed_schemes: Vec<String>,
+    disallowed: DisallowedWords,
+    custom_codes_allowed: bool,
+    effective_custom_min: usize,
+}
+
+/// An item that passed validation and is waiting to be created
+struct ValidItem {
+    index: usize,
+    destination_url: String,
+    short_code: Option<String>,
+    title: Option<String>,
+    tags: Vec<String>,
+    expires_at: Option<i64>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/links/bulk",
+    tag = "Links",
+    summary = "Create links in bulk",
+    description = "Creates up to 100 links in one request. Each item is validated like POST /api/links and reported separately in `results` as {index, status, short_code | error}; an invalid item, or a custom short code that is already taken, fails only that item. The monthly link quota is reserved for all valid items at once: if they do not all fit, no link is created and the request fails with 403. Every item in the request counts against the link creation rate limits; if the remaining budget cannot cover all of them, the request fails with 429 and nothing is created. With mode=atomic the batch is all-or-nothing: the first invalid item (reported as `links[index]: error`), a tier check failure, or a short code collision fails the whole request and no link is created",
+    params(
+        ("mode" = Option<String>, Query, description = "partial (default): items fail individually; atomic: all-or-nothing"),
+    ),
+    request_body(content = BulkCreateLinksRequest, description = "Links to create"),
+    responses(
+        (status = 200, description = "Per-item results", body = BulkCreateLinksResponse),
+        (status = 400, description = "Invalid request body, no or too many items, or (atomic) an invalid item"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "The valid items would exceed the monthly link limit, or (atomic) an item needs a higher tier"),
+        (status = 409, description = "(atomic) A short code was taken while the batch was being created"),
+        (status = 429, description = "Rate limit exceeded. Body: {message, code, scope}"),
+    ),
+    security(
+        ("Bearer" = []),
+        ("session_cookie" = [])
+    )
+)]
+pub async fn handle_bulk_create_links(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_bulk_create(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_bulk_create(mut req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    let user_id = &user_ctx.user_id;
+    let org_id = &user_ctx.org_id;
+
+    let atomic = match QueryParams::from_request(&req)?.get("mode").as_deref() {
+        None | Some("partial") => false,
+        Some("atomic") => true,
+        Some(_) => {
+            return Err(AppError::BadRequest(
+                "mode must be 'partial' or 'atomic'".to_string(),
+            ));
+        }
+    };
+
+    let client_ip = get_client_ip(&req);
+    let body: BulkCreateLinksRequest = req
+        .json()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Invalid request format: {}", e)))?;
+    if body.links.is_empty() {
+        return Err(AppError::BadRequest(
+            "At least one link is required".to_string(),
+        ));
+    }
+    if body.links.len() > MAX_BULK_CREATE_LINKS {
+        return Err(AppError::BadRequest(format!(
+            "Maximum {} links per request",
+            MAX_BULK_CREATE_LINKS
+        )));
+    }
+
+    // Every item is charged against the creation rate limits, so bulk
+    // creation cannot be used to get around them
+    let kv = ctx.kv("URL_MAPPINGS")?;
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    if let Some(response) = check_link_creation_rate_limits(
+        &ctx.env,
+        &kv,
+        &db,
+        user_id,
+        org_id,
+        &client_ip,
+        "bulk_create_links",
+        body.links.len() as u32,
+    )
+    .await?
+    {
+        return Ok(response);
+    }
+
+    let settings_service = SettingsService::new();
+    let lengths = settings_service.get_code_length_settings(&db).await?;
+    let rules = ItemRules {
+        allowed_schemes: settings_service
+            .get_allowed_destination_schemes(&db)
+            .await?,
+        disallowed: CodeWordRepository::new().get_disallowed_words(&db).await?,
+        custom_codes_allowed: settings_service.are_custom_short_codes_allowed(&db).await?,
+        effective_custom_min: lengths.effective_custom_min,
+    };
+
+    let link_service = LinkService::new();
+    let mut results: Vec<BulkCreateLinkResult> = Vec::with_capacity(body.links.len());
+    let mut valid: Vec<ValidItem> = Vec::new();
+    // Custom codes claimed by earlier items, so two items can't ask for the same one
+    let mut batch_codes: HashSet<String> = HashSet::new();
+
+    for (index, item) in body.links.into_iter().enumerate() {
+        match validate_item(index, item, &rules, &db, &kv, &link_service, &batch_codes).await {
+            Ok(valid_item) => {
+                if let Some(ref code) = valid_item.short_code {
+                    batch_codes.insert(code.clone());
+                }
+                valid.push(valid_item);
+            }
+            Err(error) => results.push(BulkCreateLinkResult::failed(index, error)),
+        }
+    }
+
+    if atomic {
+        // Failures so far are validation errors, recorded in index order
+        if let Some(failed) = results.first() {
+            return Err(AppError::BadRequest(format!(
+                "links[{}]: {}",
+                failed.index,
+                failed.error.as_deref().unwrap_or("Invalid link")
+            )));
+        }
+        return create_all_or_nothing(
+            &ctx.env,
+            &db,
+            &kv,
+            &link_service,
+            user_id,
+            org_id,
+            valid,
+            &lengths,
+        )
+        .await;
+    }
+
+    if !valid.is_empty() {
+        let quota_ctx = link_service
+            .reserve_quota(&db, org_id, valid.len() as i64)
+            .await?;
+        let limits = quota_ctx.tier_limits();
+        let allow_custom = limits
+            .as_ref()
+            .map(|l| l.allow_custom_short_code)
+            .unwrap_or(false);
+        let max_tags = limits.as_ref().and_then(|l| l.max_tags);
+        let org_repo = OrgRepository::new();
+        let exclude_ambiguous = org_repo.get_exclude_ambiguous_chars(&db, org_id).await?;
+        let redirect_type =
+            quota_ctx.default_redirect_type(org_repo.get_default_redirect_type(&db, org_id).await?);
+
+        let mut not_created: i64 = 0;
+        for item in valid {
+            let index = item.index;
+            if item.short_code.is_some() && !allow_custom {
+                not_created += 1;
+                results.push(BulkCreateLinkResult::failed(
+                    index,
+                    "Custom short codes are not available on the free tier. Upgrade to Pro.",
+                ));
+                continue;
+            }
+
+            // Checked per item, so tags added by earlier items count towards the limit
+            if !item.tags.is_empty()
+                && let Some(max_tags) = max_tags
+                && let Err(e) = link_service
+                    .check_tag_limit(&db, &quota_ctx.billing_account_id, &item.tags, max_tags)
+                    .await
+            {
+                not_created += 1;
+                results.push(BulkCreateLinkResult::failed(index, e.to_string()));
+                continue;
+            }
+
+            let short_code = match item.short_code {
+                Some(ref code) => code.clone(),
+                None => match link_service
+                    .generate_progressive_short_code(
+                        &kv,
+                        &db,
+                        &ctx.env,
+                        lengths.min_random_length,
+                        lengths.system_min_length,
+                        exclude_ambiguous,
+                    )
+                    .await
+                {
+                    Ok(code) => code,
+                    Err(e) => {
+                        not_created += 1;
+                        results.push(BulkCreateLinkResult::failed(index, e.to_string()));
+                        continue;
+                    }
+                },
+            };
+
+            let link = build_link(org_id, user_id, short_code.clone(), &redirect_type, &item);
+
+            // A custom code taken since validation fails on the unique index
+            match link_service
+                .create_link(&db, &kv, &link, &item.tags, org_id)
+                .await
+            {
+                Ok(()) => results.push(BulkCreateLinkResult::created(index, short_code)),
+                Err(e) => {
+                    console_log!(
+                        "{}",
+                        serde_json::json!({
+                            "event": "bulk_create_item_failed",
+                            "org_id": org_id,
+                            "index": index,
+                            "error": e.to_string(),
+                            "level": "warn"
+                        })
+                    );
+                    not_created += 1;
+                    results.push(BulkCreateLinkResult::failed(
+                        index,
+                        "Failed to create link; the short code may already be in use",
+                    ));
+                }
+            }
+        }
+
+        link_service
+            .release_quota(&db, &quota_ctx, not_created)
+            .await?;
+    }
+
+    results.sort_by_key(|r| r.index);
+    let created = results.iter().filter(|r| r.error.is_none()).count();
+    Ok(Response::from_json(&BulkCreateLinksResponse {
+        created,
+        failed: results.len() - created,
+        results,
+    })?)
+}
+
+/// `mode=atomic` after every item passed validation: the quota is reserved and
+/// the tier checks run for the whole batch before anything is written, and
+/// any failure releases the quota again.
+#[allow(clippy::too_many_arguments)]
+async fn create_all_or_nothing(
+    env: &Env,
+    db: &D1Database,
+    kv: &KvStore,
+    link_service: &LinkService,
+    user_id: &str,
+    org_id: &str,
+    items: Vec<ValidItem>,
+    lengths: &CodeLengthSettings,
+) -> Result<Response, AppError> {
+    let count = items.len() as i64;
+    let quota_ctx = link_service.reserve_quota(db, org_id, count).await?;
+
+    let created = async {
+        let limits = quota_ctx.tier_limits();
+        let allow_custom = limits
+            .as_ref()
+            .map(|l| l.allow_custom_short_code)
+            .unwrap_or(false);
+        if let Some(item) = items.iter().find(|i| i.short_code.is_some())
+            && !allow_custom
+        {
+            return Err(AppError::Forbidden(format!(
+                "links[{}]: Custom short codes are not available on the free tier. Upgrade to Pro.",
+                item.index
+            )));
+        }
+
+        // Tags new to the billing account are counted once for the whole batch
+        if let Some(max_tags) = limits.as_ref().and_then(|l| l.max_tags) {
+            let mut batch_tags: Vec<String> = Vec::new();
+            for tag in items.iter().flat_map(|i| i.tags.iter()) {
+                if !batch_tags.contains(tag) {
+                    batch_tags.push(tag.clone());
+                }
+            }
+            if !batch_tags.is_empty() {
+                link_service
+                    .check_tag_limit(db, &quota_ctx.billing_account_id, &batch_tags, max_tags)
+                    .await?;
+            }
+        }
+
+        let org_repo = OrgRepository::new();
+        let exclude_ambiguous = org_repo.get_exclude_ambiguous_chars(db, org_id).await?;
+        let redirect_type =
+            quota_ctx.default_redirect_type(org_repo.get_default_redirect_type(db, org_id).await?);
+        let mut batch_codes: HashSet<String> =
+            items.iter().filter_map(|i| i.short_code.clone()).collect();
+        let mut links = Vec::with_capacity(items.len());
+        for item in &items {
+            let short_code = match item.short_code {
+                Some(ref code) => code.clone(),
+                None => {
+                    let code = link_service
+                        .generate_progressive_short_code(
+                            kv,
+                            db,
+                            env,
+                            lengths.min_random_length,
+                            lengths.system_min_length,
+                            exclude_ambiguous,
+                        )
+                        .await?;
+                    if !batch_codes.insert(code.clone()) {
+                        return Err(AppError::Conflict(format!(
+                            "links[{}]: Generated short code collided; retry the request",
+                            item.index
+                        )));
+                    }
+                    code
+                }
+            };
+            let link = build_link(org_id, user_id, short_code, &redirect_type, item);
+            links.push((link, item.tags.clone()));
+        }
+
+        link_service
+            .create_links_atomic(db, kv, &links, org_id)
+            .await?;
+        Ok(links)
+    }
+    .await;
+
+    let links = match created {
+        Ok(links) => links,
+        Err(e) => {
+            link_service.release_quota(db, &quota_ctx, count).await?;
+            return Err(e);
+        }
+    };
+
+    let results: Vec<BulkCreateLinkResult> = items
+        .iter()
+        .zip(links)
+        .map(|(item, (link, _))| BulkCreateLinkResult::created(item.index, link.short_code))
+        .collect();
+    Ok(Response::from_json(&BulkCreateLinksResponse {
+        created: results.len(),
+        failed: 0,
+        results,
+    })?)
+}
+
+/// The link to insert for a validated item, using the org's default redirect type
+fn build_link(
+    org_id: &str,
+    user_id: &str,
+    short_code: String,
+    redirect_type: &str,
+    item: &ValidItem,
+) -> Link {
+    Link {
+        id: uuid::Uuid::new_v4().to_string(),
+        org_id: org_id.to_string(),
+        short_code,
+        destination_url: item.destination_url.clone(),
+        title: item.title.clone(),
+        created_by: user_id.to_string(),
+        created_at: now_timestamp(),
+        updated_at: None,
+        expires_at: item.expires_at,
+        status: LinkStatus::Active,
+        click_count: 0,
+        tags: item.tags.clone(),
+        utm_params: None,
+        forward_query_params: None,
+        redirect_type: redirect_type.to_string(),
+        ios_url: None,
+        android_url: None,
+        desktop_url: None,
+        custom_domain: None,
+        response_headers: None,
+        raw_destination: None,
+        disabled_reason: None,
+        max_clicks: None,
+        fallback_url: None,
+    }
+}
+
+/// Validate one item the way POST /api/links does, except for the checks
+/// that depend on the tier, which run once the quota is reserved. Returns
+/// the message to report for the item when it is invalid.
+async fn validate_item(
+    index: usize,
+    item: BulkCreateLinkItem,
+    rules: &ItemRules,
+    db: &D1Database,
+    kv: &KvStore,
+    link_service: &LinkService,
+    batch_codes: &HashSet<String>,
+) -> Result<ValidItem, String> {
+    let destination_url = validate_url_with_schemes(&item.destination_url, &rules.allowed_schemes)
+        .map_err(|e| format!("Invalid destination URL: {}", e))?;
+
+    link_service
+        .check_blacklist(db, &destination_url)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Some(ref title) = item.title
+        && title.len() > 200
+    {
+        return Err("Title must be 200 characters or less".to_string());
+    }
+
+    if let Some(ref code) = item.short_code {
+        if !rules.custom_codes_allowed {
+            return Err(
+                "Custom short codes are disabled on this instance. Omit short_code to get a random code."
+                    .to_string(),
+            );
+        }
+        validate_custom_short_code(code, &rules.disallowed)
+            .map_err(|e| format!("Invalid short code: {}", e))?;
+        if code.len() < rules.effective_custom_min {
+            return Err(format!(
+                "Custom short code must be at least {} characters",
+                rules.effective_custom_min
+            ));
+        }
+        if batch_codes.contains(code)
+            || kv::links::short_code_exists(kv, code)
+                .await
+                .map_err(|e| e.to_string())?
+        {
+            return Err("Short code already in use".to_string());
+        }
+    }
+
+    let tags = match item.tags {
+        Some(ref tags) => validate_and_normalize_tags(tags).map_err(|e| e.to_string())?,
+        None => Vec::new(),
+    };
+
+    Ok(ValidItem {
+        index,
+        destination_url,
+        short_code: item.short_code,
+        title: item.title,
+        tags,
+        expires_at: item.expires_at,
+    })
+}
@@ -0,0 +1,62 @@
+/// POST /api/links/bulk-delete
+///
+/// Deletes up to MAX_BULK_DELETE_LINKS of the organization's links in one
+/// call. The D1 rows are deleted in batches and the KV mappings link by
+/// link; a failure is reported for the links it affects and the rest are
+/// still deleted.
+use crate::auth;
+use crate::models::link::{BulkDeleteLinksRequest, BulkDeleteLinksResponse, MAX_BULK_DELETE_LINKS};
+use crate::services::LinkService;
+use crate::utils::AppError;
+use worker::d1::D1Database;
+use worker::*;
+
+#[utoipa::path(
+    post,
+    path = "/api/links/bulk-delete",
+    tag = "Links",
+    summary = "Delete links in bulk",
+    description = "Deletes up to 200 of the organization's links, with their tags, D1 rows and KV mappings, like DELETE /api/links/{id}. IDs that are not links of the organization are reported in not_found. A link that fails to delete is reported in errors as {id, error} and the others are still deleted",
+    request_body(content = BulkDeleteLinksRequest, description = "IDs of the links to delete"),
+    responses(
+        (status = 200, description = "Deletion summary", body = BulkDeleteLinksResponse),
+        (status = 400, description = "Invalid request body, or no or too many IDs"),
+        (status = 401, description = "Unauthorized"),
+    ),
+    security(
+        ("Bearer" = []),
+        ("session_cookie" = [])
+    )
+)]
+pub async fn handle_bulk_delete_links(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner(req, ctx).await.unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner(mut req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+
+    let request: BulkDeleteLinksRequest = req
+        .json()
+        .await
+        .map_err(|_| AppError::BadRequest("Invalid request body".to_string()))?;
+
+    if request.ids.is_empty() {
+        return Err(AppError::BadRequest(
+            "At least one link id is required".to_string(),
+        ));
+    }
+    if request.ids.len() > MAX_BULK_DELETE_LINKS {
+        return Err(AppError::BadRequest(format!(
+            "At most {} links per request",
+            MAX_BULK_DELETE_LINKS
+        )));
+    }
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let kv = ctx.kv("URL_MAPPINGS")?;
+    let result = LinkService::new()
+        .bulk_delete_links(&db, &kv, &user_ctx.org_id, &request.ids)
+        .await?;
+
+    Ok(Response::from_json(&result)?)
+}
@@ -0,0 +1,124 @@
+/// GET /api/links/check-code?code=...
+///
+/// Tells the link editor whether a custom short code is free before the link
+/// is submitted.
+///
+/// Threat: an availability oracle lets anyone enumerate which custom codes
+/// exist (and so discover unlisted links) by guessing codes or by timing the
+/// KV lookup, where a hit and a miss can differ by a few milliseconds. To
+/// deter this the endpoint:
+/// - always applies a tight per-IP budget, even when KV rate limiting is
+///   otherwise turned off in favour of Cloudflare's rules, since it exists
+///   solely to answer "does this code exist";
+/// - pads every response to a randomized minimum duration, so the KV timing
+///   difference is hidden under the floor and the jitter.
+use crate::auth;
+use crate::kv;
+use crate::middleware::{RateLimitConfig, RateLimitSettings, RateLimiter};
+use crate::repositories::CodeWordRepository;
+use crate::services::SettingsService;
+use crate::utils::{AppError, QueryParams, get_client_ip, validate_custom_short_code};
+use rand::RngExt;
+use std::time::Duration;
+use worker::d1::D1Database;
+use worker::*;
+
+/// Floor for every response, well above KV lookup latency
+const CHECK_CODE_MIN_RESPONSE_MS: u64 = 150;
+/// Random extra delay on top of the floor
+const CHECK_CODE_JITTER_MS: u64 = 100;
+
+#[utoipa::path(
+    get,
+    path = "/api/links/check-code",
+    tag = "Links",
+    summary = "Check custom short code availability",
+    description = "Returns `{code, available, reason}` for a candidate custom short code. `reason` explains why an unavailable code cannot be used (invalid format, too short, or already taken). Responses are padded to a randomized minimum duration and limited to 30 requests per minute per IP to deter enumeration",
+    params(
+        ("code" = String, Query, description = "Candidate short code"),
+    ),
+    responses(
+        (status = 200, description = "Availability result"),
+        (status = 400, description = "Missing code parameter"),
+        (status = 401, description = "Unauthorized"),
+        (status = 429, description = "Rate limit exceeded"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_check_code(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_check_code(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_check_code(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    auth::authenticate_request(&req, &ctx).await?;
+
+    let kv = ctx.kv("URL_MAPPINGS")?;
+
+    let client_ip = get_client_ip(&req);
+    let rate_limit_key = RateLimiter::ip_key("check_code", &client_ip);
+    if let Err(err) = RateLimiter::check(
+        &kv,
+        &rate_limit_key,
+        &RateLimitConfig::code_check(),
+        &RateLimitSettings::from_env(&ctx.env).always_on(),
+        &client_ip,
+    )
+    .await
+    {
+        let mut response = Response::error(err.to_error_response(), 429)?;
+        if let Some(retry_after) = err.retry_after() {
+            response
+                .headers_mut()
+                .set("Retry-After", &retry_after.to_string())?;
+        }
+        return Ok(response);
+    }
+
+    let started_ms = Date::now().as_millis();
+
+    let params = QueryParams::from_request(&req)?;
+    let code = params
+        .get("code")
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .ok_or_else(|| AppError::BadRequest("Missing 'code' parameter".to_string()))?;
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let lengths = SettingsService::new().get_code_length_settings(&db).await?;
+    let disallowed = CodeWordRepository::new().get_disallowed_words(&db).await?;
+
+    let reason = if let Err(e) = validate_custom_short_code(&code, &disallowed) {
+        Some(format!("Invalid short code: {}", e))
+    } else if code.len() < lengths.effective_custom_min {
+        Some(format!(
+            "Custom short code must be at least {} characters",
+            lengths.effective_custom_min
+        ))
+    } else if kv::links::short_code_exists(&kv, &code).await? {
+        Some("Short code already in use".to_string())
+    } else {
+        None
+    };
+
+    pad_response_time(started_ms).await;
+
+    let mut response = Response::from_json(&serde_json::json!({
+        "code": code,
+        "available": reason.is_none(),
+        "reason": reason,
+    }))?;
+    response.headers_mut().set("Cache-Control", "no-store")?;
+    Ok(response)
+}
+
+/// Sleep until at least the floor plus a random jitter has passed since
+/// `started_ms`, so hits and misses take indistinguishable time.
+async fn pad_response_time(started_ms: u64) {
+    let target_ms = CHECK_CODE_MIN_RESPONSE_MS + rand::rng().random_range(0..=CHECK_CODE_JITTER_MS);
+    let elapsed_ms = Date::now().as_millis().saturating_sub(started_ms);
+    if elapsed_ms < target_ms {
+        Delay::from(Duration::from_millis(target_ms - elapsed_ms)).await;
+    }
+}
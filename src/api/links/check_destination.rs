@@ -0,0 +1,91 @@
+/// GET /api/links/check-destination?url=...
+///
+/// Tells the link editor whether a destination is blacklisted before the link
+/// is submitted, so the user is warned up front instead of getting a 403 from
+/// POST /api/links. Nothing is created.
+use crate::auth;
+use crate::middleware::{RateLimitConfig, RateLimitSettings, RateLimiter};
+use crate::repositories::BlacklistRepository;
+use crate::services::SettingsService;
+use crate::utils::{AppError, QueryParams, get_client_ip, validate_url_with_schemes};
+use worker::d1::D1Database;
+use worker::*;
+
+#[utoipa::path(
+    get,
+    path = "/api/links/check-destination",
+    tag = "Links",
+    summary = "Check whether a destination is blocked",
+    description = "Returns `{blocked, match_type}` for a candidate destination URL, where `match_type` (exact or domain) is only present when the URL is blocked. The URL is validated as on link creation. Limited to 60 requests per minute per user",
+    params(
+        ("url" = String, Query, description = "Candidate destination URL"),
+    ),
+    responses(
+        (status = 200, description = "Blacklist check result"),
+        (status = 400, description = "Missing or invalid url parameter"),
+        (status = 401, description = "Unauthorized"),
+        (status = 429, description = "Rate limit exceeded"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_check_destination(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_check_destination(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_check_destination(
+    req: Request,
+    ctx: RouteContext<()>,
+) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+
+    let kv = ctx.kv("URL_MAPPINGS")?;
+
+    let client_ip = get_client_ip(&req);
+    let rate_limit_key = RateLimiter::user_key("check_destination", &user_ctx.user_id);
+    // Always enforced: every check makes an outbound request to the destination
+    if let Err(err) = RateLimiter::check(
+        &kv,
+        &rate_limit_key,
+        &RateLimitConfig::destination_check(),
+        &RateLimitSettings::from_env(&ctx.env).always_on(),
+        &client_ip,
+    )
+    .await
+    {
+        let mut response = Response::error(err.to_error_response(), 429)?;
+        if let Some(retry_after) = err.retry_after() {
+            response
+                .headers_mut()
+                .set("Retry-After", &retry_after.to_string())?;
+        }
+        return Ok(response);
+    }
+
+    let params = QueryParams::from_request(&req)?;
+    let url = params
+        .get("url")
+        .map(|u| u.trim().to_string())
+        .filter(|u| !u.is_empty())
+        .ok_or_else(|| AppError::BadRequest("Missing 'url' parameter".to_string()))?;
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let allowed_schemes = SettingsService::new()
+        .get_allowed_destination_schemes(&db)
+        .await?;
+    let destination_url = validate_url_with_schemes(&url, &allowed_schemes)
+        .map_err(|e| AppError::BadRequest(format!("Invalid destination URL: {}", e)))?;
+
+    let match_type = BlacklistRepository::new()
+        .find_match_type(&db, &destination_url)
+        .await?;
+
+    let mut body = serde_json::json!({ "blocked": match_type.is_some() });
+    if let Some(match_type) = match_type {
+        body["match_type"] = match_type.into();
+    }
+    let mut response = Response::from_json(&body)?;
+    response.headers_mut().set("Cache-Control", "no-store")?;
+    Ok(response)
+}
@@ -0,0 +1,240 @@
+/// Anonymous link creation and claiming
+///
+/// POST /api/links/anonymous — create a link without an account (only when the
+///                             `anonymous_link_creation_enabled` setting is on)
+/// POST /api/links/claim     — move an anonymous link into the caller's org
+use crate::auth;
+use crate::middleware::{RateLimitConfig, RateLimitError, RateLimitSettings, RateLimiter};
+use crate::models::link::{ANONYMOUS_ORG_ID, ANONYMOUS_USER_ID, Link, LinkStatus};
+use crate::services::{LinkService, SettingsService};
+use crate::utils::json_fields::{JsonFieldSpec, JsonFieldType, check_json_field_types};
+use crate::utils::short_code::CODE_GENERATION_RETRY_AFTER_SECS;
+use crate::utils::{AppError, get_client_ip, now_timestamp, validate_url_with_schemes};
+use worker::d1::D1Database;
+use worker::*;
+
+/// Fields accepted by POST /api/links/anonymous, with their expected JSON types.
+const ANONYMOUS_LINK_FIELDS: &[JsonFieldSpec] = &[
+    ("destination_url", JsonFieldType::String, true),
+    ("title", JsonFieldType::String, false),
+];
+
+fn rate_limited_response(err: &RateLimitError) -> Result<Response, AppError> {
+    let mut response = Response::error(err.to_error_response(), 429)?;
+    if let Some(retry_after) = err.retry_after() {
+        response
+            .headers_mut()
+            .set("Retry-After", &retry_after.to_string())?;
+    }
+    Ok(response)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/links/anonymous",
+    tag = "Links",
+    summary = "Create a link without an account",
+    description = "Creates a short link with a random code without authentication. Only available when the admin setting anonymous_link_creation_enabled is on. The response includes a claim_token, shown once, that a signed-in user can pass to POST /api/links/claim to take ownership of the link. Rate-limited per IP",
+    request_body(content = serde_json::Value, description = "{destination_url, title (optional)}"),
+    responses(
+        (status = 200, description = "Link created, with its claim_token"),
+        (status = 400, description = "Invalid request body or URL"),
+        (status = 403, description = "Anonymous link creation is disabled, or the destination is blocked"),
+        (status = 429, description = "Rate limit exceeded"),
+        (status = 503, description = "No unique short code could be generated; retry after the Retry-After delay"),
+    ),
+)]
+pub async fn handle_create_anonymous_link(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_create_anonymous_link(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_create_anonymous_link(
+    mut req: Request,
+    ctx: RouteContext<()>,
+) -> Result<Response, AppError> {
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let settings_service = SettingsService::new();
+    if !settings_service.are_anonymous_links_enabled(&db).await? {
+        return Err(AppError::Forbidden(
+            "Anonymous link creation is disabled on this instance".to_string(),
+        ));
+    }
+
+    let kv = ctx.kv("URL_MAPPINGS")?;
+    let client_ip = get_client_ip(&req);
+    if let Err(err) = RateLimiter::check(
+        &kv,
+        &RateLimiter::ip_key("create_anonymous_link", &client_ip),
+        &RateLimitConfig::anonymous_link_creation(),
+        &RateLimitSettings::from_env(&ctx.env).always_on(),
+        &client_ip,
+    )
+    .await
+    {
+        return rate_limited_response(&err);
+    }
+
+    let raw_body: serde_json::Value = req
+        .json()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Invalid JSON: {}", e)))?;
+    let obj = raw_body
+        .as_object()
+        .ok_or_else(|| AppError::BadRequest("Request body must be a JSON object".to_string()))?;
+    if let Some(field_name) = obj
+        .keys()
+        .find(|key| !ANONYMOUS_LINK_FIELDS.iter().any(|(name, _, _)| name == key))
+    {
+        return Err(AppError::BadRequest(format!(
+            "Unknown field '{}'. Expected fields: destination_url, title (optional)",
+            field_name
+        )));
+    }
+    check_json_field_types(obj, ANONYMOUS_LINK_FIELDS).map_err(AppError::BadRequest)?;
+
+    let title = obj
+        .get("title")
+        .and_then(|v| v.as_str())
+        .map(|t| t.to_string());
+    if title.as_ref().is_some_and(|t| t.len() > 200) {
+        return Err(AppError::BadRequest(
+            "Title must be 200 characters or less".to_string(),
+        ));
+    }
+
+    let allowed_schemes = settings_service
+        .get_allowed_destination_schemes(&db)
+        .await?;
+    let destination_url = validate_url_with_schemes(
+        obj.get("destination_url")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default(),
+        &allowed_schemes,
+    )
+    .map_err(|e| AppError::BadRequest(format!("Invalid destination URL: {}", e)))?;
+
+    let link_service = LinkService::new();
+    link_service.check_blacklist(&db, &destination_url).await?;
+
+    let lengths = settings_service.get_code_length_settings(&db).await?;
+    let short_code = match link_service
+        .generate_progressive_short_code(
+            &kv,
+            &db,
+            &ctx.env,
+            lengths.min_random_length,
+            lengths.system_min_length,
+            false,
+        )
+        .await
+    {
+        Ok(code) => code,
+        Err(e @ AppError::ServiceUnavailable(_)) => {
+            let mut response = e.into_response();
+            response
+                .headers_mut()
+                .set("Retry-After", &CODE_GENERATION_RETRY_AFTER_SECS.to_string())?;
+            return Ok(response);
+        }
+        Err(e) => return Err(e),
+    };
+
+    let link = Link {
+        id: uuid::Uuid::new_v4().to_string(),
+        org_id: ANONYMOUS_ORG_ID.to_string(),
+        short_code,
+        destination_url,
+        title,
+        created_by: ANONYMOUS_USER_ID.to_string(),
+        created_at: now_timestamp(),
+        updated_at: None,
+        expires_at: None,
+        status: LinkStatus::Active,
+        click_count: 0,
+        tags: Vec::new(),
+        utm_params: None,
+        forward_query_params: None,
+        redirect_type: "301".to_string(),
+        ios_url: None,
+        android_url: None,
+        desktop_url: None,
+        custom_domain: None,
+        response_headers: None,
+        raw_destination: None,
+        disabled_reason: None,
+        max_clicks: None,
+        fallback_url: None,
+    };
+
+    link_service
+        .create_link(&db, &kv, &link, &[], ANONYMOUS_ORG_ID)
+        .await?;
+    let claim_token = link_service.issue_claim_token(&db, &link.id).await?;
+
+    let mut body = serde_json::to_value(&link)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize link: {}", e)))?;
+    body["claim_token"] = serde_json::Value::String(claim_token);
+    Ok(Response::from_json(&body)?)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/links/claim",
+    tag = "Links",
+    summary = "Claim an anonymously created link",
+    description = "Moves the anonymous link identified by `token` (the claim_token returned by POST /api/links/anonymous) into the caller's current organization. Counts against the monthly link limit. Each token can be used once. Rate-limited per user",
+    request_body(content = serde_json::Value, description = "{token}"),
+    responses(
+        (status = 200, description = "Claimed link", body = Link),
+        (status = 400, description = "Missing token"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Monthly link limit reached for current tier"),
+        (status = 404, description = "Invalid claim token"),
+        (status = 409, description = "Link already claimed"),
+        (status = 429, description = "Rate limit exceeded"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_claim_link(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_claim_link(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_claim_link(mut req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+
+    let body: serde_json::Value = req
+        .json()
+        .await
+        .map_err(|_| AppError::BadRequest("Invalid JSON body".to_string()))?;
+    let token = body
+        .get("token")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| AppError::BadRequest("token is required".to_string()))?;
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let kv = ctx.kv("URL_MAPPINGS")?;
+    let client_ip = get_client_ip(&req);
+    if let Err(err) = RateLimiter::check(
+        &kv,
+        &RateLimiter::user_key("claim_link", &user_ctx.user_id),
+        &RateLimitConfig::link_claim(),
+        &RateLimitSettings::from_env(&ctx.env).always_on(),
+        &client_ip,
+    )
+    .await
+    {
+        return rate_limited_response(&err);
+    }
+
+    let link = LinkService::new()
+        .claim_link(&db, &kv, token, &user_ctx.org_id, &user_ctx.user_id)
+        .await?;
+
+    Ok(Response::from_json(&link)?)
+}
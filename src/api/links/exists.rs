@@ -0,0 +1,78 @@
+/// GET /api/links/exists/:code
+///
+/// Public, KV-only probe telling monitoring tools whether a short code exists
+/// and is currently live. Never reads D1 and never records a click.
+use crate::api::links::redirect::get_custom_host;
+use crate::kv;
+use crate::middleware::{RateLimitConfig, RateLimitSettings, RateLimiter};
+use crate::models::link::LinkStatus;
+use crate::utils::{get_client_ip, now_timestamp};
+use worker::*;
+
+#[utoipa::path(
+    get,
+    path = "/api/links/exists/{code}",
+    tag = "Links",
+    summary = "Check whether a short code is live",
+    description = "Public probe for monitoring tools. Returns whether the short code resolves and whether it is active (not expired). Disabled and deleted links are removed from the edge mapping and report exists=false. Answered from the edge mapping only: no database read and no click is recorded. On a custom domain the code is looked up for that domain. Rate-limited per IP",
+    params(
+        ("code" = String, Path, description = "Short code"),
+    ),
+    responses(
+        (status = 200, description = "Probe result: {exists, active}"),
+        (status = 429, description = "Rate limit exceeded"),
+    ),
+)]
+pub async fn handle_link_exists(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let code = ctx
+        .param("code")
+        .ok_or_else(|| Error::RustError("Missing short code".to_string()))?
+        .to_string();
+
+    let kv = ctx.kv("URL_MAPPINGS")?;
+
+    let client_ip = get_client_ip(&req);
+    let rate_limit_key = RateLimiter::ip_key("link_exists", &client_ip);
+    if let Err(err) = RateLimiter::check(
+        &kv,
+        &rate_limit_key,
+        &RateLimitConfig::link_probe(),
+        &RateLimitSettings::from_env(&ctx.env).always_on(),
+        &client_ip,
+    )
+    .await
+    {
+        let mut response = Response::error(err.to_error_response(), 429)?;
+        if let Some(retry_after) = err.retry_after() {
+            response
+                .headers_mut()
+                .set("Retry-After", &retry_after.to_string())?;
+        }
+        return Ok(response);
+    }
+
+    let mapping = match get_custom_host(&req, &ctx.env) {
+        Some(hostname) => kv::links::get_link_mapping_for_domain(&kv, &hostname, &code).await?,
+        None => kv::get_link_mapping(&kv, &code).await?,
+    };
+
+    let (exists, active) = match mapping {
+        Some(mapping) => {
+            let expired = mapping
+                .expires_at
+                .is_some_and(|expires_at| now_timestamp() > expires_at);
+            (
+                true,
+                matches!(mapping.status, LinkStatus::Active) && !expired,
+            )
+        }
+        None => (false, false),
+    };
+
+    let mut response = Response::from_json(&serde_json::json!({
+        "exists": exists,
+        "active": active,
+    }))?;
+    response.headers_mut().set("Cache-Control", "no-store")?;
+    Ok(response)
+}
@@ -1,7 +1,13 @@
+use crate::repositories::{AnalyticsRepository, RewriteRuleRepository};
 use crate::services::LinkService;
+use crate::utils::{AppError, now_timestamp};
 use worker::d1::D1Database;
 use worker::*;
 
+/// Version of the single-link snapshot format. Bump when fields are renamed
+/// or removed so consumers can tell snapshots apart.
+pub const LINK_EXPORT_SCHEMA_VERSION: u32 = 1;
+
 /// Escape a single CSV field: wraps in double-quotes if the value contains
 /// a comma, double-quote, or newline; doubles any embedded double-quotes.
 pub fn csv_escape(value: &str) -> String {
@@ -127,3 +133,75 @@ async fn inner_export_links(
     )?;
     Ok(response)
 }
+
+#[utoipa::path(
+    get,
+    path = "/api/links/{id}/export",
+    tag = "Links",
+    summary = "Export a single link snapshot",
+    description = "Returns a versioned JSON snapshot of one link for support and migration: every link field including tags, the org rewrite rules that currently apply to its destination, and lifetime click stats. Links have no status history, so the snapshot carries the current status only",
+    params(
+        ("id" = String, Path, description = "Link ID"),
+    ),
+    responses(
+        (status = 200, description = "Link snapshot"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Link not found"),
+    ),
+    security(
+        ("Bearer" = []),
+        ("session_cookie" = [])
+    )
+)]
+pub async fn handle_export_link(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_export_link(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_export_link(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = crate::auth::authenticate_request(&req, &ctx).await?;
+    let org_id = &user_ctx.org_id;
+
+    let link_id = ctx
+        .param("id")
+        .ok_or_else(|| AppError::BadRequest("Missing link ID".to_string()))?;
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let link = LinkService::new()
+        .get_link(&db, link_id, org_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Link not found".to_string()))?;
+
+    let rewrite_rules: Vec<_> = RewriteRuleRepository::new()
+        .list_by_org(&db, org_id)
+        .await?
+        .into_iter()
+        .filter(|rule| rule.apply(&link.destination_url).is_some())
+        .collect();
+
+    let (recorded_clicks, first_click_at, last_click_at) = AnalyticsRepository::new()
+        .get_link_click_summary(&db, &link.id, org_id)
+        .await?;
+
+    let mut response = Response::from_json(&serde_json::json!({
+        "schema_version": LINK_EXPORT_SCHEMA_VERSION,
+        "exported_at": now_timestamp(),
+        "link": link,
+        "rewrite_rules": rewrite_rules,
+        "stats": {
+            "click_count": link.click_count,
+            "recorded_clicks": recorded_clicks,
+            "first_click_at": first_click_at,
+            "last_click_at": last_click_at,
+        },
+    }))?;
+    response.headers_mut().set(
+        "Content-Disposition",
+        &format!(
+            "attachment; filename=\"rushomon-link-{}.json\"",
+            link.short_code
+        ),
+    )?;
+    Ok(response)
+}
@@ -1,3 +1,4 @@
+use crate::models::link::{GetLinksByCodesRequest, MAX_LINKS_BY_CODES};
 use crate::services::LinkService;
 use worker::d1::D1Database;
 use worker::*;
@@ -97,3 +98,68 @@ async fn inner_get_link_by_code(
         )),
     }
 }
+
+#[utoipa::path(
+    post,
+    path = "/api/links/by-codes",
+    tag = "Links",
+    summary = "Get links by short codes",
+    description = "Resolves up to 100 short codes of the authenticated organization in one call. Returns {links, not_found}: the matching links, and the requested codes that are not links of the organization",
+    request_body = GetLinksByCodesRequest,
+    responses(
+        (status = 200, description = "Matching links and unmatched codes"),
+        (status = 400, description = "No codes, or more than 100"),
+        (status = 401, description = "Unauthorized"),
+    ),
+    security(
+        ("Bearer" = []),
+        ("session_cookie" = [])
+    )
+)]
+pub async fn handle_get_links_by_codes(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_get_links_by_codes(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_get_links_by_codes(
+    mut req: Request,
+    ctx: RouteContext<()>,
+) -> Result<Response, crate::utils::AppError> {
+    let user_ctx = crate::auth::authenticate_request(&req, &ctx).await?;
+    let org_id = &user_ctx.org_id;
+
+    let request: GetLinksByCodesRequest = req
+        .json()
+        .await
+        .map_err(|_| crate::utils::AppError::BadRequest("Invalid request body".to_string()))?;
+
+    // Resolve each code once, keeping the caller's order for not_found
+    let mut codes: Vec<String> = Vec::with_capacity(request.codes.len());
+    for code in request.codes {
+        if !codes.contains(&code) {
+            codes.push(code);
+        }
+    }
+    if codes.is_empty() {
+        return Err(crate::utils::AppError::BadRequest(
+            "At least one short code is required".to_string(),
+        ));
+    }
+    if codes.len() > MAX_LINKS_BY_CODES {
+        return Err(crate::utils::AppError::BadRequest(format!(
+            "At most {} short codes per request",
+            MAX_LINKS_BY_CODES
+        )));
+    }
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let (links, not_found) = LinkService::new()
+        .get_links_by_codes(&db, &codes, org_id)
+        .await?;
+
+    Ok(Response::from_json(&serde_json::json!({
+        "links": links,
+        "not_found": not_found,
+    }))?)
+}
@@ -1,10 +1,10 @@
 use crate::auth;
 use crate::kv;
 use crate::models::link::{Link, LinkStatus};
-use crate::repositories::OrgRepository;
+use crate::repositories::{CodeWordRepository, OrgRepository};
 use crate::services::{LinkService, SettingsService};
 use crate::utils::validate_and_normalize_tags;
-use crate::utils::{now_timestamp, validate_short_code, validate_url};
+use crate::utils::{now_timestamp, validate_custom_short_code, validate_url_with_schemes};
 use worker::d1::D1Database;
 use worker::*;
 
@@ -15,6 +15,9 @@ struct ImportLinkRow {
     title: Option<String>,
     tags: Option<Vec<String>>,
     expires_at: Option<i64>,
+    /// Original creation time when migrating from another service; must not
+    /// be in the future. Defaults to the import time.
+    created_at: Option<i64>,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -50,7 +53,7 @@ struct ImportResponse {
     path = "/api/links/import",
     tag = "Links",
     summary = "Import links from CSV",
-    description = "Bulk-imports links from a CSV payload. Accepts a JSON array of rows parsed from CSV. Each row must have at least a destination_url. Returns counts of created, skipped (duplicate short codes), and failed rows",
+    description = "Bulk-imports links from a CSV payload. Accepts a JSON array of rows parsed from CSV. Each row must have at least a destination_url. An optional created_at (Unix seconds, not in the future) preserves the link's original creation date. Returns counts of created, skipped (duplicate short codes), and failed rows",
     responses(
         (status = 200, description = "Import result with created/skipped/failed counts"),
         (status = 400, description = "Invalid request body"),
@@ -96,7 +99,13 @@ pub async fn handle_import_links(mut req: Request, ctx: RouteContext<()>) -> Res
     let now = now_timestamp();
 
     // Fetch all code length settings in a single query for performance
-    let lengths = SettingsService::new().get_code_length_settings(&db).await?;
+    let settings_service = SettingsService::new();
+    let lengths = settings_service.get_code_length_settings(&db).await?;
+    let disallowed = CodeWordRepository::new().get_disallowed_words(&db).await?;
+    let allowed_schemes = settings_service
+        .get_allowed_destination_schemes(&db)
+        .await?;
+    let custom_codes_allowed = settings_service.are_custom_short_codes_allowed(&db).await?;
     // Org-level default: whether generated codes exclude ambiguous characters
     let exclude_ambiguous = OrgRepository::new()
         .get_exclude_ambiguous_chars(&db, org_id)
@@ -114,18 +123,19 @@ pub async fn handle_import_links(mut req: Request, ctx: RouteContext<()>) -> Res
     for (idx, row) in body.links.iter().enumerate() {
         let row_num = idx + 1;
 
-        let destination_url = match validate_url(&row.destination_url) {
-            Ok(url) => url,
-            Err(e) => {
-                failed += 1;
-                errors.push(ImportError {
-                    row: row_num,
-                    destination_url: row.destination_url.clone(),
-                    reason: format!("Invalid URL: {}", e),
-                });
-                continue;
-            }
-        };
+        let destination_url =
+            match validate_url_with_schemes(&row.destination_url, &allowed_schemes) {
+                Ok(url) => url,
+                Err(e) => {
+                    failed += 1;
+                    errors.push(ImportError {
+                        row: row_num,
+                        destination_url: row.destination_url.clone(),
+                        reason: format!("Invalid URL: {}", e),
+                    });
+                    continue;
+                }
+            };
 
         if let Err(e) = link_service.check_blacklist(&db, &destination_url).await {
             failed += 1;
@@ -137,6 +147,20 @@ pub async fn handle_import_links(mut req: Request, ctx: RouteContext<()>) -> Res
             continue;
         }
 
+        let created_at = match row.created_at {
+            None => now,
+            Some(ts) if ts > 0 && ts <= now => ts,
+            Some(_) => {
+                failed += 1;
+                errors.push(ImportError {
+                    row: row_num,
+                    destination_url: destination_url.clone(),
+                    reason: "created_at must be a Unix timestamp in the past".to_string(),
+                });
+                continue;
+            }
+        };
+
         let quota_ctx = match link_service.check_quota(&db, org_id).await {
             Ok(q) => q,
             Err(e) => {
@@ -152,9 +176,21 @@ pub async fn handle_import_links(mut req: Request, ctx: RouteContext<()>) -> Res
         let limits = quota_ctx.tier_limits();
         let is_pro_or_above = quota_ctx.is_pro_or_above();
 
+        // The instance-wide setting applies before the tier check, as on create
+        if row.short_code.is_some() && !custom_codes_allowed {
+            skipped += 1;
+            errors.push(ImportError {
+                row: row_num,
+                destination_url: destination_url.clone(),
+                reason: "Custom short codes are disabled on this instance. Omit short_code to get a random code."
+                    .to_string(),
+            });
+            continue;
+        }
+
         let short_code: String;
         if is_pro_or_above && let Some(provided_code) = row.short_code.as_ref() {
-            if let Err(e) = validate_short_code(provided_code) {
+            if let Err(e) = validate_custom_short_code(provided_code, &disallowed) {
                 skipped += 1;
                 errors.push(ImportError {
                     row: row_num,
@@ -300,7 +336,7 @@ pub async fn handle_import_links(mut req: Request, ctx: RouteContext<()>) -> Res
             destination_url: destination_url.clone(),
             title,
             created_by: user_id.to_string(),
-            created_at: now,
+            created_at,
             updated_at: None,
             expires_at: row.expires_at,
             status: LinkStatus::Active,
@@ -313,6 +349,11 @@ pub async fn handle_import_links(mut req: Request, ctx: RouteContext<()>) -> Res
             android_url: None,
             desktop_url: None,
             custom_domain: None,
+            response_headers: None,
+            raw_destination: None,
+            disabled_reason: None,
+            max_clicks: None,
+            fallback_url: None,
         };
 
         links_to_import.push(link);
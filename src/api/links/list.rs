@@ -1,5 +1,8 @@
+use crate::models::link::{DEFAULT_LINK_SORT, LINK_SORT_OPTIONS};
 use crate::models::{PaginatedResponse, PaginationMeta};
+use crate::repositories::OrgRepository;
 use crate::services::LinkService;
+use crate::utils::{AppError, QueryParams};
 use worker::d1::D1Database;
 use worker::*;
 
@@ -14,11 +17,15 @@ use worker::*;
         ("limit" = Option<i64>, Query, description = "Items per page (default: 50, max: 100)"),
         ("tag" = Option<String>, Query, description = "Filter by tag"),
         ("search" = Option<String>, Query, description = "Search by title or URL"),
-        ("sort" = Option<String>, Query, description = "Sort field: created_at, click_count"),
+        ("sort" = Option<String>, Query, description = "Sort field: created, updated, clicks, title, code (default: the org's default_link_sort setting)"),
         ("order" = Option<String>, Query, description = "Sort order: asc, desc"),
+        ("created_after" = Option<i64>, Query, description = "Only links created at or after this Unix timestamp (seconds)"),
+        ("created_before" = Option<i64>, Query, description = "Only links created before this Unix timestamp (seconds)"),
+        ("count_only" = Option<bool>, Query, description = "Return only `{\"total\": n}` for the filters, skipping rows, tags and stats"),
     ),
     responses(
-        (status = 200, description = "Paginated list of links"),
+        (status = 200, description = "Paginated list of links, or the total when count_only=true"),
+        (status = 400, description = "Invalid created_after/created_before"),
         (status = 401, description = "Unauthorized"),
     ),
     security(
@@ -32,16 +39,13 @@ pub async fn handle_list_links(req: Request, ctx: RouteContext<()>) -> Result<Re
         .unwrap_or_else(|e| e.into_response()))
 }
 
-async fn inner_list_links(
-    req: Request,
-    ctx: RouteContext<()>,
-) -> Result<Response, crate::utils::AppError> {
+async fn inner_list_links(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
     let user_ctx = crate::auth::authenticate_request(&req, &ctx).await?;
     let org_id = &user_ctx.org_id;
 
     let url = req
         .url()
-        .map_err(|e| crate::utils::AppError::Internal(format!("Invalid URL: {}", e)))?;
+        .map_err(|e| AppError::Internal(format!("Invalid URL: {}", e)))?;
     let query = url.query().unwrap_or("");
 
     let page: i64 = query
@@ -76,15 +80,17 @@ async fn inner_list_links(
             _ => None,
         });
 
-    let sort = query
+    let sort_param = query
         .split('&')
         .find(|s| s.starts_with("sort="))
         .and_then(|s| s.split('=').nth(1))
-        .map(|s| match s {
-            "clicks" | "updated" | "title" | "code" => s,
-            _ => "created",
-        })
-        .unwrap_or("created");
+        .map(|s| {
+            if LINK_SORT_OPTIONS.contains(&s) {
+                s
+            } else {
+                DEFAULT_LINK_SORT
+            }
+        });
 
     let tags_filter: Vec<String> = query
         .split('&')
@@ -104,6 +110,16 @@ async fn inner_list_links(
         })
         .unwrap_or_default();
 
+    let created_after = parse_timestamp_param(query, "created_after")?;
+    let created_before = parse_timestamp_param(query, "created_before")?;
+    if let (Some(after), Some(before)) = (created_after, created_before)
+        && after >= before
+    {
+        return Err(AppError::BadRequest(
+            "created_after must be earlier than created_before".to_string(),
+        ));
+    }
+
     let tags_filter_opt: Option<&[String]> = if tags_filter.is_empty() {
         None
     } else {
@@ -115,6 +131,36 @@ async fn inner_list_links(
     let db = ctx.env.get_binding::<D1Database>("rushomon")?;
     let service = LinkService::new();
 
+    if QueryParams::from_request(&req)?.flag("count_only") {
+        let total = service
+            .count_links(
+                &db,
+                org_id,
+                search.as_deref(),
+                status_filter,
+                tags_filter_opt,
+                created_after,
+                created_before,
+            )
+            .await?;
+        return Ok(Response::from_json(&serde_json::json!({ "total": total }))?);
+    }
+
+    let org_default_sort;
+    let sort = match sort_param {
+        Some(s) => s,
+        None => {
+            org_default_sort = OrgRepository::new()
+                .get_default_link_sort(&db, org_id)
+                .await?;
+            if LINK_SORT_OPTIONS.contains(&org_default_sort.as_str()) {
+                org_default_sort.as_str()
+            } else {
+                DEFAULT_LINK_SORT
+            }
+        }
+    };
+
     let (links, total, stats_json) = service
         .list_links(
             &db,
@@ -125,6 +171,8 @@ async fn inner_list_links(
             limit,
             offset,
             tags_filter_opt,
+            created_after,
+            created_before,
         )
         .await?;
 
@@ -133,3 +181,25 @@ async fn inner_list_links(
 
     Ok(Response::from_json(&response)?)
 }
+
+/// Latest accepted timestamp for date filters (9999-12-31T23:59:59Z)
+const MAX_FILTER_TIMESTAMP: i64 = 253_402_300_799;
+
+/// Parse an optional Unix timestamp (seconds) query parameter.
+fn parse_timestamp_param(query: &str, name: &str) -> Result<Option<i64>, AppError> {
+    let prefix = format!("{}=", name);
+    let Some(raw) = query
+        .split('&')
+        .find_map(|s| s.strip_prefix(prefix.as_str()))
+        .filter(|s| !s.is_empty())
+    else {
+        return Ok(None);
+    };
+    match raw.parse::<i64>() {
+        Ok(ts) if (0..=MAX_FILTER_TIMESTAMP).contains(&ts) => Ok(Some(ts)),
+        _ => Err(AppError::BadRequest(format!(
+            "{} must be a Unix timestamp in seconds",
+            name
+        ))),
+    }
+}
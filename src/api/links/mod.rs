@@ -1,22 +1,42 @@
 pub mod admin;
+pub mod aliases;
+pub mod bulk;
+pub mod bulk_delete;
+pub mod check_code;
+pub mod check_destination;
+pub mod claim;
 pub mod create;
 pub mod delete;
+pub mod exists;
 pub mod export;
 pub mod get;
 pub mod import;
 pub mod list;
+pub mod public;
+pub mod qr;
 pub mod redirect;
+pub mod reset_clicks;
 pub mod update;
 
 pub use admin::{
-    handle_admin_delete_link, handle_admin_list_links, handle_admin_sync_link_kv,
-    handle_admin_update_link_status,
+    handle_admin_delete_link, handle_admin_get_deleted_link_analytics, handle_admin_list_links,
+    handle_admin_sync_link_kv, handle_admin_update_link_status,
 };
+pub use aliases::{handle_create_link_alias, handle_delete_link_alias, handle_list_link_aliases};
+pub use bulk::handle_bulk_create_links;
+pub use bulk_delete::handle_bulk_delete_links;
+pub use check_code::handle_check_code;
+pub use check_destination::handle_check_destination;
+pub use claim::{handle_claim_link, handle_create_anonymous_link};
 pub use create::handle_create_link;
 pub use delete::handle_delete_link;
-pub use export::handle_export_links;
-pub use get::{handle_get_link, handle_get_link_by_code};
+pub use exists::handle_link_exists;
+pub use export::{handle_export_link, handle_export_links};
+pub use get::{handle_get_link, handle_get_link_by_code, handle_get_links_by_codes};
 pub use import::handle_import_links;
 pub use list::handle_list_links;
+pub use public::handle_get_public_link;
+pub use qr::handle_get_link_qr;
 pub use redirect::{handle_redirect, sync_link_mapping_from_link};
+pub use reset_clicks::handle_reset_link_clicks;
 pub use update::handle_update_link;
@@ -0,0 +1,87 @@
+/// GET /api/public/links/:code
+///
+/// Unauthenticated, minimal metadata for share-preview cards on social and
+/// chat apps. Unlike /api/links/by-code it exposes only what the preview
+/// needs: the title, the destination host (never the full URL, which may carry
+/// tokens), and the creation time.
+use crate::middleware::{RateLimitConfig, RateLimitSettings, RateLimiter};
+use crate::repositories::LinkRepository;
+use crate::utils::{AppError, get_client_ip};
+use worker::d1::D1Database;
+use worker::*;
+
+/// How long previews may be cached by browsers and the CDN
+const PUBLIC_LINK_CACHE_SECS: u32 = 300;
+
+#[utoipa::path(
+    get,
+    path = "/api/public/links/{code}",
+    tag = "Links",
+    summary = "Get public link metadata",
+    description = "Unauthenticated metadata for link preview cards: {short_code, title, destination_host, created_at}. Only active, unexpired links are returned; anything else is a 404. Cacheable for 5 minutes. Rate-limited per IP",
+    params(
+        ("code" = String, Path, description = "Short code"),
+    ),
+    responses(
+        (status = 200, description = "Link metadata"),
+        (status = 404, description = "No active link with this code"),
+        (status = 429, description = "Rate limit exceeded"),
+    ),
+)]
+pub async fn handle_get_public_link(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_get_public_link(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_get_public_link(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let code = ctx
+        .param("code")
+        .ok_or_else(|| AppError::BadRequest("Missing short code".to_string()))?
+        .to_string();
+
+    let kv = ctx.kv("URL_MAPPINGS")?;
+
+    let client_ip = get_client_ip(&req);
+    let rate_limit_key = RateLimiter::ip_key("public_link", &client_ip);
+    if let Err(err) = RateLimiter::check(
+        &kv,
+        &rate_limit_key,
+        &RateLimitConfig::public_link_metadata(),
+        &RateLimitSettings::from_env(&ctx.env).always_on(),
+        &client_ip,
+    )
+    .await
+    {
+        let mut response = Response::error(err.to_error_response(), 429)?;
+        if let Some(retry_after) = err.retry_after() {
+            response
+                .headers_mut()
+                .set("Retry-After", &retry_after.to_string())?;
+        }
+        return Ok(response);
+    }
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let link = LinkRepository::new()
+        .get_active_by_short_code(&db, &code)
+        .await?
+        .filter(|link| !link.is_expired())
+        .ok_or_else(|| AppError::NotFound("Link not found".to_string()))?;
+
+    let destination_host = Url::parse(&link.destination_url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string));
+
+    let mut response = Response::from_json(&serde_json::json!({
+        "short_code": link.short_code,
+        "title": link.title,
+        "destination_host": destination_host,
+        "created_at": link.created_at,
+    }))?;
+    response.headers_mut().set(
+        "Cache-Control",
+        &format!("public, max-age={}", PUBLIC_LINK_CACHE_SECS),
+    )?;
+    Ok(response)
+}
@@ -0,0 +1,90 @@
+/// GET /api/links/:id/qr
+///
+/// QR code for a link's short URL, so printed material doesn't need a
+/// third-party generator. The encoded URL uses the redirect domain (the
+/// link's custom domain, or `DOMAIN`), never the frontend's.
+use crate::repositories::LinkRepository;
+use crate::utils::env::{get_domain, get_scheme};
+use crate::utils::qr::{QrFormat, clamp_qr_size, encode_qr, render_png, render_svg};
+use crate::utils::{AppError, QueryParams};
+use worker::d1::D1Database;
+use worker::*;
+
+#[utoipa::path(
+    get,
+    path = "/api/links/{id}/qr",
+    tag = "Links",
+    summary = "Get a link's QR code",
+    description = "Returns a QR code encoding the link's full short URL, on its custom domain if it has one and on the redirect domain otherwise. PNG by default, or SVG with format=svg",
+    params(
+        ("id" = String, Path, description = "Link ID"),
+        ("format" = Option<String>, Query, description = "png (default) or svg"),
+        ("size" = Option<u32>, Query, description = "Image width and height in pixels, clamped to 128-1024 (default 256)"),
+    ),
+    responses(
+        (status = 200, description = "QR code image (image/png or image/svg+xml)"),
+        (status = 400, description = "Invalid format or size"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Link not found"),
+    ),
+    security(
+        ("Bearer" = []),
+        ("session_cookie" = [])
+    )
+)]
+pub async fn handle_get_link_qr(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_get_link_qr(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_get_link_qr(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = crate::auth::authenticate_request(&req, &ctx).await?;
+
+    let link_id = ctx
+        .param("id")
+        .ok_or_else(|| AppError::BadRequest("Missing link ID".to_string()))?;
+
+    let params = QueryParams::from_request(&req)?;
+    let format = match params.get("format") {
+        Some(value) => QrFormat::from_param(&value).ok_or_else(|| {
+            AppError::BadRequest("Invalid format. Must be 'png' or 'svg'".to_string())
+        })?,
+        None => QrFormat::Png,
+    };
+    let size =
+        match params.get("size") {
+            Some(value) => Some(value.parse::<u32>().map_err(|_| {
+                AppError::BadRequest("'size' must be a number of pixels".to_string())
+            })?),
+            None => None,
+        };
+    let size = clamp_qr_size(size);
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let link = LinkRepository::new()
+        .get_by_id(&db, link_id, &user_ctx.org_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Link not found".to_string()))?;
+
+    let short_url = match &link.custom_domain {
+        Some(domain) => format!("https://{}/{}", domain, link.short_code),
+        None => format!(
+            "{}://{}/{}",
+            get_scheme(&ctx.env),
+            get_domain(&ctx.env),
+            link.short_code
+        ),
+    };
+    let qr = encode_qr(&short_url)
+        .ok_or_else(|| AppError::Internal("Short URL too long for a QR code".to_string()))?;
+
+    let mut response = match format {
+        QrFormat::Png => Response::from_bytes(render_png(&qr, size))?,
+        QrFormat::Svg => Response::ok(render_svg(&qr, size))?,
+    };
+    response
+        .headers_mut()
+        .set("Content-Type", format.content_type())?;
+    Ok(response)
+}
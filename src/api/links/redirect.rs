@@ -1,8 +1,21 @@
 use crate::kv;
-use crate::middleware::{RateLimitConfig, RateLimiter, is_kv_rate_limiting_enabled};
+use crate::middleware::{RateLimitConfig, RateLimitSettings, RateLimiter};
+use crate::models::blocked_link::BLOCKED_LINK_PAGE_HTML;
+use crate::models::click_dedup::{click_dedup_key, click_dedup_ttl, is_duplicate_click};
+use crate::models::link::DISABLED_REASON_SUSPENSION;
+use crate::models::maintenance::{MAINTENANCE_PAGE_HTML, MAINTENANCE_RETRY_AFTER_SECS};
+use crate::models::org_redirect_config::{
+    OrgRedirectConfig, render_crawler_preview_page, render_interstitial_page,
+};
+use crate::models::rewrite_rule::rewrite_destination;
 use crate::models::{AnalyticsEvent, link::LinkStatus};
-use crate::repositories::{CustomDomainRepository, LinkRepository};
-use crate::utils::device::{DeviceType, detect_device};
+use crate::repositories::{
+    BlacklistRepository, CustomDomainRepository, LinkRepository, OrgRepository,
+};
+use crate::services::{OrgService, SettingsService};
+use crate::utils::device::{DeviceType, detect_device, is_crawler};
+use crate::utils::url_template::{TemplateValues, render_destination_template};
+use crate::utils::utm_token::{UTM_TOKEN_PARAM, verify_utm_token};
 use crate::utils::{get_client_ip, get_frontend_url, hash_ip, now_timestamp};
 use chrono::TimeZone;
 use std::future::Future;
@@ -10,10 +23,74 @@ use std::pin::Pin;
 use worker::d1::D1Database;
 use worker::*;
 
+/// 503 maintenance page returned instead of a redirect while maintenance mode is on.
+fn maintenance_response() -> Result<Response> {
+    let mut response = Response::from_html(MAINTENANCE_PAGE_HTML)?.with_status(503);
+    let headers = response.headers_mut();
+    headers.set("Retry-After", &MAINTENANCE_RETRY_AFTER_SECS.to_string())?;
+    headers.set("Cache-Control", "no-store")?;
+    Ok(response)
+}
+
+/// "Removed for policy reasons" page served for blocked links when the
+/// `blocked_link_response` setting asks for a dedicated status.
+fn blocked_link_response(status: u16) -> Result<Response> {
+    let mut response = Response::from_html(BLOCKED_LINK_PAGE_HTML)?.with_status(status);
+    response.headers_mut().set("Cache-Control", "no-store")?;
+    Ok(response)
+}
+
+/// Branded interstitial page served (200) instead of an immediate redirect.
+/// Not cacheable, so every visit is counted like a redirect. When only the
+/// untrusted-destination policy forced it, the page warns about the external site.
+fn interstitial_response(
+    destination_url: &Url,
+    config: &OrgRedirectConfig,
+    delay_seconds: u32,
+) -> Result<Response> {
+    let html = render_interstitial_page(
+        destination_url.as_str(),
+        &config.org_name,
+        delay_seconds,
+        !config.interstitial_enabled(),
+    );
+    let mut response = Response::from_html(html)?;
+    response.headers_mut().set("Cache-Control", "no-store")?;
+    Ok(response)
+}
+
+/// OpenGraph page served (200) to link-preview crawlers instead of the redirect,
+/// for orgs with `crawler_preview` on. Not cacheable, since browsers hitting
+/// the same URL must still get the redirect.
+fn crawler_preview_response(destination_url: &Url, title: Option<&str>) -> Result<Response> {
+    let mut response =
+        Response::from_html(render_crawler_preview_page(destination_url.as_str(), title))?;
+    let headers = response.headers_mut();
+    headers.set("Cache-Control", "no-store")?;
+    headers.set("Vary", "User-Agent")?;
+    Ok(response)
+}
+
+/// Build a redirect response carrying per-link custom headers.
+/// Header names were validated against the allowlist when the link was saved;
+/// a header that still fails to apply is skipped rather than failing the redirect.
+fn build_redirect_with_headers(
+    destination_url: &Url,
+    status: u16,
+    custom_headers: &std::collections::BTreeMap<String, String>,
+) -> Result<Response> {
+    let headers = Headers::new();
+    headers.set("Location", destination_url.as_str())?;
+    for (name, value) in custom_headers {
+        let _ = headers.set(name, value);
+    }
+    Ok(Response::empty()?.with_status(status).with_headers(headers))
+}
+
 /// Determine if the request is on a custom domain by comparing the request host
 /// to the configured SHORT_DOMAIN. Returns Some(hostname) if it's a custom domain,
 /// or None if it's the default short domain.
-fn get_custom_host(req: &Request, env: &Env) -> Option<String> {
+pub(crate) fn get_custom_host(req: &Request, env: &Env) -> Option<String> {
     let short_domain = env.var("SHORT_DOMAIN").ok().map(|v| v.to_string());
     let request_host = req
         .url()
@@ -59,6 +136,19 @@ pub async fn handle_redirect(
 ) -> Result<RedirectResult> {
     let kv = ctx.kv("URL_MAPPINGS")?;
 
+    // Maintenance mode pauses every redirect. A KV error fails open so an
+    // outage of the flag never takes redirects down with it.
+    if kv::get_maintenance_state(&kv)
+        .await
+        .map(|state| state.enabled)
+        .unwrap_or(false)
+    {
+        return Ok(RedirectResult {
+            response: maintenance_response()?,
+            analytics_future: None,
+        });
+    }
+
     let client_ip = get_client_ip(&req);
     let rate_limit_key = RateLimiter::ip_key("redirect", &client_ip);
     let rate_limit_config = RateLimitConfig::redirect();
@@ -67,7 +157,8 @@ pub async fn handle_redirect(
         &kv,
         &rate_limit_key,
         &rate_limit_config,
-        is_kv_rate_limiting_enabled(&ctx.env),
+        &RateLimitSettings::from_env(&ctx.env),
+        &client_ip,
     )
     .await
     {
@@ -136,9 +227,54 @@ pub async fn handle_redirect(
         });
     };
 
+    if mapping.status == LinkStatus::Blocked {
+        let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+        // Settings errors fall back to the plain /404 redirect
+        let blocked = SettingsService::new()
+            .get_blocked_link_response(&db)
+            .await
+            .unwrap_or_default();
+        let response = match blocked.status_code() {
+            Some(status) => blocked_link_response(status)?,
+            None => Response::redirect_with_status(not_found_url, 302)?,
+        };
+        return Ok(RedirectResult {
+            response,
+            analytics_future: None,
+        });
+    }
+
+    if mapping.status == LinkStatus::Disabled
+        && mapping.disabled_reason.as_deref() == Some(DISABLED_REASON_SUSPENSION)
+    {
+        let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+        // Settings errors fall back to the plain /404 redirect
+        let suspended_url = SettingsService::new()
+            .get_suspended_link_redirect_url(&db)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|url| Url::parse(&url).ok());
+        if let Some(url) = suspended_url {
+            return Ok(RedirectResult {
+                response: Response::redirect_with_status(url, 302)?,
+                analytics_future: None,
+            });
+        }
+    }
+
+    // This link's dead ends (disabled, expired, over its click cap) go to its
+    // fallback_url when set; links disabled by a suspension or an admin never do
+    let unavailable_url = mapping
+        .fallback_url
+        .as_deref()
+        .filter(|_| mapping.disabled_reason.is_none())
+        .and_then(|url| Url::parse(url).ok())
+        .unwrap_or(not_found_url);
+
     if !matches!(mapping.status, LinkStatus::Active) {
         return Ok(RedirectResult {
-            response: Response::redirect_with_status(not_found_url, 302)?,
+            response: Response::redirect_with_status(unavailable_url, 302)?,
             analytics_future: None,
         });
     }
@@ -147,7 +283,27 @@ pub async fn handle_redirect(
         let now = now_timestamp();
         if now > expires_at {
             return Ok(RedirectResult {
-                response: Response::redirect_with_status(not_found_url, 302)?,
+                response: Response::redirect_with_status(unavailable_url, 302)?,
+                analytics_future: None,
+            });
+        }
+    }
+
+    // Click cap. The counter is incremented by the deferred analytics future,
+    // so the KV mapping's view of it would be stale: capped links (and only
+    // those) read the current count from D1 here instead. Clicks racing each
+    // other before their increments land can each be served, so a link may
+    // overshoot max_clicks by the number of clicks in flight at once; repeat
+    // clicks skipped by click dedup do not count. D1 errors fail open.
+    if let Some(max_clicks) = mapping.max_clicks {
+        let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+        let clicks = LinkRepository::new()
+            .get_click_count(&db, &mapping.link_id)
+            .await
+            .unwrap_or(0);
+        if clicks >= max_clicks {
+            return Ok(RedirectResult {
+                response: Response::redirect_with_status(unavailable_url, 302)?,
                 analytics_future: None,
             });
         }
@@ -173,21 +329,108 @@ pub async fn handle_redirect(
         }
     };
 
-    let mut destination_url = Url::parse(effective_destination)?;
+    // Fill click-time template placeholders; plain links skip the scan
+    let rendered;
+    let effective_destination = if mapping.templated {
+        let values = TemplateValues {
+            country: req.headers().get("CF-IPCountry").ok().flatten(),
+            city: req.headers().get("CF-IPCity").ok().flatten(),
+            date: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+        };
+        rendered = render_destination_template(effective_destination, &values);
+        rendered.as_str()
+    } else {
+        effective_destination.as_str()
+    };
+
+    // Apply org-level rewrite rules. Rules are read from the KV cache; a KV
+    // failure must never block a redirect, so errors fall back to the stored URL.
+    let rewritten = match mapping.org_id.as_deref() {
+        Some(org_id) => match kv::get_rewrite_rules(&kv, org_id).await {
+            Ok(rules) => rewrite_destination(&rules, effective_destination),
+            Err(e) => {
+                console_log!(
+                    "{}",
+                    serde_json::json!({
+                        "event": "rewrite_rules_kv_error",
+                        "link_id": mapping.link_id,
+                        "error": e.to_string(),
+                        "level": "warn"
+                    })
+                );
+                None
+            }
+        },
+        None => None,
+    };
+
+    // A rule's to_pattern is blacklist-checked when it is saved, but the
+    // rewritten URL can still hit an entry added later or a more specific
+    // one: a blocked rewrite is dropped and the stored destination is used.
+    // D1 errors fail open, like the click cap.
+    let rewritten = match rewritten {
+        Some(url) => {
+            let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+            let blocked = BlacklistRepository::new()
+                .is_blacklisted(&db, &url)
+                .await
+                .unwrap_or(false);
+            (!blocked).then_some(url)
+        }
+        None => None,
+    };
+
+    let mut destination_url = Url::parse(rewritten.as_deref().unwrap_or(effective_destination))?;
 
-    if let Some(ref utm) = mapping.utm_params {
-        let pairs: Vec<(&str, &str)> = [
-            ("utm_source", utm.utm_source.as_deref()),
-            ("utm_medium", utm.utm_medium.as_deref()),
-            ("utm_campaign", utm.utm_campaign.as_deref()),
-            ("utm_term", utm.utm_term.as_deref()),
-            ("utm_content", utm.utm_content.as_deref()),
-            ("utm_ref", utm.utm_ref.as_deref()),
+    // UTM tags and visitor query params only make sense for web destinations;
+    // appending them to mailto:/tel: would change the message or number
+    let is_web_destination = matches!(destination_url.scheme(), "http" | "https");
+
+    // Per-org config (branded interstitial, UTM signing secret). Fails open
+    // to a plain redirect.
+    let org_config = match mapping.org_id.as_deref() {
+        Some(org_id) => kv::get_org_redirect_config(&kv, org_id)
+            .await
+            .unwrap_or_default(),
+        None => OrgRedirectConfig::default(),
+    };
+
+    // Signed UTM override (`?u=`). A token that fails verification is
+    // ignored and the link's stored UTMs apply unchanged.
+    let utm_override: Option<Vec<(String, String)>> = match org_config.utm_signing_secret {
+        Some(ref secret) if is_web_destination => req.url().ok().and_then(|incoming_url| {
+            incoming_url
+                .query_pairs()
+                .find(|(k, _)| k == UTM_TOKEN_PARAM)
+                .and_then(|(_, token)| verify_utm_token(secret, &short_code, &token))
+        }),
+        _ => None,
+    };
+
+    if is_web_destination {
+        let stored = mapping.utm_params.as_ref();
+        let mut pairs: Vec<(&str, &str)> = [
+            ("utm_source", stored.and_then(|u| u.utm_source.as_deref())),
+            ("utm_medium", stored.and_then(|u| u.utm_medium.as_deref())),
+            (
+                "utm_campaign",
+                stored.and_then(|u| u.utm_campaign.as_deref()),
+            ),
+            ("utm_term", stored.and_then(|u| u.utm_term.as_deref())),
+            ("utm_content", stored.and_then(|u| u.utm_content.as_deref())),
+            ("utm_ref", stored.and_then(|u| u.utm_ref.as_deref())),
         ]
         .into_iter()
         .filter_map(|(k, v)| v.filter(|s| !s.is_empty()).map(|s| (k, s)))
         .collect();
 
+        for (key, value) in utm_override.iter().flatten() {
+            match pairs.iter_mut().find(|(k, _)| *k == key.as_str()) {
+                Some(pair) => pair.1 = value.as_str(),
+                None => pairs.push((key.as_str(), value.as_str())),
+            }
+        }
+
         if !pairs.is_empty() {
             let mut q = destination_url.query_pairs_mut();
             for (k, v) in pairs {
@@ -196,11 +439,14 @@ pub async fn handle_redirect(
         }
     }
 
-    if mapping.forward_query_params
+    if is_web_destination
+        && mapping.forward_query_params
         && let Ok(incoming_url) = req.url()
     {
+        // A verified token has been applied; don't leak it to the destination
         let visitor_pairs: Vec<(String, String)> = incoming_url
             .query_pairs()
+            .filter(|(k, _)| utm_override.is_none() || k != UTM_TOKEN_PARAM)
             .map(|(k, v)| (k.into_owned(), v.into_owned()))
             .collect();
         if !visitor_pairs.is_empty() {
@@ -211,19 +457,87 @@ pub async fn handle_redirect(
         }
     }
 
+    let is_preview_crawler = org_config.crawler_preview
+        && req
+            .headers()
+            .get("User-Agent")
+            .ok()
+            .flatten()
+            .is_some_and(|ua| is_crawler(&ua));
+
+    let interstitial_delay = org_config.interstitial_delay_for(destination_url.host_str());
+
     let redirect_status = mapping.redirect_type.parse::<u16>().unwrap_or(301);
-    let response = Response::redirect_with_status(destination_url, redirect_status)?;
+    let mut response = if is_preview_crawler {
+        // The title is not cached in KV; crawler hits are rare enough to read D1
+        let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+        let title = LinkRepository::new()
+            .get_by_id_no_auth(&db, &mapping.link_id)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|link| link.title);
+        crawler_preview_response(&destination_url, title.as_deref())?
+    } else if let Some(delay) = interstitial_delay {
+        interstitial_response(&destination_url, &org_config, delay)?
+    } else {
+        match mapping.response_headers {
+            Some(ref custom_headers) if !custom_headers.is_empty() => {
+                build_redirect_with_headers(&destination_url, redirect_status, custom_headers)?
+            }
+            _ => Response::redirect_with_status(destination_url, redirect_status)?,
+        }
+    };
+
+    // A just-edited destination must not be pinned by browser or proxy caches,
+    // nor may a capped link, whose cached redirect would outlive the cap
+    if mapping.recently_edited(now_timestamp()) || mapping.max_clicks.is_some() {
+        response.headers_mut().set("Cache-Control", "no-store")?;
+    }
 
     let referrer = req.headers().get("Referer").ok().flatten();
     let user_agent = req.headers().get("User-Agent").ok().flatten();
     let country = req.headers().get("CF-IPCountry").ok().flatten();
     let city = req.headers().get("CF-IPCity").ok().flatten();
+    // Only the org's allowlisted headers are recorded
+    let extra = org_config.collect_analytics_headers(|name| req.headers().get(name).ok().flatten());
 
     let db = ctx.env.get_binding::<D1Database>("rushomon")?;
     let link_id = mapping.link_id.clone();
+    let requested_code = short_code;
     let now = now_timestamp();
+    let visitor_hash = hash_ip(&format!(
+        "{}|{}",
+        client_ip,
+        user_agent.as_deref().unwrap_or("")
+    ));
+    let dedup_kv = kv;
 
     let analytics_future: Pin<Box<dyn Future<Output = ()> + 'static>> = Box::pin(async move {
+        // Repeat clicks by the same visitor within the dedup window are not
+        // counted at all. Settings and KV errors fail open to counting.
+        let dedup_window = SettingsService::new()
+            .get_click_dedup_window(&db)
+            .await
+            .unwrap_or(0);
+        if dedup_window > 0 {
+            let key = click_dedup_key(&visitor_hash, &link_id);
+            let last_counted = crate::kv::click_dedup::get_last_counted_click(&dedup_kv, &key)
+                .await
+                .ok()
+                .flatten();
+            if is_duplicate_click(last_counted, now, dedup_window) {
+                return;
+            }
+            let _ = crate::kv::click_dedup::store_counted_click(
+                &dedup_kv,
+                &key,
+                now,
+                click_dedup_ttl(dedup_window),
+            )
+            .await;
+        }
+
         let repo = LinkRepository::new();
         let link = match repo.get_by_id_no_auth(&db, &link_id).await {
             Ok(Some(link)) => link,
@@ -247,15 +561,28 @@ pub async fn handle_redirect(
             return;
         }
 
-        let event = AnalyticsEvent {
-            id: None,
-            link_id: link_id.clone(),
-            org_id: link.org_id,
-            timestamp: now,
-            referrer,
-            user_agent,
-            country,
-            city,
+        // Alias codes resolve to the link's mapping; record which one was used
+        let alias_code = (requested_code != link.short_code).then_some(requested_code);
+
+        // Past the soft cap only 1 in N raw events is stored; the counters
+        // below still see every click. Settings errors fail open to storing.
+        // The org tier is only looked up once the link is past the lowest cap.
+        let sample_rate = match SettingsService::new()
+            .get_analytics_sampling_policy(&db)
+            .await
+        {
+            Ok(Some(policy))
+                if policy
+                    .min_soft_cap()
+                    .is_some_and(|cap| link.click_count >= cap) =>
+            {
+                let tier = match OrgRepository::new().get_by_id(&db, &link.org_id).await {
+                    Ok(Some(org)) => Some(OrgService::new().get_org_tier(&db, &org).await),
+                    _ => None,
+                };
+                policy.sample_rate_for(tier.as_ref(), link.click_count)
+            }
+            _ => Some(1),
         };
 
         let year_month = chrono::Utc
@@ -263,31 +590,68 @@ pub async fn handle_redirect(
             .single()
             .map(|dt| dt.format("%Y-%m").to_string())
             .unwrap_or_default();
-        if !year_month.is_empty() {
-            if let Err(e) = repo
-                .log_analytics_event_and_increment(&db, &event, &year_month)
-                .await
-            {
-                console_log!(
-                    "{}",
-                    serde_json::json!({
-                        "event": "analytics_event_failed",
-                        "link_id": link_id,
-                        "error": e.to_string(),
-                        "level": "error"
-                    })
-                );
+
+        match sample_rate {
+            Some(sample_rate) => {
+                let event = AnalyticsEvent {
+                    id: None,
+                    link_id: link_id.clone(),
+                    org_id: link.org_id,
+                    timestamp: now,
+                    referrer,
+                    user_agent,
+                    country,
+                    city,
+                    alias_code,
+                    sample_rate,
+                    extra,
+                };
+
+                if !year_month.is_empty() {
+                    if let Err(e) = repo
+                        .log_analytics_event_and_increment(&db, &event, &year_month)
+                        .await
+                    {
+                        console_log!(
+                            "{}",
+                            serde_json::json!({
+                                "event": "analytics_event_failed",
+                                "link_id": link_id,
+                                "error": e.to_string(),
+                                "level": "error"
+                            })
+                        );
+                    }
+                } else if let Err(e) = repo.log_analytics_event(&db, &event).await {
+                    console_log!(
+                        "{}",
+                        serde_json::json!({
+                            "event": "analytics_event_failed",
+                            "link_id": link_id,
+                            "error": e.to_string(),
+                            "level": "error"
+                        })
+                    );
+                }
+            }
+            // Sampled out: no raw event, but the monthly counter stays exact
+            None => {
+                if !year_month.is_empty()
+                    && let Err(e) = repo
+                        .increment_monthly_clicks(&db, &link_id, &link.org_id, &year_month, now)
+                        .await
+                {
+                    console_log!(
+                        "{}",
+                        serde_json::json!({
+                            "event": "monthly_clicks_failed",
+                            "link_id": link_id,
+                            "error": e.to_string(),
+                            "level": "error"
+                        })
+                    );
+                }
             }
-        } else if let Err(e) = repo.log_analytics_event(&db, &event).await {
-            console_log!(
-                "{}",
-                serde_json::json!({
-                    "event": "analytics_event_failed",
-                    "link_id": link_id,
-                    "error": e.to_string(),
-                    "level": "error"
-                })
-            );
         }
         if let Err(e) = repo.increment_click_count(&db, &link_id).await {
             console_log!(
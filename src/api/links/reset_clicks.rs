@@ -0,0 +1,85 @@
+/// Link click counter reset handler
+///
+/// POST /api/links/{id}/reset-clicks — zero a link's click count, e.g. after a
+/// bot storm, optionally deleting its raw analytics events in a window.
+use crate::auth;
+use crate::services::LinkService;
+use crate::utils::{AppError, QueryParams, now_timestamp};
+use worker::d1::D1Database;
+use worker::*;
+
+#[utoipa::path(
+    post,
+    path = "/api/links/{id}/reset-clicks",
+    tag = "Links",
+    summary = "Reset a link's click count",
+    description = "Sets the link's click_count to 0. With delete_events=true the link's raw analytics events between start and end (Unix seconds, inclusive; default: all time up to now) are also deleted. Deleting events is destructive: the analytics for that window cannot be recovered. Organization owners and admins only",
+    params(
+        ("id" = String, Path, description = "Link ID"),
+        ("delete_events" = Option<bool>, Query, description = "Also delete raw analytics events (default: false)"),
+        ("start" = Option<i64>, Query, description = "Start of the event window to delete (default: 0)"),
+        ("end" = Option<i64>, Query, description = "End of the event window to delete (default: now)"),
+    ),
+    responses(
+        (status = 200, description = "{click_count, events_deleted}"),
+        (status = 400, description = "Invalid event window"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Owner or admin role required"),
+        (status = 404, description = "Link not found"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_reset_link_clicks(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner(req, ctx).await.unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+
+    let link_id = ctx
+        .param("id")
+        .ok_or_else(|| AppError::BadRequest("Missing link ID".to_string()))?;
+
+    let params = QueryParams::from_request(&req)?;
+    let delete_events = if params.flag("delete_events") {
+        let start = params.get_i64("start").unwrap_or(0);
+        let end = params.get_i64("end").unwrap_or_else(now_timestamp);
+        if start > end {
+            return Err(AppError::BadRequest(
+                "'start' must not be after 'end'".to_string(),
+            ));
+        }
+        Some((start, end))
+    } else {
+        None
+    };
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let events_deleted = LinkService::new()
+        .reset_click_count(
+            &db,
+            link_id,
+            &user_ctx.org_id,
+            &user_ctx.user_id,
+            delete_events,
+        )
+        .await?;
+
+    console_log!(
+        "{}",
+        serde_json::json!({
+            "event": "link_clicks_reset",
+            "link_id": link_id,
+            "org_id": user_ctx.org_id,
+            "user_id": user_ctx.user_id,
+            "events_window": delete_events,
+            "events_deleted": events_deleted,
+            "level": "info"
+        })
+    );
+
+    Ok(Response::from_json(&serde_json::json!({
+        "click_count": 0,
+        "events_deleted": events_deleted,
+    }))?)
+}
@@ -1,9 +1,10 @@
 use crate::auth;
 use crate::models::link::UpdateLinkRequest;
 use crate::repositories::BlacklistRepository;
-use crate::services::LinkService;
+use crate::services::{LinkService, SettingsService};
+use crate::utils::response_headers::validate_response_headers;
 use crate::utils::validate_and_normalize_tags;
-use crate::utils::{now_timestamp, validate_url};
+use crate::utils::{now_timestamp, validate_url_with_schemes};
 use serde_json::json;
 use worker::d1::D1Database;
 use worker::*;
@@ -19,7 +20,7 @@ fn json_error(message: &str, status: u16) -> Response {
     path = "/api/links/{id}",
     tag = "Links",
     summary = "Update a link",
-    description = "Updates a link's destination URL, title, tags, expiry, UTM parameters, redirect type, or forward-query-params setting. Use clear_expiration=true to remove the expiration date. Updates are written to both D1 and KV atomically",
+    description = "Updates a link's destination URL, title, tags, expiry, UTM parameters, redirect type, forward-query-params setting, or fallback URL. Use clear_expiration=true to remove the expiration date and clear_fallback_url=true to go back to the 404 page. Updates are written to both D1 and KV atomically",
     params(
         ("id" = String, Path, description = "Link ID"),
     ),
@@ -52,11 +53,14 @@ pub async fn handle_update_link(mut req: Request, ctx: RouteContext<()>) -> Resu
     };
 
     if let Some(url) = &update_req.destination_url {
-        if let Err(e) = validate_url(url) {
+        let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+        let allowed_schemes = SettingsService::new()
+            .get_allowed_destination_schemes(&db)
+            .await?;
+        if let Err(e) = validate_url_with_schemes(url, &allowed_schemes) {
             return Ok(json_error(&format!("Invalid URL: {}", e), 400));
         }
 
-        let db = ctx.env.get_binding::<D1Database>("rushomon")?;
         let blacklist_repo = BlacklistRepository::new();
         if blacklist_repo.is_blacklisted(&db, url).await? {
             return Ok(json_error("Destination URL is blocked", 403));
@@ -69,6 +73,18 @@ pub async fn handle_update_link(mut req: Request, ctx: RouteContext<()>) -> Resu
         return Ok(json_error("Title must be 200 characters or less", 400));
     }
 
+    // Empty map clears the headers
+    let response_headers_value = match update_req.response_headers {
+        Some(ref headers) => match validate_response_headers(headers) {
+            Ok(h) if h.is_empty() => Some(None),
+            Ok(h) => Some(Some(h)),
+            Err(e) => {
+                return Ok(json_error(&format!("Invalid response headers: {}", e), 400));
+            }
+        },
+        None => None,
+    };
+
     let now = now_timestamp();
 
     // Convert clear_expiration flag to expires_at format for repository
@@ -143,6 +159,70 @@ pub async fn handle_update_link(mut req: Request, ctx: RouteContext<()>) -> Resu
         }
     }
 
+    // Device destinations follow the same URL and blacklist rules as destination_url
+    let mut device_urls = (None, None, None);
+    if update_req.ios_url.is_some()
+        || update_req.android_url.is_some()
+        || update_req.desktop_url.is_some()
+    {
+        let allowed_schemes = SettingsService::new()
+            .get_allowed_destination_schemes(&db)
+            .await?;
+        let validated = async {
+            Ok::<_, crate::utils::AppError>((
+                link_service
+                    .validate_optional_url(
+                        &db,
+                        "ios_url",
+                        update_req.ios_url.as_deref(),
+                        &allowed_schemes,
+                    )
+                    .await?,
+                link_service
+                    .validate_optional_url(
+                        &db,
+                        "android_url",
+                        update_req.android_url.as_deref(),
+                        &allowed_schemes,
+                    )
+                    .await?,
+                link_service
+                    .validate_optional_url(
+                        &db,
+                        "desktop_url",
+                        update_req.desktop_url.as_deref(),
+                        &allowed_schemes,
+                    )
+                    .await?,
+            ))
+        }
+        .await;
+        device_urls = match validated {
+            Ok(urls) => urls,
+            Err(e) => return Ok(e.into_response()),
+        };
+    }
+    let (ios_url, android_url, desktop_url) = device_urls;
+
+    let mut fallback_url = None;
+    if update_req.fallback_url.is_some() {
+        let allowed_schemes = SettingsService::new()
+            .get_allowed_destination_schemes(&db)
+            .await?;
+        fallback_url = match link_service
+            .validate_optional_url(
+                &db,
+                "fallback_url",
+                update_req.fallback_url.as_deref(),
+                &allowed_schemes,
+            )
+            .await
+        {
+            Ok(url) => url,
+            Err(e) => return Ok(e.into_response()),
+        };
+    }
+
     let mut normalized_tags = None;
     if let Some(ref tags) = update_req.tags {
         let tags = match validate_and_normalize_tags(tags) {
@@ -179,17 +259,22 @@ pub async fn handle_update_link(mut req: Request, ctx: RouteContext<()>) -> Resu
     let ios_url_value = if update_req.clear_ios_url == Some(true) {
         Some(None) // Clear the URL
     } else {
-        update_req.ios_url.map(Some)
+        ios_url.map(Some)
     };
     let android_url_value = if update_req.clear_android_url == Some(true) {
         Some(None) // Clear the URL
     } else {
-        update_req.android_url.map(Some)
+        android_url.map(Some)
     };
     let desktop_url_value = if update_req.clear_desktop_url == Some(true) {
         Some(None) // Clear the URL
     } else {
-        update_req.desktop_url.map(Some)
+        desktop_url.map(Some)
+    };
+    let fallback_url_value = if update_req.clear_fallback_url == Some(true) {
+        Some(None) // Back to the 404 page
+    } else {
+        fallback_url.map(Some)
     };
 
     let status_str = update_req.status.as_ref().map(|s| s.as_str().to_string());
@@ -214,6 +299,8 @@ pub async fn handle_update_link(mut req: Request, ctx: RouteContext<()>) -> Resu
             ios_url_value,
             android_url_value,
             desktop_url_value,
+            response_headers_value,
+            fallback_url_value,
         )
         .await
         .map_err(|e| worker::Error::RustError(e.to_string()))?;
@@ -2,12 +2,14 @@ pub mod admin;
 pub mod analytics;
 pub mod auth;
 pub mod billing;
+pub mod dashboard;
 pub mod domains;
 pub mod keys;
 pub mod links;
 pub mod notifications;
 pub mod orgs;
 pub mod reports;
+pub mod robots;
 pub mod router;
 pub mod settings;
 pub mod tags;
@@ -5,12 +5,16 @@
 /// PATCH  /api/orgs/{id} - Update organization
 /// DELETE /api/orgs/{id} - Delete organization
 use crate::auth;
+use crate::models::InvitationStatus;
 use crate::repositories::OrgRepository;
 use crate::services::OrgService;
-use crate::utils::AppError;
+use crate::utils::{AppError, QueryParams};
 use worker::d1::D1Database;
 use worker::*;
 
+/// Pending invitations listed inline by GET /api/orgs/{id}
+const INLINE_PENDING_INVITATIONS_LIMIT: i64 = 50;
+
 #[utoipa::path(
     post,
     path = "/api/orgs",
@@ -22,6 +26,7 @@ use worker::*;
         (status = 400, description = "Missing name"),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Org limit reached for current tier"),
+        (status = 409, description = "Name already used in this billing account (when uniqueness is enabled)"),
     ),
     security(("Bearer" = []), ("session_cookie" = []))
 )]
@@ -70,23 +75,16 @@ async fn inner_create_org(mut req: Request, ctx: RouteContext<()>) -> Result<Res
         &user_ctx.session_id,
         &user_ctx.role,
         &jwt_secret,
+        &auth::session::JwtClaimsConfig::from_env(&ctx.env),
     )?;
 
     // Update session KV to the new org
     auth::session::store_session(&kv_store, &user_ctx.session_id, &user_ctx.user_id, &org.id)
         .await?;
 
-    let domain = ctx
-        .env
-        .var("DOMAIN")
-        .map(|v| v.to_string())
-        .unwrap_or_else(|_| "localhost:8787".to_string());
-    let scheme = if domain.starts_with("localhost") {
-        "http"
-    } else {
-        "https"
-    };
-    let access_cookie = auth::session::create_access_cookie_with_scheme(&new_access_token, scheme);
+    let cookie_config = auth::session::CookieConfig::from_env(&ctx.env)?;
+    let access_cookie =
+        auth::session::create_access_cookie_with_config(&new_access_token, &cookie_config);
 
     let mut response = Response::from_json(&serde_json::json!({
         "org": org,
@@ -101,9 +99,10 @@ async fn inner_create_org(mut req: Request, ctx: RouteContext<()>) -> Result<Res
     path = "/api/orgs/{id}",
     tag = "Organizations",
     summary = "Get organization",
-    description = "Returns org details including the member list with roles and pending invitations. The caller must be a member of the org",
+    description = "Returns org details including the member list with roles and, for owners and admins, the 50 newest pending invitations; `pending_invitations_total` gives the full count, and GET /api/orgs/{id}/invitations pages through them. The caller must be a member of the org. Pass `include=age` to add `age_days` (whole days since creation) and `is_primary` (whether this is the caller's primary org: the first org they joined as owner)",
     params(
         ("id" = String, Path, description = "Organization ID"),
+        ("include" = Option<String>, Query, description = "Set to `age` to include age_days and is_primary"),
     ),
     responses(
         (status = 200, description = "Organization with members and invitations"),
@@ -140,29 +139,53 @@ async fn inner_get_org(req: Request, ctx: RouteContext<()>) -> Result<Response,
     // Get tier from billing account for API response
     let tier = service.get_org_tier(&db, &org).await;
 
-    // Owners and admins see pending invitations
-    let pending_invitations = if member.role == "owner" || member.role == "admin" {
-        repo.list_pending_invitations(&db, &org_id).await?
-    } else {
-        vec![]
-    };
+    // Owners and admins see the newest pending invitations; the full list is
+    // paginated at GET /api/orgs/:id/invitations
+    let (pending_invitations, pending_invitations_total) =
+        if member.role == "owner" || member.role == "admin" {
+            let invitations = repo
+                .list_invitations(
+                    &db,
+                    &org_id,
+                    InvitationStatus::Pending,
+                    INLINE_PENDING_INVITATIONS_LIMIT,
+                    0,
+                )
+                .await?;
+            let total = repo.count_pending_invitations(&db, &org_id).await?;
+            (invitations, total)
+        } else {
+            (vec![], 0)
+        };
 
     let logo_url = repo.get_logo_url(&db, &org_id).await.unwrap_or(None);
     let link_count = repo.count_links(&db, &org_id).await.unwrap_or(0);
 
+    let mut org_json = serde_json::json!({
+        "id": org.id,
+        "name": org.name,
+        "tier": tier.as_str(),
+        "created_at": org.created_at,
+        "role": member.role,
+        "logo_url": logo_url,
+        "link_count": link_count,
+        "billing_account_id": org.billing_account_id,
+    });
+
+    let include_age = QueryParams::from_request(&req)?
+        .get("include")
+        .is_some_and(|v| v.split(',').any(|part| part.trim() == "age"));
+    if include_age {
+        let primary_org_id = repo.get_primary_org_id(&db, &user_ctx.user_id).await?;
+        org_json["age_days"] = org.age_days(crate::utils::now_timestamp()).into();
+        org_json["is_primary"] = (primary_org_id.as_deref() == Some(org.id.as_str())).into();
+    }
+
     Ok(Response::from_json(&serde_json::json!({
-        "org": {
-            "id": org.id,
-            "name": org.name,
-            "tier": tier.as_str(),
-            "created_at": org.created_at,
-            "role": member.role,
-            "logo_url": logo_url,
-            "link_count": link_count,
-            "billing_account_id": org.billing_account_id,
-        },
+        "org": org_json,
         "members": members,
         "pending_invitations": pending_invitations,
+        "pending_invitations_total": pending_invitations_total,
     }))?)
 }
 
@@ -181,6 +204,7 @@ async fn inner_get_org(req: Request, ctx: RouteContext<()>) -> Result<Response,
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Owner or admin required"),
         (status = 404, description = "Organization not found"),
+        (status = 409, description = "Name already used in this billing account (when uniqueness is enabled)"),
     ),
     security(("Bearer" = []), ("session_cookie" = []))
 )]
@@ -230,7 +254,13 @@ async fn inner_update_org(mut req: Request, ctx: RouteContext<()>) -> Result<Res
         ));
     }
 
-    repo.update_name(&db, &org_id, &name).await?;
+    service.rename_org(&db, &org_id, &name).await?;
+
+    // Keep the name on the branded interstitial page in sync
+    if repo.get_interstitial_delay(&db, &org_id).await? > 0 {
+        let kv = ctx.kv("URL_MAPPINGS")?;
+        service.sync_redirect_config(&db, &kv, &org_id).await?;
+    }
 
     let updated_org = repo
         .get_by_id(&db, &org_id)
@@ -256,7 +286,7 @@ async fn inner_update_org(mut req: Request, ctx: RouteContext<()>) -> Result<Res
     path = "/api/orgs/{id}",
     tag = "Organizations",
     summary = "Delete an organization",
-    description = "Permanently deletes an organization. Requires owner role and the user must belong to at least one other org. Links can either be migrated to another org or deleted",
+    description = "Permanently deletes an organization. Requires owner role and the user must belong to at least one other org. Links can either be migrated to another org or deleted; deleting an org that has links requires `confirm_link_count` to equal its current link count",
     params(
         ("id" = String, Path, description = "Organization ID"),
     ),
@@ -266,6 +296,7 @@ async fn inner_update_org(mut req: Request, ctx: RouteContext<()>) -> Result<Res
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Owner required"),
         (status = 404, description = "Organization not found"),
+        (status = 409, description = "confirm_link_count missing or stale; body carries the current link_count"),
     ),
     security(("Bearer" = []), ("session_cookie" = []))
 )]
@@ -316,6 +347,22 @@ async fn inner_delete_org(mut req: Request, ctx: RouteContext<()>) -> Result<Res
     })?;
     let target_org_id = body["target_org_id"].as_str();
 
+    // Deleting links is irreversible: the caller must echo the current link
+    // count so a stale UI can't wipe links it never showed the user
+    if action == "delete" {
+        let link_count = repo.count_links(&db, &org_id).await?;
+        if link_count > 0 && body["confirm_link_count"].as_i64() != Some(link_count) {
+            return Ok(Response::from_json(&serde_json::json!({
+                "message": format!(
+                    "This organization has {} links. Set confirm_link_count to {} to delete them",
+                    link_count, link_count
+                ),
+                "link_count": link_count,
+            }))?
+            .with_status(409));
+        }
+    }
+
     service
         .delete_org(&db, &kv, &org_id, &user_ctx.user_id, action, target_org_id)
         .await?;
@@ -335,6 +382,7 @@ async fn inner_delete_org(mut req: Request, ctx: RouteContext<()>) -> Result<Res
         &user_ctx.session_id,
         &user_ctx.role,
         &jwt_secret,
+        &auth::session::JwtClaimsConfig::from_env(&ctx.env),
     )?;
 
     auth::session::store_session(
@@ -345,17 +393,9 @@ async fn inner_delete_org(mut req: Request, ctx: RouteContext<()>) -> Result<Res
     )
     .await?;
 
-    let domain = ctx
-        .env
-        .var("DOMAIN")
-        .map(|v| v.to_string())
-        .unwrap_or_else(|_| "localhost:8787".to_string());
-    let scheme = if domain.starts_with("localhost") {
-        "http"
-    } else {
-        "https"
-    };
-    let access_cookie = auth::session::create_access_cookie_with_scheme(&new_access_token, scheme);
+    let cookie_config = auth::session::CookieConfig::from_env(&ctx.env)?;
+    let access_cookie =
+        auth::session::create_access_cookie_with_config(&new_access_token, &cookie_config);
 
     let mut response = Response::from_json(&serde_json::json!({
         "success": true,
@@ -1,18 +1,25 @@
 /// Org invitation handlers
 ///
+/// GET /api/orgs/{id}/invitations - List invitations (paginated)
 /// POST /api/orgs/{id}/invitations - Create invitation
 /// DELETE /api/orgs/{id}/invitations/{invitation_id} - Revoke invitation
 /// POST /api/orgs/{id}/invitations/{invitation_id}/resend - Resend invitation
 /// GET /api/invite/{token} - Get invite info (public)
 /// POST /api/invite/{token}/accept - Accept invite
 use crate::auth;
+use crate::models::{InvitationStatus, PaginatedResponse, PaginationMeta};
 use crate::repositories::{OrgRepository, UserRepository};
 use crate::services::OrgService;
 use crate::utils::email::send_org_invitation;
-use crate::utils::{AppError, get_frontend_url};
+use crate::utils::{AppError, QueryParams, get_frontend_url};
 use worker::d1::D1Database;
 use worker::*;
 
+/// Invitations per page when `limit` is not given
+const INVITATIONS_DEFAULT_LIMIT: i64 = 20;
+/// Largest accepted `limit`
+const INVITATIONS_MAX_LIMIT: i64 = 100;
+
 async fn require_owner_or_admin(
     repo: &OrgRepository,
     db: &D1Database,
@@ -29,12 +36,75 @@ async fn require_owner_or_admin(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/orgs/{id}/invitations",
+    tag = "Organizations",
+    summary = "List invitations",
+    description = "Lists the org's invitations in one state, newest first, paginated. Requires owner or admin role",
+    params(
+        ("id" = String, Path, description = "Organization ID"),
+        ("status" = Option<String>, Query, description = "pending (default), expired or accepted"),
+        ("page" = Option<i64>, Query, description = "Page number (default: 1)"),
+        ("limit" = Option<i64>, Query, description = "Invitations per page (default: 20, max 100)"),
+    ),
+    responses(
+        (status = 200, description = "Paginated invitations: {data, pagination}"),
+        (status = 400, description = "Invalid status"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Owner or admin required"),
+        (status = 404, description = "Organization not found"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_list_invitations(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_list_invitations(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_list_invitations(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    let org_id = ctx
+        .param("id")
+        .ok_or_else(|| AppError::BadRequest("Missing org id".to_string()))?
+        .to_string();
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let repo = OrgRepository::new();
+    require_owner_or_admin(&repo, &db, &org_id, &user_ctx.user_id).await?;
+
+    let params = QueryParams::from_request(&req)?;
+    let status = match params.get("status") {
+        Some(value) => InvitationStatus::from_param(&value).ok_or_else(|| {
+            AppError::BadRequest(
+                "Invalid status. Must be 'pending', 'expired' or 'accepted'".to_string(),
+            )
+        })?,
+        None => InvitationStatus::Pending,
+    };
+    let page = params.get_i64("page").unwrap_or(1).max(1);
+    let limit = params
+        .get_i64("limit")
+        .unwrap_or(INVITATIONS_DEFAULT_LIMIT)
+        .clamp(1, INVITATIONS_MAX_LIMIT);
+
+    let invitations = repo
+        .list_invitations(&db, &org_id, status, limit, (page - 1) * limit)
+        .await?;
+    let total = repo.count_invitations(&db, &org_id, status).await?;
+
+    Ok(Response::from_json(&PaginatedResponse::new(
+        invitations,
+        PaginationMeta::new(page, limit, total),
+    ))?)
+}
+
 #[utoipa::path(
     post,
     path = "/api/orgs/{id}/invitations",
     tag = "Organizations",
     summary = "Invite a member",
-    description = "Sends an email invitation to join the organization. Requires owner or admin role. Respects tier member limits. Sends an invitation email with a unique token",
+    description = "Sends an email invitation to join the organization. Requires owner or admin role. Respects tier member limits and the per-org cap on pending invitations. Sends an invitation email with a unique token",
     params(
         ("id" = String, Path, description = "Organization ID"),
     ),
@@ -42,7 +112,7 @@ async fn require_owner_or_admin(
         (status = 200, description = "Invitation created and email sent"),
         (status = 400, description = "Invalid email or missing fields"),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Owner or admin required, or member limit reached"),
+        (status = 403, description = "Owner or admin required, member limit reached, or too many pending invitations"),
         (status = 409, description = "Already a member or invitation pending"),
     ),
     security(("Bearer" = []), ("session_cookie" = []))
@@ -66,7 +136,11 @@ async fn inner_create_invitation(
     let repo = OrgRepository::new();
     require_owner_or_admin(&repo, &db, &org_id, &user_ctx.user_id).await?;
 
-    OrgService::new().check_member_limit(&db, &org_id).await?;
+    let org_service = OrgService::new();
+    org_service
+        .check_pending_invitation_limit(&db, &org_id)
+        .await?;
+    org_service.check_member_limit(&db, &org_id).await?;
 
     let org = repo
         .get_by_id(&db, &org_id)
@@ -125,8 +199,17 @@ async fn inner_create_invitation(
 
     let frontend_url = get_frontend_url(&ctx.env);
     let invite_url = format!("{}/invite/{}", frontend_url, invitation.id);
-    if let Err(e) =
-        send_org_invitation(&ctx.env, &email, &inviter_name, &org.name, &invite_url).await
+    let branding = repo.get_invitation_branding(&db, &org_id).await?;
+    if let Err(e) = send_org_invitation(
+        &ctx.env,
+        &email,
+        &inviter_name,
+        &org.name,
+        &invite_url,
+        branding.invite_subject.as_deref(),
+        branding.invite_message.as_deref(),
+    )
+    .await
     {
         console_log!(
             "{{\"event\":\"invitation_email_failed\",\"org_id\":\"{}\",\"email\":\"{}\",\"error\":\"{}\"}}",
@@ -200,7 +283,7 @@ async fn inner_revoke_invitation(
     path = "/api/orgs/{id}/invitations/{invitation_id}/resend",
     tag = "Organizations",
     summary = "Resend an invitation",
-    description = "Resends the invitation email for a pending invitation. Requires owner or admin role",
+    description = "Resends the invitation email for a pending invitation. Resends of the same invitation must be at least invitation_resend_interval_seconds apart (default 300); sooner attempts get 429 with a Retry-After header. Requires owner or admin role",
     params(
         ("id" = String, Path, description = "Organization ID"),
         ("invitation_id" = String, Path, description = "Invitation ID"),
@@ -211,6 +294,7 @@ async fn inner_revoke_invitation(
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Owner or admin required"),
         (status = 404, description = "Invitation not found or already accepted"),
+        (status = 429, description = "Resent too recently; see Retry-After"),
     ),
     security(("Bearer" = []), ("session_cookie" = []))
 )]
@@ -257,6 +341,22 @@ async fn inner_resend_invitation(
         return Err(AppError::BadRequest("Invitation has expired".to_string()));
     }
 
+    // The slot is taken before sending, so a failing send still counts
+    if let Some(retry_after) = OrgService::new()
+        .claim_invitation_resend(&db, &invitation)
+        .await?
+    {
+        let mut response = AppError::TooManyRequests(format!(
+            "Invitation was resent recently. Try again in {} seconds.",
+            retry_after
+        ))
+        .into_response();
+        response
+            .headers_mut()
+            .set("Retry-After", &retry_after.to_string())?;
+        return Ok(response);
+    }
+
     let org = repo
         .get_by_id(&db, &org_id)
         .await?
@@ -273,12 +373,15 @@ async fn inner_resend_invitation(
 
     let frontend_url = get_frontend_url(&ctx.env);
     let invite_url = format!("{}/invite/{}", frontend_url, invitation.id);
+    let branding = repo.get_invitation_branding(&db, &org_id).await?;
     if let Err(e) = send_org_invitation(
         &ctx.env,
         &invitation.email,
         &inviter_name,
         &org.name,
         &invite_url,
+        branding.invite_subject.as_deref(),
+        branding.invite_message.as_deref(),
     )
     .await
     {
@@ -447,6 +550,7 @@ async fn inner_accept_invite(req: Request, ctx: RouteContext<()>) -> Result<Resp
         &user_ctx.session_id,
         &user_ctx.role,
         &jwt_secret,
+        &auth::session::JwtClaimsConfig::from_env(&ctx.env),
     )?;
     auth::session::store_session(
         &kv,
@@ -456,17 +560,9 @@ async fn inner_accept_invite(req: Request, ctx: RouteContext<()>) -> Result<Resp
     )
     .await?;
 
-    let domain = ctx
-        .env
-        .var("DOMAIN")
-        .map(|v| v.to_string())
-        .unwrap_or_else(|_| "localhost:8787".to_string());
-    let scheme = if domain.starts_with("localhost") {
-        "http"
-    } else {
-        "https"
-    };
-    let access_cookie = auth::session::create_access_cookie_with_scheme(&new_access_token, scheme);
+    let cookie_config = auth::session::CookieConfig::from_env(&ctx.env)?;
+    let access_cookie =
+        auth::session::create_access_cookie_with_config(&new_access_token, &cookie_config);
 
     let tier = OrgService::new().get_org_tier(&db, &_org).await;
 
@@ -0,0 +1,47 @@
+/// Org limits handler
+///
+/// GET /api/orgs/{id}/limits - Effective tier limits for the org
+use crate::auth;
+use crate::services::OrgService;
+use crate::utils::AppError;
+use worker::d1::D1Database;
+use worker::*;
+
+#[utoipa::path(
+    get,
+    path = "/api/orgs/{id}/limits",
+    tag = "Organizations",
+    summary = "Get org tier limits",
+    description = "Returns the organization's billing tier and the limits it resolves to, so clients can gate features without re-deriving them from the tier name. Null numeric limits mean unlimited. Caller must be a member of the org",
+    params(
+        ("id" = String, Path, description = "Organization ID"),
+    ),
+    responses(
+        (status = 200, description = "{tier, limits}"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Organization not found or not a member"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_get_org_limits(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner(req, ctx).await.unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+
+    let org_id = ctx
+        .param("id")
+        .ok_or_else(|| AppError::BadRequest("Missing org id".to_string()))?
+        .to_string();
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let (tier, limits) = OrgService::new()
+        .get_org_limits(&db, &org_id, &user_ctx.user_id)
+        .await?;
+
+    Ok(Response::from_json(&serde_json::json!({
+        "tier": tier,
+        "limits": limits,
+    }))?)
+}
@@ -4,7 +4,7 @@
 /// POST /api/auth/switch-org - Switch active organization
 use crate::auth;
 use crate::services::OrgService;
-use crate::utils::AppError;
+use crate::utils::{AppError, QueryParams};
 use worker::d1::D1Database;
 use worker::*;
 
@@ -13,7 +13,10 @@ use worker::*;
     path = "/api/orgs",
     tag = "Organizations",
     summary = "List user organizations",
-    description = "Returns all organizations the authenticated user belongs to, including their role in each org and the org's current billing tier. Also returns the active org_id from the current session",
+    description = "Returns all organizations the authenticated user belongs to, including their role in each org and the org's current billing tier. Also returns the active org_id from the current session. Pass `include=stats` to add each org's link count and clicks this month",
+    params(
+        ("include" = Option<String>, Query, description = "Set to `stats` to include per-org link count and clicks this month"),
+    ),
     responses(
         (status = 200, description = "List of organizations with role and tier info"),
         (status = 401, description = "Unauthorized"),
@@ -29,9 +32,13 @@ pub async fn handle_list_user_orgs(req: Request, ctx: RouteContext<()>) -> Resul
 async fn inner_list_user_orgs(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
     let user_ctx = auth::authenticate_request(&req, &ctx).await?;
 
+    let include_stats = QueryParams::from_request(&req)?
+        .get("include")
+        .is_some_and(|v| v.split(',').any(|part| part.trim() == "stats"));
+
     let db = ctx.env.get_binding::<D1Database>("rushomon")?;
     let orgs_with_tier = OrgService::new()
-        .list_user_orgs_with_tier(&db, &user_ctx.user_id)
+        .list_user_orgs_with_tier(&db, &user_ctx.user_id, include_stats)
         .await?;
 
     Ok(Response::from_json(&serde_json::json!({
@@ -92,23 +99,16 @@ async fn inner_switch_org(mut req: Request, ctx: RouteContext<()>) -> Result<Res
         &user_ctx.session_id,
         &user_ctx.role,
         &jwt_secret,
+        &auth::session::JwtClaimsConfig::from_env(&ctx.env),
     )?;
 
     // Update session KV to reflect the new active org
     auth::session::store_session(&kv, &user_ctx.session_id, &user_ctx.user_id, &target_org_id)
         .await?;
 
-    let domain = ctx
-        .env
-        .var("DOMAIN")
-        .map(|v| v.to_string())
-        .unwrap_or_else(|_| "localhost:8787".to_string());
-    let scheme = if domain.starts_with("localhost") {
-        "http"
-    } else {
-        "https"
-    };
-    let access_cookie = auth::session::create_access_cookie_with_scheme(&new_access_token, scheme);
+    let cookie_config = auth::session::CookieConfig::from_env(&ctx.env)?;
+    let access_cookie =
+        auth::session::create_access_cookie_with_config(&new_access_token, &cookie_config);
 
     // Get tier from billing account for API response
     let tier = service.get_org_tier(&db, &org).await;
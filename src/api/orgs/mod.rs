@@ -6,21 +6,32 @@
 /// - `members`: Member management
 /// - `invitations`: Invite flow
 /// - `settings`: Org-level settings
+/// - `limits`: Effective tier limits
 /// - `logo`: Logo upload/get/delete
+/// - `rewrite_rules`: Org-level destination rewrite rules
 pub mod crud;
 pub mod invitations;
+pub mod limits;
 pub mod list;
 pub mod logo;
 pub mod members;
+pub mod rewrite_rules;
 pub mod settings;
 
 // Re-export all public handlers for router registration
 pub use crud::{handle_create_org, handle_delete_org, handle_get_org, handle_update_org};
 pub use invitations::{
     handle_accept_invite, handle_create_invitation, handle_get_invite_info,
-    handle_resend_invitation, handle_revoke_invitation,
+    handle_list_invitations, handle_resend_invitation, handle_revoke_invitation,
 };
+pub use limits::handle_get_org_limits;
 pub use list::{handle_list_user_orgs, handle_switch_org};
 pub use logo::{handle_delete_org_logo, handle_get_org_logo, handle_upload_org_logo};
 pub use members::{handle_remove_member, handle_update_member_role};
-pub use settings::{handle_get_org_settings, handle_update_org_settings};
+pub use rewrite_rules::{
+    handle_create_rewrite_rule, handle_delete_rewrite_rule, handle_dry_run_rewrite_rule,
+    handle_list_rewrite_rules,
+};
+pub use settings::{
+    handle_get_org_settings, handle_rotate_utm_signing_secret, handle_update_org_settings,
+};
@@ -0,0 +1,154 @@
+/// Org destination rewrite rule handlers
+///
+/// GET    /api/orgs/{id}/rewrite-rules            - List rules
+/// POST   /api/orgs/{id}/rewrite-rules            - Create a rule
+/// POST   /api/orgs/{id}/rewrite-rules/dry-run    - Count links a rule would affect
+/// DELETE /api/orgs/{id}/rewrite-rules/{rule_id}  - Delete a rule
+use crate::auth;
+use crate::services::{OrgService, RewriteRuleService};
+use crate::utils::AppError;
+use worker::d1::D1Database;
+use worker::*;
+
+const FORBIDDEN_MSG: &str = "Only org owners and admins can manage rewrite rules";
+
+pub async fn handle_list_rewrite_rules(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_list(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_list(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    let org_id = org_id_param(&ctx)?;
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+
+    OrgService::new()
+        .require_owner_or_admin(&db, &org_id, &user_ctx.user_id, FORBIDDEN_MSG)
+        .await?;
+
+    let rules = RewriteRuleService::new().list_rules(&db, &org_id).await?;
+    Ok(Response::from_json(&rules)?)
+}
+
+pub async fn handle_create_rewrite_rule(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_create(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_create(mut req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    let org_id = org_id_param(&ctx)?;
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+
+    OrgService::new()
+        .require_owner_or_admin(&db, &org_id, &user_ctx.user_id, FORBIDDEN_MSG)
+        .await?;
+
+    let (from_pattern, to_pattern) = parse_patterns(&mut req).await?;
+    let kv = ctx.kv("URL_MAPPINGS")?;
+
+    let rule = RewriteRuleService::new()
+        .create_rule(
+            &db,
+            &kv,
+            &org_id,
+            &user_ctx.user_id,
+            &from_pattern,
+            &to_pattern,
+        )
+        .await?;
+
+    // The rule is saved; bringing older KV mappings up to date is best-effort
+    let env = ctx.env.clone();
+    let from_pattern = rule.from_pattern.clone();
+    crate::api::router::defer_task(async move {
+        let (Ok(db), Ok(kv)) = (
+            env.get_binding::<D1Database>("rushomon"),
+            env.kv("URL_MAPPINGS"),
+        ) else {
+            return;
+        };
+        RewriteRuleService::new()
+            .resync_matching_links(&db, &kv, &org_id, &from_pattern)
+            .await;
+    });
+
+    Ok(Response::from_json(&rule)?.with_status(201))
+}
+
+pub async fn handle_dry_run_rewrite_rule(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_dry_run(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_dry_run(mut req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    let org_id = org_id_param(&ctx)?;
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+
+    OrgService::new()
+        .require_owner_or_admin(&db, &org_id, &user_ctx.user_id, FORBIDDEN_MSG)
+        .await?;
+
+    let (from_pattern, to_pattern) = parse_patterns(&mut req).await?;
+
+    let result = RewriteRuleService::new()
+        .dry_run(&db, &org_id, &from_pattern, &to_pattern)
+        .await?;
+
+    Ok(Response::from_json(&result)?)
+}
+
+pub async fn handle_delete_rewrite_rule(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_delete(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_delete(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+    let org_id = org_id_param(&ctx)?;
+    let rule_id = ctx
+        .param("rule_id")
+        .ok_or_else(|| AppError::BadRequest("Missing rule id".to_string()))?
+        .to_string();
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+
+    OrgService::new()
+        .require_owner_or_admin(&db, &org_id, &user_ctx.user_id, FORBIDDEN_MSG)
+        .await?;
+
+    let kv = ctx.kv("URL_MAPPINGS")?;
+    RewriteRuleService::new()
+        .delete_rule(&db, &kv, &org_id, &rule_id)
+        .await?;
+
+    Ok(Response::from_json(
+        &serde_json::json!({ "deleted": true }),
+    )?)
+}
+
+fn org_id_param(ctx: &RouteContext<()>) -> Result<String, AppError> {
+    ctx.param("id")
+        .map(|s| s.to_string())
+        .ok_or_else(|| AppError::BadRequest("Missing org id".to_string()))
+}
+
+async fn parse_patterns(req: &mut Request) -> Result<(String, String), AppError> {
+    let body: serde_json::Value = req
+        .json()
+        .await
+        .map_err(|_| AppError::BadRequest("Invalid JSON body".to_string()))?;
+
+    let from_pattern = body["from_pattern"]
+        .as_str()
+        .ok_or_else(|| AppError::BadRequest("from_pattern is required".to_string()))?;
+    let to_pattern = body["to_pattern"]
+        .as_str()
+        .ok_or_else(|| AppError::BadRequest("to_pattern is required".to_string()))?;
+
+    Ok((from_pattern.to_string(), to_pattern.to_string()))
+}
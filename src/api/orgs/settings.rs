@@ -2,7 +2,13 @@
 ///
 /// GET  /api/orgs/{id}/settings - Get org settings
 /// PATCH /api/orgs/{id}/settings - Update org settings
+/// POST /api/orgs/{id}/utm-signing-secret - Rotate the UTM signing secret
 use crate::auth;
+use crate::models::link::{LINK_SORT_OPTIONS, REDIRECT_TYPE_OPTIONS};
+use crate::models::org_redirect_config::{
+    validate_analytics_header_fields, validate_interstitial_delay, validate_trusted_domains,
+};
+use crate::models::organization::{validate_invite_message, validate_invite_subject};
 use crate::services::OrgService;
 use crate::utils::AppError;
 use worker::d1::D1Database;
@@ -13,7 +19,7 @@ use worker::*;
     path = "/api/orgs/{id}/settings",
     tag = "Organizations",
     summary = "Get org settings",
-    description = "Returns organization-level settings (forward_query_params, exclude_ambiguous_chars). The forward_query_params setting is only available on Pro+ tiers",
+    description = "Returns organization-level settings (forward_query_params, exclude_ambiguous_chars, interstitial_delay_seconds, default_link_sort, default_redirect_type, public_sitemap, crawler_preview, untrusted_interstitial, trusted_domains, analytics_header_fields, invite_subject, invite_message, org_link_quota). The forward_query_params setting is only available on Pro+ tiers",
     params(
         ("id" = String, Path, description = "Organization ID"),
     ),
@@ -51,7 +57,7 @@ async fn inner_get_org_settings(req: Request, ctx: RouteContext<()>) -> Result<R
     path = "/api/orgs/{id}/settings",
     tag = "Organizations",
     summary = "Update org settings",
-    description = "Updates organization-level settings. Omitted fields are unchanged. Enabling forward_query_params requires Pro+ tier. Caller must be owner or admin",
+    description = "Updates organization-level settings. Omitted fields are unchanged. Enabling forward_query_params requires Pro+ tier. interstitial_delay_seconds (0-10, 0 = off) shows a branded page before redirecting. default_link_sort (created, updated, clicks, title, code) is used by the links list when no sort is given. default_redirect_type (301, 302, 307, 308) applies to new links created without a redirect_type; values other than 301 require Pro+. public_sitemap lists the org's active links in /sitemap.xml on the redirect domain. crawler_preview answers link-preview crawlers (Slack, Twitter, Facebook, ...) with a 200 page of OpenGraph tags for the destination instead of the redirect. untrusted_interstitial shows the interstitial, with an external-site warning, for destinations whose domain is not in trusted_domains (a list of hostnames, subdomains included, at most 100); trusted destinations redirect directly. analytics_header_fields lists request headers (at most 10, e.g. x-tenant) whose values, truncated to 200 characters, are recorded with each click; credential headers such as cookie and authorization are rejected, and an empty list stops recording. invite_subject (max 150 characters, one line) and invite_message (max 1000 characters) customize invitation emails; null or an empty string restores the default. org_link_quota caps the links this org may create per calendar month on top of the billing account's tier limit (null = no org-level cap); only the owner may change it. Caller must be owner or admin",
     params(
         ("id" = String, Path, description = "Organization ID"),
     ),
@@ -100,16 +106,210 @@ async fn inner_update_org_settings(
         })?),
     };
 
-    if forward.is_none() && exclude_ambiguous.is_none() {
+    let interstitial_delay = match body.get("interstitial_delay_seconds") {
+        None => None,
+        Some(v) => {
+            let raw = v.as_i64().ok_or_else(|| {
+                AppError::BadRequest("interstitial_delay_seconds must be an integer".to_string())
+            })?;
+            Some(validate_interstitial_delay(raw).map_err(AppError::BadRequest)?)
+        }
+    };
+
+    let default_link_sort = match body.get("default_link_sort") {
+        None => None,
+        Some(v) => Some(
+            v.as_str()
+                .filter(|s| LINK_SORT_OPTIONS.contains(s))
+                .ok_or_else(|| {
+                    AppError::BadRequest(format!(
+                        "default_link_sort must be one of: {}",
+                        LINK_SORT_OPTIONS.join(", ")
+                    ))
+                })?,
+        ),
+    };
+
+    let default_redirect_type = match body.get("default_redirect_type") {
+        None => None,
+        Some(v) => Some(
+            v.as_str()
+                .filter(|s| REDIRECT_TYPE_OPTIONS.contains(s))
+                .ok_or_else(|| {
+                    AppError::BadRequest(format!(
+                        "default_redirect_type must be one of: {}",
+                        REDIRECT_TYPE_OPTIONS.join(", ")
+                    ))
+                })?,
+        ),
+    };
+
+    let public_sitemap =
+        match body.get("public_sitemap") {
+            None => None,
+            Some(v) => Some(v.as_bool().ok_or_else(|| {
+                AppError::BadRequest("public_sitemap must be a boolean".to_string())
+            })?),
+        };
+
+    let crawler_preview = match body.get("crawler_preview") {
+        None => None,
+        Some(v) => Some(v.as_bool().ok_or_else(|| {
+            AppError::BadRequest("crawler_preview must be a boolean".to_string())
+        })?),
+    };
+
+    let untrusted_interstitial = match body.get("untrusted_interstitial") {
+        None => None,
+        Some(v) => Some(v.as_bool().ok_or_else(|| {
+            AppError::BadRequest("untrusted_interstitial must be a boolean".to_string())
+        })?),
+    };
+
+    let trusted_domains = match body.get("trusted_domains") {
+        None => None,
+        Some(v) => {
+            let raw: Vec<String> = serde_json::from_value(v.clone()).map_err(|_| {
+                AppError::BadRequest("trusted_domains must be an array of strings".to_string())
+            })?;
+            Some(validate_trusted_domains(&raw).map_err(AppError::BadRequest)?)
+        }
+    };
+
+    let analytics_header_fields = match body.get("analytics_header_fields") {
+        None => None,
+        Some(v) => {
+            let raw: Vec<String> = serde_json::from_value(v.clone()).map_err(|_| {
+                AppError::BadRequest(
+                    "analytics_header_fields must be an array of strings".to_string(),
+                )
+            })?;
+            Some(validate_analytics_header_fields(&raw).map_err(AppError::BadRequest)?)
+        }
+    };
+
+    // null or "" restores the default wording
+    let invite_subject = match body.get("invite_subject") {
+        None => None,
+        Some(serde_json::Value::Null) => Some(None),
+        Some(v) => Some(
+            validate_invite_subject(v.as_str().ok_or_else(|| {
+                AppError::BadRequest("invite_subject must be a string".to_string())
+            })?)
+            .map_err(AppError::BadRequest)?,
+        ),
+    };
+    let invite_message = match body.get("invite_message") {
+        None => None,
+        Some(serde_json::Value::Null) => Some(None),
+        Some(v) => Some(
+            validate_invite_message(v.as_str().ok_or_else(|| {
+                AppError::BadRequest("invite_message must be a string".to_string())
+            })?)
+            .map_err(AppError::BadRequest)?,
+        ),
+    };
+
+    // null removes the org-level cap
+    let org_link_quota = match body.get("org_link_quota") {
+        None => None,
+        Some(serde_json::Value::Null) => Some(None),
+        Some(v) => Some(Some(v.as_i64().filter(|q| *q >= 0).ok_or_else(|| {
+            AppError::BadRequest(
+                "org_link_quota must be a non-negative integer or null".to_string(),
+            )
+        })?)),
+    };
+
+    if forward.is_none()
+        && exclude_ambiguous.is_none()
+        && interstitial_delay.is_none()
+        && default_link_sort.is_none()
+        && default_redirect_type.is_none()
+        && public_sitemap.is_none()
+        && crawler_preview.is_none()
+        && untrusted_interstitial.is_none()
+        && trusted_domains.is_none()
+        && analytics_header_fields.is_none()
+        && invite_subject.is_none()
+        && invite_message.is_none()
+        && org_link_quota.is_none()
+    {
         return Err(AppError::BadRequest(
-            "At least one setting (forward_query_params, exclude_ambiguous_chars) is required"
+            "At least one setting (forward_query_params, exclude_ambiguous_chars, interstitial_delay_seconds, default_link_sort, default_redirect_type, public_sitemap, crawler_preview, untrusted_interstitial, trusted_domains, analytics_header_fields, invite_subject, invite_message, org_link_quota) is required"
                 .to_string(),
         ));
     }
 
+    let kv = ctx.kv("URL_MAPPINGS")?;
     let updated = OrgService::new()
-        .update_org_settings(&db, &org_id, &user_ctx.user_id, forward, exclude_ambiguous)
+        .update_org_settings(
+            &db,
+            &kv,
+            &org_id,
+            &user_ctx.user_id,
+            forward,
+            exclude_ambiguous,
+            interstitial_delay,
+            default_link_sort,
+            default_redirect_type,
+            public_sitemap,
+            crawler_preview,
+            untrusted_interstitial,
+            trusted_domains.as_deref(),
+            analytics_header_fields.as_deref(),
+            invite_subject.as_ref().map(|s| s.as_deref()),
+            invite_message.as_ref().map(|m| m.as_deref()),
+            org_link_quota,
+        )
         .await?;
 
     Ok(Response::from_json(&updated)?)
 }
+
+#[utoipa::path(
+    post,
+    path = "/api/orgs/{id}/utm-signing-secret",
+    tag = "Organizations",
+    summary = "Rotate the UTM signing secret",
+    description = "Generates a new secret for signed UTM override tokens and returns it once. Short links accept `?u=<payload>.<signature>`, where the payload is a base64url (unpadded) query string of utm_* pairs and the signature is base64url HMAC-SHA256 of `<short_code>.<payload>` with this secret. Valid tokens add or override the link's UTM parameters; invalid ones are ignored. Rotating invalidates existing tokens. Caller must be owner or admin; requires Pro+",
+    params(
+        ("id" = String, Path, description = "Organization ID"),
+    ),
+    responses(
+        (status = 200, description = "New secret"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Owner or admin required, or Pro+ required"),
+    ),
+    security(("Bearer" = []), ("session_cookie" = []))
+)]
+pub async fn handle_rotate_utm_signing_secret(
+    req: Request,
+    ctx: RouteContext<()>,
+) -> Result<Response> {
+    Ok(inner_rotate_utm_signing_secret(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_rotate_utm_signing_secret(
+    req: Request,
+    ctx: RouteContext<()>,
+) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+
+    let org_id = ctx
+        .param("id")
+        .ok_or_else(|| AppError::BadRequest("Missing org id".to_string()))?
+        .to_string();
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let kv = ctx.kv("URL_MAPPINGS")?;
+    let secret = OrgService::new()
+        .rotate_utm_signing_secret(&db, &kv, &org_id, &user_ctx.user_id)
+        .await?;
+
+    Ok(Response::from_json(
+        &serde_json::json!({ "utm_signing_secret": secret }),
+    )?)
+}
@@ -2,9 +2,11 @@
 ///
 /// Accepts both authenticated and anonymous submissions.
 /// Duplicate reports for the same link + reason + reporter within 24 h are rejected.
+/// Anonymous submissions are also capped per IP by the `reports_per_ip_per_hour` setting.
 use crate::auth;
-use crate::services::ReportService;
-use crate::utils::AppError;
+use crate::middleware::{RateLimitConfig, RateLimitSettings, RateLimiter};
+use crate::services::{ReportService, SettingsService};
+use crate::utils::{AppError, get_client_ip};
 use worker::d1::D1Database;
 use worker::*;
 
@@ -13,13 +15,13 @@ use worker::*;
     path = "/api/reports/links",
     tag = "Reports",
     summary = "Report a link for abuse",
-    description = "Submits an abuse report for a link. Accepts both authenticated and anonymous submissions. Duplicate reports for the same link, reason, and reporter within 24 hours are rejected",
+    description = "Submits an abuse report for a link. Accepts both authenticated and anonymous submissions. Duplicate reports for the same link, reason, and reporter within 24 hours are rejected. Anonymous submissions are limited per IP per hour (the `reports_per_ip_per_hour` setting, 10 by default)",
     responses(
         (status = 200, description = "Report submitted successfully"),
         (status = 400, description = "Missing required fields"),
         (status = 404, description = "Link not found or already removed"),
         (status = 422, description = "Link is already disabled"),
-        (status = 429, description = "Duplicate report within 24 hours"),
+        (status = 429, description = "Duplicate report within 24 hours, or per-IP report limit exceeded"),
     )
 )]
 pub async fn handle_report_link(req: Request, ctx: RouteContext<()>) -> Result<Response> {
@@ -29,6 +31,37 @@ pub async fn handle_report_link(req: Request, ctx: RouteContext<()>) -> Result<R
 }
 
 async fn inner_report_link(mut req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await.ok();
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+
+    // Signed-in reporters are accountable; only anonymous ones are capped per IP
+    if user_ctx.is_none() {
+        let max_per_hour = SettingsService::new()
+            .get_reports_per_ip_per_hour(&db)
+            .await?;
+        if max_per_hour > 0 {
+            let kv = ctx.kv("URL_MAPPINGS")?;
+            let client_ip = get_client_ip(&req);
+            if let Err(err) = RateLimiter::check(
+                &kv,
+                &RateLimiter::ip_key("report", &client_ip),
+                &RateLimitConfig::report_submission(max_per_hour),
+                &RateLimitSettings::from_env(&ctx.env).always_on(),
+                &client_ip,
+            )
+            .await
+            {
+                let mut response = Response::error(err.to_error_response(), 429)?;
+                if let Some(retry_after) = err.retry_after() {
+                    response
+                        .headers_mut()
+                        .set("Retry-After", &retry_after.to_string())?;
+                }
+                return Ok(response);
+            }
+        }
+    }
+
     let body: serde_json::Value = req
         .json()
         .await
@@ -46,16 +79,7 @@ async fn inner_report_link(mut req: Request, ctx: RouteContext<()>) -> Result<Re
 
     let reporter_email = body.get("reporter_email").and_then(|v| v.as_str());
 
-    let (reporter_user_id, reporter_email_opt) = match auth::authenticate_request(&req, &ctx).await
-    {
-        Ok(user_ctx) => (
-            Some(user_ctx.user_id),
-            reporter_email.map(|s| s.to_string()),
-        ),
-        Err(_) => (None, reporter_email.map(|s| s.to_string())),
-    };
-
-    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let reporter_user_id = user_ctx.map(|u| u.user_id);
 
     let report = ReportService::new()
         .submit_link_report(
@@ -63,7 +87,7 @@ async fn inner_report_link(mut req: Request, ctx: RouteContext<()>) -> Result<Re
             link_id,
             reason,
             reporter_user_id.as_deref(),
-            reporter_email_opt.as_deref(),
+            reporter_email,
         )
         .await?;
 
@@ -0,0 +1,200 @@
+/// Crawler handlers for the redirect domain
+///
+/// GET /robots.txt  — the `robots_txt` setting, or a default that disallows
+///                    crawling short codes
+/// GET /sitemap.xml — index of the pages below, for orgs that opted into
+///                    `public_sitemap`
+/// GET /sitemap.xml?page=N — one page of those orgs' active links
+///
+/// On the frontend domain the static robots.txt asset is served first, so
+/// these only answer for the redirect domain and custom domains.
+use crate::repositories::LinkRepository;
+use crate::services::SettingsService;
+use crate::utils::robots::{
+    SITEMAP_PAGE_SIZE, render_robots_txt, render_sitemap_index, render_sitemap_xml,
+    sitemap_page_count,
+};
+use crate::utils::{AppError, now_timestamp};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use worker::d1::D1Database;
+use worker::*;
+
+/// Crawlers refetch these rarely; an hour keeps setting changes visible soon.
+const CRAWLER_CACHE_CONTROL: &str = "public, max-age=3600";
+/// How long an isolate reuses a rendered body before querying D1 again
+const CRAWLER_ISOLATE_TTL_SECS: i64 = 300;
+/// Bodies kept per isolate; the cache is dropped when it grows past this
+const MAX_CACHED_BODIES: usize = 64;
+
+// Workers are single-threaded, so thread_local is safe as a per-isolate cache.
+// Keyed by request origin and path (plus page), valued by (body, rendered at).
+thread_local! {
+    static CACHED_BODIES: RefCell<HashMap<String, (Option<String>, i64)>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Rendered body for `key` if this isolate built it recently. The inner
+/// Option is None when the body was a 404.
+fn cached_body(key: &str, now: i64) -> Option<Option<String>> {
+    CACHED_BODIES.with(|c| {
+        c.borrow()
+            .get(key)
+            .filter(|(_, rendered_at)| now - rendered_at < CRAWLER_ISOLATE_TTL_SECS)
+            .map(|(body, _)| body.clone())
+    })
+}
+
+fn store_body(key: String, body: Option<String>, now: i64) {
+    CACHED_BODIES.with(|c| {
+        let mut cache = c.borrow_mut();
+        if cache.len() >= MAX_CACHED_BODIES {
+            cache.clear();
+        }
+        cache.insert(key, (body, now));
+    });
+}
+
+/// Request origin (`scheme://host[:port]`) and the custom domain it maps to,
+/// or None for the default short domain.
+fn request_domain(req: &Request, ctx: &RouteContext<()>) -> Result<(String, Option<String>)> {
+    let url = req.url()?;
+    let host = url.host_str().unwrap_or("").to_string();
+    let authority = match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.clone(),
+    };
+    let origin = format!("{}://{}", url.scheme(), authority);
+    let custom_domain = (authority != crate::utils::env::get_domain(&ctx.env)).then_some(host);
+    Ok((origin, custom_domain))
+}
+
+fn text_response(body: String, content_type: &str) -> Result<Response> {
+    let mut response = Response::ok(body)?;
+    let headers = response.headers_mut();
+    headers.set("Content-Type", content_type)?;
+    headers.set("Cache-Control", CRAWLER_CACHE_CONTROL)?;
+    Ok(response)
+}
+
+#[utoipa::path(
+    get,
+    path = "/robots.txt",
+    tag = "System",
+    summary = "Get robots.txt for the redirect domain",
+    description = "Returns the instance's `robots_txt` setting, or a default that disallows crawling short codes when it is empty. A `Sitemap:` line is added when orgs on this domain opted into the public sitemap",
+    responses(
+        (status = 200, description = "robots.txt body", content_type = "text/plain"),
+    )
+)]
+pub async fn handle_robots_txt(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_robots_txt(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_robots_txt(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let (origin, custom_domain) = request_domain(&req, &ctx)?;
+    let now = now_timestamp();
+    let cache_key = format!("{}/robots.txt", origin);
+
+    let body = match cached_body(&cache_key, now).flatten() {
+        Some(body) => body,
+        None => {
+            let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+            let configured = SettingsService::new().get_robots_txt(&db).await?;
+            let has_sitemap = !LinkRepository::new()
+                .list_sitemap_entries(&db, custom_domain.as_deref(), now, 1, 0)
+                .await?
+                .is_empty();
+            let sitemap_url = has_sitemap.then(|| format!("{}/sitemap.xml", origin));
+            let body = render_robots_txt(configured.as_deref(), sitemap_url.as_deref());
+            store_body(cache_key, Some(body.clone()), now);
+            body
+        }
+    };
+
+    Ok(text_response(body, "text/plain; charset=utf-8")?)
+}
+
+#[utoipa::path(
+    get,
+    path = "/sitemap.xml",
+    tag = "System",
+    summary = "Get the sitemap for the redirect domain",
+    description = "Without `page`, returns a sitemap index pointing at `/sitemap.xml?page=N`. With `page`, lists up to 1,000 active, unexpired short links on this domain whose org enabled `public_sitemap`. Returns 404 when no org on this domain opted in or the page is past the end",
+    params(
+        ("page" = Option<i64>, Query, description = "Sitemap page, starting at 1"),
+    ),
+    responses(
+        (status = 200, description = "Sitemap index or sitemap XML", content_type = "application/xml"),
+        (status = 400, description = "Invalid page"),
+        (status = 404, description = "No public links on this domain"),
+    )
+)]
+pub async fn handle_sitemap_xml(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner_sitemap_xml(req, ctx)
+        .await
+        .unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner_sitemap_xml(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let (origin, custom_domain) = request_domain(&req, &ctx)?;
+    let page = match req.url()?.query_pairs().find(|(k, _)| k == "page") {
+        Some((_, v)) => Some(
+            v.parse::<i64>()
+                .ok()
+                .filter(|p| *p >= 1)
+                .ok_or_else(|| AppError::BadRequest("Invalid page".to_string()))?,
+        ),
+        None => None,
+    };
+    let now = now_timestamp();
+    let cache_key = match page {
+        Some(page) => format!("{}/sitemap.xml?page={}", origin, page),
+        None => format!("{}/sitemap.xml", origin),
+    };
+
+    let body = match cached_body(&cache_key, now) {
+        Some(body) => body,
+        None => {
+            let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+            let repo = LinkRepository::new();
+            let body = match page {
+                None => {
+                    let total = repo
+                        .count_sitemap_entries(&db, custom_domain.as_deref(), now)
+                        .await?;
+                    let pages = sitemap_page_count(total);
+                    (pages > 0).then(|| render_sitemap_index(&origin, pages))
+                }
+                Some(page) => {
+                    let entries = repo
+                        .list_sitemap_entries(
+                            &db,
+                            custom_domain.as_deref(),
+                            now,
+                            SITEMAP_PAGE_SIZE,
+                            (page - 1) * SITEMAP_PAGE_SIZE,
+                        )
+                        .await?;
+                    (!entries.is_empty()).then(|| {
+                        render_sitemap_xml(
+                            &origin,
+                            entries
+                                .iter()
+                                .map(|e| (e.short_code.as_str(), e.last_modified)),
+                        )
+                    })
+                }
+            };
+            store_body(cache_key, body.clone(), now);
+            body
+        }
+    };
+
+    match body {
+        Some(body) => Ok(text_response(body, "application/xml; charset=utf-8")?),
+        None => Err(AppError::NotFound("No sitemap for this domain".to_string())),
+    }
+}
@@ -10,11 +10,111 @@ use worker::*;
 // Workers are single-threaded, so thread_local is safe and avoids passing Context through the Router.
 thread_local! {
     pub static DEFERRED_ANALYTICS: RefCell<Option<Pin<Box<dyn Future<Output = ()> + 'static>>>> = RefCell::new(None);
+    /// Other best-effort work to run after the response, e.g. bookkeeping writes.
+    pub static DEFERRED_TASKS: RefCell<Vec<Pin<Box<dyn Future<Output = ()> + 'static>>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Queue a future to run via `ctx.wait_until()` once the response is sent.
+pub fn defer_task(task: impl Future<Output = ()> + 'static) {
+    DEFERRED_TASKS.with(|cell| cell.borrow_mut().push(Box::pin(task)));
+}
+
+/// Order in which methods are listed in the `Allow` header.
+const ALLOW_METHOD_ORDER: &[&str] = &["GET", "HEAD", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"];
+
+/// `Router` wrapper that records each route's method and pattern, so a 405
+/// can name the methods the path does support in its `Allow` header.
+struct RouteTable<'a> {
+    router: Router<'a, ()>,
+    routes: Vec<(&'static str, String)>,
+}
+
+macro_rules! recorded_route {
+    ($($name:ident => $method:literal),* $(,)?) => {
+        $(
+            fn $name<T>(
+                mut self,
+                pattern: &str,
+                func: impl Fn(Request, RouteContext<()>) -> T + 'a,
+            ) -> Self
+            where
+                T: Future<Output = Result<Response>> + 'a,
+            {
+                self.routes.push(($method, pattern.to_string()));
+                self.router = self.router.$name(pattern, func);
+                self
+            }
+        )*
+    };
+}
+
+impl<'a> RouteTable<'a> {
+    fn new() -> Self {
+        Self {
+            router: Router::new(),
+            routes: Vec::new(),
+        }
+    }
+
+    recorded_route! {
+        get_async => "GET",
+        head_async => "HEAD",
+        post_async => "POST",
+        put_async => "PUT",
+        patch_async => "PATCH",
+        delete_async => "DELETE",
+    }
+
+    /// Run the router. The Worker router answers a known path with an
+    /// unregistered method by a bare 405; replace it with a JSON error and an
+    /// `Allow` header.
+    async fn run(self, req: Request, env: Env) -> Result<Response> {
+        let Self { router, routes } = self;
+        let path = req.path();
+        let response = router.run(req, env).await?;
+        if response.status_code() != 405 {
+            return Ok(response);
+        }
+
+        let allow = allowed_methods(&routes, &path).join(", ");
+        let mut response = Response::from_json(&serde_json::json!({
+            "message": format!("Method not allowed. Allowed methods: {}", allow)
+        }))?
+        .with_status(405);
+        response.headers_mut().set("Allow", &allow)?;
+        Ok(response)
+    }
+}
+
+/// Whether a route pattern (`/api/links/:id`) matches a request path.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let pattern_segments: Vec<&str> = pattern.split('/').collect();
+    let path_segments: Vec<&str> = path.split('/').collect();
+    pattern_segments.len() == path_segments.len()
+        && pattern_segments
+            .iter()
+            .zip(&path_segments)
+            .all(|(p, s)| p == s || (p.starts_with(':') && !s.is_empty()))
+}
+
+/// Methods registered for `path`, in `ALLOW_METHOD_ORDER`. OPTIONS is listed
+/// for API paths since CORS preflights are answered before routing.
+fn allowed_methods(routes: &[(&'static str, String)], path: &str) -> Vec<&'static str> {
+    ALLOW_METHOD_ORDER
+        .iter()
+        .copied()
+        .filter(|method| {
+            (*method == "OPTIONS" && path.starts_with("/api/"))
+                || routes
+                    .iter()
+                    .any(|(m, pattern)| m == method && pattern_matches(pattern, path))
+        })
+        .collect()
 }
 
 /// Register all routes and run the router against the incoming request.
 pub async fn run(req: Request, env: Env, is_frontend_domain: bool) -> Result<Response> {
-    let router = Router::new();
+    let router = RouteTable::new();
 
     router
         // Notification preference routes (must come before catch-all /:code)
@@ -26,6 +126,9 @@ pub async fn run(req: Request, env: Env, is_frontend_domain: bool) -> Result<Res
             "/api/notifications/preferences",
             crate::api::notifications::handle_update_notification_preferences,
         )
+        // Crawler files for the redirect domain (must come before catch-all /:code)
+        .get_async("/robots.txt", crate::api::robots::handle_robots_txt)
+        .get_async("/sitemap.xml", crate::api::robots::handle_sitemap_xml)
         // Public redirect routes - must come first to catch short codes
         .get_async("/:code", move |req, route_ctx| async move {
             let code = route_ctx
@@ -105,6 +208,10 @@ pub async fn run(req: Request, env: Env, is_frontend_domain: bool) -> Result<Res
             "/api/auth/providers",
             crate::api::auth::providers::handle_list_auth_providers,
         )
+        .get_async(
+            "/api/auth/config",
+            crate::api::auth::config::handle_get_auth_config,
+        )
         .get_async(
             "/api/auth/github",
             crate::api::auth::oauth::handle_github_login,
@@ -130,18 +237,89 @@ pub async fn run(req: Request, env: Env, is_frontend_domain: bool) -> Result<Res
         )
         .post_async("/api/auth/logout", crate::api::auth::session::handle_logout)
         .get_async("/api/usage", crate::api::analytics::usage::handle_get_usage)
+        .get_async(
+            "/api/dashboard",
+            crate::api::dashboard::handle_get_dashboard,
+        )
         .post_async("/api/links", crate::api::links::handle_create_link)
         .get_async("/api/links", crate::api::links::handle_list_links)
         .get_async("/api/links/export", crate::api::links::handle_export_links)
+        .get_async(
+            "/api/links/check-code",
+            crate::api::links::handle_check_code,
+        )
+        .get_async(
+            "/api/links/check-destination",
+            crate::api::links::handle_check_destination,
+        )
         .post_async("/api/links/import", crate::api::links::handle_import_links)
+        .post_async(
+            "/api/links/bulk",
+            crate::api::links::handle_bulk_create_links,
+        )
+        .post_async(
+            "/api/links/bulk-delete",
+            crate::api::links::handle_bulk_delete_links,
+        )
+        .post_async(
+            "/api/links/anonymous",
+            crate::api::links::handle_create_anonymous_link,
+        )
+        .post_async("/api/links/claim", crate::api::links::handle_claim_link)
+        .post_async("/api/links/bulk-tags", crate::api::tags::handle_bulk_tags)
+        .get_async(
+            "/api/links/suggest-tags",
+            crate::api::tags::handle_suggest_tags,
+        )
+        .get_async(
+            "/api/links/exists/:code",
+            crate::api::links::handle_link_exists,
+        )
         .get_async(
             "/api/links/by-code/:code",
             crate::api::links::handle_get_link_by_code,
         )
+        .post_async(
+            "/api/links/by-codes",
+            crate::api::links::handle_get_links_by_codes,
+        )
+        .get_async(
+            "/api/public/links/:code",
+            crate::api::links::handle_get_public_link,
+        )
         .get_async(
             "/api/links/:id/analytics",
             crate::api::analytics::link::handle_get_link_analytics,
         )
+        .get_async(
+            "/api/links/:id/analytics/export",
+            crate::api::analytics::export::handle_export_link_analytics,
+        )
+        .get_async(
+            "/api/links/:id/events",
+            crate::api::analytics::events::handle_get_link_events,
+        )
+        .get_async(
+            "/api/links/:id/export",
+            crate::api::links::handle_export_link,
+        )
+        .get_async("/api/links/:id/qr", crate::api::links::handle_get_link_qr)
+        .get_async(
+            "/api/links/:id/aliases",
+            crate::api::links::handle_list_link_aliases,
+        )
+        .post_async(
+            "/api/links/:id/aliases",
+            crate::api::links::handle_create_link_alias,
+        )
+        .delete_async(
+            "/api/links/:id/aliases/:code",
+            crate::api::links::handle_delete_link_alias,
+        )
+        .post_async(
+            "/api/links/:id/reset-clicks",
+            crate::api::links::handle_reset_link_clicks,
+        )
         .get_async("/api/links/:id", crate::api::links::handle_get_link)
         .put_async("/api/links/:id", crate::api::links::handle_update_link)
         .delete_async("/api/links/:id", crate::api::links::handle_delete_link)
@@ -166,6 +344,22 @@ pub async fn run(req: Request, env: Env, is_frontend_domain: bool) -> Result<Res
             "/api/admin/links/:id/sync-kv",
             crate::api::links::handle_admin_sync_link_kv,
         )
+        .get_async(
+            "/api/admin/deleted-links/:id/analytics",
+            crate::api::links::handle_admin_get_deleted_link_analytics,
+        )
+        .get_async(
+            "/api/admin/analytics/by-status",
+            crate::api::admin::analytics::handle_admin_clicks_by_status,
+        )
+        .get_async(
+            "/api/admin/short-code/preview",
+            crate::api::admin::short_codes::handle_admin_preview_short_codes,
+        )
+        .get_async(
+            "/api/admin/audit/kv-consistency",
+            crate::api::admin::audit::handle_admin_audit_kv_consistency,
+        )
         .post_async(
             "/api/admin/blacklist",
             crate::api::admin::blacklist::handle_admin_block_destination,
@@ -174,10 +368,42 @@ pub async fn run(req: Request, env: Env, is_frontend_domain: bool) -> Result<Res
             "/api/admin/blacklist",
             crate::api::admin::blacklist::handle_admin_get_blacklist,
         )
+        .get_async(
+            "/api/admin/blacklist/preview",
+            crate::api::admin::blacklist::handle_admin_preview_blacklist,
+        )
         .delete_async(
             "/api/admin/blacklist/:id",
             crate::api::admin::blacklist::handle_admin_remove_blacklist,
         )
+        .get_async(
+            "/api/admin/code-words",
+            crate::api::admin::code_words::handle_admin_list_code_words,
+        )
+        .post_async(
+            "/api/admin/code-words",
+            crate::api::admin::code_words::handle_admin_add_code_word,
+        )
+        .delete_async(
+            "/api/admin/code-words/:id",
+            crate::api::admin::code_words::handle_admin_remove_code_word,
+        )
+        .get_async(
+            "/api/admin/config/export",
+            crate::api::admin::config::handle_admin_export_config,
+        )
+        .post_async(
+            "/api/admin/config/import",
+            crate::api::admin::config::handle_admin_import_config,
+        )
+        .get_async(
+            "/api/admin/users/:id/links",
+            crate::api::admin::users::handle_admin_list_user_links,
+        )
+        .post_async(
+            "/api/admin/users/merge",
+            crate::api::admin::users::handle_admin_merge_users,
+        )
         .put_async(
             "/api/admin/users/:id/suspend",
             crate::api::admin::users::handle_admin_suspend_user,
@@ -312,6 +538,10 @@ pub async fn run(req: Request, env: Env, is_frontend_domain: bool) -> Result<Res
             "/api/tags/analytics",
             crate::api::tags::handle_get_tag_analytics,
         )
+        .get_async(
+            "/api/tags/autocomplete",
+            crate::api::tags::handle_autocomplete_tags,
+        )
         // Public settings route
         .get_async(
             "/api/settings",
@@ -325,6 +555,7 @@ pub async fn run(req: Request, env: Env, is_frontend_domain: bool) -> Result<Res
             "/api/settings/api-keys",
             crate::api::keys::handle_list_api_keys,
         )
+        .get_async("/api/keys", crate::api::keys::handle_list_keys)
         .delete_async(
             "/api/settings/api-keys/:id",
             crate::api::keys::handle_revoke_api_key,
@@ -354,6 +585,10 @@ pub async fn run(req: Request, env: Env, is_frontend_domain: bool) -> Result<Res
         .get_async("/api/orgs", crate::api::orgs::handle_list_user_orgs)
         .post_async("/api/orgs", crate::api::orgs::handle_create_org)
         .get_async("/api/orgs/:id", crate::api::orgs::handle_get_org)
+        .get_async(
+            "/api/orgs/:id/limits",
+            crate::api::orgs::handle_get_org_limits,
+        )
         .patch_async("/api/orgs/:id", crate::api::orgs::handle_update_org)
         .get_async(
             "/api/orgs/:id/settings",
@@ -363,7 +598,27 @@ pub async fn run(req: Request, env: Env, is_frontend_domain: bool) -> Result<Res
             "/api/orgs/:id/settings",
             crate::api::orgs::handle_update_org_settings,
         )
+        .post_async(
+            "/api/orgs/:id/utm-signing-secret",
+            crate::api::orgs::handle_rotate_utm_signing_secret,
+        )
         .delete_async("/api/orgs/:id", crate::api::orgs::handle_delete_org)
+        .get_async(
+            "/api/orgs/:id/rewrite-rules",
+            crate::api::orgs::handle_list_rewrite_rules,
+        )
+        .post_async(
+            "/api/orgs/:id/rewrite-rules",
+            crate::api::orgs::handle_create_rewrite_rule,
+        )
+        .post_async(
+            "/api/orgs/:id/rewrite-rules/dry-run",
+            crate::api::orgs::handle_dry_run_rewrite_rule,
+        )
+        .delete_async(
+            "/api/orgs/:id/rewrite-rules/:rule_id",
+            crate::api::orgs::handle_delete_rewrite_rule,
+        )
         .delete_async(
             "/api/orgs/:id/members/:user_id",
             crate::api::orgs::handle_remove_member,
@@ -372,6 +627,10 @@ pub async fn run(req: Request, env: Env, is_frontend_domain: bool) -> Result<Res
             "/api/orgs/:id/members/:user_id/role",
             crate::api::orgs::handle_update_member_role,
         )
+        .get_async(
+            "/api/orgs/:id/invitations",
+            crate::api::orgs::handle_list_invitations,
+        )
         .post_async(
             "/api/orgs/:id/invitations",
             crate::api::orgs::handle_create_invitation,
@@ -459,13 +718,27 @@ pub async fn run(req: Request, env: Env, is_frontend_domain: bool) -> Result<Res
             "/api/analytics/org",
             crate::api::analytics::org::handle_get_org_analytics,
         )
+        .get_async(
+            "/api/orgs/:id/analytics/export",
+            crate::api::analytics::export::handle_export_org_analytics,
+        )
+        .get_async(
+            "/api/orgs/:id/analytics/top-countries",
+            crate::api::analytics::org::handle_get_org_top_countries,
+        )
+        .get_async(
+            "/api/orgs/:id/analytics/header-fields",
+            crate::api::analytics::org::handle_get_org_header_field_breakdown,
+        )
         // Title fetch route (public, can be called by anyone)
         .post_async("/api/fetch-title", crate::api::title_fetch::fetch_title)
-        // Root redirect: redirect to frontend (e.g., rush.mn/ → rushomon.cc/)
+        // Root redirect: redirect to frontend (e.g., rush.mn/ → rushomon.cc/), or to the
+        // per-domain target from get_root_redirect_target. The frontend domain only
+        // redirects when configured to; otherwise its 404 falls through to the SPA.
         // Skip redirect if request comes via the fallback domain (used for Cloudflare for SaaS
         // custom hostname routing) — the fallback domain is internal infrastructure and should
         // not redirect, otherwise Cloudflare for SaaS will see a 301 and fail with a 522 error.
-        .get_async("/", |req, ctx| async move {
+        .get_async("/", move |req, ctx| async move {
             let fallback_domain = crate::utils::get_fallback_domain(&ctx.env);
             let request_host = req
                 .url()
@@ -475,9 +748,52 @@ pub async fn run(req: Request, env: Env, is_frontend_domain: bool) -> Result<Res
             if request_host == fallback_domain {
                 return Response::error("Not found", 404);
             }
-            let url = Url::parse(&crate::utils::get_frontend_url(&ctx.env))?;
-            Response::redirect_with_status(url, 301)
+            let Some(target) =
+                crate::utils::env::get_root_redirect_target(&ctx.env, is_frontend_domain)
+            else {
+                return Response::error("Not found", 404);
+            };
+            Response::redirect_with_status(Url::parse(&target)?, 301)
         })
         .run(req, env)
         .await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn routes() -> Vec<(&'static str, String)> {
+        vec![
+            ("GET", "/:code".to_string()),
+            ("HEAD", "/:code".to_string()),
+            ("GET", "/api/links".to_string()),
+            ("POST", "/api/links".to_string()),
+            ("GET", "/api/links/:id".to_string()),
+            ("PUT", "/api/links/:id".to_string()),
+            ("DELETE", "/api/links/:id".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_pattern_matches() {
+        assert!(pattern_matches("/api/links/:id", "/api/links/abc"));
+        assert!(!pattern_matches("/api/links/:id", "/api/links/"));
+        assert!(!pattern_matches("/api/links/:id", "/api/links/abc/tags"));
+        assert!(!pattern_matches("/api/links", "/api/tags"));
+    }
+
+    #[test]
+    fn test_allowed_methods_lists_registered_methods() {
+        let routes = routes();
+        assert_eq!(
+            allowed_methods(&routes, "/api/links"),
+            vec!["GET", "POST", "OPTIONS"]
+        );
+        assert_eq!(
+            allowed_methods(&routes, "/api/links/abc"),
+            vec!["GET", "PUT", "DELETE", "OPTIONS"]
+        );
+        assert_eq!(allowed_methods(&routes, "/abc123"), vec!["GET", "HEAD"]);
+    }
+}
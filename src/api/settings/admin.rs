@@ -89,6 +89,13 @@ async fn inner_update(mut req: Request, ctx: RouteContext<()>) -> Result<Respons
 
     let settings = settings_service.update_setting(&db, &key, &value).await?;
 
+    if key.starts_with("maintenance_") {
+        let kv = ctx.kv("URL_MAPPINGS")?;
+        settings_service
+            .sync_maintenance_cache(&kv, &settings)
+            .await?;
+    }
+
     let settings_map: serde_json::Map<String, serde_json::Value> = settings
         .into_iter()
         .map(|(k, v)| (k, serde_json::Value::String(v)))
@@ -0,0 +1,56 @@
+/// GET /api/tags/autocomplete?q=&limit=
+///
+/// Prefix search over the organization's tags for the tag input.
+use crate::auth;
+use crate::services::TagService;
+use crate::services::tag_service::{DEFAULT_TAG_AUTOCOMPLETE_LIMIT, MAX_TAG_AUTOCOMPLETE_LIMIT};
+use crate::utils::{AppError, QueryParams};
+use worker::d1::D1Database;
+use worker::*;
+
+#[utoipa::path(
+    get,
+    path = "/api/tags/autocomplete",
+    tag = "Tags",
+    summary = "Autocomplete tags",
+    description = "Returns the organization's tags whose name starts with `q` (case-insensitive), most used first. Only tags currently on at least one link are returned.",
+    params(
+        ("q" = Option<String>, Query, description = "Tag name prefix (empty matches every tag)"),
+        ("limit" = Option<i64>, Query, description = "Maximum number of tags (default: 10, max: 50)"),
+    ),
+    responses(
+        (status = 200, description = "Matching tags with usage counts"),
+        (status = 400, description = "Prefix too long"),
+        (status = 401, description = "Unauthorized"),
+    ),
+    security(
+        ("Bearer" = []),
+        ("session_cookie" = [])
+    )
+)]
+pub async fn handle_autocomplete_tags(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner(req, ctx).await.unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+
+    let params = QueryParams::from_request(&req)?;
+    let prefix = params.get("q").unwrap_or_default();
+    if prefix.chars().count() > 100 {
+        return Err(AppError::BadRequest(
+            "q must be at most 100 characters".to_string(),
+        ));
+    }
+    let limit = params
+        .get_i64("limit")
+        .unwrap_or(DEFAULT_TAG_AUTOCOMPLETE_LIMIT)
+        .clamp(1, MAX_TAG_AUTOCOMPLETE_LIMIT);
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let tags = TagService::new()
+        .autocomplete_tags(&db, &user_ctx.org_id, &prefix, limit)
+        .await?;
+
+    Ok(Response::from_json(&tags)?)
+}
@@ -0,0 +1,87 @@
+/// POST /api/links/bulk-tags
+///
+/// Add and/or remove tags across many of the organization's links at once.
+use crate::auth;
+use crate::repositories::OrgRepository;
+use crate::services::tag_service::{BulkTagsRequest, MAX_BULK_TAG_LINKS};
+use crate::services::{LinkService, OrgService, TagService};
+use crate::utils::{AppError, validate_and_normalize_tags};
+use worker::d1::D1Database;
+use worker::*;
+
+#[utoipa::path(
+    post,
+    path = "/api/links/bulk-tags",
+    tag = "Tags",
+    summary = "Bulk add/remove tags",
+    description = "Adds and removes tags across up to 100 of the organization's links in one request. IDs that are not links of the organization are ignored and reported in not_found. Fails without changes if any link would exceed 20 tags. Returns per-link added/removed counts.",
+    request_body = BulkTagsRequest,
+    responses(
+        (status = 200, description = "Per-link results"),
+        (status = 400, description = "Invalid request, invalid tag, or per-link tag cap exceeded"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Tag limit for the billing account reached"),
+    ),
+    security(
+        ("Bearer" = []),
+        ("session_cookie" = [])
+    )
+)]
+pub async fn handle_bulk_tags(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner(req, ctx).await.unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner(mut req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+
+    let request: BulkTagsRequest = req
+        .json()
+        .await
+        .map_err(|_| AppError::BadRequest("Invalid request body".to_string()))?;
+
+    if request.ids.is_empty() {
+        return Err(AppError::BadRequest(
+            "At least one link id is required".to_string(),
+        ));
+    }
+    if request.ids.len() > MAX_BULK_TAG_LINKS {
+        return Err(AppError::BadRequest(format!(
+            "At most {} links per request",
+            MAX_BULK_TAG_LINKS
+        )));
+    }
+    let add = validate_and_normalize_tags(&request.add)
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+    let remove = validate_and_normalize_tags(&request.remove)
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+    if add.is_empty() && remove.is_empty() {
+        return Err(AppError::BadRequest(
+            "Nothing to do: provide tags to add or remove".to_string(),
+        ));
+    }
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+
+    // Adding tags may create new ones, which count towards the tier's tag limit
+    if !add.is_empty()
+        && let Some(org) = OrgRepository::new()
+            .get_by_id(&db, &user_ctx.org_id)
+            .await?
+        && let Some(ref billing_account_id) = org.billing_account_id
+        && let Some(max_tags) = OrgService::new()
+            .get_org_tier(&db, &org)
+            .await
+            .limits()
+            .max_tags
+    {
+        LinkService::new()
+            .check_tag_limit(&db, billing_account_id, &add, max_tags)
+            .await?;
+    }
+
+    let result = TagService::new()
+        .bulk_update_link_tags(&db, &user_ctx.org_id, &request.ids, &add, &remove)
+        .await?;
+
+    Ok(Response::from_json(&result)?)
+}
@@ -1,16 +1,27 @@
 mod analytics;
 /// Tag API handlers
+mod autocomplete;
+mod bulk;
 mod create;
 mod delete;
 mod list;
 mod merge;
 mod rename;
+mod suggest;
 
 // Re-export handlers and utoipa path helpers
 #[allow(unused_imports)]
 pub use analytics::__path_handle_get_tag_analytics;
 pub use analytics::handle_get_tag_analytics;
 
+#[allow(unused_imports)]
+pub use autocomplete::__path_handle_autocomplete_tags;
+pub use autocomplete::handle_autocomplete_tags;
+
+#[allow(unused_imports)]
+pub use bulk::__path_handle_bulk_tags;
+pub use bulk::handle_bulk_tags;
+
 #[allow(unused_imports)]
 pub use create::__path_handle_create_tag;
 pub use create::handle_create_tag;
@@ -30,3 +41,7 @@ pub use merge::handle_merge_tags;
 #[allow(unused_imports)]
 pub use rename::__path_handle_rename_org_tag;
 pub use rename::handle_rename_org_tag;
+
+#[allow(unused_imports)]
+pub use suggest::__path_handle_suggest_tags;
+pub use suggest::handle_suggest_tags;
@@ -0,0 +1,49 @@
+/// GET /api/links/suggest-tags?url=
+///
+/// Suggest tags for a destination URL based on the organization's existing
+/// tags on the same domain and the global domain -> tag mapping.
+use crate::auth;
+use crate::services::TagService;
+use crate::utils::{AppError, QueryParams, validate_url};
+use worker::d1::D1Database;
+use worker::*;
+
+#[utoipa::path(
+    get,
+    path = "/api/links/suggest-tags",
+    tag = "Tags",
+    summary = "Suggest tags for a destination",
+    description = "Returns tag suggestions for a destination URL. Tags the organization already uses on links to the same domain come first (most used first), followed by globally configured tags for well-known domains.",
+    params(
+        ("url" = String, Query, description = "Destination URL to suggest tags for"),
+    ),
+    responses(
+        (status = 200, description = "Suggested tags for the destination domain"),
+        (status = 400, description = "Missing or invalid URL"),
+        (status = 401, description = "Unauthorized"),
+    ),
+    security(
+        ("Bearer" = []),
+        ("session_cookie" = [])
+    )
+)]
+pub async fn handle_suggest_tags(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    Ok(inner(req, ctx).await.unwrap_or_else(|e| e.into_response()))
+}
+
+async fn inner(req: Request, ctx: RouteContext<()>) -> Result<Response, AppError> {
+    let user_ctx = auth::authenticate_request(&req, &ctx).await?;
+
+    let url = QueryParams::from_request(&req)?
+        .get("url")
+        .filter(|u| !u.trim().is_empty())
+        .ok_or_else(|| AppError::BadRequest("Missing url parameter".to_string()))?;
+    let normalized_url = validate_url(url.trim()).map_err(AppError::BadRequest)?;
+
+    let db = ctx.env.get_binding::<D1Database>("rushomon")?;
+    let suggestions = TagService::new()
+        .suggest_tags(&db, &user_ctx.org_id, &normalized_url)
+        .await?;
+
+    Ok(Response::from_json(&suggestions)?)
+}
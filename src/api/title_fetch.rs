@@ -1,7 +1,8 @@
-use worker::{Env, Fetch, Request, Response, Result, RouteContext, console_log};
+use crate::utils::safe_fetch::{SafeFetchError, SafeFetchPolicy, safe_fetch};
+use worker::{Request, Response, Result, RouteContext, console_log};
 
 /// Fetch the title of a web page from a given URL
-pub async fn fetch_title(mut req: Request, ctx: RouteContext<()>) -> Result<Response> {
+pub async fn fetch_title(mut req: Request, _ctx: RouteContext<()>) -> Result<Response> {
     // Only allow POST requests
     if req.method() != worker::Method::Post {
         return Response::error("Method not allowed", 405);
@@ -20,7 +21,7 @@ pub async fn fetch_title(mut req: Request, ctx: RouteContext<()>) -> Result<Resp
     }
 
     // Fetch the URL
-    let fetch_result = fetch_url(url, &ctx.env).await;
+    let fetch_result = fetch_url(url).await;
 
     match fetch_result {
         Ok(html) => {
@@ -52,9 +53,8 @@ pub async fn fetch_title(mut req: Request, ctx: RouteContext<()>) -> Result<Resp
     }
 }
 
-/// Fetch a URL using worker's fetch
-async fn fetch_url(url: &str, _env: &Env) -> Result<String> {
-    // Use the global fetch available in Cloudflare Workers
+/// Fetch a URL through the SSRF-safe fetcher
+async fn fetch_url(url: &str) -> std::result::Result<String, SafeFetchError> {
     let headers = worker::Headers::new();
     headers.set(
         "Accept",
@@ -65,21 +65,13 @@ async fn fetch_url(url: &str, _env: &Env) -> Result<String> {
         "Mozilla/5.0 (compatible; Rushomon-TitleFetcher/1.0)",
     )?;
 
-    let request = worker::Request::new_with_init(
-        url,
-        worker::RequestInit::new()
-            .with_method(worker::Method::Get)
-            .with_headers(headers),
-    )?;
-
-    // Use the fetch function from the worker crate
-    let mut resp = Fetch::Request(request).send().await?;
+    let resp = safe_fetch(url, &headers, &SafeFetchPolicy::default()).await?;
 
-    if resp.status_code() != 200 {
-        return Err(worker::Error::from(format!("HTTP {}", resp.status_code())));
+    if resp.status != 200 {
+        return Err(SafeFetchError::Fetch(format!("HTTP {}", resp.status)));
     }
 
-    resp.text().await
+    Ok(resp.text())
 }
 
 /// Extract title from HTML content
@@ -1,10 +1,15 @@
-use crate::auth::session::{UserContext, get_session, parse_cookie_header, validate_jwt};
+use crate::auth::session::{
+    JwtClaimsConfig, UserContext, get_session, parse_cookie_header, validate_jwt,
+};
+use crate::models::api_key::{
+    API_KEY_LAST_USED_DEBOUNCE_SECS, required_scope, scopes_from_column, should_record_key_use,
+};
 use crate::repositories::{ApiKeyRepository, UserRepository};
 use crate::utils::time::now_timestamp;
 use hex; // Add hex crate for formatting
 use sha2::{Digest, Sha256};
 use worker::{D1Database, console_log};
-use worker::{Request, Response, RouteContext}; // Assuming you have a time util, or use chrono
+use worker::{Method, Request, Response, RouteContext}; // Assuming you have a time util, or use chrono
 
 /// Authentication error that can be converted to an HTTP response
 pub enum AuthError {
@@ -215,12 +220,35 @@ pub async fn authenticate_request(
             ));
         };
 
-        // 8. Update the 'last_used_at' timestamp
-        if let Err(e) = api_key_repo
-            .update_last_used(&db, &api_key_with_tier.id, now_timestamp())
-            .await
-        {
-            console_log!("Failed to update API key last_used_at: {:?}", e);
+        // 7b. Check the key's scopes cover this endpoint
+        let is_read = matches!(req.method(), Method::Get | Method::Head);
+        if let Some(required) = required_scope(is_read, &req.path()) {
+            let scopes = scopes_from_column(api_key_with_tier.scopes.as_deref());
+            if !scopes.iter().any(|scope| scope.grants(required)) {
+                return Err(AuthError::Forbidden(format!(
+                    "insufficient_scope: this API key needs the '{}' scope for this endpoint",
+                    required.as_str()
+                )));
+            }
+        }
+
+        // 8. Update the 'last_used_at' timestamp after the response is sent,
+        // at most once per debounce window
+        let now = now_timestamp();
+        if should_record_key_use(api_key_with_tier.last_used_at, now) {
+            let env = ctx.env.clone();
+            let key_id = api_key_with_tier.id.clone();
+            crate::api::router::defer_task(async move {
+                let Ok(db) = env.get_binding::<D1Database>("rushomon") else {
+                    return;
+                };
+                if let Err(e) = ApiKeyRepository::new()
+                    .update_last_used(&db, &key_id, now, API_KEY_LAST_USED_DEBOUNCE_SECS)
+                    .await
+                {
+                    console_log!("Failed to update API key last_used_at: {:?}", e);
+                }
+            });
         }
 
         // 9. Successfully authenticate!
@@ -250,7 +278,7 @@ pub async fn authenticate_request(
     };
 
     // Validate JWT
-    let claims = match validate_jwt(&jwt, &jwt_secret) {
+    let claims = match validate_jwt(&jwt, &jwt_secret, &JwtClaimsConfig::from_env(&ctx.env)) {
         Ok(claims) => claims,
         Err(_e) => {
             console_log!(
@@ -420,6 +448,8 @@ mod tests {
             expires_at: Some(1234567890 + 86400 * 30), // 30 days from now
             status: status.to_string(),
             tier: Some("free".to_string()),
+            scopes: None,
+            last_used_at: None,
         }
     }
 
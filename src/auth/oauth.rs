@@ -250,6 +250,7 @@ pub async fn handle_oauth_callback(
     // or influence the session ID before authentication is confirmed.
     let session_id = uuid::Uuid::new_v4().to_string();
     let jwt_secret = env.secret("JWT_SECRET")?.to_string();
+    let claims_config = crate::auth::session::JwtClaimsConfig::from_env(env);
 
     let access_token = crate::auth::session::create_access_token(
         &user.id,
@@ -257,6 +258,7 @@ pub async fn handle_oauth_callback(
         &session_id,
         &user.role,
         &jwt_secret,
+        &claims_config,
     )?;
     let refresh_token = crate::auth::session::create_refresh_token(
         &user.id,
@@ -264,6 +266,7 @@ pub async fn handle_oauth_callback(
         &session_id,
         &user.role,
         &jwt_secret,
+        &claims_config,
     )?;
 
     Ok((
@@ -316,8 +319,10 @@ async fn create_or_get_user(
             .create_or_update(db, create_data, &user.org_id)
             .await?;
 
-        // Update last login timestamp
-        user_repo.update_last_login(db, &updated_user.id).await?;
+        // Record the login time and the provider used
+        user_repo
+            .update_last_login(db, &updated_user.id, &updated_user.oauth_provider)
+            .await?;
 
         let org_repo = OrgRepository::new();
         let org = org_repo
@@ -374,8 +379,10 @@ async fn create_or_get_user(
             .create_or_update(db, create_data, &user.org_id)
             .await?;
 
-        // Update last login timestamp
-        user_repo.update_last_login(db, &updated_user.id).await?;
+        // Record the login time and the provider used
+        user_repo
+            .update_last_login(db, &updated_user.id, &updated_user.oauth_provider)
+            .await?;
 
         let org_repo = OrgRepository::new();
         let org = org_repo
@@ -386,7 +393,8 @@ async fn create_or_get_user(
         return Ok((updated_user, org));
     }
 
-    // Step 3: new user — check if signups are enabled (first user is always allowed)
+    // Step 3: new user — check if signups are enabled and the email domain is
+    // allowed (first user is always allowed)
     let user_repo = UserRepository::new();
     let user_count = user_repo.count(db).await?;
     if user_count > 0 {
@@ -398,6 +406,17 @@ async fn create_or_get_user(
         if signups_enabled != "true" {
             return Err(Error::RustError("SIGNUPS_DISABLED".to_string()));
         }
+
+        // Restrict signups to the configured email domains, if any
+        let allowed_domains = settings_repo
+            .get_setting(db, "signup_email_domains")
+            .await?;
+        if !crate::utils::validation::is_signup_email_allowed(
+            allowed_domains.as_deref(),
+            &normalized_user.email,
+        ) {
+            return Err(Error::RustError("EMAIL_DOMAIN_NOT_ALLOWED".to_string()));
+        }
     }
 
     // Derive org name from display name or email prefix
@@ -432,8 +451,10 @@ async fn create_or_get_user(
     };
     let user = user_repo.create_or_update(db, create_data, &org.id).await?;
 
-    // Update last login timestamp
-    user_repo.update_last_login(db, &user.id).await?;
+    // Record the login time and the provider used
+    user_repo
+        .update_last_login(db, &user.id, &user.oauth_provider)
+        .await?;
 
     // Add the user as an organization member with owner role
     org_repo.add_member(db, &org.id, &user.id, "owner").await?;
@@ -468,6 +489,7 @@ mod tests {
             suspension_reason: None,
             suspended_by: None,
             last_login_at: None,
+            last_login_provider: None,
         }
     }
 
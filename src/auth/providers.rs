@@ -1,6 +1,8 @@
 /// OAuth provider configuration - all URL and credential env var keys for a provider.
 pub struct OAuthProviderConfig {
     pub name: &'static str,
+    /// Human-readable name for login buttons
+    pub label: &'static str,
     pub authorize_url_env: &'static str,
     pub token_url_env: &'static str,
     pub user_url_env: &'static str,
@@ -44,17 +46,33 @@ impl OAuthProviderConfig {
         Ok(env.secret(self.client_secret_env)?.to_string())
     }
 
-    /// Returns true if this provider is configured (client ID env var is present)
+    /// Returns true if this provider is configured (client ID and secret are set)
     pub fn is_enabled(&self, env: &worker::Env) -> bool {
-        env.var(self.client_id_env)
-            .map(|v| !v.to_string().is_empty())
-            .unwrap_or(false)
+        self.is_configured(|key| env_value(env, key))
     }
+
+    /// Whether both credentials are present and non-empty, reading env
+    /// values through `lookup`. Without the secret the token exchange fails,
+    /// so a provider with only a client ID would show a broken login button.
+    fn is_configured(&self, lookup: impl Fn(&str) -> Option<String>) -> bool {
+        [self.client_id_env, self.client_secret_env]
+            .iter()
+            .all(|key| lookup(key).is_some_and(|v| !v.trim().is_empty()))
+    }
+}
+
+/// Read a plain var or a secret (credentials may be set as either).
+fn env_value(env: &worker::Env, key: &str) -> Option<String> {
+    env.var(key)
+        .or_else(|_| env.secret(key))
+        .ok()
+        .map(|v| v.to_string())
 }
 
 /// GitHub OAuth provider configuration
 pub static GITHUB: OAuthProviderConfig = OAuthProviderConfig {
     name: "github",
+    label: "GitHub",
     authorize_url_env: "GITHUB_AUTHORIZE_URL",
     token_url_env: "GITHUB_TOKEN_URL",
     user_url_env: "GITHUB_USER_URL",
@@ -69,6 +87,7 @@ pub static GITHUB: OAuthProviderConfig = OAuthProviderConfig {
 /// Google OAuth provider configuration
 pub static GOOGLE: OAuthProviderConfig = OAuthProviderConfig {
     name: "google",
+    label: "Google",
     authorize_url_env: "GOOGLE_AUTHORIZE_URL",
     token_url_env: "GOOGLE_TOKEN_URL",
     user_url_env: "GOOGLE_USER_URL",
@@ -80,6 +99,23 @@ pub static GOOGLE: OAuthProviderConfig = OAuthProviderConfig {
     client_secret_env: "GOOGLE_CLIENT_SECRET",
 };
 
+/// Every supported provider, in the order login buttons are shown.
+pub static ALL_PROVIDERS: [&OAuthProviderConfig; 2] = [&GITHUB, &GOOGLE];
+
+/// Providers whose credentials are configured on this instance.
+pub fn available_oauth_providers(env: &worker::Env) -> Vec<&'static OAuthProviderConfig> {
+    providers_configured(|key| env_value(env, key))
+}
+
+fn providers_configured(
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Vec<&'static OAuthProviderConfig> {
+    ALL_PROVIDERS
+        .into_iter()
+        .filter(|provider| provider.is_configured(&lookup))
+        .collect()
+}
+
 /// Normalized user profile — common across all providers
 #[derive(Debug)]
 pub struct NormalizedUser {
@@ -89,3 +125,65 @@ pub struct NormalizedUser {
     pub name: Option<String>,
     pub avatar_url: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn names_with(vars: &[(&str, &str)]) -> Vec<&'static str> {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        providers_configured(|key| vars.get(key).map(|v| v.to_string()))
+            .into_iter()
+            .map(|p| p.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_no_providers_configured() {
+        assert!(names_with(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_github_only() {
+        assert_eq!(
+            names_with(&[("GITHUB_CLIENT_ID", "id"), ("GITHUB_CLIENT_SECRET", "s")]),
+            vec!["github"]
+        );
+    }
+
+    #[test]
+    fn test_google_only() {
+        assert_eq!(
+            names_with(&[("GOOGLE_CLIENT_ID", "id"), ("GOOGLE_CLIENT_SECRET", "s")]),
+            vec!["google"]
+        );
+    }
+
+    #[test]
+    fn test_both_providers_in_display_order() {
+        assert_eq!(
+            names_with(&[
+                ("GOOGLE_CLIENT_ID", "id"),
+                ("GOOGLE_CLIENT_SECRET", "s"),
+                ("GITHUB_CLIENT_ID", "id"),
+                ("GITHUB_CLIENT_SECRET", "s"),
+            ]),
+            vec!["github", "google"]
+        );
+    }
+
+    #[test]
+    fn test_partial_or_empty_credentials_are_not_configured() {
+        assert!(names_with(&[("GITHUB_CLIENT_ID", "id")]).is_empty());
+        assert!(names_with(&[("GOOGLE_CLIENT_SECRET", "s")]).is_empty());
+        assert!(names_with(&[("GITHUB_CLIENT_ID", ""), ("GITHUB_CLIENT_SECRET", "s")]).is_empty());
+        assert!(
+            names_with(&[("GOOGLE_CLIENT_ID", "id"), ("GOOGLE_CLIENT_SECRET", "  ")]).is_empty()
+        );
+        // One provider's credentials never enable the other
+        assert!(
+            names_with(&[("GITHUB_CLIENT_ID", "id"), ("GOOGLE_CLIENT_SECRET", "s")]).is_empty()
+        );
+    }
+}
@@ -5,11 +5,15 @@ use jwt_compact::{
     prelude::*,
 };
 use serde::{Deserialize, Serialize};
-use worker::{Error, Result, kv::KvStore};
+use worker::{Env, Error, Result, kv::KvStore};
 
 const SESSION_TTL_SECONDS: u64 = 604800; // 7 days
 const ACCESS_TOKEN_TTL_SECONDS: u64 = 3600; // 1 hour
 const REFRESH_TOKEN_TTL_SECONDS: u64 = 604800; // 7 days
+const LAST_PROVIDER_TTL_SECONDS: u64 = 31536000; // 1 year
+
+/// Cookie remembering which OAuth provider the browser last signed in with
+pub const LAST_PROVIDER_COOKIE: &str = "rushomon_last_provider";
 const MIN_JWT_SECRET_LENGTH: usize = 32; // Minimum 32 characters for security
 
 /// Token type for distinguishing between access and refresh tokens
@@ -43,6 +47,84 @@ pub struct JwtClaims {
     pub token_type: String, // "access" or "refresh"
     #[serde(default)]
     pub role: String, // "admin" or "member" (instance-level)
+    /// Issuer, set when `JWT_ISSUER` is configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    /// Audience, set when `JWT_AUDIENCE` is configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
+}
+
+/// Optional `iss`/`aud` claims for deployments behind a gateway that
+/// validates our JWTs, configured via `JWT_ISSUER` and `JWT_AUDIENCE`.
+///
+/// Configured claims are stamped on every new token and checked on every
+/// validated one: a mismatch is always rejected. Tokens issued before the
+/// claims were configured carry neither, so a missing claim is accepted
+/// until `JWT_REQUIRE_ISS_AUD=true` ends the rollout grace period.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JwtClaimsConfig {
+    pub issuer: Option<String>,
+    pub audience: Option<String>,
+    pub require: bool,
+}
+
+impl JwtClaimsConfig {
+    /// Build a config from raw values; blank values mean "not configured"
+    pub fn new(issuer: Option<&str>, audience: Option<&str>, require: bool) -> Self {
+        let non_empty = |v: Option<&str>| {
+            v.map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            issuer: non_empty(issuer),
+            audience: non_empty(audience),
+            require,
+        }
+    }
+
+    /// Read `JWT_ISSUER`, `JWT_AUDIENCE` and `JWT_REQUIRE_ISS_AUD` from the environment
+    pub fn from_env(env: &Env) -> Self {
+        let issuer = env.var("JWT_ISSUER").ok().map(|v| v.to_string());
+        let audience = env.var("JWT_AUDIENCE").ok().map(|v| v.to_string());
+        let require = env
+            .var("JWT_REQUIRE_ISS_AUD")
+            .map(|v| v.to_string() == "true")
+            .unwrap_or(false);
+        Self::new(issuer.as_deref(), audience.as_deref(), require)
+    }
+
+    /// Check a token's `iss`/`aud` against the configured values
+    pub fn check(&self, claims: &JwtClaims) -> Result<()> {
+        check_claim(
+            "issuer",
+            self.issuer.as_deref(),
+            claims.iss.as_deref(),
+            self.require,
+        )?;
+        check_claim(
+            "audience",
+            self.audience.as_deref(),
+            claims.aud.as_deref(),
+            self.require,
+        )
+    }
+}
+
+fn check_claim(
+    name: &str,
+    expected: Option<&str>,
+    actual: Option<&str>,
+    require: bool,
+) -> Result<()> {
+    match (expected, actual) {
+        (None, _) => Ok(()),
+        (Some(expected), Some(actual)) if expected == actual => Ok(()),
+        (Some(_), None) if !require => Ok(()),
+        (Some(_), None) => Err(Error::RustError(format!("Token missing {} claim", name))),
+        (Some(_), Some(_)) => Err(Error::RustError(format!("Token {} mismatch", name))),
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -77,6 +159,7 @@ pub fn create_jwt(
         role,
         secret,
         TokenType::Refresh,
+        &JwtClaimsConfig::default(),
     )
 }
 
@@ -109,6 +192,7 @@ pub fn create_jwt_with_type(
     role: &str,
     secret: &str,
     token_type: TokenType,
+    claims_config: &JwtClaimsConfig,
 ) -> Result<String> {
     // Validate secret length on first use
     validate_jwt_secret(secret)?;
@@ -123,6 +207,8 @@ pub fn create_jwt_with_type(
         session_id: session_id.to_string(),
         token_type: token_type.as_str().to_string(),
         role: role.to_string(),
+        iss: claims_config.issuer.clone(),
+        aud: claims_config.audience.clone(),
     })
     .set_duration_and_issuance(
         &time_options,
@@ -141,8 +227,17 @@ pub fn create_access_token(
     session_id: &str,
     role: &str,
     secret: &str,
+    claims_config: &JwtClaimsConfig,
 ) -> Result<String> {
-    create_jwt_with_type(user_id, org_id, session_id, role, secret, TokenType::Access)
+    create_jwt_with_type(
+        user_id,
+        org_id,
+        session_id,
+        role,
+        secret,
+        TokenType::Access,
+        claims_config,
+    )
 }
 
 /// Creates a refresh token (7 days expiry)
@@ -152,6 +247,7 @@ pub fn create_refresh_token(
     session_id: &str,
     role: &str,
     secret: &str,
+    claims_config: &JwtClaimsConfig,
 ) -> Result<String> {
     create_jwt_with_type(
         user_id,
@@ -160,11 +256,17 @@ pub fn create_refresh_token(
         role,
         secret,
         TokenType::Refresh,
+        claims_config,
     )
 }
 
-/// Validates a JWT token and returns the claims
-pub fn validate_jwt(token: &str, secret: &str) -> Result<JwtClaims> {
+/// Validates a JWT token (signature, expiry, and configured issuer/audience)
+/// and returns the claims
+pub fn validate_jwt(
+    token: &str,
+    secret: &str,
+    claims_config: &JwtClaimsConfig,
+) -> Result<JwtClaims> {
     // Validate secret meets minimum requirements before attempting validation
     validate_jwt_secret(secret)?;
 
@@ -191,10 +293,161 @@ pub fn validate_jwt(token: &str, secret: &str) -> Result<JwtClaims> {
     if let Err(_e) = claims.validate_expiration(&time_options) {
         return Err(Error::RustError("Token expired".to_string()));
     }
+    claims_config.check(&claims.custom)?;
 
     Ok(claims.custom.clone())
 }
 
+/// SameSite attribute applied to auth cookies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    Lax,
+    Strict,
+    None,
+}
+
+impl SameSite {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SameSite::Lax => "Lax",
+            SameSite::Strict => "Strict",
+            SameSite::None => "None",
+        }
+    }
+
+    /// Parse a `COOKIE_SAMESITE` value (case-insensitive)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "lax" => Some(SameSite::Lax),
+            "strict" => Some(SameSite::Strict),
+            "none" => Some(SameSite::None),
+            _ => None,
+        }
+    }
+}
+
+/// Attributes shared by all auth cookies (access, refresh, legacy session).
+///
+/// Split-domain deployments (e.g. app on `app.example.com`, API on
+/// `api.example.com`) need `Domain=.example.com` and `SameSite=None; Secure`,
+/// configured via the `COOKIE_DOMAIN` and `COOKIE_SAMESITE` env vars.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CookieConfig {
+    pub secure: bool,
+    pub same_site: SameSite,
+    pub domain: Option<String>,
+}
+
+impl CookieConfig {
+    /// Default attributes for a scheme: `SameSite=Lax`, host-only, `Secure` on https
+    pub fn for_scheme(scheme: &str) -> Self {
+        Self {
+            secure: scheme == "https",
+            same_site: SameSite::Lax,
+            domain: None,
+        }
+    }
+
+    /// Build a config from raw values, validating the combination.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `same_site` is not one of Lax/Strict/None, if
+    /// `SameSite=None` is requested without a secure scheme (browsers reject
+    /// such cookies), or if the domain contains characters that would break
+    /// the cookie header.
+    pub fn new(scheme: &str, same_site: Option<&str>, domain: Option<&str>) -> Result<Self> {
+        let mut config = Self::for_scheme(scheme);
+
+        if let Some(value) = same_site.map(str::trim).filter(|v| !v.is_empty()) {
+            config.same_site = SameSite::parse(value).ok_or_else(|| {
+                Error::RustError(format!(
+                    "Invalid COOKIE_SAMESITE value '{}'. Must be one of: Lax, Strict, None",
+                    value
+                ))
+            })?;
+        }
+
+        if config.same_site == SameSite::None && !config.secure {
+            return Err(Error::RustError(
+                "COOKIE_SAMESITE=None requires Secure cookies, which are only set on https deployments"
+                    .to_string(),
+            ));
+        }
+
+        if let Some(value) = domain.map(str::trim).filter(|v| !v.is_empty()) {
+            if value
+                .chars()
+                .any(|c| c == ';' || c == ',' || c.is_whitespace() || c.is_control())
+            {
+                return Err(Error::RustError(format!(
+                    "Invalid COOKIE_DOMAIN value '{}'",
+                    value
+                )));
+            }
+            config.domain = Some(value.to_string());
+        }
+
+        Ok(config)
+    }
+
+    /// Read `COOKIE_SAMESITE` and `COOKIE_DOMAIN` from the environment.
+    /// The scheme (and therefore the `Secure` flag) is derived from `DOMAIN`.
+    pub fn from_env(env: &Env) -> Result<Self> {
+        let scheme = crate::utils::env::get_scheme(env);
+        let same_site = env.var("COOKIE_SAMESITE").ok().map(|v| v.to_string());
+        let domain = env.var("COOKIE_DOMAIN").ok().map(|v| v.to_string());
+        Self::new(&scheme, same_site.as_deref(), domain.as_deref())
+    }
+
+    /// Format a `Set-Cookie` value with this config's attributes
+    fn build(&self, name: &str, value: &str, max_age: u64) -> String {
+        self.build_with_http_only(name, value, max_age, true)
+    }
+
+    fn build_with_http_only(
+        &self,
+        name: &str,
+        value: &str,
+        max_age: u64,
+        http_only: bool,
+    ) -> String {
+        let http_only_part = if http_only { " HttpOnly;" } else { "" };
+        let secure_part = if self.secure { " Secure;" } else { "" };
+        let domain_part = self
+            .domain
+            .as_ref()
+            .map(|d| format!("; Domain={}", d))
+            .unwrap_or_default();
+        format!(
+            "{}={};{}{} SameSite={}; Path=/; Max-Age={}{}",
+            name,
+            value,
+            http_only_part,
+            secure_part,
+            self.same_site.as_str(),
+            max_age,
+            domain_part
+        )
+    }
+
+    /// Format an immediately-expiring cookie. Logout cookies are always marked
+    /// `Secure`, and must carry the same `Domain` as the original to clear it.
+    fn build_logout(&self, name: &str) -> String {
+        let domain_part = self
+            .domain
+            .as_ref()
+            .map(|d| format!("; Domain={}", d))
+            .unwrap_or_default();
+        format!(
+            "{}=; HttpOnly; Secure; SameSite={}; Path=/; Max-Age=0{}",
+            name,
+            self.same_site.as_str(),
+            domain_part
+        )
+    }
+}
+
 /// Creates a session cookie with the JWT token
 pub fn create_session_cookie(jwt: &str) -> String {
     create_session_cookie_with_scheme(jwt, "https")
@@ -202,11 +455,7 @@ pub fn create_session_cookie(jwt: &str) -> String {
 
 /// Creates a session cookie with the JWT token and specified scheme
 pub fn create_session_cookie_with_scheme(jwt: &str, scheme: &str) -> String {
-    let secure_part = if scheme == "https" { " Secure;" } else { "" };
-    format!(
-        "rushomon_session={}; HttpOnly;{} SameSite=Lax; Path=/; Max-Age={}",
-        jwt, secure_part, SESSION_TTL_SECONDS
-    )
+    CookieConfig::for_scheme(scheme).build("rushomon_session", jwt, SESSION_TTL_SECONDS)
 }
 
 /// Parses the Cookie header and extracts the session token
@@ -221,7 +470,12 @@ pub fn parse_cookie_header(cookie_header: &str) -> Option<String> {
 
 /// Creates a logout cookie that expires immediately
 pub fn create_logout_cookie() -> String {
-    "rushomon_session=; HttpOnly; Secure; SameSite=Lax; Path=/; Max-Age=0".to_string()
+    create_logout_cookie_with_config(&CookieConfig::for_scheme("https"))
+}
+
+/// Creates a legacy session logout cookie matching the configured attributes
+pub fn create_logout_cookie_with_config(config: &CookieConfig) -> String {
+    config.build_logout("rushomon_session")
 }
 
 /// Creates an access token cookie (httpOnly for security)
@@ -231,11 +485,32 @@ pub fn create_access_cookie(jwt: &str) -> String {
 
 /// Creates an access token cookie with specified scheme
 pub fn create_access_cookie_with_scheme(jwt: &str, scheme: &str) -> String {
-    let secure_part = if scheme == "https" { " Secure;" } else { "" };
-    format!(
-        "rushomon_access={}; HttpOnly;{} SameSite=Lax; Path=/; Max-Age={}",
-        jwt, secure_part, ACCESS_TOKEN_TTL_SECONDS
-    )
+    create_access_cookie_with_config(jwt, &CookieConfig::for_scheme(scheme))
+}
+
+/// Creates an access token cookie with the configured SameSite/Domain attributes
+pub fn create_access_cookie_with_config(jwt: &str, config: &CookieConfig) -> String {
+    config.build("rushomon_access", jwt, ACCESS_TOKEN_TTL_SECONDS)
+}
+
+/// Creates the last-used provider cookie. It is readable by JavaScript so the
+/// pre-auth login page can highlight the provider, so it carries nothing but
+/// a known provider name; returns None for anything else.
+pub fn create_last_provider_cookie_with_config(
+    provider: &str,
+    config: &CookieConfig,
+) -> Option<String> {
+    crate::auth::providers::ALL_PROVIDERS
+        .iter()
+        .find(|p| p.name == provider)
+        .map(|p| {
+            config.build_with_http_only(
+                LAST_PROVIDER_COOKIE,
+                p.name,
+                LAST_PROVIDER_TTL_SECONDS,
+                false,
+            )
+        })
 }
 
 /// Parses the Cookie header and extracts the access token
@@ -250,7 +525,12 @@ pub fn parse_access_cookie_header(cookie_header: &str) -> Option<String> {
 
 /// Creates a logout cookie for access token that expires immediately
 pub fn create_access_logout_cookie() -> String {
-    "rushomon_access=; HttpOnly; Secure; SameSite=Lax; Path=/; Max-Age=0".to_string()
+    create_access_logout_cookie_with_config(&CookieConfig::for_scheme("https"))
+}
+
+/// Creates an access token logout cookie matching the configured attributes
+pub fn create_access_logout_cookie_with_config(config: &CookieConfig) -> String {
+    config.build_logout("rushomon_access")
 }
 
 /// Creates a refresh token cookie (httpOnly for security)
@@ -260,16 +540,22 @@ pub fn create_refresh_cookie(jwt: &str) -> String {
 
 /// Creates a refresh token cookie with specified scheme
 pub fn create_refresh_cookie_with_scheme(jwt: &str, scheme: &str) -> String {
-    let secure_part = if scheme == "https" { " Secure;" } else { "" };
-    format!(
-        "rushomon_refresh={}; HttpOnly;{} SameSite=Lax; Path=/; Max-Age={}",
-        jwt, secure_part, REFRESH_TOKEN_TTL_SECONDS
-    )
+    create_refresh_cookie_with_config(jwt, &CookieConfig::for_scheme(scheme))
+}
+
+/// Creates a refresh token cookie with the configured SameSite/Domain attributes
+pub fn create_refresh_cookie_with_config(jwt: &str, config: &CookieConfig) -> String {
+    config.build("rushomon_refresh", jwt, REFRESH_TOKEN_TTL_SECONDS)
 }
 
 /// Creates a logout cookie for refresh token that expires immediately
 pub fn create_refresh_logout_cookie() -> String {
-    "rushomon_refresh=; HttpOnly; Secure; SameSite=Lax; Path=/; Max-Age=0".to_string()
+    create_refresh_logout_cookie_with_config(&CookieConfig::for_scheme("https"))
+}
+
+/// Creates a refresh token logout cookie matching the configured attributes
+pub fn create_refresh_logout_cookie_with_config(config: &CookieConfig) -> String {
+    config.build_logout("rushomon_refresh")
 }
 
 /// Parses the Cookie header and extracts the refresh token
@@ -359,7 +645,7 @@ mod tests {
     fn test_jwt_roundtrip() {
         let secret = "test-secret-32-chars-minimum!!";
         let jwt = create_jwt("user1", "org1", "sess1", "admin", secret).unwrap();
-        let claims = validate_jwt(&jwt, secret).unwrap();
+        let claims = validate_jwt(&jwt, secret, &JwtClaimsConfig::default()).unwrap();
 
         assert_eq!(claims.sub, "user1");
         assert_eq!(claims.org_id, "org1");
@@ -374,10 +660,83 @@ mod tests {
         let secret2 = "different-secret-32-chars-min!";
         let jwt = create_jwt("user1", "org1", "sess1", "member", secret1).unwrap();
 
-        let result = validate_jwt(&jwt, secret2);
+        let result = validate_jwt(&jwt, secret2, &JwtClaimsConfig::default());
         assert!(result.is_err());
     }
 
+    fn claims_with(iss: Option<&str>, aud: Option<&str>) -> JwtClaims {
+        JwtClaims {
+            sub: "user1".to_string(),
+            org_id: "org1".to_string(),
+            session_id: "sess1".to_string(),
+            token_type: "access".to_string(),
+            role: "member".to_string(),
+            iss: iss.map(str::to_string),
+            aud: aud.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_jwt_claims_config_accepts_matching_claims() {
+        let config = JwtClaimsConfig::new(Some("rushomon"), Some("gateway"), true);
+        assert!(
+            config
+                .check(&claims_with(Some("rushomon"), Some("gateway")))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_jwt_claims_config_rejects_mismatched_claims() {
+        let config = JwtClaimsConfig::new(Some("rushomon"), Some("gateway"), false);
+        let err = config
+            .check(&claims_with(Some("someone-else"), Some("gateway")))
+            .unwrap_err();
+        assert!(err.to_string().contains("issuer mismatch"));
+        let err = config
+            .check(&claims_with(Some("rushomon"), Some("other-api")))
+            .unwrap_err();
+        assert!(err.to_string().contains("audience mismatch"));
+    }
+
+    #[test]
+    fn test_jwt_claims_config_missing_claims_grace_period() {
+        let legacy = claims_with(None, None);
+        let grace = JwtClaimsConfig::new(Some("rushomon"), Some("gateway"), false);
+        assert!(grace.check(&legacy).is_ok());
+
+        let strict = JwtClaimsConfig {
+            require: true,
+            ..grace
+        };
+        assert!(strict.check(&legacy).is_err());
+        assert!(strict.check(&claims_with(Some("rushomon"), None)).is_err());
+    }
+
+    #[test]
+    fn test_jwt_claims_config_unconfigured_accepts_anything() {
+        let config = JwtClaimsConfig::new(Some("  "), None, true);
+        assert_eq!(
+            config,
+            JwtClaimsConfig {
+                require: true,
+                ..Default::default()
+            }
+        );
+        assert!(config.check(&claims_with(Some("x"), Some("y"))).is_ok());
+        assert!(config.check(&claims_with(None, None)).is_ok());
+    }
+
+    #[test]
+    fn test_jwt_claims_serialize_without_unset_iss_aud() {
+        let value = serde_json::to_value(claims_with(None, None)).unwrap();
+        assert!(value.get("iss").is_none());
+        assert!(value.get("aud").is_none());
+        let value = serde_json::to_value(claims_with(Some("rushomon"), Some("gateway"))).unwrap();
+        assert_eq!(value["iss"], "rushomon");
+        assert_eq!(value["aud"], "gateway");
+    }
+
     #[test]
     fn test_cookie_parsing() {
         let header = "rushomon_session=abc123; other=xyz";
@@ -409,6 +768,101 @@ mod tests {
         assert!(cookie.contains("Max-Age=0"));
     }
 
+    #[test]
+    fn test_cookie_config_defaults_match_scheme() {
+        let cookie = create_access_cookie_with_config("tok", &CookieConfig::for_scheme("https"));
+        assert_eq!(
+            cookie,
+            format!(
+                "rushomon_access=tok; HttpOnly; Secure; SameSite=Lax; Path=/; Max-Age={}",
+                ACCESS_TOKEN_TTL_SECONDS
+            )
+        );
+
+        let cookie = create_access_cookie_with_config("tok", &CookieConfig::for_scheme("http"));
+        assert!(!cookie.contains("Secure"));
+        assert!(!cookie.contains("Domain="));
+        assert_eq!(cookie, create_access_cookie_with_scheme("tok", "http"));
+    }
+
+    #[test]
+    fn test_cookie_config_cross_subdomain() {
+        let config = CookieConfig::new("https", Some("none"), Some(".example.com")).unwrap();
+        assert_eq!(config.same_site, SameSite::None);
+
+        let cookie = create_refresh_cookie_with_config("tok", &config);
+        assert_eq!(
+            cookie,
+            format!(
+                "rushomon_refresh=tok; HttpOnly; Secure; SameSite=None; Path=/; Max-Age={}; Domain=.example.com",
+                REFRESH_TOKEN_TTL_SECONDS
+            )
+        );
+
+        let logout = create_refresh_logout_cookie_with_config(&config);
+        assert_eq!(
+            logout,
+            "rushomon_refresh=; HttpOnly; Secure; SameSite=None; Path=/; Max-Age=0; Domain=.example.com"
+        );
+    }
+
+    #[test]
+    fn test_cookie_config_strict_without_domain() {
+        let config = CookieConfig::new("http", Some("Strict"), Some("  ")).unwrap();
+        let cookie = create_access_cookie_with_config("tok", &config);
+        assert!(cookie.contains("SameSite=Strict"));
+        assert!(!cookie.contains("Secure"));
+        assert!(!cookie.contains("Domain="));
+    }
+
+    #[test]
+    fn test_cookie_config_rejects_samesite_none_without_secure() {
+        let result = CookieConfig::new("http", Some("None"), None);
+        assert!(result.is_err());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("COOKIE_SAMESITE=None requires Secure")
+        );
+    }
+
+    #[test]
+    fn test_cookie_config_rejects_invalid_values() {
+        assert!(CookieConfig::new("https", Some("sometimes"), None).is_err());
+        assert!(CookieConfig::new("https", None, Some("example.com; Path=/x")).is_err());
+        assert!(CookieConfig::new("https", None, Some("example .com")).is_err());
+    }
+
+    #[test]
+    fn test_last_provider_cookie_is_readable_and_name_only() {
+        let config = CookieConfig::for_scheme("https");
+        assert_eq!(
+            create_last_provider_cookie_with_config("google", &config).unwrap(),
+            "rushomon_last_provider=google; Secure; SameSite=Lax; Path=/; Max-Age=31536000"
+        );
+        assert_eq!(
+            create_last_provider_cookie_with_config("user@example.com", &config),
+            None
+        );
+        assert_eq!(
+            create_last_provider_cookie_with_config("github; Domain=evil.com", &config),
+            None
+        );
+    }
+
+    #[test]
+    fn test_logout_cookies_keep_legacy_format_by_default() {
+        assert_eq!(
+            create_access_logout_cookie(),
+            "rushomon_access=; HttpOnly; Secure; SameSite=Lax; Path=/; Max-Age=0"
+        );
+        assert_eq!(
+            create_logout_cookie(),
+            "rushomon_session=; HttpOnly; Secure; SameSite=Lax; Path=/; Max-Age=0"
+        );
+    }
+
     #[test]
     fn test_validate_jwt_secret_accepts_valid_secret() {
         // 32 characters - minimum valid length
@@ -0,0 +1,23 @@
+/// Short-lived markers used to skip repeat clicks from the same visitor.
+///
+/// The value is the unix timestamp of the last counted click, so windows
+/// shorter than KV's minimum TTL still expire on time.
+use worker::{Result, kv::KvStore};
+
+/// When the visitor's last counted click on the link happened, if recently
+pub async fn get_last_counted_click(kv: &KvStore, key: &str) -> Result<Option<i64>> {
+    Ok(kv
+        .get(key)
+        .text()
+        .await?
+        .and_then(|v| v.parse::<i64>().ok()))
+}
+
+/// Record a counted click, kept for `ttl_secs`
+pub async fn store_counted_click(kv: &KvStore, key: &str, now: i64, ttl_secs: u64) -> Result<()> {
+    kv.put(key, now.to_string())?
+        .expiration_ttl(ttl_secs)
+        .execute()
+        .await?;
+    Ok(())
+}
@@ -1,6 +1,17 @@
 use crate::models::LinkMapping;
+use crate::models::link_alias::AliasPointer;
+use serde::Deserialize;
 use worker::{Result, kv::KvStore};
 
+/// A value in the default namespace: a link's own mapping, or a pointer
+/// from an alias code to the link's primary short code.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredEntry {
+    Alias(AliasPointer),
+    Mapping(Box<LinkMapping>),
+}
+
 /// KV key format: link:{org_id}:{short_code}
 /// For Phase 1 (single domain), we use a simpler global format: {short_code}
 fn make_key(_org_id: &str, short_code: &str) -> String {
@@ -14,6 +25,20 @@ fn make_domain_key(hostname: &str, short_code: &str) -> String {
     format!("{}:{}", hostname, short_code)
 }
 
+/// Split a key of this namespace into (custom domain, short code) when it
+/// holds a link mapping or alias pointer: `{short_code}` or
+/// `{hostname}:{short_code}`. Other entries (`ratelimit:…`, `settings:…`,
+/// …) have no dot before the first colon and return None.
+pub fn parse_link_key(key: &str) -> Option<(Option<&str>, &str)> {
+    match key.split_once(':') {
+        None => Some((None, key)),
+        Some((host, code)) if host.contains('.') && !code.is_empty() && !code.contains(':') => {
+            Some((Some(host), code))
+        }
+        Some(_) => None,
+    }
+}
+
 /// Store a link mapping for a specific custom domain
 pub async fn store_link_mapping_for_domain(
     kv: &KvStore,
@@ -62,14 +87,53 @@ pub async fn store_link_mapping(
     Ok(())
 }
 
-/// Get a link mapping from KV
+/// Get a link mapping from KV. Alias codes resolve to their link's mapping.
 pub async fn get_link_mapping(kv: &KvStore, short_code: &str) -> Result<Option<LinkMapping>> {
     // Note: For global namespace, we don't need org_id
     // For multi-tenant with org prefix, would need to iterate or use secondary lookup
-    kv.get(short_code)
-        .json::<LinkMapping>()
+    let entry = kv
+        .get(short_code)
+        .json::<StoredEntry>()
         .await
-        .map_err(|e| worker::Error::RustError(format!("KV error: {:?}", e)))
+        .map_err(|e| worker::Error::RustError(format!("KV error: {:?}", e)))?;
+
+    match entry {
+        Some(StoredEntry::Mapping(mapping)) => Ok(Some(*mapping)),
+        // One hop only: the primary code always holds a mapping
+        Some(StoredEntry::Alias(pointer)) => Ok(kv
+            .get(&pointer.alias_of)
+            .json::<LinkMapping>()
+            .await
+            .map_err(|e| worker::Error::RustError(format!("KV error: {:?}", e)))?
+            .filter(|mapping| mapping.link_id == pointer.link_id)),
+        None => Ok(None),
+    }
+}
+
+/// Store the KV pointer for an alias code
+pub async fn store_alias_pointer(
+    kv: &KvStore,
+    alias_code: &str,
+    pointer: &AliasPointer,
+) -> Result<()> {
+    kv.put(alias_code, pointer)?.execute().await?;
+    Ok(())
+}
+
+/// Replace a blocked link's mapping with a destination-less tombstone
+pub async fn store_blocked_tombstone(
+    kv: &KvStore,
+    org_id: &str,
+    short_code: &str,
+    link_id: &str,
+) -> Result<()> {
+    store_link_mapping(
+        kv,
+        org_id,
+        short_code,
+        &LinkMapping::blocked_tombstone(link_id, org_id),
+    )
+    .await
 }
 
 /// Delete a link mapping from KV
@@ -111,3 +175,37 @@ pub async fn update_link_mapping(
     put.execute().await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_stored_entry_distinguishes_alias_pointers() {
+        let pointer: StoredEntry =
+            serde_json::from_value(json!({"alias_of": "promo", "link_id": "link-1"})).unwrap();
+        assert!(matches!(pointer, StoredEntry::Alias(p) if p.alias_of == "promo"));
+
+        let mapping: StoredEntry = serde_json::from_value(json!({
+            "destination_url": "https://example.com",
+            "link_id": "link-1",
+            "expires_at": null,
+            "status": "active",
+        }))
+        .unwrap();
+        assert!(matches!(mapping, StoredEntry::Mapping(m) if m.link_id == "link-1"));
+    }
+
+    #[test]
+    fn test_parse_link_key() {
+        assert_eq!(parse_link_key("abc123"), Some((None, "abc123")));
+        assert_eq!(
+            parse_link_key("go.example.com:abc123"),
+            Some((Some("go.example.com"), "abc123"))
+        );
+        assert_eq!(parse_link_key("settings:maintenance"), None);
+        assert_eq!(parse_link_key("ratelimit:login:1.2.3.4"), None);
+        assert_eq!(parse_link_key("org_redirect_config:org-1"), None);
+    }
+}
@@ -0,0 +1,54 @@
+/// KV cache of the global maintenance state.
+///
+/// Every redirect checks this flag, so it is read from KV (edge-cached) rather
+/// than D1. The settings table stays the source of truth; the admin settings
+/// handler writes the derived state here whenever a maintenance setting changes.
+///
+/// On top of KV, each isolate keeps the last state it read for a short while,
+/// so most requests skip the KV read entirely.
+use crate::models::maintenance::MaintenanceState;
+use crate::utils::now_timestamp;
+use std::cell::Cell;
+use worker::{Result, kv::KvStore};
+
+const MAINTENANCE_KEY: &str = "settings:maintenance";
+
+/// Edge cache TTL for the flag.
+const MAINTENANCE_CACHE_TTL_SECS: u64 = 60;
+
+/// How long an isolate reuses the state it last read. Toggling maintenance can
+/// take up to this plus the edge cache TTL to reach every isolate.
+const MAINTENANCE_ISOLATE_TTL_SECS: i64 = 15;
+
+// Workers are single-threaded, so thread_local is safe as a per-isolate cache.
+thread_local! {
+    static CACHED_STATE: Cell<Option<(MaintenanceState, i64)>> = const { Cell::new(None) };
+}
+
+/// Store the maintenance state
+pub async fn store_maintenance_state(kv: &KvStore, state: &MaintenanceState) -> Result<()> {
+    kv.put(MAINTENANCE_KEY, state)?.execute().await?;
+    // The isolate that made the change sees it immediately
+    CACHED_STATE.with(|c| c.set(Some((*state, now_timestamp()))));
+    Ok(())
+}
+
+/// Get the maintenance state (off if never set)
+pub async fn get_maintenance_state(kv: &KvStore) -> Result<MaintenanceState> {
+    let now = now_timestamp();
+    if let Some((state, fetched_at)) = CACHED_STATE.with(Cell::get)
+        && now - fetched_at < MAINTENANCE_ISOLATE_TTL_SECS
+    {
+        return Ok(state);
+    }
+
+    let state = kv
+        .get(MAINTENANCE_KEY)
+        .cache_ttl(MAINTENANCE_CACHE_TTL_SECS)
+        .json::<MaintenanceState>()
+        .await
+        .map(Option::unwrap_or_default)
+        .map_err(|e| worker::Error::RustError(format!("KV error: {:?}", e)))?;
+    CACHED_STATE.with(|c| c.set(Some((state, now))));
+    Ok(state)
+}
@@ -1,5 +1,15 @@
+pub mod click_dedup;
 pub mod links;
+pub mod maintenance;
+pub mod org_config;
+pub mod rewrite_rules;
 pub mod sync;
 
-pub use links::{delete_link_mapping, get_link_mapping, store_link_mapping, update_link_mapping};
+pub use links::{
+    delete_link_mapping, get_link_mapping, store_blocked_tombstone, store_link_mapping,
+    update_link_mapping,
+};
+pub use maintenance::{get_maintenance_state, store_maintenance_state};
+pub use org_config::{get_org_redirect_config, store_org_redirect_config};
+pub use rewrite_rules::{get_rewrite_rules, store_rewrite_rules};
 pub use sync::sync_custom_domain_kv;
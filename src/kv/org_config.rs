@@ -0,0 +1,34 @@
+/// KV cache of per-org redirect configuration.
+///
+/// D1 stays the source of truth; `OrgService::sync_redirect_config` rewrites the
+/// cached entry whenever a field it contains changes.
+use crate::models::org_redirect_config::OrgRedirectConfig;
+use worker::{Result, kv::KvStore};
+
+/// Edge cache TTL for config lookups on the redirect path.
+const CONFIG_CACHE_TTL_SECS: u64 = 60;
+
+/// KV key format: org_redirect_config:{org_id}
+fn make_key(org_id: &str) -> String {
+    format!("org_redirect_config:{}", org_id)
+}
+
+/// Store the redirect config for an org
+pub async fn store_org_redirect_config(
+    kv: &KvStore,
+    org_id: &str,
+    config: &OrgRedirectConfig,
+) -> Result<()> {
+    kv.put(&make_key(org_id), config)?.execute().await?;
+    Ok(())
+}
+
+/// Get the redirect config for an org (defaults if none is cached)
+pub async fn get_org_redirect_config(kv: &KvStore, org_id: &str) -> Result<OrgRedirectConfig> {
+    kv.get(&make_key(org_id))
+        .cache_ttl(CONFIG_CACHE_TTL_SECS)
+        .json::<OrgRedirectConfig>()
+        .await
+        .map(Option::unwrap_or_default)
+        .map_err(|e| worker::Error::RustError(format!("KV error: {:?}", e)))
+}
@@ -0,0 +1,36 @@
+/// KV cache of org-level destination rewrite rules.
+///
+/// The redirect hot path cannot afford a D1 query, so the full rule list for an
+/// org is written through to KV whenever it changes (D1 stays the source of truth).
+use crate::models::RewriteRule;
+use worker::{Result, kv::KvStore};
+
+/// Edge cache TTL for rule lookups. Rule edits can take this long to reach
+/// every colo, which is acceptable for a migration tool.
+const RULES_CACHE_TTL_SECS: u64 = 60;
+
+/// KV key format: org_rewrite_rules:{org_id}
+fn make_key(org_id: &str) -> String {
+    format!("org_rewrite_rules:{}", org_id)
+}
+
+/// Replace the cached rule list for an org. An empty list removes the key.
+pub async fn store_rewrite_rules(kv: &KvStore, org_id: &str, rules: &[RewriteRule]) -> Result<()> {
+    let key = make_key(org_id);
+    if rules.is_empty() {
+        kv.delete(&key).await?;
+    } else {
+        kv.put(&key, rules)?.execute().await?;
+    }
+    Ok(())
+}
+
+/// Get the cached rule list for an org (empty if none are defined).
+pub async fn get_rewrite_rules(kv: &KvStore, org_id: &str) -> Result<Vec<RewriteRule>> {
+    kv.get(&make_key(org_id))
+        .cache_ttl(RULES_CACHE_TTL_SECS)
+        .json::<Vec<RewriteRule>>()
+        .await
+        .map(Option::unwrap_or_default)
+        .map_err(|e| worker::Error::RustError(format!("KV error: {:?}", e)))
+}
@@ -74,6 +74,24 @@ async fn main(req: Request, env: Env, worker_ctx: Context) -> Result<Response> {
         return Ok(add_cors_headers(response, origin, &env));
     }
 
+    // Maintenance mode can optionally gate the API as well as redirects.
+    // Only paths that could be blocked pay for the (edge-cached) KV read.
+    if path.starts_with("/api/")
+        && let Ok(kv) = env.kv("URL_MAPPINGS")
+        && let Ok(state) = kv::get_maintenance_state(&kv).await
+        && state.blocks_api_path(path)
+    {
+        let mut response = utils::AppError::ServiceUnavailable(
+            "The service is temporarily down for maintenance".to_string(),
+        )
+        .into_response();
+        let _ = response.headers_mut().set(
+            "Retry-After",
+            &models::maintenance::MAINTENANCE_RETRY_AFTER_SECS.to_string(),
+        );
+        return Ok(add_cors_headers(response, origin, &env));
+    }
+
     let response = api::router::run(req, env.clone(), is_frontend_domain).await?;
 
     // Execute deferred analytics via wait_until (non-blocking, runs after response is sent)
@@ -82,13 +100,17 @@ async fn main(req: Request, env: Env, worker_ctx: Context) -> Result<Response> {
     if let Some(analytics_future) = deferred {
         worker_ctx.wait_until(analytics_future);
     }
+    for task in api::router::DEFERRED_TASKS.with(|cell| cell.take()) {
+        worker_ctx.wait_until(task);
+    }
 
     // SPA fallback: if router returned 404 on the frontend domain, serve fallback.html.
     // This enables client-side routing for paths like /dashboard, /auth/callback, etc.
     // Short code redirects are already handled by the router above (returning 301/302).
+    // Excluded prefixes (API, /.well-known/, SPA_FALLBACK_EXCLUDE) keep their real 404.
     if response.status_code() == 404
         && is_frontend_domain
-        && !path.starts_with("/api/")
+        && !utils::env::is_spa_fallback_excluded(path, &utils::env::get_spa_fallback_excludes(&env))
         && let Ok(assets) = env.get_binding::<worker::Fetcher>("ASSETS")
     {
         let fallback_url = format!("{}://{}/fallback.html", url.scheme(), request_authority);
@@ -2,4 +2,4 @@ pub mod cors;
 pub mod rate_limit;
 
 pub use cors::{add_cors_headers, add_security_headers, rebuild_asset_response};
-pub use rate_limit::{RateLimitConfig, RateLimiter, is_kv_rate_limiting_enabled};
+pub use rate_limit::{RateLimitConfig, RateLimitError, RateLimitSettings, RateLimiter};
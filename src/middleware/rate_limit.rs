@@ -7,23 +7,34 @@
 /// Cloudflare's built-in rate limiting rules. Set ENABLE_KV_RATE_LIMITING=true
 /// to re-enable KV-based rate limiting for specific use cases.
 ///
+/// When enabled, KV failures fail open: a KV read/write error is logged as a
+/// warning and the request is allowed, so a KV hiccup never takes down redirects.
+///
+/// Clients whose IP falls inside a `TRUSTED_IPS` range (comma-separated CIDR
+/// list, e.g. internal monitoring or our own backend) bypass every limit.
+///
 /// # Current Implementation Status
 ///
 /// Rate limiting is currently applied to:
 /// - ✅ Public redirects (GET /{short_code}): 300/min per IP
-/// - ✅ Link creation (POST /api/links): 100/hour per user
+/// - ✅ Link creation (POST /api/links): 100/hour per user, plus a per-org
+///   budget set by the `org_links_per_hour` setting, always on
 /// - ✅ OAuth endpoints (GET /api/auth/github, GET /api/auth/callback): 20/15min per IP
 /// - ✅ Token refresh (POST /api/auth/refresh): 30/hour per session
 /// - ✅ Auth check (GET /api/auth/me): 100/min per session
+/// - ✅ Short code availability (GET /api/links/check-code): 30/min per IP, always on
+/// - ✅ Anonymous abuse reports (POST /api/reports/links): per IP per hour, set by
+///   the `reports_per_ip_per_hour` setting, always on
 ///
 /// TODO: Apply rate limiting to remaining endpoints:
 /// - Link listing (GET /api/links): 200/hour per user
 /// - Admin endpoints (GET /api/admin/users): 50/hour per admin
 ///
 /// See SECURITY.md for complete rate limiting roadmap.
+use crate::utils::cidr::{IpCidr, ip_in_ranges, parse_cidr_list};
 use serde::{Deserialize, Serialize};
-use worker::Env;
 use worker::kv::KvStore;
+use worker::{Env, console_log};
 
 /// Check whether KV-based rate limiting is enabled in the current environment.
 ///
@@ -34,6 +45,55 @@ pub fn is_kv_rate_limiting_enabled(env: &Env) -> bool {
         .unwrap_or(false)
 }
 
+/// Environment-level rate limiting settings, read once per request
+#[derive(Debug, Clone, Default)]
+pub struct RateLimitSettings {
+    /// Whether KV-based rate limiting is enabled (`ENABLE_KV_RATE_LIMITING`)
+    pub kv_enabled: bool,
+    /// Client networks exempt from all limits (`TRUSTED_IPS`)
+    pub trusted_ips: Vec<IpCidr>,
+}
+
+impl RateLimitSettings {
+    /// Read `ENABLE_KV_RATE_LIMITING` and `TRUSTED_IPS`. Invalid CIDR entries
+    /// are skipped with a warning rather than failing the request.
+    pub fn from_env(env: &Env) -> Self {
+        let trusted_ips = match env.var("TRUSTED_IPS") {
+            Ok(list) => {
+                let (ranges, invalid) = parse_cidr_list(&list.to_string());
+                if !invalid.is_empty() {
+                    console_log!(
+                        "{}",
+                        serde_json::json!({
+                            "event": "trusted_ips_invalid_entries",
+                            "entries": invalid,
+                            "level": "warn"
+                        })
+                    );
+                }
+                ranges
+            }
+            Err(_) => Vec::new(),
+        };
+        Self {
+            kv_enabled: is_kv_rate_limiting_enabled(env),
+            trusted_ips,
+        }
+    }
+
+    /// Force KV limiting on, for limiters that must apply even when it is
+    /// otherwise disabled in favour of Cloudflare's rules
+    pub fn always_on(mut self) -> Self {
+        self.kv_enabled = true;
+        self
+    }
+
+    /// Whether `client_ip` is exempt from rate limiting
+    pub fn is_trusted(&self, client_ip: &str) -> bool {
+        ip_in_ranges(client_ip, &self.trusted_ips)
+    }
+}
+
 /// Rate limit tracking data stored in KV
 #[derive(Debug, Serialize, Deserialize)]
 struct RateLimitData {
@@ -89,6 +149,32 @@ impl RateLimitConfig {
         }
     }
 
+    /// Link creation across an org: `max_per_hour` shared by all members.
+    /// Enforced even when KV rate limiting is disabled, so a team cannot
+    /// outrun the per-user limit by spreading creation over many accounts
+    pub fn org_link_creation(max_per_hour: u32) -> Self {
+        Self {
+            max_requests: max_per_hour,
+            window_seconds: 3600, // 1 hour
+        }
+    }
+
+    /// Anonymous link creation: 10 per hour per IP
+    pub fn anonymous_link_creation() -> Self {
+        Self {
+            max_requests: 10,
+            window_seconds: 3600, // 1 hour
+        }
+    }
+
+    /// Anonymous link claims: 20 per hour per user
+    pub fn link_claim() -> Self {
+        Self {
+            max_requests: 20,
+            window_seconds: 3600, // 1 hour
+        }
+    }
+
     /// Link listing: 200 per hour
     /// Increased from 100 to handle frequent dashboard refreshes
     #[allow(dead_code)] // TODO: Apply to link listing endpoint
@@ -99,6 +185,56 @@ impl RateLimitConfig {
         }
     }
 
+    /// Public link existence probe: 120 per minute per IP
+    pub fn link_probe() -> Self {
+        Self {
+            max_requests: 120,
+            window_seconds: 60, // 1 minute
+        }
+    }
+
+    /// Public link metadata for share previews: 60 per minute per IP
+    pub fn public_link_metadata() -> Self {
+        Self {
+            max_requests: 60,
+            window_seconds: 60, // 1 minute
+        }
+    }
+
+    /// Short code availability check: 30 per minute per IP. Enforced even
+    /// when KV rate limiting is disabled, as the endpoint is an existence oracle
+    pub fn code_check() -> Self {
+        Self {
+            max_requests: 30,
+            window_seconds: 60, // 1 minute
+        }
+    }
+
+    /// Destination blacklist pre-check from the link editor: 60 per minute per user
+    pub fn destination_check() -> Self {
+        Self {
+            max_requests: 60,
+            window_seconds: 60, // 1 minute
+        }
+    }
+
+    /// Anonymous abuse reports: `max_per_hour` per IP. Enforced even when KV
+    /// rate limiting is disabled, so one client cannot flood the report queue
+    pub fn report_submission(max_per_hour: u32) -> Self {
+        Self {
+            max_requests: max_per_hour,
+            window_seconds: 3600, // 1 hour
+        }
+    }
+
+    /// Public auth config (/api/auth/config): 60 per minute per IP
+    pub fn auth_config() -> Self {
+        Self {
+            max_requests: 60,
+            window_seconds: 60, // 1 minute
+        }
+    }
+
     /// Public redirects: 300 per minute per IP
     /// Increased from 100 to handle legitimate high-traffic scenarios
     pub fn redirect() -> Self {
@@ -117,8 +253,8 @@ pub enum RateLimitError {
         /// How many seconds until the rate limit resets
         retry_after: u64,
     },
-    /// Internal error checking rate limit
-    #[allow(dead_code)] // Error message used in Display impl
+    /// Internal error checking rate limit (KV unavailable). Never returned by
+    /// `RateLimiter::check`, which fails open on this variant.
     Internal(String),
 }
 
@@ -151,23 +287,100 @@ impl RateLimiter {
     /// * `kv` - KV store for tracking rate limits
     /// * `key` - Unique identifier for rate limit (e.g., "ratelimit:oauth:{ip}")
     /// * `config` - Rate limit configuration
-    /// * `kv_rate_limiting_enabled` - Whether KV rate limiting is enabled (from environment)
+    /// * `settings` - KV toggle and trusted IP ranges (from environment)
+    /// * `client_ip` - Caller's IP from `get_client_ip`, checked against trusted ranges
     ///
     /// # Returns
     ///
-    /// Ok(()) if request is allowed, Err(RateLimitError) if rate limit exceeded
+    /// Ok(()) if request is allowed, Err(RateLimitError::Exceeded) if rate limit exceeded.
+    /// KV errors are logged and treated as allowed (fail-open).
     pub async fn check(
         kv: &KvStore,
         key: &str,
         config: &RateLimitConfig,
-        kv_rate_limiting_enabled: bool,
+        settings: &RateLimitSettings,
+        client_ip: &str,
+    ) -> std::result::Result<(), RateLimitError> {
+        Self::check_n(kv, key, config, settings, client_ip, 1).await
+    }
+
+    /// Like `check`, but the request uses `cost` units of the budget at once
+    /// (e.g. one per link of a bulk request). Either all of them fit in the
+    /// current window and are counted, or the request is refused and nothing is.
+    pub async fn check_n(
+        kv: &KvStore,
+        key: &str,
+        config: &RateLimitConfig,
+        settings: &RateLimitSettings,
+        client_ip: &str,
+        cost: u32,
     ) -> std::result::Result<(), RateLimitError> {
         // Check if KV-based rate limiting is disabled
-        if !kv_rate_limiting_enabled {
+        if !settings.kv_enabled {
             // KV rate limiting is disabled, allow all requests
             return Ok(());
         }
 
+        if settings.is_trusted(client_ip) {
+            console_log!(
+                "{}",
+                serde_json::json!({
+                    "event": "rate_limit_bypass_trusted_ip",
+                    "limiter": Self::key_prefix(key),
+                    "level": "debug"
+                })
+            );
+            return Ok(());
+        }
+
+        let outcome = Self::check_kv(kv, key, config, cost).await;
+
+        if let Err(RateLimitError::Internal(ref error)) = outcome {
+            // Only log the limiter name (e.g. "redirect"), never the IP/user part of the key
+            console_log!(
+                "{}",
+                serde_json::json!({
+                    "event": "rate_limit_kv_error",
+                    "limiter": Self::key_prefix(key),
+                    "error": error,
+                    "action": "fail_open",
+                    "level": "warn"
+                })
+            );
+        }
+
+        Self::fail_open(outcome)
+    }
+
+    /// Map the outcome of a KV-backed check to the final decision.
+    ///
+    /// `Exceeded` is preserved; `Internal` (KV unavailable) is downgraded to
+    /// `Ok(())` so the request is served.
+    fn fail_open(
+        outcome: std::result::Result<(), RateLimitError>,
+    ) -> std::result::Result<(), RateLimitError> {
+        match outcome {
+            Err(RateLimitError::Internal(_)) => Ok(()),
+            other => other,
+        }
+    }
+
+    /// Extract the limiter name from a key produced by `ip_key`/`user_key`/`session_key`
+    fn key_prefix(key: &str) -> &str {
+        key.strip_prefix("ratelimit:")
+            .and_then(|rest| rest.split(':').next())
+            .unwrap_or("unknown")
+    }
+
+    /// Read, evaluate and persist the rate limit window in KV.
+    ///
+    /// Distinguishes "limit exceeded" (`Exceeded`) from KV failures (`Internal`).
+    async fn check_kv(
+        kv: &KvStore,
+        key: &str,
+        config: &RateLimitConfig,
+        cost: u32,
+    ) -> std::result::Result<(), RateLimitError> {
         let now = Self::current_timestamp();
 
         // Get existing rate limit data
@@ -184,21 +397,49 @@ impl RateLimiter {
             }
         };
 
+        let new_data = Self::next_window(existing_data, now, config, cost)?;
+
+        let value = serde_json::to_string(&new_data)
+            .map_err(|e| RateLimitError::Internal(format!("Failed to serialize: {}", e)))?;
+
+        // Store with TTL equal to window duration
+        if kv
+            .put(key, value)
+            .map_err(|e| RateLimitError::Internal(format!("Failed to put: {}", e)))?
+            .expiration_ttl(config.window_seconds)
+            .execute()
+            .await
+            .is_err()
+        {
+            return Err(RateLimitError::Internal("Failed to store".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Compute the next window state after a request costing `cost`, or
+    /// `Exceeded` if it would go over the limit
+    fn next_window(
+        existing_data: Option<RateLimitData>,
+        now: u64,
+        config: &RateLimitConfig,
+        cost: u32,
+    ) -> std::result::Result<RateLimitData, RateLimitError> {
         // Calculate new rate limit state
         let (new_count, window_start) = match existing_data {
             Some(data) => {
                 // Check if we're still in the same window
                 if now - data.window_start < config.window_seconds {
                     // Same window, increment count
-                    (data.count + 1, data.window_start)
+                    (data.count.saturating_add(cost), data.window_start)
                 } else {
                     // New window, reset count
-                    (1, now)
+                    (cost, now)
                 }
             }
             None => {
                 // First request, start new window
-                (1, now)
+                (cost, now)
             }
         };
 
@@ -208,28 +449,10 @@ impl RateLimiter {
             return Err(RateLimitError::Exceeded { retry_after });
         }
 
-        // Update rate limit data in KV
-        let new_data = RateLimitData {
+        Ok(RateLimitData {
             count: new_count,
             window_start,
-        };
-
-        let value = serde_json::to_string(&new_data)
-            .map_err(|e| RateLimitError::Internal(format!("Failed to serialize: {}", e)))?;
-
-        // Store with TTL equal to window duration
-        if kv
-            .put(key, value)
-            .map_err(|e| RateLimitError::Internal(format!("Failed to put: {}", e)))?
-            .expiration_ttl(config.window_seconds)
-            .execute()
-            .await
-            .is_err()
-        {
-            return Err(RateLimitError::Internal("Failed to store".to_string()));
-        }
-
-        Ok(())
+        })
     }
 
     /// Get current timestamp in seconds
@@ -247,6 +470,11 @@ impl RateLimiter {
         format!("ratelimit:{}:user:{}", prefix, user_id)
     }
 
+    /// Generate rate limit key for org-based limiting
+    pub fn org_key(prefix: &str, org_id: &str) -> String {
+        format!("ratelimit:{}:org:{}", prefix, org_id)
+    }
+
     /// Generate rate limit key for session-based limiting
     pub fn session_key(prefix: &str, session_id: &str) -> String {
         format!("ratelimit:{}:session:{}", prefix, session_id)
@@ -278,6 +506,10 @@ mod tests {
             RateLimiter::user_key("links", "user123"),
             "ratelimit:links:user:user123"
         );
+        assert_eq!(
+            RateLimiter::org_key("create_link", "org789"),
+            "ratelimit:create_link:org:org789"
+        );
         assert_eq!(
             RateLimiter::session_key("refresh", "sess456"),
             "ratelimit:refresh:session:sess456"
@@ -297,4 +529,125 @@ mod tests {
         assert_eq!(internal.to_error_response(), "Failed to check rate limit");
         assert_eq!(internal.retry_after(), None);
     }
+
+    #[test]
+    fn test_kv_error_fails_open() {
+        // A KV read/write failure must not block the request
+        let kv_error = Err(RateLimitError::Internal(
+            "Failed to read rate limit data".to_string(),
+        ));
+        assert!(RateLimiter::fail_open(kv_error).is_ok());
+
+        // An exceeded limit is still enforced
+        let exceeded = Err(RateLimitError::Exceeded { retry_after: 30 });
+        assert!(matches!(
+            RateLimiter::fail_open(exceeded),
+            Err(RateLimitError::Exceeded { retry_after: 30 })
+        ));
+
+        assert!(RateLimiter::fail_open(Ok(())).is_ok());
+    }
+
+    #[test]
+    fn test_next_window_distinguishes_exceeded() {
+        let config = RateLimitConfig {
+            max_requests: 2,
+            window_seconds: 60,
+        };
+
+        let first = RateLimiter::next_window(None, 1000, &config, 1).unwrap();
+        assert_eq!(first.count, 1);
+        assert_eq!(first.window_start, 1000);
+
+        let second = RateLimiter::next_window(Some(first), 1010, &config, 1).unwrap();
+        assert_eq!(second.count, 2);
+
+        let third = RateLimiter::next_window(Some(second), 1020, &config, 1);
+        assert!(matches!(
+            third,
+            Err(RateLimitError::Exceeded { retry_after: 40 })
+        ));
+
+        // Window expired: counter resets
+        let stale = RateLimitData {
+            count: 2,
+            window_start: 1000,
+        };
+        let reset = RateLimiter::next_window(Some(stale), 1060, &config, 1).unwrap();
+        assert_eq!(reset.count, 1);
+        assert_eq!(reset.window_start, 1060);
+    }
+
+    #[test]
+    fn test_next_window_charges_cost_all_or_nothing() {
+        let config = RateLimitConfig {
+            max_requests: 10,
+            window_seconds: 60,
+        };
+
+        let first = RateLimiter::next_window(None, 1000, &config, 8).unwrap();
+        assert_eq!(first.count, 8);
+
+        // 3 more do not fit in the 2 left, so the whole request is refused
+        let stored = RateLimitData {
+            count: first.count,
+            window_start: first.window_start,
+        };
+        assert!(matches!(
+            RateLimiter::next_window(Some(stored), 1010, &config, 3),
+            Err(RateLimitError::Exceeded { .. })
+        ));
+        let exact = RateLimiter::next_window(Some(first), 1010, &config, 2).unwrap();
+        assert_eq!(exact.count, 10);
+
+        assert!(RateLimiter::next_window(None, 1000, &config, 11).is_err());
+    }
+
+    #[test]
+    fn test_org_link_budget_is_shared_and_separate_from_user_budget() {
+        let org = RateLimitConfig::org_link_creation(1);
+        assert_eq!(org.window_seconds, 3600);
+
+        // Two members share the org key: the second link of the hour is refused
+        let first = RateLimiter::next_window(None, 1000, &org, 1).unwrap();
+        assert!(matches!(
+            RateLimiter::next_window(Some(first), 1001, &org, 1),
+            Err(RateLimitError::Exceeded { retry_after: 3599 })
+        ));
+
+        // Each member's own budget is unaffected
+        let user = RateLimitConfig::link_creation();
+        assert!(RateLimiter::next_window(None, 1001, &user, 1).is_ok());
+        assert_ne!(
+            RateLimiter::org_key("create_link", "org-1"),
+            RateLimiter::user_key("create_link", "org-1")
+        );
+    }
+
+    #[test]
+    fn test_key_prefix_hides_identifier() {
+        assert_eq!(
+            RateLimiter::key_prefix("ratelimit:redirect:1.2.3.4"),
+            "redirect"
+        );
+        assert_eq!(
+            RateLimiter::key_prefix("ratelimit:links:user:user123"),
+            "links"
+        );
+        assert_eq!(RateLimiter::key_prefix("garbage"), "unknown");
+    }
+
+    #[test]
+    fn test_trusted_ip_settings() {
+        let settings = RateLimitSettings {
+            kv_enabled: true,
+            trusted_ips: parse_cidr_list("10.0.0.0/8, 2001:db8::/32").0,
+        };
+        assert!(settings.is_trusted("10.20.30.40"));
+        assert!(settings.is_trusted("2001:db8::1"));
+        assert!(!settings.is_trusted("203.0.113.5"));
+        assert!(!settings.is_trusted("unknown"));
+        assert!(!RateLimitSettings::default().is_trusted("10.0.0.1"));
+        assert!(RateLimitSettings::default().always_on().kv_enabled);
+    }
 }
@@ -1,7 +1,67 @@
 use crate::models::Link;
+use crate::models::link::LinkStatus;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use utoipa::ToSchema;
 
+/// Bucket size for `clicks_over_time`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Granularity {
+    Hour,
+    Day,
+    Week,
+    Month,
+}
+
+/// Widest range accepted with hourly buckets (31 days = 744 buckets)
+pub const MAX_HOURLY_RANGE_SECS: i64 = 31 * 24 * 60 * 60;
+
+impl Granularity {
+    /// SQLite expression turning `local_ts` (a unix timestamp already shifted
+    /// to the viewer's offset) into the bucket label: `2024-01-15T09:00`,
+    /// `2024-01-15`, the Monday starting the week, or `2024-01`.
+    pub fn bucket_sql(self, local_ts: &str) -> String {
+        match self {
+            Granularity::Hour => format!("strftime('%Y-%m-%dT%H:00', {}, 'unixepoch')", local_ts),
+            Granularity::Day => format!("date({}, 'unixepoch')", local_ts),
+            Granularity::Week => format!("date({}, 'unixepoch', 'weekday 0', '-6 days')", local_ts),
+            Granularity::Month => format!("strftime('%Y-%m', {}, 'unixepoch')", local_ts),
+        }
+    }
+
+    /// Nominal bucket length in seconds (months averaged), used to estimate
+    /// how many buckets a window spans
+    pub fn approx_secs(self) -> i64 {
+        match self {
+            Granularity::Hour => 60 * 60,
+            Granularity::Day => 24 * 60 * 60,
+            Granularity::Week => 7 * 24 * 60 * 60,
+            Granularity::Month => 2_629_746, // 30.44 days
+        }
+    }
+}
+
+/// Most points `max_points` may request for a downsampled series
+pub const MAX_SERIES_POINTS: u32 = 1000;
+
+/// Interval length, in whole seconds, to split the inclusive `[start, end]`
+/// window into at most `max_points` evenly-spaced buckets. None when the
+/// window already spans no more than `max_points` buckets at `granularity`,
+/// so the full-resolution series is kept.
+pub fn downsample_interval_secs(
+    granularity: Granularity,
+    start: i64,
+    end: i64,
+    max_points: u32,
+) -> Option<i64> {
+    let span = end - start + 1;
+    let natural_buckets = (span + granularity.approx_secs() - 1) / granularity.approx_secs();
+    if max_points == 0 || natural_buckets <= max_points as i64 {
+        return None;
+    }
+    Some((span + max_points as i64 - 1) / max_points as i64)
+}
+
 /// Time range specification for analytics queries
 /// Extensible design that can grow to support custom ranges, timezones, comparisons, etc.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
@@ -91,6 +151,31 @@ mod tests {
         assert_eq!(calculated_end, end);
     }
 
+    #[test]
+    fn test_downsample_interval_secs() {
+        let day = 24 * 60 * 60;
+        let start = test_timestamp();
+
+        // A week of daily buckets fits under the cap
+        assert_eq!(
+            downsample_interval_secs(Granularity::Day, start, start + 7 * day - 1, 52),
+            None
+        );
+
+        // A year of days is split into at most 52 intervals covering the window
+        let end = start + 365 * day - 1;
+        let width = downsample_interval_secs(Granularity::Day, start, end, 52).unwrap();
+        let buckets = (end - start) / width + 1;
+        assert!(buckets <= 52, "{} buckets", buckets);
+        assert!(width * 52 > end - start);
+
+        // Hourly buckets over two days exceed a cap of 24
+        assert_eq!(
+            downsample_interval_secs(Granularity::Hour, start, start + 2 * day - 1, 24),
+            Some(2 * 60 * 60)
+        );
+    }
+
     #[test]
     fn test_time_range_json_serialization() {
         let time_range = TimeRange::Days { value: 30 };
@@ -487,6 +572,31 @@ mod tests {
             assert_eq!(expected_start, mock_now - 86400);
         }
     }
+
+    #[test]
+    fn test_complete_status_breakdown_zero_fills_in_status_order() {
+        let counts = vec![
+            StatusClickCount {
+                status: "blocked".to_string(),
+                clicks: 7,
+                links: 1,
+            },
+            StatusClickCount {
+                status: "active".to_string(),
+                clicks: 3,
+                links: 2,
+            },
+        ];
+        let breakdown = complete_status_breakdown(counts);
+        let summary: Vec<(&str, i64, i64)> = breakdown
+            .iter()
+            .map(|c| (c.status.as_str(), c.clicks, c.links))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("active", 3, 2), ("disabled", 0, 0), ("blocked", 7, 1)]
+        );
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -499,6 +609,19 @@ pub struct AnalyticsEvent {
     pub user_agent: Option<String>,
     pub country: Option<String>,
     pub city: Option<String>,
+    /// Alias code the click came through; None for the link's own code
+    #[serde(default)]
+    pub alias_code: Option<String>,
+    /// Clicks this stored event stands for (1 unless sampled past the soft cap)
+    #[serde(default = "default_sample_rate")]
+    pub sample_rate: i64,
+    /// Values of the org's allowlisted request headers, stored as JSON
+    #[serde(default)]
+    pub extra: Option<BTreeMap<String, String>>,
+}
+
+fn default_sample_rate() -> i64 {
+    1
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -517,6 +640,37 @@ pub struct ReferrerCount {
     pub count: i64,
 }
 
+/// One raw click event of a link (`GET /api/links/{id}/events`)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LinkEvent {
+    #[schema(example = 1042)]
+    pub id: i64,
+    /// Unix timestamp of the click
+    #[schema(example = 1705312800)]
+    pub timestamp: i64,
+    #[schema(example = "https://google.com")]
+    pub referrer: Option<String>,
+    #[schema(example = "US")]
+    pub country: Option<String>,
+    #[schema(example = "San Francisco")]
+    pub city: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+/// One raw click event in an org analytics export
+#[derive(Debug, Clone)]
+pub struct ExportedAnalyticsEvent {
+    pub id: i64,
+    pub timestamp: i64,
+    pub link_id: String,
+    /// Empty when the link row no longer exists
+    pub link_short_code: String,
+    pub referrer: Option<String>,
+    pub user_agent: Option<String>,
+    pub country: Option<String>,
+    pub city: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CountryCount {
     #[schema(example = "US")]
@@ -525,6 +679,110 @@ pub struct CountryCount {
     pub count: i64,
 }
 
+/// Clicks on links that currently have a given status
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StatusClickCount {
+    #[schema(example = "blocked")]
+    pub status: String,
+    #[schema(example = 1200)]
+    pub clicks: i64,
+    /// Distinct links with at least one click in the window
+    #[schema(example = 3)]
+    pub links: i64,
+}
+
+/// Link statuses reported by the clicks-by-status breakdown, in display order
+const BREAKDOWN_STATUSES: [LinkStatus; 3] = [
+    LinkStatus::Active,
+    LinkStatus::Disabled,
+    LinkStatus::Blocked,
+];
+
+/// One row per link status, in `BREAKDOWN_STATUSES` order, with statuses
+/// that got no clicks filled in as zero
+pub fn complete_status_breakdown(counts: Vec<StatusClickCount>) -> Vec<StatusClickCount> {
+    BREAKDOWN_STATUSES
+        .iter()
+        .map(|status| {
+            counts
+                .iter()
+                .find(|c| c.status == status.as_str())
+                .map(|c| StatusClickCount {
+                    status: c.status.clone(),
+                    clicks: c.clicks,
+                    links: c.links,
+                })
+                .unwrap_or_else(|| StatusClickCount {
+                    status: status.as_str().to_string(),
+                    clicks: 0,
+                    links: 0,
+                })
+        })
+        .collect()
+}
+
+/// Instance-wide clicks split by the link's current status
+/// (`GET /api/admin/analytics/by-status`)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ClicksByStatusResponse {
+    /// Window start (Unix seconds, inclusive)
+    #[schema(example = 1609459200)]
+    pub start: i64,
+    /// Window end (Unix seconds, inclusive)
+    #[schema(example = 1612137600)]
+    pub end: i64,
+    #[schema(example = 1500)]
+    pub total_clicks: i64,
+    pub statuses: Vec<StatusClickCount>,
+}
+
+/// Org-wide top countries (`GET /api/orgs/{id}/analytics/top-countries`)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrgTopCountriesResponse {
+    /// Window start (Unix seconds), after tier retention is applied
+    #[schema(example = 1609459200)]
+    pub start: i64,
+    /// Window end (Unix seconds)
+    #[schema(example = 1612137600)]
+    pub end: i64,
+    pub countries: Vec<CountryCount>,
+    /// Whether the window was clamped by the tier's retention limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub analytics_gated: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gated_reason: Option<String>,
+}
+
+/// Clicks recorded with one value of a request header
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HeaderValueCount {
+    #[schema(example = "acme")]
+    pub value: String,
+    #[schema(example = 25)]
+    pub count: i64,
+}
+
+/// Org-wide clicks by recorded header value
+/// (`GET /api/orgs/{id}/analytics/header-fields`)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrgHeaderFieldBreakdownResponse {
+    /// Lowercased header name
+    #[schema(example = "x-tenant")]
+    pub field: String,
+    /// Window start (Unix seconds), after tier retention is applied
+    #[schema(example = 1609459200)]
+    pub start: i64,
+    /// Window end (Unix seconds)
+    #[schema(example = 1612137600)]
+    pub end: i64,
+    pub values: Vec<HeaderValueCount>,
+    /// Whether the window was clamped by the tier's retention limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub analytics_gated: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gated_reason: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UserAgentCount {
     #[schema(example = "Mozilla/5.0...")]
@@ -565,6 +823,39 @@ pub struct OrgAnalyticsResponse {
     /// Reason analytics are gated (e.g., "click_limit_exceeded", "retention_limited")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gated_reason: Option<String>,
+    /// Previous-period comparison, present when requested with `compare=previous`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comparison: Option<PeriodComparison>,
+    /// Whether any raw events in range were sampled, making event-based
+    /// counts estimates rather than exact figures
+    #[schema(example = false)]
+    pub sampled: bool,
+    /// Largest sample rate among raw events in range (1 when none were sampled)
+    #[schema(example = 1)]
+    pub sample_rate: i64,
+}
+
+/// Total clicks for the requested window next to the immediately preceding
+/// window of the same length (`?compare=previous`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PeriodComparison {
+    /// Previous window start (Unix seconds), after tier retention is applied
+    #[schema(example = 1608854400)]
+    pub previous_start: i64,
+    /// Previous window end (Unix seconds, inclusive)
+    #[schema(example = 1609459199)]
+    pub previous_end: i64,
+    #[schema(example = 150)]
+    pub current_total_clicks: i64,
+    #[schema(example = 120)]
+    pub previous_total_clicks: i64,
+    /// Percentage change in total clicks (one decimal). Null when the
+    /// previous window had no clicks.
+    #[schema(example = 25.0)]
+    pub total_clicks_change_pct: Option<f64>,
+    /// Whether the previous window was clamped by the tier's retention limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_gated: Option<bool>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -582,4 +873,22 @@ pub struct LinkAnalyticsResponse {
     /// Reason analytics are gated (e.g., "click_limit_exceeded", "retention_limited")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gated_reason: Option<String>,
+    /// Previous-period comparison, present when requested with `compare=previous`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comparison: Option<PeriodComparison>,
+    /// Largest sample rate among raw events in range. Above 1 the link passed
+    /// its analytics soft cap and only 1 in N clicks was stored; event-based
+    /// counts are already scaled by each event's rate, while `link.click_count`
+    /// is always exact.
+    #[schema(example = 1)]
+    pub sample_rate: i64,
+    /// Whether any raw events in range were sampled (`sample_rate` above 1),
+    /// making event-based counts estimates
+    #[schema(example = false)]
+    pub sampled: bool,
+    /// Length in seconds of each `clicks_over_time` bucket when the series was
+    /// downsampled to `max_points`. Buckets then start at the window start and
+    /// are labelled with the date (or hour, for hourly granularity) they begin on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bucket_seconds: Option<i64>,
 }
@@ -0,0 +1,210 @@
+use crate::models::Tier;
+use std::collections::HashMap;
+
+/// Sample rate used when `analytics_sample_rate` is unset or invalid
+pub const DEFAULT_ANALYTICS_SAMPLE_RATE: i64 = 10;
+/// Largest configurable sample rate (store 1 in N)
+pub const MAX_ANALYTICS_SAMPLE_RATE: i64 = 1000;
+
+/// Prefix of the per-tier cap settings, e.g. `analytics_event_soft_cap_pro`
+pub const TIER_SOFT_CAP_KEY_PREFIX: &str = "analytics_event_soft_cap_";
+
+/// Tiers that can carry their own soft cap
+const CAPPED_TIERS: [Tier; 4] = [Tier::Free, Tier::Pro, Tier::Business, Tier::Unlimited];
+
+/// Soft cap on raw analytics events per link. Once a link has this many
+/// clicks, only 1 in `sample_rate` further clicks is stored as a raw event
+/// (tagged with its sample rate so aggregates can be scaled back up);
+/// `click_count` and the monthly counters still count every click.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnalyticsSamplingPolicy {
+    /// Instance-wide clicks per link recorded in full (None = no sampling)
+    pub soft_cap: Option<i64>,
+    /// Per-tier overrides of `soft_cap` (None = no sampling for that tier)
+    pub tier_caps: Vec<(Tier, Option<i64>)>,
+    /// Store 1 in N raw events beyond the cap
+    pub sample_rate: i64,
+}
+
+/// Parse a cap setting: Some(None) for an explicit 0 (off), None when invalid
+fn parse_cap(value: &str) -> Option<Option<i64>> {
+    value
+        .parse::<i64>()
+        .ok()
+        .filter(|cap| *cap >= 0)
+        .map(|cap| (cap > 0).then_some(cap))
+}
+
+impl AnalyticsSamplingPolicy {
+    /// Read the policy from settings. `analytics_event_soft_cap_<tier>`
+    /// overrides `analytics_event_soft_cap` for that tier. None when every
+    /// cap is 0 or unset, i.e. every click is stored.
+    pub fn from_settings(settings: &HashMap<String, String>) -> Option<Self> {
+        let soft_cap = settings
+            .get("analytics_event_soft_cap")
+            .and_then(|v| parse_cap(v))
+            .flatten();
+        let tier_caps = CAPPED_TIERS
+            .into_iter()
+            .filter_map(|tier| {
+                let key = format!("{}{}", TIER_SOFT_CAP_KEY_PREFIX, tier.as_str());
+                settings
+                    .get(&key)
+                    .and_then(|v| parse_cap(v))
+                    .map(|cap| (tier, cap))
+            })
+            .collect();
+        let sample_rate = settings
+            .get("analytics_sample_rate")
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|rate| (2..=MAX_ANALYTICS_SAMPLE_RATE).contains(rate))
+            .unwrap_or(DEFAULT_ANALYTICS_SAMPLE_RATE);
+        let policy = Self {
+            soft_cap,
+            tier_caps,
+            sample_rate,
+        };
+        policy.min_soft_cap().is_some().then_some(policy)
+    }
+
+    /// Cap for links of an org on `tier` (the instance cap when the tier is
+    /// unknown or has no override)
+    pub fn soft_cap_for(&self, tier: Option<&Tier>) -> Option<i64> {
+        tier.and_then(|tier| self.tier_caps.iter().find(|(t, _)| t == tier))
+            .map(|(_, cap)| *cap)
+            .unwrap_or(self.soft_cap)
+    }
+
+    /// Lowest cap of any tier. Links below it are never sampled, so the
+    /// redirect path only looks up a link's tier past this point.
+    pub fn min_soft_cap(&self) -> Option<i64> {
+        self.tier_caps
+            .iter()
+            .map(|(_, cap)| *cap)
+            .chain([self.soft_cap])
+            .flatten()
+            .min()
+    }
+
+    /// Sample rate to store with the raw event for a click on a link (of an
+    /// org on `tier`) that already had `prior_clicks`, or None when the raw
+    /// event is skipped.
+    pub fn sample_rate_for(&self, tier: Option<&Tier>, prior_clicks: i64) -> Option<i64> {
+        let Some(soft_cap) = self.soft_cap_for(tier) else {
+            return Some(1);
+        };
+        if prior_clicks < soft_cap {
+            return Some(1);
+        }
+        ((prior_clicks - soft_cap) % self.sample_rate == 0).then_some(self.sample_rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_from_settings() {
+        assert_eq!(AnalyticsSamplingPolicy::from_settings(&settings(&[])), None);
+        assert_eq!(
+            AnalyticsSamplingPolicy::from_settings(&settings(&[("analytics_event_soft_cap", "0")])),
+            None
+        );
+        assert_eq!(
+            AnalyticsSamplingPolicy::from_settings(&settings(&[
+                ("analytics_event_soft_cap", "500"),
+                ("analytics_sample_rate", "1"),
+            ])),
+            Some(AnalyticsSamplingPolicy {
+                soft_cap: Some(500),
+                tier_caps: vec![],
+                sample_rate: DEFAULT_ANALYTICS_SAMPLE_RATE,
+            })
+        );
+        assert_eq!(
+            AnalyticsSamplingPolicy::from_settings(&settings(&[
+                ("analytics_event_soft_cap", "500"),
+                ("analytics_sample_rate", "100"),
+            ])),
+            Some(AnalyticsSamplingPolicy {
+                soft_cap: Some(500),
+                tier_caps: vec![],
+                sample_rate: 100,
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_settings_reads_tier_caps() {
+        let policy = AnalyticsSamplingPolicy::from_settings(&settings(&[
+            ("analytics_event_soft_cap", "0"),
+            ("analytics_event_soft_cap_free", "1000"),
+            ("analytics_event_soft_cap_unlimited", "0"),
+            ("analytics_event_soft_cap_pro", "lots"),
+        ]))
+        .unwrap();
+        assert_eq!(policy.soft_cap, None);
+        assert_eq!(
+            policy.tier_caps,
+            vec![(Tier::Free, Some(1000)), (Tier::Unlimited, None)]
+        );
+        assert_eq!(policy.soft_cap_for(Some(&Tier::Free)), Some(1000));
+        assert_eq!(policy.soft_cap_for(Some(&Tier::Pro)), None);
+        assert_eq!(policy.soft_cap_for(None), None);
+        assert_eq!(policy.min_soft_cap(), Some(1000));
+    }
+
+    #[test]
+    fn test_tier_cap_overrides_instance_cap() {
+        let policy = AnalyticsSamplingPolicy {
+            soft_cap: Some(100),
+            tier_caps: vec![(Tier::Business, Some(5)), (Tier::Unlimited, None)],
+            sample_rate: 2,
+        };
+        assert_eq!(policy.min_soft_cap(), Some(5));
+        assert_eq!(policy.sample_rate_for(Some(&Tier::Business), 5), Some(2));
+        assert_eq!(policy.sample_rate_for(Some(&Tier::Business), 6), None);
+        // Tiers without an override, and unknown tiers, use the instance cap
+        assert_eq!(policy.sample_rate_for(Some(&Tier::Free), 50), Some(1));
+        assert_eq!(policy.sample_rate_for(None, 100), Some(2));
+        // A tier with sampling turned off stores every event
+        assert_eq!(
+            policy.sample_rate_for(Some(&Tier::Unlimited), 1_000_000),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_sampling_beyond_cap() {
+        let policy = AnalyticsSamplingPolicy {
+            soft_cap: Some(3),
+            tier_caps: vec![],
+            sample_rate: 2,
+        };
+        let stored: Vec<Option<i64>> = (0..8).map(|n| policy.sample_rate_for(None, n)).collect();
+        assert_eq!(
+            stored,
+            vec![
+                Some(1),
+                Some(1),
+                Some(1),
+                Some(2),
+                None,
+                Some(2),
+                None,
+                Some(2)
+            ]
+        );
+        // Summing stored rates estimates the 8 clicks to within one sample
+        let estimated: i64 = stored.iter().flatten().sum();
+        assert_eq!(estimated, 9);
+    }
+}
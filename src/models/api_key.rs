@@ -0,0 +1,317 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Permission granted to an API key.
+///
+/// Keys created without explicit scopes (including every key that predates
+/// scopes) get `Admin`, which keeps their full access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum ApiKeyScope {
+    #[serde(rename = "links:read")]
+    LinksRead,
+    #[serde(rename = "links:write")]
+    LinksWrite,
+    #[serde(rename = "analytics:read")]
+    AnalyticsRead,
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+impl ApiKeyScope {
+    pub const ALL: [ApiKeyScope; 4] = [
+        ApiKeyScope::LinksRead,
+        ApiKeyScope::LinksWrite,
+        ApiKeyScope::AnalyticsRead,
+        ApiKeyScope::Admin,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyScope::LinksRead => "links:read",
+            ApiKeyScope::LinksWrite => "links:write",
+            ApiKeyScope::AnalyticsRead => "analytics:read",
+            ApiKeyScope::Admin => "admin",
+        }
+    }
+
+    pub fn from_str_value(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|scope| scope.as_str() == s)
+    }
+
+    /// Whether holding `self` is enough for an endpoint requiring `required`.
+    /// `admin` grants everything and `links:write` implies `links:read`.
+    pub fn grants(&self, required: ApiKeyScope) -> bool {
+        *self == required
+            || *self == ApiKeyScope::Admin
+            || (*self == ApiKeyScope::LinksWrite && required == ApiKeyScope::LinksRead)
+    }
+}
+
+/// Parse the requested scope names, rejecting unknown ones and dropping
+/// duplicates. An empty list yields `[Admin]`.
+pub fn parse_scopes(names: &[String]) -> Result<Vec<ApiKeyScope>, String> {
+    let mut scopes = Vec::new();
+    for name in names {
+        let scope = ApiKeyScope::from_str_value(name.trim()).ok_or_else(|| {
+            format!(
+                "Unknown API key scope '{}'. Allowed scopes: {}",
+                name,
+                ApiKeyScope::ALL.map(|s| s.as_str()).join(", ")
+            )
+        })?;
+        if !scopes.contains(&scope) {
+            scopes.push(scope);
+        }
+    }
+    if scopes.is_empty() {
+        scopes.push(ApiKeyScope::Admin);
+    }
+    Ok(scopes)
+}
+
+/// Decode the comma-separated `api_keys.scopes` column. `NULL` (keys created
+/// before scopes existed) and unreadable values fall back to `[Admin]`.
+pub fn scopes_from_column(value: Option<&str>) -> Vec<ApiKeyScope> {
+    let scopes: Vec<ApiKeyScope> = value
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|s| ApiKeyScope::from_str_value(s.trim()))
+        .collect();
+    if scopes.is_empty() {
+        vec![ApiKeyScope::Admin]
+    } else {
+        scopes
+    }
+}
+
+/// Encode scopes for the `api_keys.scopes` column.
+pub fn scopes_to_column(scopes: &[ApiKeyScope]) -> String {
+    scopes
+        .iter()
+        .map(|s| s.as_str())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Minimum seconds between two `last_used_at` writes for the same key, so a
+/// busy key does not turn every request into a D1 write.
+pub const API_KEY_LAST_USED_DEBOUNCE_SECS: i64 = 60;
+
+/// Longest configurable inactivity period before keys are auto-revoked (10 years)
+pub const MAX_API_KEY_INACTIVE_DAYS: u32 = 3650;
+
+/// Whether a request at `now` should update the key's `last_used_at`.
+pub fn should_record_key_use(last_used_at: Option<i64>, now: i64) -> bool {
+    last_used_at.is_none_or(|last| now - last >= API_KEY_LAST_USED_DEBOUNCE_SECS)
+}
+
+/// Keys whose last activity is at or before this timestamp count as inactive.
+pub fn inactive_key_cutoff(inactive_days: u32, now: i64) -> i64 {
+    now - i64::from(inactive_days) * 86_400
+}
+
+/// Whether a key has gone unused for `inactive_days` at `now`. A key that
+/// was never used counts from its creation time.
+pub fn is_key_inactive(
+    last_used_at: Option<i64>,
+    created_at: i64,
+    inactive_days: u32,
+    now: i64,
+) -> bool {
+    last_used_at.unwrap_or(created_at) <= inactive_key_cutoff(inactive_days, now)
+}
+
+/// The scope an API key needs for a request, or `None` when any valid key
+/// may call it. Routes not listed here require `admin`, so new endpoints are
+/// closed to restricted keys until they are classified.
+pub fn required_scope(method_is_read: bool, path: &str) -> Option<ApiKeyScope> {
+    let is_analytics = path == "/api/usage"
+        || path.starts_with("/api/analytics/")
+        || path == "/api/tags/analytics"
+        || (path.starts_with("/api/orgs/") && path.contains("/analytics/"))
+        || (path.starts_with("/api/links/")
+            && (path.ends_with("/analytics") || path.contains("/analytics/")))
+        // Raw click events carry referrers, user agents and locations
+        || (path.starts_with("/api/links/") && path.ends_with("/events"))
+        // Resetting clicks can delete analytics, so it needs more than links:write
+        || (path.starts_with("/api/links/") && path.ends_with("/reset-clicks"));
+    if is_analytics {
+        return Some(if method_is_read {
+            ApiKeyScope::AnalyticsRead
+        } else {
+            ApiKeyScope::Admin
+        });
+    }
+
+    let is_links = path == "/api/links"
+        || path.starts_with("/api/links/")
+        || path == "/api/tags"
+        || path.starts_with("/api/tags/")
+        || path == "/api/fetch-title";
+    if is_links {
+        return Some(if method_is_read {
+            ApiKeyScope::LinksRead
+        } else {
+            ApiKeyScope::LinksWrite
+        });
+    }
+
+    if path == "/api/auth/me" && method_is_read {
+        return None;
+    }
+
+    Some(ApiKeyScope::Admin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_round_trip() {
+        for scope in ApiKeyScope::ALL {
+            assert_eq!(ApiKeyScope::from_str_value(scope.as_str()), Some(scope));
+        }
+        assert_eq!(ApiKeyScope::from_str_value("links:delete"), None);
+    }
+
+    #[test]
+    fn test_grants() {
+        assert!(ApiKeyScope::Admin.grants(ApiKeyScope::LinksWrite));
+        assert!(ApiKeyScope::Admin.grants(ApiKeyScope::AnalyticsRead));
+        assert!(ApiKeyScope::LinksWrite.grants(ApiKeyScope::LinksRead));
+        assert!(!ApiKeyScope::LinksRead.grants(ApiKeyScope::LinksWrite));
+        assert!(!ApiKeyScope::AnalyticsRead.grants(ApiKeyScope::LinksRead));
+        assert!(!ApiKeyScope::LinksWrite.grants(ApiKeyScope::Admin));
+    }
+
+    #[test]
+    fn test_parse_scopes() {
+        assert_eq!(parse_scopes(&[]), Ok(vec![ApiKeyScope::Admin]));
+        assert_eq!(
+            parse_scopes(&[
+                "links:read".to_string(),
+                " analytics:read ".to_string(),
+                "links:read".to_string()
+            ]),
+            Ok(vec![ApiKeyScope::LinksRead, ApiKeyScope::AnalyticsRead])
+        );
+        assert!(
+            parse_scopes(&["links:delete".to_string()])
+                .unwrap_err()
+                .contains("links:delete")
+        );
+    }
+
+    #[test]
+    fn test_column_round_trip_and_legacy_default() {
+        let scopes = vec![ApiKeyScope::LinksRead, ApiKeyScope::AnalyticsRead];
+        assert_eq!(scopes_to_column(&scopes), "links:read,analytics:read");
+        assert_eq!(
+            scopes_from_column(Some("links:read,analytics:read")),
+            scopes
+        );
+        assert_eq!(scopes_from_column(None), vec![ApiKeyScope::Admin]);
+        assert_eq!(scopes_from_column(Some("")), vec![ApiKeyScope::Admin]);
+    }
+
+    #[test]
+    fn test_should_record_key_use_debounces() {
+        assert!(should_record_key_use(None, 1_000));
+        assert!(!should_record_key_use(Some(1_000), 1_000));
+        assert!(!should_record_key_use(Some(1_000), 1_059));
+        assert!(should_record_key_use(Some(1_000), 1_060));
+    }
+
+    #[test]
+    fn test_is_key_inactive_boundaries() {
+        const DAY: i64 = 86_400;
+        const NOW: i64 = 1_700_000_000;
+        // Last used exactly 30 days ago is inactive; one second later is not
+        assert!(is_key_inactive(Some(NOW - 30 * DAY), 0, 30, NOW));
+        assert!(!is_key_inactive(Some(NOW - 30 * DAY + 1), 0, 30, NOW));
+        // Never-used keys count from creation
+        assert!(is_key_inactive(None, NOW - 31 * DAY, 30, NOW));
+        assert!(!is_key_inactive(None, NOW - 29 * DAY, 30, NOW));
+        // Recent use keeps an old key alive
+        assert!(!is_key_inactive(Some(NOW - DAY), NOW - 400 * DAY, 30, NOW));
+    }
+
+    #[test]
+    fn test_required_scope_for_links() {
+        assert_eq!(
+            required_scope(true, "/api/links"),
+            Some(ApiKeyScope::LinksRead)
+        );
+        assert_eq!(
+            required_scope(false, "/api/links"),
+            Some(ApiKeyScope::LinksWrite)
+        );
+        assert_eq!(
+            required_scope(true, "/api/links/export"),
+            Some(ApiKeyScope::LinksRead)
+        );
+        assert_eq!(
+            required_scope(false, "/api/tags/merge"),
+            Some(ApiKeyScope::LinksWrite)
+        );
+    }
+
+    #[test]
+    fn test_required_scope_for_analytics() {
+        assert_eq!(
+            required_scope(true, "/api/links/abc/analytics"),
+            Some(ApiKeyScope::AnalyticsRead)
+        );
+        assert_eq!(
+            required_scope(true, "/api/analytics/org"),
+            Some(ApiKeyScope::AnalyticsRead)
+        );
+        assert_eq!(
+            required_scope(true, "/api/orgs/org-1/analytics/top-countries"),
+            Some(ApiKeyScope::AnalyticsRead)
+        );
+        assert_eq!(
+            required_scope(true, "/api/usage"),
+            Some(ApiKeyScope::AnalyticsRead)
+        );
+        assert_eq!(
+            required_scope(false, "/api/links/abc/reset-clicks"),
+            Some(ApiKeyScope::Admin)
+        );
+    }
+
+    #[test]
+    fn test_required_scope_for_link_events() {
+        assert_eq!(
+            required_scope(true, "/api/links/abc/events"),
+            Some(ApiKeyScope::AnalyticsRead)
+        );
+    }
+
+    #[test]
+    fn test_required_scope_for_link_analytics_export() {
+        assert_eq!(
+            required_scope(true, "/api/links/abc/analytics/export"),
+            Some(ApiKeyScope::AnalyticsRead)
+        );
+        assert!(!ApiKeyScope::LinksRead.grants(ApiKeyScope::AnalyticsRead));
+    }
+
+    #[test]
+    fn test_required_scope_defaults_to_admin() {
+        assert_eq!(required_scope(true, "/api/auth/me"), None);
+        assert_eq!(
+            required_scope(false, "/api/settings/api-keys"),
+            Some(ApiKeyScope::Admin)
+        );
+        assert_eq!(
+            required_scope(true, "/api/orgs/org-1"),
+            Some(ApiKeyScope::Admin)
+        );
+        assert_eq!(
+            required_scope(true, "/api/admin/users"),
+            Some(ApiKeyScope::Admin)
+        );
+    }
+}
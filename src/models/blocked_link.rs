@@ -0,0 +1,66 @@
+/// What the redirect path serves for a link an admin has blocked,
+/// configured by the `blocked_link_response` setting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BlockedLinkResponse {
+    /// Redirect to the generic /404 page, as for a missing code
+    #[default]
+    NotFound,
+    /// 410 Gone with the "removed for policy reasons" page
+    Gone,
+    /// 451 Unavailable For Legal Reasons with the same page
+    Unavailable,
+}
+
+impl BlockedLinkResponse {
+    /// Parse the setting value; unknown values are rejected
+    pub fn from_setting(value: &str) -> Option<Self> {
+        match value {
+            "not_found" => Some(Self::NotFound),
+            "gone" => Some(Self::Gone),
+            "unavailable" => Some(Self::Unavailable),
+            _ => None,
+        }
+    }
+
+    /// Status code of the policy page, or None to fall back to the /404 redirect
+    pub fn status_code(&self) -> Option<u16> {
+        match self {
+            Self::NotFound => None,
+            Self::Gone => Some(410),
+            Self::Unavailable => Some(451),
+        }
+    }
+}
+
+/// Minimal HTML body served for blocked links unless the response is `not_found`
+pub const BLOCKED_LINK_PAGE_HTML: &str = "<!DOCTYPE html>\
+<html lang=\"en\"><head><meta charset=\"utf-8\"><title>Link removed</title>\
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+<meta name=\"robots\" content=\"noindex\"></head>\
+<body style=\"font-family:sans-serif;text-align:center;padding:3rem\">\
+<h1>Link removed</h1>\
+<p>This link has been removed for policy reasons.</p>\
+</body></html>";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocked_link_response_from_setting() {
+        assert_eq!(
+            BlockedLinkResponse::from_setting("not_found"),
+            Some(BlockedLinkResponse::NotFound)
+        );
+        assert_eq!(
+            BlockedLinkResponse::from_setting("gone").and_then(|r| r.status_code()),
+            Some(410)
+        );
+        assert_eq!(
+            BlockedLinkResponse::from_setting("unavailable").and_then(|r| r.status_code()),
+            Some(451)
+        );
+        assert_eq!(BlockedLinkResponse::NotFound.status_code(), None);
+        assert_eq!(BlockedLinkResponse::from_setting("410"), None);
+    }
+}
@@ -0,0 +1,58 @@
+/// Largest configurable click dedup window, in seconds
+pub const MAX_CLICK_DEDUP_WINDOW_SECS: u32 = 3600;
+/// Cloudflare KV rejects expiration TTLs shorter than this
+const MIN_KV_TTL_SECS: u32 = 60;
+
+/// KV key marking that a visitor's click on a link was just counted.
+/// Prefixed so it can never collide with a short code in the same namespace.
+pub fn click_dedup_key(visitor_hash: &str, link_id: &str) -> String {
+    format!("click_dedup:{}:{}", visitor_hash, link_id)
+}
+
+/// Whether a click at `now` repeats one counted at `last_counted_at` within
+/// the window. A window of 0 disables deduplication.
+pub fn is_duplicate_click(last_counted_at: Option<i64>, now: i64, window_secs: u32) -> bool {
+    window_secs > 0
+        && last_counted_at.is_some_and(|last| now >= last && now - last < window_secs as i64)
+}
+
+/// TTL for a dedup marker. KV has a 60s floor, so short windows rely on
+/// the stored timestamp rather than expiry.
+pub fn click_dedup_ttl(window_secs: u32) -> u64 {
+    window_secs.max(MIN_KV_TTL_SECS) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_click_dedup_key_is_namespaced() {
+        assert_eq!(click_dedup_key("a1b2", "link-1"), "click_dedup:a1b2:link-1");
+        assert_ne!(
+            click_dedup_key("a1b2", "link-1"),
+            click_dedup_key("a1b2", "link-2")
+        );
+    }
+
+    #[test]
+    fn test_is_duplicate_click_within_window() {
+        assert!(is_duplicate_click(Some(1_000), 1_000, 30));
+        assert!(is_duplicate_click(Some(1_000), 1_029, 30));
+        assert!(!is_duplicate_click(Some(1_000), 1_030, 30));
+        assert!(!is_duplicate_click(None, 1_000, 30));
+    }
+
+    #[test]
+    fn test_is_duplicate_click_disabled_or_clock_skew() {
+        assert!(!is_duplicate_click(Some(1_000), 1_000, 0));
+        // A marker from the future (clock skew between colos) never suppresses
+        assert!(!is_duplicate_click(Some(1_010), 1_000, 30));
+    }
+
+    #[test]
+    fn test_click_dedup_ttl_respects_kv_minimum() {
+        assert_eq!(click_dedup_ttl(10), 60);
+        assert_eq!(click_dedup_ttl(600), 600);
+    }
+}
@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Bundle format version written by the export and required by the import
+pub const CONFIG_BUNDLE_VERSION: u32 = 1;
+
+/// Instance configuration exported by `GET /api/admin/config/export` and
+/// applied by `POST /api/admin/config/import`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigBundle {
+    pub version: u32,
+    /// Unix timestamp of the export (informational)
+    #[serde(default)]
+    pub exported_at: i64,
+    /// Portable system settings (see `is_portable_setting`)
+    #[serde(default)]
+    pub settings: BTreeMap<String, String>,
+    #[serde(default)]
+    pub blacklist: Vec<BlacklistBundleEntry>,
+}
+
+/// A blacklist entry as carried in a bundle; ids and authorship stay local
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlacklistBundleEntry {
+    pub destination: String,
+    pub match_type: String,
+    #[serde(default)]
+    pub reason: String,
+}
+
+/// How an import combines the bundle with the existing configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigImportMode {
+    /// Add or overwrite what the bundle contains, keep everything else
+    Merge,
+    /// Additionally remove blacklist entries missing from the bundle
+    Replace,
+}
+
+impl ConfigImportMode {
+    pub fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value {
+            None | Some("") | Some("merge") => Ok(Self::Merge),
+            Some("replace") => Ok(Self::Replace),
+            Some(other) => Err(format!(
+                "Invalid mode '{}'. Must be 'merge' or 'replace'",
+                other
+            )),
+        }
+    }
+}
+
+/// Whether a setting travels in a config bundle. Billing product and
+/// discount ids belong to one payment account, and the code length
+/// watermark reflects this instance's namespace usage, so they stay local.
+pub fn is_portable_setting(key: &str) -> bool {
+    key != "system_min_code_length"
+        && !key.starts_with("product_")
+        && !key.starts_with("active_discount_")
+}
+
+impl ConfigBundle {
+    /// Build a bundle from the instance's settings and blacklist
+    pub fn from_parts(
+        settings: HashMap<String, String>,
+        blacklist: Vec<BlacklistBundleEntry>,
+        exported_at: i64,
+    ) -> Self {
+        Self {
+            version: CONFIG_BUNDLE_VERSION,
+            exported_at,
+            settings: settings
+                .into_iter()
+                .filter(|(key, _)| is_portable_setting(key))
+                .collect(),
+            blacklist,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_drops_instance_specific_settings() {
+        let settings = HashMap::from([
+            ("signups_enabled".to_string(), "false".to_string()),
+            ("system_min_code_length".to_string(), "6".to_string()),
+            ("product_pro_monthly_id".to_string(), "prod_1".to_string()),
+            ("active_discount_pro_annual".to_string(), "d_1".to_string()),
+        ]);
+        let bundle = ConfigBundle::from_parts(settings, Vec::new(), 1_700_000_000);
+        assert_eq!(bundle.version, CONFIG_BUNDLE_VERSION);
+        assert_eq!(
+            bundle.settings.keys().collect::<Vec<_>>(),
+            vec!["signups_enabled"]
+        );
+    }
+
+    #[test]
+    fn test_bundle_round_trips_through_json() {
+        let bundle = ConfigBundle::from_parts(
+            HashMap::from([("robots_txt".to_string(), String::new())]),
+            vec![BlacklistBundleEntry {
+                destination: "evil.example".to_string(),
+                match_type: "domain".to_string(),
+                reason: "phishing".to_string(),
+            }],
+            42,
+        );
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert_eq!(serde_json::from_str::<ConfigBundle>(&json).unwrap(), bundle);
+
+        let minimal: ConfigBundle = serde_json::from_str(r#"{"version":1}"#).unwrap();
+        assert!(minimal.settings.is_empty() && minimal.blacklist.is_empty());
+    }
+
+    #[test]
+    fn test_import_mode_parse() {
+        assert_eq!(ConfigImportMode::parse(None), Ok(ConfigImportMode::Merge));
+        assert_eq!(
+            ConfigImportMode::parse(Some("merge")),
+            Ok(ConfigImportMode::Merge)
+        );
+        assert_eq!(
+            ConfigImportMode::parse(Some("replace")),
+            Ok(ConfigImportMode::Replace)
+        );
+        assert!(ConfigImportMode::parse(Some("overwrite")).is_err());
+    }
+}
@@ -0,0 +1,70 @@
+use crate::models::analytics::{CountryCount, LinkEvent, ReferrerCount};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Longest configurable retention for analytics of admin-deleted links (1 year)
+pub const MAX_DELETED_LINK_RETENTION_DAYS: u32 = 365;
+
+/// Most raw events returned by the deleted-link analytics endpoint
+pub const DELETED_LINK_EVENTS_LIMIT: i64 = 100;
+
+/// Tombstone of an admin-deleted link whose analytics are kept for abuse
+/// investigations until `retain_until`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DeletedLink {
+    #[schema(example = "link-123456")]
+    pub id: String,
+    #[schema(example = "org-789")]
+    pub org_id: String,
+    #[schema(example = "abc123")]
+    pub short_code: String,
+    #[schema(example = "https://example.com/phishing")]
+    pub destination_url: String,
+    pub title: Option<String>,
+    #[schema(example = "user-123")]
+    pub created_by: String,
+    #[schema(example = 1609459200)]
+    pub created_at: i64,
+    #[schema(example = 42)]
+    pub click_count: i64,
+    /// Always "deleted"
+    #[schema(example = "deleted")]
+    pub status: String,
+    /// Admin who deleted the link
+    #[schema(example = "user-456")]
+    pub deleted_by: String,
+    #[schema(example = 1640995200)]
+    pub deleted_at: i64,
+    /// When the tombstone and its events are purged
+    #[schema(example = 1648771200)]
+    pub retain_until: i64,
+}
+
+/// Retained analytics of an admin-deleted link
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeletedLinkAnalyticsResponse {
+    pub link: DeletedLink,
+    /// Clicks recorded as events, scaled up for sampled events
+    #[schema(example = 42)]
+    pub total_clicks: i64,
+    pub top_referrers: Vec<ReferrerCount>,
+    pub top_countries: Vec<CountryCount>,
+    /// Most recent raw events, newest first
+    pub recent_events: Vec<LinkEvent>,
+}
+
+/// Timestamp until which a link deleted at `now` keeps its analytics
+pub fn retain_until(retention_days: u32, now: i64) -> i64 {
+    now + i64::from(retention_days) * 86_400
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retain_until() {
+        assert_eq!(retain_until(0, 1_700_000_000), 1_700_000_000);
+        assert_eq!(retain_until(30, 1_700_000_000), 1_700_000_000 + 30 * 86_400);
+    }
+}
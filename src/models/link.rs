@@ -1,7 +1,29 @@
 use crate::utils::now_timestamp;
 use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
 use utoipa::ToSchema;
 
+/// Sort orders accepted by the links list (`sort` query param and the
+/// org-level `default_link_sort` setting).
+pub const LINK_SORT_OPTIONS: [&str; 5] = ["created", "updated", "clicks", "title", "code"];
+
+/// Sort order used when neither the request nor the org specifies one.
+pub const DEFAULT_LINK_SORT: &str = "created";
+
+/// Redirect statuses an org may pick as its `default_redirect_type`.
+pub const REDIRECT_TYPE_OPTIONS: [&str; 4] = ["301", "302", "307", "308"];
+
+/// Redirect status for new links when neither the request nor the org sets one.
+pub const DEFAULT_REDIRECT_TYPE: &str = "301";
+
+/// Placeholder org holding anonymously created links until they are claimed
+/// (created by migration 0060, together with `ANONYMOUS_USER_ID`).
+pub const ANONYMOUS_ORG_ID: &str = "anonymous";
+
+/// Placeholder user recorded as `created_by` on unclaimed anonymous links.
+/// Not a real account: excluded from user counts and the admin user list.
+pub const ANONYMOUS_USER_ID: &str = "anonymous";
+
 /// Standard Google UTM parameters attached to a link.
 /// All fields are optional; only non-empty values are appended to the destination URL.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, ToSchema)]
@@ -120,6 +142,28 @@ pub struct Link {
     /// None means the link uses the default short domain.
     #[schema(example = "go.mybrand.com")]
     pub custom_domain: Option<String>,
+    /// Custom headers added to the redirect response (lowercased names).
+    #[schema(example = json!({"link": "<https://cdn.example.com>; rel=preconnect"}))]
+    pub response_headers: Option<BTreeMap<String, String>>,
+    /// Destination as submitted, kept when the link was created with
+    /// `canonicalize=true` and `destination_url` holds the canonical form.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "https://Example.com:443/page?utm_source=x")]
+    pub raw_destination: Option<String>,
+    /// Why a disabled link was disabled, when the system did it rather than
+    /// a user: `suspension` when its owner's account was suspended.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "suspension")]
+    pub disabled_reason: Option<String>,
+    /// Clicks after which the link stops redirecting (None = unlimited).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 100)]
+    pub max_clicks: Option<i64>,
+    /// Where visitors go once the link is disabled, expired or over its
+    /// click cap, instead of the 404 page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "https://example.com/campaign-ended")]
+    pub fallback_url: Option<String>,
 }
 
 impl<'de> Deserialize<'de> for Link {
@@ -147,6 +191,15 @@ impl<'de> Deserialize<'de> for Link {
             android_url: Option<String>,       // Device routing URL
             desktop_url: Option<String>,       // Device routing URL
             custom_domain: Option<String>,     // Custom domain this link belongs to
+            response_headers: Option<String>,  // JSON object string from D1
+            #[serde(default)]
+            raw_destination: Option<String>, // Original destination before canonicalization
+            #[serde(default)]
+            disabled_reason: Option<String>, // Set when the system disabled the link
+            #[serde(default)]
+            max_clicks: Option<i64>, // Click cap, NULL = unlimited
+            #[serde(default)]
+            fallback_url: Option<String>, // Replaces the 404 page for this link
         }
 
         let helper = LinkHelper::deserialize(deserializer)?;
@@ -165,6 +218,12 @@ impl<'de> Deserialize<'de> for Link {
             .as_deref()
             .and_then(UtmParams::from_json_str);
 
+        // Parse custom response headers from JSON string
+        let response_headers = helper
+            .response_headers
+            .as_deref()
+            .and_then(|s| serde_json::from_str(s).ok());
+
         // Parse forward_query_params: 1 = true, 0 = false, NULL = None
         let forward_query_params = helper.forward_query_params.map(|v| v != 0);
 
@@ -188,6 +247,11 @@ impl<'de> Deserialize<'de> for Link {
             android_url: helper.android_url,
             desktop_url: helper.desktop_url,
             custom_domain: helper.custom_domain,
+            response_headers,
+            raw_destination: helper.raw_destination,
+            disabled_reason: helper.disabled_reason,
+            max_clicks: helper.max_clicks,
+            fallback_url: helper.fallback_url,
         })
     }
 }
@@ -223,10 +287,74 @@ pub struct LinkMapping {
     /// Missing in old KV entries = None (no device routing).
     #[serde(default)]
     pub desktop_url: Option<String>,
+    /// Owning org, used to look up org-level rewrite rules on redirect.
+    /// Missing in old KV entries = None (no rewrite until the mapping is re-synced).
+    #[serde(default)]
+    pub org_id: Option<String>,
+    /// Custom headers added to the redirect response.
+    /// Missing in old KV entries = None (no extra headers).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_headers: Option<BTreeMap<String, String>>,
+    /// Destination (or a device URL) has `{{...}}` placeholders filled at click time.
+    /// Missing in old KV entries = false (no templating work on redirect).
+    #[serde(default)]
+    pub templated: bool,
+    /// When the link was last edited; redirects served shortly after carry `no-store`.
+    /// Missing in old KV entries = None (normal caching).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+    /// Why the link is disabled (`suspension` for a suspended owner).
+    /// Missing in old KV entries = None (plain disabled link).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disabled_reason: Option<String>,
+    /// Clicks after which the redirect serves the 404 page instead.
+    /// Missing in old KV entries = None (unlimited).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_clicks: Option<i64>,
+    /// Served instead of the 404 page when the link is disabled, expired or
+    /// over its click cap. Missing in old KV entries = None (404 page).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_url: Option<String>,
+}
+
+/// How long after an edit redirects are served with `Cache-Control: no-store`,
+/// so browsers don't pin a permanent redirect to a destination that was just changed.
+pub const RECENT_EDIT_NO_STORE_SECS: i64 = 24 * 60 * 60;
+
+impl LinkMapping {
+    /// Whether the link was edited within the no-store window before `now`.
+    pub fn recently_edited(&self, now: i64) -> bool {
+        self.updated_at
+            .is_some_and(|edited| now - edited < RECENT_EDIT_NO_STORE_SECS)
+    }
+
+    /// KV entry kept for a blocked link so the redirect path can tell it
+    /// apart from a missing code. Carries no destination.
+    pub fn blocked_tombstone(link_id: &str, org_id: &str) -> Self {
+        Self {
+            destination_url: String::new(),
+            link_id: link_id.to_string(),
+            expires_at: None,
+            status: LinkStatus::Blocked,
+            utm_params: None,
+            forward_query_params: false,
+            redirect_type: default_redirect_type(),
+            ios_url: None,
+            android_url: None,
+            desktop_url: None,
+            org_id: Some(org_id.to_string()),
+            response_headers: None,
+            templated: false,
+            updated_at: None,
+            disabled_reason: None,
+            max_clicks: None,
+            fallback_url: None,
+        }
+    }
 }
 
 fn default_redirect_type() -> String {
-    "301".to_string()
+    DEFAULT_REDIRECT_TYPE.to_string()
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -262,6 +390,22 @@ pub struct CreateLinkRequest {
     /// Immutable after creation. None = use default short domain.
     #[schema(example = "go.mybrand.com")]
     pub custom_domain: Option<String>,
+    /// Custom headers to add to the redirect response. Only `Link`, `X-Robots-Tag`
+    /// and custom `X-` headers are allowed.
+    #[schema(example = json!({"Link": "<https://cdn.example.com>; rel=preconnect"}))]
+    pub response_headers: Option<BTreeMap<String, String>>,
+    /// Stop redirecting after this many clicks (at least 1). None = unlimited.
+    #[schema(example = 100)]
+    pub max_clicks: Option<i64>,
+    /// Destination once the link is disabled, expired or over max_clicks,
+    /// instead of the 404 page.
+    #[schema(example = "https://example.com/campaign-ended")]
+    pub fallback_url: Option<String>,
+    /// Return the org's existing active link to the same destination, if
+    /// any, instead of creating a new one
+    #[serde(default)]
+    #[schema(example = false)]
+    pub dedupe: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -307,6 +451,134 @@ pub struct UpdateLinkRequest {
     /// Set to true to clear the Desktop URL
     #[schema(example = false)]
     pub clear_desktop_url: Option<bool>,
+    /// Custom headers to add to the redirect response. Replaces the existing set;
+    /// an empty object clears them.
+    #[schema(example = json!({"X-Robots-Tag": "noindex"}))]
+    pub response_headers: Option<BTreeMap<String, String>>,
+    /// Destination once the link is disabled, expired or over its click cap.
+    #[schema(example = "https://example.com/campaign-ended")]
+    pub fallback_url: Option<String>,
+    /// Set to true to clear the fallback URL (back to the 404 page)
+    #[schema(example = false)]
+    pub clear_fallback_url: Option<bool>,
+}
+
+/// `disabled_reason` of links disabled because their owner was suspended
+pub const DISABLED_REASON_SUSPENSION: &str = "suspension";
+
+/// `disabled_reason` of links disabled by an admin. Like suspended links,
+/// they never redirect to their `fallback_url`
+pub const DISABLED_REASON_ADMIN: &str = "admin";
+
+/// Links an org may create per hour, across all its members, unless the
+/// `org_links_per_hour` setting says otherwise
+pub const DEFAULT_ORG_LINKS_PER_HOUR: u32 = 1000;
+/// Largest configurable per-org link creation budget
+pub const MAX_ORG_LINKS_PER_HOUR: u32 = 100_000;
+
+/// Maximum number of short codes a single by-codes lookup may resolve
+pub const MAX_LINKS_BY_CODES: usize = 100;
+
+/// Request to resolve several short codes in one call
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GetLinksByCodesRequest {
+    #[schema(example = json!(["launch", "promo-2026"]))]
+    pub codes: Vec<String>,
+}
+
+/// Maximum number of links a single bulk create request may contain
+pub const MAX_BULK_CREATE_LINKS: usize = 100;
+
+/// One link of a bulk create request
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BulkCreateLinkItem {
+    #[schema(example = "https://example.com/very/long/url")]
+    pub destination_url: String,
+    /// Custom short code (Pro+)
+    #[schema(example = "launch")]
+    pub short_code: Option<String>,
+    #[schema(example = "Launch page")]
+    pub title: Option<String>,
+    #[schema(example = json!(["marketing"]))]
+    pub tags: Option<Vec<String>>,
+    #[schema(example = 1893456000)]
+    pub expires_at: Option<i64>,
+}
+
+/// Request to create several links in one call
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkCreateLinksRequest {
+    pub links: Vec<BulkCreateLinkItem>,
+}
+
+/// Outcome of one item of a bulk create request
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkCreateLinkResult {
+    /// Position of the item in the request
+    pub index: usize,
+    /// "created" or "failed"
+    #[schema(example = "created")]
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub short_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response of a bulk create request
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkCreateLinksResponse {
+    pub created: usize,
+    pub failed: usize,
+    pub results: Vec<BulkCreateLinkResult>,
+}
+
+impl BulkCreateLinkResult {
+    pub fn created(index: usize, short_code: String) -> Self {
+        Self {
+            index,
+            status: "created".to_string(),
+            short_code: Some(short_code),
+            error: None,
+        }
+    }
+
+    pub fn failed(index: usize, error: impl Into<String>) -> Self {
+        Self {
+            index,
+            status: "failed".to_string(),
+            short_code: None,
+            error: Some(error.into()),
+        }
+    }
+}
+
+/// Maximum number of links a single bulk delete request may contain
+pub const MAX_BULK_DELETE_LINKS: usize = 200;
+
+/// Request to delete several links in one call
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkDeleteLinksRequest {
+    #[schema(example = json!(["link-123456", "link-654321"]))]
+    pub ids: Vec<String>,
+}
+
+/// A link of a bulk delete request that could not be deleted
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkDeleteLinkError {
+    #[schema(example = "link-123456")]
+    pub id: String,
+    pub error: String,
+}
+
+/// Response of a bulk delete request
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkDeleteLinksResponse {
+    pub deleted: usize,
+    /// Requested IDs that are not links of this organization
+    pub not_found: Vec<String>,
+    pub errors: Vec<BulkDeleteLinkError>,
 }
 
 impl Link {
@@ -334,8 +606,24 @@ impl Link {
             ios_url: self.ios_url.clone(),
             android_url: self.android_url.clone(),
             desktop_url: self.desktop_url.clone(),
+            org_id: Some(self.org_id.clone()),
+            response_headers: self.response_headers.clone(),
+            templated: self.has_templated_destination(),
+            updated_at: self.updated_at,
+            disabled_reason: self.disabled_reason.clone(),
+            max_clicks: self.max_clicks,
+            fallback_url: self.fallback_url.clone(),
         }
     }
+
+    /// Whether any destination URL uses click-time template placeholders.
+    pub fn has_templated_destination(&self) -> bool {
+        std::iter::once(&self.destination_url)
+            .chain(self.ios_url.iter())
+            .chain(self.android_url.iter())
+            .chain(self.desktop_url.iter())
+            .any(|url| crate::utils::url_template::is_templated(url))
+    }
 }
 
 #[cfg(test)]
@@ -364,6 +652,11 @@ mod tests {
             android_url: None,
             desktop_url: None,
             custom_domain: None,
+            response_headers: None,
+            raw_destination: None,
+            disabled_reason: None,
+            max_clicks: None,
+            fallback_url: None,
         };
         assert!(!link.is_expired());
     }
@@ -396,6 +689,11 @@ mod tests {
             android_url: None,
             desktop_url: None,
             custom_domain: None,
+            response_headers: None,
+            raw_destination: None,
+            disabled_reason: None,
+            max_clicks: None,
+            fallback_url: None,
         };
         assert!(!link.is_expired());
     }
@@ -424,6 +722,11 @@ mod tests {
             android_url: None,
             desktop_url: None,
             custom_domain: None,
+            response_headers: None,
+            raw_destination: None,
+            disabled_reason: None,
+            max_clicks: None,
+            fallback_url: None,
         };
         assert!(link.is_expired());
     }
@@ -450,6 +753,11 @@ mod tests {
             android_url: None,
             desktop_url: None,
             custom_domain: None,
+            response_headers: None,
+            raw_destination: None,
+            disabled_reason: None,
+            max_clicks: None,
+            fallback_url: None,
         };
 
         let mapping = link.to_mapping(false);
@@ -483,6 +791,11 @@ mod tests {
             android_url: None,
             desktop_url: None,
             custom_domain: None,
+            response_headers: None,
+            raw_destination: None,
+            disabled_reason: None,
+            max_clicks: None,
+            fallback_url: None,
         };
 
         let mapping = link.to_mapping(false);
@@ -552,6 +865,11 @@ mod tests {
             android_url: None,
             desktop_url: None,
             custom_domain: None,
+            response_headers: None,
+            raw_destination: None,
+            disabled_reason: None,
+            max_clicks: None,
+            fallback_url: None,
         };
 
         let mapping = link.to_mapping(true);
@@ -630,6 +948,11 @@ mod redirect_type_tests {
             android_url: None,
             desktop_url: None,
             custom_domain: None,
+            response_headers: None,
+            raw_destination: None,
+            disabled_reason: None,
+            max_clicks: None,
+            fallback_url: None,
         };
 
         let json = serde_json::to_string(&link).unwrap();
@@ -659,4 +982,18 @@ mod redirect_type_tests {
         let link: Link = serde_json::from_str(json).unwrap();
         assert_eq!(link.redirect_type, "307");
     }
+
+    #[test]
+    fn test_mapping_recently_edited_window() {
+        let mut mapping: LinkMapping = serde_json::from_str(
+            r#"{"destination_url":"https://example.com","link_id":"l","expires_at":null,"status":"active"}"#,
+        )
+        .unwrap();
+        assert!(!mapping.recently_edited(1_000_000));
+
+        mapping.updated_at = Some(1_000_000);
+        assert!(mapping.recently_edited(1_000_000));
+        assert!(mapping.recently_edited(1_000_000 + RECENT_EDIT_NO_STORE_SECS - 1));
+        assert!(!mapping.recently_edited(1_000_000 + RECENT_EDIT_NO_STORE_SECS));
+    }
 }
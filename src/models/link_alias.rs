@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Maximum number of alias codes a single link may have.
+pub const MAX_ALIASES_PER_LINK: i64 = 5;
+
+/// An extra short code that redirects to an existing link.
+///
+/// Clicks through an alias count towards the link's analytics, with the
+/// alias recorded on the event.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct LinkAlias {
+    #[schema(example = "spring-sale")]
+    pub alias_code: String,
+    #[schema(example = "link-123456")]
+    pub link_id: String,
+    #[schema(example = "org-789")]
+    pub org_id: String,
+    #[schema(example = "user-123")]
+    pub created_by: String,
+    #[schema(example = 1609459200)]
+    pub created_at: i64,
+}
+
+/// KV value stored under an alias code: points the redirect handler at the
+/// link's primary short code. `link_id` guards against the primary code
+/// having been reused by a different link since the alias was written.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AliasPointer {
+    pub alias_of: String,
+    pub link_id: String,
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+/// Longest configurable grace period before disabled links are purged (10 years)
+pub const MAX_DISABLED_LINK_PURGE_DAYS: u32 = 3650;
+
+/// When and which disabled links the scheduled purge job may hard-delete
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisabledLinkPurgePolicy {
+    /// Days a link must have stayed disabled (and unchanged) before purging
+    pub grace_days: u32,
+    /// Only purge links with no clicks recorded during the grace period
+    pub require_zero_clicks: bool,
+}
+
+impl DisabledLinkPurgePolicy {
+    /// Read the policy from settings. None when purging is off (the default).
+    pub fn from_settings(settings: &HashMap<String, String>) -> Option<Self> {
+        let grace_days = settings
+            .get("disabled_link_purge_days")
+            .and_then(|v| v.parse::<u32>().ok())
+            .filter(|days| (1..=MAX_DISABLED_LINK_PURGE_DAYS).contains(days))?;
+        // Anything but an explicit "false" keeps the safer zero-click requirement
+        let require_zero_clicks = settings
+            .get("disabled_link_purge_require_zero_clicks")
+            .map(|v| v != "false")
+            .unwrap_or(true);
+        Some(Self {
+            grace_days,
+            require_zero_clicks,
+        })
+    }
+
+    /// Links last changed at or before this timestamp are past the grace period
+    pub fn cutoff(&self, now: i64) -> i64 {
+        now - i64::from(self.grace_days) * 86_400
+    }
+
+    /// Whether a disabled link is eligible for purging at `now`
+    pub fn is_eligible(&self, last_changed_at: i64, recent_clicks: i64, now: i64) -> bool {
+        last_changed_at <= self.cutoff(now) && (!self.require_zero_clicks || recent_clicks == 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400;
+    const NOW: i64 = 1_700_000_000;
+
+    fn settings(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn policy(grace_days: u32, require_zero_clicks: bool) -> DisabledLinkPurgePolicy {
+        DisabledLinkPurgePolicy {
+            grace_days,
+            require_zero_clicks,
+        }
+    }
+
+    #[test]
+    fn test_purge_off_by_default() {
+        assert_eq!(
+            DisabledLinkPurgePolicy::from_settings(&HashMap::new()),
+            None
+        );
+        assert_eq!(
+            DisabledLinkPurgePolicy::from_settings(&settings(&[("disabled_link_purge_days", "0")])),
+            None
+        );
+        assert_eq!(
+            DisabledLinkPurgePolicy::from_settings(&settings(&[(
+                "disabled_link_purge_days",
+                "soon"
+            )])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_from_settings_defaults_to_zero_click_requirement() {
+        assert_eq!(
+            DisabledLinkPurgePolicy::from_settings(&settings(&[(
+                "disabled_link_purge_days",
+                "30"
+            )])),
+            Some(policy(30, true))
+        );
+        assert_eq!(
+            DisabledLinkPurgePolicy::from_settings(&settings(&[
+                ("disabled_link_purge_days", "30"),
+                ("disabled_link_purge_require_zero_clicks", "false"),
+            ])),
+            Some(policy(30, false))
+        );
+    }
+
+    #[test]
+    fn test_eligibility_boundary_at_cutoff() {
+        let p = policy(30, true);
+        let cutoff = NOW - 30 * DAY;
+        assert_eq!(p.cutoff(NOW), cutoff);
+        assert!(p.is_eligible(cutoff, 0, NOW));
+        assert!(p.is_eligible(cutoff - 1, 0, NOW));
+        assert!(!p.is_eligible(cutoff + 1, 0, NOW));
+    }
+
+    #[test]
+    fn test_eligibility_respects_zero_click_requirement() {
+        let old = NOW - 90 * DAY;
+        assert!(!policy(30, true).is_eligible(old, 1, NOW));
+        assert!(policy(30, false).is_eligible(old, 1, NOW));
+        assert!(!policy(30, false).is_eligible(NOW - DAY, 0, NOW));
+    }
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Seconds clients are told to wait (`Retry-After`) while maintenance is on.
+pub const MAINTENANCE_RETRY_AFTER_SECS: u64 = 300;
+
+/// API paths that keep working when maintenance also gates the API, so admins
+/// can still sign in and switch maintenance off, and billing webhooks are not lost.
+const API_EXEMPT_PREFIXES: &[&str] = &[
+    "/api/admin/",
+    "/api/auth/",
+    "/api/billing/webhook",
+    "/api/settings",
+    "/api/version",
+];
+
+/// Global maintenance state, derived from the `maintenance_mode` and
+/// `maintenance_include_api` settings and cached in KV for the redirect path.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceState {
+    /// Pause all redirects (503 + Retry-After)
+    pub enabled: bool,
+    /// Also reject API requests (except admin/auth/settings) while enabled
+    #[serde(default)]
+    pub include_api: bool,
+}
+
+impl MaintenanceState {
+    /// Build the state from the settings table
+    pub fn from_settings(settings: &HashMap<String, String>) -> Self {
+        let flag = |key: &str| settings.get(key).map(|v| v == "true").unwrap_or(false);
+        Self {
+            enabled: flag("maintenance_mode"),
+            include_api: flag("maintenance_include_api"),
+        }
+    }
+
+    /// Whether an API request to `path` must be rejected under the current state
+    pub fn blocks_api_path(&self, path: &str) -> bool {
+        self.enabled
+            && self.include_api
+            && path.starts_with("/api/")
+            && !API_EXEMPT_PREFIXES.iter().any(|p| path.starts_with(p))
+    }
+}
+
+/// Minimal HTML body served instead of a redirect during maintenance
+pub const MAINTENANCE_PAGE_HTML: &str = "<!DOCTYPE html>\
+<html lang=\"en\"><head><meta charset=\"utf-8\"><title>Down for maintenance</title>\
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\"></head>\
+<body style=\"font-family:sans-serif;text-align:center;padding:3rem\">\
+<h1>Down for maintenance</h1>\
+<p>This link is temporarily unavailable. Please try again in a few minutes.</p>\
+</body></html>";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_from_settings_defaults_off() {
+        assert_eq!(
+            MaintenanceState::from_settings(&HashMap::new()),
+            MaintenanceState::default()
+        );
+    }
+
+    #[test]
+    fn test_from_settings_reads_flags() {
+        let state = MaintenanceState::from_settings(&settings(&[
+            ("maintenance_mode", "true"),
+            ("maintenance_include_api", "false"),
+        ]));
+        assert!(state.enabled);
+        assert!(!state.include_api);
+    }
+
+    #[test]
+    fn test_blocks_api_path_only_when_gating_api() {
+        let redirects_only = MaintenanceState {
+            enabled: true,
+            include_api: false,
+        };
+        assert!(!redirects_only.blocks_api_path("/api/links"));
+
+        let off = MaintenanceState {
+            enabled: false,
+            include_api: true,
+        };
+        assert!(!off.blocks_api_path("/api/links"));
+
+        let all = MaintenanceState {
+            enabled: true,
+            include_api: true,
+        };
+        assert!(all.blocks_api_path("/api/links"));
+        assert!(all.blocks_api_path("/api/orgs/org-1/settings"));
+    }
+
+    #[test]
+    fn test_blocks_api_path_exemptions() {
+        let all = MaintenanceState {
+            enabled: true,
+            include_api: true,
+        };
+        assert!(!all.blocks_api_path("/api/admin/settings"));
+        assert!(!all.blocks_api_path("/api/auth/me"));
+        assert!(!all.blocks_api_path("/api/settings"));
+        assert!(!all.blocks_api_path("/api/billing/webhook"));
+        assert!(!all.blocks_api_path("/dashboard"));
+    }
+}
@@ -1,22 +1,39 @@
 pub mod analytics;
+pub mod analytics_sampling;
+pub mod api_key;
 pub mod billing_account;
+pub mod blocked_link;
+pub mod click_dedup;
+pub mod config_bundle;
 pub mod custom_domain;
+pub mod deleted_link;
 pub mod link;
+pub mod link_alias;
+pub mod link_purge;
+pub mod maintenance;
 pub mod org_member;
+pub mod org_redirect_config;
 pub mod organization;
 pub mod pagination;
 pub mod pending_action;
+pub mod report_limit;
+pub mod rewrite_rule;
 pub mod tier;
 pub mod user;
 
 pub use analytics::{AnalyticsEvent, LinkAnalyticsResponse, TimeRange};
+pub use api_key::ApiKeyScope;
 pub use billing_account::BillingAccount;
 pub use custom_domain::CustomDomain;
 pub use link::{Link, LinkMapping};
-pub use org_member::{OrgInvitation, OrgMember, OrgMemberWithUser, OrgWithRole};
+pub use link_alias::LinkAlias;
+pub use org_member::{
+    InvitationStatus, OrgActivityStats, OrgInvitation, OrgMember, OrgMemberWithUser, OrgWithRole,
+};
 pub use organization::Organization;
 pub use pagination::{PaginatedResponse, PaginationMeta};
 #[allow(unused_imports)]
 pub use pending_action::PendingAction;
+pub use rewrite_rule::RewriteRule;
 pub use tier::Tier;
 pub use user::User;
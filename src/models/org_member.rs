@@ -29,6 +29,41 @@ pub struct OrgMemberWithUser {
     pub joined_at: i64,
 }
 
+/// Default cap on pending (unaccepted, unexpired) invitations per org, used
+/// when the `max_pending_invitations_per_org` setting is missing or invalid.
+pub const DEFAULT_MAX_PENDING_INVITATIONS: i64 = 100;
+
+/// Upper bound admins can configure for `max_pending_invitations_per_org`.
+pub const MAX_PENDING_INVITATIONS_LIMIT: i64 = 10_000;
+
+/// Effective pending-invitation cap for a raw `max_pending_invitations_per_org`
+/// setting value (default when missing or invalid)
+pub fn max_pending_invitations(setting: Option<&str>) -> i64 {
+    setting
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|max| *max > 0)
+        .unwrap_or(DEFAULT_MAX_PENDING_INVITATIONS)
+}
+
+/// Whether an org with `pending` open invitations may send another one.
+/// Returns the rejection message once the cap is reached.
+pub fn check_pending_invitations(pending: i64, max_pending: i64) -> Result<(), String> {
+    if pending >= max_pending {
+        return Err(format!(
+            "Too many pending invitations ({}/{}). Revoke an invitation or wait for one to be accepted or expire before inviting more.",
+            pending, max_pending
+        ));
+    }
+    Ok(())
+}
+
+/// Default minimum seconds between resends of the same invitation, used when
+/// the `invitation_resend_interval_seconds` setting is missing or invalid.
+pub const DEFAULT_INVITATION_RESEND_INTERVAL_SECS: i64 = 300;
+
+/// Upper bound admins can configure for `invitation_resend_interval_seconds`.
+pub const MAX_INVITATION_RESEND_INTERVAL_SECS: i64 = 86_400;
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct OrgInvitation {
     #[schema(example = "inv-123")]
@@ -46,6 +81,40 @@ pub struct OrgInvitation {
     #[schema(example = 1612137600)]
     pub expires_at: i64,
     pub accepted_at: Option<i64>,
+    /// When the invitation email was last resent (None = never resent)
+    #[serde(default)]
+    pub last_resent_at: Option<i64>,
+}
+
+impl OrgInvitation {
+    /// Seconds until the invitation may be resent, or None when a resend is
+    /// allowed at `now` under the given minimum interval.
+    pub fn resend_retry_after(&self, interval_secs: i64, now: i64) -> Option<i64> {
+        let next_allowed = self.last_resent_at? + interval_secs;
+        (next_allowed > now).then_some(next_allowed - now)
+    }
+}
+
+/// Invitation state used to filter `GET /api/orgs/:id/invitations`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvitationStatus {
+    /// Not accepted and not yet expired
+    Pending,
+    /// Not accepted before `expires_at`
+    Expired,
+    Accepted,
+}
+
+impl InvitationStatus {
+    /// Parse the `status` query parameter
+    pub fn from_param(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(Self::Pending),
+            "expired" => Some(Self::Expired),
+            "accepted" => Some(Self::Accepted),
+            _ => None,
+        }
+    }
 }
 
 /// An organization with the current user's membership role attached
@@ -60,3 +129,45 @@ pub struct OrgWithRole {
     #[schema(example = 1609459200)]
     pub joined_at: i64,
 }
+
+/// Activity summary for an organization, returned by `GET /api/orgs?include=stats`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct OrgActivityStats {
+    /// Active and disabled links in the org
+    #[schema(example = 42)]
+    pub link_count: i64,
+    /// Clicks recorded in the current calendar month (UTC)
+    #[schema(example = 1250)]
+    pub clicks_this_month: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_pending_invitations_falls_back_to_default() {
+        assert_eq!(
+            max_pending_invitations(None),
+            DEFAULT_MAX_PENDING_INVITATIONS
+        );
+        assert_eq!(
+            max_pending_invitations(Some("0")),
+            DEFAULT_MAX_PENDING_INVITATIONS
+        );
+        assert_eq!(
+            max_pending_invitations(Some("lots")),
+            DEFAULT_MAX_PENDING_INVITATIONS
+        );
+        assert_eq!(max_pending_invitations(Some("3")), 3);
+    }
+
+    #[test]
+    fn test_check_pending_invitations_rejects_at_cap() {
+        assert!(check_pending_invitations(0, 3).is_ok());
+        assert!(check_pending_invitations(2, 3).is_ok());
+        let err = check_pending_invitations(3, 3).unwrap_err();
+        assert!(err.contains("Too many pending invitations (3/3)"));
+        assert!(check_pending_invitations(4, 3).is_err());
+    }
+}
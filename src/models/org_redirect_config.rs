@@ -0,0 +1,430 @@
+use crate::utils::email::escape_html;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Longest interstitial delay an org may configure, in seconds.
+pub const MAX_INTERSTITIAL_DELAY_SECS: u32 = 10;
+/// Delay used when the untrusted-destination policy forces the interstitial
+/// but the org has no interstitial delay of its own.
+pub const UNTRUSTED_INTERSTITIAL_DELAY_SECS: u32 = 5;
+/// Most trusted domains an org may list.
+pub const MAX_TRUSTED_DOMAINS: usize = 100;
+/// Most request headers an org may record with click events.
+pub const MAX_ANALYTICS_HEADER_FIELDS: usize = 10;
+/// Recorded header values are truncated to this many characters.
+pub const MAX_ANALYTICS_HEADER_VALUE_LENGTH: usize = 200;
+/// Headers that carry credentials or visitor identity and must never be recorded.
+const FORBIDDEN_ANALYTICS_HEADERS: &[&str] = &[
+    "authorization",
+    "cf-access-jwt-assertion",
+    "cf-connecting-ip",
+    "cookie",
+    "forwarded",
+    "proxy-authorization",
+    "set-cookie",
+    "true-client-ip",
+    "x-api-key",
+    "x-forwarded-for",
+    "x-real-ip",
+];
+
+/// Per-org settings consulted on every redirect.
+///
+/// Kept in KV (see `kv::org_config`) so `handle_redirect` can read it without
+/// touching D1. Every field must default to the "plain 301" behaviour so a
+/// missing or older cache entry never changes how links resolve.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OrgRedirectConfig {
+    /// Org display name, shown on the interstitial page.
+    #[serde(default)]
+    pub org_name: String,
+    /// Seconds to show the branded interstitial before continuing. 0 = disabled.
+    #[serde(default)]
+    pub interstitial_delay_seconds: u32,
+    /// Secret for verifying signed UTM override tokens (`?u=`). None = tokens ignored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub utm_signing_secret: Option<String>,
+    /// Answer link-preview crawlers with an OpenGraph page instead of a redirect.
+    #[serde(default)]
+    pub crawler_preview: bool,
+    /// Show the interstitial for destinations outside `trusted_domains`.
+    #[serde(default)]
+    pub untrusted_interstitial: bool,
+    /// Domains (and their subdomains) that redirect directly under
+    /// `untrusted_interstitial`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_domains: Vec<String>,
+    /// Lowercased request header names whose values are stored in the
+    /// `extra` column of each click event.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub analytics_header_fields: Vec<String>,
+}
+
+impl OrgRedirectConfig {
+    pub fn interstitial_enabled(&self) -> bool {
+        self.interstitial_delay_seconds > 0
+    }
+
+    /// Interstitial delay to apply for a destination host, or None to redirect
+    /// directly. Destinations without a host (e.g. `mailto:`) count as untrusted.
+    pub fn interstitial_delay_for(&self, destination_host: Option<&str>) -> Option<u32> {
+        if self.interstitial_enabled() {
+            return Some(self.interstitial_delay_seconds);
+        }
+        let trusted =
+            destination_host.is_some_and(|host| is_trusted_domain(host, &self.trusted_domains));
+        (self.untrusted_interstitial && !trusted).then_some(UNTRUSTED_INTERSTITIAL_DELAY_SECS)
+    }
+
+    /// Values of the allowlisted headers present on a request, truncated to
+    /// MAX_ANALYTICS_HEADER_VALUE_LENGTH. None when nothing is recorded.
+    /// `get_header` looks a header up by its lowercased name.
+    pub fn collect_analytics_headers<F>(&self, get_header: F) -> Option<BTreeMap<String, String>>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let extra: BTreeMap<String, String> = self
+            .analytics_header_fields
+            .iter()
+            .filter_map(|name| {
+                let value = get_header(name)?;
+                let value: String = value
+                    .trim()
+                    .chars()
+                    .take(MAX_ANALYTICS_HEADER_VALUE_LENGTH)
+                    .collect();
+                (!value.is_empty()).then(|| (name.clone(), value))
+            })
+            .collect();
+        (!extra.is_empty()).then_some(extra)
+    }
+}
+
+/// Whether `host` is one of `trusted_domains` or a subdomain of one.
+pub fn is_trusted_domain(host: &str, trusted_domains: &[String]) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    trusted_domains.iter().any(|domain| {
+        host == *domain
+            || host
+                .strip_suffix(domain.as_str())
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    })
+}
+
+/// Normalize a trusted-domains list from user input: lowercased, a leading
+/// `*.` dropped, duplicates removed. Entries must be bare hostnames.
+pub fn validate_trusted_domains(domains: &[String]) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for raw in domains {
+        let domain = raw.trim().trim_start_matches("*.").to_ascii_lowercase();
+        let valid = !domain.is_empty()
+            && domain.len() <= 253
+            && domain.contains('.')
+            && !domain.starts_with(['.', '-'])
+            && !domain.ends_with(['.', '-'])
+            && domain
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.');
+        if !valid {
+            return Err(format!(
+                "Invalid trusted domain '{}'. Use a hostname such as example.com",
+                raw
+            ));
+        }
+        if !normalized.contains(&domain) {
+            normalized.push(domain);
+        }
+    }
+    if normalized.len() > MAX_TRUSTED_DOMAINS {
+        return Err(format!(
+            "trusted_domains may list at most {} domains",
+            MAX_TRUSTED_DOMAINS
+        ));
+    }
+    Ok(normalized)
+}
+
+/// Normalize an analytics header list from user input: lowercased,
+/// duplicates removed. Credential headers are rejected.
+pub fn validate_analytics_header_fields(fields: &[String]) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for raw in fields {
+        let name = raw.trim().to_ascii_lowercase();
+        let valid = !name.is_empty()
+            && name.len() <= 64
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        if !valid {
+            return Err(format!(
+                "Invalid header name '{}'. Use letters, digits and hyphens, such as x-tenant",
+                raw
+            ));
+        }
+        if FORBIDDEN_ANALYTICS_HEADERS.contains(&name.as_str()) {
+            return Err(format!("Header '{}' cannot be recorded", name));
+        }
+        if !normalized.contains(&name) {
+            normalized.push(name);
+        }
+    }
+    if normalized.len() > MAX_ANALYTICS_HEADER_FIELDS {
+        return Err(format!(
+            "analytics_header_fields may list at most {} headers",
+            MAX_ANALYTICS_HEADER_FIELDS
+        ));
+    }
+    Ok(normalized)
+}
+
+/// Validate an interstitial delay from user input (0 disables the interstitial).
+pub fn validate_interstitial_delay(value: i64) -> Result<u32, String> {
+    if !(0..=MAX_INTERSTITIAL_DELAY_SECS as i64).contains(&value) {
+        return Err(format!(
+            "interstitial_delay_seconds must be between 0 and {}",
+            MAX_INTERSTITIAL_DELAY_SECS
+        ));
+    }
+    Ok(value as u32)
+}
+
+/// Render the branded interstitial page. Uses a meta refresh (no script) so it
+/// works under the default Content-Security-Policy, with a manual link as fallback.
+/// `external_warning` adds a notice that the destination is an external site.
+pub fn render_interstitial_page(
+    destination: &str,
+    org_name: &str,
+    delay_seconds: u32,
+    external_warning: bool,
+) -> String {
+    let destination = escape_html(destination);
+    let org_name = if org_name.trim().is_empty() {
+        "this link".to_string()
+    } else {
+        escape_html(org_name)
+    };
+    let warning = if external_warning {
+        "<p><strong>This link leads to an external site.</strong> Make sure you trust it before continuing.</p>"
+    } else {
+        ""
+    };
+    format!(
+        "<!DOCTYPE html>\
+<html lang=\"en\"><head><meta charset=\"utf-8\">\
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+<meta name=\"robots\" content=\"noindex\">\
+<meta http-equiv=\"refresh\" content=\"{delay};url={destination}\">\
+<title>Redirecting…</title></head>\
+<body style=\"font-family:sans-serif;text-align:center;padding:3rem\">\
+<p>You're leaving via <strong>{org_name}</strong></p>\
+{warning}\
+<p>Continuing to <a href=\"{destination}\" rel=\"noopener noreferrer\">{destination}</a> in {delay} seconds…</p>\
+</body></html>",
+        delay = delay_seconds,
+        destination = destination,
+        org_name = org_name,
+        warning = warning,
+    )
+}
+
+/// Render the 200 page served to link-preview crawlers: OpenGraph/Twitter
+/// tags pointing at the destination, plus a meta refresh for clients that
+/// render it. `title` falls back to the destination URL.
+pub fn render_crawler_preview_page(destination: &str, title: Option<&str>) -> String {
+    let title = escape_html(
+        title
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or(destination),
+    );
+    let destination = escape_html(destination);
+    format!(
+        "<!DOCTYPE html>\
+<html lang=\"en\"><head><meta charset=\"utf-8\">\
+<meta name=\"robots\" content=\"noindex\">\
+<title>{title}</title>\
+<link rel=\"canonical\" href=\"{destination}\">\
+<meta property=\"og:type\" content=\"website\">\
+<meta property=\"og:title\" content=\"{title}\">\
+<meta property=\"og:url\" content=\"{destination}\">\
+<meta name=\"twitter:card\" content=\"summary\">\
+<meta name=\"twitter:title\" content=\"{title}\">\
+<meta http-equiv=\"refresh\" content=\"0;url={destination}\">\
+</head><body><a href=\"{destination}\">{title}</a></body></html>",
+        title = title,
+        destination = destination,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_is_plain_redirect() {
+        let config = OrgRedirectConfig::default();
+        assert!(!config.interstitial_enabled());
+        let parsed: OrgRedirectConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(parsed, config);
+    }
+
+    #[test]
+    fn test_validate_interstitial_delay_bounds() {
+        assert_eq!(validate_interstitial_delay(0), Ok(0));
+        assert_eq!(validate_interstitial_delay(3), Ok(3));
+        assert_eq!(
+            validate_interstitial_delay(MAX_INTERSTITIAL_DELAY_SECS as i64),
+            Ok(MAX_INTERSTITIAL_DELAY_SECS)
+        );
+        assert!(validate_interstitial_delay(-1).is_err());
+        assert!(validate_interstitial_delay(MAX_INTERSTITIAL_DELAY_SECS as i64 + 1).is_err());
+    }
+
+    #[test]
+    fn test_interstitial_contains_destination_and_org() {
+        let html = render_interstitial_page("https://example.com/page?a=1&b=2", "Acme", 3, false);
+        assert!(html.contains("content=\"3;url=https://example.com/page?a=1&amp;b=2\""));
+        assert!(html.contains("href=\"https://example.com/page?a=1&amp;b=2\""));
+        assert!(html.contains("<strong>Acme</strong>"));
+    }
+
+    #[test]
+    fn test_interstitial_escapes_org_name() {
+        let html = render_interstitial_page("https://example.com/", "<script>x</script>", 2, false);
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;"));
+    }
+
+    #[test]
+    fn test_crawler_preview_has_og_tags() {
+        let html =
+            render_crawler_preview_page("https://example.com/a?x=1&y=2", Some("My \"page\""));
+        assert!(
+            html.contains(
+                "<meta property=\"og:url\" content=\"https://example.com/a?x=1&amp;y=2\">"
+            )
+        );
+        assert!(html.contains("<meta property=\"og:title\" content=\"My &quot;page&quot;\">"));
+        assert!(html.contains("twitter:card"));
+
+        let untitled = render_crawler_preview_page("https://example.com/", Some(" "));
+        assert!(untitled.contains("<title>https://example.com/</title>"));
+    }
+
+    #[test]
+    fn test_trusted_domain_matches_subdomains_only() {
+        let trusted = vec!["example.com".to_string()];
+        assert!(is_trusted_domain("example.com", &trusted));
+        assert!(is_trusted_domain("docs.Example.com", &trusted));
+        assert!(is_trusted_domain("example.com.", &trusted));
+        assert!(!is_trusted_domain("badexample.com", &trusted));
+        assert!(!is_trusted_domain("example.com.evil.net", &trusted));
+    }
+
+    #[test]
+    fn test_untrusted_policy_forces_interstitial() {
+        let config = OrgRedirectConfig {
+            untrusted_interstitial: true,
+            trusted_domains: vec!["example.com".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(config.interstitial_delay_for(Some("www.example.com")), None);
+        assert_eq!(
+            config.interstitial_delay_for(Some("other.org")),
+            Some(UNTRUSTED_INTERSTITIAL_DELAY_SECS)
+        );
+        assert_eq!(
+            config.interstitial_delay_for(None),
+            Some(UNTRUSTED_INTERSTITIAL_DELAY_SECS)
+        );
+
+        // The org-wide interstitial still applies to every destination
+        let always = OrgRedirectConfig {
+            interstitial_delay_seconds: 2,
+            ..config
+        };
+        assert_eq!(always.interstitial_delay_for(Some("example.com")), Some(2));
+        assert_eq!(
+            OrgRedirectConfig::default().interstitial_delay_for(Some("other.org")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_validate_trusted_domains() {
+        let input = vec![
+            " Example.COM ".to_string(),
+            "*.docs.example.org".to_string(),
+            "example.com".to_string(),
+        ];
+        assert_eq!(
+            validate_trusted_domains(&input),
+            Ok(vec![
+                "example.com".to_string(),
+                "docs.example.org".to_string()
+            ])
+        );
+        assert!(validate_trusted_domains(&["localhost".to_string()]).is_err());
+        assert!(validate_trusted_domains(&["https://example.com".to_string()]).is_err());
+        assert!(validate_trusted_domains(&["".to_string()]).is_err());
+        let too_many: Vec<String> = (0..=MAX_TRUSTED_DOMAINS)
+            .map(|i| format!("d{}.example.com", i))
+            .collect();
+        assert!(validate_trusted_domains(&too_many).is_err());
+    }
+
+    #[test]
+    fn test_validate_analytics_header_fields() {
+        let input = vec![" X-Tenant ".to_string(), "x-tenant".to_string()];
+        assert_eq!(
+            validate_analytics_header_fields(&input),
+            Ok(vec!["x-tenant".to_string()])
+        );
+        for forbidden in [
+            "Cookie",
+            "set-cookie",
+            "X-Api-Key",
+            "cf-access-jwt-assertion",
+            "x-forwarded-for",
+            "CF-Connecting-IP",
+            "true-client-ip",
+            "x-real-ip",
+            "Forwarded",
+        ] {
+            assert!(validate_analytics_header_fields(&[forbidden.to_string()]).is_err());
+        }
+        assert!(validate_analytics_header_fields(&["x tenant".to_string()]).is_err());
+        let too_many: Vec<String> = (0..=MAX_ANALYTICS_HEADER_FIELDS)
+            .map(|i| format!("x-field-{}", i))
+            .collect();
+        assert!(validate_analytics_header_fields(&too_many).is_err());
+    }
+
+    #[test]
+    fn test_collect_analytics_headers_only_allowlisted() {
+        let config = OrgRedirectConfig {
+            analytics_header_fields: vec!["x-tenant".to_string(), "x-region".to_string()],
+            ..Default::default()
+        };
+        let long = "a".repeat(MAX_ANALYTICS_HEADER_VALUE_LENGTH + 50);
+        let extra = config
+            .collect_analytics_headers(|name| match name {
+                "x-tenant" => Some(long.clone()),
+                "x-secret" => Some("hidden".to_string()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(extra.len(), 1);
+        assert_eq!(extra["x-tenant"].len(), MAX_ANALYTICS_HEADER_VALUE_LENGTH);
+        assert!(
+            OrgRedirectConfig::default()
+                .collect_analytics_headers(|_| Some("v".to_string()))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_interstitial_external_warning() {
+        let html = render_interstitial_page("https://example.com/", "Acme", 5, true);
+        assert!(html.contains("external site"));
+        let plain = render_interstitial_page("https://example.com/", "Acme", 5, false);
+        assert!(!plain.contains("external site"));
+    }
+}
@@ -24,6 +24,17 @@ pub struct Organization {
 }
 
 impl Organization {
+    /// Whole days since the org was created (0 on the first day)
+    pub fn age_days(&self, now: i64) -> i64 {
+        ((now - self.created_at) / 86_400).max(0)
+    }
+
+    /// Whether `name` duplicates one of `existing` (case-insensitive)
+    pub fn is_name_taken(existing: &[String], name: &str) -> bool {
+        let name = name.trim().to_lowercase();
+        existing.iter().any(|n| n.trim().to_lowercase() == name)
+    }
+
     pub fn validate_slug(slug: &str) -> bool {
         // URL-safe: alphanumeric and hyphens only, 3-50 chars
         if slug.len() < 3 || slug.len() > 50 {
@@ -33,6 +44,59 @@ impl Organization {
     }
 }
 
+/// Longest custom invitation subject, in characters
+pub const MAX_INVITE_SUBJECT_CHARS: usize = 150;
+/// Longest custom invitation message, in characters
+pub const MAX_INVITE_MESSAGE_CHARS: usize = 1000;
+
+/// Org-customized parts of the invitation email. None = default wording.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InvitationBranding {
+    pub invite_subject: Option<String>,
+    pub invite_message: Option<String>,
+}
+
+/// Validate a custom invitation subject. Blank clears it (None). Must be a
+/// single line: it ends up in an email header.
+pub fn validate_invite_subject(value: &str) -> Result<Option<String>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    if value.chars().count() > MAX_INVITE_SUBJECT_CHARS {
+        return Err(format!(
+            "invite_subject must be at most {} characters",
+            MAX_INVITE_SUBJECT_CHARS
+        ));
+    }
+    if value.chars().any(char::is_control) {
+        return Err("invite_subject must be a single line of text".to_string());
+    }
+    Ok(Some(value.to_string()))
+}
+
+/// Validate a custom invitation message. Blank clears it (None); line
+/// breaks are kept, other control characters are rejected.
+pub fn validate_invite_message(value: &str) -> Result<Option<String>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    if value.chars().count() > MAX_INVITE_MESSAGE_CHARS {
+        return Err(format!(
+            "invite_message must be at most {} characters",
+            MAX_INVITE_MESSAGE_CHARS
+        ));
+    }
+    if value
+        .chars()
+        .any(|c| c.is_control() && c != '\n' && c != '\r')
+    {
+        return Err("invite_message contains invalid characters".to_string());
+    }
+    Ok(Some(value.replace("\r\n", "\n")))
+}
+
 // Reserved for future organization creation API
 #[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
@@ -45,6 +109,33 @@ pub struct CreateOrganizationData {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_age_days() {
+        let org = Organization {
+            id: "org-1".to_string(),
+            name: "Org".to_string(),
+            slug: "org".to_string(),
+            created_at: 1_700_000_000,
+            created_by: "user-1".to_string(),
+            billing_account_id: None,
+        };
+        assert_eq!(org.age_days(1_700_000_000), 0);
+        assert_eq!(org.age_days(1_700_000_000 + 86_399), 0);
+        assert_eq!(org.age_days(1_700_000_000 + 3 * 86_400), 3);
+        // Clock skew never yields a negative age
+        assert_eq!(org.age_days(1_699_999_000), 0);
+    }
+
+    #[test]
+    fn test_is_name_taken_ignores_case() {
+        let existing = vec!["Marketing".to_string(), "Sales Team".to_string()];
+        assert!(Organization::is_name_taken(&existing, "marketing"));
+        assert!(Organization::is_name_taken(&existing, "SALES TEAM"));
+        assert!(Organization::is_name_taken(&existing, " Marketing "));
+        assert!(!Organization::is_name_taken(&existing, "Marketing 2"));
+        assert!(!Organization::is_name_taken(&[], "Marketing"));
+    }
+
     #[test]
     fn test_validate_slug_accepts_valid_slugs() {
         assert!(Organization::validate_slug("my-org"));
@@ -126,4 +217,27 @@ mod tests {
         assert!(Organization::validate_slug("my-org-name"));
         assert!(Organization::validate_slug("a-b-c-d"));
     }
+
+    #[test]
+    fn test_validate_invite_subject() {
+        assert_eq!(validate_invite_subject("  "), Ok(None));
+        assert_eq!(
+            validate_invite_subject(" Join Acme "),
+            Ok(Some("Join Acme".to_string()))
+        );
+        assert!(validate_invite_subject("Join\r\nBcc: x@example.com").is_err());
+        assert!(validate_invite_subject(&"a".repeat(MAX_INVITE_SUBJECT_CHARS)).is_ok());
+        assert!(validate_invite_subject(&"a".repeat(MAX_INVITE_SUBJECT_CHARS + 1)).is_err());
+    }
+
+    #[test]
+    fn test_validate_invite_message() {
+        assert_eq!(validate_invite_message(""), Ok(None));
+        assert_eq!(
+            validate_invite_message("Hi!\r\nWelcome aboard."),
+            Ok(Some("Hi!\nWelcome aboard.".to_string()))
+        );
+        assert!(validate_invite_message("bell\u{7}").is_err());
+        assert!(validate_invite_message(&"a".repeat(MAX_INVITE_MESSAGE_CHARS + 1)).is_err());
+    }
 }
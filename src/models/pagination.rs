@@ -57,6 +57,15 @@ pub struct PaginatedResponse<T: ToSchema> {
 }
 
 impl<T: ToSchema> PaginatedResponse<T> {
+    /// Create a new paginated response without stats
+    pub fn new(data: Vec<T>, pagination: PaginationMeta) -> Self {
+        Self {
+            data,
+            pagination,
+            stats: None,
+        }
+    }
+
     /// Create a new paginated response with stats
     pub fn with_stats(data: Vec<T>, pagination: PaginationMeta, stats: serde_json::Value) -> Self {
         Self {
@@ -0,0 +1,5 @@
+/// Anonymous abuse reports accepted per IP per hour unless the
+/// `reports_per_ip_per_hour` setting says otherwise
+pub const DEFAULT_REPORTS_PER_IP_PER_HOUR: u32 = 10;
+/// Largest configurable per-IP report budget
+pub const MAX_REPORTS_PER_IP_PER_HOUR: u32 = 1000;
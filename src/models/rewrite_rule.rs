@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Maximum number of rewrite rules an organization may define.
+/// Rules are evaluated on every redirect, so the list is kept short.
+pub const MAX_REWRITE_RULES_PER_ORG: i64 = 20;
+
+/// Most matching links whose KV mappings are re-synced after a rule is
+/// created, to stay within the Worker's subrequest limit.
+pub const MAX_REWRITE_RESYNC_LINKS: i64 = 100;
+
+/// Maximum length of a from/to pattern.
+pub const MAX_PATTERN_LENGTH: usize = 2048;
+
+/// An org-level destination rewrite rule.
+///
+/// Any link destination that starts with `from_pattern` has that prefix
+/// replaced with `to_pattern` at redirect time. Matching is an exact,
+/// case-sensitive prefix match on the stored (normalized) destination URL.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct RewriteRule {
+    #[schema(example = "rr_abc123")]
+    pub id: String,
+    #[schema(example = "org-123456")]
+    pub org_id: String,
+    #[schema(example = "https://docs.old-domain.com/")]
+    pub from_pattern: String,
+    #[schema(example = "https://docs.new-domain.com/")]
+    pub to_pattern: String,
+    #[schema(example = "user-123")]
+    pub created_by: String,
+    #[schema(example = 1609459200)]
+    pub created_at: i64,
+}
+
+impl RewriteRule {
+    pub fn generate_id() -> String {
+        format!("rr_{}", crate::utils::generate_short_code_with_length(16))
+    }
+
+    /// Apply this rule to a destination URL.
+    /// Returns the rewritten URL, or None if the rule does not match.
+    pub fn apply(&self, destination: &str) -> Option<String> {
+        destination
+            .strip_prefix(&self.from_pattern)
+            .map(|rest| format!("{}{}", self.to_pattern, rest))
+    }
+}
+
+/// Validate and normalize a rule pattern.
+///
+/// Patterns must be absolute http(s) URLs; they are normalized the same way
+/// destinations are on link creation so prefix matching lines up with the
+/// stored destination_url values.
+pub fn normalize_pattern(field: &str, pattern: &str) -> Result<String, String> {
+    let trimmed = pattern.trim();
+    if trimmed.is_empty() {
+        return Err(format!("{} is required", field));
+    }
+    if trimmed.len() > MAX_PATTERN_LENGTH {
+        return Err(format!(
+            "{} must be at most {} characters",
+            field, MAX_PATTERN_LENGTH
+        ));
+    }
+    crate::utils::validate_url(trimmed).map_err(|e| format!("{}: {}", field, e))
+}
+
+/// Rewrite a destination with the first matching rule (rules are evaluated in
+/// creation order). Rewritten URLs that are not valid http(s) URLs are ignored
+/// so a bad rule can never break a redirect.
+pub fn rewrite_destination(rules: &[RewriteRule], destination: &str) -> Option<String> {
+    rules
+        .iter()
+        .filter_map(|rule| rule.apply(destination))
+        .find(|rewritten| crate::utils::validate_url(rewritten).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(from: &str, to: &str) -> RewriteRule {
+        RewriteRule {
+            id: "rr_test".to_string(),
+            org_id: "org-1".to_string(),
+            from_pattern: from.to_string(),
+            to_pattern: to.to_string(),
+            created_by: "user-1".to_string(),
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_apply_replaces_prefix_and_keeps_path_and_query() {
+        let r = rule("https://docs.old.com/", "https://docs.new.com/");
+        assert_eq!(
+            r.apply("https://docs.old.com/guide/intro?lang=en#top"),
+            Some("https://docs.new.com/guide/intro?lang=en#top".to_string())
+        );
+    }
+
+    #[test]
+    fn test_apply_can_move_path_prefix() {
+        let r = rule(
+            "https://example.com/blog/",
+            "https://blog.example.com/posts/",
+        );
+        assert_eq!(
+            r.apply("https://example.com/blog/2024/hello"),
+            Some("https://blog.example.com/posts/2024/hello".to_string())
+        );
+    }
+
+    #[test]
+    fn test_apply_no_match() {
+        let r = rule("https://docs.old.com/", "https://docs.new.com/");
+        assert_eq!(r.apply("https://other.com/docs.old.com/"), None);
+        assert_eq!(r.apply("http://docs.old.com/guide"), None);
+        // Prefix matching is case-sensitive on the stored destination
+        assert!(r.apply("https://docs.old.com/Guide").is_some());
+        assert_eq!(r.apply("https://DOCS.old.com/guide"), None);
+    }
+
+    #[test]
+    fn test_rewrite_destination_uses_first_matching_rule() {
+        let rules = vec![
+            rule("https://a.com/special/", "https://special.com/"),
+            rule("https://a.com/", "https://b.com/"),
+        ];
+        assert_eq!(
+            rewrite_destination(&rules, "https://a.com/special/x"),
+            Some("https://special.com/x".to_string())
+        );
+        assert_eq!(
+            rewrite_destination(&rules, "https://a.com/other"),
+            Some("https://b.com/other".to_string())
+        );
+        assert_eq!(rewrite_destination(&rules, "https://c.com/"), None);
+    }
+
+    #[test]
+    fn test_rewrite_destination_skips_invalid_results() {
+        let rules = vec![
+            rule("https://a.com/", "javascript:"),
+            rule("https://a.com/", "https://b.com/"),
+        ];
+        assert_eq!(
+            rewrite_destination(&rules, "https://a.com/page"),
+            Some("https://b.com/page".to_string())
+        );
+    }
+
+    #[test]
+    fn test_normalize_pattern_adds_root_slash() {
+        assert_eq!(
+            normalize_pattern("from_pattern", "  https://docs.old.com  "),
+            Ok("https://docs.old.com/".to_string())
+        );
+        assert_eq!(
+            normalize_pattern("from_pattern", "https://Docs.Old.com/Guide"),
+            Ok("https://docs.old.com/Guide".to_string())
+        );
+    }
+
+    #[test]
+    fn test_normalize_pattern_rejects_invalid() {
+        assert!(normalize_pattern("from_pattern", "").is_err());
+        assert!(normalize_pattern("from_pattern", "docs.old.com").is_err());
+        assert!(normalize_pattern("to_pattern", "javascript:alert(1)").is_err());
+        let long = format!("https://a.com/{}", "x".repeat(MAX_PATTERN_LENGTH));
+        assert!(normalize_pattern("to_pattern", &long).is_err());
+    }
+
+    #[test]
+    fn test_rewrite_destination_empty_rules() {
+        assert_eq!(rewrite_destination(&[], "https://a.com/"), None);
+    }
+}
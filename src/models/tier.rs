@@ -100,7 +100,7 @@ impl fmt::Display for Tier {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct TierLimits {
     /// Maximum links per calendar month. None = unlimited.
     /// When exceeded, link creation is blocked with a clear error message.
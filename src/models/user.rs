@@ -29,6 +29,10 @@ pub struct User {
     pub suspended_by: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_login_at: Option<i64>,
+    /// OAuth provider used for the most recent login
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "github")]
+    pub last_login_provider: Option<String>,
 }
 
 impl User {
@@ -16,15 +16,33 @@ use utoipa::{Modify, OpenApi};
             crate::models::link::LinkStatus,
             crate::models::link::CreateLinkRequest,
             crate::models::link::UpdateLinkRequest,
+            crate::models::link::GetLinksByCodesRequest,
+            crate::models::link::BulkCreateLinkItem,
+            crate::models::link::BulkCreateLinksRequest,
+            crate::models::link::BulkCreateLinkResult,
+            crate::models::link::BulkCreateLinksResponse,
+            crate::models::link::BulkDeleteLinksRequest,
+            crate::models::link::BulkDeleteLinkError,
+            crate::models::link::BulkDeleteLinksResponse,
+            crate::models::link_alias::LinkAlias,
             crate::models::link::UtmParams,
 
             // Analytics models
             crate::models::analytics::LinkAnalyticsResponse,
             crate::models::analytics::OrgAnalyticsResponse,
+            crate::models::analytics::OrgTopCountriesResponse,
+            crate::models::analytics::OrgHeaderFieldBreakdownResponse,
+            crate::models::analytics::HeaderValueCount,
+            crate::models::analytics::PeriodComparison,
+            crate::models::analytics::LinkEvent,
             crate::models::analytics::TimeRange,
             crate::models::analytics::DailyClicks,
             crate::models::analytics::ReferrerCount,
             crate::models::analytics::CountryCount,
+            crate::models::analytics::StatusClickCount,
+            crate::models::analytics::ClicksByStatusResponse,
+            crate::models::deleted_link::DeletedLink,
+            crate::models::deleted_link::DeletedLinkAnalyticsResponse,
             crate::models::analytics::UserAgentCount,
             crate::models::analytics::TopLinkCount,
 
@@ -33,6 +51,7 @@ use utoipa::{Modify, OpenApi};
 
             // Tier models
             crate::models::tier::Tier,
+            crate::models::tier::TierLimits,
 
             // User models
             crate::models::user::User,
@@ -51,6 +70,7 @@ use utoipa::{Modify, OpenApi};
             crate::api::keys::CreateApiKeyRequest,
             crate::api::keys::CreateApiKeyResponse,
             crate::api::keys::UpdateApiKeyOrgsRequest,
+            crate::models::api_key::ApiKeyScope,
 
             // Version response
             crate::api::version::VersionResponse,
@@ -59,6 +79,7 @@ use utoipa::{Modify, OpenApi};
     paths(
         // Authentication
         crate::api::auth::providers::handle_list_auth_providers,
+        crate::api::auth::config::handle_get_auth_config,
         crate::api::auth::oauth::handle_github_login,
         crate::api::auth::oauth::handle_google_login,
         crate::api::auth::oauth::handle_oauth_callback,
@@ -69,19 +90,42 @@ use utoipa::{Modify, OpenApi};
         // Usage
         crate::api::analytics::usage::handle_get_usage,
 
+        // Dashboard
+        crate::api::dashboard::handle_get_dashboard,
+
         // Links
         crate::api::links::create::handle_create_link,
         crate::api::links::list::handle_list_links,
         crate::api::links::get::handle_get_link,
         crate::api::links::get::handle_get_link_by_code,
+        crate::api::links::get::handle_get_links_by_codes,
+        crate::api::links::exists::handle_link_exists,
+        crate::api::links::public::handle_get_public_link,
         crate::api::analytics::link::handle_get_link_analytics,
+        crate::api::analytics::export::handle_export_link_analytics,
+        crate::api::analytics::events::handle_get_link_events,
         crate::api::links::update::handle_update_link,
         crate::api::links::delete::handle_delete_link,
         crate::api::links::export::handle_export_links,
+        crate::api::links::export::handle_export_link,
+        crate::api::links::qr::handle_get_link_qr,
+        crate::api::links::aliases::handle_list_link_aliases,
+        crate::api::links::aliases::handle_create_link_alias,
+        crate::api::links::aliases::handle_delete_link_alias,
+        crate::api::links::reset_clicks::handle_reset_link_clicks,
+        crate::api::links::check_code::handle_check_code,
+        crate::api::links::check_destination::handle_check_destination,
         crate::api::links::import::handle_import_links,
+        crate::api::links::bulk::handle_bulk_create_links,
+        crate::api::links::bulk_delete::handle_bulk_delete_links,
+        crate::api::links::claim::handle_create_anonymous_link,
+        crate::api::links::claim::handle_claim_link,
 
         // Analytics
         crate::api::analytics::org::handle_get_org_analytics,
+        crate::api::analytics::org::handle_get_org_top_countries,
+        crate::api::analytics::org::handle_get_org_header_field_breakdown,
+        crate::api::analytics::export::handle_export_org_analytics,
 
         // Tags
         crate::api::tags::handle_get_org_tags,
@@ -90,6 +134,9 @@ use utoipa::{Modify, OpenApi};
         crate::api::tags::handle_rename_org_tag,
         crate::api::tags::handle_merge_tags,
         crate::api::tags::handle_get_tag_analytics,
+        crate::api::tags::handle_suggest_tags,
+        crate::api::tags::handle_bulk_tags,
+        crate::api::tags::handle_autocomplete_tags,
 
         // Organizations
         crate::api::orgs::list::handle_list_user_orgs,
@@ -100,7 +147,10 @@ use utoipa::{Modify, OpenApi};
         crate::api::orgs::crud::handle_delete_org,
         crate::api::orgs::settings::handle_get_org_settings,
         crate::api::orgs::settings::handle_update_org_settings,
+        crate::api::orgs::settings::handle_rotate_utm_signing_secret,
+        crate::api::orgs::limits::handle_get_org_limits,
         crate::api::orgs::members::handle_remove_member,
+        crate::api::orgs::invitations::handle_list_invitations,
         crate::api::orgs::invitations::handle_create_invitation,
         crate::api::orgs::invitations::handle_revoke_invitation,
         crate::api::orgs::invitations::handle_resend_invitation,
@@ -113,6 +163,7 @@ use utoipa::{Modify, OpenApi};
         // API Keys
         crate::api::keys::handle_create_api_key,
         crate::api::keys::handle_list_api_keys,
+        crate::api::keys::handle_list_keys,
         crate::api::keys::handle_revoke_api_key,
         crate::api::keys::handle_update_api_key_orgs,
 
@@ -130,14 +181,18 @@ use utoipa::{Modify, OpenApi};
 
         // System
         crate::api::version::handle_version,
+        crate::api::robots::handle_robots_txt,
+        crate::api::robots::handle_sitemap_xml,
 
         // Admin — Users
         crate::api::admin::users::handle_admin_list_users,
         crate::api::admin::users::handle_admin_get_user,
+        crate::api::admin::users::handle_admin_list_user_links,
         crate::api::admin::users::handle_admin_update_user_role,
         crate::api::admin::users::handle_admin_suspend_user,
         crate::api::admin::users::handle_admin_unsuspend_user,
         crate::api::admin::users::handle_admin_delete_user,
+        crate::api::admin::users::handle_admin_merge_users,
 
         // Admin — Counters
         crate::api::admin::counters::handle_admin_reset_monthly_counter,
@@ -147,15 +202,25 @@ use utoipa::{Modify, OpenApi};
         crate::api::links::admin::handle_admin_update_link_status,
         crate::api::links::admin::handle_admin_delete_link,
         crate::api::links::admin::handle_admin_sync_link_kv,
+        crate::api::links::admin::handle_admin_get_deleted_link_analytics,
+        crate::api::admin::audit::handle_admin_audit_kv_consistency,
 
         // Admin — Settings
         crate::api::settings::admin::handle_admin_get_settings,
         crate::api::settings::admin::handle_admin_update_setting,
 
         // Admin — Blacklist
+        crate::api::admin::analytics::handle_admin_clicks_by_status,
         crate::api::admin::blacklist::handle_admin_get_blacklist,
         crate::api::admin::blacklist::handle_admin_block_destination,
+        crate::api::admin::blacklist::handle_admin_preview_blacklist,
         crate::api::admin::blacklist::handle_admin_remove_blacklist,
+        crate::api::admin::code_words::handle_admin_list_code_words,
+        crate::api::admin::code_words::handle_admin_add_code_word,
+        crate::api::admin::code_words::handle_admin_remove_code_word,
+        crate::api::admin::config::handle_admin_export_config,
+        crate::api::admin::config::handle_admin_import_config,
+        crate::api::admin::short_codes::handle_admin_preview_short_codes,
 
         // Admin — Reports
         crate::api::reports::admin::handle_admin_get_reports,
@@ -194,6 +259,7 @@ use utoipa::{Modify, OpenApi};
         (name = "Billing", description = "Subscription and billing management"),
         (name = "Settings", description = "Instance and organization settings"),
         (name = "Usage", description = "Tier usage and limit information"),
+        (name = "Dashboard", description = "Combined data for the dashboard's first load"),
         (name = "Tags", description = "Link tag management"),
         (name = "Reports", description = "Abuse reporting"),
         (name = "System", description = "System information endpoints"),
@@ -2,10 +2,11 @@
 ///
 /// Data access layer for analytics queries (link-level and org-level).
 use crate::models::analytics::{
-    CountryCount, DailyClicks, ReferrerCount, TopLinkCount, UserAgentCount,
+    CountryCount, DailyClicks, ExportedAnalyticsEvent, Granularity, HeaderValueCount, LinkEvent,
+    ReferrerCount, StatusClickCount, TopLinkCount, UserAgentCount,
 };
 use worker::Result;
-use worker::d1::D1Database;
+use worker::d1::{D1Database, D1PreparedStatement};
 
 pub struct AnalyticsRepository;
 
@@ -26,7 +27,7 @@ impl AnalyticsRepository {
         end: i64,
     ) -> Result<i64> {
         let stmt = db.prepare(
-            "SELECT COUNT(*) as count
+            "SELECT COALESCE(SUM(sample_rate), 0) as count
              FROM analytics_events
              WHERE link_id = ?1 AND org_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4",
         );
@@ -47,7 +48,69 @@ impl AnalyticsRepository {
         }
     }
 
-    /// Get clicks over time for a link, grouped by day
+    /// Largest sample rate among a link's raw events in range (1 when none
+    /// were sampled). Counts from a sampled range undercount real clicks.
+    pub async fn get_link_max_sample_rate_in_range(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        org_id: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<i64> {
+        let result = db
+            .prepare(
+                "SELECT MAX(sample_rate) as sample_rate
+                 FROM analytics_events
+                 WHERE link_id = ?1 AND org_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4",
+            )
+            .bind(&[
+                link_id.into(),
+                org_id.into(),
+                (start as f64).into(),
+                (end as f64).into(),
+            ])?
+            .first::<serde_json::Value>(None)
+            .await?;
+
+        Ok(result
+            .and_then(|v| v["sample_rate"].as_f64())
+            .map(|rate| rate as i64)
+            .unwrap_or(1)
+            .max(1))
+    }
+
+    /// Lifetime event count and first/last click timestamps for a link
+    pub async fn get_link_click_summary(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        org_id: &str,
+    ) -> Result<(i64, Option<i64>, Option<i64>)> {
+        let stmt = db.prepare(
+            "SELECT COALESCE(SUM(sample_rate), 0) as count, MIN(timestamp) as first_click, MAX(timestamp) as last_click
+             FROM analytics_events
+             WHERE link_id = ?1 AND org_id = ?2",
+        );
+
+        let result = stmt
+            .bind(&[link_id.into(), org_id.into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+
+        Ok(match result {
+            Some(val) => (
+                val["count"].as_f64().unwrap_or(0.0) as i64,
+                val["first_click"].as_f64().map(|t| t as i64),
+                val["last_click"].as_f64().map(|t| t as i64),
+            ),
+            None => (0, None, None),
+        })
+    }
+
+    /// Get clicks over time for a link, grouped into `granularity` buckets.
+    /// Buckets are local to `tz_offset_secs` east of UTC (0 for UTC).
+    #[allow(clippy::too_many_arguments)]
     pub async fn get_link_clicks_over_time(
         &self,
         db: &D1Database,
@@ -55,14 +118,73 @@ impl AnalyticsRepository {
         org_id: &str,
         start: i64,
         end: i64,
+        tz_offset_secs: i64,
+        granularity: Granularity,
     ) -> Result<Vec<DailyClicks>> {
-        let stmt = db.prepare(
-            "SELECT date(timestamp, 'unixepoch') as date, COUNT(*) as count
+        let query = format!(
+            "SELECT {} as date, SUM(sample_rate) as count
              FROM analytics_events
              WHERE link_id = ?1 AND org_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
              GROUP BY date
              ORDER BY date ASC",
+            granularity.bucket_sql("timestamp + ?5")
+        );
+        let stmt = db.prepare(&query);
+
+        let results = stmt
+            .bind(&[
+                link_id.into(),
+                org_id.into(),
+                (start as f64).into(),
+                (end as f64).into(),
+                (tz_offset_secs as f64).into(),
+            ])?
+            .all()
+            .await?;
+
+        let rows = results.results::<serde_json::Value>()?;
+        let clicks = rows
+            .iter()
+            .filter_map(|row| {
+                let date = row["date"].as_str()?.to_string();
+                let count = row["count"].as_f64()? as i64;
+                Some(DailyClicks { date, count })
+            })
+            .collect();
+
+        Ok(clicks)
+    }
+
+    /// Get clicks over time for a link in evenly-spaced intervals of
+    /// `interval_secs` starting at `start`, for series downsampled to a point
+    /// budget. Each bucket is labelled with the local date it begins on, or
+    /// its local hour when `granularity` is hourly.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_link_clicks_over_time_downsampled(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        org_id: &str,
+        start: i64,
+        end: i64,
+        tz_offset_secs: i64,
+        granularity: Granularity,
+        interval_secs: i64,
+    ) -> Result<Vec<DailyClicks>> {
+        let label_granularity = if granularity == Granularity::Hour {
+            Granularity::Hour
+        } else {
+            Granularity::Day
+        };
+        let query = format!(
+            "SELECT CAST((timestamp - ?3) / ?6 AS INTEGER) as bucket, {} as date, SUM(sample_rate) as count
+             FROM analytics_events
+             WHERE link_id = ?1 AND org_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
+             GROUP BY bucket
+             ORDER BY bucket ASC",
+            label_granularity.bucket_sql("?3 + CAST((timestamp - ?3) / ?6 AS INTEGER) * ?6 + ?5")
         );
+        let stmt = db.prepare(&query);
 
         let results = stmt
             .bind(&[
@@ -70,6 +192,8 @@ impl AnalyticsRepository {
                 org_id.into(),
                 (start as f64).into(),
                 (end as f64).into(),
+                (tz_offset_secs as f64).into(),
+                (interval_secs as f64).into(),
             ])?
             .all()
             .await?;
@@ -98,7 +222,7 @@ impl AnalyticsRepository {
         limit: i64,
     ) -> Result<Vec<ReferrerCount>> {
         let stmt = db.prepare(
-            "SELECT COALESCE(referrer, 'Direct / Unknown') as referrer, COUNT(*) as count
+            "SELECT COALESCE(referrer, 'Direct / Unknown') as referrer, SUM(sample_rate) as count
              FROM analytics_events
              WHERE link_id = ?1 AND org_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
              GROUP BY referrer
@@ -141,7 +265,7 @@ impl AnalyticsRepository {
         limit: i64,
     ) -> Result<Vec<CountryCount>> {
         let stmt = db.prepare(
-            "SELECT COALESCE(country, 'Unknown') as country, COUNT(*) as count
+            "SELECT COALESCE(country, 'Unknown') as country, SUM(sample_rate) as count
              FROM analytics_events
              WHERE link_id = ?1 AND org_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
              GROUP BY country
@@ -184,7 +308,7 @@ impl AnalyticsRepository {
         limit: i64,
     ) -> Result<Vec<UserAgentCount>> {
         let stmt = db.prepare(
-            "SELECT COALESCE(user_agent, 'Unknown') as user_agent, COUNT(*) as count
+            "SELECT COALESCE(user_agent, 'Unknown') as user_agent, SUM(sample_rate) as count
              FROM analytics_events
              WHERE link_id = ?1 AND org_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
              GROUP BY user_agent
@@ -216,6 +340,43 @@ impl AnalyticsRepository {
         Ok(agents)
     }
 
+    // ── Instance-wide queries (admin) ────────────────────────────────────────
+
+    /// Clicks in range across all orgs, grouped by each link's current status.
+    /// Events of deleted links have no status and are left out.
+    pub async fn get_clicks_by_link_status(
+        &self,
+        db: &D1Database,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<StatusClickCount>> {
+        let results = db
+            .prepare(
+                "SELECT l.status as status,
+                        SUM(e.sample_rate) as clicks,
+                        COUNT(DISTINCT e.link_id) as links
+                 FROM analytics_events e
+                 JOIN links l ON l.id = e.link_id
+                 WHERE e.timestamp >= ?1 AND e.timestamp <= ?2
+                 GROUP BY l.status",
+            )
+            .bind(&[(start as f64).into(), (end as f64).into()])?
+            .all()
+            .await?;
+
+        let rows = results.results::<serde_json::Value>()?;
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                Some(StatusClickCount {
+                    status: row["status"].as_str()?.to_string(),
+                    clicks: row["clicks"].as_f64()? as i64,
+                    links: row["links"].as_f64()? as i64,
+                })
+            })
+            .collect())
+    }
+
     // ── Org-level queries ────────────────────────────────────────────────────
 
     /// Get total click count for an org within a time range
@@ -227,7 +388,7 @@ impl AnalyticsRepository {
         end: i64,
     ) -> Result<i64> {
         let stmt = db.prepare(
-            "SELECT COUNT(*) as count
+            "SELECT COALESCE(SUM(sample_rate), 0) as count
              FROM analytics_events
              WHERE org_id = ?1 AND timestamp >= ?2 AND timestamp <= ?3",
         );
@@ -243,6 +404,32 @@ impl AnalyticsRepository {
         }
     }
 
+    /// Largest sample rate among an org's raw events in range (1 when none
+    /// were sampled).
+    pub async fn get_org_max_sample_rate_in_range(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<i64> {
+        let result = db
+            .prepare(
+                "SELECT MAX(sample_rate) as sample_rate
+                 FROM analytics_events
+                 WHERE org_id = ?1 AND timestamp >= ?2 AND timestamp <= ?3",
+            )
+            .bind(&[org_id.into(), (start as f64).into(), (end as f64).into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+
+        Ok(result
+            .and_then(|v| v["sample_rate"].as_f64())
+            .map(|rate| rate as i64)
+            .unwrap_or(1)
+            .max(1))
+    }
+
     /// Get unique link count clicked in an org within a time range
     pub async fn get_org_unique_links_clicked(
         &self,
@@ -277,7 +464,7 @@ impl AnalyticsRepository {
         end: i64,
     ) -> Result<Vec<DailyClicks>> {
         let stmt = db.prepare(
-            "SELECT date(timestamp, 'unixepoch') as date, COUNT(*) as count
+            "SELECT date(timestamp, 'unixepoch') as date, SUM(sample_rate) as count
              FROM analytics_events
              WHERE org_id = ?1 AND timestamp >= ?2 AND timestamp <= ?3
              GROUP BY date
@@ -312,7 +499,7 @@ impl AnalyticsRepository {
         limit: i64,
     ) -> Result<Vec<TopLinkCount>> {
         let stmt = db.prepare(
-            "SELECT ae.link_id, l.short_code, l.title, l.custom_domain, COUNT(*) as count
+            "SELECT ae.link_id, l.short_code, l.title, l.custom_domain, SUM(ae.sample_rate) as count
              FROM analytics_events ae
              JOIN links l ON ae.link_id = l.id
              WHERE ae.org_id = ?1 AND ae.timestamp >= ?2 AND ae.timestamp <= ?3
@@ -363,7 +550,7 @@ impl AnalyticsRepository {
         limit: i64,
     ) -> Result<Vec<ReferrerCount>> {
         let stmt = db.prepare(
-            "SELECT COALESCE(referrer, 'Direct / Unknown') as referrer, COUNT(*) as count
+            "SELECT COALESCE(referrer, 'Direct / Unknown') as referrer, SUM(sample_rate) as count
              FROM analytics_events
              WHERE org_id = ?1 AND timestamp >= ?2 AND timestamp <= ?3
              GROUP BY referrer
@@ -404,7 +591,7 @@ impl AnalyticsRepository {
         limit: i64,
     ) -> Result<Vec<CountryCount>> {
         let stmt = db.prepare(
-            "SELECT COALESCE(country, 'Unknown') as country, COUNT(*) as count
+            "SELECT COALESCE(country, 'Unknown') as country, SUM(sample_rate) as count
              FROM analytics_events
              WHERE org_id = ?1 AND timestamp >= ?2 AND timestamp <= ?3
              GROUP BY country
@@ -435,6 +622,52 @@ impl AnalyticsRepository {
         Ok(countries)
     }
 
+    /// Clicks per recorded value of a request header for an org. `field` is
+    /// a validated header name; events that did not record it are skipped.
+    pub async fn get_org_header_field_breakdown(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        field: &str,
+        start: i64,
+        end: i64,
+        limit: i64,
+    ) -> Result<Vec<HeaderValueCount>> {
+        let stmt = db.prepare(
+            "SELECT json_extract(extra, ?2) as value, SUM(sample_rate) as count
+             FROM analytics_events
+             WHERE org_id = ?1 AND timestamp >= ?3 AND timestamp <= ?4
+               AND extra IS NOT NULL AND json_extract(extra, ?2) IS NOT NULL
+             GROUP BY value
+             ORDER BY count DESC, value ASC
+             LIMIT ?5",
+        );
+
+        let path = format!("$.\"{}\"", field);
+        let results = stmt
+            .bind(&[
+                org_id.into(),
+                path.into(),
+                (start as f64).into(),
+                (end as f64).into(),
+                (limit as f64).into(),
+            ])?
+            .all()
+            .await?;
+
+        let rows = results.results::<serde_json::Value>()?;
+        let values = rows
+            .iter()
+            .filter_map(|row| {
+                let value = row["value"].as_str()?.to_string();
+                let count = row["count"].as_f64()? as i64;
+                Some(HeaderValueCount { value, count })
+            })
+            .collect();
+
+        Ok(values)
+    }
+
     /// Get top user agents for an org (raw strings, parsed client-side)
     pub async fn get_org_top_user_agents(
         &self,
@@ -445,7 +678,7 @@ impl AnalyticsRepository {
         limit: i64,
     ) -> Result<Vec<UserAgentCount>> {
         let stmt = db.prepare(
-            "SELECT COALESCE(user_agent, 'Unknown') as user_agent, COUNT(*) as count
+            "SELECT COALESCE(user_agent, 'Unknown') as user_agent, SUM(sample_rate) as count
              FROM analytics_events
              WHERE org_id = ?1 AND timestamp >= ?2 AND timestamp <= ?3
              GROUP BY user_agent
@@ -543,6 +776,201 @@ impl AnalyticsRepository {
         Ok(links)
     }
 
+    /// Page of raw org events in a time range, oldest first, with the link's
+    /// short code joined in. Keyset-paginated on the event id: pass the last
+    /// id of the previous page as `after_id` (0 for the first page).
+    pub async fn list_org_events_page(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        start: i64,
+        end: i64,
+        after_id: i64,
+        limit: i64,
+    ) -> Result<Vec<ExportedAnalyticsEvent>> {
+        let stmt = db.prepare(
+            "SELECT e.id, e.timestamp, e.link_id, COALESCE(l.short_code, '') as short_code,
+                    e.referrer, e.user_agent, e.country, e.city
+             FROM analytics_events e
+             LEFT JOIN links l ON l.id = e.link_id
+             WHERE e.org_id = ?1 AND e.timestamp >= ?2 AND e.timestamp <= ?3 AND e.id > ?4
+             ORDER BY e.id ASC
+             LIMIT ?5",
+        );
+
+        let results = stmt
+            .bind(&[
+                org_id.into(),
+                (start as f64).into(),
+                (end as f64).into(),
+                (after_id as f64).into(),
+                (limit as f64).into(),
+            ])?
+            .all()
+            .await?;
+
+        let rows = results.results::<serde_json::Value>()?;
+        let events = rows
+            .iter()
+            .filter_map(|row| {
+                Some(ExportedAnalyticsEvent {
+                    id: row["id"].as_f64()? as i64,
+                    timestamp: row["timestamp"].as_f64()? as i64,
+                    link_id: row["link_id"].as_str()?.to_string(),
+                    link_short_code: row["short_code"].as_str().unwrap_or("").to_string(),
+                    referrer: row["referrer"].as_str().map(|s| s.to_string()),
+                    user_agent: row["user_agent"].as_str().map(|s| s.to_string()),
+                    country: row["country"].as_str().map(|s| s.to_string()),
+                    city: row["city"].as_str().map(|s| s.to_string()),
+                })
+            })
+            .collect();
+        Ok(events)
+    }
+
+    /// Page of a link's raw events since `start`, newest first
+    pub async fn list_link_events(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        start: i64,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<LinkEvent>> {
+        let stmt = db.prepare(
+            "SELECT id, timestamp, referrer, country, city, user_agent
+             FROM analytics_events
+             WHERE link_id = ?1 AND timestamp >= ?2
+             ORDER BY timestamp DESC, id DESC
+             LIMIT ?3 OFFSET ?4",
+        );
+
+        let results = stmt
+            .bind(&[
+                link_id.into(),
+                (start as f64).into(),
+                (limit as f64).into(),
+                (offset as f64).into(),
+            ])?
+            .all()
+            .await?;
+
+        results.results::<LinkEvent>()
+    }
+
+    /// Number of a link's raw events since `start`
+    pub async fn count_link_events(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        start: i64,
+    ) -> Result<i64> {
+        let stmt = db.prepare(
+            "SELECT COUNT(*) as count
+             FROM analytics_events
+             WHERE link_id = ?1 AND timestamp >= ?2",
+        );
+
+        let result = stmt
+            .bind(&[link_id.into(), (start as f64).into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+
+        Ok(result
+            .and_then(|v| v["count"].as_f64())
+            .map(|c| c as i64)
+            .unwrap_or(0))
+    }
+
+    /// Page of a link's raw events with `start <= timestamp <= end`, oldest
+    /// first. Keyset-paginated on the event id: pass the last id of the
+    /// previous page as `after_id` (0 for the first page).
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_link_analytics_events_in_range(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        org_id: &str,
+        start: i64,
+        end: i64,
+        after_id: i64,
+        limit: i64,
+    ) -> Result<Vec<LinkEvent>> {
+        let stmt = db.prepare(
+            "SELECT id, timestamp, referrer, country, city, user_agent
+             FROM analytics_events
+             WHERE link_id = ?1 AND org_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4 AND id > ?5
+             ORDER BY id ASC
+             LIMIT ?6",
+        );
+
+        let results = stmt
+            .bind(&[
+                link_id.into(),
+                org_id.into(),
+                (start as f64).into(),
+                (end as f64).into(),
+                (after_id as f64).into(),
+                (limit as f64).into(),
+            ])?
+            .all()
+            .await?;
+
+        results.results::<LinkEvent>()
+    }
+
+    /// Number of a link's raw events with `start <= timestamp <= end`
+    pub async fn count_link_events_in_range(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        org_id: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<i64> {
+        let stmt = db.prepare(
+            "SELECT COUNT(*) as count
+             FROM analytics_events
+             WHERE link_id = ?1 AND org_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4",
+        );
+
+        let result = stmt
+            .bind(&[
+                link_id.into(),
+                org_id.into(),
+                (start as f64).into(),
+                (end as f64).into(),
+            ])?
+            .first::<serde_json::Value>(None)
+            .await?;
+
+        Ok(result
+            .and_then(|v| v["count"].as_f64())
+            .map(|c| c as i64)
+            .unwrap_or(0))
+    }
+
+    /// Statement deleting a link's raw events with `start <= timestamp <= end`
+    pub fn delete_link_events_in_range_statement(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        org_id: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<D1PreparedStatement> {
+        db.prepare(
+            "DELETE FROM analytics_events
+             WHERE link_id = ?1 AND org_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4",
+        )
+        .bind(&[
+            link_id.into(),
+            org_id.into(),
+            (start as f64).into(),
+            (end as f64).into(),
+        ])
+    }
+
     // ── Usage queries ────────────────────────────────────────────────────────
 
     /// Get monthly counter for billing account
@@ -2,6 +2,8 @@
 ///
 /// Provides data access for the `api_keys` table covering both admin and
 /// user-facing operations.
+use crate::models::ApiKeyScope;
+use crate::models::api_key::scopes_from_column;
 use crate::utils::now_timestamp;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
@@ -21,6 +23,9 @@ pub struct ApiKeyRecord {
     /// Empty means the key is legacy and falls back to the org_id on the api_keys row.
     #[serde(default)]
     pub org_ids: Vec<String>,
+    /// What the key may do; see `ApiKeyScope`.
+    #[serde(default)]
+    pub scopes: Vec<ApiKeyScope>,
 }
 
 /// Lightweight row used only to gather org IDs in a batch query.
@@ -39,6 +44,18 @@ pub struct ApiKeyWithTierRecord {
     pub expires_at: Option<i64>,
     pub status: String,
     pub tier: Option<String>,
+    /// Comma-separated scope names; `NULL` for keys created before scopes.
+    pub scopes: Option<String>,
+    pub last_used_at: Option<i64>,
+}
+
+/// An active key that has not been used since the inactivity cutoff.
+#[derive(Debug, Deserialize)]
+pub struct InactiveApiKeyRecord {
+    pub id: String,
+    pub user_id: String,
+    pub created_at: i64,
+    pub last_used_at: Option<i64>,
 }
 
 /// Admin view of an API key with user/org details.
@@ -278,10 +295,11 @@ impl ApiKeyRepository {
         hint: &str,
         created_at: i64,
         expires_at: Option<i64>,
+        scopes: &str,
     ) -> Result<()> {
         db.prepare(
-            "INSERT INTO api_keys (id, user_id, org_id, name, key_hash, hint, created_at, expires_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT INTO api_keys (id, user_id, org_id, name, key_hash, hint, created_at, expires_at, scopes)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
         )
         .bind(&[
             id.into(),
@@ -294,6 +312,7 @@ impl ApiKeyRepository {
             expires_at
                 .map(|t| (t as f64).into())
                 .unwrap_or(worker::wasm_bindgen::JsValue::NULL),
+            scopes.into(),
         ])?
         .run()
         .await?;
@@ -314,11 +333,12 @@ impl ApiKeyRepository {
             created_at: i64,
             last_used_at: Option<i64>,
             expires_at: Option<i64>,
+            scopes: Option<String>,
         }
 
         let rows = db
             .prepare(
-                "SELECT id, name, hint, created_at, last_used_at, expires_at
+                "SELECT id, name, hint, created_at, last_used_at, expires_at, scopes
                  FROM api_keys
                  WHERE user_id = ?1 AND status = 'active'
                  ORDER BY created_at DESC",
@@ -373,6 +393,7 @@ impl ApiKeyRepository {
                     last_used_at: r.last_used_at,
                     expires_at: r.expires_at,
                     org_ids,
+                    scopes: scopes_from_column(r.scopes.as_deref()),
                 }
             })
             .collect())
@@ -551,7 +572,8 @@ impl ApiKeyRepository {
         key_hash: &str,
     ) -> Result<Option<ApiKeyWithTierRecord>> {
         let stmt = db.prepare(
-            "SELECT ak.id, ak.user_id, ak.org_id, ak.expires_at, ak.status, ba.tier
+            "SELECT ak.id, ak.user_id, ak.org_id, ak.expires_at, ak.status, ba.tier, ak.scopes,
+                    ak.last_used_at
              FROM api_keys ak
              JOIN organizations o ON ak.org_id = o.id
              LEFT JOIN billing_accounts ba ON o.billing_account_id = ba.id
@@ -564,18 +586,50 @@ impl ApiKeyRepository {
     }
 
     /// Update the last_used_at timestamp for an API key.
+    ///
+    /// Skips the write when the stored value is less than `debounce_secs` old,
+    /// so concurrent requests for the same key do not all hit D1.
     pub async fn update_last_used(
         &self,
         db: &D1Database,
         key_id: &str,
         timestamp: i64,
+        debounce_secs: i64,
     ) -> Result<()> {
-        let stmt = db.prepare("UPDATE api_keys SET last_used_at = ?1 WHERE id = ?2");
-        stmt.bind(&[(timestamp as f64).into(), key_id.into()])?
-            .run()
-            .await?;
+        let stmt = db.prepare(
+            "UPDATE api_keys SET last_used_at = ?1
+             WHERE id = ?2 AND (last_used_at IS NULL OR last_used_at <= ?3)",
+        );
+        stmt.bind(&[
+            (timestamp as f64).into(),
+            key_id.into(),
+            ((timestamp - debounce_secs) as f64).into(),
+        ])?
+        .run()
+        .await?;
         Ok(())
     }
+
+    /// List active keys whose last use (or creation, if never used) is at or
+    /// before `cutoff`, oldest first.
+    pub async fn list_inactive_active_keys(
+        &self,
+        db: &D1Database,
+        cutoff: i64,
+        limit: i64,
+    ) -> Result<Vec<InactiveApiKeyRecord>> {
+        db.prepare(
+            "SELECT id, user_id, created_at, last_used_at
+             FROM api_keys
+             WHERE status = 'active' AND COALESCE(last_used_at, created_at) <= ?1
+             ORDER BY COALESCE(last_used_at, created_at) ASC
+             LIMIT ?2",
+        )
+        .bind(&[(cutoff as f64).into(), (limit as f64).into()])?
+        .all()
+        .await?
+        .results::<InactiveApiKeyRecord>()
+    }
 }
 
 impl Default for ApiKeyRepository {
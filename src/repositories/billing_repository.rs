@@ -341,6 +341,65 @@ impl BillingRepository {
         Ok(true)
     }
 
+    /// Add `count` links to the monthly counter in a single statement, only if
+    /// the total stays within `max_value`. Returns false, leaving the counter
+    /// untouched, when the whole batch does not fit.
+    pub async fn reserve_monthly_links(
+        &self,
+        db: &D1Database,
+        billing_account_id: &str,
+        year_month: &str,
+        count: i64,
+        max_value: i64,
+    ) -> Result<bool> {
+        let now = now_timestamp();
+        let result = db
+            .prepare(
+                "INSERT INTO monthly_counters (billing_account_id, year_month, links_created, updated_at)
+                 SELECT ?1, ?2, ?3, ?4 WHERE ?3 <= ?5
+                 ON CONFLICT(billing_account_id, year_month)
+                 DO UPDATE SET links_created = links_created + ?3, updated_at = ?4
+                 WHERE links_created + ?3 <= ?5",
+            )
+            .bind(&[
+                billing_account_id.into(),
+                year_month.into(),
+                (count as f64).into(),
+                (now as f64).into(),
+                (max_value as f64).into(),
+            ])?
+            .run()
+            .await?;
+        Ok(result
+            .meta()?
+            .and_then(|m| m.changes)
+            .is_some_and(|changes| changes > 0))
+    }
+
+    /// Give back links reserved with `reserve_monthly_links` that were not created.
+    pub async fn release_monthly_links(
+        &self,
+        db: &D1Database,
+        billing_account_id: &str,
+        year_month: &str,
+        count: i64,
+    ) -> Result<()> {
+        db.prepare(
+            "UPDATE monthly_counters
+             SET links_created = MAX(links_created - ?3, 0), updated_at = ?4
+             WHERE billing_account_id = ?1 AND year_month = ?2",
+        )
+        .bind(&[
+            billing_account_id.into(),
+            year_month.into(),
+            (count as f64).into(),
+            (now_timestamp() as f64).into(),
+        ])?
+        .run()
+        .await?;
+        Ok(())
+    }
+
     /// Reset monthly counter for a billing account (admin only, for testing).
     pub async fn reset_monthly_counter(
         &self,
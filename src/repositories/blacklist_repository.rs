@@ -4,7 +4,7 @@
 use crate::utils::normalize_url_for_blacklist;
 use crate::utils::now_timestamp;
 use worker::Result;
-use worker::d1::D1Database;
+use worker::d1::{D1Database, D1PreparedStatement};
 
 /// A single blacklist entry.
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -56,6 +56,21 @@ impl BlacklistRepository {
         reason: &str,
         created_by: &str,
     ) -> Result<()> {
+        self.add_statement(db, destination, match_type, reason, created_by)?
+            .run()
+            .await?;
+        Ok(())
+    }
+
+    /// Insert statement for a new entry, for callers that batch several writes.
+    pub fn add_statement(
+        &self,
+        db: &D1Database,
+        destination: &str,
+        match_type: &str,
+        reason: &str,
+        created_by: &str,
+    ) -> Result<D1PreparedStatement> {
         let id = uuid::Uuid::new_v4().to_string();
         let now = now_timestamp();
         db.prepare(
@@ -69,10 +84,13 @@ impl BlacklistRepository {
             reason.into(),
             created_by.into(),
             (now as f64).into(),
-        ])?
-        .run()
-        .await?;
-        Ok(())
+        ])
+    }
+
+    /// Delete statement for an entry, for callers that batch several writes.
+    pub fn remove_statement(&self, db: &D1Database, id: &str) -> Result<D1PreparedStatement> {
+        db.prepare("DELETE FROM destination_blacklist WHERE id = ?1")
+            .bind(&[id.into()])
     }
 
     /// Hard-delete a blacklist entry by ID.
@@ -112,8 +130,68 @@ impl BlacklistRepository {
         results.results::<crate::models::Link>()
     }
 
+    /// Active links whose destination contains `needle` (case-insensitive),
+    /// newest first, at most `limit`. Narrows the links a blacklist preview
+    /// has to check with `entry_matches`.
+    pub async fn list_preview_candidates(
+        &self,
+        db: &D1Database,
+        needle: &str,
+        limit: i64,
+    ) -> Result<Vec<crate::models::Link>> {
+        let results = db
+            .prepare(
+                "SELECT id, org_id, short_code, destination_url, title, created_by,
+                        created_at, updated_at, expires_at, status, click_count,
+                        utm_params, forward_query_params, redirect_type
+                 FROM links
+                 WHERE status = 'active' AND instr(lower(destination_url), lower(?1)) > 0
+                 ORDER BY created_at DESC
+                 LIMIT ?2",
+            )
+            .bind(&[needle.into(), (limit as f64).into()])?
+            .all()
+            .await?;
+        results.results::<crate::models::Link>()
+    }
+
+    /// Whether a single entry blocks `destination`, using the same rules as
+    /// the queries in `find_match_type`: `exact` entries equal the normalized
+    /// URL (or normalize to it), `domain` entries are contained in the host
+    /// (ASCII case-insensitive, like SQL `LIKE`).
+    pub fn entry_matches(entry: &str, match_type: &str, destination: &str) -> bool {
+        match match_type {
+            "exact" => {
+                let normalized = normalize_url_for_blacklist(destination)
+                    .unwrap_or_else(|_| destination.to_string());
+                normalized == entry
+                    || normalize_url_for_blacklist(entry).is_ok_and(|e| e == normalized)
+            }
+            "domain" => url::Url::parse(destination)
+                .ok()
+                .and_then(|url| {
+                    url.host_str().map(|host| {
+                        host.to_ascii_lowercase()
+                            .contains(&entry.to_ascii_lowercase())
+                    })
+                })
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+
     /// Check if a destination is blacklisted (exact or domain match).
     pub async fn is_blacklisted(&self, db: &D1Database, destination: &str) -> Result<bool> {
+        Ok(self.find_match_type(db, destination).await?.is_some())
+    }
+
+    /// The `match_type` ("exact" or "domain") of the first blacklist entry
+    /// blocking `destination`, or None when it is not blacklisted.
+    pub async fn find_match_type(
+        &self,
+        db: &D1Database,
+        destination: &str,
+    ) -> Result<Option<&'static str>> {
         // Normalize the destination URL for comparison
         let normalized_destination = match normalize_url_for_blacklist(destination) {
             Ok(url) => url,
@@ -134,27 +212,29 @@ impl BlacklistRepository {
             .first::<serde_json::Value>(None)
             .await
         {
-            return Ok(true);
+            return Ok(Some("exact"));
         }
 
         // Then check domain match (still uses original domain extraction logic)
         let url = match url::Url::parse(destination) {
             Ok(u) => u,
-            Err(_) => return Ok(false),
+            Err(_) => return Ok(None),
         };
 
-        let domain = url.host_str().unwrap_or("");
-        let domain_stmt = db.prepare(
-            "SELECT 1 FROM destination_blacklist
-             WHERE ?1 LIKE '%' || destination || '%' AND match_type = 'domain'
-             LIMIT 1",
-        );
-        if let Ok(Some(_)) = domain_stmt
-            .bind(&[domain.into()])?
-            .first::<serde_json::Value>(None)
-            .await
-        {
-            return Ok(true);
+        // mailto:/tel: destinations have no host, so only exact entries apply
+        if let Some(domain) = url.host_str() {
+            let domain_stmt = db.prepare(
+                "SELECT 1 FROM destination_blacklist
+                 WHERE ?1 LIKE '%' || destination || '%' AND match_type = 'domain'
+                 LIMIT 1",
+            );
+            if let Ok(Some(_)) = domain_stmt
+                .bind(&[domain.into()])?
+                .first::<serde_json::Value>(None)
+                .await
+            {
+                return Ok(Some("domain"));
+            }
         }
 
         // Finally, check if any normalized blacklist entries match our normalized destination
@@ -166,14 +246,13 @@ impl BlacklistRepository {
             .results::<serde_json::Value>()?;
         for entry in all_exact_entries {
             if let Some(dest) = entry.get("destination").and_then(|d| d.as_str())
-                && let Ok(normalized_entry) = normalize_url_for_blacklist(dest)
-                && normalized_entry == normalized_destination
+                && Self::entry_matches(dest, "exact", destination)
             {
-                return Ok(true);
+                return Ok(Some("exact"));
             }
         }
 
-        Ok(false)
+        Ok(None)
     }
 }
 
@@ -182,3 +261,42 @@ impl Default for BlacklistRepository {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_matches() {
+        assert!(BlacklistRepository::entry_matches(
+            "https://evil.com/path",
+            "exact",
+            "https://WWW.evil.com/path/#frag"
+        ));
+        assert!(!BlacklistRepository::entry_matches(
+            "https://evil.com/path",
+            "exact",
+            "https://evil.com/other"
+        ));
+        assert!(BlacklistRepository::entry_matches(
+            "evil.com",
+            "domain",
+            "https://sub.EVIL.com/anything"
+        ));
+        assert!(!BlacklistRepository::entry_matches(
+            "evil.com",
+            "domain",
+            "https://example.com/?u=evil.com"
+        ));
+        assert!(!BlacklistRepository::entry_matches(
+            "evil.com",
+            "domain",
+            "mailto:a@evil.com"
+        ));
+        assert!(!BlacklistRepository::entry_matches(
+            "evil.com",
+            "regex",
+            "https://evil.com/"
+        ));
+    }
+}
@@ -0,0 +1,104 @@
+/// Code Word Repository
+///
+/// Admin-only data access for the `disallowed_code_words` table.
+use crate::utils::code_words::DisallowedWords;
+use crate::utils::now_timestamp;
+use worker::Result;
+use worker::d1::D1Database;
+
+/// A single disallowed-words entry.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct CodeWordEntry {
+    pub id: String,
+    pub word: String,
+    pub kind: String,
+    pub created_by: String,
+    pub created_at: i64,
+}
+
+#[derive(Default)]
+pub struct CodeWordRepository;
+
+impl CodeWordRepository {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Check whether an entry with the same word + kind already exists.
+    pub async fn is_duplicate(&self, db: &D1Database, word: &str, kind: &str) -> Result<bool> {
+        let stmt = db.prepare(
+            "SELECT 1 FROM disallowed_code_words
+             WHERE word = ?1 AND kind = ?2
+             LIMIT 1",
+        );
+        Ok(stmt
+            .bind(&[word.into(), kind.into()])?
+            .first::<serde_json::Value>(None)
+            .await?
+            .is_some())
+    }
+
+    /// Insert a new entry and return it.
+    pub async fn add(
+        &self,
+        db: &D1Database,
+        word: &str,
+        kind: &str,
+        created_by: &str,
+    ) -> Result<CodeWordEntry> {
+        let entry = CodeWordEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            word: word.to_string(),
+            kind: kind.to_string(),
+            created_by: created_by.to_string(),
+            created_at: now_timestamp(),
+        };
+        db.prepare(
+            "INSERT INTO disallowed_code_words (id, word, kind, created_by, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .bind(&[
+            entry.id.as_str().into(),
+            entry.word.as_str().into(),
+            entry.kind.as_str().into(),
+            entry.created_by.as_str().into(),
+            (entry.created_at as f64).into(),
+        ])?
+        .run()
+        .await?;
+        Ok(entry)
+    }
+
+    /// Hard-delete an entry by ID. Returns false when no entry matched.
+    pub async fn remove(&self, db: &D1Database, id: &str) -> Result<bool> {
+        let result = db
+            .prepare("DELETE FROM disallowed_code_words WHERE id = ?1")
+            .bind(&[id.into()])?
+            .run()
+            .await?;
+        Ok(result.meta()?.and_then(|m| m.changes).unwrap_or(0) > 0)
+    }
+
+    /// Return all entries, deny words first, then alphabetically.
+    pub async fn list_all(&self, db: &D1Database) -> Result<Vec<CodeWordEntry>> {
+        let results = db
+            .prepare(
+                "SELECT id, word, kind, created_by, created_at
+                 FROM disallowed_code_words
+                 ORDER BY kind DESC, word ASC",
+            )
+            .all()
+            .await?;
+        results.results::<CodeWordEntry>()
+    }
+
+    /// Load the list checked against user-chosen short codes.
+    pub async fn get_disallowed_words(&self, db: &D1Database) -> Result<DisallowedWords> {
+        let entries = self.list_all(db).await?;
+        Ok(DisallowedWords::new(
+            entries
+                .iter()
+                .map(|entry| (entry.word.as_str(), entry.kind.as_str())),
+        ))
+    }
+}
@@ -107,14 +107,20 @@ impl CustomDomainRepository {
         .await
     }
 
-    /// Count custom domains for an org (all statuses, excluding failed)
-    pub async fn count_non_failed_for_org(&self, db: &D1Database, org_id: &str) -> Result<u32> {
+    /// Count non-failed custom domains across every org on a billing account
+    /// (tier limits apply per account, not per org)
+    pub async fn count_non_failed_for_billing_account(
+        &self,
+        db: &D1Database,
+        billing_account_id: &str,
+    ) -> Result<u32> {
         let result = db
             .prepare(
-                "SELECT COUNT(*) as count FROM custom_domains
-                 WHERE org_id = ?1 AND status != 'failed'",
+                "SELECT COUNT(*) as count FROM custom_domains cd
+                 JOIN organizations o ON o.id = cd.org_id
+                 WHERE o.billing_account_id = ?1 AND cd.status != 'failed'",
             )
-            .bind(&[org_id.into()])?
+            .bind(&[billing_account_id.into()])?
             .first::<serde_json::Value>(None)
             .await?;
 
@@ -0,0 +1,162 @@
+use crate::models::analytics::{CountryCount, LinkEvent, ReferrerCount};
+use crate::models::deleted_link::DeletedLink;
+use worker::Result;
+use worker::d1::D1Database;
+
+/// Tombstones of admin-deleted links and their retained click events.
+/// Rows are written by `LinkRepository::retain_and_delete`.
+pub struct DeletedLinkRepository;
+
+impl DeletedLinkRepository {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Get a tombstone that is still within its retention period
+    pub async fn get(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        now: i64,
+    ) -> Result<Option<DeletedLink>> {
+        db.prepare(
+            "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, click_count, status, deleted_by, deleted_at, retain_until
+             FROM deleted_links
+             WHERE id = ?1 AND retain_until > ?2",
+        )
+        .bind(&[link_id.into(), (now as f64).into()])?
+        .first::<DeletedLink>(None)
+        .await
+    }
+
+    /// Clicks recorded as retained events, scaled up for sampled events
+    pub async fn get_total_clicks(&self, db: &D1Database, link_id: &str) -> Result<i64> {
+        let row = db
+            .prepare(
+                "SELECT COALESCE(SUM(sample_rate), 0) as total FROM deleted_link_events WHERE link_id = ?1",
+            )
+            .bind(&[link_id.into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+        Ok(row.and_then(|r| r["total"].as_f64()).unwrap_or(0.0) as i64)
+    }
+
+    /// Top referrers among the retained events, scaled up for sampled events
+    pub async fn get_top_referrers(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        limit: i64,
+    ) -> Result<Vec<ReferrerCount>> {
+        let results = db
+            .prepare(
+                "SELECT COALESCE(referrer, 'Direct / Unknown') as referrer, SUM(sample_rate) as count
+                 FROM deleted_link_events
+                 WHERE link_id = ?1
+                 GROUP BY referrer
+                 ORDER BY count DESC
+                 LIMIT ?2",
+            )
+            .bind(&[link_id.into(), (limit as f64).into()])?
+            .all()
+            .await?;
+
+        Ok(results
+            .results::<serde_json::Value>()?
+            .iter()
+            .filter_map(|row| {
+                Some(ReferrerCount {
+                    referrer: row["referrer"].as_str()?.to_string(),
+                    count: row["count"].as_f64()? as i64,
+                })
+            })
+            .collect())
+    }
+
+    /// Top countries among the retained events, scaled up for sampled events
+    pub async fn get_top_countries(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        limit: i64,
+    ) -> Result<Vec<CountryCount>> {
+        let results = db
+            .prepare(
+                "SELECT CASE WHEN country IS NULL OR country IN ('', 'XX') THEN 'Unknown' ELSE country END as country_label,
+                        SUM(sample_rate) as count
+                 FROM deleted_link_events
+                 WHERE link_id = ?1
+                 GROUP BY country_label
+                 ORDER BY count DESC, country_label ASC
+                 LIMIT ?2",
+            )
+            .bind(&[link_id.into(), (limit as f64).into()])?
+            .all()
+            .await?;
+
+        Ok(results
+            .results::<serde_json::Value>()?
+            .iter()
+            .filter_map(|row| {
+                Some(CountryCount {
+                    country: row["country_label"].as_str()?.to_string(),
+                    count: row["count"].as_f64()? as i64,
+                })
+            })
+            .collect())
+    }
+
+    /// Most recent retained events, newest first
+    pub async fn list_recent_events(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        limit: i64,
+    ) -> Result<Vec<LinkEvent>> {
+        let results = db
+            .prepare(
+                "SELECT id, timestamp, referrer, country, city, user_agent
+                 FROM deleted_link_events
+                 WHERE link_id = ?1
+                 ORDER BY timestamp DESC, id DESC
+                 LIMIT ?2",
+            )
+            .bind(&[link_id.into(), (limit as f64).into()])?
+            .all()
+            .await?;
+
+        results.results::<LinkEvent>()
+    }
+
+    /// Delete up to `limit` tombstones whose retention ended at or before
+    /// `now`, with their events. Returns the number of tombstones deleted.
+    pub async fn purge_expired(&self, db: &D1Database, now: i64, limit: i64) -> Result<usize> {
+        let results = db
+            .prepare("SELECT id FROM deleted_links WHERE retain_until <= ?1 LIMIT ?2")
+            .bind(&[(now as f64).into(), (limit as f64).into()])?
+            .all()
+            .await?;
+        let ids: Vec<String> = results
+            .results::<serde_json::Value>()?
+            .iter()
+            .filter_map(|row| row["id"].as_str().map(String::from))
+            .collect();
+
+        for id in &ids {
+            db.batch(vec![
+                db.prepare("DELETE FROM deleted_link_events WHERE link_id = ?1")
+                    .bind(&[id.as_str().into()])?,
+                db.prepare("DELETE FROM deleted_links WHERE id = ?1")
+                    .bind(&[id.as_str().into()])?,
+            ])
+            .await?;
+        }
+        Ok(ids.len())
+    }
+}
+
+impl Default for DeletedLinkRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
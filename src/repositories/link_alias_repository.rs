@@ -0,0 +1,155 @@
+use super::link_repository::D1_MAX_BOUND_PARAMS;
+use crate::models::LinkAlias;
+use wasm_bindgen::JsValue;
+use worker::Result;
+use worker::d1::D1Database;
+
+pub struct LinkAliasRepository;
+
+impl LinkAliasRepository {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Insert a new alias
+    pub async fn create(&self, db: &D1Database, alias: &LinkAlias) -> Result<()> {
+        db.prepare(
+            "INSERT INTO link_aliases (alias_code, link_id, org_id, created_by, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .bind(&[
+            alias.alias_code.as_str().into(),
+            alias.link_id.as_str().into(),
+            alias.org_id.as_str().into(),
+            alias.created_by.as_str().into(),
+            (alias.created_at as f64).into(),
+        ])?
+        .run()
+        .await?;
+        Ok(())
+    }
+
+    /// Get all aliases of a link, oldest first
+    pub async fn list_for_link(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        org_id: &str,
+    ) -> Result<Vec<LinkAlias>> {
+        let results = db
+            .prepare(
+                "SELECT alias_code, link_id, org_id, created_by, created_at
+                 FROM link_aliases
+                 WHERE link_id = ?1 AND org_id = ?2
+                 ORDER BY created_at ASC, alias_code ASC",
+            )
+            .bind(&[link_id.into(), org_id.into()])?
+            .all()
+            .await?;
+
+        results.results::<LinkAlias>()
+    }
+
+    /// Get the alias codes of every link in an org (for KV cleanup)
+    pub async fn list_codes_for_org(&self, db: &D1Database, org_id: &str) -> Result<Vec<String>> {
+        let results = db
+            .prepare("SELECT alias_code FROM link_aliases WHERE org_id = ?1")
+            .bind(&[org_id.into()])?
+            .all()
+            .await?;
+
+        Ok(results
+            .results::<serde_json::Value>()?
+            .into_iter()
+            .filter_map(|v| v["alias_code"].as_str().map(|s| s.to_string()))
+            .collect())
+    }
+
+    /// Get `(link_id, alias_code)` for the aliases of several of an org's
+    /// links (for KV cleanup)
+    pub async fn list_codes_for_links(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        link_ids: &[&str],
+    ) -> Result<Vec<(String, String)>> {
+        let mut codes = Vec::new();
+        for chunk in link_ids.chunks(D1_MAX_BOUND_PARAMS - 1) {
+            let placeholders: Vec<String> =
+                (2..=chunk.len() + 1).map(|i| format!("?{}", i)).collect();
+            let query = format!(
+                "SELECT link_id, alias_code FROM link_aliases WHERE org_id = ?1 AND link_id IN ({})",
+                placeholders.join(", ")
+            );
+            let mut params: Vec<JsValue> = vec![org_id.into()];
+            params.extend(chunk.iter().map(|id| JsValue::from(*id)));
+            let results = db.prepare(&query).bind(&params)?.all().await?;
+            codes.extend(
+                results
+                    .results::<serde_json::Value>()?
+                    .into_iter()
+                    .filter_map(|v| {
+                        Some((
+                            v["link_id"].as_str()?.to_string(),
+                            v["alias_code"].as_str()?.to_string(),
+                        ))
+                    }),
+            );
+        }
+        Ok(codes)
+    }
+
+    /// Count aliases of a link
+    pub async fn count_for_link(&self, db: &D1Database, link_id: &str) -> Result<i64> {
+        let result = db
+            .prepare("SELECT COUNT(*) as count FROM link_aliases WHERE link_id = ?1")
+            .bind(&[link_id.into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+
+        Ok(result.and_then(|v| v["count"].as_f64()).unwrap_or(0.0) as i64)
+    }
+
+    /// Whether a code is already used as a link's short code or an alias.
+    /// Complements the KV check, which misses disabled links.
+    pub async fn code_taken(&self, db: &D1Database, code: &str) -> Result<bool> {
+        let result = db
+            .prepare(
+                "SELECT 1 as found FROM links WHERE short_code = ?1
+                 UNION ALL
+                 SELECT 1 as found FROM link_aliases WHERE alias_code = ?1
+                 LIMIT 1",
+            )
+            .bind(&[code.into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+        Ok(result.is_some())
+    }
+
+    /// Delete one alias of a link. Returns whether a row was removed.
+    pub async fn delete(
+        &self,
+        db: &D1Database,
+        alias_code: &str,
+        link_id: &str,
+        org_id: &str,
+    ) -> Result<bool> {
+        let result = db
+            .prepare(
+                "DELETE FROM link_aliases WHERE alias_code = ?1 AND link_id = ?2 AND org_id = ?3",
+            )
+            .bind(&[alias_code.into(), link_id.into(), org_id.into()])?
+            .run()
+            .await?;
+        Ok(result
+            .meta()?
+            .and_then(|m| m.changes)
+            .is_some_and(|changes| changes > 0))
+    }
+}
+
+impl Default for LinkAliasRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -7,14 +7,28 @@
 /// - Analytics event logging and click-count increment
 /// - Export helpers
 /// - Dashboard statistics
-use crate::models::{AnalyticsEvent, Link, link::LinkStatus};
+use crate::models::link::{ANONYMOUS_ORG_ID, LinkStatus};
+use crate::models::{AnalyticsEvent, Link};
 use crate::repositories::OrgRepository;
-use crate::utils::now_timestamp;
+use crate::utils::{normalize_url_for_blacklist, now_timestamp};
 use serde::Serializer;
 use wasm_bindgen::JsValue;
-use worker::d1::D1Database;
+use worker::d1::{D1Database, D1PreparedStatement};
 use worker::*;
 
+/// D1 rejects a statement binding more than this many parameters
+pub(crate) const D1_MAX_BOUND_PARAMS: usize = 100;
+
+/// Pre-migration links (NULL `normalized_destination`) a single dedupe
+/// lookup normalizes and backfills
+const MAX_LEGACY_DEDUPE_CANDIDATES: i64 = 100;
+
+/// Destination as stored in `normalized_destination`, the form
+/// `get_active_link_by_destination` matches on
+fn normalized_destination(destination: &str) -> String {
+    normalize_url_for_blacklist(destination).unwrap_or_else(|_| destination.to_string())
+}
+
 /// Serialize Option<i64> as Option<bool> for JSON responses (0 = false, 1 = true, NULL = None)
 pub fn serialize_optional_int_as_bool<S>(
     value: &Option<i64>,
@@ -73,6 +87,28 @@ pub struct AdminLink {
     pub kv_exists: bool,
 }
 
+/// Link listed in the redirect domain's public sitemap
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct SitemapEntry {
+    pub short_code: String,
+    /// Last time the link changed (updated_at, or created_at if never updated)
+    pub last_modified: i64,
+}
+
+/// Disabled link considered by the scheduled purge job
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct DisabledLinkCandidate {
+    pub id: String,
+    pub org_id: String,
+    pub short_code: String,
+    /// Last time the link changed (updated_at, or created_at if never updated).
+    /// Disabling a link bumps updated_at, so this is when it was last disabled
+    /// or edited afterwards.
+    pub last_changed_at: i64,
+    /// Clicks recorded at or after the purge cutoff
+    pub recent_clicks: i64,
+}
+
 // ─── Repository ───────────────────────────────────────────────────────────────
 
 pub struct LinkRepository;
@@ -87,11 +123,21 @@ impl LinkRepository {
 
     /// Insert a new link into D1
     pub async fn create(&self, db: &D1Database, link: &Link) -> Result<()> {
+        self.create_statement(db, link)?.run().await?;
+        Ok(())
+    }
+
+    /// Statement inserting a new link, for batching several creations
+    pub fn create_statement(&self, db: &D1Database, link: &Link) -> Result<D1PreparedStatement> {
         let utm_json = link.utm_params.as_ref().and_then(|u| u.to_json_string());
+        let headers_json = link
+            .response_headers
+            .as_ref()
+            .and_then(|h| serde_json::to_string(h).ok());
 
         let stmt = db.prepare(
-            "INSERT INTO links (id, org_id, short_code, destination_url, title, created_by, created_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)"
+            "INSERT INTO links (id, org_id, short_code, destination_url, title, created_by, created_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain, response_headers, raw_destination, max_clicks, fallback_url, inserted_at, normalized_destination)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)"
         );
 
         stmt.bind(&[
@@ -131,13 +177,177 @@ impl LinkRepository {
                 .clone()
                 .map(|s| s.into())
                 .unwrap_or(JsValue::NULL),
-        ])?
-        .run()
-        .await?;
+            headers_json.map(|s| s.into()).unwrap_or(JsValue::NULL),
+            link.raw_destination
+                .clone()
+                .map(|s| s.into())
+                .unwrap_or(JsValue::NULL),
+            link.max_clicks
+                .map(|n| (n as f64).into())
+                .unwrap_or(JsValue::NULL),
+            link.fallback_url
+                .clone()
+                .map(|s| s.into())
+                .unwrap_or(JsValue::NULL),
+            // Imports may backdate created_at; quotas count by this instead
+            (now_timestamp() as f64).into(),
+            normalized_destination(&link.destination_url).into(),
+        ])
+    }
 
+    /// Store the hashed claim token of an anonymously created link
+    pub async fn set_claim_token_hash(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        claim_token_hash: &str,
+    ) -> Result<()> {
+        db.prepare("UPDATE links SET claim_token_hash = ?1 WHERE id = ?2")
+            .bind(&[claim_token_hash.into(), link_id.into()])?
+            .run()
+            .await?;
         Ok(())
     }
 
+    /// Get the link a claim token was issued for (active or disabled only),
+    /// whether or not it has been claimed yet
+    pub async fn get_by_claim_token_hash(
+        &self,
+        db: &D1Database,
+        claim_token_hash: &str,
+    ) -> Result<Option<Link>> {
+        let stmt = db.prepare(
+            "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, updated_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain, response_headers, raw_destination
+             FROM links
+             WHERE claim_token_hash = ?1
+             AND status IN ('active', 'disabled')"
+        );
+        stmt.bind(&[claim_token_hash.into()])?
+            .first::<Link>(None)
+            .await
+    }
+
+    /// Move an unclaimed anonymous link to `org_id`/`user_id`. Returns false
+    /// if the link was claimed in the meantime.
+    pub async fn claim_anonymous(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        org_id: &str,
+        user_id: &str,
+        now: i64,
+    ) -> Result<bool> {
+        let result = db
+            .prepare(
+                "UPDATE links SET org_id = ?1, created_by = ?2, updated_at = ?3
+                 WHERE id = ?4 AND org_id = ?5",
+            )
+            .bind(&[
+                org_id.into(),
+                user_id.into(),
+                (now as f64).into(),
+                link_id.into(),
+                ANONYMOUS_ORG_ID.into(),
+            ])?
+            .run()
+            .await?;
+        Ok(result
+            .meta()?
+            .and_then(|m| m.changes)
+            .is_some_and(|changes| changes > 0))
+    }
+
+    /// Newest active, unexpired link of an org whose destination equals
+    /// `destination` once both are normalized with `normalize_url_for_blacklist`
+    /// (case of scheme and host, www., default port, trailing slash, query
+    /// order). Matched exactly on the indexed `normalized_destination` column,
+    /// falling back to rows created before that column existed (see
+    /// `match_legacy_destination`).
+    pub async fn get_active_link_by_destination(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        destination: &str,
+    ) -> Result<Option<Link>> {
+        let stmt = db.prepare(
+            "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, updated_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain, response_headers, raw_destination, disabled_reason, max_clicks, fallback_url
+             FROM links
+             WHERE org_id = ?1
+             AND normalized_destination = ?2
+             AND status = 'active'
+             AND (expires_at IS NULL OR expires_at > ?3)
+             ORDER BY created_at DESC
+             LIMIT 1"
+        );
+        let normalized = normalized_destination(destination);
+        let link = stmt
+            .bind(&[
+                org_id.into(),
+                normalized.clone().into(),
+                (now_timestamp() as f64).into(),
+            ])?
+            .first::<Link>(None)
+            .await?;
+        if link.is_some() {
+            return Ok(link);
+        }
+        self.match_legacy_destination(db, org_id, &normalized).await
+    }
+
+    /// Dedupe fallback for links whose `normalized_destination` is still NULL
+    /// (created before migration 0080). Normalizes up to
+    /// `MAX_LEGACY_DEDUPE_CANDIDATES` of the org's newest such active links in
+    /// the Worker and stores the result, so each lookup shrinks the set the
+    /// next one has to scan.
+    async fn match_legacy_destination(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        normalized: &str,
+    ) -> Result<Option<Link>> {
+        let stmt = db.prepare(
+            "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, updated_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain, response_headers, raw_destination, disabled_reason, max_clicks, fallback_url
+             FROM links
+             WHERE org_id = ?1
+             AND normalized_destination IS NULL
+             AND status = 'active'
+             AND (expires_at IS NULL OR expires_at > ?2)
+             ORDER BY created_at DESC
+             LIMIT ?3"
+        );
+        let candidates = stmt
+            .bind(&[
+                org_id.into(),
+                (now_timestamp() as f64).into(),
+                (MAX_LEGACY_DEDUPE_CANDIDATES as f64).into(),
+            ])?
+            .all()
+            .await?
+            .results::<Link>()?;
+        if candidates.is_empty() {
+            return Ok(None);
+        }
+
+        let mut backfill = Vec::with_capacity(candidates.len());
+        for link in &candidates {
+            backfill.push(
+                db.prepare(
+                    "UPDATE links SET normalized_destination = ?1
+                     WHERE id = ?2 AND normalized_destination IS NULL",
+                )
+                .bind(&[
+                    normalized_destination(&link.destination_url).into(),
+                    link.id.as_str().into(),
+                ])?,
+            );
+        }
+        db.batch(backfill).await?;
+
+        Ok(candidates
+            .into_iter()
+            .find(|link| normalized_destination(&link.destination_url) == normalized))
+    }
+
     /// Get a link by ID scoped to an org (active or disabled only)
     pub async fn get_by_id(
         &self,
@@ -146,7 +356,7 @@ impl LinkRepository {
         org_id: &str,
     ) -> Result<Option<Link>> {
         let stmt = db.prepare(
-            "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, updated_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain
+            "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, updated_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain, response_headers, raw_destination, disabled_reason, max_clicks, fallback_url
              FROM links
              WHERE id = ?1
              AND org_id = ?2
@@ -160,7 +370,7 @@ impl LinkRepository {
     /// Get a link by ID without org check — active only (public redirects)
     pub async fn get_by_id_no_auth(&self, db: &D1Database, link_id: &str) -> Result<Option<Link>> {
         let stmt = db.prepare(
-            "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, updated_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain
+            "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, updated_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain, response_headers, max_clicks, fallback_url
              FROM links
              WHERE id = ?1
              AND status = 'active'"
@@ -175,7 +385,7 @@ impl LinkRepository {
         link_id: &str,
     ) -> Result<Option<Link>> {
         let stmt = db.prepare(
-            "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, updated_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain
+            "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, updated_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain, response_headers, disabled_reason, max_clicks, fallback_url
              FROM links
              WHERE id = ?1"
         );
@@ -190,7 +400,7 @@ impl LinkRepository {
         org_id: &str,
     ) -> Result<Option<Link>> {
         let stmt = db.prepare(
-            "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, updated_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain
+            "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, updated_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain, response_headers, max_clicks, fallback_url
              FROM links
              WHERE short_code = ?1
              AND org_id = ?2
@@ -201,6 +411,40 @@ impl LinkRepository {
             .await
     }
 
+    /// Get the org's links for several short codes in one query. Same status
+    /// filter as `get_by_short_code`; codes with no match are simply absent.
+    pub async fn get_by_short_codes(
+        &self,
+        db: &D1Database,
+        short_codes: &[String],
+        org_id: &str,
+    ) -> Result<Vec<Link>> {
+        let mut links = Vec::with_capacity(short_codes.len());
+        for chunk in short_codes.chunks(D1_MAX_BOUND_PARAMS - 1) {
+            let placeholders: Vec<String> =
+                (2..=chunk.len() + 1).map(|i| format!("?{}", i)).collect();
+            let query = format!(
+                "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, updated_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain, response_headers, max_clicks, fallback_url
+                 FROM links
+                 WHERE org_id = ?1
+                 AND short_code IN ({})
+                 AND status IN ('active', 'disabled')",
+                placeholders.join(", ")
+            );
+
+            let mut params: Vec<JsValue> = vec![org_id.into()];
+            params.extend(chunk.iter().map(|code| JsValue::from(code.as_str())));
+            links.extend(
+                db.prepare(&query)
+                    .bind(&params)?
+                    .all()
+                    .await?
+                    .results::<Link>()?,
+            );
+        }
+        Ok(links)
+    }
+
     /// Get an active link by short_code (public reporting)
     pub async fn get_active_by_short_code(
         &self,
@@ -208,7 +452,7 @@ impl LinkRepository {
         short_code: &str,
     ) -> Result<Option<Link>> {
         let stmt = db.prepare(
-            "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, updated_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain
+            "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, updated_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain, response_headers, max_clicks, fallback_url
              FROM links
              WHERE short_code = ?1
              AND status = 'active'"
@@ -216,7 +460,8 @@ impl LinkRepository {
         stmt.bind(&[short_code.into()])?.first::<Link>(None).await
     }
 
-    /// Get links for an org with search/filter/sort/tag-filter options
+    /// Get links for an org with search/filter/sort/tag-filter/created-at options.
+    /// The created-at window is half-open: `created_after <= created_at < created_before`.
     #[allow(clippy::too_many_arguments)]
     pub async fn list_filtered(
         &self,
@@ -228,9 +473,11 @@ impl LinkRepository {
         limit: i64,
         offset: i64,
         tags_filter: Option<&[String]>,
+        created_after: Option<i64>,
+        created_before: Option<i64>,
     ) -> Result<Vec<Link>> {
         let mut query = String::from(
-            "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, updated_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain
+            "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, updated_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain, response_headers, max_clicks, fallback_url
              FROM links
              WHERE org_id = ?1"
         );
@@ -279,6 +526,15 @@ impl LinkRepository {
             }
         }
 
+        if let Some(after) = created_after {
+            query.push_str(&format!(" AND created_at >= ?{}", params.len() + 1));
+            params.push((after as f64).into());
+        }
+        if let Some(before) = created_before {
+            query.push_str(&format!(" AND created_at < ?{}", params.len() + 1));
+            params.push((before as f64).into());
+        }
+
         let order_clause = match sort {
             "clicks" => " ORDER BY click_count DESC",
             "updated" => " ORDER BY updated_at DESC NULLS LAST",
@@ -301,7 +557,8 @@ impl LinkRepository {
         results.results::<Link>()
     }
 
-    /// Count links for an org with search/status/tag filters
+    /// Count links for an org with search/status/tag/created-at filters
+    #[allow(clippy::too_many_arguments)]
     pub async fn count_filtered(
         &self,
         db: &D1Database,
@@ -309,6 +566,8 @@ impl LinkRepository {
         search: Option<&str>,
         status_filter: Option<&str>,
         tags_filter: Option<&[String]>,
+        created_after: Option<i64>,
+        created_before: Option<i64>,
     ) -> Result<i64> {
         let mut query = String::from("SELECT COUNT(*) as count FROM links WHERE org_id = ?1");
 
@@ -356,13 +615,22 @@ impl LinkRepository {
             }
         }
 
+        if let Some(after) = created_after {
+            query.push_str(&format!(" AND created_at >= ?{}", params.len() + 1));
+            params.push((after as f64).into());
+        }
+        if let Some(before) = created_before {
+            query.push_str(&format!(" AND created_at < ?{}", params.len() + 1));
+            params.push((before as f64).into());
+        }
+
         let stmt = db.prepare(&query);
         let result = stmt.bind(&params)?.first::<serde_json::Value>(None).await?;
         Ok(result.and_then(|v| v["count"].as_f64()).unwrap_or(0.0) as i64)
     }
 
     /// Update a link. Only provided fields are changed.
-    /// For expires_at, utm_params, forward_query_params, device URLs:
+    /// For expires_at, utm_params, forward_query_params, device URLs, response_headers:
     ///   None = don't update, Some(None) = clear to NULL, Some(Some(val)) = set to value
     #[allow(clippy::too_many_arguments)]
     pub async fn update(
@@ -380,6 +648,8 @@ impl LinkRepository {
         ios_url: Option<Option<&str>>,
         android_url: Option<Option<&str>>,
         desktop_url: Option<Option<&str>>,
+        response_headers: Option<Option<&str>>,
+        fallback_url: Option<Option<&str>>,
     ) -> Result<Link> {
         let now = now_timestamp();
 
@@ -388,9 +658,14 @@ impl LinkRepository {
         let mut param_count = 2;
 
         if let Some(url) = destination_url {
-            query.push_str(&format!(", destination_url = ?{}", param_count));
+            query.push_str(&format!(
+                ", destination_url = ?{}, normalized_destination = ?{}",
+                param_count,
+                param_count + 1
+            ));
             params.push(url.into());
-            param_count += 1;
+            params.push(normalized_destination(url).into());
+            param_count += 2;
         }
 
         if let Some(t) = title {
@@ -399,8 +674,12 @@ impl LinkRepository {
             param_count += 1;
         }
 
+        // Any explicit status change clears a system-set disabled reason
         if let Some(s) = status {
-            query.push_str(&format!(", status = ?{}", param_count));
+            query.push_str(&format!(
+                ", status = ?{}, disabled_reason = NULL",
+                param_count
+            ));
             params.push(s.into());
             param_count += 1;
         }
@@ -455,6 +734,18 @@ impl LinkRepository {
             param_count += 1;
         }
 
+        if let Some(headers_val) = response_headers {
+            query.push_str(&format!(", response_headers = ?{}", param_count));
+            params.push(headers_val.map(|s| s.into()).unwrap_or(JsValue::NULL));
+            param_count += 1;
+        }
+
+        if let Some(fallback_val) = fallback_url {
+            query.push_str(&format!(", fallback_url = ?{}", param_count));
+            params.push(fallback_val.map(|s| s.into()).unwrap_or(JsValue::NULL));
+            param_count += 1;
+        }
+
         query.push_str(&format!(
             " WHERE id = ?{} AND org_id = ?{}",
             param_count,
@@ -471,44 +762,167 @@ impl LinkRepository {
             .ok_or_else(|| worker::Error::RustError("Link not found after update".to_string()))
     }
 
-    /// Update link status by ID (admin operations — no org scope)
+    /// Update link status by ID (admin operations — no org scope), replacing
+    /// the link's `disabled_reason`
     pub async fn update_status_by_id(
         &self,
         db: &D1Database,
         link_id: &str,
         status: &str,
+        disabled_reason: Option<&str>,
     ) -> Result<()> {
         let now = now_timestamp();
-        let stmt = db.prepare("UPDATE links SET status = ?1, updated_at = ?2 WHERE id = ?3");
-        stmt.bind(&[status.into(), (now as f64).into(), link_id.into()])?
-            .run()
-            .await?;
+        let stmt = db.prepare(
+            "UPDATE links SET status = ?1, disabled_reason = ?2, updated_at = ?3 WHERE id = ?4",
+        );
+        stmt.bind(&[
+            status.into(),
+            disabled_reason.map(|r| r.into()).unwrap_or(JsValue::NULL),
+            (now as f64).into(),
+            link_id.into(),
+        ])?
+        .run()
+        .await?;
         Ok(())
     }
 
     /// Hard-delete a link and all its related data
     pub async fn hard_delete(&self, db: &D1Database, link_id: &str, org_id: &str) -> Result<()> {
-        // Delete analytics events (FK constraint)
-        let analytics_stmt = db.prepare("DELETE FROM analytics_events WHERE link_id = ?1");
-        analytics_stmt.bind(&[link_id.into()])?.run().await?;
-
-        // Delete link reports
-        let reports_stmt = db.prepare("DELETE FROM link_reports WHERE link_id = ?1");
-        reports_stmt.bind(&[link_id.into()])?.run().await?;
+        for stmt in self.hard_delete_statements(db, link_id, org_id)? {
+            stmt.run().await?;
+        }
+        Ok(())
+    }
 
-        // Delete link tag associations
-        let tags_stmt = db.prepare("DELETE FROM link_tags WHERE link_id = ?1");
-        tags_stmt.bind(&[link_id.into()])?.run().await?;
+    /// Hard-delete several links of one org in a single batch, so they are
+    /// either all removed or none are. Each table is cleared with one
+    /// statement per chunk of ids, in the same order as `hard_delete`.
+    pub async fn hard_delete_many(
+        &self,
+        db: &D1Database,
+        link_ids: &[&str],
+        org_id: &str,
+    ) -> Result<()> {
+        let mut statements = Vec::new();
+        for chunk in link_ids.chunks(D1_MAX_BOUND_PARAMS - 1) {
+            let ids: Vec<JsValue> = chunk.iter().map(|id| JsValue::from(*id)).collect();
+            let placeholders = |first: usize| {
+                (first..first + chunk.len())
+                    .map(|i| format!("?{}", i))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            for table in [
+                "analytics_events",
+                "link_reports",
+                "link_tags",
+                "link_aliases",
+            ] {
+                statements.push(
+                    db.prepare(format!(
+                        "DELETE FROM {} WHERE link_id IN ({})",
+                        table,
+                        placeholders(1)
+                    ))
+                    .bind(&ids)?,
+                );
+            }
+            let mut params: Vec<JsValue> = vec![org_id.into()];
+            params.extend(ids);
+            statements.push(
+                db.prepare(format!(
+                    "DELETE FROM links WHERE org_id = ?1 AND id IN ({})",
+                    placeholders(2)
+                ))
+                .bind(&params)?,
+            );
+        }
+        if statements.is_empty() {
+            return Ok(());
+        }
+        db.batch(statements).await?;
+        Ok(())
+    }
 
-        // Delete the link itself
-        let stmt = db.prepare("DELETE FROM links WHERE id = ?1 AND org_id = ?2");
-        stmt.bind(&[link_id.into(), org_id.into()])?.run().await?;
+    /// Statements deleting a link and its related data, in FK-safe order
+    fn hard_delete_statements(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        org_id: &str,
+    ) -> Result<Vec<D1PreparedStatement>> {
+        Ok(vec![
+            // Delete analytics events (FK constraint)
+            db.prepare("DELETE FROM analytics_events WHERE link_id = ?1")
+                .bind(&[link_id.into()])?,
+            // Delete link reports
+            db.prepare("DELETE FROM link_reports WHERE link_id = ?1")
+                .bind(&[link_id.into()])?,
+            // Delete link tag associations
+            db.prepare("DELETE FROM link_tags WHERE link_id = ?1")
+                .bind(&[link_id.into()])?,
+            // Delete alias codes
+            db.prepare("DELETE FROM link_aliases WHERE link_id = ?1")
+                .bind(&[link_id.into()])?,
+            // Delete the link itself
+            db.prepare("DELETE FROM links WHERE id = ?1 AND org_id = ?2")
+                .bind(&[link_id.into(), org_id.into()])?,
+        ])
+    }
 
+    /// Hard-delete a link like `hard_delete`, first copying the link into a
+    /// `deleted_links` tombstone and its click events into
+    /// `deleted_link_events`, kept until `retain_until`. Runs as one batch,
+    /// so the analytics are never lost without the link being deleted.
+    pub async fn retain_and_delete(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        org_id: &str,
+        deleted_by: &str,
+        retain_until: i64,
+    ) -> Result<()> {
+        let now = now_timestamp();
+        let mut statements = vec![
+            db.prepare(
+                "INSERT INTO deleted_links (id, org_id, short_code, destination_url, title, created_by, created_at, click_count, status, deleted_by, deleted_at, retain_until)
+                 SELECT id, org_id, short_code, destination_url, title, created_by, created_at, COALESCE(click_count, 0), 'deleted', ?3, ?4, ?5
+                 FROM links WHERE id = ?1 AND org_id = ?2",
+            )
+            .bind(&[
+                link_id.into(),
+                org_id.into(),
+                deleted_by.into(),
+                (now as f64).into(),
+                (retain_until as f64).into(),
+            ])?,
+            db.prepare(
+                "INSERT INTO deleted_link_events (link_id, org_id, timestamp, referrer, user_agent, country, city, alias_code, sample_rate, extra)
+                 SELECT link_id, org_id, timestamp, referrer, user_agent, country, city, alias_code, sample_rate, extra
+                 FROM analytics_events WHERE link_id = ?1",
+            )
+            .bind(&[link_id.into()])?,
+        ];
+        statements.extend(self.hard_delete_statements(db, link_id, org_id)?);
+        db.batch(statements).await?;
         Ok(())
     }
 
     // ─── Analytics ────────────────────────────────────────────────────────────
 
+    /// Current click counter of a link (0 when the link is missing)
+    pub async fn get_click_count(&self, db: &D1Database, link_id: &str) -> Result<i64> {
+        let stmt = db.prepare("SELECT click_count FROM links WHERE id = ?1");
+        let result = stmt
+            .bind(&[link_id.into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+        Ok(result
+            .and_then(|r| r["click_count"].as_f64())
+            .map(|v| v as i64)
+            .unwrap_or(0))
+    }
+
     /// Increment the click counter for a link
     pub async fn increment_click_count(&self, db: &D1Database, link_id: &str) -> Result<()> {
         let stmt = db.prepare("UPDATE links SET click_count = click_count + 1 WHERE id = ?1");
@@ -516,11 +930,22 @@ impl LinkRepository {
         Ok(())
     }
 
+    /// Statement zeroing a link's click counter, for batching with event cleanup
+    pub fn reset_click_count_statement(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        org_id: &str,
+    ) -> Result<D1PreparedStatement> {
+        db.prepare("UPDATE links SET click_count = 0 WHERE id = ?1 AND org_id = ?2")
+            .bind(&[link_id.into(), org_id.into()])
+    }
+
     /// Log an analytics event
     pub async fn log_analytics_event(&self, db: &D1Database, event: &AnalyticsEvent) -> Result<()> {
         let stmt = db.prepare(
-            "INSERT INTO analytics_events (link_id, org_id, timestamp, referrer, user_agent, country, city)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
+            "INSERT INTO analytics_events (link_id, org_id, timestamp, referrer, user_agent, country, city, alias_code, sample_rate, extra)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"
         );
         stmt.bind(&[
             event.link_id.clone().into(),
@@ -546,6 +971,18 @@ impl LinkRepository {
                 .clone()
                 .map(|t| t.into())
                 .unwrap_or(JsValue::NULL),
+            event
+                .alias_code
+                .clone()
+                .map(|t| t.into())
+                .unwrap_or(JsValue::NULL),
+            (event.sample_rate as f64).into(),
+            event
+                .extra
+                .as_ref()
+                .and_then(|extra| serde_json::to_string(extra).ok())
+                .map(|t| t.into())
+                .unwrap_or(JsValue::NULL),
         ])?
         .run()
         .await?;
@@ -562,8 +999,8 @@ impl LinkRepository {
         year_month: &str,
     ) -> Result<()> {
         let insert_event = db.prepare(
-            "INSERT INTO analytics_events (link_id, org_id, timestamp, referrer, user_agent, country, city)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
+            "INSERT INTO analytics_events (link_id, org_id, timestamp, referrer, user_agent, country, city, alias_code, sample_rate, extra)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"
         );
         let insert_counter = db.prepare(
             "INSERT INTO link_monthly_clicks (link_id, org_id, year_month, clicks, updated_at)
@@ -597,6 +1034,18 @@ impl LinkRepository {
                 .clone()
                 .map(|t| t.into())
                 .unwrap_or(JsValue::NULL),
+            event
+                .alias_code
+                .clone()
+                .map(|t| t.into())
+                .unwrap_or(JsValue::NULL),
+            (event.sample_rate as f64).into(),
+            event
+                .extra
+                .as_ref()
+                .and_then(|extra| serde_json::to_string(extra).ok())
+                .map(|t| t.into())
+                .unwrap_or(JsValue::NULL),
         ])?;
 
         let insert_counter = insert_counter.bind(&[
@@ -610,6 +1059,34 @@ impl LinkRepository {
         Ok(())
     }
 
+    /// Increment the per-link monthly click counter without storing a raw
+    /// event, for clicks skipped by analytics sampling.
+    pub async fn increment_monthly_clicks(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        org_id: &str,
+        year_month: &str,
+        timestamp: i64,
+    ) -> Result<()> {
+        db.prepare(
+            "INSERT INTO link_monthly_clicks (link_id, org_id, year_month, clicks, updated_at)
+             VALUES (?1, ?2, ?3, 1, ?4)
+             ON CONFLICT(link_id, year_month) DO UPDATE SET
+               clicks = clicks + 1,
+               updated_at = excluded.updated_at",
+        )
+        .bind(&[
+            link_id.into(),
+            org_id.into(),
+            year_month.into(),
+            (timestamp as f64).into(),
+        ])?
+        .run()
+        .await?;
+        Ok(())
+    }
+
     // ─── Tags ─────────────────────────────────────────────────────────────────
 
     /// Get all tags for a single link, sorted alphabetically
@@ -659,6 +1136,106 @@ impl LinkRepository {
         Ok(map)
     }
 
+    /// Filter `link_ids` down to the active/disabled links that belong to the org.
+    /// Queries in chunks so any number of ids stays under D1's parameter limit.
+    pub async fn filter_ids_in_org(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        link_ids: &[String],
+    ) -> Result<Vec<String>> {
+        Ok(self
+            .short_codes_in_org(db, org_id, link_ids)
+            .await?
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect())
+    }
+
+    /// Like `filter_ids_in_org`, returning `(id, short_code)` pairs.
+    pub async fn short_codes_in_org(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        link_ids: &[String],
+    ) -> Result<Vec<(String, String)>> {
+        let mut owned = Vec::with_capacity(link_ids.len());
+        for chunk in link_ids.chunks(D1_MAX_BOUND_PARAMS - 1) {
+            let placeholders: Vec<String> =
+                (2..=chunk.len() + 1).map(|i| format!("?{}", i)).collect();
+            let query = format!(
+                "SELECT id, short_code FROM links
+                 WHERE org_id = ?1 AND status IN ('active', 'disabled') AND id IN ({})",
+                placeholders.join(", ")
+            );
+
+            let mut params: Vec<JsValue> = vec![org_id.into()];
+            params.extend(chunk.iter().map(|id| JsValue::from(id.as_str())));
+            let results = db.prepare(&query).bind(&params)?.all().await?;
+            owned.extend(
+                results
+                    .results::<serde_json::Value>()?
+                    .iter()
+                    .filter_map(|row| {
+                        Some((
+                            row["id"].as_str()?.to_string(),
+                            row["short_code"].as_str()?.to_string(),
+                        ))
+                    }),
+            );
+        }
+        Ok(owned)
+    }
+
+    /// Add and remove individual (link_id, tag_name) pairs in one batch.
+    /// Adds use INSERT OR IGNORE so re-adding an existing tag is a no-op.
+    pub async fn apply_tag_changes(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        adds: &[(String, String)],
+        removes: &[(String, String)],
+    ) -> Result<()> {
+        let mut statements = Vec::new();
+
+        let mut new_tag_names: Vec<&str> = adds.iter().map(|(_, tag)| tag.as_str()).collect();
+        new_tag_names.sort_unstable();
+        new_tag_names.dedup();
+        for tag in new_tag_names {
+            statements.push(
+                db.prepare(
+                    "INSERT OR IGNORE INTO tags (org_id, tag_name, created_at) VALUES (?1, ?2, strftime('%s', 'now'))",
+                )
+                .bind(&[org_id.into(), tag.into()])?,
+            );
+        }
+        for (link_id, tag) in adds {
+            statements.push(
+                db.prepare(
+                    "INSERT OR IGNORE INTO link_tags (link_id, tag_name, org_id, created_at) VALUES (?1, ?2, ?3, strftime('%s', 'now'))",
+                )
+                .bind(&[link_id.as_str().into(), tag.as_str().into(), org_id.into()])?,
+            );
+        }
+        for (link_id, tag) in removes {
+            statements.push(
+                db.prepare(
+                    "DELETE FROM link_tags WHERE link_id = ?1 AND tag_name = ?2 AND org_id = ?3",
+                )
+                .bind(&[
+                    link_id.as_str().into(),
+                    tag.as_str().into(),
+                    org_id.into(),
+                ])?,
+            );
+        }
+
+        if !statements.is_empty() {
+            db.batch(statements).await?;
+        }
+        Ok(())
+    }
+
     /// Replace all tags for a link atomically (delete existing, insert new)
     pub async fn set_tags(
         &self,
@@ -691,6 +1268,31 @@ impl LinkRepository {
         Ok(())
     }
 
+    /// Statements tagging a newly created link (no existing tags to clear),
+    /// for batching with `create_statement`
+    pub fn new_link_tag_statements(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        org_id: &str,
+        tags: &[String],
+    ) -> Result<Vec<D1PreparedStatement>> {
+        let mut statements = Vec::with_capacity(tags.len() * 2);
+        for tag in tags {
+            statements.push(
+                db.prepare(
+                    "INSERT OR IGNORE INTO tags (org_id, tag_name, created_at) VALUES (?1, ?2, strftime('%s', 'now'))",
+                )
+                .bind(&[org_id.into(), tag.as_str().into()])?,
+            );
+            statements.push(
+                db.prepare("INSERT INTO link_tags (link_id, tag_name, org_id) VALUES (?1, ?2, ?3)")
+                    .bind(&[link_id.into(), tag.as_str().into(), org_id.into()])?,
+            );
+        }
+        Ok(statements)
+    }
+
     /// Delete all tags for a link (called on link deletion)
     pub async fn delete_tags(&self, db: &D1Database, link_id: &str) -> Result<()> {
         let stmt = db.prepare("DELETE FROM link_tags WHERE link_id = ?1");
@@ -735,12 +1337,94 @@ impl LinkRepository {
         }
     }
 
+    /// Count links an org created at or after `since`, in any status, by the
+    /// time the row was inserted (imports can backdate `created_at`).
+    /// Deleted links are gone from the table and no longer count.
+    pub async fn count_created_since(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        since: i64,
+    ) -> Result<i64> {
+        let result = db
+            .prepare(
+                "SELECT COUNT(*) as count FROM links
+                 WHERE org_id = ?1 AND COALESCE(inserted_at, created_at) >= ?2",
+            )
+            .bind(&[org_id.into(), (since as f64).into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+
+        Ok(result.and_then(|v| v["count"].as_f64()).unwrap_or(0.0) as i64)
+    }
+
+    // ─── Destination prefix matching (rewrite rules) ──────────────────────────
+
+    /// Count active links in an org whose destination starts with `prefix`.
+    /// Uses substr() rather than LIKE so the match is exact and case-sensitive.
+    pub async fn count_active_with_destination_prefix(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        prefix: &str,
+    ) -> Result<i64> {
+        let result = db
+            .prepare(
+                "SELECT COUNT(*) as count FROM links
+                 WHERE org_id = ?1
+                 AND status = 'active'
+                 AND substr(destination_url, 1, length(?2)) = ?2",
+            )
+            .bind(&[org_id.into(), prefix.into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+
+        Ok(result.and_then(|v| v["count"].as_f64()).unwrap_or(0.0) as i64)
+    }
+
+    /// Get up to `limit` active links in an org whose destination starts with
+    /// `prefix`, oldest first.
+    pub async fn list_active_with_destination_prefix(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        prefix: &str,
+        limit: i64,
+    ) -> Result<Vec<Link>> {
+        let results = db
+            .prepare(
+                "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, updated_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain, response_headers, max_clicks, fallback_url
+                 FROM links
+                 WHERE org_id = ?1
+                 AND status = 'active'
+                 AND substr(destination_url, 1, length(?2)) = ?2
+                 ORDER BY created_at ASC, id ASC
+                 LIMIT ?3",
+            )
+            .bind(&[org_id.into(), prefix.into(), (limit as f64).into()])?
+            .all()
+            .await?;
+        results.results::<Link>()
+    }
+
     // ─── Export ───────────────────────────────────────────────────────────────
 
+    /// Get all active links for an org (for bulk KV re-syncs)
+    pub async fn get_active_for_org(&self, db: &D1Database, org_id: &str) -> Result<Vec<Link>> {
+        let stmt = db.prepare(
+            "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, updated_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain, response_headers, max_clicks, fallback_url
+             FROM links
+             WHERE org_id = ?1
+             AND status = 'active'",
+        );
+        let results = stmt.bind(&[org_id.into()])?.all().await?;
+        results.results::<Link>()
+    }
+
     /// Get all active/disabled links for an org (for CSV/JSON export)
     pub async fn get_all_for_export(&self, db: &D1Database, org_id: &str) -> Result<Vec<Link>> {
         let stmt = db.prepare(
-            "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, updated_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain
+            "SELECT id, org_id, short_code, destination_url, title, created_by, created_at, updated_at, expires_at, status, click_count, utm_params, forward_query_params, redirect_type, ios_url, android_url, desktop_url, custom_domain, response_headers, max_clicks, fallback_url
              FROM links
              WHERE org_id = ?1
              AND status IN ('active', 'disabled')
@@ -750,6 +1434,120 @@ impl LinkRepository {
         results.results::<Link>()
     }
 
+    /// Disabled links (across all orgs) unchanged since `cutoff`, with their
+    /// click count since `cutoff`. With `require_zero_clicks`, links clicked
+    /// since `cutoff` are left out. Oldest first, capped at `limit`.
+    pub async fn list_disabled_purge_candidates(
+        &self,
+        db: &D1Database,
+        cutoff: i64,
+        require_zero_clicks: bool,
+        limit: i64,
+    ) -> Result<Vec<DisabledLinkCandidate>> {
+        // Filter clicked links before LIMIT so they can't crowd out eligible ones
+        let clicks_clause = if require_zero_clicks {
+            "AND NOT EXISTS (SELECT 1 FROM analytics_events ae
+                             WHERE ae.link_id = l.id AND ae.timestamp >= ?1)"
+        } else {
+            ""
+        };
+        let query = format!(
+            "SELECT l.id, l.org_id, l.short_code,
+                    COALESCE(l.updated_at, l.created_at) as last_changed_at,
+                    (SELECT COUNT(*) FROM analytics_events ae
+                     WHERE ae.link_id = l.id AND ae.timestamp >= ?1) as recent_clicks
+             FROM links l
+             WHERE l.status = 'disabled'
+             AND COALESCE(l.updated_at, l.created_at) <= ?1
+             {}
+             ORDER BY last_changed_at ASC
+             LIMIT ?2",
+            clicks_clause
+        );
+        let results = db
+            .prepare(&query)
+            .bind(&[(cutoff as f64).into(), (limit as f64).into()])?
+            .all()
+            .await?;
+        results.results::<DisabledLinkCandidate>()
+    }
+
+    /// Filter shared by the sitemap queries. Starts from the (few) opted-in
+    /// orgs so links are read through idx_links_org_status rather than a
+    /// scan of the whole table. `?1` = now, `?2` = custom domain if any.
+    fn sitemap_filter(custom_domain: Option<&str>) -> &'static str {
+        if custom_domain.is_some() {
+            "FROM organizations o
+             JOIN links l ON l.org_id = o.id AND l.status = 'active'
+             WHERE o.public_sitemap = 1
+             AND (l.expires_at IS NULL OR l.expires_at > ?1)
+             AND l.custom_domain = ?2"
+        } else {
+            "FROM organizations o
+             JOIN links l ON l.org_id = o.id AND l.status = 'active'
+             WHERE o.public_sitemap = 1
+             AND (l.expires_at IS NULL OR l.expires_at > ?1)
+             AND l.custom_domain IS NULL"
+        }
+    }
+
+    /// Number of links listed in the public sitemap on `custom_domain`
+    /// (None = the default short domain)
+    pub async fn count_sitemap_entries(
+        &self,
+        db: &D1Database,
+        custom_domain: Option<&str>,
+        now: i64,
+    ) -> Result<i64> {
+        let query = format!(
+            "SELECT COUNT(*) as count {}",
+            Self::sitemap_filter(custom_domain)
+        );
+        let mut params: Vec<JsValue> = vec![(now as f64).into()];
+        if let Some(domain) = custom_domain {
+            params.push(domain.into());
+        }
+        let result = db
+            .prepare(&query)
+            .bind(&params)?
+            .first::<serde_json::Value>(None)
+            .await?;
+        Ok(result
+            .and_then(|v| v["count"].as_f64())
+            .map(|v| v as i64)
+            .unwrap_or(0))
+    }
+
+    /// One page of active, unexpired links of orgs that opted into the public
+    /// sitemap, on `custom_domain` (None = the default short domain). Ordered
+    /// by org and creation time so pages stay stable between crawls.
+    pub async fn list_sitemap_entries(
+        &self,
+        db: &D1Database,
+        custom_domain: Option<&str>,
+        now: i64,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<SitemapEntry>> {
+        let query = format!(
+            "SELECT l.short_code, COALESCE(l.updated_at, l.created_at) as last_modified
+             {}
+             ORDER BY l.org_id, l.created_at, l.id
+             LIMIT ?{} OFFSET ?{}",
+            Self::sitemap_filter(custom_domain),
+            if custom_domain.is_some() { 3 } else { 2 },
+            if custom_domain.is_some() { 4 } else { 3 },
+        );
+        let mut params: Vec<JsValue> = vec![(now as f64).into()];
+        if let Some(domain) = custom_domain {
+            params.push(domain.into());
+        }
+        params.push((limit as f64).into());
+        params.push((offset as f64).into());
+        let results = db.prepare(&query).bind(&params)?.all().await?;
+        results.results::<SitemapEntry>()
+    }
+
     // ─── Admin ────────────────────────────────────────────────────────────────
 
     /// Get paginated admin link listing (base data, no KV status)
@@ -871,36 +1669,152 @@ impl LinkRepository {
         Ok(result.and_then(|v| v["count"].as_f64()).unwrap_or(0.0) as i64)
     }
 
+    /// Links created by one user across all orgs, newest first, optionally
+    /// restricted to one status
+    pub async fn list_admin_by_creator(
+        &self,
+        db: &D1Database,
+        user_id: &str,
+        status_filter: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AdminLinkBase>> {
+        let status_clause = if status_filter.is_some() {
+            "l.status = ?4"
+        } else {
+            "l.status IN ('active', 'disabled', 'blocked')"
+        };
+        let query = format!(
+            "SELECT l.id, l.org_id, l.short_code, l.destination_url, l.title, l.created_by, l.created_at, l.updated_at, l.expires_at, l.status, l.click_count, l.utm_params, l.forward_query_params, l.redirect_type, l.ios_url, l.android_url, l.desktop_url, u.email as creator_email, o.name as org_name
+             FROM links l
+             JOIN users u ON l.created_by = u.id
+             JOIN organizations o ON l.org_id = o.id
+             WHERE l.created_by = ?1 AND {}
+             ORDER BY l.created_at DESC
+             LIMIT ?2 OFFSET ?3",
+            status_clause
+        );
+        let mut params: Vec<JsValue> = vec![
+            user_id.into(),
+            (limit as f64).into(),
+            (offset as f64).into(),
+        ];
+        if let Some(status) = status_filter {
+            params.push(status.into());
+        }
+        let results = db.prepare(&query).bind(&params)?.all().await?;
+        results.results::<AdminLinkBase>()
+    }
+
+    /// Count links created by one user across all orgs
+    pub async fn count_admin_by_creator(
+        &self,
+        db: &D1Database,
+        user_id: &str,
+        status_filter: Option<&str>,
+    ) -> Result<i64> {
+        let status_clause = if status_filter.is_some() {
+            "status = ?2"
+        } else {
+            "status IN ('active', 'disabled', 'blocked')"
+        };
+        let query = format!(
+            "SELECT COUNT(*) as count FROM links WHERE created_by = ?1 AND {}",
+            status_clause
+        );
+        let mut params: Vec<JsValue> = vec![user_id.into()];
+        if let Some(status) = status_filter {
+            params.push(status.into());
+        }
+        let result = db
+            .prepare(&query)
+            .bind(&params)?
+            .first::<serde_json::Value>(None)
+            .await?;
+        Ok(result.and_then(|v| v["count"].as_f64()).unwrap_or(0.0) as i64)
+    }
+
+    /// Admin links for a KV consistency audit: the newest `limit` links of
+    /// `org_filter`, or a random sample across all orgs when None.
+    pub async fn list_admin_kv_audit(
+        &self,
+        db: &D1Database,
+        org_filter: Option<&str>,
+        active_only: bool,
+        limit: i64,
+    ) -> Result<Vec<AdminLinkBase>> {
+        let status_clause = if active_only {
+            "l.status = 'active'"
+        } else {
+            "l.status IN ('active', 'disabled', 'blocked')"
+        };
+        let (org_clause, order_by) = if org_filter.is_some() {
+            ("AND l.org_id = ?2", "l.created_at DESC")
+        } else {
+            ("", "RANDOM()")
+        };
+        let query = format!(
+            "SELECT l.id, l.org_id, l.short_code, l.destination_url, l.title, l.created_by, l.created_at, l.updated_at, l.expires_at, l.status, l.click_count, l.utm_params, l.forward_query_params, l.redirect_type, l.ios_url, l.android_url, l.desktop_url, u.email as creator_email, o.name as org_name
+             FROM links l
+             JOIN users u ON l.created_by = u.id
+             JOIN organizations o ON l.org_id = o.id
+             WHERE {} {}
+             ORDER BY {}
+             LIMIT ?1",
+            status_clause, org_clause, order_by
+        );
+
+        let mut params: Vec<JsValue> = vec![(limit as f64).into()];
+        if let Some(org_id) = org_filter {
+            params.push(org_id.into());
+        }
+        let results = db.prepare(&query).bind(&params)?.all().await?;
+        results.results::<AdminLinkBase>()
+    }
+
+    /// Which of `codes` D1 knows, as (short code, custom domain) pairs: link
+    /// short codes with their link's domain (any status), and alias codes
+    /// with no domain.
+    pub async fn find_known_codes(
+        &self,
+        db: &D1Database,
+        codes: &[String],
+    ) -> Result<Vec<(String, Option<String>)>> {
+        let mut known = Vec::new();
+        for chunk in codes.chunks(D1_MAX_BOUND_PARAMS) {
+            let placeholders: Vec<String> = (1..=chunk.len()).map(|i| format!("?{}", i)).collect();
+            let placeholders = placeholders.join(", ");
+            let query = format!(
+                "SELECT short_code as code, custom_domain FROM links WHERE short_code IN ({0})
+                 UNION ALL
+                 SELECT alias_code as code, NULL as custom_domain FROM link_aliases WHERE alias_code IN ({0})",
+                placeholders
+            );
+            let params: Vec<JsValue> = chunk.iter().map(|c| JsValue::from(c.as_str())).collect();
+            let results = db.prepare(&query).bind(&params)?.all().await?;
+            known.extend(
+                results
+                    .results::<serde_json::Value>()?
+                    .iter()
+                    .filter_map(|row| {
+                        Some((
+                            row["code"].as_str()?.to_string(),
+                            row["custom_domain"].as_str().map(|d| d.to_string()),
+                        ))
+                    }),
+            );
+        }
+        Ok(known)
+    }
+
     /// Check KV sync status for a link
     pub async fn check_kv_sync(
         &self,
         kv: &worker::kv::KvStore,
         link: &AdminLinkBase,
     ) -> Result<(String, bool)> {
-        use crate::kv;
-
-        let kv_mapping = kv::get_link_mapping(kv, &link.short_code).await?;
-
-        match kv_mapping {
-            Some(mapping) => {
-                let should_exist = link.status == "active";
-                let is_active = mapping.status == LinkStatus::Active;
-
-                if should_exist && is_active || !should_exist && !is_active {
-                    Ok(("synced".to_string(), true))
-                } else {
-                    Ok(("mismatched".to_string(), true))
-                }
-            }
-            None => {
-                let should_exist = link.status == "active";
-                if should_exist {
-                    Ok(("missing".to_string(), false))
-                } else {
-                    Ok(("synced".to_string(), false))
-                }
-            }
-        }
+        let kv_mapping = crate::kv::get_link_mapping(kv, &link.short_code).await?;
+        Ok(kv_sync_status(link, kv_mapping.as_ref()))
     }
 
     /// Resolve all pending reports for a specific link
@@ -957,7 +1871,8 @@ impl LinkRepository {
         use crate::kv;
         match link.status {
             LinkStatus::Blocked => {
-                kv::delete_link_mapping(kv_store, &link.org_id, &link.short_code).await
+                kv::store_blocked_tombstone(kv_store, &link.org_id, &link.short_code, &link.id)
+                    .await
             }
             LinkStatus::Active | LinkStatus::Disabled => {
                 let resolved_forward = self.resolved_forward_for_link(db, link).await;
@@ -974,6 +1889,34 @@ impl Default for LinkRepository {
     }
 }
 
+/// Compare a link's D1 row with its KV mapping: ("synced" | "missing" |
+/// "mismatched", whether a mapping exists). An active link is in sync when KV
+/// holds an active mapping for the same link and destination; any other link
+/// is in sync when KV holds no active mapping for it.
+pub fn kv_sync_status(
+    link: &AdminLinkBase,
+    mapping: Option<&crate::models::LinkMapping>,
+) -> (String, bool) {
+    let should_exist = link.status == "active";
+    match mapping {
+        Some(mapping) => {
+            let serves_link = mapping.status == LinkStatus::Active && mapping.link_id == link.id;
+            let in_sync = if should_exist {
+                serves_link && mapping.destination_url == link.destination_url
+            } else {
+                !serves_link
+            };
+            if in_sync {
+                ("synced".to_string(), true)
+            } else {
+                ("mismatched".to_string(), true)
+            }
+        }
+        None if should_exist => ("missing".to_string(), false),
+        None => ("synced".to_string(), false),
+    }
+}
+
 #[cfg(test)]
 mod admin_link_serialization_tests {
     use super::*;
@@ -1095,3 +2038,74 @@ mod admin_link_serialization_tests {
         assert!(parsed["forward_query_params"].is_null());
     }
 }
+
+#[cfg(test)]
+mod kv_sync_status_tests {
+    use super::*;
+    use crate::models::LinkMapping;
+
+    fn admin_link(status: &str) -> AdminLinkBase {
+        serde_json::from_value(serde_json::json!({
+            "id": "link-1",
+            "org_id": "org-1",
+            "short_code": "abc123",
+            "destination_url": "https://example.com",
+            "title": null,
+            "created_by": "user-1",
+            "created_at": 1234567890,
+            "updated_at": null,
+            "expires_at": null,
+            "status": status,
+            "click_count": 0,
+            "utm_params": null,
+            "forward_query_params": null,
+            "redirect_type": "301",
+            "creator_email": "test@example.com",
+            "org_name": "Test Org"
+        }))
+        .unwrap()
+    }
+
+    fn mapping(link_id: &str, destination_url: &str) -> LinkMapping {
+        serde_json::from_value(serde_json::json!({
+            "destination_url": destination_url,
+            "link_id": link_id,
+            "expires_at": null,
+            "status": "active"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_active_link_needs_matching_mapping() {
+        let link = admin_link("active");
+        assert_eq!(
+            kv_sync_status(&link, Some(&mapping("link-1", "https://example.com"))),
+            ("synced".to_string(), true)
+        );
+        assert_eq!(kv_sync_status(&link, None), ("missing".to_string(), false));
+        assert_eq!(
+            kv_sync_status(&link, Some(&mapping("link-2", "https://example.com"))),
+            ("mismatched".to_string(), true)
+        );
+        assert_eq!(
+            kv_sync_status(&link, Some(&mapping("link-1", "https://other.com"))),
+            ("mismatched".to_string(), true)
+        );
+    }
+
+    #[test]
+    fn test_inactive_link_must_not_be_served() {
+        let link = admin_link("disabled");
+        assert_eq!(kv_sync_status(&link, None), ("synced".to_string(), false));
+        assert_eq!(
+            kv_sync_status(&link, Some(&mapping("link-1", "https://example.com"))),
+            ("mismatched".to_string(), true)
+        );
+        // The code was reused by another link after this one was removed from KV
+        assert_eq!(
+            kv_sync_status(&link, Some(&mapping("link-2", "https://example.com"))),
+            ("synced".to_string(), true)
+        );
+    }
+}
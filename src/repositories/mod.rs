@@ -18,13 +18,17 @@ pub mod analytics_repository;
 pub mod api_key_repository;
 pub mod billing_repository;
 pub mod blacklist_repository;
+pub mod code_word_repository;
 pub mod custom_domain_repository;
+pub mod deleted_link_repository;
+pub mod link_alias_repository;
 pub mod link_repository;
 pub mod notification_preferences_repository;
 pub mod org_repository;
 pub mod pending_actions_repository;
 pub mod product_repository;
 pub mod report_repository;
+pub mod rewrite_rule_repository;
 pub mod settings_repository;
 pub mod tag_repository;
 pub mod user_repository;
@@ -32,12 +36,16 @@ pub use analytics_repository::AnalyticsRepository;
 pub use api_key_repository::ApiKeyRepository;
 pub use billing_repository::BillingRepository;
 pub use blacklist_repository::BlacklistRepository;
+pub use code_word_repository::CodeWordRepository;
 pub use custom_domain_repository::CustomDomainRepository;
+pub use deleted_link_repository::DeletedLinkRepository;
+pub use link_alias_repository::LinkAliasRepository;
 pub use link_repository::LinkRepository;
 pub use org_repository::OrgRepository;
 pub use pending_actions_repository::PendingActionsRepository;
 pub use product_repository::ProductRepository;
 pub use report_repository::ReportRepository;
+pub use rewrite_rule_repository::RewriteRuleRepository;
 pub use settings_repository::SettingsRepository;
 pub use tag_repository::TagRepository;
 pub use user_repository::UserRepository;
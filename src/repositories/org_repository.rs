@@ -2,11 +2,15 @@
 ///
 /// Data access layer for organization records, memberships, invitations,
 /// and org-level settings in D1.
+use crate::models::org_redirect_config::OrgRedirectConfig;
+use crate::models::organization::InvitationBranding;
 use crate::models::{
-    OrgInvitation, OrgMember, OrgMemberWithUser, OrgWithRole, Organization, link::Link,
+    InvitationStatus, OrgActivityStats, OrgInvitation, OrgMember, OrgMemberWithUser, OrgWithRole,
+    Organization, link::Link,
 };
 use crate::repositories::BillingRepository;
 use crate::utils::now_timestamp;
+use std::collections::HashMap;
 use worker::Result;
 use worker::d1::D1Database;
 
@@ -118,6 +122,33 @@ impl OrgRepository {
         })
     }
 
+    /// Names of the orgs in a billing account. `exclude_org_id` skips the org
+    /// being renamed.
+    pub async fn list_names_in_billing_account(
+        &self,
+        db: &D1Database,
+        billing_account_id: &str,
+        exclude_org_id: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let stmt = db.prepare(
+            "SELECT name FROM organizations
+             WHERE billing_account_id = ?1
+             AND id != ?2",
+        );
+        let results = stmt
+            .bind(&[
+                billing_account_id.into(),
+                exclude_org_id.unwrap_or("").into(),
+            ])?
+            .all()
+            .await?;
+        Ok(results
+            .results::<serde_json::Value>()?
+            .iter()
+            .filter_map(|row| row["name"].as_str().map(str::to_string))
+            .collect())
+    }
+
     /// Update an org's display name
     pub async fn update_name(&self, db: &D1Database, org_id: &str, name: &str) -> Result<()> {
         let stmt = db.prepare("UPDATE organizations SET name = ?1 WHERE id = ?2");
@@ -160,6 +191,27 @@ impl OrgRepository {
 
     // ─── Org Membership ─────────────────────────────────────────────────────────────
 
+    /// The user's primary org: the first org they joined as owner, i.e. the
+    /// first owned entry of `get_user_orgs`. Usually the org created at signup.
+    pub async fn get_primary_org_id(
+        &self,
+        db: &D1Database,
+        user_id: &str,
+    ) -> Result<Option<String>> {
+        let result = db
+            .prepare(
+                "SELECT m.org_id AS id
+                 FROM org_members m
+                 WHERE m.user_id = ?1 AND m.role = 'owner'
+                 ORDER BY m.joined_at ASC
+                 LIMIT 1",
+            )
+            .bind(&[user_id.into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+        Ok(result.and_then(|v| v["id"].as_str().map(|s| s.to_string())))
+    }
+
     /// Get all organizations a user belongs to (via org_members junction table)
     pub async fn get_user_orgs(&self, db: &D1Database, user_id: &str) -> Result<Vec<OrgWithRole>> {
         let stmt = db.prepare(
@@ -187,6 +239,55 @@ impl OrgRepository {
         Ok(orgs)
     }
 
+    /// Get link count and current-month clicks for every org a user belongs to,
+    /// keyed by org ID.
+    pub async fn get_user_org_stats(
+        &self,
+        db: &D1Database,
+        user_id: &str,
+        year_month: &str,
+    ) -> Result<HashMap<String, OrgActivityStats>> {
+        let stmt = db.prepare(
+            "SELECT m.org_id,
+                    COALESCE(l.link_count, 0) as link_count,
+                    COALESCE(c.clicks, 0) as clicks_this_month
+             FROM org_members m
+             LEFT JOIN (
+                 SELECT org_id, COUNT(*) as link_count
+                 FROM links
+                 WHERE status IN ('active', 'disabled')
+                 AND org_id IN (SELECT org_id FROM org_members WHERE user_id = ?1)
+                 GROUP BY org_id
+             ) l ON l.org_id = m.org_id
+             LEFT JOIN (
+                 SELECT org_id, SUM(clicks) as clicks
+                 FROM link_monthly_clicks
+                 WHERE year_month = ?2
+                 AND org_id IN (SELECT org_id FROM org_members WHERE user_id = ?1)
+                 GROUP BY org_id
+             ) c ON c.org_id = m.org_id
+             WHERE m.user_id = ?1",
+        );
+        let results = stmt
+            .bind(&[user_id.into(), year_month.into()])?
+            .all()
+            .await?;
+        let rows = results.results::<serde_json::Value>()?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                Some((
+                    row["org_id"].as_str()?.to_string(),
+                    OrgActivityStats {
+                        link_count: row["link_count"].as_f64().unwrap_or(0.0) as i64,
+                        clicks_this_month: row["clicks_this_month"].as_f64().unwrap_or(0.0) as i64,
+                    },
+                ))
+            })
+            .collect())
+    }
+
     /// Get the membership record for a specific user in a specific org
     /// Falls back to users.org_id ownership check and auto-inserts the row to self-heal
     pub async fn get_member(
@@ -397,6 +498,7 @@ impl OrgRepository {
             created_at: now,
             expires_at,
             accepted_at: None,
+            last_resent_at: None,
         })
     }
 
@@ -407,7 +509,7 @@ impl OrgRepository {
         token: &str,
     ) -> Result<Option<OrgInvitation>> {
         let stmt = db.prepare(
-            "SELECT id, org_id, invited_by, email, role, created_at, expires_at, accepted_at
+            "SELECT id, org_id, invited_by, email, role, created_at, expires_at, accepted_at, last_resent_at
              FROM org_invitations
              WHERE id = ?1",
         );
@@ -416,26 +518,56 @@ impl OrgRepository {
             .await
     }
 
-    /// List pending (not yet accepted, not expired) invitations for an org
-    pub async fn list_pending_invitations(
+    /// List an org's invitations in `status`, newest first
+    pub async fn list_invitations(
         &self,
         db: &D1Database,
         org_id: &str,
+        status: InvitationStatus,
+        limit: i64,
+        offset: i64,
     ) -> Result<Vec<OrgInvitation>> {
-        let now = now_timestamp();
-        let stmt = db.prepare(
-            "SELECT id, org_id, invited_by, email, role, created_at, expires_at, accepted_at
+        let (condition, uses_now) = invitation_status_condition(status, "?4");
+        let query = format!(
+            "SELECT id, org_id, invited_by, email, role, created_at, expires_at, accepted_at, last_resent_at
              FROM org_invitations
-             WHERE org_id = ?1 AND accepted_at IS NULL AND expires_at > ?2
-             ORDER BY created_at DESC",
+             WHERE org_id = ?1 AND {}
+             ORDER BY created_at DESC
+             LIMIT ?2 OFFSET ?3",
+            condition
         );
-        let results = stmt
-            .bind(&[org_id.into(), (now as f64).into()])?
-            .all()
-            .await?;
+        let mut params = vec![org_id.into(), (limit as f64).into(), (offset as f64).into()];
+        if uses_now {
+            params.push((now_timestamp() as f64).into());
+        }
+        let results = db.prepare(&query).bind(&params)?.all().await?;
         results.results::<OrgInvitation>()
     }
 
+    /// Count an org's invitations in `status`
+    pub async fn count_invitations(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        status: InvitationStatus,
+    ) -> Result<i64> {
+        let (condition, uses_now) = invitation_status_condition(status, "?2");
+        let query = format!(
+            "SELECT COUNT(*) as count FROM org_invitations WHERE org_id = ?1 AND {}",
+            condition
+        );
+        let mut params = vec![org_id.into()];
+        if uses_now {
+            params.push((now_timestamp() as f64).into());
+        }
+        let result = db
+            .prepare(&query)
+            .bind(&params)?
+            .first::<serde_json::Value>(None)
+            .await?;
+        Ok(result.and_then(|v| v["count"].as_f64()).unwrap_or(0.0) as i64)
+    }
+
     /// Mark an invitation as accepted
     pub async fn accept_invitation(&self, db: &D1Database, token: &str) -> Result<()> {
         let now = now_timestamp();
@@ -446,6 +578,34 @@ impl OrgRepository {
         Ok(())
     }
 
+    /// Record a resend at `now` unless the invitation was already resent after
+    /// `cutoff`. Returns whether the resend was recorded;
+    /// the conditional update keeps concurrent resends from both going out.
+    pub async fn claim_invitation_resend(
+        &self,
+        db: &D1Database,
+        invitation_id: &str,
+        now: i64,
+        cutoff: i64,
+    ) -> Result<bool> {
+        let stmt = db.prepare(
+            "UPDATE org_invitations SET last_resent_at = ?1
+             WHERE id = ?2 AND (last_resent_at IS NULL OR last_resent_at <= ?3)",
+        );
+        let changes = stmt
+            .bind(&[
+                (now as f64).into(),
+                invitation_id.into(),
+                (cutoff as f64).into(),
+            ])?
+            .run()
+            .await?
+            .meta()?
+            .and_then(|m| m.changes)
+            .unwrap_or(0);
+        Ok(changes > 0)
+    }
+
     /// Delete an invitation (revoke)
     pub async fn revoke_invitation(&self, db: &D1Database, invitation_id: &str) -> Result<()> {
         let stmt = db.prepare("DELETE FROM org_invitations WHERE id = ?1");
@@ -453,18 +613,10 @@ impl OrgRepository {
         Ok(())
     }
 
-    /// Count pending (not yet accepted) invitations for an org
+    /// Count pending (not yet accepted, not expired) invitations for an org
     pub async fn count_pending_invitations(&self, db: &D1Database, org_id: &str) -> Result<i64> {
-        let stmt = db.prepare(
-            "SELECT COUNT(*) as count FROM org_invitations
-             WHERE org_id = ?1 AND accepted_at IS NULL AND expires_at > ?2",
-        );
-        let now = now_timestamp();
-        let result = stmt
-            .bind(&[org_id.into(), (now as f64).into()])?
-            .first::<serde_json::Value>(None)
-            .await?;
-        Ok(result.and_then(|v| v["count"].as_f64()).unwrap_or(0.0) as i64)
+        self.count_invitations(db, org_id, InvitationStatus::Pending)
+            .await
     }
 
     /// Check whether a pending (non-expired) invite for this email already exists in the org
@@ -554,6 +706,400 @@ impl OrgRepository {
         Ok(())
     }
 
+    /// Get the org-level interstitial delay in seconds (0 = disabled)
+    pub async fn get_interstitial_delay(&self, db: &D1Database, org_id: &str) -> Result<u32> {
+        let stmt = db.prepare(
+            "SELECT COALESCE(interstitial_delay_seconds, 0) as interstitial_delay_seconds
+             FROM organizations
+             WHERE id = ?1",
+        );
+        let result = stmt
+            .bind(&[org_id.into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+        Ok(result
+            .and_then(|r| r["interstitial_delay_seconds"].as_f64())
+            .unwrap_or(0.0) as u32)
+    }
+
+    /// Update the org-level interstitial delay
+    pub async fn set_interstitial_delay(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        delay_seconds: u32,
+    ) -> Result<()> {
+        let stmt =
+            db.prepare("UPDATE organizations SET interstitial_delay_seconds = ?1 WHERE id = ?2");
+        stmt.bind(&[(delay_seconds as f64).into(), org_id.into()])?
+            .run()
+            .await?;
+        Ok(())
+    }
+
+    /// Get the org-level default sort for the links list
+    pub async fn get_default_link_sort(&self, db: &D1Database, org_id: &str) -> Result<String> {
+        let stmt = db.prepare(
+            "SELECT COALESCE(default_link_sort, 'created') as default_link_sort
+             FROM organizations
+             WHERE id = ?1",
+        );
+        let result = stmt
+            .bind(&[org_id.into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+        Ok(result
+            .and_then(|r| r["default_link_sort"].as_str().map(|s| s.to_string()))
+            .unwrap_or_else(|| crate::models::link::DEFAULT_LINK_SORT.to_string()))
+    }
+
+    /// Update the org-level default sort for the links list
+    pub async fn set_default_link_sort(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        sort: &str,
+    ) -> Result<()> {
+        let stmt = db.prepare("UPDATE organizations SET default_link_sort = ?1 WHERE id = ?2");
+        stmt.bind(&[sort.into(), org_id.into()])?.run().await?;
+        Ok(())
+    }
+
+    /// Redirect status applied to new links created without a redirect_type
+    pub async fn get_default_redirect_type(&self, db: &D1Database, org_id: &str) -> Result<String> {
+        let stmt = db.prepare(
+            "SELECT COALESCE(default_redirect_type, '301') as default_redirect_type
+             FROM organizations
+             WHERE id = ?1",
+        );
+        let result = stmt
+            .bind(&[org_id.into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+        Ok(result
+            .and_then(|r| r["default_redirect_type"].as_str().map(|s| s.to_string()))
+            .unwrap_or_else(|| crate::models::link::DEFAULT_REDIRECT_TYPE.to_string()))
+    }
+
+    /// Update the org-level default redirect status for new links
+    pub async fn set_default_redirect_type(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        redirect_type: &str,
+    ) -> Result<()> {
+        let stmt = db.prepare("UPDATE organizations SET default_redirect_type = ?1 WHERE id = ?2");
+        stmt.bind(&[redirect_type.into(), org_id.into()])?
+            .run()
+            .await?;
+        Ok(())
+    }
+
+    /// Whether the org lists its active links in the redirect domain's sitemap
+    pub async fn get_public_sitemap(&self, db: &D1Database, org_id: &str) -> Result<bool> {
+        let stmt = db.prepare(
+            "SELECT COALESCE(public_sitemap, 0) as public_sitemap
+             FROM organizations
+             WHERE id = ?1",
+        );
+        let result = stmt
+            .bind(&[org_id.into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+        Ok(result
+            .and_then(|r| r["public_sitemap"].as_f64())
+            .map(|v| v != 0.0)
+            .unwrap_or(false))
+    }
+
+    /// Update the org-level public_sitemap opt-in
+    pub async fn set_public_sitemap(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        enabled: bool,
+    ) -> Result<()> {
+        let stmt = db.prepare("UPDATE organizations SET public_sitemap = ?1 WHERE id = ?2");
+        let value: i64 = if enabled { 1 } else { 0 };
+        stmt.bind(&[(value as f64).into(), org_id.into()])?
+            .run()
+            .await?;
+        Ok(())
+    }
+
+    /// Whether link-preview crawlers get an OpenGraph page instead of the redirect
+    pub async fn get_crawler_preview(&self, db: &D1Database, org_id: &str) -> Result<bool> {
+        let stmt = db.prepare(
+            "SELECT COALESCE(crawler_preview, 0) as crawler_preview
+             FROM organizations
+             WHERE id = ?1",
+        );
+        let result = stmt
+            .bind(&[org_id.into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+        Ok(result
+            .and_then(|r| r["crawler_preview"].as_f64())
+            .map(|v| v != 0.0)
+            .unwrap_or(false))
+    }
+
+    /// Update the org-level crawler_preview opt-in
+    pub async fn set_crawler_preview(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        enabled: bool,
+    ) -> Result<()> {
+        let stmt = db.prepare("UPDATE organizations SET crawler_preview = ?1 WHERE id = ?2");
+        let value: i64 = if enabled { 1 } else { 0 };
+        stmt.bind(&[(value as f64).into(), org_id.into()])?
+            .run()
+            .await?;
+        Ok(())
+    }
+
+    /// Whether destinations outside the trusted-domains list get the interstitial
+    pub async fn get_untrusted_interstitial(&self, db: &D1Database, org_id: &str) -> Result<bool> {
+        let stmt = db.prepare(
+            "SELECT COALESCE(untrusted_interstitial, 0) as untrusted_interstitial
+             FROM organizations
+             WHERE id = ?1",
+        );
+        let result = stmt
+            .bind(&[org_id.into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+        Ok(result
+            .and_then(|r| r["untrusted_interstitial"].as_f64())
+            .map(|v| v != 0.0)
+            .unwrap_or(false))
+    }
+
+    /// Get the org's own monthly link quota (None = no org-level cap)
+    pub async fn get_org_link_quota(&self, db: &D1Database, org_id: &str) -> Result<Option<i64>> {
+        let stmt = db.prepare("SELECT org_link_quota FROM organizations WHERE id = ?1");
+        let result = stmt
+            .bind(&[org_id.into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+        Ok(result
+            .and_then(|r| r["org_link_quota"].as_f64())
+            .map(|v| v as i64))
+    }
+
+    /// Set or clear (None) the org's own monthly link quota
+    pub async fn set_org_link_quota(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        quota: Option<i64>,
+    ) -> Result<()> {
+        let stmt = db.prepare("UPDATE organizations SET org_link_quota = ?1 WHERE id = ?2");
+        stmt.bind(&[
+            quota
+                .map(|q| (q as f64).into())
+                .unwrap_or(wasm_bindgen::JsValue::NULL),
+            org_id.into(),
+        ])?
+        .run()
+        .await?;
+        Ok(())
+    }
+
+    /// Update the org-level untrusted_interstitial policy
+    pub async fn set_untrusted_interstitial(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        enabled: bool,
+    ) -> Result<()> {
+        let stmt = db.prepare("UPDATE organizations SET untrusted_interstitial = ?1 WHERE id = ?2");
+        let value: i64 = if enabled { 1 } else { 0 };
+        stmt.bind(&[(value as f64).into(), org_id.into()])?
+            .run()
+            .await?;
+        Ok(())
+    }
+
+    /// Get the org's trusted destination domains (stored as a JSON array)
+    pub async fn get_trusted_domains(&self, db: &D1Database, org_id: &str) -> Result<Vec<String>> {
+        let stmt = db.prepare("SELECT trusted_domains FROM organizations WHERE id = ?1");
+        let result = stmt
+            .bind(&[org_id.into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+        Ok(result
+            .and_then(|r| r["trusted_domains"].as_str().map(parse_string_list))
+            .unwrap_or_default())
+    }
+
+    /// Replace the org's trusted destination domains
+    pub async fn set_trusted_domains(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        domains: &[String],
+    ) -> Result<()> {
+        let json = serde_json::to_string(domains)
+            .map_err(|e| worker::Error::RustError(format!("Failed to serialize domains: {}", e)))?;
+        let stmt = db.prepare("UPDATE organizations SET trusted_domains = ?1 WHERE id = ?2");
+        stmt.bind(&[json.into(), org_id.into()])?.run().await?;
+        Ok(())
+    }
+
+    /// Get the request headers recorded with the org's click events
+    pub async fn get_analytics_header_fields(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+    ) -> Result<Vec<String>> {
+        let stmt = db.prepare("SELECT analytics_header_fields FROM organizations WHERE id = ?1");
+        let result = stmt
+            .bind(&[org_id.into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+        Ok(result
+            .and_then(|r| r["analytics_header_fields"].as_str().map(parse_string_list))
+            .unwrap_or_default())
+    }
+
+    /// Replace the request headers recorded with the org's click events
+    pub async fn set_analytics_header_fields(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        fields: &[String],
+    ) -> Result<()> {
+        let json = serde_json::to_string(fields).map_err(|e| {
+            worker::Error::RustError(format!("Failed to serialize header fields: {}", e))
+        })?;
+        let stmt =
+            db.prepare("UPDATE organizations SET analytics_header_fields = ?1 WHERE id = ?2");
+        stmt.bind(&[json.into(), org_id.into()])?.run().await?;
+        Ok(())
+    }
+
+    /// Get the org's custom invitation subject and message
+    pub async fn get_invitation_branding(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+    ) -> Result<InvitationBranding> {
+        let stmt = db.prepare(
+            "SELECT invite_subject, invite_message
+             FROM organizations
+             WHERE id = ?1",
+        );
+        let result = stmt
+            .bind(&[org_id.into()])?
+            .first::<InvitationBranding>(None)
+            .await?;
+        Ok(result.unwrap_or_default())
+    }
+
+    /// Update the org's custom invitation subject (None = default)
+    pub async fn set_invite_subject(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        subject: Option<&str>,
+    ) -> Result<()> {
+        let stmt = db.prepare("UPDATE organizations SET invite_subject = ?1 WHERE id = ?2");
+        stmt.bind(&[
+            subject
+                .map(|s| s.into())
+                .unwrap_or(wasm_bindgen::JsValue::NULL),
+            org_id.into(),
+        ])?
+        .run()
+        .await?;
+        Ok(())
+    }
+
+    /// Update the org's custom invitation message (None = none shown)
+    pub async fn set_invite_message(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        message: Option<&str>,
+    ) -> Result<()> {
+        let stmt = db.prepare("UPDATE organizations SET invite_message = ?1 WHERE id = ?2");
+        stmt.bind(&[
+            message
+                .map(|s| s.into())
+                .unwrap_or(wasm_bindgen::JsValue::NULL),
+            org_id.into(),
+        ])?
+        .run()
+        .await?;
+        Ok(())
+    }
+
+    /// Load the fields that make up an org's cached redirect config
+    pub async fn get_redirect_config(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+    ) -> Result<Option<OrgRedirectConfig>> {
+        let stmt = db.prepare(
+            "SELECT name, COALESCE(interstitial_delay_seconds, 0) as interstitial_delay_seconds,
+                    utm_signing_secret, COALESCE(crawler_preview, 0) as crawler_preview,
+                    COALESCE(untrusted_interstitial, 0) as untrusted_interstitial,
+                    trusted_domains, analytics_header_fields
+             FROM organizations
+             WHERE id = ?1",
+        );
+        let result = stmt
+            .bind(&[org_id.into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+        Ok(result.map(|r| OrgRedirectConfig {
+            org_name: r["name"].as_str().unwrap_or_default().to_string(),
+            interstitial_delay_seconds: r["interstitial_delay_seconds"].as_f64().unwrap_or(0.0)
+                as u32,
+            utm_signing_secret: r["utm_signing_secret"].as_str().map(|s| s.to_string()),
+            crawler_preview: r["crawler_preview"].as_f64().is_some_and(|v| v != 0.0),
+            untrusted_interstitial: r["untrusted_interstitial"]
+                .as_f64()
+                .is_some_and(|v| v != 0.0),
+            trusted_domains: r["trusted_domains"]
+                .as_str()
+                .map(parse_string_list)
+                .unwrap_or_default(),
+            analytics_header_fields: r["analytics_header_fields"]
+                .as_str()
+                .map(parse_string_list)
+                .unwrap_or_default(),
+        }))
+    }
+
+    /// Whether the org has a UTM signing secret configured
+    pub async fn has_utm_signing_secret(&self, db: &D1Database, org_id: &str) -> Result<bool> {
+        let stmt = db.prepare(
+            "SELECT utm_signing_secret IS NOT NULL as has_secret FROM organizations WHERE id = ?1",
+        );
+        let result = stmt
+            .bind(&[org_id.into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+        Ok(result
+            .and_then(|r| r["has_secret"].as_f64())
+            .is_some_and(|v| v > 0.0))
+    }
+
+    /// Replace the org's UTM signing secret
+    pub async fn set_utm_signing_secret(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        secret: &str,
+    ) -> Result<()> {
+        let stmt = db.prepare("UPDATE organizations SET utm_signing_secret = ?1 WHERE id = ?2");
+        stmt.bind(&[secret.into(), org_id.into()])?.run().await?;
+        Ok(())
+    }
+
     /// Get the org logo_url (nullable)
     pub async fn get_logo_url(&self, db: &D1Database, org_id: &str) -> Result<Option<String>> {
         let stmt = db.prepare("SELECT logo_url FROM organizations WHERE id = ?1");
@@ -635,6 +1181,12 @@ impl OrgRepository {
         stmt.bind(&[to_org_id.into(), from_org_id.into()])?
             .run()
             .await?;
+
+        let aliases_stmt = db.prepare("UPDATE link_aliases SET org_id = ?1 WHERE org_id = ?2");
+        aliases_stmt
+            .bind(&[to_org_id.into(), from_org_id.into()])?
+            .run()
+            .await?;
         Ok(())
     }
 
@@ -645,6 +1197,9 @@ impl OrgRepository {
         let analytics_stmt = db.prepare("DELETE FROM analytics_events WHERE org_id = ?1");
         analytics_stmt.bind(&[org_id.into()])?.run().await?;
 
+        let aliases_stmt = db.prepare("DELETE FROM link_aliases WHERE org_id = ?1");
+        aliases_stmt.bind(&[org_id.into()])?.run().await?;
+
         // Then delete the links themselves
         let stmt = db.prepare("DELETE FROM links WHERE org_id = ?1");
         stmt.bind(&[org_id.into()])?.run().await?;
@@ -674,12 +1229,35 @@ impl OrgRepository {
     }
 }
 
+/// Decode a JSON string-list column (`trusted_domains`,
+/// `analytics_header_fields`); malformed JSON counts as an empty list.
+fn parse_string_list(raw: &str) -> Vec<String> {
+    serde_json::from_str(raw).unwrap_or_default()
+}
+
 impl Default for OrgRepository {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// SQL condition selecting invitations in `status`, comparing expiry against
+/// the `now_param` placeholder. Also returns whether that placeholder is used,
+/// so callers only bind the timestamp when the statement expects it.
+fn invitation_status_condition(status: InvitationStatus, now_param: &str) -> (String, bool) {
+    match status {
+        InvitationStatus::Pending => (
+            format!("accepted_at IS NULL AND expires_at > {}", now_param),
+            true,
+        ),
+        InvitationStatus::Expired => (
+            format!("accepted_at IS NULL AND expires_at <= {}", now_param),
+            true,
+        ),
+        InvitationStatus::Accepted => ("accepted_at IS NOT NULL".to_string(), false),
+    }
+}
+
 #[cfg(test)]
 mod slug_tests {
     // Test slug generation logic without database dependency
@@ -0,0 +1,107 @@
+use crate::models::RewriteRule;
+use worker::Result;
+use worker::d1::D1Database;
+
+pub struct RewriteRuleRepository;
+
+impl RewriteRuleRepository {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Insert a new rewrite rule
+    pub async fn create(&self, db: &D1Database, rule: &RewriteRule) -> Result<()> {
+        db.prepare(
+            "INSERT INTO org_rewrite_rules (id, org_id, from_pattern, to_pattern, created_by, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )
+        .bind(&[
+            rule.id.as_str().into(),
+            rule.org_id.as_str().into(),
+            rule.from_pattern.as_str().into(),
+            rule.to_pattern.as_str().into(),
+            rule.created_by.as_str().into(),
+            (rule.created_at as f64).into(),
+        ])?
+        .run()
+        .await?;
+        Ok(())
+    }
+
+    /// Get all rewrite rules for an org, in evaluation (creation) order
+    pub async fn list_by_org(&self, db: &D1Database, org_id: &str) -> Result<Vec<RewriteRule>> {
+        let results = db
+            .prepare(
+                "SELECT id, org_id, from_pattern, to_pattern, created_by, created_at
+                 FROM org_rewrite_rules
+                 WHERE org_id = ?1
+                 ORDER BY created_at ASC, id ASC",
+            )
+            .bind(&[org_id.into()])?
+            .all()
+            .await?;
+
+        results.results::<RewriteRule>()
+    }
+
+    /// Get a rewrite rule by ID (must belong to org for authorization)
+    pub async fn get_by_id_and_org(
+        &self,
+        db: &D1Database,
+        id: &str,
+        org_id: &str,
+    ) -> Result<Option<RewriteRule>> {
+        db.prepare(
+            "SELECT id, org_id, from_pattern, to_pattern, created_by, created_at
+             FROM org_rewrite_rules
+             WHERE id = ?1 AND org_id = ?2",
+        )
+        .bind(&[id.into(), org_id.into()])?
+        .first::<RewriteRule>(None)
+        .await
+    }
+
+    /// Check whether the org already has a rule for this from_pattern
+    pub async fn exists_for_pattern(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        from_pattern: &str,
+    ) -> Result<bool> {
+        let result = db
+            .prepare(
+                "SELECT 1 as found FROM org_rewrite_rules
+                 WHERE org_id = ?1 AND from_pattern = ?2",
+            )
+            .bind(&[org_id.into(), from_pattern.into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+        Ok(result.is_some())
+    }
+
+    /// Count rewrite rules for an org
+    pub async fn count_by_org(&self, db: &D1Database, org_id: &str) -> Result<i64> {
+        let result = db
+            .prepare("SELECT COUNT(*) as count FROM org_rewrite_rules WHERE org_id = ?1")
+            .bind(&[org_id.into()])?
+            .first::<serde_json::Value>(None)
+            .await?;
+
+        Ok(result.and_then(|v| v["count"].as_f64()).unwrap_or(0.0) as i64)
+    }
+
+    /// Delete a rewrite rule
+    pub async fn delete(&self, db: &D1Database, id: &str, org_id: &str) -> Result<()> {
+        db.prepare("DELETE FROM org_rewrite_rules WHERE id = ?1 AND org_id = ?2")
+            .bind(&[id.into(), org_id.into()])?
+            .run()
+            .await?;
+        Ok(())
+    }
+}
+
+impl Default for RewriteRuleRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
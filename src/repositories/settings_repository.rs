@@ -2,7 +2,7 @@
 ///
 /// Handles all database operations related to system settings.
 use std::collections::HashMap;
-use worker::d1::D1Database;
+use worker::d1::{D1Database, D1PreparedStatement};
 use worker::*;
 
 /// Repository for settings operations
@@ -44,6 +44,21 @@ impl SettingsRepository {
         Ok(settings)
     }
 
+    /// Upsert statement for a setting, for callers that batch several writes
+    pub fn set_setting_statement(
+        &self,
+        db: &D1Database,
+        key: &str,
+        value: &str,
+    ) -> Result<D1PreparedStatement> {
+        let now = crate::utils::now_timestamp();
+        db.prepare(
+            "INSERT INTO settings (key, value, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(key) DO UPDATE SET value = ?2, updated_at = ?3",
+        )
+        .bind(&[key.into(), value.into(), (now as f64).into()])
+    }
+
     /// Set a setting value (upsert)
     pub async fn set_setting(&self, db: &D1Database, key: &str, value: &str) -> Result<()> {
         let now = crate::utils::now_timestamp();
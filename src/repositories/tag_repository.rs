@@ -15,6 +15,27 @@ pub struct OrgTag {
     pub color_index: Option<i32>,
 }
 
+/// Tag name with its link count, for autocomplete
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TagMatch {
+    pub name: String,
+    pub count: i64,
+}
+
+/// Build a `LIKE` pattern matching names that start with `prefix`, escaping
+/// `%`, `_` and the escape character itself so they match literally.
+fn prefix_like_pattern(prefix: &str) -> String {
+    let mut pattern = String::with_capacity(prefix.len() + 1);
+    for c in prefix.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
 /// Similar tag group for analytics
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct SimilarTagGroup {
@@ -78,6 +99,93 @@ impl TagRepository {
         Ok(tags)
     }
 
+    /// Tags used on the org's links whose name starts with `prefix`
+    /// (case-insensitive), most used first.
+    pub async fn autocomplete_tags(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        prefix: &str,
+        limit: i64,
+    ) -> Result<Vec<TagMatch>> {
+        let stmt = db.prepare(
+            "SELECT tag_name as name, COUNT(*) as count
+             FROM link_tags
+             WHERE org_id = ?1 AND tag_name LIKE ?2 ESCAPE '\\'
+             GROUP BY tag_name
+             ORDER BY count DESC, tag_name ASC
+             LIMIT ?3",
+        );
+        stmt.bind(&[
+            org_id.into(),
+            prefix_like_pattern(prefix).into(),
+            (limit as f64).into(),
+        ])?
+        .all()
+        .await?
+        .results::<TagMatch>()
+    }
+
+    /// Get globally configured tag suggestions for any of the given domains.
+    pub async fn get_domain_tag_suggestions(
+        &self,
+        db: &D1Database,
+        domains: &[String],
+    ) -> Result<Vec<String>> {
+        if domains.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders: Vec<String> = (1..=domains.len()).map(|i| format!("?{}", i)).collect();
+        let query = format!(
+            "SELECT DISTINCT tag_name FROM domain_tag_suggestions
+             WHERE domain IN ({})
+             ORDER BY tag_name ASC",
+            placeholders.join(", ")
+        );
+        let params: Vec<wasm_bindgen::JsValue> =
+            domains.iter().map(|d| d.as_str().into()).collect();
+        let results = db.prepare(&query).bind(&params)?.all().await?;
+        let rows = results.results::<serde_json::Value>()?;
+        Ok(rows
+            .iter()
+            .filter_map(|row| row["tag_name"].as_str().map(|s| s.to_string()))
+            .collect())
+    }
+
+    /// Get (tag_name, destination_url) pairs for an org's tagged links whose
+    /// destination contains `host`. Callers must re-check the host precisely;
+    /// the LIKE is only a cheap pre-filter (a `_` in the host may over-match).
+    pub async fn get_tagged_destinations_matching(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        host: &str,
+    ) -> Result<Vec<(String, String)>> {
+        let pattern = format!("%{}%", host);
+        let results = db
+            .prepare(
+                "SELECT lt.tag_name, l.destination_url
+                 FROM link_tags lt
+                 JOIN links l ON l.id = lt.link_id
+                 WHERE lt.org_id = ?1
+                 AND l.destination_url LIKE ?2
+                 LIMIT 500",
+            )
+            .bind(&[org_id.into(), pattern.into()])?
+            .all()
+            .await?;
+        let rows = results.results::<serde_json::Value>()?;
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                Some((
+                    row["tag_name"].as_str()?.to_string(),
+                    row["destination_url"].as_str()?.to_string(),
+                ))
+            })
+            .collect())
+    }
+
     /// Create a new tag in the organization.
     /// Returns true if created, false if tag already exists.
     pub async fn create_tag(
@@ -499,3 +607,16 @@ impl TagRepository {
         Ok(affected_rows > 0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_like_pattern_escapes_wildcards() {
+        assert_eq!(prefix_like_pattern("mark"), "mark%");
+        assert_eq!(prefix_like_pattern(""), "%");
+        assert_eq!(prefix_like_pattern("50%_off"), "50\\%\\_off%");
+        assert_eq!(prefix_like_pattern("a\\b"), "a\\\\b%");
+    }
+}
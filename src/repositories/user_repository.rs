@@ -2,9 +2,10 @@
 ///
 /// Data access layer for user records in D1.
 /// Note: Session data is stored in KV and managed via auth::session.
-use crate::models::link::Link;
+use crate::models::link::{ANONYMOUS_USER_ID, DISABLED_REASON_SUSPENSION, Link};
 use crate::models::user::{CreateUserData, User};
 use crate::utils::now_timestamp;
+use wasm_bindgen::JsValue;
 use worker::Result;
 use worker::d1::D1Database;
 
@@ -24,6 +25,7 @@ pub struct UserWithBillingInfo {
     pub suspension_reason: Option<String>,
     pub suspended_by: Option<String>,
     pub last_login_at: Option<i64>,
+    pub last_login_provider: Option<String>,
     pub billing_account_id: Option<String>,
     pub billing_account_tier: Option<String>,
 }
@@ -50,7 +52,7 @@ impl UserRepository {
         // Try to find existing user by OAuth provider and ID
         let stmt = db.prepare(
             "SELECT id, email, name, avatar_url, oauth_provider, oauth_id, org_id, role, created_at,
-                    suspended_at, suspension_reason, suspended_by, last_login_at
+                    suspended_at, suspension_reason, suspended_by, last_login_at, last_login_provider
              FROM users
              WHERE oauth_provider = ?1 AND oauth_id = ?2",
         );
@@ -95,6 +97,7 @@ impl UserRepository {
                 suspension_reason: user.suspension_reason,
                 suspended_by: user.suspended_by,
                 last_login_at: user.last_login_at,
+                last_login_provider: user.last_login_provider,
             })
         } else {
             // Determine role: first user on the instance gets admin, all others get member
@@ -136,6 +139,7 @@ impl UserRepository {
                 suspension_reason: None,
                 suspended_by: None,
                 last_login_at: None,
+                last_login_provider: None,
             })
         }
     }
@@ -144,7 +148,7 @@ impl UserRepository {
     pub async fn get_user_by_id(&self, db: &D1Database, user_id: &str) -> Result<Option<User>> {
         let stmt = db.prepare(
             "SELECT id, email, name, avatar_url, oauth_provider, oauth_id, org_id, role, created_at,
-                    suspended_at, suspension_reason, suspended_by, last_login_at
+                    suspended_at, suspension_reason, suspended_by, last_login_at, last_login_provider
              FROM users
              WHERE id = ?1",
         );
@@ -155,7 +159,7 @@ impl UserRepository {
     pub async fn get_by_email(&self, db: &D1Database, email: &str) -> Result<Option<User>> {
         let stmt = db.prepare(
             "SELECT id, email, name, avatar_url, oauth_provider, oauth_id, org_id, role, created_at,
-                    suspended_at, suspension_reason, suspended_by, last_login_at
+                    suspended_at, suspension_reason, suspended_by, last_login_at, last_login_provider
              FROM users
              WHERE email = ?1",
         );
@@ -173,14 +177,20 @@ impl UserRepository {
             .prepare(
                 "SELECT u.id, u.email, u.name, u.avatar_url, u.oauth_provider, u.oauth_id,
                         u.org_id, u.role, u.created_at, u.suspended_at, u.suspension_reason, u.suspended_by, u.last_login_at,
+                        u.last_login_provider,
                         o.billing_account_id, ba.tier as billing_account_tier
                  FROM users u
                  LEFT JOIN organizations o ON u.org_id = o.id
                  LEFT JOIN billing_accounts ba ON o.billing_account_id = ba.id
+                 WHERE u.id != ?3
                  ORDER BY u.created_at DESC
                  LIMIT ?1 OFFSET ?2",
             )
-            .bind(&[(limit as f64).into(), (offset as f64).into()])?
+            .bind(&[
+                (limit as f64).into(),
+                (offset as f64).into(),
+                ANONYMOUS_USER_ID.into(),
+            ])?
             .all()
             .await?
             .results::<serde_json::Value>()?;
@@ -202,6 +212,7 @@ impl UserRepository {
                     suspension_reason: row["suspension_reason"].as_str().map(|s| s.to_string()),
                     suspended_by: row["suspended_by"].as_str().map(|s| s.to_string()),
                     last_login_at: row["last_login_at"].as_f64().map(|v| v as i64),
+                    last_login_provider: row["last_login_provider"].as_str().map(|s| s.to_string()),
                     billing_account_id: row["billing_account_id"].as_str().map(|s| s.to_string()),
                     billing_account_tier: row["billing_account_tier"]
                         .as_str()
@@ -213,10 +224,12 @@ impl UserRepository {
         Ok(users)
     }
 
-    /// Total number of users on the instance.
+    /// Total number of users on the instance (excluding the anonymous
+    /// placeholder user).
     pub async fn count(&self, db: &D1Database) -> Result<i64> {
         let result = db
-            .prepare("SELECT COUNT(*) as count FROM users")
+            .prepare("SELECT COUNT(*) as count FROM users WHERE id != ?1")
+            .bind(&[ANONYMOUS_USER_ID.into()])?
             .first::<serde_json::Value>(None)
             .await?;
         Ok(result
@@ -357,6 +370,114 @@ impl UserRepository {
         Ok((user_count, links_count, analytics_count))
     }
 
+    /// Merge `duplicate_id` into `primary_id` and delete the duplicate, in one
+    /// D1 batch so a failure leaves both accounts untouched.
+    ///
+    /// Org memberships move to the primary; where both belong to the same org
+    /// the primary keeps the higher role. Everything else pointing at the
+    /// duplicate (links, billing ownership, reports, invitations, API keys,
+    /// blacklist and code-word entries, rewrite rules, aliases, pending
+    /// actions) is reassigned before the duplicate row is deleted, so no
+    /// foreign key is left dangling. An instance admin role carries over.
+    /// Returns `(memberships_moved, links_moved)`.
+    pub async fn merge_into(
+        &self,
+        db: &D1Database,
+        primary_id: &str,
+        duplicate_id: &str,
+    ) -> Result<(usize, usize)> {
+        let rank = |column: &str| {
+            format!(
+                "CASE {} WHEN 'owner' THEN 3 WHEN 'admin' THEN 2 ELSE 1 END",
+                column
+            )
+        };
+
+        let ids: [JsValue; 2] = [primary_id.into(), duplicate_id.into()];
+        let mut statements = vec![
+            // Shared orgs: upgrade the primary's role, then drop the duplicate's row
+            db.prepare(format!(
+                "UPDATE org_members SET role = (
+                     SELECT d.role FROM org_members d
+                     WHERE d.org_id = org_members.org_id AND d.user_id = ?2
+                 )
+                 WHERE user_id = ?1 AND EXISTS (
+                     SELECT 1 FROM org_members d
+                     WHERE d.org_id = org_members.org_id AND d.user_id = ?2
+                       AND {} > {}
+                 )",
+                rank("d.role"),
+                rank("org_members.role")
+            ))
+            .bind(&ids)?,
+            db.prepare(
+                "DELETE FROM org_members
+                 WHERE user_id = ?2 AND org_id IN (SELECT org_id FROM org_members WHERE user_id = ?1)",
+            )
+            .bind(&ids)?,
+            db.prepare("UPDATE org_members SET user_id = ?1 WHERE user_id = ?2")
+                .bind(&ids)?,
+            db.prepare("UPDATE links SET created_by = ?1 WHERE created_by = ?2")
+                .bind(&ids)?,
+        ];
+
+        for (table, column) in [
+            ("billing_accounts", "owner_user_id"),
+            ("organizations", "created_by"),
+            ("link_reports", "reporter_user_id"),
+            ("link_reports", "reviewed_by"),
+            ("org_invitations", "invited_by"),
+            ("api_keys", "user_id"),
+            ("api_keys", "updated_by"),
+            ("destination_blacklist", "created_by"),
+            ("disallowed_code_words", "created_by"),
+            ("org_rewrite_rules", "created_by"),
+            ("link_aliases", "created_by"),
+            ("pending_actions", "initiated_by"),
+            ("users", "suspended_by"),
+        ] {
+            statements.push(
+                db.prepare(format!(
+                    "UPDATE {} SET {} = ?1 WHERE {} = ?2",
+                    table, column, column
+                ))
+                .bind(&ids)?,
+            );
+        }
+
+        // An admin duplicate makes the primary an admin
+        statements.push(
+            db.prepare(
+                "UPDATE users SET role = 'admin'
+                 WHERE id = ?1 AND role != 'admin'
+                   AND EXISTS (SELECT 1 FROM users WHERE id = ?2 AND role = 'admin')",
+            )
+            .bind(&ids)?,
+        );
+
+        // The primary keeps its own notification preferences
+        statements.push(
+            db.prepare("DELETE FROM notification_preferences WHERE user_id = ?1")
+                .bind(&[duplicate_id.into()])?,
+        );
+        statements.push(
+            db.prepare("DELETE FROM users WHERE id = ?1")
+                .bind(&[duplicate_id.into()])?,
+        );
+
+        let results = db.batch(statements).await?;
+        let changes = |index: usize| {
+            results
+                .get(index)
+                .and_then(|r| r.meta().ok().flatten())
+                .and_then(|m| m.changes)
+                .unwrap_or(0)
+        };
+
+        // Memberships merged into an existing row count as moved too
+        Ok((changes(1) + changes(2), changes(3)))
+    }
+
     /// All links created by a specific user (for KV cleanup before deletion).
     pub async fn get_links_by_creator(&self, db: &D1Database, user_id: &str) -> Result<Vec<Link>> {
         db.prepare(
@@ -377,7 +498,7 @@ impl UserRepository {
         db.prepare(
             "SELECT id, org_id, short_code, destination_url, title, created_by,
                     created_at, updated_at, expires_at, status, click_count,
-                    utm_params, forward_query_params, redirect_type
+                    utm_params, forward_query_params, redirect_type, disabled_reason
              FROM links
              WHERE org_id = ?1",
         )
@@ -387,15 +508,20 @@ impl UserRepository {
         .results::<Link>()
     }
 
-    /// Soft-disable all active links for an org; returns the number of rows changed.
+    /// Soft-disable all active links for an org because its owner was
+    /// suspended; returns the number of rows changed.
     pub async fn disable_all_links_for_org(&self, db: &D1Database, org_id: &str) -> Result<i64> {
         let now = now_timestamp();
         let result = db
             .prepare(
-                "UPDATE links SET status = 'disabled', updated_at = ?1
-                 WHERE org_id = ?2 AND status = 'active'",
+                "UPDATE links SET status = 'disabled', disabled_reason = ?1, updated_at = ?2
+                 WHERE org_id = ?3 AND status = 'active'",
             )
-            .bind(&[(now as f64).into(), org_id.into()])?
+            .bind(&[
+                DISABLED_REASON_SUSPENSION.into(),
+                (now as f64).into(),
+                org_id.into(),
+            ])?
             .run()
             .await?;
         Ok(result
@@ -405,11 +531,33 @@ impl UserRepository {
             .unwrap_or(0))
     }
 
+    /// Forget that an org's links were disabled by a suspension, on
+    /// unsuspend. The links stay disabled.
+    pub async fn clear_suspension_disabled_reason(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+    ) -> Result<()> {
+        db.prepare(
+            "UPDATE links SET disabled_reason = NULL WHERE org_id = ?1 AND disabled_reason = ?2",
+        )
+        .bind(&[org_id.into(), DISABLED_REASON_SUSPENSION.into()])?
+        .run()
+        .await?;
+        Ok(())
+    }
+
     /// Update the last login timestamp for a user.
-    pub async fn update_last_login(&self, db: &D1Database, user_id: &str) -> Result<()> {
+    pub async fn update_last_login(
+        &self,
+        db: &D1Database,
+        user_id: &str,
+        provider: &str,
+    ) -> Result<()> {
         let now = now_timestamp();
-        let stmt = db.prepare("UPDATE users SET last_login_at = ?1 WHERE id = ?2");
-        stmt.bind(&[(now as f64).into(), user_id.into()])?
+        let stmt = db
+            .prepare("UPDATE users SET last_login_at = ?1, last_login_provider = ?2 WHERE id = ?3");
+        stmt.bind(&[(now as f64).into(), provider.into(), user_id.into()])?
             .run()
             .await?;
         Ok(())
@@ -31,6 +31,14 @@ pub async fn scheduled(event: ScheduledEvent, env: Env, _ctx: ScheduleContext) {
         "0 4 * * *" => {
             console_log!("[cron] Starting webhook cleanup job (4 AM UTC)");
             service.cleanup_webhooks(&db).await;
+            match env.kv("URL_MAPPINGS") {
+                Ok(kv) => {
+                    super::purge_disabled_links::run(&db, &kv).await;
+                }
+                Err(e) => console_error!("[cron] Failed to get KV binding: {}", e),
+            }
+            super::purge_deleted_links::run(&db).await;
+            super::revoke_inactive_api_keys::run(&db).await;
         }
         "*/15 * * * *" => {
             console_log!("[cron] Starting domain status poll job (every 15 minutes)");
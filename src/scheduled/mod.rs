@@ -2,3 +2,6 @@
 
 pub mod downgrade_expired_subscriptions;
 pub mod poll_domain_status;
+pub mod purge_deleted_links;
+pub mod purge_disabled_links;
+pub mod revoke_inactive_api_keys;
@@ -0,0 +1,33 @@
+//! Scheduled job: purge tombstones of admin-deleted links, and their retained
+//! click events, once their retention period has ended.
+//!
+//! Runs with the daily cleanup job. Tombstones only exist while
+//! `deleted_link_retention_days` is (or was) set.
+
+use crate::repositories::DeletedLinkRepository;
+use crate::utils::now_timestamp;
+use worker::d1::D1Database;
+use worker::*;
+
+/// Most tombstones purged per run, so a large backlog is spread over several days.
+const MAX_PURGES_PER_RUN: i64 = 500;
+
+/// Purge expired tombstones. Returns the number of tombstones deleted.
+pub async fn run(db: &D1Database) -> usize {
+    match DeletedLinkRepository::new()
+        .purge_expired(db, now_timestamp(), MAX_PURGES_PER_RUN)
+        .await
+    {
+        Ok(purged) => {
+            console_log!(
+                "[purge] Purged {} expired deleted-link tombstone(s)",
+                purged
+            );
+            purged
+        }
+        Err(e) => {
+            console_error!("[purge] Failed to purge deleted-link tombstones: {}", e);
+            0
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! Scheduled job: hard-delete links that have stayed disabled past the
+//! configured grace period, freeing their short codes.
+//!
+//! Runs with the daily cleanup job. Off unless `disabled_link_purge_days` is
+//! set; by default only links with no clicks during the grace period qualify.
+
+use crate::repositories::LinkRepository;
+use crate::services::{LinkService, SettingsService};
+use crate::utils::now_timestamp;
+use worker::d1::D1Database;
+use worker::kv::KvStore;
+use worker::*;
+
+/// Most links purged per run, so a large backlog is spread over several days.
+const MAX_PURGES_PER_RUN: i64 = 500;
+
+/// Purge eligible disabled links. Returns the number of links deleted.
+pub async fn run(db: &D1Database, kv: &KvStore) -> usize {
+    let policy = match SettingsService::new()
+        .get_disabled_link_purge_policy(db)
+        .await
+    {
+        Ok(Some(policy)) => policy,
+        Ok(None) => return 0,
+        Err(e) => {
+            console_error!("[purge] Failed to read purge settings: {}", e);
+            return 0;
+        }
+    };
+
+    let now = now_timestamp();
+    let candidates = match LinkRepository::new()
+        .list_disabled_purge_candidates(
+            db,
+            policy.cutoff(now),
+            policy.require_zero_clicks,
+            MAX_PURGES_PER_RUN,
+        )
+        .await
+    {
+        Ok(c) => c,
+        Err(e) => {
+            console_error!("[purge] Failed to list disabled links: {}", e);
+            return 0;
+        }
+    };
+
+    let service = LinkService::new();
+    let mut purged = 0;
+
+    for link in candidates
+        .iter()
+        .filter(|l| policy.is_eligible(l.last_changed_at, l.recent_clicks, now))
+    {
+        match service.delete_link(db, kv, &link.id, &link.org_id).await {
+            Ok(()) => {
+                purged += 1;
+                console_log!(
+                    "{}",
+                    serde_json::json!({
+                        "event": "disabled_link_purged",
+                        "link_id": link.id,
+                        "org_id": link.org_id,
+                        "short_code": link.short_code,
+                        "disabled_since": link.last_changed_at,
+                        "grace_days": policy.grace_days,
+                        "require_zero_clicks": policy.require_zero_clicks,
+                        "level": "info"
+                    })
+                );
+            }
+            Err(e) => {
+                console_error!("[purge] Failed to purge link {}: {}", link.id, e);
+            }
+        }
+    }
+
+    console_log!("[purge] Purged {} disabled link(s)", purged);
+    purged
+}
@@ -0,0 +1,74 @@
+//! Scheduled job: revoke API keys that have not been used for the configured
+//! number of days, so leaked-but-forgotten keys stop working.
+//!
+//! Runs with the daily cleanup job. Off unless `api_key_inactive_revoke_days`
+//! is set. Keys that were never used count from their creation time.
+
+use crate::models::api_key::{inactive_key_cutoff, is_key_inactive};
+use crate::repositories::ApiKeyRepository;
+use crate::services::SettingsService;
+use crate::utils::now_timestamp;
+use worker::d1::D1Database;
+use worker::*;
+
+/// Most keys revoked per run, so a large backlog is spread over several days.
+const MAX_REVOCATIONS_PER_RUN: i64 = 500;
+
+/// Revoke inactive API keys. Returns the number of keys revoked.
+pub async fn run(db: &D1Database) -> usize {
+    let inactive_days = match SettingsService::new()
+        .get_api_key_inactive_revoke_days(db)
+        .await
+    {
+        Ok(Some(days)) => days,
+        Ok(None) => return 0,
+        Err(e) => {
+            console_error!("[api-keys] Failed to read inactivity setting: {}", e);
+            return 0;
+        }
+    };
+
+    let now = now_timestamp();
+    let repo = ApiKeyRepository::new();
+    let candidates = match repo
+        .list_inactive_active_keys(
+            db,
+            inactive_key_cutoff(inactive_days, now),
+            MAX_REVOCATIONS_PER_RUN,
+        )
+        .await
+    {
+        Ok(keys) => keys,
+        Err(e) => {
+            console_error!("[api-keys] Failed to list inactive keys: {}", e);
+            return 0;
+        }
+    };
+
+    let stale: Vec<_> = candidates
+        .into_iter()
+        .filter(|k| is_key_inactive(k.last_used_at, k.created_at, inactive_days, now))
+        .collect();
+    let key_ids: Vec<String> = stale.iter().map(|k| k.id.clone()).collect();
+    if let Err(e) = repo.revoke_keys_system(db, &key_ids).await {
+        console_error!("[api-keys] Failed to revoke inactive keys: {}", e);
+        return 0;
+    }
+
+    for key in &stale {
+        console_log!(
+            "{}",
+            serde_json::json!({
+                "event": "api_key_revoked_inactive",
+                "key_id": key.id,
+                "user_id": key.user_id,
+                "last_used_at": key.last_used_at,
+                "inactive_days": inactive_days,
+                "level": "info"
+            })
+        );
+    }
+
+    console_log!("[api-keys] Revoked {} inactive API key(s)", stale.len());
+    stale.len()
+}
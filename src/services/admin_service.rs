@@ -1,8 +1,10 @@
 /// Admin service - Business logic for administrative operations
 ///
 /// Handles admin-specific business rules and validation.
-/// Orchestrates UserRepository, ApiKeyRepository, BillingRepository.
-use crate::repositories::{ApiKeyRepository, BillingRepository, UserRepository};
+/// Orchestrates UserRepository, ApiKeyRepository, BillingRepository, LinkRepository.
+use crate::models::link::{DISABLED_REASON_SUSPENSION, Link};
+use crate::repositories::link_repository::AdminLinkBase;
+use crate::repositories::{ApiKeyRepository, BillingRepository, LinkRepository, UserRepository};
 use crate::utils::AppError;
 use worker::d1::D1Database;
 
@@ -32,6 +34,39 @@ impl AdminService {
         Ok((users_json, total))
     }
 
+    /// Total number of users, for count-only listings.
+    pub async fn count_users(&self, db: &D1Database) -> Result<i64, AppError> {
+        Ok(UserRepository::new().count(db).await?)
+    }
+
+    /// Links a user created in any org (paginated), for abuse investigations.
+    pub async fn list_user_links(
+        &self,
+        db: &D1Database,
+        user_id: &str,
+        status_filter: Option<&str>,
+        page: i64,
+        limit: i64,
+    ) -> Result<(Vec<AdminLinkBase>, i64), AppError> {
+        if UserRepository::new()
+            .get_user_by_id(db, user_id)
+            .await?
+            .is_none()
+        {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+
+        let offset = (page - 1) * limit;
+        let repo = LinkRepository::new();
+        let links = repo
+            .list_admin_by_creator(db, user_id, status_filter, limit, offset)
+            .await?;
+        let total = repo
+            .count_admin_by_creator(db, user_id, status_filter)
+            .await?;
+        Ok((links, total))
+    }
+
     /// Get a single user by ID.
     pub async fn get_user(
         &self,
@@ -129,15 +164,31 @@ impl AdminService {
         }))
     }
 
-    /// Unsuspend a user.
+    /// Unsuspend a user. Their links stay disabled, but no longer as
+    /// suspended; returns those links so their KV mappings can be re-synced.
     pub async fn unsuspend_user(
         &self,
         db: &D1Database,
         target_user_id: &str,
-    ) -> Result<(), AppError> {
+    ) -> Result<Vec<Link>, AppError> {
         let repo = UserRepository::new();
         repo.unsuspend(db, target_user_id).await?;
-        Ok(())
+
+        let Some(target_user) = repo.get_user_by_id(db, target_user_id).await? else {
+            return Ok(Vec::new());
+        };
+        let mut links: Vec<Link> = repo
+            .get_links_by_org(db, &target_user.org_id)
+            .await?
+            .into_iter()
+            .filter(|link| link.disabled_reason.as_deref() == Some(DISABLED_REASON_SUSPENSION))
+            .collect();
+        repo.clear_suspension_disabled_reason(db, &target_user.org_id)
+            .await?;
+        for link in &mut links {
+            link.disabled_reason = None;
+        }
+        Ok(links)
     }
 
     /// Delete a user and all associated data.
@@ -185,6 +236,52 @@ impl AdminService {
         ))
     }
 
+    /// Merge a duplicate account into a primary one, e.g. a user who signed
+    /// in with GitHub and later with Google. The duplicate's memberships,
+    /// links, billing ownership, reports and API keys move to the primary,
+    /// which also becomes an instance admin if the duplicate was one. The
+    /// duplicate is then deleted.
+    ///
+    /// Returns `(memberships_moved, links_moved)`.
+    pub async fn merge_users(
+        &self,
+        db: &D1Database,
+        primary_id: &str,
+        duplicate_id: &str,
+        admin_user_id: &str,
+    ) -> Result<(i64, i64), AppError> {
+        if primary_id == duplicate_id {
+            return Err(AppError::BadRequest(
+                "Cannot merge a user into itself".to_string(),
+            ));
+        }
+        if duplicate_id == admin_user_id {
+            return Err(AppError::BadRequest(
+                "Cannot merge away your own account".to_string(),
+            ));
+        }
+
+        let repo = UserRepository::new();
+        let primary = repo
+            .get_user_by_id(db, primary_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Primary user not found".to_string()))?;
+        repo.get_user_by_id(db, duplicate_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Duplicate user not found".to_string()))?;
+
+        if primary.suspended_at.is_some() {
+            return Err(AppError::BadRequest(
+                "Cannot merge into a suspended account".to_string(),
+            ));
+        }
+
+        let (memberships_moved, links_moved) =
+            repo.merge_into(db, primary_id, duplicate_id).await?;
+
+        Ok((memberships_moved as i64, links_moved as i64))
+    }
+
     /// List all API keys (paginated, with optional search and status filter).
     pub async fn list_api_keys(
         &self,
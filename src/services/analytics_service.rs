@@ -2,6 +2,9 @@
 ///
 /// Business logic for analytics gating and time range parsing.
 /// Moved from api/analytics.rs to the services layer.
+use crate::models::analytics::{
+    Granularity, MAX_HOURLY_RANGE_SECS, MAX_SERIES_POINTS, downsample_interval_secs,
+};
 use crate::models::{Tier, TimeRange};
 
 /// Analytics gating result
@@ -45,6 +48,144 @@ pub fn apply_analytics_gating(
     }
 }
 
+/// Parse the `compare` query parameter. Only `previous` is supported.
+pub fn parse_compare_param(value: Option<&str>) -> Result<bool, crate::utils::AppError> {
+    match value {
+        None | Some("") => Ok(false),
+        Some("previous") => Ok(true),
+        Some(other) => Err(crate::utils::AppError::BadRequest(format!(
+            "Invalid compare value '{}'. Supported: previous",
+            other
+        ))),
+    }
+}
+
+/// Lowest accepted UTC offset for day bucketing (UTC-12:00)
+pub const MIN_TZ_OFFSET_MINUTES: i64 = -12 * 60;
+/// Highest accepted UTC offset for day bucketing (UTC+14:00)
+pub const MAX_TZ_OFFSET_MINUTES: i64 = 14 * 60;
+
+/// Parse the `tz_offset_minutes` query parameter: the viewer's offset from
+/// UTC (e.g. 540 for UTC+9), used to bucket clicks into local days.
+/// Absent means UTC.
+pub fn parse_tz_offset_param(value: Option<&str>) -> Result<i64, crate::utils::AppError> {
+    match value {
+        None | Some("") => Ok(0),
+        Some(raw) => raw
+            .parse::<i64>()
+            .ok()
+            .filter(|m| (MIN_TZ_OFFSET_MINUTES..=MAX_TZ_OFFSET_MINUTES).contains(m))
+            .ok_or_else(|| {
+                crate::utils::AppError::BadRequest(format!(
+                    "Invalid tz_offset_minutes '{}'. Must be an integer between {} and {}",
+                    raw, MIN_TZ_OFFSET_MINUTES, MAX_TZ_OFFSET_MINUTES
+                ))
+            }),
+    }
+}
+
+/// Reject hourly buckets over ranges wider than `MAX_HOURLY_RANGE_SECS`,
+/// which would return an unbounded number of buckets.
+pub fn check_granularity_range(
+    granularity: Granularity,
+    start: i64,
+    end: i64,
+) -> Result<(), crate::utils::AppError> {
+    if granularity == Granularity::Hour && end - start > MAX_HOURLY_RANGE_SECS {
+        return Err(crate::utils::AppError::BadRequest(format!(
+            "granularity=hour supports ranges of up to {} days; use a smaller window or granularity=day",
+            MAX_HOURLY_RANGE_SECS / (24 * 60 * 60)
+        )));
+    }
+    Ok(())
+}
+
+/// Parse the `granularity` query parameter. Absent means daily buckets.
+pub fn parse_granularity_param(value: Option<&str>) -> Result<Granularity, crate::utils::AppError> {
+    match value {
+        None | Some("") | Some("day") => Ok(Granularity::Day),
+        Some("hour") => Ok(Granularity::Hour),
+        Some("week") => Ok(Granularity::Week),
+        Some("month") => Ok(Granularity::Month),
+        Some(other) => Err(crate::utils::AppError::BadRequest(format!(
+            "Invalid granularity '{}'. Supported: hour, day, week, month",
+            other
+        ))),
+    }
+}
+
+/// Parse the `max_points` query parameter: the most buckets clicks_over_time
+/// may return. Absent means full resolution.
+pub fn parse_max_points_param(value: Option<&str>) -> Result<Option<u32>, crate::utils::AppError> {
+    match value {
+        None | Some("") => Ok(None),
+        Some(raw) => raw
+            .parse::<u32>()
+            .ok()
+            .filter(|n| (1..=MAX_SERIES_POINTS).contains(n))
+            .map(Some)
+            .ok_or_else(|| {
+                crate::utils::AppError::BadRequest(format!(
+                    "Invalid max_points '{}'. Must be an integer between 1 and {}",
+                    raw, MAX_SERIES_POINTS
+                ))
+            }),
+    }
+}
+
+/// The window immediately before the inclusive `[start, end]` window, with
+/// the same length. Never starts before the epoch.
+pub fn previous_window(start: i64, end: i64) -> (i64, i64) {
+    let previous_end = start - 1;
+    ((previous_end - (end - start)).max(0), previous_end)
+}
+
+/// Percentage change from `previous` to `current`, rounded to one decimal.
+/// None when there is nothing to compare against (previous is zero).
+pub fn percent_change(current: i64, previous: i64) -> Option<f64> {
+    if previous == 0 {
+        return None;
+    }
+    let change = (current - previous) as f64 / previous as f64 * 100.0;
+    Some((change * 10.0).round() / 10.0)
+}
+
+/// Compare `current_total` for the requested `[start, end]` window against
+/// the preceding window, clamping that window to the tier's retention.
+/// `count_clicks` returns the total clicks for an inclusive range.
+async fn compare_with_previous_window<F, Fut>(
+    tier: Tier,
+    start: i64,
+    end: i64,
+    now: i64,
+    current_total: i64,
+    count_clicks: F,
+) -> Result<crate::models::analytics::PeriodComparison, crate::utils::AppError>
+where
+    F: FnOnce(i64, i64) -> Fut,
+    Fut: std::future::Future<Output = worker::Result<i64>>,
+{
+    let (previous_start, previous_end) = previous_window(start, end);
+    let gating = apply_analytics_gating(tier, previous_start, previous_end, now);
+    let previous_start = gating.adjusted_start;
+
+    // Entirely outside the retention window: nothing to count
+    let previous_total = if previous_start > previous_end {
+        0
+    } else {
+        count_clicks(previous_start, previous_end).await?
+    };
+
+    Ok(crate::models::analytics::PeriodComparison {
+        previous_start,
+        previous_end,
+        current_total_clicks: current_total,
+        previous_total_clicks: previous_total,
+        total_clicks_change_pct: percent_change(current_total, previous_total),
+        previous_gated: gating.gated.then_some(true),
+    })
+}
+
 /// Parse time range from query string
 /// Supports both ?days=N and ?start=UNIX&end=UNIX formats
 pub fn parse_time_range_from_query(query: &str) -> TimeRange {
@@ -94,7 +235,8 @@ pub async fn get_usage(
 ) -> Result<UsageInfo, crate::utils::AppError> {
     use crate::models::Tier;
     use crate::repositories::{
-        AnalyticsRepository, BillingRepository, OrgRepository, TagRepository,
+        AnalyticsRepository, BillingRepository, CustomDomainRepository, OrgRepository,
+        TagRepository,
     };
     use chrono::{Datelike, TimeZone};
 
@@ -131,6 +273,11 @@ pub async fn get_usage(
         .count_distinct_tags_for_billing_account(db, &billing_account.id)
         .await?;
 
+    // Custom domains count against the billing account, like the limit check
+    let custom_domains_count = CustomDomainRepository::new()
+        .count_non_failed_for_billing_account(db, &billing_account.id)
+        .await?;
+
     // Calculate next reset time (first day of next month at midnight UTC)
     let now = chrono::Utc::now();
     let next_reset = chrono::Utc
@@ -144,6 +291,7 @@ pub async fn get_usage(
         limits,
         links_created_this_month,
         tags_count,
+        custom_domains_count,
         next_reset_utc: next_reset.to_rfc3339(),
         next_reset_timestamp,
     })
@@ -156,6 +304,7 @@ pub struct UsageInfo {
     pub limits: crate::models::tier::TierLimits,
     pub links_created_this_month: i64,
     pub tags_count: i64,
+    pub custom_domains_count: u32,
     pub next_reset_utc: String,
     pub next_reset_timestamp: i64,
 }
@@ -163,11 +312,16 @@ pub struct UsageInfo {
 /// Get link-level analytics.
 ///
 /// Returns click analytics for a single link with tier-based gating applied.
+#[allow(clippy::too_many_arguments)]
 pub async fn get_link_analytics(
     db: &worker::d1::D1Database,
     link_id: &str,
     org_id: &str,
     time_range: crate::models::TimeRange,
+    compare_previous: bool,
+    tz_offset_minutes: i64,
+    granularity: Granularity,
+    max_points: Option<u32>,
 ) -> Result<LinkAnalyticsResult, crate::utils::AppError> {
     use crate::models::Tier;
     use crate::repositories::{
@@ -204,8 +358,9 @@ pub async fn get_link_analytics(
     // Apply tier-based gating
     let (mut start, end) = time_range.calculate_timestamps();
     let now = crate::models::analytics::now_timestamp();
-    let gating_result = apply_analytics_gating(tier, start, end, now);
+    let gating_result = apply_analytics_gating(tier.clone(), start, end, now);
     start = gating_result.adjusted_start;
+    check_granularity_range(granularity, start, end)?;
 
     // If gated, return empty data
     if gating_result.gated {
@@ -218,6 +373,9 @@ pub async fn get_link_analytics(
             user_agents: vec![],
             gated: true,
             gated_reason: gating_result.reason,
+            comparison: None,
+            sample_rate: 1,
+            bucket_seconds: None,
         });
     }
 
@@ -226,10 +384,42 @@ pub async fn get_link_analytics(
         .get_link_total_clicks_in_range(db, link_id, org_id, start, end)
         .await?;
 
-    let clicks_over_time = analytics_repo
-        .get_link_clicks_over_time(db, link_id, org_id, start, end)
+    let sample_rate = analytics_repo
+        .get_link_max_sample_rate_in_range(db, link_id, org_id, start, end)
         .await?;
 
+    let bucket_seconds =
+        max_points.and_then(|max| downsample_interval_secs(granularity, start, end, max));
+    let clicks_over_time = match bucket_seconds {
+        Some(interval_secs) => {
+            analytics_repo
+                .get_link_clicks_over_time_downsampled(
+                    db,
+                    link_id,
+                    org_id,
+                    start,
+                    end,
+                    tz_offset_minutes * 60,
+                    granularity,
+                    interval_secs,
+                )
+                .await?
+        }
+        None => {
+            analytics_repo
+                .get_link_clicks_over_time(
+                    db,
+                    link_id,
+                    org_id,
+                    start,
+                    end,
+                    tz_offset_minutes * 60,
+                    granularity,
+                )
+                .await?
+        }
+    };
+
     let referrers = analytics_repo
         .get_link_top_referrers(db, link_id, org_id, start, end, 10)
         .await?;
@@ -242,6 +432,17 @@ pub async fn get_link_analytics(
         .get_link_top_user_agents(db, link_id, org_id, start, end, 20)
         .await?;
 
+    let comparison = if compare_previous {
+        Some(
+            compare_with_previous_window(tier, start, end, now, total_clicks, |s, e| {
+                analytics_repo.get_link_total_clicks_in_range(db, link_id, org_id, s, e)
+            })
+            .await?,
+        )
+    } else {
+        None
+    };
+
     Ok(LinkAnalyticsResult {
         link,
         total_clicks,
@@ -251,6 +452,9 @@ pub async fn get_link_analytics(
         user_agents,
         gated: false,
         gated_reason: None,
+        comparison,
+        sample_rate,
+        bucket_seconds,
     })
 }
 
@@ -265,6 +469,11 @@ pub struct LinkAnalyticsResult {
     pub user_agents: Vec<crate::models::analytics::UserAgentCount>,
     pub gated: bool,
     pub gated_reason: Option<String>,
+    pub comparison: Option<crate::models::analytics::PeriodComparison>,
+    /// Largest raw-event sample rate in range (1 = every click stored)
+    pub sample_rate: i64,
+    /// Bucket length when clicks_over_time was downsampled to `max_points`
+    pub bucket_seconds: Option<i64>,
 }
 
 /// Get organization-level analytics.
@@ -274,6 +483,7 @@ pub async fn get_org_analytics(
     db: &worker::d1::D1Database,
     org_id: &str,
     time_range: crate::models::TimeRange,
+    compare_previous: bool,
 ) -> Result<OrgAnalyticsResult, crate::utils::AppError> {
     use crate::models::Tier;
     use crate::repositories::{AnalyticsRepository, BillingRepository, OrgRepository};
@@ -299,16 +509,20 @@ pub async fn get_org_analytics(
     };
 
     // Apply tier-based gating
-    let (mut start, end) = time_range.calculate_timestamps();
+    let (requested_start, end) = time_range.calculate_timestamps();
     let now = crate::models::analytics::now_timestamp();
-    let gating_result = apply_analytics_gating(tier, start, end, now);
-    start = gating_result.adjusted_start;
+    let gating_result = apply_analytics_gating(tier.clone(), requested_start, end, now);
+    let start = gating_result.adjusted_start;
 
     // Fetch org-level analytics
     let total_clicks = analytics_repo
         .get_org_total_clicks_in_range(db, org_id, start, end)
         .await?;
 
+    let sample_rate = analytics_repo
+        .get_org_max_sample_rate_in_range(db, org_id, start, end)
+        .await?;
+
     let unique_links = analytics_repo
         .get_org_unique_links_clicked(db, org_id, start, end)
         .await?;
@@ -333,6 +547,18 @@ pub async fn get_org_analytics(
         .get_org_top_user_agents(db, org_id, start, end, 20)
         .await?;
 
+    // The previous window mirrors the requested one, not the clamped one
+    let comparison = if compare_previous {
+        Some(
+            compare_with_previous_window(tier, requested_start, end, now, total_clicks, |s, e| {
+                analytics_repo.get_org_total_clicks_in_range(db, org_id, s, e)
+            })
+            .await?,
+        )
+    } else {
+        None
+    };
+
     Ok(OrgAnalyticsResult {
         total_clicks,
         unique_links,
@@ -343,9 +569,201 @@ pub async fn get_org_analytics(
         user_agents,
         gated: gating_result.gated,
         gated_reason: gating_result.reason,
+        comparison,
+        sample_rate,
+    })
+}
+
+/// Get an org's top countries over a window, with tier retention applied.
+pub async fn get_org_top_countries(
+    db: &worker::d1::D1Database,
+    org_id: &str,
+    start: i64,
+    end: i64,
+    limit: i64,
+) -> Result<crate::models::analytics::OrgTopCountriesResponse, crate::utils::AppError> {
+    use crate::repositories::AnalyticsRepository;
+
+    let tier = org_analytics_tier(db, org_id).await?;
+    let now = crate::models::analytics::now_timestamp();
+    let gating_result = apply_analytics_gating(tier, start, end, now);
+    let start = gating_result.adjusted_start;
+
+    let countries = AnalyticsRepository::new()
+        .get_org_top_countries(db, org_id, start, end, limit)
+        .await?;
+
+    Ok(crate::models::analytics::OrgTopCountriesResponse {
+        start,
+        end,
+        countries,
+        analytics_gated: gating_result.gated.then_some(true),
+        gated_reason: gating_result.reason,
+    })
+}
+
+/// Get an org's clicks per recorded value of a request header over a
+/// window, with tier retention applied.
+pub async fn get_org_header_field_breakdown(
+    db: &worker::d1::D1Database,
+    org_id: &str,
+    field: &str,
+    start: i64,
+    end: i64,
+    limit: i64,
+) -> Result<crate::models::analytics::OrgHeaderFieldBreakdownResponse, crate::utils::AppError> {
+    use crate::repositories::AnalyticsRepository;
+
+    let tier = org_analytics_tier(db, org_id).await?;
+    let now = crate::models::analytics::now_timestamp();
+    let gating_result = apply_analytics_gating(tier, start, end, now);
+    let start = gating_result.adjusted_start;
+
+    let values = AnalyticsRepository::new()
+        .get_org_header_field_breakdown(db, org_id, field, start, end, limit)
+        .await?;
+
+    Ok(crate::models::analytics::OrgHeaderFieldBreakdownResponse {
+        field: field.to_string(),
+        start,
+        end,
+        values,
+        analytics_gated: gating_result.gated.then_some(true),
+        gated_reason: gating_result.reason,
+    })
+}
+
+/// Retained analytics of an admin-deleted link, for abuse investigations.
+/// NotFound once the tombstone's retention period has ended.
+pub async fn get_deleted_link_analytics(
+    db: &worker::d1::D1Database,
+    link_id: &str,
+) -> Result<crate::models::deleted_link::DeletedLinkAnalyticsResponse, crate::utils::AppError> {
+    use crate::models::deleted_link::{DELETED_LINK_EVENTS_LIMIT, DeletedLinkAnalyticsResponse};
+    use crate::repositories::DeletedLinkRepository;
+
+    let repo = DeletedLinkRepository::new();
+    let now = crate::models::analytics::now_timestamp();
+    let link = repo.get(db, link_id, now).await?.ok_or_else(|| {
+        crate::utils::AppError::NotFound("No retained analytics for this link".to_string())
+    })?;
+
+    Ok(DeletedLinkAnalyticsResponse {
+        total_clicks: repo.get_total_clicks(db, link_id).await?,
+        top_referrers: repo.get_top_referrers(db, link_id, 10).await?,
+        top_countries: repo.get_top_countries(db, link_id, 10).await?,
+        recent_events: repo
+            .list_recent_events(db, link_id, DELETED_LINK_EVENTS_LIMIT)
+            .await?,
+        link,
     })
 }
 
+/// Tier of an org's billing account, defaulting to Free.
+async fn org_analytics_tier(
+    db: &worker::d1::D1Database,
+    org_id: &str,
+) -> Result<Tier, crate::utils::AppError> {
+    use crate::repositories::{BillingRepository, OrgRepository};
+
+    let org = OrgRepository::new()
+        .get_by_id(db, org_id)
+        .await?
+        .ok_or_else(|| crate::utils::AppError::NotFound("Organization not found".to_string()))?;
+
+    Ok(match org.billing_account_id {
+        Some(ref billing_account_id) => BillingRepository::new()
+            .get_by_id(db, billing_account_id)
+            .await?
+            .map(|ba| Tier::from_str_value(&ba.tier).unwrap_or(Tier::Free))
+            .unwrap_or(Tier::Free),
+        None => Tier::Free,
+    })
+}
+
+/// Page of a link's raw click events, newest first, limited to the org's
+/// tier retention window. Returns `(events, total, gated)`, where `gated`
+/// means the tier's retention window applies.
+pub async fn get_link_events(
+    db: &worker::d1::D1Database,
+    link_id: &str,
+    org_id: &str,
+    page: i64,
+    limit: i64,
+) -> Result<(Vec<crate::models::analytics::LinkEvent>, i64, bool), crate::utils::AppError> {
+    use crate::repositories::{AnalyticsRepository, LinkRepository, OrgRepository};
+
+    LinkRepository::new()
+        .get_by_id(db, link_id, org_id)
+        .await?
+        .ok_or_else(|| crate::utils::AppError::NotFound("Link not found".to_string()))?;
+    let org = OrgRepository::new()
+        .get_by_id(db, org_id)
+        .await?
+        .ok_or_else(|| crate::utils::AppError::NotFound("Organization not found".to_string()))?;
+
+    let tier = crate::services::OrgService::new()
+        .get_org_tier(db, &org)
+        .await;
+    let now = crate::models::analytics::now_timestamp();
+    let gating_result = apply_analytics_gating(tier, 0, now, now);
+
+    let analytics_repo = AnalyticsRepository::new();
+    let events = analytics_repo
+        .list_link_events(
+            db,
+            link_id,
+            gating_result.adjusted_start,
+            limit,
+            (page - 1) * limit,
+        )
+        .await?;
+    let total = analytics_repo
+        .count_link_events(db, link_id, gating_result.adjusted_start)
+        .await?;
+    Ok((events, total, gating_result.gated))
+}
+
+/// Tier-gated window for an org analytics export: the start is raised to
+/// the tier's retention limit. Returns `(start, end, gated)`.
+pub async fn get_org_export_window(
+    db: &worker::d1::D1Database,
+    org: &crate::models::Organization,
+    start: i64,
+    end: i64,
+) -> (i64, i64, bool) {
+    let tier = crate::services::OrgService::new()
+        .get_org_tier(db, org)
+        .await;
+    let now = crate::models::analytics::now_timestamp();
+    let gating_result = apply_analytics_gating(tier, start, end, now);
+    (gating_result.adjusted_start, end, gating_result.gated)
+}
+
+/// Tier-gated window for a single link's click export, with the start raised
+/// to the tier's retention limit. Returns `(link, start, end, gated)`.
+pub async fn get_link_export_window(
+    db: &worker::d1::D1Database,
+    link_id: &str,
+    org_id: &str,
+    time_range: crate::models::TimeRange,
+) -> Result<(crate::models::Link, i64, i64, bool), crate::utils::AppError> {
+    use crate::repositories::{LinkRepository, OrgRepository};
+
+    let link = LinkRepository::new()
+        .get_by_id(db, link_id, org_id)
+        .await?
+        .ok_or_else(|| crate::utils::AppError::NotFound("Link not found".to_string()))?;
+    let org = OrgRepository::new()
+        .get_by_id(db, org_id)
+        .await?
+        .ok_or_else(|| crate::utils::AppError::NotFound("Organization not found".to_string()))?;
+
+    let (start, end) = time_range.calculate_timestamps();
+    let (start, end, gated) = get_org_export_window(db, &org, start, end).await;
+    Ok((link, start, end, gated))
+}
+
 /// Organization analytics result.
 #[derive(Debug)]
 pub struct OrgAnalyticsResult {
@@ -358,6 +776,9 @@ pub struct OrgAnalyticsResult {
     pub user_agents: Vec<crate::models::analytics::UserAgentCount>,
     pub gated: bool,
     pub gated_reason: Option<String>,
+    pub comparison: Option<crate::models::analytics::PeriodComparison>,
+    /// Largest raw-event sample rate in range (1 = every click stored)
+    pub sample_rate: i64,
 }
 
 #[cfg(test)]
@@ -367,6 +788,63 @@ mod tests {
     /// Fixed timestamp for consistent testing
     const TEST_NOW: i64 = 1640995200; // 2022-01-01 00:00:00 UTC
 
+    #[test]
+    fn test_parse_granularity_param() {
+        assert_eq!(parse_granularity_param(None).unwrap(), Granularity::Day);
+        assert_eq!(parse_granularity_param(Some("")).unwrap(), Granularity::Day);
+        assert_eq!(
+            parse_granularity_param(Some("hour")).unwrap(),
+            Granularity::Hour
+        );
+        assert_eq!(
+            parse_granularity_param(Some("week")).unwrap(),
+            Granularity::Week
+        );
+        assert_eq!(
+            parse_granularity_param(Some("month")).unwrap(),
+            Granularity::Month
+        );
+        for invalid in ["Hour", "minute", "1h"] {
+            assert!(
+                parse_granularity_param(Some(invalid)).is_err(),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_max_points_param() {
+        assert_eq!(parse_max_points_param(None).unwrap(), None);
+        assert_eq!(parse_max_points_param(Some("52")).unwrap(), Some(52));
+        for invalid in ["0", "-1", "1001", "many"] {
+            assert!(
+                parse_max_points_param(Some(invalid)).is_err(),
+                "{} should be rejected",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_hourly_granularity_range_cap() {
+        assert!(check_granularity_range(Granularity::Hour, 0, MAX_HOURLY_RANGE_SECS).is_ok());
+        assert!(check_granularity_range(Granularity::Hour, 0, MAX_HOURLY_RANGE_SECS + 1).is_err());
+        assert!(check_granularity_range(Granularity::Day, 0, 10 * MAX_HOURLY_RANGE_SECS).is_ok());
+    }
+
+    #[test]
+    fn test_parse_tz_offset_param() {
+        assert_eq!(parse_tz_offset_param(None).unwrap(), 0);
+        assert_eq!(parse_tz_offset_param(Some("")).unwrap(), 0);
+        assert_eq!(parse_tz_offset_param(Some("540")).unwrap(), 540);
+        assert_eq!(parse_tz_offset_param(Some("-720")).unwrap(), -720);
+        assert_eq!(parse_tz_offset_param(Some("840")).unwrap(), 840);
+        for invalid in ["841", "-721", "5.5", "UTC+9"] {
+            assert!(parse_tz_offset_param(Some(invalid)).is_err(), "{}", invalid);
+        }
+    }
+
     #[test]
     fn test_parse_time_range_days_parameter() {
         let time_range = parse_time_range_from_query_with_now("days=30", TEST_NOW);
@@ -772,4 +1250,76 @@ mod tests {
         assert!(free_result.gated);
         assert!(!pro_result.gated);
     }
+
+    #[test]
+    fn test_previous_window_has_same_length_and_ends_before_start() {
+        let start = TEST_NOW - 7 * 24 * 60 * 60;
+        let (prev_start, prev_end) = previous_window(start, TEST_NOW);
+        assert_eq!(prev_end, start - 1);
+        assert_eq!(prev_end - prev_start, TEST_NOW - start);
+        assert_eq!(prev_start, start - 1 - 7 * 24 * 60 * 60);
+    }
+
+    #[test]
+    fn test_previous_window_never_before_epoch() {
+        assert_eq!(previous_window(100, 1000), (0, 99));
+        assert_eq!(previous_window(0, TEST_NOW), (0, -1));
+    }
+
+    #[test]
+    fn test_percent_change() {
+        assert_eq!(percent_change(150, 120), Some(25.0));
+        assert_eq!(percent_change(60, 120), Some(-50.0));
+        assert_eq!(percent_change(120, 120), Some(0.0));
+        assert_eq!(percent_change(1, 3), Some(-66.7));
+        assert_eq!(percent_change(10, 0), None);
+    }
+
+    #[tokio::test]
+    async fn test_compare_with_previous_window_counts_preceding_range() {
+        let start = TEST_NOW - 7 * 24 * 60 * 60;
+        let comparison =
+            compare_with_previous_window(Tier::Unlimited, start, TEST_NOW, TEST_NOW, 30, |s, e| {
+                assert_eq!((s, e), previous_window(start, TEST_NOW));
+                async { Ok::<i64, worker::Error>(20) }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(comparison.previous_end, start - 1);
+        assert_eq!(comparison.current_total_clicks, 30);
+        assert_eq!(comparison.previous_total_clicks, 20);
+        assert_eq!(comparison.total_clicks_change_pct, Some(50.0));
+        assert_eq!(comparison.previous_gated, None);
+    }
+
+    #[tokio::test]
+    async fn test_compare_with_previous_window_applies_retention() {
+        // Free tier keeps 7 days: the previous 5-day window is partly clamped
+        let start = TEST_NOW - 5 * 24 * 60 * 60;
+        let comparison =
+            compare_with_previous_window(Tier::Free, start, TEST_NOW, TEST_NOW, 4, |s, _| {
+                assert_eq!(s, TEST_NOW - 7 * 24 * 60 * 60);
+                async { Ok::<i64, worker::Error>(4) }
+            })
+            .await
+            .unwrap();
+        assert_eq!(comparison.previous_start, TEST_NOW - 7 * 24 * 60 * 60);
+        assert_eq!(comparison.previous_gated, Some(true));
+        assert_eq!(comparison.total_clicks_change_pct, Some(0.0));
+
+        // A previous window entirely outside retention is not queried
+        let start = TEST_NOW - 7 * 24 * 60 * 60;
+        let comparison =
+            compare_with_previous_window(Tier::Free, start, TEST_NOW, TEST_NOW, 4, |_, _| async {
+                Err(worker::Error::RustError(
+                    "previous window should not be queried".to_string(),
+                ))
+            })
+            .await
+            .unwrap();
+        assert_eq!(comparison.previous_total_clicks, 0);
+        assert_eq!(comparison.total_clicks_change_pct, None);
+        assert_eq!(comparison.previous_gated, Some(true));
+    }
 }
@@ -5,7 +5,8 @@
 /// - Token generation and hashing
 /// - Ownership-scoped revocation
 /// - Org-scope management (set/update which orgs a key can act on behalf of)
-use crate::models::Tier;
+use crate::models::api_key::{parse_scopes, scopes_to_column};
+use crate::models::{ApiKeyScope, Tier};
 use crate::repositories::api_key_repository::ApiKeyRecord;
 use crate::repositories::{ApiKeyRepository, BillingRepository, OrgRepository};
 use crate::utils::{generate_short_code_with_length, now_timestamp};
@@ -26,9 +27,11 @@ impl ApiKeyService {
     /// Performs the tier check, generates the token + hash, and persists the record.
     /// `org_ids` is the list of orgs this key is allowed to act on behalf of.
     /// When empty, defaults to all orgs the user currently belongs to.
+    /// `scopes` limits what the key may do; when empty the key gets `admin`.
     ///
-    /// Returns `(key_id, raw_token, hint, created_at, expires_at, org_ids)`.
+    /// Returns `(key_id, raw_token, hint, created_at, expires_at, org_ids, scopes)`.
     /// The raw token is shown exactly once — callers must surface it to the user immediately.
+    #[allow(clippy::too_many_arguments)]
     pub async fn create(
         &self,
         db: &D1Database,
@@ -37,7 +40,16 @@ impl ApiKeyService {
         name: &str,
         expires_in_days: Option<i64>,
         requested_org_ids: Vec<String>,
-    ) -> Result<(String, String, String, i64, Option<i64>, Vec<String>)> {
+        requested_scopes: Vec<String>,
+    ) -> Result<(
+        String,
+        String,
+        String,
+        i64,
+        Option<i64>,
+        Vec<String>,
+        Vec<ApiKeyScope>,
+    )> {
         // --- Tier gate (check against the current session org) ---
         let billing_repo = BillingRepository::new();
         let tier = match billing_repo.get_billing_account_for_org(db, org_id).await? {
@@ -51,6 +63,8 @@ impl ApiKeyService {
             ));
         }
 
+        let scopes = parse_scopes(&requested_scopes).map_err(worker::Error::RustError)?;
+
         // --- Resolve org scope ---
         // If the caller supplied a list, validate all are orgs the user actually belongs to.
         // If the caller supplied nothing, default to all of the user's orgs.
@@ -93,7 +107,16 @@ impl ApiKeyService {
 
         // --- Persist key ---
         repo.create_for_user(
-            db, &key_id, user_id, org_id, name, &key_hash, &hint, now, expires_at,
+            db,
+            &key_id,
+            user_id,
+            org_id,
+            name,
+            &key_hash,
+            &hint,
+            now,
+            expires_at,
+            &scopes_to_column(&scopes),
         )
         .await?;
 
@@ -101,7 +124,15 @@ impl ApiKeyService {
         let org_id_refs: Vec<&str> = final_org_ids.iter().map(|s| s.as_str()).collect();
         repo.set_key_orgs(db, &key_id, &org_id_refs).await?;
 
-        Ok((key_id, raw_token, hint, now, expires_at, final_org_ids))
+        Ok((
+            key_id,
+            raw_token,
+            hint,
+            now,
+            expires_at,
+            final_org_ids,
+            scopes,
+        ))
     }
 
     /// List active API keys for the given user (includes org_ids per key).
@@ -3,12 +3,16 @@
 /// Handles checking URLs against the blacklist and cascading blocks to
 /// all existing links that match a newly-added blacklist entry.
 /// Orchestrates BlacklistRepository, LinkRepository, and ReportRepository.
+use crate::models::Link;
 use crate::repositories::{BlacklistRepository, LinkRepository, ReportRepository};
 use crate::utils::AppError;
 use worker::console_log;
 use worker::d1::D1Database;
 use worker::kv::KvStore;
 
+/// Most links a blacklist preview reads from D1
+pub const MAX_BLACKLIST_PREVIEW_CANDIDATES: i64 = 5_000;
+
 /// Service for blacklist-related business logic
 #[derive(Default)]
 pub struct BlacklistService;
@@ -18,6 +22,85 @@ impl BlacklistService {
         Self
     }
 
+    /// Normalize a destination into the form stored for `match_type`: the
+    /// normalized URL for `exact`, the host for `domain`.
+    pub fn normalize_entry(destination: &str, match_type: &str) -> String {
+        if match_type == "exact" {
+            match crate::utils::normalize_url_for_blacklist(destination) {
+                Ok(url) => url,
+                Err(e) => {
+                    console_log!(
+                        "{}",
+                        serde_json::json!({
+                            "event": "url_normalize_failed",
+                            "url": destination,
+                            "error": e.to_string(),
+                            "level": "warn"
+                        })
+                    );
+                    destination.to_string()
+                }
+            }
+        } else {
+            match url::Url::parse(destination) {
+                Ok(url) => url.host_str().unwrap_or(destination).to_string(),
+                Err(_) => {
+                    console_log!(
+                        "{}",
+                        serde_json::json!({
+                            "event": "domain_extract_failed",
+                            "url": destination,
+                            "level": "warn"
+                        })
+                    );
+                    let without_protocol = destination
+                        .trim_start_matches("http://")
+                        .trim_start_matches("https://");
+                    without_protocol
+                        .split('/')
+                        .next()
+                        .unwrap_or(without_protocol)
+                        .to_string()
+                }
+            }
+        }
+    }
+
+    /// Active links a new `match_type` entry for `entry` (already normalized)
+    /// would block, without changing anything. Only links whose destination
+    /// contains the entry's host are checked, at most
+    /// `MAX_BLACKLIST_PREVIEW_CANDIDATES` of them. Returns up to `limit` links,
+    /// the number matched, and whether the candidate cap was hit (the count
+    /// is then a lower bound).
+    pub async fn preview_matching_links(
+        &self,
+        db: &D1Database,
+        match_type: &str,
+        entry: &str,
+        limit: usize,
+    ) -> Result<(Vec<Link>, usize, bool), AppError> {
+        // Every match contains the entry's host, so it narrows the scan in SQL
+        let needle = match match_type {
+            "exact" => url::Url::parse(entry)
+                .ok()
+                .and_then(|url| url.host_str().map(|h| h.to_string()))
+                .unwrap_or_else(|| entry.to_string()),
+            _ => entry.to_string(),
+        };
+        let candidates = BlacklistRepository::new()
+            .list_preview_candidates(db, &needle, MAX_BLACKLIST_PREVIEW_CANDIDATES)
+            .await?;
+        let truncated = candidates.len() as i64 >= MAX_BLACKLIST_PREVIEW_CANDIDATES;
+        let matching: Vec<Link> = candidates
+            .into_iter()
+            .filter(|link| {
+                BlacklistRepository::entry_matches(entry, match_type, &link.destination_url)
+            })
+            .collect();
+        let total = matching.len();
+        Ok((matching.into_iter().take(limit).collect(), total, truncated))
+    }
+
     /// Block all existing active/disabled links whose destination matches the blacklist,
     /// remove them from KV, and auto-resolve any open reports for those links.
     ///
@@ -46,7 +129,7 @@ impl BlacklistService {
             }
 
             link_repo
-                .update_status_by_id(db, &link.id, "blocked")
+                .update_status_by_id(db, &link.id, "blocked", None)
                 .await?;
             blocked_count += 1;
 
@@ -71,7 +154,9 @@ impl BlacklistService {
                 );
             }
 
-            match crate::kv::delete_link_mapping(kv, &link.org_id, &link.short_code).await {
+            match crate::kv::store_blocked_tombstone(kv, &link.org_id, &link.short_code, &link.id)
+                .await
+            {
                 Ok(()) => {}
                 Err(e) => {
                     console_log!(
@@ -0,0 +1,181 @@
+/// Config bundle service - Instance configuration export and import
+///
+/// Bundles the portable system settings and the destination blacklist so an
+/// operator can restore them or move them to another instance.
+/// Orchestrates SettingsRepository and BlacklistRepository.
+use crate::models::config_bundle::{
+    BlacklistBundleEntry, CONFIG_BUNDLE_VERSION, ConfigBundle, ConfigImportMode,
+    is_portable_setting,
+};
+use crate::repositories::{BlacklistRepository, SettingsRepository};
+use crate::services::{BlacklistService, SettingsService};
+use crate::utils::{AppError, now_timestamp};
+use std::collections::HashSet;
+use worker::d1::D1Database;
+use worker::kv::KvStore;
+
+/// What an import changed
+#[derive(Debug, Default, serde::Serialize)]
+pub struct ConfigImportSummary {
+    pub settings_applied: usize,
+    pub blacklist_added: usize,
+    pub blacklist_removed: usize,
+    /// Existing links blocked because they match an imported entry
+    pub links_blocked: i64,
+}
+
+/// Service for config bundle export/import
+#[derive(Default)]
+pub struct ConfigBundleService;
+
+impl ConfigBundleService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Current portable settings and blacklist as a bundle
+    pub async fn export(&self, db: &D1Database) -> Result<ConfigBundle, AppError> {
+        let settings = SettingsRepository::new().get_all_settings(db).await?;
+        let blacklist = BlacklistRepository::new()
+            .list_all(db)
+            .await?
+            .into_iter()
+            .map(|entry| BlacklistBundleEntry {
+                destination: entry.destination,
+                match_type: entry.match_type,
+                reason: entry.reason,
+            })
+            .collect();
+        Ok(ConfigBundle::from_parts(
+            settings,
+            blacklist,
+            now_timestamp(),
+        ))
+    }
+
+    /// Validate every entry of `bundle`, then apply it in a single D1 batch so
+    /// a bad entry leaves the configuration untouched. Newly blacklisted
+    /// destinations are cascaded to existing links like a manual block.
+    pub async fn import(
+        &self,
+        db: &D1Database,
+        kv: &KvStore,
+        bundle: &ConfigBundle,
+        mode: ConfigImportMode,
+        acted_by: &str,
+    ) -> Result<ConfigImportSummary, AppError> {
+        if bundle.version != CONFIG_BUNDLE_VERSION {
+            return Err(AppError::BadRequest(format!(
+                "Unsupported bundle version {}. Expected {}",
+                bundle.version, CONFIG_BUNDLE_VERSION
+            )));
+        }
+
+        let settings_service = SettingsService::new();
+        for (key, value) in &bundle.settings {
+            if !is_portable_setting(key) {
+                return Err(AppError::BadRequest(format!(
+                    "Setting '{}' is instance-specific and cannot be imported",
+                    key
+                )));
+            }
+            settings_service.validate_setting(db, key, value).await?;
+        }
+
+        let mut wanted: Vec<BlacklistBundleEntry> = Vec::new();
+        for (i, entry) in bundle.blacklist.iter().enumerate() {
+            if entry.match_type != "exact" && entry.match_type != "domain" {
+                return Err(AppError::BadRequest(format!(
+                    "blacklist[{}]: Invalid match_type. Must be 'exact' or 'domain'",
+                    i
+                )));
+            }
+            if entry.destination.trim().is_empty() {
+                return Err(AppError::BadRequest(format!(
+                    "blacklist[{}]: Missing destination",
+                    i
+                )));
+            }
+            let destination =
+                BlacklistService::normalize_entry(entry.destination.trim(), &entry.match_type);
+            if !wanted
+                .iter()
+                .any(|w| w.destination == destination && w.match_type == entry.match_type)
+            {
+                wanted.push(BlacklistBundleEntry {
+                    destination,
+                    match_type: entry.match_type.clone(),
+                    reason: entry.reason.clone(),
+                });
+            }
+        }
+
+        let settings_repo = SettingsRepository::new();
+        let blacklist_repo = BlacklistRepository::new();
+        let existing = blacklist_repo.list_all(db).await?;
+        let existing_keys: HashSet<(&str, &str)> = existing
+            .iter()
+            .map(|e| (e.destination.as_str(), e.match_type.as_str()))
+            .collect();
+        let wanted_keys: HashSet<(&str, &str)> = wanted
+            .iter()
+            .map(|e| (e.destination.as_str(), e.match_type.as_str()))
+            .collect();
+
+        let mut summary = ConfigImportSummary::default();
+        let mut statements = Vec::new();
+        for (key, value) in &bundle.settings {
+            statements.push(settings_repo.set_setting_statement(db, key, value)?);
+            summary.settings_applied += 1;
+        }
+        for entry in &wanted {
+            if !existing_keys.contains(&(entry.destination.as_str(), entry.match_type.as_str())) {
+                statements.push(blacklist_repo.add_statement(
+                    db,
+                    &entry.destination,
+                    &entry.match_type,
+                    &entry.reason,
+                    acted_by,
+                )?);
+                summary.blacklist_added += 1;
+            }
+        }
+        if mode == ConfigImportMode::Replace {
+            for entry in &existing {
+                if !wanted_keys.contains(&(entry.destination.as_str(), entry.match_type.as_str())) {
+                    statements.push(blacklist_repo.remove_statement(db, &entry.id)?);
+                    summary.blacklist_removed += 1;
+                }
+            }
+        }
+
+        if !statements.is_empty() {
+            db.batch(statements).await?;
+        }
+
+        if bundle
+            .settings
+            .keys()
+            .any(|k| k.starts_with("maintenance_"))
+        {
+            let settings = settings_repo.get_all_settings(db).await?;
+            settings_service
+                .sync_maintenance_cache(kv, &settings)
+                .await?;
+        }
+
+        if summary.blacklist_added > 0 {
+            summary.links_blocked = BlacklistService::new()
+                .block_matching_links(
+                    db,
+                    kv,
+                    acted_by,
+                    "config import",
+                    &format!("{} entries", summary.blacklist_added),
+                )
+                .await?;
+        }
+
+        Ok(summary)
+    }
+}
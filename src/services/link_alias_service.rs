@@ -0,0 +1,187 @@
+/// Link alias service - Business logic for extra short codes on a link
+///
+/// Aliases live in D1 and are written to KV as pointers to the link's
+/// primary short code, so the redirect handler always follows the link's
+/// current mapping (status, destination, expiry) without re-syncing aliases.
+use crate::models::LinkAlias;
+use crate::models::link_alias::{AliasPointer, MAX_ALIASES_PER_LINK};
+use crate::repositories::{CodeWordRepository, LinkAliasRepository, LinkRepository, OrgRepository};
+use crate::services::{OrgService, SettingsService};
+use crate::utils::{AppError, now_timestamp, validate_custom_short_code};
+use worker::d1::D1Database;
+use worker::kv::KvStore;
+
+/// Service for link alias operations
+#[derive(Default)]
+pub struct LinkAliasService {
+    repository: LinkAliasRepository,
+}
+
+impl LinkAliasService {
+    pub fn new() -> Self {
+        Self {
+            repository: LinkAliasRepository::new(),
+        }
+    }
+
+    /// List the aliases of a link in the org.
+    pub async fn list_aliases(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        link_id: &str,
+    ) -> Result<Vec<LinkAlias>, AppError> {
+        Self::require_link(db, org_id, link_id).await?;
+        Ok(self.repository.list_for_link(db, link_id, org_id).await?)
+    }
+
+    /// Add an alias code to a link. Aliases are custom codes, so they follow
+    /// the same rules: a tier that allows custom codes, the instance switch,
+    /// code validation (including reserved words) and the minimum length.
+    pub async fn create_alias(
+        &self,
+        db: &D1Database,
+        kv: &KvStore,
+        org_id: &str,
+        user_id: &str,
+        link_id: &str,
+        alias_code: &str,
+    ) -> Result<LinkAlias, AppError> {
+        let link = Self::require_link(db, org_id, link_id).await?;
+
+        let settings = SettingsService::new();
+        if !settings.are_custom_short_codes_allowed(db).await? {
+            return Err(AppError::Forbidden(
+                "Custom short codes are disabled on this instance".to_string(),
+            ));
+        }
+
+        let org = OrgRepository::new()
+            .get_by_id(db, org_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Organization not found".to_string()))?;
+        let tier = OrgService::new().get_org_tier(db, &org).await;
+        if !tier.limits().allow_custom_short_code {
+            return Err(AppError::TierLimitReached(
+                "Alias codes are not available on the free tier. Upgrade to Pro.".to_string(),
+            ));
+        }
+
+        let disallowed = CodeWordRepository::new().get_disallowed_words(db).await?;
+        validate_custom_short_code(alias_code, &disallowed)
+            .map_err(|e| AppError::BadRequest(format!("Invalid alias code: {}", e)))?;
+        // Single segment only, so the code can be addressed in the DELETE path
+        if alias_code.contains('/') {
+            return Err(AppError::BadRequest(
+                "Invalid alias code: alias codes cannot contain forward slashes".to_string(),
+            ));
+        }
+        let lengths = settings.get_code_length_settings(db).await?;
+        if alias_code.len() < lengths.effective_custom_min {
+            return Err(AppError::BadRequest(format!(
+                "Alias code must be at least {} characters",
+                lengths.effective_custom_min
+            )));
+        }
+
+        if self.repository.count_for_link(db, link_id).await? >= MAX_ALIASES_PER_LINK {
+            return Err(AppError::BadRequest(format!(
+                "A link can have at most {} alias codes",
+                MAX_ALIASES_PER_LINK
+            )));
+        }
+
+        if crate::kv::links::short_code_exists(kv, alias_code).await?
+            || self.repository.code_taken(db, alias_code).await?
+        {
+            return Err(AppError::Conflict("Short code already in use".to_string()));
+        }
+
+        let alias = LinkAlias {
+            alias_code: alias_code.to_string(),
+            link_id: link.id.clone(),
+            org_id: org_id.to_string(),
+            created_by: user_id.to_string(),
+            created_at: now_timestamp(),
+        };
+        self.repository.create(db, &alias).await?;
+
+        crate::kv::links::store_alias_pointer(
+            kv,
+            alias_code,
+            &AliasPointer {
+                alias_of: link.short_code,
+                link_id: link.id,
+            },
+        )
+        .await?;
+
+        Ok(alias)
+    }
+
+    /// Remove an alias from a link and free its code.
+    pub async fn delete_alias(
+        &self,
+        db: &D1Database,
+        kv: &KvStore,
+        org_id: &str,
+        link_id: &str,
+        alias_code: &str,
+    ) -> Result<(), AppError> {
+        Self::require_link(db, org_id, link_id).await?;
+        if !self
+            .repository
+            .delete(db, alias_code, link_id, org_id)
+            .await?
+        {
+            return Err(AppError::NotFound("Alias not found".to_string()));
+        }
+        kv.delete(alias_code)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to delete alias mapping: {}", e)))?;
+        Ok(())
+    }
+
+    /// Delete the KV pointers of a link's aliases. Call before the link's
+    /// rows are deleted; the D1 rows go with the link.
+    pub async fn delete_alias_pointers_for_link(
+        &self,
+        db: &D1Database,
+        kv: &KvStore,
+        link_id: &str,
+        org_id: &str,
+    ) -> Result<(), AppError> {
+        for alias in self.repository.list_for_link(db, link_id, org_id).await? {
+            kv.delete(&alias.alias_code).await.map_err(|e| {
+                AppError::Internal(format!("Failed to delete alias mapping: {}", e))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Delete the KV pointers of every alias in an org.
+    pub async fn delete_alias_pointers_for_org(
+        &self,
+        db: &D1Database,
+        kv: &KvStore,
+        org_id: &str,
+    ) -> Result<(), AppError> {
+        for alias_code in self.repository.list_codes_for_org(db, org_id).await? {
+            kv.delete(&alias_code).await.map_err(|e| {
+                AppError::Internal(format!("Failed to delete alias mapping: {}", e))
+            })?;
+        }
+        Ok(())
+    }
+
+    async fn require_link(
+        db: &D1Database,
+        org_id: &str,
+        link_id: &str,
+    ) -> Result<crate::models::Link, AppError> {
+        LinkRepository::new()
+            .get_by_id(db, link_id, org_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Link not found".to_string()))
+    }
+}
@@ -3,13 +3,20 @@ use crate::models::Tier;
 ///
 /// Handles quota enforcement, blacklist checks, and tag limit validation.
 /// Orchestrates BillingRepository, BlacklistRepository, and TagRepository.
-use crate::models::link::{Link, LinkStatus, UtmParams};
+use crate::models::link::{
+    ANONYMOUS_ORG_ID, BulkDeleteLinkError, BulkDeleteLinksResponse, Link, LinkStatus, UtmParams,
+};
 use crate::repositories::{
-    BillingRepository, BlacklistRepository, LinkRepository, SettingsRepository, TagRepository,
+    BillingRepository, BlacklistRepository, LinkRepository, OrgRepository, SettingsRepository,
+    TagRepository,
+};
+use crate::utils::short_code::{
+    CodeGenerationAttempts, CodeGenerationPolicy, CollisionAction, generate_short_code_with_charset,
 };
-use crate::utils::AppError;
-use crate::utils::short_code::{DEFAULT_COLLISION_THRESHOLD, generate_short_code_with_charset};
+use crate::utils::{AppError, MonthlyLimitDetails, validate_url_with_schemes};
 use chrono::Datelike;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use worker::d1::D1Database;
 use worker::kv::KvStore;
 
@@ -31,6 +38,73 @@ impl QuotaContext {
             Some(Tier::Pro) | Some(Tier::Business) | Some(Tier::Unlimited)
         )
     }
+
+    /// Redirect type for links created without one. The org default was
+    /// tier-checked when saved, so after a downgrade below Pro it falls back
+    /// to 301.
+    pub fn default_redirect_type(&self, org_default: String) -> String {
+        if self.is_pro_or_above() {
+            org_default
+        } else {
+            crate::models::link::DEFAULT_REDIRECT_TYPE.to_string()
+        }
+    }
+}
+
+/// Result of an admin KV consistency audit
+#[derive(Debug, serde::Serialize)]
+pub struct KvAuditReport {
+    /// Number of links compared against KV
+    pub checked: usize,
+    /// Links whose KV entry is missing or disagrees with D1 (as found, before any repair)
+    pub mismatches: Vec<crate::repositories::link_repository::AdminLink>,
+    /// Number of mismatches re-synced from D1
+    pub repaired: usize,
+}
+
+/// Result of an admin scan for KV entries with no link in D1
+#[derive(Debug, serde::Serialize)]
+pub struct KvOrphanReport {
+    /// Number of link keys read from KV on this page
+    pub checked: usize,
+    /// Keys that no D1 link or alias owns (as found, before any repair)
+    pub orphans: Vec<String>,
+    /// Number of orphaned keys deleted
+    pub repaired: usize,
+    /// Pass back as `cursor` to scan the next page; None once the scan is done
+    pub cursor: Option<String>,
+}
+
+/// Prefix of claim tokens returned for anonymously created links
+pub const CLAIM_TOKEN_PREFIX: &str = "rcl_";
+
+/// Hex SHA-256 of a claim token, as stored in `links.claim_token_hash`
+fn hash_claim_token(token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(token.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Unix timestamp of 00:00 UTC on the first day of the month of `now`
+pub fn month_start(now: chrono::DateTime<chrono::Utc>) -> i64 {
+    chrono::NaiveDate::from_ymd_opt(now.year(), now.month(), 1)
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc().timestamp())
+        .unwrap_or_default()
+}
+
+/// Unix timestamp of 00:00 UTC on the first day of the month after `now`,
+/// when monthly link counters start over.
+pub fn next_month_start(now: chrono::DateTime<chrono::Utc>) -> i64 {
+    let (year, month) = if now.month() == 12 {
+        (now.year() + 1, 1)
+    } else {
+        (now.year(), now.month() + 1)
+    };
+    chrono::NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc().timestamp())
+        .unwrap_or_default()
 }
 
 /// Service for link-related business logic
@@ -45,18 +119,53 @@ impl LinkService {
     /// Load billing account for org, increment the monthly counter if a limit applies,
     /// and return a QuotaContext for downstream checks.
     ///
-    /// Returns Err(AppError::Forbidden) if the monthly limit has been reached.
+    /// An org-level `org_link_quota`, when set, is checked first against the
+    /// links the org created this month, so a capped org does not use up the
+    /// billing account's counter.
+    ///
+    /// Returns Err(AppError::MonthlyLimitReached) if either monthly limit has been reached.
     /// Returns Err(AppError::InternalError) if there is no billing account.
     pub async fn check_quota(
         &self,
         db: &D1Database,
         org_id: &str,
+    ) -> Result<QuotaContext, AppError> {
+        self.reserve_quota(db, org_id, 1).await
+    }
+
+    /// Batch form of `check_quota`: reserve `count` links at once. Either the
+    /// whole batch fits within both monthly limits and is counted, or nothing
+    /// is and MonthlyLimitReached is returned. Reservations for links that end
+    /// up not being created are returned with `release_quota`.
+    pub async fn reserve_quota(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        count: i64,
     ) -> Result<QuotaContext, AppError> {
         let billing_repo = BillingRepository::new();
         let billing_account = billing_repo.get_for_org(db, org_id).await?.ok_or_else(|| {
             AppError::Internal("No billing account found for organization".to_string())
         })?;
 
+        if let Some(org_quota) = OrgRepository::new().get_org_link_quota(db, org_id).await? {
+            let now = chrono::Utc::now();
+            let used = LinkRepository::new()
+                .count_created_since(db, org_id, month_start(now))
+                .await?;
+            if used + count > org_quota {
+                return Err(AppError::MonthlyLimitReached(MonthlyLimitDetails {
+                    message:
+                        "This organization has reached the monthly link quota set by its owner."
+                            .to_string(),
+                    limit: org_quota,
+                    used,
+                    resets_at: next_month_start(now),
+                    scope: "organization",
+                }));
+            }
+        }
+
         let tier = Tier::from_str_value(&billing_account.tier);
         let limits = tier.as_ref().map(|t| t.limits());
 
@@ -67,7 +176,7 @@ impl LinkService {
             let year_month = format!("{}-{:02}", now.year(), now.month());
 
             let can_create = billing_repo
-                .increment_monthly_counter(db, &billing_account.id, &year_month, max_links)
+                .reserve_monthly_links(db, &billing_account.id, &year_month, count, max_links)
                 .await?;
 
             if !can_create {
@@ -83,7 +192,13 @@ impl LinkService {
                 } else {
                     "You have reached your monthly link limit across all organizations. Upgrade your plan to create more links.".to_string()
                 };
-                return Err(AppError::Forbidden(message));
+                return Err(AppError::MonthlyLimitReached(MonthlyLimitDetails {
+                    message,
+                    limit: max_links,
+                    used: current_count,
+                    resets_at: next_month_start(now),
+                    scope: "billing_account",
+                }));
             }
         }
 
@@ -93,6 +208,26 @@ impl LinkService {
         })
     }
 
+    /// Return `count` links reserved with `reserve_quota` that were not created.
+    pub async fn release_quota(
+        &self,
+        db: &D1Database,
+        quota_ctx: &QuotaContext,
+        count: i64,
+    ) -> Result<(), AppError> {
+        let has_monthly_limit = quota_ctx
+            .tier_limits()
+            .is_some_and(|l| l.max_links_per_month.is_some());
+        if count > 0 && has_monthly_limit {
+            let now = chrono::Utc::now();
+            let year_month = format!("{}-{:02}", now.year(), now.month());
+            BillingRepository::new()
+                .release_monthly_links(db, &quota_ctx.billing_account_id, &year_month, count)
+                .await?;
+        }
+        Ok(())
+    }
+
     /// Check whether a destination URL is blacklisted.
     ///
     /// Returns Err(AppError::Forbidden) if blocked.
@@ -106,6 +241,27 @@ impl LinkService {
         Ok(())
     }
 
+    /// Validate a secondary destination (`field` is ios_url, android_url,
+    /// desktop_url or fallback_url) the same way as destination_url: allowed
+    /// scheme and not blacklisted. Returns the normalized URL; None passes through.
+    pub async fn validate_optional_url(
+        &self,
+        db: &D1Database,
+        field: &str,
+        url: Option<&str>,
+        allowed_schemes: &[String],
+    ) -> Result<Option<String>, AppError> {
+        let Some(url) = url else {
+            return Ok(None);
+        };
+        let url = validate_url_with_schemes(url, allowed_schemes)
+            .map_err(|e| AppError::BadRequest(format!("Invalid {}: {}", field, e)))?;
+        if BlacklistRepository::new().is_blacklisted(db, &url).await? {
+            return Err(AppError::Forbidden(format!("{} is blocked", field)));
+        }
+        Ok(Some(url))
+    }
+
     /// Check whether adding the given new tags would exceed the billing account's tag limit.
     ///
     /// Returns Err(AppError::Forbidden) with a user-facing message if the limit would be exceeded.
@@ -162,6 +318,27 @@ impl LinkService {
         Ok(())
     }
 
+    /// Rewrite the KV mappings (default and custom-domain keys) of the given
+    /// active links from their D1 state. Used when an org-level feature needs
+    /// fields that older mappings lack (e.g. `org_id`).
+    pub async fn resync_kv_mappings(
+        &self,
+        db: &D1Database,
+        kv: &KvStore,
+        org_id: &str,
+        links: &[Link],
+    ) -> Result<(), AppError> {
+        let repo = LinkRepository::new();
+        for link in links {
+            let resolved_forward = repo.resolved_forward_for_link(db, link).await;
+            let mapping = link.to_mapping(resolved_forward);
+            crate::kv::store_link_mapping(kv, org_id, &link.short_code, &mapping).await?;
+            crate::kv::sync_custom_domain_kv(kv, db, org_id, &link.short_code, Some(&mapping))
+                .await?;
+        }
+        Ok(())
+    }
+
     // ─── CRUD Operations ────────────────────────────────────────────────────
 
     /// Get a single link by ID with its tags.
@@ -192,6 +369,32 @@ impl LinkService {
             .map_err(AppError::from)
     }
 
+    /// Get the org's links for several short codes, with tags attached.
+    /// Returns the links found and the requested codes that matched nothing.
+    pub async fn get_links_by_codes(
+        &self,
+        db: &D1Database,
+        short_codes: &[String],
+        org_id: &str,
+    ) -> Result<(Vec<Link>, Vec<String>), AppError> {
+        let repo = LinkRepository::new();
+        let mut links = repo.get_by_short_codes(db, short_codes, org_id).await?;
+
+        let link_ids: Vec<String> = links.iter().map(|l| l.id.clone()).collect();
+        let tags_map = repo.get_tags_for_links(db, &link_ids).await?;
+        for link in &mut links {
+            link.tags = tags_map.get(&link.id).cloned().unwrap_or_default();
+        }
+
+        let not_found = short_codes
+            .iter()
+            .filter(|code| !links.iter().any(|l| &l.short_code == *code))
+            .cloned()
+            .collect();
+
+        Ok((links, not_found))
+    }
+
     /// List links with filtering, sorting, and pagination.
     #[allow(clippy::too_many_arguments)]
     pub async fn list_links(
@@ -204,11 +407,21 @@ impl LinkService {
         limit: i64,
         offset: i64,
         tags_filter: Option<&[String]>,
+        created_after: Option<i64>,
+        created_before: Option<i64>,
     ) -> Result<(Vec<Link>, i64, serde_json::Value), AppError> {
         let repo = LinkRepository::new();
 
         let total = repo
-            .count_filtered(db, org_id, search, status_filter, tags_filter)
+            .count_filtered(
+                db,
+                org_id,
+                search,
+                status_filter,
+                tags_filter,
+                created_after,
+                created_before,
+            )
             .await?;
 
         let mut links = repo
@@ -221,6 +434,8 @@ impl LinkService {
                 limit,
                 offset,
                 tags_filter,
+                created_after,
+                created_before,
             )
             .await?;
 
@@ -238,6 +453,32 @@ impl LinkService {
         Ok((links, total, stats_json))
     }
 
+    /// Count links matching the same filters as `list_links`, without
+    /// fetching rows, tags, or dashboard stats.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn count_links(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        search: Option<&str>,
+        status_filter: Option<&str>,
+        tags_filter: Option<&[String]>,
+        created_after: Option<i64>,
+        created_before: Option<i64>,
+    ) -> Result<i64, AppError> {
+        Ok(LinkRepository::new()
+            .count_filtered(
+                db,
+                org_id,
+                search,
+                status_filter,
+                tags_filter,
+                created_after,
+                created_before,
+            )
+            .await?)
+    }
+
     /// Update a link with new values.
     #[allow(clippy::too_many_arguments)]
     pub async fn update_link(
@@ -257,6 +498,8 @@ impl LinkService {
         ios_url: Option<Option<String>>,
         android_url: Option<Option<String>>,
         desktop_url: Option<Option<String>>,
+        response_headers: Option<Option<BTreeMap<String, String>>>,
+        fallback_url: Option<Option<String>>,
     ) -> Result<Link, AppError> {
         let repo = LinkRepository::new();
 
@@ -275,6 +518,13 @@ impl LinkService {
         let ios_ref: Option<Option<&str>> = ios_url.as_ref().map(|o| o.as_deref());
         let android_ref: Option<Option<&str>> = android_url.as_ref().map(|o| o.as_deref());
         let desktop_ref: Option<Option<&str>> = desktop_url.as_ref().map(|o| o.as_deref());
+        let fallback_ref: Option<Option<&str>> = fallback_url.as_ref().map(|o| o.as_deref());
+
+        // Convert response headers to JSON string if provided
+        let headers_string: Option<Option<String>> = response_headers
+            .as_ref()
+            .map(|h| h.as_ref().and_then(|m| serde_json::to_string(m).ok()));
+        let headers_ref: Option<Option<&str>> = headers_string.as_ref().map(|o| o.as_deref());
 
         // Update the link (single call handles all fields)
         let updated = repo
@@ -292,18 +542,22 @@ impl LinkService {
                 ios_ref,
                 android_ref,
                 desktop_ref,
+                headers_ref,
+                fallback_ref,
             )
             .await?;
 
         // Determine if KV sync is needed
-        // Sync if: status changed, destination_url changed, device URLs changed, redirect_type changed, or expires_at changed
+        // Sync if: status changed, destination_url changed, device URLs changed, redirect_type changed, expires_at changed, response headers changed, or fallback_url changed
         let needs_kv_sync = status.is_some()
             || destination_url.is_some()
             || ios_url.is_some()
             || android_url.is_some()
             || desktop_url.is_some()
             || redirect_type.is_some()
-            || expires_at.is_some();
+            || expires_at.is_some()
+            || response_headers.is_some()
+            || fallback_url.is_some();
 
         if needs_kv_sync {
             // Only sync to KV if the link is active
@@ -330,6 +584,22 @@ impl LinkService {
                     Some(&mapping),
                 )
                 .await?;
+            } else if updated.status == LinkStatus::Disabled
+                && updated.disabled_reason.is_none()
+                && updated.fallback_url.is_some()
+            {
+                // Keep the disabled mapping so redirects can send visitors to the
+                // fallback; links disabled by a suspension or an admin stay 404
+                let mapping = updated.to_mapping(updated.forward_query_params.unwrap_or(false));
+                crate::kv::store_link_mapping(kv, org_id, &updated.short_code, &mapping).await?;
+                crate::kv::sync_custom_domain_kv(
+                    kv,
+                    db,
+                    org_id,
+                    &updated.short_code,
+                    Some(&mapping),
+                )
+                .await?;
             } else {
                 // Link is not active, ensure it's removed from KV
                 crate::kv::delete_link_mapping(kv, org_id, &updated.short_code).await?;
@@ -366,6 +636,10 @@ impl LinkService {
             .await?
             .ok_or_else(|| AppError::NotFound("Link not found".to_string()))?;
 
+        crate::services::LinkAliasService::new()
+            .delete_alias_pointers_for_link(db, kv, link_id, org_id)
+            .await?;
+
         // Delete from D1
         repo.hard_delete(db, link_id, org_id).await?;
 
@@ -376,6 +650,143 @@ impl LinkService {
         Ok(())
     }
 
+    /// Delete many of an org's links. IDs outside the org are reported in
+    /// `not_found`. The D1 rows are deleted in one batch per chunk of links,
+    /// then each link's KV mappings are removed like `delete_link`; a failure
+    /// is reported in `errors` without stopping the remaining deletions.
+    pub async fn bulk_delete_links(
+        &self,
+        db: &D1Database,
+        kv: &KvStore,
+        org_id: &str,
+        ids: &[String],
+    ) -> Result<BulkDeleteLinksResponse, AppError> {
+        let mut requested: Vec<String> = Vec::with_capacity(ids.len());
+        for id in ids {
+            if !requested.contains(id) {
+                requested.push(id.clone());
+            }
+        }
+        let repo = LinkRepository::new();
+        let owned = repo.short_codes_in_org(db, org_id, &requested).await?;
+        let not_found: Vec<String> = requested
+            .into_iter()
+            .filter(|id| !owned.iter().any(|(owned_id, _)| owned_id == id))
+            .collect();
+
+        // Looked up once rather than per link, to keep the number of
+        // subrequests down
+        let domains = crate::repositories::CustomDomainRepository::new()
+            .get_active_for_org(db, org_id)
+            .await?;
+        let alias_repo = crate::repositories::LinkAliasRepository::new();
+
+        let mut deleted = 0;
+        let mut errors = Vec::new();
+        for chunk in owned.chunks(crate::repositories::link_repository::D1_MAX_BOUND_PARAMS - 1) {
+            let chunk_ids: Vec<&str> = chunk.iter().map(|(id, _)| id.as_str()).collect();
+            let deleted_rows = async {
+                let alias_codes = alias_repo
+                    .list_codes_for_links(db, org_id, &chunk_ids)
+                    .await?;
+                repo.hard_delete_many(db, &chunk_ids, org_id).await?;
+                Ok::<_, worker::Error>(alias_codes)
+            }
+            .await;
+            let alias_codes = match deleted_rows {
+                Ok(alias_codes) => alias_codes,
+                Err(e) => {
+                    errors.extend(chunk.iter().map(|(id, _)| BulkDeleteLinkError {
+                        id: id.clone(),
+                        error: e.to_string(),
+                    }));
+                    continue;
+                }
+            };
+
+            for (id, short_code) in chunk {
+                let unmapped = async {
+                    for (_, alias_code) in alias_codes.iter().filter(|(link_id, _)| link_id == id) {
+                        kv.delete(alias_code).await?;
+                    }
+                    crate::kv::delete_link_mapping(kv, org_id, short_code).await?;
+                    for domain in &domains {
+                        crate::kv::links::delete_link_mapping_for_domain(
+                            kv,
+                            &domain.hostname,
+                            short_code,
+                        )
+                        .await?;
+                    }
+                    Ok::<_, worker::Error>(())
+                }
+                .await;
+                match unmapped {
+                    Ok(()) => deleted += 1,
+                    Err(e) => errors.push(BulkDeleteLinkError {
+                        id: id.clone(),
+                        error: e.to_string(),
+                    }),
+                }
+            }
+        }
+
+        Ok(BulkDeleteLinksResponse {
+            deleted,
+            not_found,
+            errors,
+        })
+    }
+
+    /// Zero a link's click counter, optionally deleting its raw analytics
+    /// events with `start <= timestamp <= end`. Org owners and admins only.
+    ///
+    /// Both writes run in one batch. Deleted events cannot be recovered.
+    /// Returns the number of events deleted.
+    pub async fn reset_click_count(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+        org_id: &str,
+        user_id: &str,
+        delete_events: Option<(i64, i64)>,
+    ) -> Result<i64, AppError> {
+        crate::services::OrgService::new()
+            .require_owner_or_admin(
+                db,
+                org_id,
+                user_id,
+                "Only organization owners and admins can reset click counts",
+            )
+            .await?;
+
+        LinkRepository::new()
+            .get_by_id(db, link_id, org_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Link not found".to_string()))?;
+
+        let mut statements =
+            vec![LinkRepository::new().reset_click_count_statement(db, link_id, org_id)?];
+        if let Some((start, end)) = delete_events {
+            statements.push(
+                crate::repositories::AnalyticsRepository::new()
+                    .delete_link_events_in_range_statement(db, link_id, org_id, start, end)?,
+            );
+        }
+
+        let results = db.batch(statements).await?;
+        let events_deleted = match delete_events {
+            Some(_) => results
+                .get(1)
+                .and_then(|r| r.meta().ok().flatten())
+                .and_then(|m| m.changes)
+                .unwrap_or(0) as i64,
+            None => 0,
+        };
+
+        Ok(events_deleted)
+    }
+
     /// Get all links for export.
     pub async fn export_links(&self, db: &D1Database, org_id: &str) -> Result<Vec<Link>, AppError> {
         let repo = LinkRepository::new();
@@ -428,6 +839,19 @@ impl LinkService {
         Ok((links, total))
     }
 
+    /// Count links for admin with the same filters as `admin_list_links`.
+    pub async fn admin_count_links(
+        &self,
+        db: &D1Database,
+        org_filter: Option<&str>,
+        email_filter: Option<&str>,
+        domain_filter: Option<&str>,
+    ) -> Result<i64, AppError> {
+        Ok(LinkRepository::new()
+            .count_admin(db, org_filter, email_filter, domain_filter)
+            .await?)
+    }
+
     /// Update link status (admin only).
     pub async fn admin_update_link_status(
         &self,
@@ -444,8 +868,11 @@ impl LinkService {
             .await?
             .ok_or_else(|| AppError::NotFound("Link not found".to_string()))?;
 
-        // Update status in D1
-        repo.update_status_by_id(db, link_id, status.as_str())
+        // Update status in D1; admin-disabled links are marked so they never
+        // go to the owner's fallback URL
+        let disabled_reason =
+            (status == LinkStatus::Disabled).then_some(crate::models::link::DISABLED_REASON_ADMIN);
+        repo.update_status_by_id(db, link_id, status.as_str(), disabled_reason)
             .await?;
 
         // Sync KV based on new status
@@ -462,8 +889,19 @@ impl LinkService {
                 ios_url: link.ios_url.clone(),
                 android_url: link.android_url.clone(),
                 desktop_url: link.desktop_url.clone(),
+                org_id: Some(link.org_id.clone()),
+                response_headers: link.response_headers.clone(),
+                templated: link.has_templated_destination(),
+                updated_at: Some(crate::utils::now_timestamp()),
+                disabled_reason: None,
+                max_clicks: link.max_clicks,
+                fallback_url: link.fallback_url.clone(),
             };
             crate::kv::store_link_mapping(kv, &link.org_id, &link.short_code, &mapping).await?;
+        } else if status == LinkStatus::Blocked {
+            // Keep a tombstone so redirects can serve the blocked response
+            crate::kv::store_blocked_tombstone(kv, &link.org_id, &link.short_code, &link.id)
+                .await?;
         } else {
             // Disable in KV
             crate::kv::delete_link_mapping(kv, &link.org_id, &link.short_code).await?;
@@ -484,13 +922,16 @@ impl LinkService {
         Ok(())
     }
 
-    /// Delete a link as admin.
+    /// Delete a link as admin. When `deleted_link_retention_days` is set, the
+    /// link's analytics are kept under a tombstone and the retention end is
+    /// returned; otherwise everything is purged and None is returned.
     pub async fn admin_delete_link(
         &self,
         db: &D1Database,
         kv: &KvStore,
         link_id: &str,
-    ) -> Result<(), AppError> {
+        deleted_by: &str,
+    ) -> Result<Option<i64>, AppError> {
         let repo = LinkRepository::new();
 
         // Get link without org check
@@ -499,13 +940,33 @@ impl LinkService {
             .await?
             .ok_or_else(|| AppError::NotFound("Link not found".to_string()))?;
 
+        crate::services::LinkAliasService::new()
+            .delete_alias_pointers_for_link(db, kv, link_id, &link.org_id)
+            .await?;
+
+        let retention_days = crate::services::SettingsService::new()
+            .get_deleted_link_retention_days(db)
+            .await?;
+
         // Delete from D1
-        repo.hard_delete(db, link_id, &link.org_id).await?;
+        let retain_until = match retention_days {
+            Some(days) => {
+                let retain_until =
+                    crate::models::deleted_link::retain_until(days, crate::utils::now_timestamp());
+                repo.retain_and_delete(db, link_id, &link.org_id, deleted_by, retain_until)
+                    .await?;
+                Some(retain_until)
+            }
+            None => {
+                repo.hard_delete(db, link_id, &link.org_id).await?;
+                None
+            }
+        };
 
         // Delete from KV
         crate::kv::delete_link_mapping(kv, &link.org_id, &link.short_code).await?;
 
-        Ok(())
+        Ok(retain_until)
     }
 
     /// Generate a unique random short code starting at the configured minimum
@@ -515,6 +976,8 @@ impl LinkService {
     /// length, the namespace is considered effectively exhausted: the target
     /// length is incremented and the `system_min_code_length` high-watermark is
     /// persisted so the whole application self-heals to the longer length.
+    /// `SHORT_CODE_LENGTH_BUMP = "false"` disables the bump, and after
+    /// `SHORT_CODE_MAX_ATTEMPTS` collisions this gives up with a 503.
     pub async fn generate_progressive_short_code(
         &self,
         kv: &KvStore,
@@ -523,49 +986,63 @@ impl LinkService {
         admin_min_length: usize,
         system_min_length: usize,
         exclude_ambiguous: bool,
-    ) -> worker::Result<String> {
-        let collision_threshold = env
-            .var("COLLISION_THRESHOLD")
-            .ok()
-            .and_then(|v| v.to_string().parse::<usize>().ok())
-            .unwrap_or(DEFAULT_COLLISION_THRESHOLD);
+    ) -> Result<String, AppError> {
+        let env_value = |name: &str| env.var(name).ok().map(|v| v.to_string());
+        let policy = CodeGenerationPolicy::from_env_values(
+            env_value("COLLISION_THRESHOLD").as_deref(),
+            env_value("SHORT_CODE_MAX_ATTEMPTS").as_deref(),
+            env_value("SHORT_CODE_LENGTH_BUMP").as_deref(),
+        );
 
-        let mut current_length = admin_min_length.max(system_min_length);
-        let mut total_attempts = 0;
-        let mut current_length_attempts = 0;
+        let start_length = admin_min_length.max(system_min_length);
+        let mut current_length = start_length;
+        let mut attempts = CodeGenerationAttempts::new(policy);
 
         loop {
             let code = generate_short_code_with_charset(current_length, exclude_ambiguous);
 
             if !crate::kv::links::short_code_exists(kv, &code).await? {
+                if attempts.collisions() >= policy.collision_threshold {
+                    log_frequent_collisions(
+                        &policy,
+                        attempts.collisions(),
+                        start_length,
+                        current_length,
+                        false,
+                    );
+                }
                 return Ok(code);
             }
 
-            total_attempts += 1;
-            current_length_attempts += 1;
+            match attempts.record_collision(current_length) {
+                CollisionAction::Retry => {}
+                CollisionAction::BumpLength => {
+                    current_length += 1;
 
-            // Exhaustion Trigger: Dynamic threshold based on env var
-            if current_length_attempts >= collision_threshold {
-                current_length += 1;
-                current_length_attempts = 0;
-
-                let settings_repo = SettingsRepository::new();
-                let _ = settings_repo
-                    .set_setting(db, "system_min_code_length", &current_length.to_string())
-                    .await;
-
-                if admin_min_length < current_length {
+                    let settings_repo = SettingsRepository::new();
                     let _ = settings_repo
-                        .set_setting(db, "min_random_code_length", &current_length.to_string())
+                        .set_setting(db, "system_min_code_length", &current_length.to_string())
                         .await;
-                }
-            }
 
-            // Scale the ultimate fail-safe based on the threshold too
-            if total_attempts > (collision_threshold * 3).max(20) {
-                return Err(worker::Error::RustError(
-                    "Failed to generate unique short code".into(),
-                ));
+                    if admin_min_length < current_length {
+                        let _ = settings_repo
+                            .set_setting(db, "min_random_code_length", &current_length.to_string())
+                            .await;
+                    }
+                }
+                CollisionAction::GiveUp => {
+                    log_frequent_collisions(
+                        &policy,
+                        attempts.collisions(),
+                        start_length,
+                        current_length,
+                        true,
+                    );
+                    return Err(AppError::ServiceUnavailable(
+                        "Could not generate a unique short code right now. Please try again shortly."
+                            .to_string(),
+                    ));
+                }
             }
         }
     }
@@ -612,6 +1089,135 @@ impl LinkService {
         Ok(())
     }
 
+    /// Create several links all-or-nothing. The D1 rows and tags are written
+    /// in one batch, which D1 runs as a single transaction, so a unique-index
+    /// collision leaves nothing behind. KV mappings are written afterwards; if
+    /// one fails, the mappings already written are removed and the D1 rows
+    /// are deleted again in one batch.
+    pub async fn create_links_atomic(
+        &self,
+        db: &D1Database,
+        kv: &KvStore,
+        links: &[(Link, Vec<String>)],
+        org_id: &str,
+    ) -> Result<(), AppError> {
+        let repo = LinkRepository::new();
+
+        let mut statements = Vec::new();
+        for (link, tags) in links {
+            statements.push(repo.create_statement(db, link)?);
+            statements.extend(repo.new_link_tag_statements(db, &link.id, org_id, tags)?);
+        }
+        db.batch(statements).await.map_err(|e| {
+            AppError::Conflict(format!(
+                "Failed to create links; a short code may already be in use: {}",
+                e
+            ))
+        })?;
+
+        let org_forward = crate::repositories::OrgRepository::new()
+            .get_forward_query_params(db, org_id)
+            .await
+            .unwrap_or(false);
+
+        for (written, (link, _)) in links.iter().enumerate() {
+            let mapping = link.to_mapping(link.forward_query_params.unwrap_or(org_forward));
+            let stored = async {
+                crate::kv::store_link_mapping(kv, org_id, &link.short_code, &mapping).await?;
+                crate::kv::sync_custom_domain_kv(kv, db, org_id, &link.short_code, Some(&mapping))
+                    .await
+            }
+            .await;
+
+            if let Err(e) = stored {
+                // Compensate: unwind this and earlier mappings, then the rows
+                for (link, _) in &links[..=written] {
+                    let _ = crate::kv::delete_link_mapping(kv, org_id, &link.short_code).await;
+                    let _ =
+                        crate::kv::sync_custom_domain_kv(kv, db, org_id, &link.short_code, None)
+                            .await;
+                }
+                let link_ids: Vec<&str> = links.iter().map(|(l, _)| l.id.as_str()).collect();
+                repo.hard_delete_many(db, &link_ids, org_id).await?;
+                return Err(AppError::Internal(format!(
+                    "Failed to store link mappings: {}",
+                    e
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Generate and store a claim token for an anonymously created link.
+    /// Only the SHA-256 hash is kept; the token is returned once.
+    pub async fn issue_claim_token(
+        &self,
+        db: &D1Database,
+        link_id: &str,
+    ) -> Result<String, AppError> {
+        let token = format!(
+            "{}{}",
+            CLAIM_TOKEN_PREFIX,
+            crate::utils::generate_short_code_with_length(32)
+        );
+        LinkRepository::new()
+            .set_claim_token_hash(db, link_id, &hash_claim_token(&token))
+            .await?;
+        Ok(token)
+    }
+
+    /// Assign the anonymous link identified by `token` to `org_id`, created
+    /// by `user_id`. Counts against the org's monthly link quota.
+    ///
+    /// Returns Err(AppError::NotFound) for an unknown token and
+    /// Err(AppError::Conflict) if the link has already been claimed.
+    pub async fn claim_link(
+        &self,
+        db: &D1Database,
+        kv: &KvStore,
+        token: &str,
+        org_id: &str,
+        user_id: &str,
+    ) -> Result<Link, AppError> {
+        let repo = LinkRepository::new();
+        let link = repo
+            .get_by_claim_token_hash(db, &hash_claim_token(token))
+            .await?
+            .ok_or_else(|| AppError::NotFound("Invalid claim token".to_string()))?;
+
+        if link.org_id != ANONYMOUS_ORG_ID {
+            return Err(AppError::Conflict(
+                "This link has already been claimed".to_string(),
+            ));
+        }
+
+        let quota_ctx = self.check_quota(db, org_id).await?;
+
+        // A concurrent claim can win the race; hand back the reservation
+        let claimed = repo
+            .claim_anonymous(db, &link.id, org_id, user_id, crate::utils::now_timestamp())
+            .await;
+        if !matches!(claimed, Ok(true)) {
+            self.release_quota(db, &quota_ctx, 1).await?;
+            claimed?;
+            return Err(AppError::Conflict(
+                "This link has already been claimed".to_string(),
+            ));
+        }
+
+        let claimed = repo
+            .get_by_id(db, &link.id, org_id)
+            .await?
+            .ok_or_else(|| AppError::Internal("Claimed link not found".to_string()))?;
+
+        // The KV mapping still names the placeholder org
+        self.resync_kv_mappings(db, kv, org_id, std::slice::from_ref(&claimed))
+            .await?;
+
+        Ok(claimed)
+    }
+
     /// Re-sync a link's KV entry based on its current status (admin only).
     pub async fn admin_sync_link_kv(
         &self,
@@ -648,6 +1254,11 @@ impl LinkService {
                     android_url: link.android_url.clone(),
                     desktop_url: link.desktop_url.clone(),
                     custom_domain: link.custom_domain.clone(),
+                    response_headers: link.response_headers.clone(),
+                    raw_destination: link.raw_destination.clone(),
+                    disabled_reason: None,
+                    max_clicks: link.max_clicks,
+                    fallback_url: link.fallback_url.clone(),
                 };
                 let org_repo = crate::repositories::OrgRepository::new();
                 let resolved_forward = if let Some(forward) = link.forward_query_params {
@@ -661,7 +1272,22 @@ impl LinkService {
                 let mapping = link_model.to_mapping(resolved_forward);
                 crate::kv::store_link_mapping(kv, &link.org_id, &link.short_code, &mapping).await?;
             }
-            "blocked" | "disabled" => {
+            "blocked" => {
+                crate::kv::store_blocked_tombstone(kv, &link.org_id, &link.short_code, &link.id)
+                    .await?;
+            }
+            // Links disabled by a suspension, or by their owner with a fallback
+            // URL, keep a disabled mapping, so redirects can still serve the
+            // suspended-link response or send visitors to the fallback
+            "disabled"
+                if link.disabled_reason.as_deref()
+                    == Some(crate::models::link::DISABLED_REASON_SUSPENSION)
+                    || (link.disabled_reason.is_none() && link.fallback_url.is_some()) =>
+            {
+                let mapping = link.to_mapping(link.forward_query_params.unwrap_or(false));
+                crate::kv::store_link_mapping(kv, &link.org_id, &link.short_code, &mapping).await?;
+            }
+            "disabled" => {
                 crate::kv::delete_link_mapping(kv, &link.org_id, &link.short_code).await?;
             }
             _ => {
@@ -674,6 +1300,117 @@ impl LinkService {
         Ok(())
     }
 
+    /// Cross-check D1 against KV for the newest `limit` links of `org_filter`
+    /// (or a random sample across all orgs) and return the out-of-sync links.
+    /// With `repair`, each of them is re-synced from D1 like
+    /// `admin_sync_link_kv`: active links are written back to KV and stale
+    /// entries of disabled or blocked links are removed.
+    pub async fn admin_audit_kv_consistency(
+        &self,
+        db: &D1Database,
+        kv: &KvStore,
+        org_filter: Option<&str>,
+        active_only: bool,
+        limit: i64,
+        repair: bool,
+    ) -> Result<KvAuditReport, AppError> {
+        let repo = LinkRepository::new();
+        let links = repo
+            .list_admin_kv_audit(db, org_filter, active_only, limit)
+            .await?;
+        let checked = links.len();
+
+        let mut mismatches = Vec::new();
+        for base in links {
+            let (kv_sync_status, kv_exists) = repo.check_kv_sync(kv, &base).await?;
+            if kv_sync_status != "synced" {
+                mismatches.push(crate::repositories::link_repository::AdminLink {
+                    base,
+                    kv_sync_status,
+                    kv_exists,
+                });
+            }
+        }
+
+        let mut repaired = 0;
+        if repair {
+            for mismatch in &mismatches {
+                self.admin_sync_link_kv(db, kv, &mismatch.base.id).await?;
+                repaired += 1;
+            }
+        }
+
+        Ok(KvAuditReport {
+            checked,
+            mismatches,
+            repaired,
+        })
+    }
+
+    /// Read one page of up to `limit` KV keys (continuing from `cursor`) and
+    /// return the link keys that no D1 link or alias owns. With `repair`,
+    /// those keys are deleted so the orphaned codes stop redirecting.
+    pub async fn admin_audit_kv_orphans(
+        &self,
+        db: &D1Database,
+        kv: &KvStore,
+        cursor: Option<String>,
+        limit: u64,
+        repair: bool,
+    ) -> Result<KvOrphanReport, AppError> {
+        let mut list = kv.list().limit(limit);
+        if let Some(cursor) = cursor {
+            list = list.cursor(cursor);
+        }
+        let page = list
+            .execute()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to list KV keys: {}", e)))?;
+
+        let link_keys: Vec<(String, Option<String>, String)> = page
+            .keys
+            .iter()
+            .filter_map(|key| {
+                crate::kv::links::parse_link_key(&key.name).map(|(domain, code)| {
+                    (
+                        key.name.clone(),
+                        domain.map(|d| d.to_string()),
+                        code.to_string(),
+                    )
+                })
+            })
+            .collect();
+        let codes: Vec<String> = link_keys.iter().map(|(_, _, code)| code.clone()).collect();
+        let known = LinkRepository::new().find_known_codes(db, &codes).await?;
+
+        let orphans: Vec<String> = link_keys
+            .iter()
+            .filter(|(_, domain, code)| {
+                !known.iter().any(|(known_code, known_domain)| {
+                    known_code == code && (domain.is_none() || known_domain == domain)
+                })
+            })
+            .map(|(key, _, _)| key.clone())
+            .collect();
+
+        let mut repaired = 0;
+        if repair {
+            for key in &orphans {
+                kv.delete(key).await.map_err(|e| {
+                    AppError::Internal(format!("Failed to delete orphaned KV key: {}", e))
+                })?;
+                repaired += 1;
+            }
+        }
+
+        Ok(KvOrphanReport {
+            checked: link_keys.len(),
+            orphans,
+            repaired,
+            cursor: (!page.list_complete).then_some(page.cursor).flatten(),
+        })
+    }
+
     /// Check whether the org's billing tier allows Pro-only link features.
     ///
     /// Returns the org's billing account tier. Returns Err(AppError::Forbidden)
@@ -858,6 +1595,31 @@ impl LinkService {
     }
 }
 
+/// Warn operators when short-code generation needed many attempts, a sign the
+/// configured code length is running out of namespace.
+fn log_frequent_collisions(
+    policy: &CodeGenerationPolicy,
+    collisions: usize,
+    start_length: usize,
+    final_length: usize,
+    gave_up: bool,
+) {
+    worker::console_warn!(
+        "{}",
+        serde_json::json!({
+            "event": "short_code_collisions",
+            "collisions": collisions,
+            "start_length": start_length,
+            "final_length": final_length,
+            "collision_threshold": policy.collision_threshold,
+            "max_attempts": policy.max_attempts,
+            "length_bump": policy.bump_length,
+            "gave_up": gave_up,
+            "level": "warn"
+        })
+    );
+}
+
 /// Result of a bulk import operation.
 #[derive(Debug)]
 pub struct ImportResult {
@@ -883,3 +1645,68 @@ pub struct ImportWarning {
     pub destination_url: String,
     pub reason: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_default_redirect_type_requires_pro() {
+        let quota_ctx = |tier| QuotaContext {
+            billing_account_id: "ba".to_string(),
+            tier,
+        };
+        assert_eq!(
+            quota_ctx(Some(Tier::Pro)).default_redirect_type("307".to_string()),
+            "307"
+        );
+        assert_eq!(
+            quota_ctx(Some(Tier::Free)).default_redirect_type("307".to_string()),
+            "301"
+        );
+        assert_eq!(
+            quota_ctx(None).default_redirect_type("308".to_string()),
+            "301"
+        );
+    }
+
+    #[test]
+    fn test_next_month_start() {
+        let mid_month = chrono::Utc
+            .with_ymd_and_hms(2025, 3, 15, 12, 30, 0)
+            .unwrap();
+        assert_eq!(
+            next_month_start(mid_month),
+            chrono::Utc
+                .with_ymd_and_hms(2025, 4, 1, 0, 0, 0)
+                .unwrap()
+                .timestamp()
+        );
+
+        let december = chrono::Utc
+            .with_ymd_and_hms(2025, 12, 31, 23, 59, 59)
+            .unwrap();
+        assert_eq!(
+            next_month_start(december),
+            chrono::Utc
+                .with_ymd_and_hms(2026, 1, 1, 0, 0, 0)
+                .unwrap()
+                .timestamp()
+        );
+    }
+
+    #[test]
+    fn test_month_start() {
+        let end_of_month = chrono::Utc
+            .with_ymd_and_hms(2025, 12, 31, 23, 59, 59)
+            .unwrap();
+        assert_eq!(
+            month_start(end_of_month),
+            chrono::Utc
+                .with_ymd_and_hms(2025, 12, 1, 0, 0, 0)
+                .unwrap()
+                .timestamp()
+        );
+    }
+}
@@ -19,14 +19,17 @@ pub mod api_key_service;
 pub mod auth_service;
 pub mod billing_service;
 pub mod blacklist_service;
+pub mod config_bundle_service;
 pub mod domain_service;
 pub mod email_notification_service;
+pub mod link_alias_service;
 pub mod link_service;
 pub mod oauth_service;
 pub mod org_service;
 pub mod ownership_transfer_service;
 pub mod product_service;
 pub mod report_service;
+pub mod rewrite_rule_service;
 pub mod settings_service;
 pub mod subscription_service;
 pub mod tag_service;
@@ -35,7 +38,9 @@ pub use api_key_service::ApiKeyService;
 pub use auth_service::AuthService;
 pub use billing_service::BillingService;
 pub use blacklist_service::BlacklistService;
+pub use config_bundle_service::ConfigBundleService;
 pub use domain_service::DomainService;
+pub use link_alias_service::LinkAliasService;
 pub use link_service::LinkService;
 pub use oauth_service::OAuthService;
 pub use org_service::OrgService;
@@ -43,6 +48,7 @@ pub use org_service::OrgService;
 pub use ownership_transfer_service::OwnershipTransferService;
 pub use product_service::ProductService;
 pub use report_service::ReportService;
+pub use rewrite_rule_service::RewriteRuleService;
 pub use settings_service::SettingsService;
 pub use subscription_service::SubscriptionService;
 pub use tag_service::TagService;
@@ -2,18 +2,45 @@
 ///
 /// Handles org limit enforcement and member limit checks.
 /// Orchestrates BillingRepository and OrgRepository.
+use crate::models::org_member::{
+    DEFAULT_INVITATION_RESEND_INTERVAL_SECS, MAX_INVITATION_RESEND_INTERVAL_SECS, OrgInvitation,
+    check_pending_invitations, max_pending_invitations,
+};
+use crate::models::tier::TierLimits;
 use crate::models::{OrgMember, Organization, Tier};
-use crate::repositories::{BillingRepository, LinkRepository, OrgRepository};
+use crate::repositories::{BillingRepository, LinkRepository, OrgRepository, SettingsRepository};
 use crate::utils::AppError;
 use chrono::Datelike;
 use worker::d1::D1Database;
 use worker::kv::KvStore;
 
 /// Org-level settings (defaults applied to new links)
-#[derive(Debug, Clone, Copy, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct OrgSettings {
     pub forward_query_params: bool,
     pub exclude_ambiguous_chars: bool,
+    /// Seconds the branded interstitial is shown before redirecting (0 = off)
+    pub interstitial_delay_seconds: u32,
+    /// Sort applied to the links list when the request does not pass one
+    pub default_link_sort: String,
+    /// Redirect status for new links created without a redirect_type
+    pub default_redirect_type: String,
+    /// List the org's active links in the redirect domain's /sitemap.xml
+    pub public_sitemap: bool,
+    /// Serve link-preview crawlers an OpenGraph page instead of the redirect
+    pub crawler_preview: bool,
+    /// Show the interstitial for destinations outside `trusted_domains`
+    pub untrusted_interstitial: bool,
+    /// Destination domains (and subdomains) that always redirect directly
+    pub trusted_domains: Vec<String>,
+    /// Request headers whose values are recorded with each click event
+    pub analytics_header_fields: Vec<String>,
+    /// Custom invitation email subject (None = default)
+    pub invite_subject: Option<String>,
+    /// Note from the org shown in invitation emails
+    pub invite_message: Option<String>,
+    /// Links the org may create per month on top of the tier limit (None = no cap)
+    pub org_link_quota: Option<i64>,
 }
 
 /// Service for organization-related business logic
@@ -94,6 +121,56 @@ impl OrgService {
         Ok(())
     }
 
+    /// Record a resend of `invitation` if the minimum resend interval has
+    /// passed. Returns the seconds to wait when it has not.
+    ///
+    /// The interval comes from the `invitation_resend_interval_seconds`
+    /// setting (0 = no cooldown) and protects invitees from email bombing.
+    pub async fn claim_invitation_resend(
+        &self,
+        db: &D1Database,
+        invitation: &OrgInvitation,
+    ) -> Result<Option<i64>, AppError> {
+        let interval = SettingsRepository::new()
+            .get_setting(db, "invitation_resend_interval_seconds")
+            .await?
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|secs| (0..=MAX_INVITATION_RESEND_INTERVAL_SECS).contains(secs))
+            .unwrap_or(DEFAULT_INVITATION_RESEND_INTERVAL_SECS);
+
+        let now = crate::utils::now_timestamp();
+        if let Some(retry_after) = invitation.resend_retry_after(interval, now) {
+            return Ok(Some(retry_after));
+        }
+        let claimed = OrgRepository::new()
+            .claim_invitation_resend(db, &invitation.id, now, now - interval)
+            .await?;
+        // Lost the race to a concurrent resend that was just recorded
+        Ok((!claimed).then_some(interval.max(1)))
+    }
+
+    /// Check whether an org may have another pending invitation.
+    ///
+    /// The cap comes from the `max_pending_invitations_per_org` setting and
+    /// applies regardless of tier, so a compromised or careless admin cannot
+    /// queue an unbounded number of invitation emails.
+    /// Returns Err(AppError::Forbidden) once the cap is reached.
+    pub async fn check_pending_invitation_limit(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+    ) -> Result<(), AppError> {
+        let setting = SettingsRepository::new()
+            .get_setting(db, "max_pending_invitations_per_org")
+            .await?;
+        let max_pending = max_pending_invitations(setting.as_deref());
+
+        let pending = OrgRepository::new()
+            .count_pending_invitations(db, org_id)
+            .await?;
+        check_pending_invitations(pending, max_pending).map_err(AppError::Forbidden)
+    }
+
     /// Get the effective billing tier for an organization.
     ///
     /// Looks up the billing account linked to the org and returns its tier.
@@ -111,15 +188,26 @@ impl OrgService {
     /// List all organizations a user belongs to, enriched with billing tier.
     ///
     /// Returns a JSON-serializable list of org summaries including tier, role, and joined_at.
+    /// When `include_stats` is set, each org also carries a `stats` object with its
+    /// link count and clicks this month (one grouped query for all orgs).
     pub async fn list_user_orgs_with_tier(
         &self,
         db: &D1Database,
         user_id: &str,
+        include_stats: bool,
     ) -> Result<Vec<serde_json::Value>, AppError> {
         let repo = OrgRepository::new();
         let billing_repo = BillingRepository::new();
         let orgs = repo.get_user_orgs(db, user_id).await?;
 
+        let mut stats = if include_stats {
+            let now = chrono::Utc::now();
+            let year_month = format!("{}-{:02}", now.year(), now.month());
+            Some(repo.get_user_org_stats(db, user_id, &year_month).await?)
+        } else {
+            None
+        };
+
         let mut result = Vec::with_capacity(orgs.len());
         for org in orgs {
             let (tier, billing_account_id) = if let Ok(Some(org_details)) =
@@ -140,14 +228,22 @@ impl OrgService {
                 ("free".to_string(), None)
             };
 
-            result.push(serde_json::json!({
+            let mut summary = serde_json::json!({
                 "id": org.id,
                 "name": org.name,
                 "tier": tier,
                 "role": org.role,
                 "joined_at": org.joined_at,
                 "billing_account_id": billing_account_id,
-            }));
+            });
+            if let Some(stats) = stats.as_mut() {
+                let org_stats = stats.remove(&org.id).unwrap_or_default();
+                summary["stats"] = serde_json::json!({
+                    "link_count": org_stats.link_count,
+                    "clicks_this_month": org_stats.clicks_this_month,
+                });
+            }
+            result.push(summary);
         }
         Ok(result)
     }
@@ -341,21 +437,69 @@ impl OrgService {
         if repo.get_member(db, org_id, user_id).await?.is_none() {
             return Err(AppError::NotFound("Organization not found".to_string()));
         }
+        let branding = repo.get_invitation_branding(db, org_id).await?;
         Ok(OrgSettings {
             forward_query_params: repo.get_forward_query_params(db, org_id).await?,
             exclude_ambiguous_chars: repo.get_exclude_ambiguous_chars(db, org_id).await?,
+            interstitial_delay_seconds: repo.get_interstitial_delay(db, org_id).await?,
+            default_link_sort: repo.get_default_link_sort(db, org_id).await?,
+            default_redirect_type: repo.get_default_redirect_type(db, org_id).await?,
+            public_sitemap: repo.get_public_sitemap(db, org_id).await?,
+            crawler_preview: repo.get_crawler_preview(db, org_id).await?,
+            untrusted_interstitial: repo.get_untrusted_interstitial(db, org_id).await?,
+            trusted_domains: repo.get_trusted_domains(db, org_id).await?,
+            analytics_header_fields: repo.get_analytics_header_fields(db, org_id).await?,
+            invite_subject: branding.invite_subject,
+            invite_message: branding.invite_message,
+            org_link_quota: repo.get_org_link_quota(db, org_id).await?,
         })
     }
 
+    /// Resolved tier and its limits for an org the user belongs to.
+    pub async fn get_org_limits(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        user_id: &str,
+    ) -> Result<(Tier, TierLimits), AppError> {
+        let repo = OrgRepository::new();
+        if repo.get_member(db, org_id, user_id).await?.is_none() {
+            return Err(AppError::NotFound("Organization not found".to_string()));
+        }
+        let org = repo
+            .get_by_id(db, org_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Organization not found".to_string()))?;
+        let tier = self.get_org_tier(db, &org).await;
+        let limits = tier.limits();
+        Ok((tier, limits))
+    }
+
     /// Update org settings with owner/admin checks. Fields left as None are
-    /// unchanged. Enabling forward_query_params additionally requires Pro+.
+    /// unchanged; `Some(None)` clears the invitation subject or message, or
+    /// the link quota. Enabling forward_query_params or a default redirect
+    /// type other than 301 additionally requires Pro+, and only owners may
+    /// change the org link quota.
+    #[allow(clippy::too_many_arguments)]
     pub async fn update_org_settings(
         &self,
         db: &D1Database,
+        kv: &KvStore,
         org_id: &str,
         user_id: &str,
         forward_query_params: Option<bool>,
         exclude_ambiguous_chars: Option<bool>,
+        interstitial_delay_seconds: Option<u32>,
+        default_link_sort: Option<&str>,
+        default_redirect_type: Option<&str>,
+        public_sitemap: Option<bool>,
+        crawler_preview: Option<bool>,
+        untrusted_interstitial: Option<bool>,
+        trusted_domains: Option<&[String]>,
+        analytics_header_fields: Option<&[String]>,
+        invite_subject: Option<Option<&str>>,
+        invite_message: Option<Option<&str>>,
+        org_link_quota: Option<Option<i64>>,
     ) -> Result<OrgSettings, AppError> {
         let repo = OrgRepository::new();
 
@@ -366,8 +510,20 @@ impl OrgService {
             "Only org owners and admins can change organization settings",
         )
         .await?;
+        if org_link_quota.is_some() {
+            self.require_owner(
+                db,
+                org_id,
+                user_id,
+                "Only org owners can change the organization link quota",
+            )
+            .await?;
+        }
 
-        if let Some(forward) = forward_query_params {
+        let wants_forward = forward_query_params == Some(true);
+        let wants_redirect_type =
+            default_redirect_type.is_some_and(|t| t != crate::models::link::DEFAULT_REDIRECT_TYPE);
+        if wants_forward || wants_redirect_type {
             let org = repo
                 .get_by_id(db, org_id)
                 .await?
@@ -376,12 +532,19 @@ impl OrgService {
             let tier = self.get_org_tier(db, &org).await;
             let is_pro_or_above = matches!(tier, Tier::Pro | Tier::Business | Tier::Unlimited);
 
-            if forward && !is_pro_or_above {
+            if wants_forward && !is_pro_or_above {
                 return Err(AppError::Forbidden(
                     "Query parameter forwarding requires a Pro plan or above.".to_string(),
                 ));
             }
+            if wants_redirect_type && !is_pro_or_above {
+                return Err(AppError::Forbidden(
+                    "Custom redirect types require a Pro plan or above.".to_string(),
+                ));
+            }
+        }
 
+        if let Some(forward) = forward_query_params {
             repo.set_forward_query_params(db, org_id, forward).await?;
         }
 
@@ -390,12 +553,160 @@ impl OrgService {
                 .await?;
         }
 
+        let mut redirect_config_enabled = false;
+        if let Some(delay) = interstitial_delay_seconds {
+            let previous = repo.get_interstitial_delay(db, org_id).await?;
+            repo.set_interstitial_delay(db, org_id, delay).await?;
+            redirect_config_enabled |= previous == 0 && delay > 0;
+        }
+
+        if let Some(enabled) = crawler_preview {
+            let previous = repo.get_crawler_preview(db, org_id).await?;
+            repo.set_crawler_preview(db, org_id, enabled).await?;
+            redirect_config_enabled |= !previous && enabled;
+        }
+
+        if let Some(enabled) = untrusted_interstitial {
+            let previous = repo.get_untrusted_interstitial(db, org_id).await?;
+            repo.set_untrusted_interstitial(db, org_id, enabled).await?;
+            redirect_config_enabled |= !previous && enabled;
+        }
+
+        if let Some(domains) = trusted_domains {
+            repo.set_trusted_domains(db, org_id, domains).await?;
+        }
+
+        if let Some(fields) = analytics_header_fields {
+            let previous = repo.get_analytics_header_fields(db, org_id).await?;
+            repo.set_analytics_header_fields(db, org_id, fields).await?;
+            redirect_config_enabled |= previous.is_empty() && !fields.is_empty();
+        }
+
+        if interstitial_delay_seconds.is_some()
+            || crawler_preview.is_some()
+            || untrusted_interstitial.is_some()
+            || trusted_domains.is_some()
+            || analytics_header_fields.is_some()
+        {
+            self.sync_redirect_config(db, kv, org_id).await?;
+
+            // The redirect path finds the org config via the mapping's org_id,
+            // which older KV mappings lack: re-sync them when first enabling.
+            if redirect_config_enabled {
+                let links = LinkRepository::new().get_active_for_org(db, org_id).await?;
+                crate::services::LinkService::new()
+                    .resync_kv_mappings(db, kv, org_id, &links)
+                    .await?;
+            }
+        }
+
+        if let Some(sort) = default_link_sort {
+            repo.set_default_link_sort(db, org_id, sort).await?;
+        }
+
+        if let Some(redirect_type) = default_redirect_type {
+            repo.set_default_redirect_type(db, org_id, redirect_type)
+                .await?;
+        }
+
+        if let Some(enabled) = public_sitemap {
+            repo.set_public_sitemap(db, org_id, enabled).await?;
+        }
+
+        if let Some(subject) = invite_subject {
+            repo.set_invite_subject(db, org_id, subject).await?;
+        }
+
+        if let Some(message) = invite_message {
+            repo.set_invite_message(db, org_id, message).await?;
+        }
+
+        if let Some(quota) = org_link_quota {
+            repo.set_org_link_quota(db, org_id, quota).await?;
+        }
+
+        let branding = repo.get_invitation_branding(db, org_id).await?;
         Ok(OrgSettings {
             forward_query_params: repo.get_forward_query_params(db, org_id).await?,
             exclude_ambiguous_chars: repo.get_exclude_ambiguous_chars(db, org_id).await?,
+            interstitial_delay_seconds: repo.get_interstitial_delay(db, org_id).await?,
+            default_link_sort: repo.get_default_link_sort(db, org_id).await?,
+            default_redirect_type: repo.get_default_redirect_type(db, org_id).await?,
+            public_sitemap: repo.get_public_sitemap(db, org_id).await?,
+            crawler_preview: repo.get_crawler_preview(db, org_id).await?,
+            untrusted_interstitial: repo.get_untrusted_interstitial(db, org_id).await?,
+            trusted_domains: repo.get_trusted_domains(db, org_id).await?,
+            analytics_header_fields: repo.get_analytics_header_fields(db, org_id).await?,
+            invite_subject: branding.invite_subject,
+            invite_message: branding.invite_message,
+            org_link_quota: repo.get_org_link_quota(db, org_id).await?,
         })
     }
 
+    /// Generate a new UTM signing secret for the org, replacing any previous
+    /// one (tokens signed with the old secret stop working). Owner/admin and
+    /// Pro+ only. Returns the secret; it is not shown again.
+    pub async fn rotate_utm_signing_secret(
+        &self,
+        db: &D1Database,
+        kv: &KvStore,
+        org_id: &str,
+        user_id: &str,
+    ) -> Result<String, AppError> {
+        self.require_owner_or_admin(
+            db,
+            org_id,
+            user_id,
+            "Only org owners and admins can manage the UTM signing secret",
+        )
+        .await?;
+
+        let repo = OrgRepository::new();
+        let org = repo
+            .get_by_id(db, org_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Organization not found".to_string()))?;
+        let tier = self.get_org_tier(db, &org).await;
+        if !matches!(tier, Tier::Pro | Tier::Business | Tier::Unlimited) {
+            return Err(AppError::Forbidden(
+                "Signed UTM overrides require a Pro plan or above.".to_string(),
+            ));
+        }
+
+        let had_secret = repo.has_utm_signing_secret(db, org_id).await?;
+        let secret = format!("utm_{}", crate::utils::generate_short_code_with_length(40));
+        repo.set_utm_signing_secret(db, org_id, &secret).await?;
+        self.sync_redirect_config(db, kv, org_id).await?;
+
+        // Like the interstitial, the redirect path finds the secret via the
+        // mapping's org_id, which older KV mappings lack.
+        if !had_secret {
+            let links = LinkRepository::new().get_active_for_org(db, org_id).await?;
+            crate::services::LinkService::new()
+                .resync_kv_mappings(db, kv, org_id, &links)
+                .await?;
+        }
+
+        Ok(secret)
+    }
+
+    /// Rewrite the org's KV redirect config from D1. Call after changing any
+    /// field it caches (name, interstitial settings, trusted domains, UTM
+    /// signing secret, crawler preview).
+    pub async fn sync_redirect_config(
+        &self,
+        db: &D1Database,
+        kv: &KvStore,
+        org_id: &str,
+    ) -> Result<(), AppError> {
+        let config = OrgRepository::new()
+            .get_redirect_config(db, org_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Organization not found".to_string()))?;
+        crate::kv::store_org_redirect_config(kv, org_id, &config).await?;
+        Ok(())
+    }
+
     // ─── Org Logo ─────────────────────────────────────────────────────────────
 
     /// Check org logo upload permissions (owner/admin + Pro+).
@@ -478,6 +789,9 @@ impl OrgService {
             .await?
             .ok_or_else(|| AppError::Internal("No billing account found".to_string()))?;
 
+        self.check_org_name_available(db, &billing_account.id, name, None)
+            .await?;
+
         let repo = OrgRepository::new();
         let org = repo
             .create_with_billing_account(db, name, user_id, &billing_account.id)
@@ -487,6 +801,56 @@ impl OrgService {
         Ok(org)
     }
 
+    /// Rename an organization, enforcing per-billing-account name uniqueness
+    /// when enabled. Caller is responsible for the owner/admin check.
+    pub async fn rename_org(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        name: &str,
+    ) -> Result<(), AppError> {
+        let repo = OrgRepository::new();
+        let org = repo
+            .get_by_id(db, org_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Organization not found".to_string()))?;
+        if let Some(ref billing_account_id) = org.billing_account_id {
+            self.check_org_name_available(db, billing_account_id, name, Some(org_id))
+                .await?;
+        }
+        repo.update_name(db, org_id, name).await?;
+        Ok(())
+    }
+
+    /// Reject a name already used by another org in the same billing account
+    /// (case-insensitive) when the `unique_org_names_per_billing_account`
+    /// setting is on. Returns Err(AppError::Conflict) on a duplicate.
+    async fn check_org_name_available(
+        &self,
+        db: &D1Database,
+        billing_account_id: &str,
+        name: &str,
+        exclude_org_id: Option<&str>,
+    ) -> Result<(), AppError> {
+        let enforced = SettingsRepository::new()
+            .get_setting(db, "unique_org_names_per_billing_account")
+            .await?
+            .is_some_and(|v| v == "true");
+        if !enforced {
+            return Ok(());
+        }
+        let existing = OrgRepository::new()
+            .list_names_in_billing_account(db, billing_account_id, exclude_org_id)
+            .await?;
+        if Organization::is_name_taken(&existing, name) {
+            return Err(AppError::Conflict(format!(
+                "An organization named '{}' already exists in this billing account",
+                name
+            )));
+        }
+        Ok(())
+    }
+
     /// Get org with membership verification.
     ///
     /// Returns the org + member record, or Err(NotFound/Forbidden) if user is not a member.
@@ -534,6 +898,9 @@ impl OrgService {
                         let _ = kv.delete(&link.short_code).await;
                     }
                 }
+                crate::services::LinkAliasService::new()
+                    .delete_alias_pointers_for_org(db, kv, org_id)
+                    .await?;
                 repo.delete_all_links(db, org_id).await?;
             }
             "migrate" => {
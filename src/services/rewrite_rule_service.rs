@@ -0,0 +1,212 @@
+/// Rewrite rule service - Business logic for org-level destination rewrites
+///
+/// Rules live in D1 and are written through to KV so the redirect handler can
+/// apply them without a database round-trip.
+use crate::models::RewriteRule;
+use crate::models::rewrite_rule::{
+    MAX_REWRITE_RESYNC_LINKS, MAX_REWRITE_RULES_PER_ORG, normalize_pattern,
+};
+use crate::repositories::{BlacklistRepository, LinkRepository, RewriteRuleRepository};
+use crate::services::LinkService;
+use crate::utils::{AppError, now_timestamp};
+use worker::console_log;
+use worker::d1::D1Database;
+use worker::kv::KvStore;
+
+/// Result of a dry run: how many active links a rule would affect.
+#[derive(Debug, serde::Serialize)]
+pub struct RewriteDryRun {
+    pub from_pattern: String,
+    pub to_pattern: String,
+    pub matching_links: i64,
+}
+
+/// Service for rewrite rule operations
+#[derive(Default)]
+pub struct RewriteRuleService {
+    repository: RewriteRuleRepository,
+}
+
+impl RewriteRuleService {
+    pub fn new() -> Self {
+        Self {
+            repository: RewriteRuleRepository::new(),
+        }
+    }
+
+    /// List all rules for an org, in evaluation order.
+    pub async fn list_rules(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+    ) -> Result<Vec<RewriteRule>, AppError> {
+        Ok(self.repository.list_by_org(db, org_id).await?)
+    }
+
+    /// Count the active links a rule would rewrite, without saving it.
+    pub async fn dry_run(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        from_pattern: &str,
+        to_pattern: &str,
+    ) -> Result<RewriteDryRun, AppError> {
+        let (from_pattern, to_pattern) = Self::validate_patterns(from_pattern, to_pattern)?;
+        Self::check_to_pattern_allowed(db, &to_pattern).await?;
+        let matching_links = LinkRepository::new()
+            .count_active_with_destination_prefix(db, org_id, &from_pattern)
+            .await?;
+
+        Ok(RewriteDryRun {
+            from_pattern,
+            to_pattern,
+            matching_links,
+        })
+    }
+
+    /// Create a rule and refresh the KV rule cache. If the cache cannot be
+    /// written the rule is removed again, so a failed request saves nothing.
+    /// Callers then run `resync_matching_links` after the response.
+    pub async fn create_rule(
+        &self,
+        db: &D1Database,
+        kv: &KvStore,
+        org_id: &str,
+        user_id: &str,
+        from_pattern: &str,
+        to_pattern: &str,
+    ) -> Result<RewriteRule, AppError> {
+        let (from_pattern, to_pattern) = Self::validate_patterns(from_pattern, to_pattern)?;
+        Self::check_to_pattern_allowed(db, &to_pattern).await?;
+
+        if self.repository.count_by_org(db, org_id).await? >= MAX_REWRITE_RULES_PER_ORG {
+            return Err(AppError::BadRequest(format!(
+                "Organizations can have at most {} rewrite rules",
+                MAX_REWRITE_RULES_PER_ORG
+            )));
+        }
+
+        if self
+            .repository
+            .exists_for_pattern(db, org_id, &from_pattern)
+            .await?
+        {
+            return Err(AppError::Conflict(
+                "A rewrite rule for this from_pattern already exists".to_string(),
+            ));
+        }
+
+        let rule = RewriteRule {
+            id: RewriteRule::generate_id(),
+            org_id: org_id.to_string(),
+            from_pattern,
+            to_pattern,
+            created_by: user_id.to_string(),
+            created_at: now_timestamp(),
+        };
+        self.repository.create(db, &rule).await?;
+
+        if let Err(e) = self.refresh_kv_cache(db, kv, org_id).await {
+            let _ = self.repository.delete(db, &rule.id, org_id).await;
+            return Err(e);
+        }
+
+        Ok(rule)
+    }
+
+    /// Delete a rule and refresh the KV rule cache.
+    pub async fn delete_rule(
+        &self,
+        db: &D1Database,
+        kv: &KvStore,
+        org_id: &str,
+        rule_id: &str,
+    ) -> Result<(), AppError> {
+        self.repository
+            .get_by_id_and_org(db, rule_id, org_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Rewrite rule not found".to_string()))?;
+
+        self.repository.delete(db, rule_id, org_id).await?;
+        self.refresh_kv_cache(db, kv, org_id).await
+    }
+
+    fn validate_patterns(
+        from_pattern: &str,
+        to_pattern: &str,
+    ) -> Result<(String, String), AppError> {
+        let from_pattern =
+            normalize_pattern("from_pattern", from_pattern).map_err(AppError::BadRequest)?;
+        let to_pattern =
+            normalize_pattern("to_pattern", to_pattern).map_err(AppError::BadRequest)?;
+        if from_pattern == to_pattern {
+            return Err(AppError::BadRequest(
+                "from_pattern and to_pattern must differ".to_string(),
+            ));
+        }
+        Ok((from_pattern, to_pattern))
+    }
+
+    /// Links are blacklist-checked on create and update; a rule must not be
+    /// a way around that by sending them to a blocked destination.
+    async fn check_to_pattern_allowed(db: &D1Database, to_pattern: &str) -> Result<(), AppError> {
+        if BlacklistRepository::new()
+            .is_blacklisted(db, to_pattern)
+            .await?
+        {
+            return Err(AppError::Forbidden("to_pattern is blocked".to_string()));
+        }
+        Ok(())
+    }
+
+    async fn refresh_kv_cache(
+        &self,
+        db: &D1Database,
+        kv: &KvStore,
+        org_id: &str,
+    ) -> Result<(), AppError> {
+        let rules = self.repository.list_by_org(db, org_id).await?;
+        crate::kv::store_rewrite_rules(kv, org_id, &rules).await?;
+        Ok(())
+    }
+
+    /// Re-sync the KV mappings of the links a new rule matches: older
+    /// mappings predate the `org_id` field the redirect handler needs to find
+    /// the org's rules. Meant to run after the response; at most
+    /// MAX_REWRITE_RESYNC_LINKS links are re-synced, oldest first since newer
+    /// mappings already carry `org_id`, and failures are only logged.
+    pub async fn resync_matching_links(
+        &self,
+        db: &D1Database,
+        kv: &KvStore,
+        org_id: &str,
+        from_pattern: &str,
+    ) {
+        let resynced = async {
+            let links = LinkRepository::new()
+                .list_active_with_destination_prefix(
+                    db,
+                    org_id,
+                    from_pattern,
+                    MAX_REWRITE_RESYNC_LINKS,
+                )
+                .await?;
+            LinkService::new()
+                .resync_kv_mappings(db, kv, org_id, &links)
+                .await
+        }
+        .await;
+
+        if let Err(e) = resynced {
+            console_log!(
+                "{}",
+                serde_json::json!({
+                    "event": "rewrite_rule_resync_failed",
+                    "org_id": org_id,
+                    "error": e.to_string(),
+                    "level": "warn"
+                })
+            );
+        }
+    }
+}
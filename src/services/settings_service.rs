@@ -2,16 +2,48 @@
 ///
 /// Handles setting validation, business rules, and orchestrates the settings repository.
 use crate::models::Tier;
-use crate::repositories::SettingsRepository;
+use crate::models::analytics_sampling::{
+    AnalyticsSamplingPolicy, MAX_ANALYTICS_SAMPLE_RATE, TIER_SOFT_CAP_KEY_PREFIX,
+};
+use crate::models::api_key::MAX_API_KEY_INACTIVE_DAYS;
+use crate::models::blocked_link::BlockedLinkResponse;
+use crate::models::click_dedup::MAX_CLICK_DEDUP_WINDOW_SECS;
+use crate::models::deleted_link::MAX_DELETED_LINK_RETENTION_DAYS;
+use crate::models::link::{DEFAULT_ORG_LINKS_PER_HOUR, MAX_ORG_LINKS_PER_HOUR};
+use crate::models::link_purge::{DisabledLinkPurgePolicy, MAX_DISABLED_LINK_PURGE_DAYS};
+use crate::models::maintenance::MaintenanceState;
+use crate::models::org_member::{
+    MAX_INVITATION_RESEND_INTERVAL_SECS, MAX_PENDING_INVITATIONS_LIMIT,
+};
+use crate::models::report_limit::{DEFAULT_REPORTS_PER_IP_PER_HOUR, MAX_REPORTS_PER_IP_PER_HOUR};
+use crate::repositories::{SettingsRepository, UserRepository};
 use crate::utils::AppError;
+use crate::utils::now_timestamp;
+use crate::utils::robots::MAX_ROBOTS_TXT_BYTES;
 use crate::utils::short_code::{
     DEFAULT_MIN_CUSTOM_CODE_LENGTH, DEFAULT_MIN_RANDOM_CODE_LENGTH, DEFAULT_SYSTEM_MIN_CODE_LENGTH,
     MAX_SHORT_CODE_LENGTH,
 };
+use crate::utils::url_normalization::{DEFAULT_CANONICAL_STRIP_PARAMS, parse_strip_params};
+use crate::utils::validation::{
+    DEFAULT_DESTINATION_SCHEMES, parse_allowed_destination_schemes, parse_email_domain_list,
+    validate_url,
+};
+use std::cell::RefCell;
 use std::collections::HashMap;
 use worker::d1::D1Database;
 use worker::*;
 
+/// How long an isolate reuses settings read on the redirect path. Admin
+/// changes can take this long to reach other isolates.
+const SETTINGS_CACHE_TTL_SECS: i64 = 30;
+
+// Workers are single-threaded, so thread_local is safe as a per-isolate cache.
+thread_local! {
+    static CACHED_SETTINGS: RefCell<Option<(HashMap<String, String>, i64)>> =
+        const { RefCell::new(None) };
+}
+
 /// Code length settings with effective limits applied
 ///
 /// `system_min_length` is a self-healing high-watermark: when random code
@@ -42,6 +74,29 @@ impl SettingsService {
         self.repository.get_all_settings(db).await
     }
 
+    /// All settings, reused from this isolate's cache for up to
+    /// `SETTINGS_CACHE_TTL_SECS`. For reads on every click, where a D1 query
+    /// per request is too expensive.
+    pub async fn get_all_settings_cached(
+        &self,
+        db: &D1Database,
+    ) -> Result<HashMap<String, String>> {
+        let now = now_timestamp();
+        let cached = CACHED_SETTINGS.with(|c| {
+            c.borrow()
+                .as_ref()
+                .filter(|(_, fetched_at)| now - fetched_at < SETTINGS_CACHE_TTL_SECS)
+                .map(|(settings, _)| settings.clone())
+        });
+        if let Some(settings) = cached {
+            return Ok(settings);
+        }
+
+        let settings = self.repository.get_all_settings(db).await?;
+        CACHED_SETTINGS.with(|c| *c.borrow_mut() = Some((settings.clone(), now)));
+        Ok(settings)
+    }
+
     /// Get a single setting value by key
     #[allow(dead_code)]
     pub async fn get_setting(&self, db: &D1Database, key: &str) -> Result<Option<String>> {
@@ -56,7 +111,24 @@ impl SettingsService {
         key: &str,
         value: &str,
     ) -> Result<HashMap<String, String>, AppError> {
-        // Validate known settings
+        self.validate_setting(db, key, value).await?;
+
+        // Update the setting
+        self.repository.set_setting(db, key, value).await?;
+
+        // Return updated settings; the isolate that made the change sees it at once
+        let settings = self.repository.get_all_settings(db).await?;
+        CACHED_SETTINGS.with(|c| *c.borrow_mut() = Some((settings.clone(), now_timestamp())));
+        Ok(settings)
+    }
+
+    /// Check that `key` is a known setting and `value` is valid for it
+    pub async fn validate_setting(
+        &self,
+        db: &D1Database,
+        key: &str,
+        value: &str,
+    ) -> Result<(), AppError> {
         match key {
             "signups_enabled" => {
                 if value != "true" && value != "false" {
@@ -74,6 +146,187 @@ impl SettingsService {
                     ));
                 }
             }
+            "maintenance_mode"
+            | "maintenance_include_api"
+            | "unique_org_names_per_billing_account"
+            | "disabled_link_purge_require_zero_clicks"
+            | "allow_custom_short_codes"
+            | "anonymous_link_creation_enabled" => {
+                if value != "true" && value != "false" {
+                    return Err(AppError::BadRequest(format!(
+                        "Invalid value for '{}'. Must be 'true' or 'false'",
+                        key
+                    )));
+                }
+            }
+            "allowed_destination_schemes" => {
+                parse_allowed_destination_schemes(value).map_err(|e| {
+                    AppError::BadRequest(format!(
+                        "Invalid value for 'allowed_destination_schemes'. {}",
+                        e
+                    ))
+                })?;
+            }
+            "signup_email_domains" => {
+                parse_email_domain_list(value).map_err(|e| {
+                    AppError::BadRequest(format!(
+                        "Invalid value for 'signup_email_domains'. {}. Must be a comma-separated list of domains, or empty to allow any",
+                        e
+                    ))
+                })?;
+            }
+            "canonicalize_strip_params" => {
+                parse_strip_params(value).map_err(|e| {
+                    AppError::BadRequest(format!(
+                        "Invalid value for 'canonicalize_strip_params'. {}",
+                        e
+                    ))
+                })?;
+            }
+            "disabled_link_purge_days" => {
+                if !value
+                    .parse::<u32>()
+                    .is_ok_and(|days| days <= MAX_DISABLED_LINK_PURGE_DAYS)
+                {
+                    return Err(AppError::BadRequest(format!(
+                        "Invalid value for 'disabled_link_purge_days'. Must be a number of days between 0 (off) and {}",
+                        MAX_DISABLED_LINK_PURGE_DAYS
+                    )));
+                }
+            }
+            "deleted_link_retention_days" => {
+                if !value
+                    .parse::<u32>()
+                    .is_ok_and(|days| days <= MAX_DELETED_LINK_RETENTION_DAYS)
+                {
+                    return Err(AppError::BadRequest(format!(
+                        "Invalid value for 'deleted_link_retention_days'. Must be a number of days between 0 (purge immediately) and {}",
+                        MAX_DELETED_LINK_RETENTION_DAYS
+                    )));
+                }
+            }
+            "api_key_inactive_revoke_days" => {
+                if !value
+                    .parse::<u32>()
+                    .is_ok_and(|days| days <= MAX_API_KEY_INACTIVE_DAYS)
+                {
+                    return Err(AppError::BadRequest(format!(
+                        "Invalid value for 'api_key_inactive_revoke_days'. Must be a number of days between 0 (off) and {}",
+                        MAX_API_KEY_INACTIVE_DAYS
+                    )));
+                }
+            }
+            "max_pending_invitations_per_org" => {
+                if !value
+                    .parse::<i64>()
+                    .is_ok_and(|max| (1..=MAX_PENDING_INVITATIONS_LIMIT).contains(&max))
+                {
+                    return Err(AppError::BadRequest(format!(
+                        "Invalid value for 'max_pending_invitations_per_org'. Must be a number between 1 and {}",
+                        MAX_PENDING_INVITATIONS_LIMIT
+                    )));
+                }
+            }
+            "invitation_resend_interval_seconds" => {
+                if !value
+                    .parse::<i64>()
+                    .is_ok_and(|secs| (0..=MAX_INVITATION_RESEND_INTERVAL_SECS).contains(&secs))
+                {
+                    return Err(AppError::BadRequest(format!(
+                        "Invalid value for 'invitation_resend_interval_seconds'. Must be a number of seconds between 0 (off) and {}",
+                        MAX_INVITATION_RESEND_INTERVAL_SECS
+                    )));
+                }
+            }
+            "analytics_event_soft_cap" => {
+                if !value.parse::<i64>().is_ok_and(|cap| cap >= 0) {
+                    return Err(AppError::BadRequest(
+                        "Invalid value for 'analytics_event_soft_cap'. Must be a number of clicks per link, or 0 (off)"
+                            .to_string(),
+                    ));
+                }
+            }
+            // Per-tier override of analytics_event_soft_cap; blank inherits it
+            _ if key
+                .strip_prefix(TIER_SOFT_CAP_KEY_PREFIX)
+                .and_then(Tier::from_str_value)
+                .is_some() =>
+            {
+                if !value.is_empty() && !value.parse::<i64>().is_ok_and(|cap| cap >= 0) {
+                    return Err(AppError::BadRequest(format!(
+                        "Invalid value for '{}'. Must be a number of clicks per link, 0 (off), or blank to use analytics_event_soft_cap",
+                        key
+                    )));
+                }
+            }
+            "analytics_sample_rate" => {
+                if !value
+                    .parse::<i64>()
+                    .is_ok_and(|rate| (2..=MAX_ANALYTICS_SAMPLE_RATE).contains(&rate))
+                {
+                    return Err(AppError::BadRequest(format!(
+                        "Invalid value for 'analytics_sample_rate'. Must be a number between 2 and {}",
+                        MAX_ANALYTICS_SAMPLE_RATE
+                    )));
+                }
+            }
+            "click_dedup_window_seconds" => {
+                if !value
+                    .parse::<u32>()
+                    .is_ok_and(|secs| secs <= MAX_CLICK_DEDUP_WINDOW_SECS)
+                {
+                    return Err(AppError::BadRequest(format!(
+                        "Invalid value for 'click_dedup_window_seconds'. Must be a number of seconds between 0 (off) and {}",
+                        MAX_CLICK_DEDUP_WINDOW_SECS
+                    )));
+                }
+            }
+            "reports_per_ip_per_hour" => {
+                if !value
+                    .parse::<u32>()
+                    .is_ok_and(|max| max <= MAX_REPORTS_PER_IP_PER_HOUR)
+                {
+                    return Err(AppError::BadRequest(format!(
+                        "Invalid value for 'reports_per_ip_per_hour'. Must be a number between 0 (off) and {}",
+                        MAX_REPORTS_PER_IP_PER_HOUR
+                    )));
+                }
+            }
+            "org_links_per_hour" => {
+                if !value
+                    .parse::<u32>()
+                    .is_ok_and(|max| max <= MAX_ORG_LINKS_PER_HOUR)
+                {
+                    return Err(AppError::BadRequest(format!(
+                        "Invalid value for 'org_links_per_hour'. Must be a number between 0 (off) and {}",
+                        MAX_ORG_LINKS_PER_HOUR
+                    )));
+                }
+            }
+            "blocked_link_response" => {
+                if BlockedLinkResponse::from_setting(value).is_none() {
+                    return Err(AppError::BadRequest(
+                        "Invalid value for 'blocked_link_response'. Must be 'not_found', 'gone' or 'unavailable'"
+                            .to_string(),
+                    ));
+                }
+            }
+            "suspended_link_redirect_url" => {
+                if !value.is_empty() && validate_url(value).is_err() {
+                    return Err(AppError::BadRequest(
+                        "Invalid value for 'suspended_link_redirect_url'. Must be empty or an http(s) URL"
+                            .to_string(),
+                    ));
+                }
+            }
+            "robots_txt" => {
+                if value.len() > MAX_ROBOTS_TXT_BYTES {
+                    return Err(AppError::BadRequest(format!(
+                        "Invalid value for 'robots_txt'. Must be at most {} bytes (empty = default)",
+                        MAX_ROBOTS_TXT_BYTES
+                    )));
+                }
+            }
             "founder_pricing_active" => {
                 if value != "true" && value != "false" {
                     return Err(AppError::BadRequest(
@@ -118,13 +371,18 @@ impl SettingsService {
                 return Err(AppError::BadRequest(format!("Unknown setting: {}", key)));
             }
         }
+        Ok(())
+    }
 
-        // Update the setting
-        self.repository.set_setting(db, key, value).await?;
-
-        // Return updated settings
-        let settings = self.repository.get_all_settings(db).await?;
-        Ok(settings)
+    /// Write the maintenance state derived from `settings` to its KV cache,
+    /// which is what the redirect path actually reads.
+    pub async fn sync_maintenance_cache(
+        &self,
+        kv: &worker::kv::KvStore,
+        settings: &HashMap<String, String>,
+    ) -> Result<()> {
+        let state = MaintenanceState::from_settings(settings);
+        crate::kv::store_maintenance_state(kv, &state).await
     }
 
     /// Fetch all code length related settings in a single query and calculate
@@ -154,6 +412,173 @@ impl SettingsService {
         })
     }
 
+    /// Destination URL schemes links may use. Falls back to http/https when the
+    /// setting is missing or invalid so a bad value never widens what is accepted.
+    pub async fn get_allowed_destination_schemes(&self, db: &D1Database) -> Result<Vec<String>> {
+        let value = self
+            .repository
+            .get_setting(db, "allowed_destination_schemes")
+            .await?;
+        Ok(allowed_schemes_from_setting(value.as_deref()))
+    }
+
+    /// Tracking parameter patterns stripped when a link is created with
+    /// `canonicalize=true`. Falls back to the defaults when unset or invalid.
+    pub async fn get_canonicalize_strip_params(&self, db: &D1Database) -> Result<Vec<String>> {
+        let value = self
+            .repository
+            .get_setting(db, "canonicalize_strip_params")
+            .await?;
+        Ok(value
+            .and_then(|v| parse_strip_params(&v).ok())
+            .unwrap_or_else(|| {
+                DEFAULT_CANONICAL_STRIP_PARAMS
+                    .iter()
+                    .map(|p| p.to_string())
+                    .collect()
+            }))
+    }
+
+    /// Disabled-link purge policy, or None when purging is off
+    pub async fn get_disabled_link_purge_policy(
+        &self,
+        db: &D1Database,
+    ) -> Result<Option<DisabledLinkPurgePolicy>> {
+        let settings = self.repository.get_all_settings(db).await?;
+        Ok(DisabledLinkPurgePolicy::from_settings(&settings))
+    }
+
+    /// Raw analytics event sampling policy, or None when every click is stored
+    pub async fn get_analytics_sampling_policy(
+        &self,
+        db: &D1Database,
+    ) -> Result<Option<AnalyticsSamplingPolicy>> {
+        let settings = self.get_all_settings_cached(db).await?;
+        Ok(AnalyticsSamplingPolicy::from_settings(&settings))
+    }
+
+    /// Seconds within which repeat clicks by the same visitor are not
+    /// counted (0 = count every click). Read on every click, so it comes
+    /// from the in-isolate settings cache.
+    pub async fn get_click_dedup_window(&self, db: &D1Database) -> Result<u32> {
+        Ok(self
+            .get_all_settings_cached(db)
+            .await?
+            .get("click_dedup_window_seconds")
+            .and_then(|v| v.parse::<u32>().ok())
+            .filter(|secs| *secs <= MAX_CLICK_DEDUP_WINDOW_SECS)
+            .unwrap_or(0))
+    }
+
+    /// Anonymous abuse reports accepted per IP per hour (0 = no limit)
+    pub async fn get_reports_per_ip_per_hour(&self, db: &D1Database) -> Result<u32> {
+        Ok(self
+            .repository
+            .get_setting(db, "reports_per_ip_per_hour")
+            .await?
+            .and_then(|v| v.parse::<u32>().ok())
+            .filter(|max| *max <= MAX_REPORTS_PER_IP_PER_HOUR)
+            .unwrap_or(DEFAULT_REPORTS_PER_IP_PER_HOUR))
+    }
+
+    /// Links an org may create per hour across all members (0 = no limit)
+    pub async fn get_org_links_per_hour(&self, db: &D1Database) -> Result<u32> {
+        Ok(self
+            .repository
+            .get_setting(db, "org_links_per_hour")
+            .await?
+            .and_then(|v| v.parse::<u32>().ok())
+            .filter(|max| *max <= MAX_ORG_LINKS_PER_HOUR)
+            .unwrap_or(DEFAULT_ORG_LINKS_PER_HOUR))
+    }
+
+    /// Response served for blocked links (default: the /404 redirect)
+    pub async fn get_blocked_link_response(&self, db: &D1Database) -> Result<BlockedLinkResponse> {
+        Ok(self
+            .repository
+            .get_setting(db, "blocked_link_response")
+            .await?
+            .and_then(|v| BlockedLinkResponse::from_setting(&v))
+            .unwrap_or_default())
+    }
+
+    /// Where links disabled by their owner's suspension redirect to, or None
+    /// for the /404 page like any other disabled link
+    pub async fn get_suspended_link_redirect_url(&self, db: &D1Database) -> Result<Option<String>> {
+        Ok(self
+            .repository
+            .get_setting(db, "suspended_link_redirect_url")
+            .await?
+            .filter(|url| validate_url(url).is_ok()))
+    }
+
+    /// robots.txt body configured for the redirect domain, or None to use
+    /// the default
+    pub async fn get_robots_txt(&self, db: &D1Database) -> Result<Option<String>> {
+        Ok(self
+            .repository
+            .get_setting(db, "robots_txt")
+            .await?
+            .filter(|v| !v.trim().is_empty()))
+    }
+
+    /// Days without use after which API keys are revoked, or None when off
+    pub async fn get_api_key_inactive_revoke_days(&self, db: &D1Database) -> Result<Option<u32>> {
+        Ok(self
+            .repository
+            .get_setting(db, "api_key_inactive_revoke_days")
+            .await?
+            .and_then(|v| v.parse::<u32>().ok())
+            .filter(|days| (1..=MAX_API_KEY_INACTIVE_DAYS).contains(days)))
+    }
+
+    /// Days admin-deleted links keep their analytics, or None when deletion
+    /// purges them immediately (the default)
+    pub async fn get_deleted_link_retention_days(&self, db: &D1Database) -> Result<Option<u32>> {
+        Ok(self
+            .repository
+            .get_setting(db, "deleted_link_retention_days")
+            .await?
+            .and_then(|v| v.parse::<u32>().ok())
+            .filter(|days| (1..=MAX_DELETED_LINK_RETENTION_DAYS).contains(days)))
+    }
+
+    /// Whether links may be created with a caller-chosen short code at all.
+    /// Off forces random codes instance-wide; the tier check still applies
+    /// on top when it is on.
+    pub async fn are_custom_short_codes_allowed(&self, db: &D1Database) -> Result<bool> {
+        Ok(self
+            .repository
+            .get_setting(db, "allow_custom_short_codes")
+            .await?
+            .is_none_or(|v| v == "true"))
+    }
+
+    /// Whether links may be created without an account via
+    /// POST /api/links/anonymous (default off).
+    pub async fn are_anonymous_links_enabled(&self, db: &D1Database) -> Result<bool> {
+        Ok(self
+            .repository
+            .get_setting(db, "anonymous_link_creation_enabled")
+            .await?
+            .is_some_and(|v| v == "true"))
+    }
+
+    /// Whether a new user may sign up right now. Mirrors the OAuth callback:
+    /// the `signups_enabled` setting (default on), except that the first
+    /// user of an empty instance is always allowed.
+    pub async fn are_signups_enabled(&self, db: &D1Database) -> Result<bool> {
+        let enabled = self
+            .repository
+            .get_setting(db, "signups_enabled")
+            .await?
+            .is_none_or(|v| v == "true");
+        if enabled {
+            return Ok(true);
+        }
+        Ok(UserRepository::new().count(db).await? == 0)
+    }
+
     /// Get public settings for frontend consumption
     pub async fn get_public_settings(
         &self,
@@ -205,6 +630,24 @@ impl SettingsService {
             "active_discount_amount_business_monthly": get_setting_i64("active_discount_amount_business_monthly", 0),
             "active_discount_amount_business_annual": get_setting_i64("active_discount_amount_business_annual", 0),
             "email_notifications_enabled": email_notifications_enabled,
+            "maintenance_mode": MaintenanceState::from_settings(&settings).enabled,
+            "allow_custom_short_codes": settings
+                .get("allow_custom_short_codes")
+                .is_none_or(|v| v == "true"),
+            "allowed_destination_schemes": allowed_schemes_from_setting(
+                settings.get("allowed_destination_schemes").map(|v| v.as_str())
+            ),
         }))
     }
 }
+
+fn allowed_schemes_from_setting(value: Option<&str>) -> Vec<String> {
+    value
+        .and_then(|v| parse_allowed_destination_schemes(v).ok())
+        .unwrap_or_else(|| {
+            DEFAULT_DESTINATION_SCHEMES
+                .iter()
+                .map(|s| s.to_string())
+                .collect()
+        })
+}
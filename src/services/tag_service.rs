@@ -1,9 +1,11 @@
 /// Tag service - Business logic for tag operations
 ///
 /// Handles tag validation, business rules, and orchestrates the tag repository.
-use crate::repositories::TagRepository;
-use crate::repositories::tag_repository::{OrgTag, SimilarTagGroup};
-use crate::utils::normalize_tag;
+use crate::repositories::tag_repository::{OrgTag, SimilarTagGroup, TagMatch};
+use crate::repositories::{LinkRepository, TagRepository};
+use crate::utils::tags::{MAX_TAGS_PER_LINK, suggestion_domains, suggestion_host};
+use crate::utils::{AppError, normalize_tag};
+use std::collections::{HashMap, HashSet};
 use worker::d1::D1Database;
 use worker::*;
 
@@ -33,6 +35,53 @@ pub struct MergeResult {
     pub destination_tag: String,
 }
 
+/// Maximum number of links a single bulk tag request may touch
+pub const MAX_BULK_TAG_LINKS: usize = 100;
+
+/// Request to add/remove tags across many links
+#[derive(Debug, serde::Deserialize, utoipa::ToSchema)]
+pub struct BulkTagsRequest {
+    pub ids: Vec<String>,
+    #[serde(default)]
+    pub add: Vec<String>,
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+/// Per-link outcome of a bulk tag operation
+#[derive(Debug, serde::Serialize)]
+pub struct BulkTagLinkResult {
+    pub id: String,
+    pub added: usize,
+    pub removed: usize,
+    pub tag_count: usize,
+}
+
+/// Result of a bulk tag operation
+#[derive(Debug, serde::Serialize)]
+pub struct BulkTagsResult {
+    pub links: Vec<BulkTagLinkResult>,
+    /// Requested IDs that are not links of this organization (ignored)
+    pub not_found: Vec<String>,
+}
+
+/// Tag suggestions for a destination URL
+#[derive(Debug, serde::Serialize)]
+pub struct TagSuggestions {
+    /// Normalized destination host the suggestions were derived from
+    pub domain: String,
+    /// Suggested tags: the org's own tags used on the same domain first
+    /// (most used first), then globally configured domain tags
+    pub suggestions: Vec<String>,
+}
+
+/// Maximum number of suggestions returned
+const MAX_TAG_SUGGESTIONS: usize = 10;
+
+/// Default and maximum number of tags returned by autocomplete
+pub const DEFAULT_TAG_AUTOCOMPLETE_LIMIT: i64 = 10;
+pub const MAX_TAG_AUTOCOMPLETE_LIMIT: i64 = 50;
+
 /// Service for tag operations
 #[derive(Default)]
 pub struct TagService {
@@ -52,6 +101,134 @@ impl TagService {
         self.repository.get_org_tags(db, org_id).await
     }
 
+    /// Tags whose name starts with `prefix` (case-insensitive), most used first.
+    pub async fn autocomplete_tags(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        prefix: &str,
+        limit: i64,
+    ) -> Result<Vec<TagMatch>> {
+        self.repository
+            .autocomplete_tags(db, org_id, prefix.trim(), limit)
+            .await
+    }
+
+    /// Suggest tags for a destination URL from the org's existing tags on the
+    /// same domain and the global domain -> tag mapping.
+    pub async fn suggest_tags(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        url: &str,
+    ) -> Result<TagSuggestions> {
+        let host = suggestion_host(url)
+            .ok_or_else(|| worker::Error::RustError("URL has no host".to_string()))?;
+        let domains = suggestion_domains(&host);
+
+        // Org tags used on links pointing at this host (or a subdomain of it)
+        let mut counts: HashMap<String, i64> = HashMap::new();
+        for (tag, destination) in self
+            .repository
+            .get_tagged_destinations_matching(db, org_id, &host)
+            .await?
+        {
+            let matches = suggestion_host(&destination)
+                .map(|h| h == host || h.ends_with(&format!(".{}", host)))
+                .unwrap_or(false);
+            if matches {
+                *counts.entry(tag).or_insert(0) += 1;
+            }
+        }
+        let mut org_tags: Vec<(String, i64)> = counts.into_iter().collect();
+        org_tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        let mapped = self
+            .repository
+            .get_domain_tag_suggestions(db, &domains)
+            .await?;
+
+        let mut seen = HashSet::new();
+        let suggestions = org_tags
+            .into_iter()
+            .map(|(tag, _)| tag)
+            .chain(mapped)
+            .filter(|tag| seen.insert(tag.to_lowercase()))
+            .take(MAX_TAG_SUGGESTIONS)
+            .collect();
+
+        Ok(TagSuggestions {
+            domain: host,
+            suggestions,
+        })
+    }
+
+    /// Add and remove tags across the org's links. `add`/`remove` must already
+    /// be normalized. Tag names match case-insensitively, so adding `Dev` to a
+    /// link tagged `dev` is a no-op. Fails without changing anything if any link
+    /// would end up above the per-link tag cap.
+    pub async fn bulk_update_link_tags(
+        &self,
+        db: &D1Database,
+        org_id: &str,
+        ids: &[String],
+        add: &[String],
+        remove: &[String],
+    ) -> std::result::Result<BulkTagsResult, AppError> {
+        let link_repo = LinkRepository::new();
+        let mut requested: Vec<String> = Vec::with_capacity(ids.len());
+        for id in ids {
+            if !requested.contains(id) {
+                requested.push(id.clone());
+            }
+        }
+        let owned = link_repo.filter_ids_in_org(db, org_id, &requested).await?;
+        let (found, not_found): (Vec<String>, Vec<String>) =
+            requested.into_iter().partition(|id| owned.contains(id));
+
+        let current = link_repo.get_tags_for_links(db, &found).await?;
+        let remove_lower: HashSet<String> = remove.iter().map(|t| t.to_lowercase()).collect();
+
+        let mut adds: Vec<(String, String)> = Vec::new();
+        let mut removes: Vec<(String, String)> = Vec::new();
+        let mut links = Vec::with_capacity(found.len());
+        for id in found {
+            let existing = current.get(&id).cloned().unwrap_or_default();
+            let (removed, mut kept): (Vec<String>, Vec<String>) = existing
+                .into_iter()
+                .partition(|t| remove_lower.contains(&t.to_lowercase()));
+            let mut added = 0;
+            for tag in add {
+                if !kept.iter().any(|t| t.to_lowercase() == tag.to_lowercase()) {
+                    kept.push(tag.clone());
+                    adds.push((id.clone(), tag.clone()));
+                    added += 1;
+                }
+            }
+            if kept.len() > MAX_TAGS_PER_LINK {
+                return Err(AppError::BadRequest(format!(
+                    "Link {} would have {} tags; the maximum is {} per link",
+                    id,
+                    kept.len(),
+                    MAX_TAGS_PER_LINK
+                )));
+            }
+            links.push(BulkTagLinkResult {
+                id: id.clone(),
+                added,
+                removed: removed.len(),
+                tag_count: kept.len(),
+            });
+            removes.extend(removed.into_iter().map(|tag| (id.clone(), tag)));
+        }
+
+        link_repo
+            .apply_tag_changes(db, org_id, &adds, &removes)
+            .await?;
+
+        Ok(BulkTagsResult { links, not_found })
+    }
+
     /// Create a new tag manually (without a link).
     /// Returns the updated tag list and whether the tag was newly created.
     pub async fn create_tag(
@@ -0,0 +1,156 @@
+/// CIDR ranges for IP allowlists
+///
+/// Parses entries such as `10.0.0.0/8`, `2001:db8::/32` or a bare address
+/// (treated as a single-host range) and tests client IPs against them.
+/// IPv4-mapped IPv6 clients (`::ffff:10.0.0.1`) match IPv4 ranges.
+use std::net::IpAddr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpCidr {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpCidr {
+    /// Parse `addr/prefix` or a bare address. Returns None for malformed
+    /// input or a prefix longer than the address family allows.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let network: IpAddr = addr.parse().ok()?;
+        let max_len = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix {
+            Some(p) => p.parse::<u8>().ok().filter(|len| *len <= max_len)?,
+            None => max_len,
+        };
+        Some(Self {
+            network,
+            prefix_len,
+        })
+    }
+
+    /// Whether `ip` falls inside this range. Addresses of the other family
+    /// never match.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Parse a comma- or whitespace-separated CIDR list, skipping invalid
+/// entries. Returns the parsed ranges and the entries that were rejected.
+pub fn parse_cidr_list(list: &str) -> (Vec<IpCidr>, Vec<String>) {
+    let mut ranges = Vec::new();
+    let mut invalid = Vec::new();
+    for entry in list
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|e| !e.is_empty())
+    {
+        match IpCidr::parse(entry) {
+            Some(range) => ranges.push(range),
+            None => invalid.push(entry.to_string()),
+        }
+    }
+    (ranges, invalid)
+}
+
+/// Whether the textual client IP falls inside any of `ranges`. Unparseable
+/// IPs (e.g. the "unknown" placeholder) never match.
+pub fn ip_in_ranges(ip: &str, ranges: &[IpCidr]) -> bool {
+    match ip.trim().parse::<IpAddr>() {
+        Ok(ip) => ranges.iter().any(|range| range.contains(ip)),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_ipv4_cidr_membership() {
+        let range = IpCidr::parse("10.1.0.0/16").unwrap();
+        assert!(range.contains(ip("10.1.0.1")));
+        assert!(range.contains(ip("10.1.255.255")));
+        assert!(!range.contains(ip("10.2.0.1")));
+        assert!(!range.contains(ip("::1")));
+    }
+
+    #[test]
+    fn test_ipv6_cidr_membership() {
+        let range = IpCidr::parse("2001:db8::/32").unwrap();
+        assert!(range.contains(ip("2001:db8::1")));
+        assert!(range.contains(ip("2001:db8:ffff:ffff::1")));
+        assert!(!range.contains(ip("2001:db9::1")));
+        assert!(!range.contains(ip("10.0.0.1")));
+    }
+
+    #[test]
+    fn test_bare_address_and_edge_prefixes() {
+        let single = IpCidr::parse("192.0.2.7").unwrap();
+        assert!(single.contains(ip("192.0.2.7")));
+        assert!(!single.contains(ip("192.0.2.8")));
+
+        let single_v6 = IpCidr::parse("2001:db8::7").unwrap();
+        assert!(single_v6.contains(ip("2001:db8::7")));
+        assert!(!single_v6.contains(ip("2001:db8::8")));
+
+        let all_v4 = IpCidr::parse("0.0.0.0/0").unwrap();
+        assert!(all_v4.contains(ip("203.0.113.9")));
+        let all_v6 = IpCidr::parse("::/0").unwrap();
+        assert!(all_v6.contains(ip("fe80::1")));
+    }
+
+    #[test]
+    fn test_ipv4_mapped_ipv6_matches_ipv4_range() {
+        let range = IpCidr::parse("10.0.0.0/8").unwrap();
+        assert!(range.contains(ip("::ffff:10.0.0.1")));
+    }
+
+    #[test]
+    fn test_invalid_cidrs_are_rejected() {
+        for entry in [
+            "",
+            "10.0.0.0/33",
+            "::/129",
+            "10.0.0.0/",
+            "10.0.0/8",
+            "nope/8",
+            "10.0.0.0/-1",
+        ] {
+            assert_eq!(IpCidr::parse(entry), None, "{entry:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_parse_cidr_list_and_lookup() {
+        let (ranges, invalid) = parse_cidr_list("10.0.0.0/8, 2001:db8::/32 bogus\n192.0.2.1");
+        assert_eq!(ranges.len(), 3);
+        assert_eq!(invalid, vec!["bogus".to_string()]);
+        assert!(ip_in_ranges("10.9.8.7", &ranges));
+        assert!(ip_in_ranges("2001:db8::abcd", &ranges));
+        assert!(ip_in_ranges("192.0.2.1", &ranges));
+        assert!(!ip_in_ranges("192.0.2.2", &ranges));
+        assert!(!ip_in_ranges("unknown", &ranges));
+    }
+}
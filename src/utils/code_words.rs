@@ -0,0 +1,117 @@
+//! Disallowed words in custom short codes
+//!
+//! Admins keep a list of `deny` words that may not appear anywhere in a
+//! user-chosen code, and `allow` words that exempt benign codes containing one.
+
+/// Kinds of entries in the admin-managed `disallowed_code_words` list.
+/// `deny` words may not appear in custom codes; `allow` words exempt benign
+/// codes that happen to contain a denied word (e.g. "class" for "ass").
+pub const CODE_WORD_KINDS: &[&str] = &["deny", "allow"];
+
+/// Longest accepted word, matching the longest short-code segment.
+pub const MAX_CODE_WORD_LENGTH: usize = 50;
+
+/// Fold a code or word to the form words are matched in: lowercase, common
+/// leetspeak digits mapped to letters, and separators dropped, so "B4d-W0rd"
+/// and "badword" compare equal.
+pub fn normalize_code_word(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| *c != '-' && *c != '/')
+        .flat_map(char::to_lowercase)
+        .map(|c| match c {
+            '0' => 'o',
+            '1' => 'i',
+            '3' => 'e',
+            '4' => 'a',
+            '5' => 's',
+            '7' => 't',
+            '8' => 'b',
+            '9' => 'g',
+            other => other,
+        })
+        .collect()
+}
+
+/// The deny and allow words, normalized, as checked against custom codes.
+#[derive(Debug, Clone, Default)]
+pub struct DisallowedWords {
+    deny: Vec<String>,
+    allow: Vec<String>,
+}
+
+impl DisallowedWords {
+    /// Build the list from `(word, kind)` pairs; unknown kinds are ignored.
+    pub fn new<'a>(entries: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut words = Self::default();
+        for (word, kind) in entries {
+            let word = normalize_code_word(word);
+            if word.is_empty() {
+                continue;
+            }
+            match kind {
+                "deny" => words.deny.push(word),
+                "allow" => words.allow.push(word),
+                _ => {}
+            }
+        }
+        words
+    }
+
+    /// Whether `code` contains a denied word outside every allowed word.
+    pub fn matches(&self, code: &str) -> bool {
+        if self.deny.is_empty() {
+            return false;
+        }
+        let normalized = normalize_code_word(code);
+
+        let mut allowed = vec![false; normalized.len()];
+        for word in &self.allow {
+            for (start, found) in normalized.match_indices(word.as_str()) {
+                allowed[start..start + found.len()].fill(true);
+            }
+        }
+
+        self.deny.iter().any(|word| {
+            normalized
+                .match_indices(word.as_str())
+                .any(|(start, found)| !allowed[start..start + found.len()].iter().all(|a| *a))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_code_word_folds_case_leetspeak_and_separators() {
+        assert_eq!(normalize_code_word("B4d-W0rd"), "badword");
+        assert_eq!(normalize_code_word("a/5/5"), "ass");
+    }
+
+    #[test]
+    fn test_disallowed_words_match_substrings_and_leetspeak() {
+        let words = DisallowedWords::new([("ass", "deny")]);
+        assert!(words.matches("kickass"));
+        assert!(words.matches("KICK-A55"));
+        assert!(words.matches("my/a-s-s/page"));
+        assert!(!words.matches("kickball"));
+    }
+
+    #[test]
+    fn test_disallowed_words_allow_list_exempts_false_positives() {
+        let words = DisallowedWords::new([("ass", "deny"), ("class", "allow")]);
+        assert!(!words.matches("classroom"));
+        assert!(!words.matches("Cla55-2024"));
+        // The exemption only covers the allowed word itself
+        assert!(words.matches("class-ass"));
+        assert!(words.matches("assclass"));
+    }
+
+    #[test]
+    fn test_disallowed_words_empty_list_matches_nothing() {
+        assert!(!DisallowedWords::default().matches("anything"));
+        assert!(!DisallowedWords::new([("--", "deny")]).matches("a--b"));
+    }
+}
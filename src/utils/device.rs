@@ -73,6 +73,33 @@ pub fn detect_device(user_agent: &str) -> DeviceType {
     DeviceType::Other
 }
 
+/// User-Agent fragments (lowercase) of the link-preview fetchers used by
+/// social networks and chat apps to unfurl shared links.
+const CRAWLER_UA_MARKERS: &[&str] = &[
+    "facebookexternalhit",
+    "facebot",
+    "twitterbot",
+    "linkedinbot",
+    "slackbot",
+    "discordbot",
+    "telegrambot",
+    "whatsapp",
+    "pinterest",
+    "redditbot",
+    "skypeuripreview",
+    "mastodon",
+    "embedly",
+    "iframely",
+    "vkshare",
+];
+
+/// Whether a User-Agent belongs to a link-preview crawler. Search engine
+/// bots are not included: they follow redirects and should keep seeing them.
+pub fn is_crawler(user_agent: &str) -> bool {
+    let ua = user_agent.to_lowercase();
+    CRAWLER_UA_MARKERS.iter().any(|marker| ua.contains(marker))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -184,6 +211,21 @@ mod tests {
         assert_eq!(detect_device(UA_EMPTY), DeviceType::Other);
     }
 
+    #[test]
+    fn test_is_crawler() {
+        assert!(is_crawler(
+            "facebookexternalhit/1.1 (+http://www.facebook.com/externalhit_uatext.php)"
+        ));
+        assert!(is_crawler("Twitterbot/1.0"));
+        assert!(is_crawler(
+            "Slackbot-LinkExpanding 1.0 (+https://api.slack.com/robots)"
+        ));
+        assert!(!is_crawler(UA_GOOGLEBOT));
+        assert!(!is_crawler(UA_IPHONE_SAFARI));
+        assert!(!is_crawler(UA_WINDOWS_CHROME));
+        assert!(!is_crawler(UA_EMPTY));
+    }
+
     #[test]
     fn test_device_type_as_str() {
         assert_eq!(DeviceType::IOS.as_str(), "ios");
@@ -26,41 +26,36 @@ pub struct OrgMonthlySummary {
     pub top_links: Vec<TopLinkSummary>,
 }
 
-/// Send an organization invitation email via Mailgun
-pub async fn send_org_invitation(
-    env: &Env,
-    to_email: &str,
+/// Subject, HTML and plain-text bodies of an organization invitation.
+/// `custom_subject` replaces the default subject; `custom_message` is shown
+/// as a note from the org above the accept button.
+pub fn render_org_invitation(
     inviter_name: &str,
     org_name: &str,
     invite_url: &str,
-) -> Result<()> {
-    let api_key = env
-        .var("MAILGUN_API_KEY")
-        .map(|v| v.to_string())
-        .unwrap_or_default();
-    let base_url: String = env
-        .var("MAILGUN_BASE_URL")
-        .map(|v| v.to_string())
+    custom_subject: Option<&str>,
+    custom_message: Option<&str>,
+) -> (String, String, String) {
+    let subject = match custom_subject {
+        Some(subject) => subject.to_string(),
+        None => format!(
+            "{} invited you to join {} on Rushomon",
+            inviter_name, org_name
+        ),
+    };
+
+    let message_html = custom_message
+        .map(|message| {
+            format!(
+                r#"
+  <blockquote style="margin: 24px 0; padding: 12px 16px; border-left: 4px solid #f97316; background: #fff7ed; color: #374151;">{}</blockquote>"#,
+                escape_html(message).replace('\n', "<br>")
+            )
+        })
         .unwrap_or_default();
-    let domain = env
-        .var("MAILGUN_DOMAIN")
-        .map(|v| v.to_string())
+    let message_text = custom_message
+        .map(|message| format!("\n\n{}", message))
         .unwrap_or_default();
-    let from: String = env
-        .var("MAILGUN_FROM")
-        .map(|v| v.to_string())
-        .unwrap_or_else(|_| format!("invites@{}", domain));
-
-    if api_key.is_empty() || domain.is_empty() {
-        return Err(worker::Error::RustError(
-            "Mailgun not configured: MAILGUN_API_KEY and MAILGUN_DOMAIN are required".to_string(),
-        ));
-    }
-
-    let subject = format!(
-        "{} invited you to join {} on Rushomon",
-        inviter_name, org_name
-    );
 
     let html_body = format!(
         r#"<!DOCTYPE html>
@@ -68,7 +63,7 @@ pub async fn send_org_invitation(
 <head><meta charset="utf-8"></head>
 <body style="font-family: Arial, sans-serif; max-width: 600px; margin: 0 auto; padding: 20px; color: #1f2937;">
   <h2 style="color: #ea580c;">You've been invited to join {org_name}</h2>
-  <p><strong>{inviter_name}</strong> has invited you to join their organization <strong>{org_name}</strong> on Rushomon.</p>
+  <p><strong>{inviter_name}</strong> has invited you to join their organization <strong>{org_name}</strong> on Rushomon.</p>{message_html}
   <p>Click the button below to accept the invitation. This invite expires in 7 days.</p>
   <p style="margin: 32px 0;">
     <a href="{invite_url}"
@@ -90,11 +85,56 @@ pub async fn send_org_invitation(
         org_name = org_name,
         inviter_name = inviter_name,
         invite_url = invite_url,
+        message_html = message_html,
     );
 
     let text_body = format!(
-        "{} has invited you to join {} on Rushomon.\n\nAccept the invitation here: {}\n\nThis invite expires in 7 days.\n\nIf you did not expect this invitation, you can safely ignore this email.",
-        inviter_name, org_name, invite_url
+        "{} has invited you to join {} on Rushomon.{}\n\nAccept the invitation here: {}\n\nThis invite expires in 7 days.\n\nIf you did not expect this invitation, you can safely ignore this email.",
+        inviter_name, org_name, message_text, invite_url
+    );
+
+    (subject, html_body, text_body)
+}
+
+/// Send an organization invitation email via Mailgun
+pub async fn send_org_invitation(
+    env: &Env,
+    to_email: &str,
+    inviter_name: &str,
+    org_name: &str,
+    invite_url: &str,
+    custom_subject: Option<&str>,
+    custom_message: Option<&str>,
+) -> Result<()> {
+    let api_key = env
+        .var("MAILGUN_API_KEY")
+        .map(|v| v.to_string())
+        .unwrap_or_default();
+    let base_url: String = env
+        .var("MAILGUN_BASE_URL")
+        .map(|v| v.to_string())
+        .unwrap_or_default();
+    let domain = env
+        .var("MAILGUN_DOMAIN")
+        .map(|v| v.to_string())
+        .unwrap_or_default();
+    let from: String = env
+        .var("MAILGUN_FROM")
+        .map(|v| v.to_string())
+        .unwrap_or_else(|_| format!("invites@{}", domain));
+
+    if api_key.is_empty() || domain.is_empty() {
+        return Err(worker::Error::RustError(
+            "Mailgun not configured: MAILGUN_API_KEY and MAILGUN_DOMAIN are required".to_string(),
+        ));
+    }
+
+    let (subject, html_body, text_body) = render_org_invitation(
+        inviter_name,
+        org_name,
+        invite_url,
+        custom_subject,
+        custom_message,
     );
 
     send_via_mailgun(
@@ -343,7 +383,7 @@ pub async fn send_monthly_stats_email(
 // ── Private helpers ──────────────────────────────────────────────────────────
 
 /// Escape the minimum set of HTML special characters.
-fn escape_html(s: &str) -> String {
+pub fn escape_html(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
@@ -787,6 +827,31 @@ mod tests {
         assert_eq!(escape_html("a&b&c"), "a&amp;b&amp;c");
     }
 
+    // ── render_org_invitation ─────────────────────────────────────────────────
+
+    #[test]
+    fn test_invitation_default_wording() {
+        let (subject, html, text) =
+            render_org_invitation("Ann", "Acme", "https://app/invite/1", None, None);
+        assert_eq!(subject, "Ann invited you to join Acme on Rushomon");
+        assert!(!html.contains("<blockquote"));
+        assert!(text.starts_with("Ann has invited you to join Acme on Rushomon.\n\nAccept"));
+    }
+
+    #[test]
+    fn test_invitation_custom_subject_and_message() {
+        let (subject, html, text) = render_org_invitation(
+            "Ann",
+            "Acme",
+            "https://app/invite/1",
+            Some("Come build with Acme"),
+            Some("Hi <team>,\nsee you Monday!"),
+        );
+        assert_eq!(subject, "Come build with Acme");
+        assert!(html.contains("Hi &lt;team&gt;,<br>see you Monday!</blockquote>"));
+        assert!(text.contains("on Rushomon.\n\nHi <team>,\nsee you Monday!\n\nAccept"));
+    }
+
     // ── build_trend_html ──────────────────────────────────────────────────────
 
     #[test]
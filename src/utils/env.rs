@@ -49,3 +49,154 @@ pub fn is_mailgun_configured(env: &Env) -> bool {
         .unwrap_or_default();
     !api_key.is_empty() && !domain.is_empty()
 }
+
+/// Where `GET /` redirects to on the domain the request came in on, or None
+/// to serve no redirect (404, so the frontend domain gets its SPA shell).
+///
+/// When one Worker serves both domains, the redirect domain's root defaults to
+/// the frontend and the frontend's root has no default, since redirecting it
+/// to `FRONTEND_URL` would point it at itself. `ROOT_REDIRECT_URL` and
+/// `FRONTEND_ROOT_REDIRECT_URL` override each domain's target.
+pub fn get_root_redirect_target(env: &Env, is_frontend_domain: bool) -> Option<String> {
+    let var = |name: &str| env.var(name).ok().map(|v| v.to_string());
+    resolve_root_redirect_target(
+        is_frontend_domain,
+        &get_frontend_url(env),
+        var("ROOT_REDIRECT_URL").as_deref(),
+        var("FRONTEND_ROOT_REDIRECT_URL").as_deref(),
+    )
+}
+
+fn resolve_root_redirect_target(
+    is_frontend_domain: bool,
+    frontend_url: &str,
+    redirect_domain_root: Option<&str>,
+    frontend_domain_root: Option<&str>,
+) -> Option<String> {
+    let configured = |value: Option<&str>| {
+        value
+            .map(str::trim)
+            .filter(|v| worker::Url::parse(v).is_ok())
+            .map(str::to_string)
+    };
+    if is_frontend_domain {
+        configured(frontend_domain_root)
+    } else {
+        configured(redirect_domain_root).or_else(|| Some(frontend_url.to_string()))
+    }
+}
+
+/// Path prefixes that never get the SPA shell on a 404, so crawlers and
+/// tooling see a real 404. `/api/` is always excluded.
+pub const DEFAULT_SPA_FALLBACK_EXCLUDES: [&str; 5] = [
+    "/api/",
+    "/.well-known/",
+    "/robots.txt",
+    "/sitemap.xml",
+    "/favicon.ico",
+];
+
+/// SPA fallback exclusions: the defaults plus any comma-separated prefixes
+/// from `SPA_FALLBACK_EXCLUDE`.
+pub fn get_spa_fallback_excludes(env: &Env) -> Vec<String> {
+    parse_spa_fallback_excludes(
+        env.var("SPA_FALLBACK_EXCLUDE")
+            .ok()
+            .map(|v| v.to_string())
+            .as_deref(),
+    )
+}
+
+fn parse_spa_fallback_excludes(value: Option<&str>) -> Vec<String> {
+    let mut prefixes: Vec<String> = DEFAULT_SPA_FALLBACK_EXCLUDES
+        .iter()
+        .map(|p| p.to_string())
+        .collect();
+    for prefix in value
+        .unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|p| p.starts_with('/') && p.len() > 1)
+    {
+        if !prefixes.iter().any(|p| p == prefix) {
+            prefixes.push(prefix.to_string());
+        }
+    }
+    prefixes
+}
+
+/// Whether a 404 on `path` must be returned as-is instead of the SPA shell
+pub fn is_spa_fallback_excluded(path: &str, excludes: &[String]) -> bool {
+    excludes
+        .iter()
+        .any(|prefix| path.starts_with(prefix.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_root_redirect_target_redirect_domain() {
+        let frontend = "https://app.example.com";
+        assert_eq!(
+            resolve_root_redirect_target(false, frontend, None, None).as_deref(),
+            Some(frontend)
+        );
+        assert_eq!(
+            resolve_root_redirect_target(false, frontend, Some("https://example.com/about"), None)
+                .as_deref(),
+            Some("https://example.com/about")
+        );
+        // Unparseable overrides fall back to the frontend
+        assert_eq!(
+            resolve_root_redirect_target(false, frontend, Some("not a url"), None).as_deref(),
+            Some(frontend)
+        );
+    }
+
+    #[test]
+    fn test_root_redirect_target_frontend_domain() {
+        let frontend = "https://app.example.com";
+        // Never redirects to itself by default
+        assert_eq!(
+            resolve_root_redirect_target(true, frontend, Some("https://example.com"), None),
+            None
+        );
+        assert_eq!(
+            resolve_root_redirect_target(true, frontend, None, Some(" https://www.example.com "))
+                .as_deref(),
+            Some("https://www.example.com")
+        );
+        assert_eq!(
+            resolve_root_redirect_target(true, frontend, None, Some("")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_spa_fallback_excludes_defaults_and_custom() {
+        let excludes = parse_spa_fallback_excludes(Some(" /internal/, nope, /, /api/ "));
+        assert_eq!(
+            excludes.len(),
+            DEFAULT_SPA_FALLBACK_EXCLUDES.len() + 1,
+            "relative, bare and duplicate entries are ignored"
+        );
+        assert!(is_spa_fallback_excluded("/internal/status", &excludes));
+        assert!(is_spa_fallback_excluded(
+            "/.well-known/security.txt",
+            &excludes
+        ));
+        assert!(is_spa_fallback_excluded("/robots.txt", &excludes));
+        assert!(is_spa_fallback_excluded("/api/whatever", &excludes));
+        assert!(!is_spa_fallback_excluded("/dashboard", &excludes));
+        assert!(!is_spa_fallback_excluded("/auth/callback", &excludes));
+    }
+
+    #[test]
+    fn test_spa_fallback_excludes_without_env() {
+        let excludes = parse_spa_fallback_excludes(None);
+        assert_eq!(excludes.len(), DEFAULT_SPA_FALLBACK_EXCLUDES.len());
+        assert!(!is_spa_fallback_excluded("/settings", &excludes));
+    }
+}
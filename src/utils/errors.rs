@@ -19,6 +19,19 @@
 use crate::auth::middleware::AuthError;
 use worker::Response;
 
+/// Details of an exhausted monthly link quota, returned alongside the
+/// message so clients can schedule retries.
+#[derive(Debug)]
+pub struct MonthlyLimitDetails {
+    pub message: String,
+    pub limit: i64,
+    pub used: i64,
+    /// Unix timestamp of the start of the next month (UTC)
+    pub resets_at: i64,
+    /// Which quota was hit: `billing_account` (tier limit) or `organization`
+    pub scope: &'static str,
+}
+
 /// Unified error type for all API handler layers.
 #[derive(Debug)]
 pub enum AppError {
@@ -36,8 +49,12 @@ pub enum AppError {
     Internal(String),
     /// 403 with tier upgrade message
     TierLimitReached(String),
+    /// 403 when the monthly link quota is used up, with machine-readable fields
+    MonthlyLimitReached(MonthlyLimitDetails),
     /// 429 Too Many Requests — rate limit or duplicate submission
     TooManyRequests(String),
+    /// 503 Service Unavailable — e.g. maintenance mode
+    ServiceUnavailable(String),
 }
 
 impl AppError {
@@ -51,7 +68,20 @@ impl AppError {
             AppError::Conflict(m) => (m, 409),
             AppError::Internal(m) => (m, 500),
             AppError::TierLimitReached(m) => (m, 403),
+            AppError::MonthlyLimitReached(details) => {
+                return Response::from_json(&serde_json::json!({
+                    "message": details.message,
+                    "code": "monthly_limit_reached",
+                    "limit": details.limit,
+                    "used": details.used,
+                    "resets_at": details.resets_at,
+                    "scope": details.scope,
+                }))
+                .unwrap_or_else(|_| Response::error("Error", 403).unwrap())
+                .with_status(403);
+            }
             AppError::TooManyRequests(m) => (m, 429),
+            AppError::ServiceUnavailable(m) => (m, 503),
         };
         Response::from_json(&serde_json::json!({ "message": msg }))
             .unwrap_or_else(|_| Response::error("Error", status).unwrap())
@@ -69,7 +99,9 @@ impl std::fmt::Display for AppError {
             | AppError::Conflict(m)
             | AppError::Internal(m)
             | AppError::TierLimitReached(m)
-            | AppError::TooManyRequests(m) => m.as_str(),
+            | AppError::TooManyRequests(m)
+            | AppError::ServiceUnavailable(m) => m.as_str(),
+            AppError::MonthlyLimitReached(d) => d.message.as_str(),
         };
         write!(f, "{}", msg)
     }
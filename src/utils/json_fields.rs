@@ -0,0 +1,156 @@
+use serde_json::{Map, Value};
+
+/// Expected JSON type of a request body field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonFieldType {
+    String,
+    Integer,
+    Boolean,
+    StringArray,
+    Object,
+}
+
+impl JsonFieldType {
+    fn describe(self) -> &'static str {
+        match self {
+            JsonFieldType::String => "a string",
+            JsonFieldType::Integer => "an integer",
+            JsonFieldType::Boolean => "a boolean",
+            JsonFieldType::StringArray => "an array of strings",
+            JsonFieldType::Object => "an object",
+        }
+    }
+
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            JsonFieldType::String => value.is_string(),
+            JsonFieldType::Integer => value.is_i64(),
+            JsonFieldType::Boolean => value.is_boolean(),
+            JsonFieldType::StringArray => value
+                .as_array()
+                .is_some_and(|items| items.iter().all(Value::is_string)),
+            JsonFieldType::Object => value.is_object(),
+        }
+    }
+}
+
+/// A field in a request body: name, expected type, and whether it is required.
+pub type JsonFieldSpec = (&'static str, JsonFieldType, bool);
+
+fn describe_value(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(n) if n.is_i64() => "an integer",
+        Value::Number(n) if n.is_u64() => "an out-of-range integer",
+        Value::Number(_) => "a decimal number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+/// Check the top-level field types of a JSON object before deserializing it,
+/// so clients get an error naming the offending field instead of serde's
+/// position-less "invalid type" message.
+///
+/// Optional fields may be `null`. Unknown fields are not checked here.
+pub fn check_json_field_types(
+    obj: &Map<String, Value>,
+    fields: &[JsonFieldSpec],
+) -> Result<(), String> {
+    for &(name, expected, required) in fields {
+        match obj.get(name) {
+            None | Some(Value::Null) if required => {
+                return Err(format!("Missing required field '{}'", name));
+            }
+            None | Some(Value::Null) => {}
+            Some(value) if !expected.matches(value) => {
+                return Err(format!(
+                    "Field '{}' must be {}, got {}",
+                    name,
+                    expected.describe(),
+                    describe_value(value)
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const FIELDS: &[JsonFieldSpec] = &[
+        ("destination_url", JsonFieldType::String, true),
+        ("expires_at", JsonFieldType::Integer, false),
+        ("tags", JsonFieldType::StringArray, false),
+        ("forward_query_params", JsonFieldType::Boolean, false),
+        ("utm_params", JsonFieldType::Object, false),
+    ];
+
+    fn check(value: Value) -> Result<(), String> {
+        check_json_field_types(value.as_object().unwrap(), FIELDS)
+    }
+
+    #[test]
+    fn test_accepts_valid_body() {
+        assert!(
+            check(json!({
+                "destination_url": "https://example.com",
+                "expires_at": 1700000000,
+                "tags": ["a", "b"],
+                "forward_query_params": true,
+                "utm_params": {"utm_source": "x"}
+            }))
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_optional_fields_may_be_absent_or_null() {
+        assert!(check(json!({"destination_url": "https://example.com"})).is_ok());
+        assert!(
+            check(json!({"destination_url": "https://example.com", "expires_at": null})).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_missing_required_field() {
+        assert_eq!(
+            check(json!({"title": "x"})),
+            Err("Missing required field 'destination_url'".to_string())
+        );
+        assert_eq!(
+            check(json!({"destination_url": null})),
+            Err("Missing required field 'destination_url'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_wrong_type_names_field() {
+        assert_eq!(
+            check(json!({"destination_url": 42})),
+            Err("Field 'destination_url' must be a string, got an integer".to_string())
+        );
+        assert_eq!(
+            check(json!({"destination_url": "https://example.com", "expires_at": "tomorrow"})),
+            Err("Field 'expires_at' must be an integer, got a string".to_string())
+        );
+        assert_eq!(
+            check(json!({"destination_url": "https://example.com", "expires_at": 1.5})),
+            Err("Field 'expires_at' must be an integer, got a decimal number".to_string())
+        );
+    }
+
+    #[test]
+    fn test_string_array_rejects_mixed_items() {
+        assert_eq!(
+            check(json!({"destination_url": "https://example.com", "tags": ["a", 1]})),
+            Err("Field 'tags' must be an array of strings, got an array".to_string())
+        );
+    }
+}
@@ -1,25 +1,37 @@
 pub mod cf_saas;
+pub mod cidr;
+pub mod code_words;
 pub mod crypto;
 pub mod device;
 pub mod email;
 pub mod env;
 pub mod errors;
 pub mod http;
+pub mod json_fields;
+pub mod qr;
 pub mod query_params;
+pub mod response_headers;
+pub mod robots;
+pub mod safe_fetch;
 pub mod short_code;
 pub mod tags;
 pub mod time;
 pub mod url;
 pub mod url_normalization;
+pub mod url_template;
+pub mod utm_token;
 pub mod validation;
 
 pub use crypto::{secure_compare, verify_polar_webhook_signature};
 pub use env::{get_fallback_domain, get_frontend_url, is_mailgun_configured};
-pub use errors::AppError;
+pub use errors::{AppError, MonthlyLimitDetails};
 pub use http::{get_client_ip, hash_ip};
 pub use query_params::QueryParams;
 pub use short_code::{generate_short_code, generate_short_code_with_length};
 pub use tags::validate_and_normalize_tags;
 pub use time::now_timestamp;
 pub use url_normalization::normalize_url_for_blacklist;
-pub use validation::{normalize_tag, validate_short_code, validate_url};
+pub use validation::{
+    normalize_tag, validate_custom_short_code, validate_short_code, validate_url,
+    validate_url_with_schemes,
+};
@@ -0,0 +1,212 @@
+/// QR code rendering for short links
+///
+/// Codes are encoded with `qrcodegen` and drawn by hand, as SVG or as a
+/// 1-bit grayscale PNG, so the Worker doesn't need an image library. The PNG
+/// uses stored (uncompressed) deflate blocks: a two-colour image at most
+/// 1024px wide is ~128 KiB raw, small enough not to bother compressing.
+use qrcodegen::{QrCode, QrCodeEcc};
+
+pub const MIN_QR_SIZE: u32 = 128;
+pub const MAX_QR_SIZE: u32 = 1024;
+pub const DEFAULT_QR_SIZE: u32 = 256;
+
+/// Light border around the symbol, in modules, as the QR spec requires
+const QUIET_ZONE: i32 = 4;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+/// Largest payload of a single stored deflate block
+const MAX_STORED_BLOCK: usize = 65_535;
+
+/// Image format of a rendered QR code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QrFormat {
+    Png,
+    Svg,
+}
+
+impl QrFormat {
+    /// Parse the `format` query parameter
+    pub fn from_param(value: &str) -> Option<Self> {
+        match value {
+            "png" => Some(Self::Png),
+            "svg" => Some(Self::Svg),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Svg => "image/svg+xml",
+        }
+    }
+}
+
+/// Clamp a requested image size (pixels per side) to the supported range
+pub fn clamp_qr_size(requested: Option<u32>) -> u32 {
+    requested
+        .unwrap_or(DEFAULT_QR_SIZE)
+        .clamp(MIN_QR_SIZE, MAX_QR_SIZE)
+}
+
+/// Encode `text` with medium error correction. Fails only if the text is
+/// too long for the largest QR version.
+pub fn encode_qr(text: &str) -> Option<QrCode> {
+    QrCode::encode_text(text, QrCodeEcc::Medium).ok()
+}
+
+/// Render as an SVG `size` pixels wide, one unit per module
+pub fn render_svg(qr: &QrCode, size: u32) -> String {
+    let modules = qr.size() + 2 * QUIET_ZONE;
+    let mut path = String::new();
+    for y in 0..qr.size() {
+        for x in 0..qr.size() {
+            if qr.get_module(x, y) {
+                path.push_str(&format!("M{},{}h1v1h-1z", x + QUIET_ZONE, y + QUIET_ZONE));
+            }
+        }
+    }
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size}\" height=\"{size}\" viewBox=\"0 0 {modules} {modules}\" shape-rendering=\"crispEdges\"><rect width=\"100%\" height=\"100%\" fill=\"#fff\"/><path d=\"{path}\" fill=\"#000\"/></svg>"
+    )
+}
+
+/// Render as a `size` x `size` PNG. Modules are scaled nearest-neighbour, so
+/// they may differ by a pixel when `size` isn't a multiple of the module count.
+pub fn render_png(qr: &QrCode, size: u32) -> Vec<u8> {
+    let modules = (qr.size() + 2 * QUIET_ZONE) as u32;
+    let row_bytes = size.div_ceil(8) as usize;
+
+    // Each scanline is a filter byte (0 = none) followed by 1-bit pixels,
+    // where a set bit is white
+    let mut raw = Vec::with_capacity((row_bytes + 1) * size as usize);
+    for py in 0..size {
+        let y = (py * modules / size) as i32 - QUIET_ZONE;
+        raw.push(0);
+        let mut row = vec![0u8; row_bytes];
+        for px in 0..size {
+            let x = (px * modules / size) as i32 - QUIET_ZONE;
+            if !qr.get_module(x, y) {
+                row[(px / 8) as usize] |= 0x80 >> (px % 8);
+            }
+        }
+        raw.extend_from_slice(&row);
+    }
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&size.to_be_bytes());
+    ihdr.extend_from_slice(&size.to_be_bytes());
+    // Bit depth 1, grayscale, deflate, adaptive filtering, no interlace
+    ihdr.extend_from_slice(&[1, 0, 0, 0, 0]);
+
+    let mut png = PNG_SIGNATURE.to_vec();
+    write_png_chunk(&mut png, b"IHDR", &ihdr);
+    write_png_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+    write_png_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Wrap `data` in a zlib stream of stored deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let blocks = data.len().div_ceil(MAX_STORED_BLOCK).max(1);
+    let mut out = Vec::with_capacity(data.len() + blocks * 5 + 6);
+    out.extend_from_slice(&[0x78, 0x01]);
+    let mut chunks = data.chunks(MAX_STORED_BLOCK).peekable();
+    if chunks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(chunk) = chunks.next() {
+        out.push(u8::from(chunks.peek().is_none()));
+        let len = chunk.len() as u16;
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(chunk);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + u32::from(byte)) % 65_521;
+        b = (b + a) % 65_521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clamp_qr_size() {
+        assert_eq!(clamp_qr_size(None), DEFAULT_QR_SIZE);
+        assert_eq!(clamp_qr_size(Some(16)), MIN_QR_SIZE);
+        assert_eq!(clamp_qr_size(Some(512)), 512);
+        assert_eq!(clamp_qr_size(Some(5000)), MAX_QR_SIZE);
+    }
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn test_render_png_header() {
+        let qr = encode_qr("https://example.com/abc123").unwrap();
+        let png = render_png(&qr, 300);
+        assert_eq!(&png[..8], PNG_SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..20], &300u32.to_be_bytes());
+        assert_eq!(&png[20..24], &300u32.to_be_bytes());
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+    }
+
+    #[test]
+    fn test_zlib_stored_splits_large_input() {
+        let data = vec![7u8; MAX_STORED_BLOCK + 10];
+        let out = zlib_stored(&data);
+        // Header, two block headers, data and the Adler-32 trailer
+        assert_eq!(out.len(), 2 + 2 * 5 + data.len() + 4);
+        assert_eq!(out[2], 0);
+        assert_eq!(out[2 + 5 + MAX_STORED_BLOCK], 1);
+    }
+
+    #[test]
+    fn test_render_svg_draws_each_dark_module() {
+        let qr = encode_qr("https://example.com/abc123").unwrap();
+        let svg = render_svg(&qr, 256);
+        let dark = (0..qr.size())
+            .flat_map(|y| (0..qr.size()).map(move |x| (x, y)))
+            .filter(|&(x, y)| qr.get_module(x, y))
+            .count();
+        assert_eq!(svg.matches("h1v1h-1z").count(), dark);
+        assert!(svg.contains("width=\"256\""));
+    }
+}
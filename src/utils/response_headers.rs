@@ -0,0 +1,200 @@
+/// Validation for per-link custom response headers applied on redirect
+use std::collections::BTreeMap;
+
+/// Maximum number of custom headers per link
+pub const MAX_RESPONSE_HEADERS: usize = 10;
+
+/// Maximum length of a single header value
+const MAX_HEADER_VALUE_LENGTH: usize = 1024;
+
+/// Standard headers links may set. Anything else must be an `X-` extension header.
+const ALLOWED_HEADERS: &[&str] = &["link", "x-robots-tag"];
+
+/// Headers that are never settable: hop-by-hop headers, headers the redirect
+/// itself owns, and security-sensitive headers (including `X-` ones).
+const REJECTED_HEADERS: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "proxy-connection",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+    "location",
+    "content-length",
+    "content-type",
+    "set-cookie",
+    "cache-control",
+    "content-security-policy",
+    "strict-transport-security",
+    "x-frame-options",
+    "x-content-type-options",
+    "x-xss-protection",
+    "x-forwarded-for",
+    "x-forwarded-host",
+    "x-forwarded-proto",
+    "x-real-ip",
+];
+
+/// Header name prefixes that are rejected for the same reasons as `REJECTED_HEADERS`.
+const REJECTED_PREFIXES: &[&str] = &["access-control-", "x-ratelimit-", "cf-"];
+
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+/// Validate a single header name. Returns the lowercased name on success.
+pub fn validate_response_header_name(name: &str) -> Result<String, String> {
+    if name.is_empty() || !name.chars().all(is_token_char) {
+        return Err(format!("Invalid header name: '{}'", name));
+    }
+    let lower = name.to_ascii_lowercase();
+
+    if REJECTED_HEADERS.contains(&lower.as_str())
+        || REJECTED_PREFIXES.iter().any(|p| lower.starts_with(p))
+    {
+        return Err(format!("Header '{}' cannot be set on redirects", name));
+    }
+    if !ALLOWED_HEADERS.contains(&lower.as_str()) && !lower.starts_with("x-") {
+        return Err(format!(
+            "Header '{}' is not allowed. Allowed: Link, X-Robots-Tag, or custom X- headers",
+            name
+        ));
+    }
+    Ok(lower)
+}
+
+/// Validate and normalize a map of custom response headers.
+/// Names are lowercased; values must be non-empty, printable and at most 1024 characters.
+pub fn validate_response_headers(
+    headers: &BTreeMap<String, String>,
+) -> Result<BTreeMap<String, String>, String> {
+    if headers.len() > MAX_RESPONSE_HEADERS {
+        return Err(format!(
+            "Maximum {} response headers per link",
+            MAX_RESPONSE_HEADERS
+        ));
+    }
+
+    let mut normalized = BTreeMap::new();
+    for (name, value) in headers {
+        let name = validate_response_header_name(name)?;
+        let value = value.trim();
+        if value.is_empty() || value.len() > MAX_HEADER_VALUE_LENGTH {
+            return Err(format!(
+                "Value for header '{}' must be between 1 and {} characters",
+                name, MAX_HEADER_VALUE_LENGTH
+            ));
+        }
+        if value.chars().any(|c| c.is_control()) {
+            return Err(format!(
+                "Value for header '{}' contains control characters",
+                name
+            ));
+        }
+        if normalized.insert(name.clone(), value.to_string()).is_some() {
+            return Err(format!("Duplicate header: '{}'", name));
+        }
+    }
+    Ok(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_allowed_header_names() {
+        assert_eq!(
+            validate_response_header_name("Link"),
+            Ok("link".to_string())
+        );
+        assert_eq!(
+            validate_response_header_name("X-Robots-Tag"),
+            Ok("x-robots-tag".to_string())
+        );
+        assert_eq!(
+            validate_response_header_name("X-Campaign-Id"),
+            Ok("x-campaign-id".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rejects_hop_by_hop_headers() {
+        for name in [
+            "Connection",
+            "Keep-Alive",
+            "Transfer-Encoding",
+            "Upgrade",
+            "TE",
+        ] {
+            assert!(validate_response_header_name(name).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_rejects_security_sensitive_headers() {
+        for name in [
+            "Location",
+            "Set-Cookie",
+            "Content-Security-Policy",
+            "Strict-Transport-Security",
+            "Access-Control-Allow-Origin",
+            "X-Frame-Options",
+            "X-Forwarded-For",
+            "X-RateLimit-Limit",
+            "CF-Connecting-IP",
+        ] {
+            assert!(validate_response_header_name(name).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_rejects_non_allowlisted_and_malformed_names() {
+        assert!(validate_response_header_name("Server").is_err());
+        assert!(validate_response_header_name("Refresh").is_err());
+        assert!(validate_response_header_name("").is_err());
+        assert!(validate_response_header_name("X-Bad Name").is_err());
+        assert!(validate_response_header_name("X-Bad:Name").is_err());
+    }
+
+    #[test]
+    fn test_validate_headers_normalizes() {
+        let result = validate_response_headers(&headers(&[
+            ("Link", " <https://cdn.example.com>; rel=preconnect "),
+            ("X-Campaign", "spring"),
+        ]))
+        .unwrap();
+        assert_eq!(
+            result.get("link").map(String::as_str),
+            Some("<https://cdn.example.com>; rel=preconnect")
+        );
+        assert_eq!(result.get("x-campaign").map(String::as_str), Some("spring"));
+    }
+
+    #[test]
+    fn test_validate_headers_rejects_bad_values() {
+        assert!(validate_response_headers(&headers(&[("X-A", "")])).is_err());
+        assert!(validate_response_headers(&headers(&[("X-A", "a\r\nSet-Cookie: x=1")])).is_err());
+        let long = "a".repeat(1025);
+        assert!(validate_response_headers(&headers(&[("X-A", &long)])).is_err());
+    }
+
+    #[test]
+    fn test_validate_headers_rejects_case_duplicates_and_too_many() {
+        assert!(validate_response_headers(&headers(&[("X-A", "1"), ("x-a", "2")])).is_err());
+        let many: BTreeMap<String, String> = (0..=MAX_RESPONSE_HEADERS)
+            .map(|i| (format!("X-H{}", i), "v".to_string()))
+            .collect();
+        assert!(validate_response_headers(&many).is_err());
+    }
+}
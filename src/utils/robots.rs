@@ -0,0 +1,150 @@
+/// robots.txt and sitemap.xml bodies for the redirect domain
+///
+/// Short codes are opaque redirects, so by default crawlers are asked to stay
+/// away entirely. Admins can replace the body with the `robots_txt` setting;
+/// orgs that opt into `public_sitemap` get their active links listed in
+/// /sitemap.xml (an index of `?page=N` sitemaps), which is then advertised
+/// from robots.txt.
+use chrono::{DateTime, SecondsFormat};
+
+/// Served when the `robots_txt` setting is empty or unset
+pub const DEFAULT_ROBOTS_TXT: &str = "User-agent: *\nDisallow: /\n";
+/// Largest accepted `robots_txt` setting
+pub const MAX_ROBOTS_TXT_BYTES: usize = 8 * 1024;
+/// Sitemap protocol limit on URLs (or child sitemaps) per file
+pub const MAX_SITEMAP_URLS: i64 = 50_000;
+/// Links per /sitemap.xml?page=N; kept well under the protocol limit so each
+/// page is one bounded D1 read
+pub const SITEMAP_PAGE_SIZE: i64 = 1_000;
+
+/// Number of sitemap pages needed to list `total` links
+pub fn sitemap_page_count(total: i64) -> i64 {
+    ((total + SITEMAP_PAGE_SIZE - 1) / SITEMAP_PAGE_SIZE).clamp(0, MAX_SITEMAP_URLS)
+}
+
+/// Body for /robots.txt: the configured text (or the default), followed by a
+/// `Sitemap:` line when a sitemap is available.
+pub fn render_robots_txt(configured: Option<&str>, sitemap_url: Option<&str>) -> String {
+    let mut body = configured
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .unwrap_or(DEFAULT_ROBOTS_TXT.trim_end())
+        .to_string();
+    body.push('\n');
+    if let Some(url) = sitemap_url
+        && !body
+            .lines()
+            .any(|l| l.to_ascii_lowercase().starts_with("sitemap:"))
+    {
+        body.push_str(&format!("\nSitemap: {}\n", url));
+    }
+    body
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Body for /sitemap.xml listing `base_url/<short_code>` for each entry of
+/// (short code, last-modified unix timestamp).
+pub fn render_sitemap_xml<'a>(
+    base_url: &str,
+    entries: impl IntoIterator<Item = (&'a str, i64)>,
+) -> String {
+    let base_url = base_url.trim_end_matches('/');
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for (short_code, last_modified) in entries {
+        xml.push_str("  <url><loc>");
+        xml.push_str(&escape_xml(&format!("{}/{}", base_url, short_code)));
+        xml.push_str("</loc>");
+        if let Some(ts) = DateTime::from_timestamp(last_modified, 0) {
+            xml.push_str("<lastmod>");
+            xml.push_str(&ts.to_rfc3339_opts(SecondsFormat::Secs, true));
+            xml.push_str("</lastmod>");
+        }
+        xml.push_str("</url>\n");
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+/// Body for /sitemap.xml: a sitemap index pointing at
+/// `base_url/sitemap.xml?page=1..=pages`.
+pub fn render_sitemap_index(base_url: &str, pages: i64) -> String {
+    let base_url = base_url.trim_end_matches('/');
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for page in 1..=pages {
+        xml.push_str("  <sitemap><loc>");
+        xml.push_str(&escape_xml(&format!(
+            "{}/sitemap.xml?page={}",
+            base_url, page
+        )));
+        xml.push_str("</loc></sitemap>\n");
+    }
+    xml.push_str("</sitemapindex>\n");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_robots_default_and_configured() {
+        assert_eq!(render_robots_txt(None, None), DEFAULT_ROBOTS_TXT);
+        assert_eq!(render_robots_txt(Some("  "), None), DEFAULT_ROBOTS_TXT);
+        assert_eq!(
+            render_robots_txt(Some("User-agent: *\nAllow: /\n"), None),
+            "User-agent: *\nAllow: /\n"
+        );
+    }
+
+    #[test]
+    fn test_robots_advertises_sitemap_once() {
+        assert_eq!(
+            render_robots_txt(None, Some("https://rush.mn/sitemap.xml")),
+            "User-agent: *\nDisallow: /\n\nSitemap: https://rush.mn/sitemap.xml\n"
+        );
+        let configured = "User-agent: *\nSitemap: https://example.com/s.xml";
+        assert_eq!(
+            render_robots_txt(Some(configured), Some("https://rush.mn/sitemap.xml")),
+            format!("{}\n", configured)
+        );
+    }
+
+    #[test]
+    fn test_sitemap_page_count() {
+        assert_eq!(sitemap_page_count(0), 0);
+        assert_eq!(sitemap_page_count(1), 1);
+        assert_eq!(sitemap_page_count(SITEMAP_PAGE_SIZE), 1);
+        assert_eq!(sitemap_page_count(SITEMAP_PAGE_SIZE + 1), 2);
+    }
+
+    #[test]
+    fn test_sitemap_index_lists_pages() {
+        let xml = render_sitemap_index("https://rush.mn/", 2);
+        assert!(xml.contains("<sitemapindex"));
+        assert!(xml.contains("<loc>https://rush.mn/sitemap.xml?page=1</loc>"));
+        assert!(xml.contains("<loc>https://rush.mn/sitemap.xml?page=2</loc>"));
+        assert!(!xml.contains("page=3"));
+    }
+
+    #[test]
+    fn test_sitemap_lists_escaped_urls() {
+        let xml = render_sitemap_xml("https://rush.mn/", [("abc", 0), ("a&b", 1_700_000_000)]);
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains(
+            "<url><loc>https://rush.mn/abc</loc><lastmod>1970-01-01T00:00:00Z</lastmod></url>"
+        ));
+        assert!(xml.contains("<loc>https://rush.mn/a&amp;b</loc>"));
+        assert!(xml.trim_end().ends_with("</urlset>"));
+    }
+}
@@ -12,6 +12,104 @@ pub const DEFAULT_MIN_CUSTOM_CODE_LENGTH: usize = 3;
 pub const DEFAULT_SYSTEM_MIN_CODE_LENGTH: usize = 1;
 pub const MAX_SHORT_CODE_LENGTH: usize = 100;
 pub const DEFAULT_COLLISION_THRESHOLD: usize = 3;
+/// Lower bound on the default attempt budget for one generated code
+pub const MIN_DEFAULT_MAX_ATTEMPTS: usize = 20;
+/// Seconds clients are told to wait (`Retry-After`) when no unique code
+/// could be generated within the attempt budget.
+pub const CODE_GENERATION_RETRY_AFTER_SECS: u64 = 5;
+
+/// Tuning for random short-code generation under contention.
+///
+/// Read from the `COLLISION_THRESHOLD`, `SHORT_CODE_MAX_ATTEMPTS` and
+/// `SHORT_CODE_LENGTH_BUMP` env vars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodeGenerationPolicy {
+    /// Consecutive collisions at one length before moving to a longer code
+    pub collision_threshold: usize,
+    /// Total collisions tolerated before giving up
+    pub max_attempts: usize,
+    /// Whether reaching the threshold bumps the code length
+    pub bump_length: bool,
+}
+
+impl CodeGenerationPolicy {
+    /// Build the policy from raw env var values, falling back to defaults for
+    /// missing or invalid ones.
+    pub fn from_env_values(
+        collision_threshold: Option<&str>,
+        max_attempts: Option<&str>,
+        bump_length: Option<&str>,
+    ) -> Self {
+        let collision_threshold = collision_threshold
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_COLLISION_THRESHOLD);
+        let max_attempts = max_attempts
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or((collision_threshold * 3).max(MIN_DEFAULT_MAX_ATTEMPTS));
+        let bump_length = bump_length.map(|v| v != "false").unwrap_or(true);
+        Self {
+            collision_threshold,
+            max_attempts,
+            bump_length,
+        }
+    }
+}
+
+/// What to do after a generated code collided with an existing one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionAction {
+    /// Try again at the same length
+    Retry,
+    /// Try again one character longer
+    BumpLength,
+    /// Attempt budget exhausted
+    GiveUp,
+}
+
+/// Collision bookkeeping for generating a single short code
+#[derive(Debug, Clone)]
+pub struct CodeGenerationAttempts {
+    policy: CodeGenerationPolicy,
+    collisions: usize,
+    collisions_at_length: usize,
+}
+
+impl CodeGenerationAttempts {
+    pub fn new(policy: CodeGenerationPolicy) -> Self {
+        Self {
+            policy,
+            collisions: 0,
+            collisions_at_length: 0,
+        }
+    }
+
+    /// Total collisions so far
+    pub fn collisions(&self) -> usize {
+        self.collisions
+    }
+
+    /// Record a collision at `current_length` and decide what to do next
+    pub fn record_collision(&mut self, current_length: usize) -> CollisionAction {
+        self.collisions += 1;
+        self.collisions_at_length += 1;
+
+        if self.collisions >= self.policy.max_attempts {
+            return CollisionAction::GiveUp;
+        }
+
+        if self.policy.bump_length
+            && self.collisions_at_length >= self.policy.collision_threshold
+            && current_length < MAX_SHORT_CODE_LENGTH
+        {
+            self.collisions_at_length = 0;
+            return CollisionAction::BumpLength;
+        }
+
+        CollisionAction::Retry
+    }
+}
 
 /// Generate a random base62 short code
 pub fn generate_short_code() -> String {
@@ -23,15 +121,30 @@ pub fn generate_short_code_with_length(length: usize) -> String {
     generate_short_code_with_charset(length, false)
 }
 
+/// Parse an alphabet name into the `exclude_ambiguous` flag:
+/// `base62` (the default) or `base58` (no 0, O, I, l)
+pub fn parse_code_alphabet(name: &str) -> Option<bool> {
+    match name {
+        "base62" => Some(false),
+        "base58" => Some(true),
+        _ => None,
+    }
+}
+
+/// Characters generated codes are drawn from
+pub fn code_alphabet_chars(exclude_ambiguous: bool) -> &'static [u8] {
+    if exclude_ambiguous {
+        BASE58_CHARS
+    } else {
+        BASE62_CHARS
+    }
+}
+
 /// Generate a random short code with custom length, optionally excluding
 /// ambiguous characters (0, O, I, l) by using the Base58 alphabet
 pub fn generate_short_code_with_charset(length: usize, exclude_ambiguous: bool) -> String {
     let mut rng = rand::rng();
-    let charset = if exclude_ambiguous {
-        BASE58_CHARS
-    } else {
-        BASE62_CHARS
-    };
+    let charset = code_alphabet_chars(exclude_ambiguous);
 
     (0..length)
         .map(|_| {
@@ -45,6 +158,82 @@ pub fn generate_short_code_with_charset(length: usize, exclude_ambiguous: bool)
 mod tests {
     use super::*;
 
+    fn policy(threshold: usize, max_attempts: usize, bump_length: bool) -> CodeGenerationPolicy {
+        CodeGenerationPolicy {
+            collision_threshold: threshold,
+            max_attempts,
+            bump_length,
+        }
+    }
+
+    #[test]
+    fn test_policy_defaults() {
+        assert_eq!(
+            CodeGenerationPolicy::from_env_values(None, None, None),
+            policy(DEFAULT_COLLISION_THRESHOLD, MIN_DEFAULT_MAX_ATTEMPTS, true)
+        );
+        assert_eq!(
+            CodeGenerationPolicy::from_env_values(Some("10"), Some("abc"), Some("false")),
+            policy(10, 30, false)
+        );
+        assert_eq!(
+            CodeGenerationPolicy::from_env_values(Some("0"), Some("5"), Some("true")),
+            policy(DEFAULT_COLLISION_THRESHOLD, 5, true)
+        );
+    }
+
+    #[test]
+    fn test_repeated_collisions_bump_length_then_give_up() {
+        let mut attempts = CodeGenerationAttempts::new(policy(3, 7, true));
+        let mut length = 6;
+        let mut actions = Vec::new();
+        loop {
+            let action = attempts.record_collision(length);
+            actions.push(action);
+            match action {
+                CollisionAction::Retry => {}
+                CollisionAction::BumpLength => length += 1,
+                CollisionAction::GiveUp => break,
+            }
+        }
+        assert_eq!(
+            actions,
+            vec![
+                CollisionAction::Retry,
+                CollisionAction::Retry,
+                CollisionAction::BumpLength,
+                CollisionAction::Retry,
+                CollisionAction::Retry,
+                CollisionAction::BumpLength,
+                CollisionAction::GiveUp,
+            ]
+        );
+        assert_eq!(length, 8);
+        assert_eq!(attempts.collisions(), 7);
+    }
+
+    #[test]
+    fn test_repeated_collisions_without_length_bump() {
+        let mut attempts = CodeGenerationAttempts::new(policy(2, 5, false));
+        for _ in 0..4 {
+            assert_eq!(attempts.record_collision(6), CollisionAction::Retry);
+        }
+        assert_eq!(attempts.record_collision(6), CollisionAction::GiveUp);
+    }
+
+    #[test]
+    fn test_no_bump_past_max_length() {
+        let mut attempts = CodeGenerationAttempts::new(policy(1, 10, true));
+        assert_eq!(
+            attempts.record_collision(MAX_SHORT_CODE_LENGTH),
+            CollisionAction::Retry
+        );
+        assert_eq!(
+            attempts.record_collision(MAX_SHORT_CODE_LENGTH - 1),
+            CollisionAction::BumpLength
+        );
+    }
+
     #[test]
     fn test_generate_short_code_returns_correct_length() {
         let code = generate_short_code();
@@ -121,4 +310,13 @@ mod tests {
         let code = generate_short_code_with_charset(10, false);
         assert_eq!(code.len(), 10);
     }
+
+    #[test]
+    fn test_parse_code_alphabet() {
+        assert_eq!(parse_code_alphabet("base62"), Some(false));
+        assert_eq!(parse_code_alphabet("base58"), Some(true));
+        assert_eq!(parse_code_alphabet("hex"), None);
+        assert!(!code_alphabet_chars(true).contains(&b'0'));
+        assert_eq!(code_alphabet_chars(false).len(), 62);
+    }
 }
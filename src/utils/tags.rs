@@ -2,12 +2,16 @@
 use crate::utils::normalize_tag;
 use worker::Result;
 
+/// Maximum number of tags a single link may carry
+pub const MAX_TAGS_PER_LINK: usize = 20;
+
 /// Validate and normalize a list of tags. Returns an error if any tag is invalid.
 pub fn validate_and_normalize_tags(tags: &[String]) -> Result<Vec<String>> {
-    if tags.len() > 20 {
-        return Err(worker::Error::RustError(
-            "Maximum 20 tags per link".to_string(),
-        ));
+    if tags.len() > MAX_TAGS_PER_LINK {
+        return Err(worker::Error::RustError(format!(
+            "Maximum {} tags per link",
+            MAX_TAGS_PER_LINK
+        )));
     }
     let mut normalized = Vec::with_capacity(tags.len());
     for tag in tags {
@@ -26,6 +30,22 @@ pub fn validate_and_normalize_tags(tags: &[String]) -> Result<Vec<String>> {
     Ok(normalized)
 }
 
+/// Normalized host of a URL for tag suggestions: lowercased, without a leading `www.`.
+pub fn suggestion_host(url: &str) -> Option<String> {
+    let parsed = url::Url::parse(url).ok()?;
+    let host = parsed.host_str()?.to_ascii_lowercase();
+    Some(host.strip_prefix("www.").unwrap_or(&host).to_string())
+}
+
+/// The host and each parent domain with at least two labels, most specific first
+/// (`gist.github.com` -> `["gist.github.com", "github.com"]`).
+pub fn suggestion_domains(host: &str) -> Vec<String> {
+    let labels: Vec<&str> = host.split('.').filter(|l| !l.is_empty()).collect();
+    (0..labels.len().saturating_sub(1))
+        .map(|i| labels[i..].join("."))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -124,4 +144,30 @@ mod tests {
         assert_eq!(result.len(), 1);
         assert_eq!(result[0], "single");
     }
+
+    #[test]
+    fn test_suggestion_host_strips_www_and_lowercases() {
+        assert_eq!(
+            suggestion_host("https://WWW.GitHub.com/rust-lang"),
+            Some("github.com".to_string())
+        );
+        assert_eq!(
+            suggestion_host("https://gist.github.com/x"),
+            Some("gist.github.com".to_string())
+        );
+        assert_eq!(suggestion_host("not a url"), None);
+    }
+
+    #[test]
+    fn test_suggestion_domains_includes_parents() {
+        assert_eq!(
+            suggestion_domains("gist.github.com"),
+            vec!["gist.github.com".to_string(), "github.com".to_string()]
+        );
+        assert_eq!(
+            suggestion_domains("github.com"),
+            vec!["github.com".to_string()]
+        );
+        assert!(suggestion_domains("localhost").is_empty());
+    }
 }
@@ -1,20 +1,21 @@
 use url::Url;
 
-/// Normalize a URL for blacklist comparison
-/// This function applies various normalization techniques to ensure that
-/// URLs that point to the same content are normalized to the same form
-pub fn normalize_url_for_blacklist(input_url: &str) -> Result<String, String> {
-    let mut url = Url::parse(input_url).map_err(|e| format!("Failed to parse URL: {}", e))?;
+/// Tracking query parameters stripped by default when canonicalizing a
+/// destination. A trailing `*` matches any parameter with that prefix.
+pub const DEFAULT_CANONICAL_STRIP_PARAMS: &[&str] = &["utm_*", "fbclid"];
 
-    // 1. Convert scheme and host to lowercase (RFC 3986)
+/// Lowercase the scheme and host (RFC 3986)
+fn lowercase_scheme_and_host(url: &mut Url) {
     let scheme = url.scheme().to_lowercase();
     let _ = url.set_scheme(&scheme);
 
     if let Some(host) = url.host_str() {
         let _ = url.set_host(Some(&host.to_lowercase()));
     }
+}
 
-    // 2. Remove default ports (80 for http, 443 for https)
+/// Remove default ports (80 for http, 443 for https)
+fn remove_default_port(url: &mut Url) {
     match url.scheme() {
         "http" if url.port_or_known_default() == Some(80) => {
             let _ = url.set_port(None);
@@ -24,36 +25,12 @@ pub fn normalize_url_for_blacklist(input_url: &str) -> Result<String, String> {
         }
         _ => {}
     }
+}
 
-    // 3. Handle www prefix - normalize to non-www form
-    if let Some(host) = url.host_str() {
-        let host_str = host.to_string();
-        if let Some(non_www_host) = host_str.strip_prefix("www.") {
-            let _ = url.set_host(Some(non_www_host));
-        }
-    }
-
-    // 4. Normalize path
-    let mut path = url.path().to_string();
-
-    // Remove trailing slash for non-root paths (preserves semantics)
-    if path.len() > 1 && path.ends_with('/') {
-        path.pop();
-    }
-
-    // Add leading slash for empty paths (when authority is present)
-    if path.is_empty() && !url.authority().is_empty() {
-        path = "/".to_string();
-    }
-
-    // Remove dot segments (., ..) - the url crate does this automatically
-    // but we ensure it's properly normalized
-    url.set_path(&path);
-
-    // 5. Remove fragment (never seen by server)
-    url.set_fragment(None);
-
-    // 6. Sort query parameters alphabetically
+/// Sort query parameters by key, then value, dropping any for which `drop`
+/// returns true. Parameters without a value are kept only if `keep_valueless`.
+/// An empty result removes the query entirely.
+fn sort_query_params(url: &mut Url, keep_valueless: bool, drop: impl Fn(&str) -> bool) {
     if let Some(query) = url.query()
         && !query.is_empty()
     {
@@ -63,9 +40,11 @@ pub fn normalize_url_for_blacklist(input_url: &str) -> Result<String, String> {
                 let mut parts = pair.splitn(2, '=');
                 match (parts.next(), parts.next()) {
                     (Some(key), Some(value)) => Some((key, value)),
+                    (Some(key), None) if keep_valueless && !key.is_empty() => Some((key, "")),
                     _ => None,
                 }
             })
+            .filter(|(key, _)| !drop(key))
             .collect();
 
         // Sort by key, then by value
@@ -87,10 +66,107 @@ pub fn normalize_url_for_blacklist(input_url: &str) -> Result<String, String> {
         url.set_query(Some(&normalized_query));
     }
 
-    // 7. Remove empty query (when query is just "?")
+    // Remove empty query (when query is just "?")
     if url.query().is_some_and(|q| q.is_empty()) {
         url.set_query(None);
     }
+}
+
+/// Normalize a URL for blacklist comparison
+/// This function applies various normalization techniques to ensure that
+/// URLs that point to the same content are normalized to the same form
+pub fn normalize_url_for_blacklist(input_url: &str) -> Result<String, String> {
+    let mut url = Url::parse(input_url).map_err(|e| format!("Failed to parse URL: {}", e))?;
+
+    // 1. Convert scheme and host to lowercase (RFC 3986)
+    lowercase_scheme_and_host(&mut url);
+
+    // 2. Remove default ports (80 for http, 443 for https)
+    remove_default_port(&mut url);
+
+    // 3. Handle www prefix - normalize to non-www form
+    if let Some(host) = url.host_str() {
+        let host_str = host.to_string();
+        if let Some(non_www_host) = host_str.strip_prefix("www.") {
+            let _ = url.set_host(Some(non_www_host));
+        }
+    }
+
+    // 4. Normalize path
+    let mut path = url.path().to_string();
+
+    // Remove trailing slash for non-root paths (preserves semantics)
+    if path.len() > 1 && path.ends_with('/') {
+        path.pop();
+    }
+
+    // Add leading slash for empty paths (when authority is present)
+    if path.is_empty() && !url.authority().is_empty() {
+        path = "/".to_string();
+    }
+
+    // Remove dot segments (., ..) - the url crate does this automatically
+    // but we ensure it's properly normalized
+    url.set_path(&path);
+
+    // 5. Remove fragment (never seen by server)
+    url.set_fragment(None);
+
+    // 6. Sort query parameters alphabetically (and drop an empty query)
+    sort_query_params(&mut url, false, |_| false);
+
+    Ok(url.to_string())
+}
+
+/// Parse a comma-separated list of tracking parameter patterns
+/// (e.g. `utm_*,fbclid`). Patterns are lowercased; a trailing `*` is a
+/// prefix wildcard.
+pub fn parse_strip_params(value: &str) -> Result<Vec<String>, String> {
+    value
+        .split(',')
+        .map(|p| p.trim().to_lowercase())
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let name = p.strip_suffix('*').unwrap_or(&p);
+            let valid = !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.');
+            if valid {
+                Ok(p)
+            } else {
+                Err(format!(
+                    "'{}' is not a valid parameter name (letters, digits, '_', '-', '.', optional trailing '*')",
+                    p
+                ))
+            }
+        })
+        .collect()
+}
+
+fn matches_strip_param(key: &str, patterns: &[String]) -> bool {
+    let key = key.to_lowercase();
+    patterns.iter().any(|p| match p.strip_suffix('*') {
+        Some(prefix) => key.starts_with(prefix),
+        None => key == *p,
+    })
+}
+
+/// Canonicalize a destination URL before storing it (opt-in on link creation).
+///
+/// Lowercases the scheme and host, removes default ports, strips tracking
+/// parameters matching `strip_params`, and sorts the remaining query
+/// parameters. Unlike the blacklist normalizer it keeps `www.`, trailing
+/// slashes and fragments, which can change what the destination serves.
+pub fn canonicalize_destination_url(
+    input_url: &str,
+    strip_params: &[String],
+) -> Result<String, String> {
+    let mut url = Url::parse(input_url).map_err(|e| format!("Failed to parse URL: {}", e))?;
+
+    lowercase_scheme_and_host(&mut url);
+    remove_default_port(&mut url);
+    sort_query_params(&mut url, true, |key| matches_strip_param(key, strip_params));
 
     Ok(url.to_string())
 }
@@ -275,4 +351,89 @@ mod tests {
             "http://different.com"
         ));
     }
+
+    // ─── Destination canonicalization ─────────────────────────────────────────
+
+    fn default_strip() -> Vec<String> {
+        DEFAULT_CANONICAL_STRIP_PARAMS
+            .iter()
+            .map(|p| p.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_canonicalize_lowercases_host() {
+        assert_eq!(
+            canonicalize_destination_url("https://Docs.EXAMPLE.com/Path", &[]).unwrap(),
+            "https://docs.example.com/Path"
+        );
+    }
+
+    #[test]
+    fn test_canonicalize_removes_default_port() {
+        assert_eq!(
+            canonicalize_destination_url("https://example.com:443/a", &[]).unwrap(),
+            "https://example.com/a"
+        );
+        assert_eq!(
+            canonicalize_destination_url("http://example.com:80/a", &[]).unwrap(),
+            "http://example.com/a"
+        );
+        assert_eq!(
+            canonicalize_destination_url("https://example.com:8443/a", &[]).unwrap(),
+            "https://example.com:8443/a"
+        );
+    }
+
+    #[test]
+    fn test_canonicalize_sorts_query_params() {
+        assert_eq!(
+            canonicalize_destination_url("https://example.com/?b=2&a=1&flag", &[]).unwrap(),
+            "https://example.com/?a=1&b=2&flag"
+        );
+    }
+
+    #[test]
+    fn test_canonicalize_strips_tracking_params() {
+        assert_eq!(
+            canonicalize_destination_url(
+                "https://example.com/p?utm_source=x&id=7&UTM_Medium=y&fbclid=abc",
+                &default_strip()
+            )
+            .unwrap(),
+            "https://example.com/p?id=7"
+        );
+        // Only tracking params: the query is dropped entirely
+        assert_eq!(
+            canonicalize_destination_url("https://example.com/p?fbclid=abc", &default_strip())
+                .unwrap(),
+            "https://example.com/p"
+        );
+        // Exact patterns do not match by prefix
+        assert_eq!(
+            canonicalize_destination_url("https://example.com/?fbclid2=1", &default_strip())
+                .unwrap(),
+            "https://example.com/?fbclid2=1"
+        );
+    }
+
+    #[test]
+    fn test_canonicalize_keeps_www_trailing_slash_and_fragment() {
+        assert_eq!(
+            canonicalize_destination_url("https://www.example.com/docs/#intro", &default_strip())
+                .unwrap(),
+            "https://www.example.com/docs/#intro"
+        );
+    }
+
+    #[test]
+    fn test_parse_strip_params() {
+        assert_eq!(
+            parse_strip_params(" utm_* , FBCLID,,gclid ").unwrap(),
+            vec!["utm_*", "fbclid", "gclid"]
+        );
+        assert_eq!(parse_strip_params("").unwrap(), Vec::<String>::new());
+        assert!(parse_strip_params("*").is_err());
+        assert!(parse_strip_params("utm_*,a=b").is_err());
+    }
 }
@@ -0,0 +1,198 @@
+/// Click-time destination templating
+///
+/// A destination such as `https://shop.example.com/?country={{country}}` has
+/// its placeholders filled in on every redirect from the visitor's CF
+/// headers. Only the variables in `TEMPLATE_VARIABLES` are supported and
+/// values are always percent-encoded, so a visitor-controlled value can never
+/// change the URL's structure. Placeholders are recognised both literally and
+/// percent-encoded (`%7B%7Bcountry%7D%7D`), since URL serialization encodes
+/// braces in the path.
+use urlencoding::encode;
+
+/// Variables a destination template may use.
+pub const TEMPLATE_VARIABLES: [&str; 3] = ["country", "city", "date"];
+
+/// Longest placeholder name considered, to bound the scan on junk input.
+const MAX_PLACEHOLDER_NAME_LEN: usize = 32;
+
+const OPEN_TOKENS: [&str; 2] = ["{{", "%7B%7B"];
+const CLOSE_TOKENS: [&str; 2] = ["}}", "%7D%7D"];
+
+/// Values substituted at redirect time. Missing values render as empty.
+#[derive(Debug, Default, Clone)]
+pub struct TemplateValues {
+    pub country: Option<String>,
+    pub city: Option<String>,
+    /// Click date as `YYYY-MM-DD` (UTC)
+    pub date: String,
+}
+
+impl TemplateValues {
+    fn get(&self, name: &str) -> Option<&str> {
+        match name {
+            "country" => Some(self.country.as_deref().unwrap_or("")),
+            "city" => Some(self.city.as_deref().unwrap_or("")),
+            "date" => Some(&self.date),
+            _ => None,
+        }
+    }
+}
+
+/// Length of the token from `tokens` that `s` starts with (ASCII
+/// case-insensitive, for percent-encoded hex digits).
+fn token_len_at(s: &str, tokens: &[&str]) -> Option<usize> {
+    tokens
+        .iter()
+        .find(|t| {
+            s.get(..t.len())
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(t))
+        })
+        .map(|t| t.len())
+}
+
+/// A placeholder found in a template: byte range and variable name
+struct Placeholder<'a> {
+    start: usize,
+    end: usize,
+    name: &'a str,
+}
+
+fn find_placeholders(template: &str) -> Vec<Placeholder<'_>> {
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < template.len() {
+        let rest = &template[i..];
+        let Some(open_len) = token_len_at(rest, &OPEN_TOKENS) else {
+            i += rest.chars().next().map_or(1, char::len_utf8);
+            continue;
+        };
+        let name_start = i + open_len;
+        let close = template[name_start..]
+            .char_indices()
+            .take(MAX_PLACEHOLDER_NAME_LEN + 1)
+            .find_map(|(offset, _)| {
+                token_len_at(&template[name_start + offset..], &CLOSE_TOKENS)
+                    .map(|len| (offset, len))
+            });
+        match close {
+            Some((offset, close_len)) if offset > 0 => {
+                let end = name_start + offset + close_len;
+                found.push(Placeholder {
+                    start: i,
+                    end,
+                    name: &template[name_start..name_start + offset],
+                });
+                i = end;
+            }
+            _ => i += open_len,
+        }
+    }
+    found
+}
+
+/// Whether the destination contains any placeholders (assumed validated).
+pub fn is_templated(destination: &str) -> bool {
+    !find_placeholders(destination).is_empty()
+}
+
+/// Reject destinations using placeholders outside `TEMPLATE_VARIABLES`.
+pub fn validate_destination_template(destination: &str) -> Result<(), String> {
+    match find_placeholders(destination)
+        .into_iter()
+        .find(|p| !TEMPLATE_VARIABLES.contains(&p.name))
+    {
+        Some(p) => Err(format!(
+            "Unknown template variable '{{{{{}}}}}'. Supported: {}",
+            p.name,
+            TEMPLATE_VARIABLES
+                .iter()
+                .map(|v| format!("{{{{{}}}}}", v))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Substitute placeholders with percent-encoded values. Unknown placeholders
+/// are left untouched.
+pub fn render_destination_template(template: &str, values: &TemplateValues) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut last = 0;
+    for p in find_placeholders(template) {
+        if let Some(value) = values.get(p.name) {
+            out.push_str(&template[last..p.start]);
+            out.push_str(&encode(value));
+            last = p.end;
+        }
+    }
+    out.push_str(&template[last..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> TemplateValues {
+        TemplateValues {
+            country: Some("US".to_string()),
+            city: Some("São Paulo & Co/?#".to_string()),
+            date: "2026-03-01".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_substitutes_known_variables() {
+        assert_eq!(
+            render_destination_template(
+                "https://shop.example.com/?country={{country}}&d={{date}}",
+                &values()
+            ),
+            "https://shop.example.com/?country=US&d=2026-03-01"
+        );
+    }
+
+    #[test]
+    fn test_values_are_percent_encoded() {
+        assert_eq!(
+            render_destination_template("https://example.com/?city={{city}}", &values()),
+            "https://example.com/?city=S%C3%A3o%20Paulo%20%26%20Co%2F%3F%23"
+        );
+    }
+
+    #[test]
+    fn test_percent_encoded_placeholders_in_path() {
+        assert_eq!(
+            render_destination_template("https://example.com/%7B%7Bcountry%7d%7D/page", &values()),
+            "https://example.com/US/page"
+        );
+    }
+
+    #[test]
+    fn test_missing_values_render_empty() {
+        assert_eq!(
+            render_destination_template(
+                "https://example.com/?c={{city}}",
+                &TemplateValues::default()
+            ),
+            "https://example.com/?c="
+        );
+    }
+
+    #[test]
+    fn test_validation_rejects_unknown_placeholders() {
+        assert!(validate_destination_template("https://example.com/?c={{country}}").is_ok());
+        assert!(validate_destination_template("https://example.com/plain").is_ok());
+        let err = validate_destination_template("https://example.com/?x={{ip}}").unwrap_err();
+        assert!(err.contains("{{ip}}"));
+        assert!(validate_destination_template("https://example.com/%7B%7Buser%7D%7D").is_err());
+    }
+
+    #[test]
+    fn test_is_templated() {
+        assert!(is_templated("https://example.com/?c={{country}}"));
+        assert!(!is_templated("https://example.com/?q={}&r={{}}"));
+        assert!(!is_templated("https://example.com/{{unterminated"));
+    }
+}
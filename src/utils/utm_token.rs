@@ -0,0 +1,206 @@
+/// Signed UTM override tokens
+///
+/// A token lets a campaign manager add UTM parameters to a single short link
+/// at click time (`/<code>?u=<token>`) without being able to forge or tamper
+/// with them. Format: `<payload>.<signature>`, both base64url without
+/// padding. The payload is a URL-encoded query string of UTM pairs; the
+/// signature is HMAC-SHA256 over `<short_code>.<payload>` with the org's
+/// UTM signing secret, so a token only works on the link it was made for.
+use hmac::{Hmac, KeyInit, Mac};
+use sha2::Sha256;
+
+/// Query parameter carrying the token on redirect URLs.
+pub const UTM_TOKEN_PARAM: &str = "u";
+
+/// UTM keys a token may set. Anything else in the payload is ignored.
+pub const UTM_TOKEN_KEYS: [&str; 6] = [
+    "utm_source",
+    "utm_medium",
+    "utm_campaign",
+    "utm_term",
+    "utm_content",
+    "utm_ref",
+];
+
+fn is_utm_token_key(key: &str) -> bool {
+    UTM_TOKEN_KEYS.contains(&key)
+}
+
+/// Longest token accepted, to bound the work done for junk input.
+const MAX_TOKEN_LEN: usize = 2048;
+
+const BASE64URL: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+fn encode_base64url(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, b)| acc | (u32::from(*b) << (16 - 8 * i)));
+        for i in 0..=chunk.len() {
+            out.push(BASE64URL[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+        }
+    }
+    out
+}
+
+fn decode_base64url(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 4 == 1 {
+        return None;
+    }
+    let mut out = Vec::with_capacity(s.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in s.bytes() {
+        let value = BASE64URL.iter().position(|b| *b == c)? as u32;
+        buffer = (buffer << 6) | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(out)
+}
+
+fn mac_for(secret: &str, short_code: &str, payload: &str) -> Option<Hmac<Sha256>> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).ok()?;
+    mac.update(short_code.as_bytes());
+    mac.update(b".");
+    mac.update(payload.as_bytes());
+    Some(mac)
+}
+
+/// Create a token for `short_code` carrying the given UTM pairs.
+/// Pairs with keys outside `UTM_TOKEN_KEYS` or empty values are dropped.
+pub fn sign_utm_token(secret: &str, short_code: &str, pairs: &[(&str, &str)]) -> String {
+    let query: String = url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(
+            pairs
+                .iter()
+                .filter(|(k, v)| is_utm_token_key(k) && !v.is_empty()),
+        )
+        .finish();
+    let payload = encode_base64url(query.as_bytes());
+    let signature = mac_for(secret, short_code, &payload)
+        .map(|mac| encode_base64url(&mac.finalize().into_bytes()))
+        .unwrap_or_default();
+    format!("{}.{}", payload, signature)
+}
+
+/// Verify a token for `short_code` and return its UTM pairs, or None when
+/// the token is malformed or the signature does not match.
+pub fn verify_utm_token(
+    secret: &str,
+    short_code: &str,
+    token: &str,
+) -> Option<Vec<(String, String)>> {
+    if secret.is_empty() || token.len() > MAX_TOKEN_LEN {
+        return None;
+    }
+    let (payload, signature) = token.split_once('.')?;
+    let signature = decode_base64url(signature)?;
+    mac_for(secret, short_code, payload)?
+        .verify_slice(&signature)
+        .ok()?;
+
+    let query = String::from_utf8(decode_base64url(payload)?).ok()?;
+    let pairs = url::form_urlencoded::parse(query.as_bytes())
+        .filter(|(k, v)| is_utm_token_key(k) && !v.is_empty())
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    Some(pairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "org-secret";
+
+    #[test]
+    fn test_base64url_round_trip() {
+        let inputs: [&[u8]; 8] = [
+            b"",
+            b"f",
+            b"fo",
+            b"foo",
+            b"foob",
+            b"fooba",
+            b"foobar",
+            &[0xff, 0xfe],
+        ];
+        for input in inputs {
+            let encoded = encode_base64url(input);
+            assert!(!encoded.contains('='));
+            assert_eq!(decode_base64url(&encoded).as_deref(), Some(input));
+        }
+        assert_eq!(encode_base64url(&[0xfb, 0xff]), "-_8");
+        assert_eq!(decode_base64url("a*b"), None);
+    }
+
+    #[test]
+    fn test_valid_token_round_trips() {
+        let token = sign_utm_token(
+            SECRET,
+            "promo",
+            &[
+                ("utm_source", "newsletter"),
+                ("utm_campaign", "spring & summer"),
+            ],
+        );
+        assert_eq!(
+            verify_utm_token(SECRET, "promo", &token),
+            Some(vec![
+                ("utm_source".to_string(), "newsletter".to_string()),
+                ("utm_campaign".to_string(), "spring & summer".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_non_utm_keys_are_dropped() {
+        let token = sign_utm_token(
+            SECRET,
+            "promo",
+            &[("utm_medium", "email"), ("next", "evil")],
+        );
+        assert_eq!(
+            verify_utm_token(SECRET, "promo", &token),
+            Some(vec![("utm_medium".to_string(), "email".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_wrong_secret_or_code_is_rejected() {
+        let token = sign_utm_token(SECRET, "promo", &[("utm_source", "ads")]);
+        assert_eq!(verify_utm_token("other-secret", "promo", &token), None);
+        assert_eq!(verify_utm_token(SECRET, "other", &token), None);
+        assert_eq!(verify_utm_token("", "promo", &token), None);
+    }
+
+    #[test]
+    fn test_tampered_payload_is_rejected() {
+        let token = sign_utm_token(SECRET, "promo", &[("utm_source", "ads")]);
+        let (_, signature) = token.split_once('.').unwrap();
+        let forged_payload = encode_base64url(b"utm_source=forged");
+        assert_eq!(
+            verify_utm_token(
+                SECRET,
+                "promo",
+                &format!("{}.{}", forged_payload, signature)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_malformed_tokens_are_rejected() {
+        let too_long = "a".repeat(MAX_TOKEN_LEN + 1);
+        for token in ["", "no-separator", ".", "abc.!!!", too_long.as_str()] {
+            assert_eq!(verify_utm_token(SECRET, "promo", token), None);
+        }
+    }
+}
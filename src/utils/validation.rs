@@ -1,4 +1,6 @@
+use crate::utils::code_words::DisallowedWords;
 use crate::utils::short_code::MAX_SHORT_CODE_LENGTH;
+use crate::utils::url_template::validate_destination_template;
 use url::Url;
 
 /// Reserved short codes that cannot be used (prevent conflicts with routes)
@@ -49,24 +51,119 @@ pub fn normalize_tag(tag: &str) -> Option<String> {
     }
 }
 
+/// Destination schemes allowed when no `allowed_destination_schemes` setting is configured
+pub const DEFAULT_DESTINATION_SCHEMES: &[&str] = &["http", "https"];
+
+/// Every scheme an admin may enable for destinations. Anything else (javascript:,
+/// data:, file:, ...) can never be allowed.
+pub const SUPPORTED_DESTINATION_SCHEMES: &[&str] = &["http", "https", "mailto", "tel"];
+
 /// Validate a destination URL
 /// Must be http or https scheme
 pub fn validate_url(url_str: &str) -> Result<String, String> {
+    validate_url_with_schemes(url_str, DEFAULT_DESTINATION_SCHEMES)
+}
+
+/// Validate a destination URL against a list of allowed schemes.
+/// http(s) URLs must have a host; mailto:/tel: URLs must have a target.
+pub fn validate_url_with_schemes<S: AsRef<str>>(
+    url_str: &str,
+    allowed_schemes: &[S],
+) -> Result<String, String> {
     match Url::parse(url_str) {
         Ok(url) => {
             let scheme = url.scheme();
-            if scheme != "http" && scheme != "https" {
+            if !allowed_schemes.iter().any(|s| s.as_ref() == scheme)
+                || !SUPPORTED_DESTINATION_SCHEMES.contains(&scheme)
+            {
+                let allowed: Vec<&str> = allowed_schemes.iter().map(|s| s.as_ref()).collect();
                 return Err(format!(
-                    "Invalid URL scheme: {}. Only http and https are allowed",
-                    scheme
+                    "Invalid URL scheme: {}. Only {} are allowed",
+                    scheme,
+                    allowed.join(", ")
                 ));
             }
+            if matches!(scheme, "mailto" | "tel") && url.path().trim().is_empty() {
+                return Err(format!("Invalid URL: {}: link has no target", scheme));
+            }
+            validate_destination_template(url_str)?;
             Ok(url.to_string())
         }
         Err(e) => Err(format!("Invalid URL: {}", e)),
     }
 }
 
+/// Parse the comma-separated `allowed_destination_schemes` setting.
+/// Schemes are lowercased and deduplicated; only supported schemes are accepted.
+pub fn parse_allowed_destination_schemes(value: &str) -> Result<Vec<String>, String> {
+    let mut schemes: Vec<String> = Vec::new();
+    for scheme in value.split(',').map(|s| s.trim().to_ascii_lowercase()) {
+        if scheme.is_empty() || schemes.contains(&scheme) {
+            continue;
+        }
+        if !SUPPORTED_DESTINATION_SCHEMES.contains(&scheme.as_str()) {
+            return Err(format!(
+                "Unsupported scheme '{}'. Supported schemes: {}",
+                scheme,
+                SUPPORTED_DESTINATION_SCHEMES.join(", ")
+            ));
+        }
+        schemes.push(scheme);
+    }
+    if schemes.is_empty() {
+        return Err("At least one scheme must be allowed".to_string());
+    }
+    Ok(schemes)
+}
+
+/// Parse the comma-separated `signup_email_domains` setting. Domains are
+/// lowercased and deduplicated, and a leading `@` is dropped. An empty value
+/// is an empty list, meaning signups from any domain are allowed.
+pub fn parse_email_domain_list(value: &str) -> Result<Vec<String>, String> {
+    let mut domains: Vec<String> = Vec::new();
+    for domain in value
+        .split(',')
+        .map(|d| d.trim().trim_start_matches('@').to_ascii_lowercase())
+    {
+        if domain.is_empty() || domains.contains(&domain) {
+            continue;
+        }
+        let valid = domain.contains('.')
+            && domain.split('.').all(|label| {
+                !label.is_empty()
+                    && !label.starts_with('-')
+                    && !label.ends_with('-')
+                    && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            });
+        if !valid {
+            return Err(format!("Invalid email domain '{}'", domain));
+        }
+        domains.push(domain);
+    }
+    Ok(domains)
+}
+
+/// Whether `email` belongs to one of `allowed_domains` (exact match, case-insensitive).
+/// An empty list allows every email.
+pub fn is_email_domain_allowed(email: &str, allowed_domains: &[String]) -> bool {
+    if allowed_domains.is_empty() {
+        return true;
+    }
+    email
+        .rsplit_once('@')
+        .is_some_and(|(_, domain)| allowed_domains.contains(&domain.to_ascii_lowercase()))
+}
+
+/// Whether a new user with `email` may sign up under the stored
+/// `signup_email_domains` setting. Unset, empty or unparseable values allow
+/// every domain, so a bad stored value never locks out signups.
+pub fn is_signup_email_allowed(setting: Option<&str>, email: &str) -> bool {
+    let allowed_domains = setting
+        .and_then(|v| parse_email_domain_list(v).ok())
+        .unwrap_or_default();
+    is_email_domain_allowed(email, &allowed_domains)
+}
+
 /// Validate a custom short code
 /// Rules:
 /// - 1-100 characters long
@@ -130,6 +227,16 @@ pub fn validate_short_code(code: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Validate a user-chosen short code: the `validate_short_code` rules, plus no
+/// admin-configured disallowed word. The error never echoes the matched word.
+pub fn validate_custom_short_code(code: &str, disallowed: &DisallowedWords) -> Result<(), String> {
+    validate_short_code(code)?;
+    if disallowed.matches(code) {
+        return Err("Short code contains a word that is not allowed".to_string());
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -187,6 +294,104 @@ mod tests {
         assert!(validate_url("htp://example.com").is_err()); // typo in scheme
     }
 
+    #[test]
+    fn test_validate_url_default_rejects_mailto_and_tel() {
+        assert!(validate_url("mailto:team@example.com").is_err());
+        assert!(validate_url("tel:+15551234567").is_err());
+    }
+
+    #[test]
+    fn test_validate_url_with_schemes_accepts_allowed_non_http() {
+        let allowed = ["http", "https", "mailto", "tel"];
+        assert_eq!(
+            validate_url_with_schemes("mailto:team@example.com?subject=Hi", &allowed),
+            Ok("mailto:team@example.com?subject=Hi".to_string())
+        );
+        assert_eq!(
+            validate_url_with_schemes("tel:+15551234567", &allowed),
+            Ok("tel:+15551234567".to_string())
+        );
+        assert!(validate_url_with_schemes("https://example.com", &allowed).is_ok());
+    }
+
+    #[test]
+    fn test_validate_url_with_schemes_rejects_disallowed() {
+        let allowed = ["https", "mailto"];
+        assert!(validate_url_with_schemes("http://example.com", &allowed).is_err());
+        assert!(validate_url_with_schemes("tel:+15551234567", &allowed).is_err());
+        assert!(validate_url_with_schemes("mailto:", &allowed).is_err());
+        // Unsupported schemes stay rejected even if listed
+        assert!(validate_url_with_schemes("javascript:alert(1)", &["javascript"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_allowed_destination_schemes() {
+        assert_eq!(
+            parse_allowed_destination_schemes(" HTTPS, http,mailto,https "),
+            Ok(vec![
+                "https".to_string(),
+                "http".to_string(),
+                "mailto".to_string()
+            ])
+        );
+        assert!(parse_allowed_destination_schemes("").is_err());
+        assert!(parse_allowed_destination_schemes("https,javascript").is_err());
+        assert!(parse_allowed_destination_schemes("ftp").is_err());
+    }
+
+    #[test]
+    fn test_parse_email_domain_list() {
+        assert_eq!(parse_email_domain_list(""), Ok(vec![]));
+        assert_eq!(
+            parse_email_domain_list(" Example.com, @corp.example.org,example.com "),
+            Ok(vec![
+                "example.com".to_string(),
+                "corp.example.org".to_string()
+            ])
+        );
+        assert!(parse_email_domain_list("localhost").is_err());
+        assert!(parse_email_domain_list("example.com,bad domain.com").is_err());
+        assert!(parse_email_domain_list("-example.com").is_err());
+    }
+
+    #[test]
+    fn test_is_email_domain_allowed() {
+        let allowed = vec!["example.com".to_string()];
+        assert!(is_email_domain_allowed("alice@Example.COM", &allowed));
+        assert!(!is_email_domain_allowed("bob@gmail.com", &allowed));
+        assert!(!is_email_domain_allowed("eve@mail.example.com", &allowed));
+        assert!(!is_email_domain_allowed("no-at-sign", &allowed));
+        assert!(is_email_domain_allowed("anyone@anywhere.org", &[]));
+    }
+
+    #[test]
+    fn test_is_signup_email_allowed() {
+        assert!(is_signup_email_allowed(None, "bob@gmail.com"));
+        assert!(is_signup_email_allowed(Some(""), "bob@gmail.com"));
+        assert!(!is_signup_email_allowed(
+            Some("example.com"),
+            "bob@gmail.com"
+        ));
+        assert!(is_signup_email_allowed(
+            Some("example.com, Gmail.com"),
+            "bob@gmail.com"
+        ));
+        // A stored value that no longer parses fails open
+        assert!(is_signup_email_allowed(
+            Some("not a domain"),
+            "bob@gmail.com"
+        ));
+    }
+
+    #[test]
+    fn test_validate_custom_short_code_rejects_disallowed_word_without_echoing_it() {
+        let disallowed = DisallowedWords::new([("badword", "deny")]);
+        let err = validate_custom_short_code("my-b4dword", &disallowed).unwrap_err();
+        assert!(!err.to_lowercase().contains("badword"));
+        assert!(validate_custom_short_code("my-goodword", &disallowed).is_ok());
+        assert!(validate_custom_short_code("api", &disallowed).is_err());
+    }
+
     // Short Code Validation Tests
     #[test]
     fn test_validate_short_code_accepts_alphanumeric() {
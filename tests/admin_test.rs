@@ -248,6 +248,93 @@ async fn test_admin_block_destination() {
     assert_eq!(remove_response.status(), StatusCode::OK);
 }
 
+#[tokio::test]
+async fn test_admin_disallowed_code_words() {
+    let client = authenticated_client();
+
+    // Unique words so concurrent tests' codes never match
+    let denied = unique_short_code("zqx");
+    let allowed = format!("{}ette", denied);
+
+    let deny_response = client
+        .post(format!("{}/api/admin/code-words", BASE_URL))
+        .json(&json!({ "word": denied }))
+        .send()
+        .await
+        .unwrap();
+    if deny_response.status() == StatusCode::FORBIDDEN {
+        println!("Test user is not an admin - skipping test");
+        return;
+    }
+    assert_eq!(deny_response.status(), StatusCode::OK);
+    let deny_entry: serde_json::Value = deny_response.json().await.unwrap();
+    assert_eq!(deny_entry["kind"], "deny");
+
+    let allow_response = client
+        .post(format!("{}/api/admin/code-words", BASE_URL))
+        .json(&json!({ "word": allowed, "kind": "allow" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(allow_response.status(), StatusCode::OK);
+    let allow_entry: serde_json::Value = allow_response.json().await.unwrap();
+
+    let duplicate = client
+        .post(format!("{}/api/admin/code-words", BASE_URL))
+        .json(&json!({ "word": denied, "kind": "deny" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(duplicate.status(), StatusCode::CONFLICT);
+
+    // A code containing the denied word (any case) is rejected without echoing it
+    let banned = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": "https://example.com/banned-word",
+            "short_code": format!("my-{}", denied.to_uppercase()),
+        }))
+        .send()
+        .await
+        .unwrap();
+    let banned_status = banned.status();
+    let banned_body = banned.text().await.unwrap();
+
+    // A benign code that only contains it inside the allowed word is accepted
+    let benign_code = format!("{}-day", allowed);
+    let benign = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": "https://example.com/allowed-word",
+            "short_code": benign_code,
+        }))
+        .send()
+        .await
+        .unwrap();
+    let benign_status = benign.status();
+
+    // Remove both entries before asserting so a failure can't leave them behind
+    for entry in [&deny_entry, &allow_entry] {
+        let remove_response = client
+            .delete(format!(
+                "{}/api/admin/code-words/{}",
+                BASE_URL,
+                entry["id"].as_str().unwrap()
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(remove_response.status(), StatusCode::OK);
+    }
+
+    assert_eq!(banned_status, StatusCode::BAD_REQUEST);
+    assert!(banned_body.contains("not allowed"));
+    assert!(!banned_body.to_lowercase().contains(&denied));
+    assert_eq!(benign_status, StatusCode::OK);
+    let benign_link: serde_json::Value = benign.json().await.unwrap();
+    assert_eq!(benign_link["short_code"], benign_code);
+}
+
 #[tokio::test]
 async fn test_admin_list_links_requires_auth() {
     let client = test_client();
@@ -524,3 +611,404 @@ async fn test_admin_reset_counter_admin_access() {
     assert!(body["success"].as_bool().unwrap_or(false));
     assert_eq!(body["message"], "Monthly counter reset for billing account");
 }
+
+#[tokio::test]
+async fn test_admin_list_users_count_only() {
+    let client = authenticated_client();
+
+    let count_response = client
+        .get(format!("{}/api/admin/users?count_only=true", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    if count_response.status() == StatusCode::FORBIDDEN {
+        println!("Test user is not an admin - skipping test");
+        return;
+    }
+    assert_eq!(count_response.status(), StatusCode::OK);
+    let count_body: serde_json::Value = count_response.json().await.unwrap();
+    assert!(count_body.get("users").is_none());
+
+    let full_body: serde_json::Value = client
+        .get(format!("{}/api/admin/users?limit=1", BASE_URL))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(full_body["total"].as_i64(), count_body["total"].as_i64());
+}
+
+/// Deleting a link's KV entry behind the API's back is reported by the KV
+/// consistency audit, and `repair=true` restores the redirect.
+///
+/// The drift is seeded with `wrangler kv key delete` against the local dev KV.
+#[tokio::test]
+async fn test_admin_kv_audit_detects_and_repairs_missing_entry() {
+    let client = authenticated_client();
+    let link: serde_json::Value = create_test_link("https://example.com/kv-audit", None)
+        .await
+        .json()
+        .await
+        .unwrap();
+    let link_id = link["id"].as_str().unwrap().to_string();
+    let short_code = link["short_code"].as_str().unwrap().to_string();
+    let org_id = link["org_id"].as_str().unwrap().to_string();
+    let audit_url = format!(
+        "{}/api/admin/audit/kv-consistency?org_id={}&active_only=true",
+        BASE_URL, org_id
+    );
+
+    let response = client.get(&audit_url).send().await.unwrap();
+    if response.status() == StatusCode::FORBIDDEN {
+        println!("Test user is not an admin - skipping test");
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let reported = |body: &serde_json::Value| {
+        body["mismatches"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|m| m["id"].as_str() == Some(link_id.as_str()))
+            .cloned()
+    };
+    assert!(
+        reported(&body).is_none(),
+        "Freshly created link should be in sync"
+    );
+
+    let deleted = std::process::Command::new("wrangler")
+        .args([
+            "kv",
+            "key",
+            "delete",
+            &short_code,
+            "--binding",
+            "URL_MAPPINGS",
+            "--local",
+        ])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false);
+    if !deleted {
+        println!("SKIP: wrangler kv unavailable — cannot seed KV drift");
+        return;
+    }
+
+    let body: serde_json::Value = client
+        .get(&audit_url)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let mismatch = reported(&body).expect("Link missing from KV should be reported");
+    assert_eq!(mismatch["kv_sync_status"], "missing");
+    assert_eq!(mismatch["kv_exists"], false);
+    assert_eq!(body["repaired"], 0);
+
+    let body: serde_json::Value = client
+        .get(format!("{}&repair=true", audit_url))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(reported(&body).is_some());
+    assert!(body["repaired"].as_u64().unwrap() >= 1);
+
+    let redirect = test_client()
+        .get(format!("{}/{}", BASE_URL, short_code))
+        .send()
+        .await
+        .unwrap();
+    assert!(
+        redirect.status().is_redirection(),
+        "Repaired link should redirect again, got {}",
+        redirect.status()
+    );
+
+    let body: serde_json::Value = client
+        .get(&audit_url)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(
+        reported(&body).is_none(),
+        "Link should be in sync after repair"
+    );
+}
+
+#[tokio::test]
+async fn test_admin_list_user_links_spans_orgs() {
+    let client = authenticated_client();
+    let user_id = get_test_user_id();
+    let links_url = format!("{}/api/admin/users/{}/links?limit=100", BASE_URL, user_id);
+
+    let response = client.get(&links_url).send().await.unwrap();
+    if response.status() == StatusCode::FORBIDDEN {
+        println!("Test user is not an admin - skipping test");
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // One link in the current org, one in a freshly created org
+    let original_org_id = get_primary_test_org_id().await;
+    let first: serde_json::Value = create_test_link("https://example.com/user-links-a", None)
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let other_org_id = client
+        .post(format!("{}/api/orgs", BASE_URL))
+        .json(&json!({ "name": format!("User Links Org {}", unique_short_code("ul")) }))
+        .send()
+        .await
+        .unwrap()
+        .json::<serde_json::Value>()
+        .await
+        .unwrap()["org"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let switch_to = |org_id: String| {
+        let client = client.clone();
+        async move {
+            let response = client
+                .post(format!("{}/api/auth/switch-org", BASE_URL))
+                .json(&json!({ "org_id": org_id }))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+    };
+    switch_to(other_org_id.clone()).await;
+    let second: serde_json::Value = create_test_link("https://example.com/user-links-b", None)
+        .await
+        .json()
+        .await
+        .unwrap();
+    switch_to(original_org_id).await;
+
+    let body: serde_json::Value = client
+        .get(&links_url)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let links = body["links"].as_array().unwrap();
+    for expected in [&first, &second] {
+        let found = links
+            .iter()
+            .find(|l| l["id"] == expected["id"])
+            .unwrap_or_else(|| panic!("Link {} should be listed", expected["id"]));
+        assert_eq!(found["org_id"], expected["org_id"]);
+        assert!(found["org_name"].is_string());
+    }
+    assert_eq!(second["org_id"].as_str(), Some(other_org_id.as_str()));
+    assert!(body["total"].as_i64().unwrap() >= 2);
+
+    let response = client
+        .get(format!("{}&status=archived", links_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+// Retention is an instance-wide setting, so this test follows whatever the
+// instance has configured instead of changing it under other tests.
+#[tokio::test]
+async fn test_admin_delete_link_retains_analytics() {
+    let client = authenticated_client();
+    let response = client
+        .get(format!("{}/api/admin/settings", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    if response.status() == StatusCode::FORBIDDEN {
+        println!("Test user is not an admin - skipping test");
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let settings: serde_json::Value = response.json().await.unwrap();
+    let retention_days = settings["deleted_link_retention_days"]
+        .as_str()
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(0);
+
+    let link: serde_json::Value = create_test_link("https://example.com/abuse-report", None)
+        .await
+        .json()
+        .await
+        .unwrap();
+    let link_id = link["id"].as_str().unwrap();
+    let short_code = link["short_code"].as_str().unwrap();
+
+    // Record a click before the link is deleted
+    let response = test_client()
+        .get(format!("{}/{}", BASE_URL, short_code))
+        .header("Referer", "https://phish.example.net/")
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_redirection());
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let response = client
+        .delete(format!("{}/api/admin/links/{}", BASE_URL, link_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+
+    // The link itself is gone either way
+    let response = client
+        .get(format!("{}/api/links/{}", BASE_URL, link_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = client
+        .get(format!(
+            "{}/api/admin/deleted-links/{}/analytics",
+            BASE_URL, link_id
+        ))
+        .send()
+        .await
+        .unwrap();
+
+    if retention_days == 0 {
+        // Default: deletion purges analytics
+        assert!(body["analytics_retained_until"].is_null());
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        return;
+    }
+
+    assert!(body["analytics_retained_until"].as_i64().is_some());
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["link"]["status"], "deleted");
+    assert_eq!(body["link"]["short_code"], short_code);
+    assert!(body["total_clicks"].as_i64().unwrap() >= 1);
+    assert!(
+        body["top_referrers"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|r| r["referrer"] == "https://phish.example.net/")
+    );
+    assert!(!body["recent_events"].as_array().unwrap().is_empty());
+}
+
+/// A KV entry written behind the API's back, with no link in D1, is
+/// reported by the orphan scan and deleted with `repair=true`.
+#[tokio::test]
+async fn test_admin_kv_audit_reports_orphaned_keys() {
+    let client = authenticated_client();
+    let orphan_code = unique_short_code("orph");
+    let scan_url = format!(
+        "{}/api/admin/audit/kv-consistency?orphans=true&limit=100",
+        BASE_URL
+    );
+
+    let response = client.get(&scan_url).send().await.unwrap();
+    if response.status() == StatusCode::FORBIDDEN {
+        println!("Test user is not an admin - skipping test");
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mapping = serde_json::json!({
+        "destination_url": "https://example.com/orphan",
+        "link_id": "no-such-link",
+        "expires_at": null,
+        "status": "active"
+    })
+    .to_string();
+    let seeded = std::process::Command::new("wrangler")
+        .args([
+            "kv",
+            "key",
+            "put",
+            &orphan_code,
+            &mapping,
+            "--binding",
+            "URL_MAPPINGS",
+            "--local",
+        ])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false);
+    if !seeded {
+        println!("SKIP: wrangler kv unavailable — cannot seed an orphaned key");
+        return;
+    }
+
+    // Walk the scan until the page holding the seeded key
+    let mut cursor: Option<String> = None;
+    let mut found_on: Option<Option<String>> = None;
+    for _ in 0..100 {
+        let url = match &cursor {
+            Some(c) => format!("{}&cursor={}", scan_url, urlencoding::encode(c)),
+            None => scan_url.clone(),
+        };
+        let body: serde_json::Value = client.get(&url).send().await.unwrap().json().await.unwrap();
+        assert_eq!(body["repaired"], 0);
+        if body["orphans"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|k| k.as_str() == Some(orphan_code.as_str()))
+        {
+            found_on = Some(cursor.clone());
+            break;
+        }
+        match body["cursor"].as_str() {
+            Some(next) => cursor = Some(next.to_string()),
+            None => break,
+        }
+    }
+    let page_cursor = found_on.expect("Orphaned key should be reported");
+
+    let repair_url = match &page_cursor {
+        Some(c) => format!("{}&repair=true&cursor={}", scan_url, urlencoding::encode(c)),
+        None => format!("{}&repair=true", scan_url),
+    };
+    let body: serde_json::Value = client
+        .get(&repair_url)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(body["repaired"].as_u64().unwrap() >= 1);
+
+    let redirect = test_client()
+        .get(format!("{}/{}", BASE_URL, orphan_code))
+        .send()
+        .await
+        .unwrap();
+    assert!(
+        !redirect.status().is_redirection(),
+        "Deleted orphan should no longer redirect, got {}",
+        redirect.status()
+    );
+}
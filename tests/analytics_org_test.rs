@@ -210,3 +210,261 @@ async fn test_get_org_analytics_top_links_structure() {
         );
     }
 }
+
+#[tokio::test]
+async fn test_org_top_countries_requires_auth() {
+    let org_id = get_primary_test_org_id().await;
+    let response = test_client()
+        .get(format!(
+            "{}/api/orgs/{}/analytics/top-countries",
+            BASE_URL, org_id
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_org_top_countries_orders_by_clicks() {
+    let client = authenticated_client();
+    let redirect_client = test_client();
+    let org_id = get_primary_test_org_id().await;
+
+    let short_code = create_link_and_get_code("https://example.com/top-countries").await;
+
+    // Three clicks from Antarctica, one from Bouvet Island
+    for country in ["AQ", "AQ", "AQ", "BV"] {
+        let response = redirect_client
+            .get(format!("{}/{}", BASE_URL, short_code))
+            .header("CF-IPCountry", country)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+    }
+
+    // Wait for deferred analytics to complete
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let response = client
+        .get(format!(
+            "{}/api/orgs/{}/analytics/top-countries?limit=50",
+            BASE_URL, org_id
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["start"].as_i64().unwrap() <= body["end"].as_i64().unwrap());
+    let countries = body["countries"].as_array().unwrap();
+    let position = |code: &str| {
+        countries
+            .iter()
+            .position(|c| c["country"].as_str() == Some(code))
+            .unwrap_or_else(|| panic!("{} should be in top countries", code))
+    };
+    let (aq, bv) = (position("AQ"), position("BV"));
+    assert!(aq < bv, "AQ (3 clicks) should rank above BV (1 click)");
+    assert!(countries[aq]["count"].as_i64().unwrap() >= 3);
+    assert!(countries[bv]["count"].as_i64().unwrap() >= 1);
+
+    // Counts are sorted descending
+    let counts: Vec<i64> = countries
+        .iter()
+        .map(|c| c["count"].as_i64().unwrap())
+        .collect();
+    assert!(counts.windows(2).all(|w| w[0] >= w[1]));
+}
+
+#[tokio::test]
+async fn test_org_top_countries_rejects_non_member() {
+    let client = authenticated_client();
+    let response = client
+        .get(format!(
+            "{}/api/orgs/org-does-not-exist/analytics/top-countries",
+            BASE_URL
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_export_org_analytics_csv_includes_events_from_multiple_links() {
+    let client = authenticated_client();
+    let redirect_client = test_client();
+
+    let mut links = Vec::new();
+    for i in 0..2 {
+        let response = create_test_link(
+            &format!("https://example.com/org-export-{}", i),
+            Some("Org Export Test"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let link: serde_json::Value = response.json().await.unwrap();
+        links.push(link);
+    }
+    let org_id = links[0]["org_id"].as_str().unwrap().to_string();
+
+    // Two clicks on the first link, one on the second
+    for (link, clicks) in links.iter().zip([2, 1]) {
+        for _ in 0..clicks {
+            redirect_client
+                .get(format!(
+                    "{}/{}",
+                    BASE_URL,
+                    link["short_code"].as_str().unwrap()
+                ))
+                .header("User-Agent", "Mozilla/5.0 OrgExportBot")
+                .send()
+                .await
+                .unwrap();
+        }
+    }
+
+    // Wait for deferred analytics to complete
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let response = client
+        .get(format!(
+            "{}/api/orgs/{}/analytics/export?days=1",
+            BASE_URL, org_id
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(
+        response
+            .headers()
+            .get("content-type")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .starts_with("text/csv")
+    );
+
+    let csv = response.text().await.unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next().unwrap(),
+        "event_id,timestamp,link_short_code,link_id,referrer,user_agent,country,city"
+    );
+
+    let rows: Vec<Vec<&str>> = lines.map(|l| l.split(',').collect()).collect();
+    for (link, expected) in links.iter().zip([2, 1]) {
+        let link_id = link["id"].as_str().unwrap();
+        let short_code = link["short_code"].as_str().unwrap();
+        let matching: Vec<_> = rows.iter().filter(|r| r[3] == link_id).collect();
+        assert_eq!(
+            matching.len(),
+            expected,
+            "Expected {} events for {}",
+            expected,
+            short_code
+        );
+        assert!(
+            matching.iter().all(|r| r[2] == short_code),
+            "Events for {} should carry its short code",
+            link_id
+        );
+    }
+
+    for link in &links {
+        let _ = client
+            .delete(format!(
+                "{}/api/links/{}",
+                BASE_URL,
+                link["id"].as_str().unwrap()
+            ))
+            .send()
+            .await;
+    }
+}
+
+#[tokio::test]
+async fn test_org_header_fields_records_only_allowlisted_headers() {
+    let client = authenticated_client();
+    let redirect_client = test_client();
+    let org_id = get_primary_test_org_id().await;
+
+    let response = client
+        .patch(format!("{}/api/orgs/{}/settings", BASE_URL, org_id))
+        .json(&json!({ "analytics_header_fields": ["X-Tenant"] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["analytics_header_fields"], json!(["x-tenant"]));
+
+    let short_code = create_link_and_get_code("https://example.com/header-fields").await;
+    let tenant = unique_short_code("tenant");
+    let secret = unique_short_code("secret");
+    let response = redirect_client
+        .get(format!("{}/{}", BASE_URL, short_code))
+        .header("X-Tenant", &tenant)
+        .header("X-Secret", &secret)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+
+    // Wait for deferred analytics to complete
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let breakdown = |field: &'static str| {
+        let client = client.clone();
+        let org_id = org_id.clone();
+        async move {
+            let response = client
+                .get(format!(
+                    "{}/api/orgs/{}/analytics/header-fields?field={}&limit=100",
+                    BASE_URL, org_id, field
+                ))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body: serde_json::Value = response.json().await.unwrap();
+            body["values"].as_array().unwrap().clone()
+        }
+    };
+
+    let tenants = breakdown("x-tenant").await;
+    let recorded = tenants
+        .iter()
+        .find(|v| v["value"].as_str() == Some(tenant.as_str()))
+        .expect("allowlisted header value should be recorded");
+    assert_eq!(recorded["count"].as_i64(), Some(1));
+
+    let secrets = breakdown("x-secret").await;
+    assert!(
+        !secrets
+            .iter()
+            .any(|v| v["value"].as_str() == Some(secret.as_str())),
+        "non-allowlisted header must not be recorded"
+    );
+
+    // Credential headers can't be allowlisted
+    let response = client
+        .patch(format!("{}/api/orgs/{}/settings", BASE_URL, org_id))
+        .json(&json!({ "analytics_header_fields": ["cookie"] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = client
+        .patch(format!("{}/api/orgs/{}/settings", BASE_URL, org_id))
+        .json(&json!({ "analytics_header_fields": [] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
@@ -249,3 +249,736 @@ async fn test_get_link_by_short_code_not_found() {
 
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
+
+#[tokio::test]
+async fn test_get_link_analytics_compare_previous() {
+    let client = authenticated_client();
+    let redirect_client = test_client();
+
+    let create_response = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": "https://example.com/analytics-compare-test",
+            "redirect_type": "301"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(create_response.status(), StatusCode::OK);
+    let created_link: serde_json::Value = create_response.json().await.unwrap();
+    let link_id = created_link["id"].as_str().unwrap();
+    let short_code = created_link["short_code"].as_str().unwrap();
+
+    for _ in 0..3 {
+        redirect_client
+            .get(format!("{}/{}", BASE_URL, short_code))
+            .send()
+            .await
+            .unwrap();
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+
+    // Clicks fall in the current window; the preceding hour-long window is empty
+    let (start, end) = (now - 3600, now + 3600);
+    let body: serde_json::Value = client
+        .get(format!(
+            "{}/api/links/{}/analytics?start={}&end={}&compare=previous",
+            BASE_URL, link_id, start, end
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let comparison = &body["comparison"];
+    assert_eq!(comparison["previous_end"], start - 1);
+    assert_eq!(comparison["previous_start"], start - 1 - (end - start));
+    assert_eq!(comparison["current_total_clicks"], 3);
+    assert_eq!(comparison["previous_total_clicks"], 0);
+    assert!(comparison["total_clicks_change_pct"].is_null());
+
+    // Shift the window forward so the clicks fall in the previous window
+    let (start, end) = (now + 1800, now + 9000);
+    let body: serde_json::Value = client
+        .get(format!(
+            "{}/api/links/{}/analytics?start={}&end={}&compare=previous",
+            BASE_URL, link_id, start, end
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let comparison = &body["comparison"];
+    assert_eq!(comparison["current_total_clicks"], 0);
+    assert_eq!(comparison["previous_total_clicks"], 3);
+    assert_eq!(comparison["total_clicks_change_pct"], -100.0);
+
+    // Without compare, no comparison is returned
+    let body: serde_json::Value = client
+        .get(format!("{}/api/links/{}/analytics", BASE_URL, link_id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(body.get("comparison").is_none());
+}
+
+#[tokio::test]
+async fn test_get_org_analytics_rejects_unknown_compare() {
+    let client = authenticated_client();
+
+    let response = client
+        .get(format!("{}/api/analytics/org?compare=last_year", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_link_clicks_over_time_bucketed_by_tz_offset() {
+    let client = authenticated_client();
+
+    let response = create_test_link("https://example.com/analytics-tz-test", None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let link: serde_json::Value = response.json().await.unwrap();
+    let link_id = link["id"].as_str().unwrap();
+    let short_code = link["short_code"].as_str().unwrap();
+
+    test_client()
+        .get(format!("{}/{}", BASE_URL, short_code))
+        .send()
+        .await
+        .unwrap();
+    let clicked_at = chrono::Utc::now();
+
+    // Wait for deferred analytics to complete
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    // UTC+14 and UTC-12 are 26 hours apart, so the same click always lands
+    // on different local days; each must match the click time shifted by it
+    for offset_minutes in [0i64, 840, -720] {
+        let response = client
+            .get(format!(
+                "{}/api/links/{}/analytics?days=7&tz_offset_minutes={}",
+                BASE_URL, link_id, offset_minutes
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = response.json().await.unwrap();
+
+        let expected_day = (clicked_at + chrono::Duration::minutes(offset_minutes))
+            .format("%Y-%m-%d")
+            .to_string();
+        let buckets = body["clicks_over_time"].as_array().unwrap();
+        assert_eq!(buckets.len(), 1, "offset {}: {:?}", offset_minutes, buckets);
+        assert_eq!(
+            buckets[0]["date"].as_str(),
+            Some(expected_day.as_str()),
+            "offset {}",
+            offset_minutes
+        );
+        assert_eq!(buckets[0]["count"], 1);
+    }
+
+    let response = client
+        .get(format!(
+            "{}/api/links/{}/analytics?tz_offset_minutes=900",
+            BASE_URL, link_id
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let _ = client
+        .delete(format!("{}/api/links/{}", BASE_URL, link_id))
+        .send()
+        .await;
+}
+
+#[tokio::test]
+async fn test_link_clicks_over_time_granularity() {
+    use chrono::Datelike;
+
+    let client = authenticated_client();
+
+    let response = create_test_link("https://example.com/analytics-granularity", None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let link: serde_json::Value = response.json().await.unwrap();
+    let link_id = link["id"].as_str().unwrap();
+    let short_code = link["short_code"].as_str().unwrap();
+
+    for _ in 0..3 {
+        test_client()
+            .get(format!("{}/{}", BASE_URL, short_code))
+            .send()
+            .await
+            .unwrap();
+    }
+    let clicked_at = chrono::Utc::now();
+
+    // Wait for deferred analytics to complete
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let week_start = clicked_at.date_naive()
+        - chrono::Duration::days(clicked_at.weekday().num_days_from_monday() as i64);
+    let expected = [
+        ("hour", clicked_at.format("%Y-%m-%dT%H:00").to_string()),
+        ("day", clicked_at.format("%Y-%m-%d").to_string()),
+        ("week", week_start.format("%Y-%m-%d").to_string()),
+        ("month", clicked_at.format("%Y-%m").to_string()),
+    ];
+    for (granularity, expected_bucket) in expected {
+        let response = client
+            .get(format!(
+                "{}/api/links/{}/analytics?days=7&granularity={}",
+                BASE_URL, link_id, granularity
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "{}", granularity);
+        let body: serde_json::Value = response.json().await.unwrap();
+
+        let buckets = body["clicks_over_time"].as_array().unwrap();
+        assert_eq!(buckets.len(), 1, "{}: {:?}", granularity, buckets);
+        assert_eq!(
+            buckets[0]["date"].as_str(),
+            Some(expected_bucket.as_str()),
+            "{}",
+            granularity
+        );
+        assert_eq!(buckets[0]["count"], 3, "{}", granularity);
+    }
+
+    let response = client
+        .get(format!(
+            "{}/api/links/{}/analytics?days=7&granularity=minute",
+            BASE_URL, link_id
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let _ = client
+        .delete(format!("{}/api/links/{}", BASE_URL, link_id))
+        .send()
+        .await;
+}
+
+#[tokio::test]
+async fn test_link_analytics_downsampled_to_max_points() {
+    let client = authenticated_client();
+
+    let response = create_test_link("https://example.com/analytics-max-points", None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let link: serde_json::Value = response.json().await.unwrap();
+    let link_id = link["id"].as_str().unwrap();
+    let short_code = link["short_code"].as_str().unwrap();
+
+    for _ in 0..3 {
+        test_client()
+            .get(format!("{}/{}", BASE_URL, short_code))
+            .send()
+            .await
+            .unwrap();
+    }
+
+    // Wait for deferred analytics to complete
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    // A week of hourly buckets is 168 points, so this is downsampled
+    let downsampled: serde_json::Value = client
+        .get(format!(
+            "{}/api/links/{}/analytics?days=7&granularity=hour&max_points=52",
+            BASE_URL, link_id
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // Under the cap the full-resolution series is kept
+    let full: serde_json::Value = client
+        .get(format!(
+            "{}/api/links/{}/analytics?days=7&granularity=hour&max_points=1000",
+            BASE_URL, link_id
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let invalid_status = client
+        .get(format!(
+            "{}/api/links/{}/analytics?days=7&max_points=0",
+            BASE_URL, link_id
+        ))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let _ = client
+        .delete(format!("{}/api/links/{}", BASE_URL, link_id))
+        .send()
+        .await;
+
+    let buckets = downsampled["clicks_over_time"].as_array().unwrap();
+    assert!(buckets.len() <= 52, "{} buckets", buckets.len());
+    let summed: i64 = buckets.iter().map(|b| b["count"].as_i64().unwrap()).sum();
+    assert_eq!(
+        summed,
+        downsampled["total_clicks_in_range"].as_i64().unwrap()
+    );
+    assert_eq!(summed, 3);
+    let bucket_seconds = downsampled["bucket_seconds"].as_i64().unwrap();
+    assert!(bucket_seconds * 52 >= 7 * 24 * 60 * 60);
+
+    assert!(full.get("bucket_seconds").is_none());
+    let full_buckets = full["clicks_over_time"].as_array().unwrap();
+    assert_eq!(full_buckets.len(), 1);
+    assert_eq!(full_buckets[0]["count"], 3);
+
+    assert_eq!(invalid_status, StatusCode::BAD_REQUEST);
+}
+
+// Sampling itself is covered by unit tests in models::analytics_sampling;
+// only rejected values are sent here so global settings never change.
+#[tokio::test]
+async fn test_analytics_sampling_settings_validation() {
+    let client = authenticated_client();
+
+    let mut statuses = Vec::new();
+    for (key, value) in [
+        ("analytics_sample_rate", "1"),
+        ("analytics_event_soft_cap_pro", "-5"),
+        ("analytics_event_soft_cap_platinum", "100"),
+    ] {
+        let status = client
+            .put(format!("{}/api/admin/settings", BASE_URL))
+            .json(&json!({ "key": key, "value": value }))
+            .send()
+            .await
+            .unwrap()
+            .status();
+        if status == StatusCode::FORBIDDEN {
+            println!("Test user is not an admin - skipping test");
+            return;
+        }
+        statuses.push(status);
+    }
+
+    assert!(statuses.iter().all(|s| *s == StatusCode::BAD_REQUEST));
+}
+
+#[tokio::test]
+async fn test_get_link_events_paginates_newest_first() {
+    let client = authenticated_client();
+    let redirect_client = test_client();
+
+    let created_link: serde_json::Value = create_test_link("https://example.com/events-test", None)
+        .await
+        .json()
+        .await
+        .unwrap();
+    let link_id = created_link["id"].as_str().unwrap();
+    let short_code = created_link["short_code"].as_str().unwrap();
+
+    for i in 0..3 {
+        redirect_client
+            .get(format!("{}/{}", BASE_URL, short_code))
+            .header("User-Agent", format!("Mozilla/5.0 EventsBot/{}", i))
+            .header("Referer", "https://events.example.com")
+            .send()
+            .await
+            .unwrap();
+    }
+
+    // Wait briefly for deferred analytics to complete
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let fetch_page = |page: i64| {
+        let client = client.clone();
+        let url = format!(
+            "{}/api/links/{}/events?page={}&limit=2",
+            BASE_URL, link_id, page
+        );
+        async move {
+            let response = client.get(url).send().await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            response.json::<serde_json::Value>().await.unwrap()
+        }
+    };
+
+    let first = fetch_page(1).await;
+    assert_eq!(first["pagination"]["total"], 3);
+    assert_eq!(first["pagination"]["total_pages"], 2);
+    assert_eq!(first["pagination"]["has_next"], true);
+    assert_eq!(first["pagination"]["has_prev"], false);
+
+    let second = fetch_page(2).await;
+    assert_eq!(second["pagination"]["has_next"], false);
+    assert_eq!(second["pagination"]["has_prev"], true);
+
+    let events: Vec<serde_json::Value> = first["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .chain(second["data"].as_array().unwrap())
+        .cloned()
+        .collect();
+    assert_eq!(events.len(), 3);
+    for pair in events.windows(2) {
+        assert!(
+            pair[0]["timestamp"].as_i64().unwrap() >= pair[1]["timestamp"].as_i64().unwrap(),
+            "events should be newest first"
+        );
+    }
+    let mut agents: Vec<&str> = events
+        .iter()
+        .map(|e| e["user_agent"].as_str().unwrap())
+        .collect();
+    agents.sort();
+    assert_eq!(
+        agents,
+        vec![
+            "Mozilla/5.0 EventsBot/0",
+            "Mozilla/5.0 EventsBot/1",
+            "Mozilla/5.0 EventsBot/2"
+        ]
+    );
+    assert!(
+        events
+            .iter()
+            .all(|e| e["referrer"] == "https://events.example.com")
+    );
+
+    // Past the last page: empty, but the total is still reported
+    let beyond = fetch_page(3).await;
+    assert!(beyond["data"].as_array().unwrap().is_empty());
+    assert_eq!(beyond["pagination"]["total"], 3);
+}
+
+#[tokio::test]
+async fn test_get_link_events_not_found() {
+    let client = authenticated_client();
+
+    let response = client
+        .get(format!("{}/api/links/nonexistent-id/events", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_reset_link_clicks_zeroes_count_and_deletes_events() {
+    let client = authenticated_client();
+    let redirect_client = test_client();
+
+    let created_link: serde_json::Value =
+        create_test_link("https://example.com/reset-clicks-test", None)
+            .await
+            .json()
+            .await
+            .unwrap();
+    let link_id = created_link["id"].as_str().unwrap();
+    let short_code = created_link["short_code"].as_str().unwrap();
+
+    for _ in 0..3 {
+        redirect_client
+            .get(format!("{}/{}", BASE_URL, short_code))
+            .send()
+            .await
+            .unwrap();
+    }
+
+    // Wait briefly for deferred analytics to complete
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let link_url = format!("{}/api/links/{}", BASE_URL, link_id);
+    let events_url = format!("{}/api/links/{}/events", BASE_URL, link_id);
+    let get_json = |url: String| {
+        let client = client.clone();
+        async move {
+            client
+                .get(url)
+                .send()
+                .await
+                .unwrap()
+                .json::<serde_json::Value>()
+                .await
+                .unwrap()
+        }
+    };
+    assert_eq!(get_json(link_url.clone()).await["click_count"], 3);
+
+    // Counter only: events are kept
+    let response = client
+        .post(format!("{}/reset-clicks", link_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["click_count"], 0);
+    assert_eq!(body["events_deleted"], 0);
+    assert_eq!(get_json(link_url.clone()).await["click_count"], 0);
+    assert_eq!(get_json(events_url.clone()).await["pagination"]["total"], 3);
+
+    let response = client
+        .post(format!(
+            "{}/reset-clicks?delete_events=true&start=10&end=5",
+            link_url
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Counter and events
+    let response = client
+        .post(format!("{}/reset-clicks?delete_events=true", link_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["events_deleted"], 3);
+    assert_eq!(get_json(events_url).await["pagination"]["total"], 0);
+
+    let response = client
+        .post(format!(
+            "{}/api/links/nonexistent-id/reset-clicks",
+            BASE_URL
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let _ = client.delete(link_url).send().await;
+}
+
+#[tokio::test]
+async fn test_admin_clicks_by_link_status() {
+    let client = authenticated_client();
+    let public_client = test_client();
+    let start = chrono::Utc::now().timestamp() - 1;
+
+    // (destination, clicks, status to move the link to after clicking)
+    let plan = [
+        ("https://example.com/status-active", 3, "active"),
+        ("https://example.com/status-disabled", 1, "disabled"),
+        ("https://example.com/status-blocked", 2, "blocked"),
+    ];
+    for (destination, clicks, status) in plan {
+        let link: serde_json::Value = create_test_link(destination, None)
+            .await
+            .json()
+            .await
+            .unwrap();
+        for _ in 0..clicks {
+            public_client
+                .get(format!(
+                    "{}/{}",
+                    BASE_URL,
+                    link["short_code"].as_str().unwrap()
+                ))
+                .send()
+                .await
+                .unwrap();
+        }
+        if status != "active" {
+            let response = client
+                .put(format!(
+                    "{}/api/admin/links/{}",
+                    BASE_URL,
+                    link["id"].as_str().unwrap()
+                ))
+                .json(&json!({ "status": status }))
+                .send()
+                .await
+                .unwrap();
+            if response.status() == StatusCode::FORBIDDEN {
+                println!("Test user is not an admin - skipping test");
+                return;
+            }
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let end = chrono::Utc::now().timestamp() + 60;
+    let response = client
+        .get(format!(
+            "{}/api/admin/analytics/by-status?start={}&end={}",
+            BASE_URL, start, end
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["start"], start);
+    assert_eq!(body["end"], end);
+
+    // Other tests may add clicks in the same window, so counts are lower bounds
+    let statuses = body["statuses"].as_array().unwrap();
+    let names: Vec<&str> = statuses
+        .iter()
+        .map(|s| s["status"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["active", "disabled", "blocked"]);
+    for (entry, (_, clicks, _)) in statuses.iter().zip(plan) {
+        assert!(
+            entry["clicks"].as_i64().unwrap() >= clicks,
+            "Expected at least {} clicks for {}, got {}",
+            clicks,
+            entry["status"],
+            entry["clicks"]
+        );
+        assert!(entry["links"].as_i64().unwrap() >= 1);
+    }
+    let total: i64 = statuses.iter().map(|s| s["clicks"].as_i64().unwrap()).sum();
+    assert_eq!(body["total_clicks"].as_i64().unwrap(), total);
+
+    // A window is required and must be well-formed
+    for query in [
+        format!("start={}", start),
+        format!("start={}&end={}", end, start),
+        "start=abc&end=1".to_string(),
+    ] {
+        let response = client
+            .get(format!(
+                "{}/api/admin/analytics/by-status?{}",
+                BASE_URL, query
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", query);
+    }
+
+    let response = public_client
+        .get(format!(
+            "{}/api/admin/analytics/by-status?start={}&end={}",
+            BASE_URL, start, end
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_export_link_analytics_csv() {
+    let client = authenticated_client();
+    let redirect_client = test_client();
+
+    let created_link: serde_json::Value =
+        create_test_link("https://example.com/click-export-test", None)
+            .await
+            .json()
+            .await
+            .unwrap();
+    let link_id = created_link["id"].as_str().unwrap();
+    let short_code = created_link["short_code"].as_str().unwrap();
+
+    for i in 0..2 {
+        redirect_client
+            .get(format!("{}/{}", BASE_URL, short_code))
+            .header("User-Agent", format!("Mozilla/5.0 ClickExportBot/{}", i))
+            .header("Referer", "https://export.example.com")
+            .send()
+            .await
+            .unwrap();
+    }
+
+    // Wait briefly for deferred analytics to complete
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let response = client
+        .get(format!(
+            "{}/api/links/{}/analytics/export?days=1",
+            BASE_URL, link_id
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let headers = response.headers();
+    assert!(
+        headers
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/csv"))
+    );
+    assert!(
+        headers
+            .get("content-disposition")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("attachment;") && v.contains(short_code))
+    );
+    assert_eq!(
+        headers
+            .get("x-export-total-rows")
+            .and_then(|v| v.to_str().ok()),
+        Some("2")
+    );
+    assert!(headers.get("x-export-truncated").is_none());
+
+    let csv = response.text().await.unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next().unwrap(),
+        "timestamp,referrer,user_agent,country,city"
+    );
+    let rows: Vec<Vec<&str>> = lines.map(|l| l.split(',').collect()).collect();
+    assert_eq!(rows.len(), 2);
+    assert!(rows.iter().all(|r| r[1] == "https://export.example.com"));
+    let mut agents: Vec<&str> = rows.iter().map(|r| r[2]).collect();
+    agents.sort();
+    assert_eq!(
+        agents,
+        vec![
+            "Mozilla/5.0 ClickExportBot/0",
+            "Mozilla/5.0 ClickExportBot/1"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_export_link_analytics_not_found() {
+    let client = authenticated_client();
+
+    let response = client
+        .get(format!(
+            "{}/api/links/nonexistent-id/analytics/export",
+            BASE_URL
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
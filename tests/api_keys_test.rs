@@ -923,3 +923,190 @@ async fn test_admin_api_keys_search_with_status() {
         });
     assert!(beta_active_found);
 }
+
+/// Create a key scoped to the primary test org with the given scopes.
+/// Returns `(key_id, raw_token)`.
+async fn create_scoped_key(scopes: serde_json::Value) -> (String, String) {
+    let org_id = get_primary_test_org_id().await;
+    let res = authenticated_client()
+        .post(format!("{}/api/settings/api-keys", BASE_URL))
+        .json(&json!({
+            "name": "Scoped Test Key",
+            "expires_in_days": 1,
+            "org_ids": [org_id],
+            "scopes": scopes
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let data: serde_json::Value = res.json().await.unwrap();
+    (
+        data["id"].as_str().unwrap().to_string(),
+        data["raw_token"].as_str().unwrap().to_string(),
+    )
+}
+
+async fn revoke_key(key_id: &str) {
+    authenticated_client()
+        .delete(format!("{}/api/settings/api-keys/{}", BASE_URL, key_id))
+        .send()
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_read_only_api_key_cannot_create_links() {
+    let (key_id, raw_token) = create_scoped_key(json!(["links:read"])).await;
+    let server_client = test_client();
+
+    let list_res = server_client
+        .get(format!("{}/api/links", BASE_URL))
+        .header("Authorization", format!("Bearer {}", raw_token))
+        .send()
+        .await
+        .unwrap();
+    let list_status = list_res.status();
+
+    let create_res = server_client
+        .post(format!("{}/api/links", BASE_URL))
+        .header("Authorization", format!("Bearer {}", raw_token))
+        .json(&json!({"destination_url": "https://example.com/read-only-scope"}))
+        .send()
+        .await
+        .unwrap();
+    let create_status = create_res.status();
+    let create_body = create_res.text().await.unwrap();
+
+    let analytics_res = server_client
+        .get(format!("{}/api/analytics/org?days=7", BASE_URL))
+        .header("Authorization", format!("Bearer {}", raw_token))
+        .send()
+        .await
+        .unwrap();
+    let analytics_status = analytics_res.status();
+
+    revoke_key(&key_id).await;
+
+    assert_eq!(list_status, StatusCode::OK);
+    assert_eq!(create_status, StatusCode::FORBIDDEN);
+    assert!(create_body.contains("insufficient_scope"));
+    assert_eq!(analytics_status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_scoped_api_key_allowed_combinations() {
+    let (key_id, raw_token) = create_scoped_key(json!(["links:write", "analytics:read"])).await;
+    let server_client = test_client();
+
+    // links:write implies links:read
+    let list_res = server_client
+        .get(format!("{}/api/links", BASE_URL))
+        .header("Authorization", format!("Bearer {}", raw_token))
+        .send()
+        .await
+        .unwrap();
+    let list_status = list_res.status();
+
+    let create_res = server_client
+        .post(format!("{}/api/links", BASE_URL))
+        .header("Authorization", format!("Bearer {}", raw_token))
+        .json(&json!({"destination_url": "https://example.com/write-scope"}))
+        .send()
+        .await
+        .unwrap();
+    let create_status = create_res.status();
+
+    let analytics_res = server_client
+        .get(format!("{}/api/analytics/org?days=7", BASE_URL))
+        .header("Authorization", format!("Bearer {}", raw_token))
+        .send()
+        .await
+        .unwrap();
+    let analytics_status = analytics_res.status();
+
+    // Managing API keys needs the admin scope
+    let keys_res = server_client
+        .get(format!("{}/api/settings/api-keys", BASE_URL))
+        .header("Authorization", format!("Bearer {}", raw_token))
+        .send()
+        .await
+        .unwrap();
+    let keys_status = keys_res.status();
+
+    revoke_key(&key_id).await;
+
+    assert_eq!(list_status, StatusCode::OK);
+    assert_eq!(create_status, StatusCode::OK);
+    assert_eq!(analytics_status, StatusCode::OK);
+    assert_eq!(keys_status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_api_key_scopes_default_to_admin_and_reject_unknown() {
+    let auth_client = authenticated_client();
+
+    let (key_id, _) = create_scoped_key(json!([])).await;
+    let list_res = auth_client
+        .get(format!("{}/api/settings/api-keys", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    let keys = list_res.json::<Vec<serde_json::Value>>().await.unwrap();
+    let scopes = keys
+        .iter()
+        .find(|k| k["id"].as_str() == Some(key_id.as_str()))
+        .map(|k| k["scopes"].clone());
+    revoke_key(&key_id).await;
+    assert_eq!(scopes, Some(json!(["admin"])));
+
+    let res = auth_client
+        .post(format!("{}/api/settings/api-keys", BASE_URL))
+        .json(&json!({"name": "Bad Scope Key", "scopes": ["links:delete"]}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_list_keys_reports_last_used_at() {
+    let (key_id, raw_token) = create_scoped_key(json!(["links:read"])).await;
+    let auth_client = authenticated_client();
+
+    let find_key = |keys: Vec<serde_json::Value>| {
+        keys.into_iter()
+            .find(|k| k["id"].as_str() == Some(key_id.as_str()))
+            .expect("key should be listed")
+    };
+
+    let before = auth_client
+        .get(format!("{}/api/keys", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(before.status(), StatusCode::OK);
+    let before_key = find_key(before.json().await.unwrap());
+    assert!(before_key["last_used_at"].is_null());
+
+    let use_res = test_client()
+        .get(format!("{}/api/links", BASE_URL))
+        .header("Authorization", format!("Bearer {}", raw_token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(use_res.status(), StatusCode::OK);
+
+    // The write happens after the response is sent
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let after = auth_client
+        .get(format!("{}/api/keys", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    let after_key = find_key(after.json().await.unwrap());
+    revoke_key(&key_id).await;
+
+    assert!(after_key["last_used_at"].is_i64());
+}
@@ -183,3 +183,394 @@ async fn test_google_oauth_account_linking_via_email() {
         "Google login should redirect"
     );
 }
+
+/// Test that a Google login records the provider on the user and drops the
+/// readable `rushomon_last_provider` cookie holding only the provider name.
+#[tokio::test]
+async fn test_google_oauth_records_last_login_provider() {
+    let client = test_client();
+
+    let init_response = client
+        .get(format!("{}/api/auth/google", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(init_response.status(), StatusCode::FOUND);
+    let location = init_response
+        .headers()
+        .get("location")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    let state = location
+        .split(['?', '&'])
+        .find_map(|part| part.strip_prefix("state="))
+        .expect("state param not found in redirect URL")
+        .to_string();
+
+    let callback_response = client
+        .get(format!(
+            "{}/api/auth/callback?code=mock-google-code-{}&state={}",
+            BASE_URL, state, state
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(callback_response.status(), StatusCode::FOUND);
+
+    let cookies: Vec<String> = callback_response
+        .headers()
+        .get_all("set-cookie")
+        .iter()
+        .map(|v| v.to_str().unwrap().to_string())
+        .collect();
+
+    let provider_cookie = cookies
+        .iter()
+        .find(|c| c.starts_with("rushomon_last_provider="))
+        .expect("Callback should set rushomon_last_provider cookie");
+    assert!(
+        provider_cookie.starts_with("rushomon_last_provider=google;"),
+        "Cookie should hold only the provider name, got: {}",
+        provider_cookie
+    );
+    assert!(
+        !provider_cookie.contains("HttpOnly"),
+        "Cookie must be readable by the login page, got: {}",
+        provider_cookie
+    );
+
+    let access_token = cookies
+        .iter()
+        .find_map(|c| c.strip_prefix("rushomon_access="))
+        .and_then(|rest| rest.split(';').next())
+        .expect("Callback should set rushomon_access cookie");
+
+    let me_response = client
+        .get(format!("{}/api/auth/me", BASE_URL))
+        .header("Cookie", format!("rushomon_access={}", access_token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(me_response.status(), StatusCode::OK);
+    let me: serde_json::Value = me_response.json().await.unwrap();
+    assert_eq!(me["last_login_provider"], "google");
+    assert!(me["last_login_at"].is_i64());
+}
+
+/// Run a Google sign-in for a fresh mock user (always `@gmail.com`) and
+/// return the callback response.
+async fn google_sign_in_new_user(client: &reqwest::Client) -> reqwest::Response {
+    let init_response = client
+        .get(format!("{}/api/auth/google", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(init_response.status(), StatusCode::FOUND);
+    let location = init_response
+        .headers()
+        .get("location")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    let state = location
+        .split(['?', '&'])
+        .find_map(|part| part.strip_prefix("state="))
+        .expect("state param not found in redirect URL")
+        .to_string();
+
+    client
+        .get(format!(
+            "{}/api/auth/callback?code=mock-google-code-{}&state={}",
+            BASE_URL, state, state
+        ))
+        .send()
+        .await
+        .unwrap()
+}
+
+/// Test the `signup_email_domains` setting without changing it: the gate
+/// itself is unit-tested in utils::validation, and other tests here sign up
+/// @gmail.com users concurrently, so this only checks that an invalid value
+/// is rejected and that new users get through while the list is empty.
+#[tokio::test]
+async fn test_signup_email_domain_allowlist() {
+    let admin = authenticated_client();
+    let invalid_status = admin
+        .put(format!("{}/api/admin/settings", BASE_URL))
+        .json(&serde_json::json!({ "key": "signup_email_domains", "value": "not a domain" }))
+        .send()
+        .await
+        .unwrap()
+        .status();
+    if invalid_status == StatusCode::FORBIDDEN {
+        println!("Test user is not an admin - skipping test");
+        return;
+    }
+    assert_eq!(invalid_status, StatusCode::BAD_REQUEST);
+
+    let settings: serde_json::Value = admin
+        .get(format!("{}/api/admin/settings", BASE_URL))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    if settings["signup_email_domains"]
+        .as_str()
+        .is_some_and(|v| !v.is_empty())
+    {
+        println!("signup_email_domains is set on this instance - skipping signup check");
+        return;
+    }
+
+    let allowed = google_sign_in_new_user(&test_client()).await;
+    assert_eq!(allowed.status(), StatusCode::FOUND);
+    assert!(
+        allowed
+            .headers()
+            .get_all("set-cookie")
+            .iter()
+            .any(|v| v.to_str().unwrap_or("").contains("rushomon_access=")),
+        "an empty allowlist should let any domain sign up"
+    );
+}
+
+/// Sign in a fresh Google user and return `(access_token, me)`.
+async fn new_google_user() -> (String, serde_json::Value) {
+    let callback_response = google_sign_in_new_user(&test_client()).await;
+    assert_eq!(callback_response.status(), StatusCode::FOUND);
+    let access_token = callback_response
+        .headers()
+        .get_all("set-cookie")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .find_map(|c| c.strip_prefix("rushomon_access="))
+        .and_then(|rest| rest.split(';').next())
+        .expect("Callback should set rushomon_access cookie")
+        .to_string();
+
+    let me: serde_json::Value = test_client()
+        .get(format!("{}/api/auth/me", BASE_URL))
+        .header("Cookie", format!("rushomon_access={}", access_token))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    (access_token, me)
+}
+
+/// Test that merging a duplicate account moves its links and memberships to
+/// the primary account and deletes the duplicate.
+#[tokio::test]
+async fn test_admin_merge_duplicate_users() {
+    let admin = authenticated_client();
+    let response = admin
+        .get(format!("{}/api/admin/users?limit=1", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    if response.status() == StatusCode::FORBIDDEN {
+        println!("Test user is not an admin - skipping test");
+        return;
+    }
+
+    let (primary_token, primary) = new_google_user().await;
+    let (duplicate_token, duplicate) = new_google_user().await;
+    let primary_id = primary["id"].as_str().unwrap().to_string();
+    let duplicate_id = duplicate["id"].as_str().unwrap().to_string();
+    let duplicate_org_id = duplicate["org_id"].as_str().unwrap().to_string();
+
+    let link: serde_json::Value = test_client()
+        .post(format!("{}/api/links", BASE_URL))
+        .header("Cookie", format!("rushomon_access={}", duplicate_token))
+        .json(&serde_json::json!({ "destination_url": "https://example.com/merge-users" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let link_id = link["id"].as_str().unwrap().to_string();
+
+    let merge = |primary_id: String, duplicate_id: String| {
+        let admin = admin.clone();
+        async move {
+            admin
+                .post(format!("{}/api/admin/users/merge", BASE_URL))
+                .json(&serde_json::json!({
+                    "primary_id": primary_id,
+                    "duplicate_id": duplicate_id
+                }))
+                .send()
+                .await
+                .unwrap()
+        }
+    };
+
+    let self_merge_status = merge(primary_id.clone(), primary_id.clone()).await.status();
+
+    let response = merge(primary_id.clone(), duplicate_id.clone()).await;
+    let merge_status = response.status();
+    let merged: serde_json::Value = response.json().await.unwrap();
+
+    let primary_links: serde_json::Value = admin
+        .get(format!(
+            "{}/api/admin/users/{}/links?limit=100",
+            BASE_URL, primary_id
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let primary_orgs: serde_json::Value = test_client()
+        .get(format!("{}/api/orgs", BASE_URL))
+        .header("Cookie", format!("rushomon_access={}", primary_token))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let duplicate_status = admin
+        .get(format!("{}/api/admin/users/{}", BASE_URL, duplicate_id))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let _ = admin
+        .delete(format!("{}/api/admin/users/{}", BASE_URL, primary_id))
+        .json(&serde_json::json!({ "confirmation": "DELETE" }))
+        .send()
+        .await;
+
+    assert_eq!(self_merge_status, StatusCode::BAD_REQUEST);
+    assert_eq!(merge_status, StatusCode::OK, "{}", merged);
+    assert_eq!(merged["memberships_moved"], 1);
+    assert_eq!(merged["links_moved"], 1);
+
+    let moved_link = primary_links["links"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|l| l["id"] == link_id.as_str())
+        .expect("the duplicate's link should now belong to the primary");
+    assert_eq!(moved_link["org_id"], duplicate_org_id.as_str());
+
+    let duplicate_org = primary_orgs["orgs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|o| o["id"] == duplicate_org_id.as_str())
+        .expect("the primary should be a member of the duplicate's org");
+    assert_eq!(duplicate_org["role"], "owner");
+
+    assert_eq!(duplicate_status, StatusCode::NOT_FOUND);
+}
+
+/// Test that links disabled by a suspension redirect to the configured
+/// `suspended_link_redirect_url`, and back to /404 once the user is unsuspended.
+#[tokio::test]
+async fn test_suspended_user_links_use_configured_redirect() {
+    let admin = authenticated_client();
+    let set_url = |value: &'static str| {
+        let admin = admin.clone();
+        async move {
+            admin
+                .put(format!("{}/api/admin/settings", BASE_URL))
+                .json(&serde_json::json!({ "key": "suspended_link_redirect_url", "value": value }))
+                .send()
+                .await
+                .unwrap()
+                .status()
+        }
+    };
+    let notice_url = "https://example.com/account-suspended";
+    let status = set_url(notice_url).await;
+    if status == StatusCode::FORBIDDEN {
+        println!("Test user is not an admin - skipping test");
+        return;
+    }
+    assert_eq!(status, StatusCode::OK);
+
+    let (token, me) = new_google_user().await;
+    let user_id = me["id"].as_str().unwrap().to_string();
+    let link: serde_json::Value = test_client()
+        .post(format!("{}/api/links", BASE_URL))
+        .header("Cookie", format!("rushomon_access={}", token))
+        .json(&serde_json::json!({ "destination_url": "https://example.com/suspended-owner" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let short_code = link["short_code"].as_str().unwrap().to_string();
+
+    let suspend_status = admin
+        .put(format!("{}/api/admin/users/{}/suspend", BASE_URL, user_id))
+        .json(&serde_json::json!({ "reason": "Terms violation" }))
+        .send()
+        .await
+        .unwrap()
+        .status();
+    let redirect_location = |short_code: String| async move {
+        let response = test_client()
+            .get(format!("{}/{}", BASE_URL, short_code))
+            .send()
+            .await
+            .unwrap();
+        (
+            response.status(),
+            response
+                .headers()
+                .get("location")
+                .and_then(|l| l.to_str().ok())
+                .unwrap_or_default()
+                .to_string(),
+        )
+    };
+    let (suspended_status, suspended_location) = redirect_location(short_code.clone()).await;
+
+    let invalid_status = set_url("not a url").await;
+    let unsuspend_status = admin
+        .put(format!(
+            "{}/api/admin/users/{}/unsuspend",
+            BASE_URL, user_id
+        ))
+        .send()
+        .await
+        .unwrap()
+        .status();
+    let (_, unsuspended_location) = redirect_location(short_code).await;
+
+    // Restore the default and clean up before asserting
+    let restore_status = set_url("").await;
+    let _ = admin
+        .delete(format!("{}/api/admin/users/{}", BASE_URL, user_id))
+        .json(&serde_json::json!({ "confirmation": "DELETE" }))
+        .send()
+        .await;
+
+    assert_eq!(suspend_status, StatusCode::OK);
+    assert_eq!(suspended_status, StatusCode::FOUND);
+    assert_eq!(suspended_location, notice_url);
+    assert_eq!(invalid_status, StatusCode::BAD_REQUEST);
+    assert_eq!(unsuspend_status, StatusCode::OK);
+    assert!(
+        unsuspended_location.ends_with("/404"),
+        "after unsuspend the link should 404 like any disabled link, got {}",
+        unsuspended_location
+    );
+    assert_eq!(restore_status, StatusCode::OK);
+}
@@ -474,3 +474,860 @@ async fn test_update_link_utm_params() {
         .send()
         .await;
 }
+
+#[tokio::test]
+async fn test_create_link_canonicalize_destination() {
+    let client = authenticated_client();
+    let submitted = "https://Example.COM:443/Page?utm_source=news&b=2&a=1&fbclid=xyz";
+
+    let response = client
+        .post(format!("{}/api/links?canonicalize=true", BASE_URL))
+        .json(&json!({ "destination_url": submitted }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let link: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(link["destination_url"], "https://example.com/Page?a=1&b=2");
+    assert_eq!(link["raw_destination"], submitted);
+
+    // The original is kept for reference on reads
+    let fetched: serde_json::Value = client
+        .get(format!(
+            "{}/api/links/{}",
+            BASE_URL,
+            link["id"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(
+        fetched["destination_url"],
+        "https://example.com/Page?a=1&b=2"
+    );
+    assert_eq!(fetched["raw_destination"], submitted);
+}
+
+#[tokio::test]
+async fn test_create_link_without_canonicalize_keeps_query() {
+    let client = authenticated_client();
+
+    let response = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({ "destination_url": "https://example.com/page?utm_source=news&b=2&a=1" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let link: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        link["destination_url"],
+        "https://example.com/page?utm_source=news&b=2&a=1"
+    );
+    assert!(link.get("raw_destination").is_none());
+}
+
+#[tokio::test]
+async fn test_list_links_count_only_matches_full_listing() {
+    let client = authenticated_client();
+    let marker = unique_short_code("cnt");
+    for i in 0..3 {
+        let response = create_test_link(
+            &format!("https://example.com/{}/{}", marker, i),
+            Some(&format!("Count only {}", marker)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let count_response = client
+        .get(format!(
+            "{}/api/links?search={}&count_only=true",
+            BASE_URL, marker
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(count_response.status(), StatusCode::OK);
+    let count_body: serde_json::Value = count_response.json().await.unwrap();
+    assert!(count_body.get("data").is_none());
+    assert!(count_body.get("links").is_none());
+    assert_eq!(count_body["total"].as_i64(), Some(3));
+
+    let full_body: serde_json::Value = client
+        .get(format!("{}/api/links?search={}", BASE_URL, marker))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(
+        full_body["pagination"]["total"].as_i64(),
+        count_body["total"].as_i64()
+    );
+}
+
+#[tokio::test]
+async fn test_check_code_reports_availability_and_enforces_rate_limit() {
+    let client = authenticated_client();
+    let taken = create_link_and_get_code("https://example.com/check-code").await;
+    let free = unique_short_code("free");
+
+    let mut limited = None;
+    // The per-IP budget is 30 requests per minute and is always enforced
+    for i in 0..31 {
+        let code = if i % 2 == 0 { &taken } else { &free };
+        let response = client
+            .get(format!("{}/api/links/check-code?code={}", BASE_URL, code))
+            .send()
+            .await
+            .unwrap();
+
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            limited = Some(response);
+            break;
+        }
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["code"], code.as_str());
+        assert_eq!(
+            body["available"],
+            i % 2 == 1,
+            "unexpected result for {}",
+            code
+        );
+    }
+
+    let limited = limited.expect("check-code should be rate limited after 30 requests");
+    assert!(limited.headers().get("retry-after").is_some());
+}
+
+#[tokio::test]
+async fn test_export_single_link_snapshot() {
+    let client = authenticated_client();
+
+    let response = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": "https://example.com/single-export",
+            "title": "Single Export",
+            "tags": ["export-a", "export-b"],
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let link: serde_json::Value = response.json().await.unwrap();
+    let link_id = link["id"].as_str().unwrap();
+
+    let response = client
+        .get(format!("{}/api/links/{}/export", BASE_URL, link_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let snapshot: serde_json::Value = response.json().await.unwrap();
+
+    assert_eq!(snapshot["schema_version"], 1);
+    assert_eq!(snapshot["link"]["id"], link["id"]);
+    assert_eq!(snapshot["link"]["short_code"], link["short_code"]);
+    assert_eq!(
+        snapshot["link"]["destination_url"],
+        "https://example.com/single-export"
+    );
+    assert_eq!(snapshot["link"]["title"], "Single Export");
+    let mut tags: Vec<&str> = snapshot["link"]["tags"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|t| t.as_str())
+        .collect();
+    tags.sort();
+    assert_eq!(tags, vec!["export-a", "export-b"]);
+    assert!(snapshot["rewrite_rules"].is_array());
+    assert_eq!(snapshot["stats"]["click_count"], 0);
+
+    let missing = client
+        .get(format!("{}/api/links/does-not-exist/export", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+
+    let _ = client
+        .delete(format!("{}/api/links/{}", BASE_URL, link_id))
+        .send()
+        .await;
+}
+
+#[tokio::test]
+async fn test_dashboard_bootstrap_contains_all_sections() {
+    let client = authenticated_client();
+    let link: serde_json::Value = create_test_link("https://example.com/dashboard-bootstrap", None)
+        .await
+        .json()
+        .await
+        .unwrap();
+    let link_id = link["id"].as_str().unwrap().to_string();
+
+    let response = client
+        .get(format!("{}/api/dashboard?limit=5", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    let status = response.status();
+    let body: serde_json::Value = response.json().await.unwrap();
+
+    let _ = client
+        .delete(format!("{}/api/links/{}", BASE_URL, link_id))
+        .send()
+        .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["user"]["id"].is_string());
+    assert!(body["user"]["email"].is_string());
+    assert!(!body["orgs"].as_array().unwrap().is_empty());
+    assert!(body["current_org_id"].is_string());
+    assert!(body["usage"]["tier"].is_string());
+    assert!(body["usage"]["limits"].is_object());
+    assert!(body["usage"]["usage"]["links_created_this_month"].is_number());
+    assert!(body["links"]["data"].as_array().unwrap().len() <= 5);
+    assert!(body["links"]["pagination"]["total"].as_i64().unwrap() >= 1);
+}
+
+// Reads anonymous_link_creation_enabled instead of toggling it, so the
+// shared instance setting never changes under concurrent tests.
+#[tokio::test]
+async fn test_claim_anonymous_link_once() {
+    let client = authenticated_client();
+
+    let unknown_token_status = client
+        .post(format!("{}/api/links/claim", BASE_URL))
+        .json(&json!({ "token": "rcl_not-a-real-token" }))
+        .send()
+        .await
+        .unwrap()
+        .status();
+    assert_eq!(unknown_token_status, StatusCode::NOT_FOUND);
+
+    let settings_response = client
+        .get(format!("{}/api/admin/settings", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    if settings_response.status() == StatusCode::FORBIDDEN {
+        println!("Test user is not an admin - skipping test");
+        return;
+    }
+    let settings: serde_json::Value = settings_response.json().await.unwrap();
+    let enabled = settings["anonymous_link_creation_enabled"] == "true";
+
+    let create_response = test_client()
+        .post(format!("{}/api/links/anonymous", BASE_URL))
+        .json(&json!({ "destination_url": "https://example.com/anonymous-claim" }))
+        .send()
+        .await
+        .unwrap();
+    if !enabled {
+        assert_eq!(create_response.status(), StatusCode::FORBIDDEN);
+        return;
+    }
+
+    assert_eq!(create_response.status(), StatusCode::OK);
+    let created: serde_json::Value = create_response.json().await.unwrap();
+    let token = created["claim_token"].as_str().unwrap().to_string();
+    let link_id = created["id"].as_str().unwrap().to_string();
+
+    let claim = client
+        .post(format!("{}/api/links/claim", BASE_URL))
+        .json(&json!({ "token": token }))
+        .send()
+        .await
+        .unwrap();
+    let claim_status = claim.status();
+    let claimed: serde_json::Value = claim.json().await.unwrap();
+
+    let second_claim_status = client
+        .post(format!("{}/api/links/claim", BASE_URL))
+        .json(&json!({ "token": token }))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    // The claimed link is now visible to the caller's org
+    let get_status = client
+        .get(format!("{}/api/links/{}", BASE_URL, link_id))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    let _ = client
+        .delete(format!("{}/api/links/{}", BASE_URL, link_id))
+        .send()
+        .await;
+
+    assert_eq!(claim_status, StatusCode::OK);
+    assert_eq!(claimed["id"], link_id.as_str());
+    assert_ne!(claimed["org_id"], "anonymous");
+    assert_eq!(second_claim_status, StatusCode::CONFLICT);
+    assert_eq!(get_status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_import_preserves_created_at() {
+    let client = authenticated_client();
+    let marker = unique_short_code("imp");
+    let now = chrono::Utc::now().timestamp();
+    let older = now - 2 * 86_400;
+    let newer = now - 86_400;
+
+    let response = client
+        .post(format!("{}/api/links/import", BASE_URL))
+        .json(&json!({
+            "links": [
+                {
+                    "destination_url": "https://example.com/imported-older",
+                    "title": format!("Older {}", marker),
+                    "created_at": older
+                },
+                {
+                    "destination_url": "https://example.com/imported-newer",
+                    "title": format!("Newer {}", marker),
+                    "created_at": newer
+                },
+                {
+                    "destination_url": "https://example.com/imported-future",
+                    "title": format!("Future {}", marker),
+                    "created_at": now + 86_400
+                }
+            ]
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let result: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(result["created"], 2);
+    assert_eq!(result["failed"], 1);
+    assert_eq!(result["errors"][0]["row"], 3);
+
+    let body: serde_json::Value = client
+        .get(format!(
+            "{}/api/links?search={}&sort=created",
+            BASE_URL, marker
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let links = body["data"].as_array().unwrap().clone();
+
+    for link in &links {
+        let _ = client
+            .delete(format!(
+                "{}/api/links/{}",
+                BASE_URL,
+                link["id"].as_str().unwrap()
+            ))
+            .send()
+            .await;
+    }
+
+    let created: Vec<i64> = links
+        .iter()
+        .map(|l| l["created_at"].as_i64().unwrap())
+        .collect();
+    assert_eq!(created, vec![newer, older]);
+}
+
+#[tokio::test]
+async fn test_get_links_by_codes() {
+    let client = authenticated_client();
+    let first = unique_short_code("bc");
+    let second = unique_short_code("bc");
+    let missing = unique_short_code("bcmiss");
+
+    let mut link_ids = Vec::new();
+    for code in [&first, &second] {
+        let response = client
+            .post(format!("{}/api/links", BASE_URL))
+            .json(&json!({
+                "destination_url": "https://example.com/by-codes",
+                "short_code": code
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let link: serde_json::Value = response.json().await.unwrap();
+        link_ids.push(link["id"].as_str().unwrap().to_string());
+    }
+
+    let response = client
+        .post(format!("{}/api/links/by-codes", BASE_URL))
+        .json(&json!({ "codes": [first, missing, second, first] }))
+        .send()
+        .await
+        .unwrap();
+    let status = response.status();
+    let body: serde_json::Value = response.json().await.unwrap();
+
+    let too_many: Vec<String> = (0..101).map(|i| format!("code{}", i)).collect();
+    let too_many_status = client
+        .post(format!("{}/api/links/by-codes", BASE_URL))
+        .json(&json!({ "codes": too_many }))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    for id in &link_ids {
+        let _ = client
+            .delete(format!("{}/api/links/{}", BASE_URL, id))
+            .send()
+            .await;
+    }
+
+    assert_eq!(status, StatusCode::OK);
+    let mut found: Vec<&str> = body["links"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|l| l["short_code"].as_str().unwrap())
+        .collect();
+    found.sort();
+    let mut expected = vec![first.as_str(), second.as_str()];
+    expected.sort();
+    assert_eq!(found, expected);
+    assert_eq!(body["not_found"], json!([missing]));
+    assert_eq!(too_many_status, StatusCode::BAD_REQUEST);
+}
+
+// The org budget itself is unit-tested in middleware::rate_limit; changing
+// org_links_per_hour here would throttle every concurrently running test.
+#[tokio::test]
+async fn test_org_link_creation_limit_validation() {
+    let client = authenticated_client();
+
+    for value in ["-1", "100001", "lots"] {
+        let response = client
+            .put(format!("{}/api/admin/settings", BASE_URL))
+            .json(&json!({ "key": "org_links_per_hour", "value": value }))
+            .send()
+            .await
+            .unwrap();
+        if response.status() == StatusCode::FORBIDDEN {
+            println!("Test user is not an admin - skipping test");
+            return;
+        }
+        assert_eq!(
+            response.status(),
+            StatusCode::BAD_REQUEST,
+            "org_links_per_hour={} should be rejected",
+            value
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_get_link_qr_code() {
+    let client = authenticated_client();
+    let link: serde_json::Value = create_test_link("https://example.com/qr-menu", None)
+        .await
+        .json()
+        .await
+        .unwrap();
+    let link_id = link["id"].as_str().unwrap();
+
+    let response = client
+        .get(format!("{}/api/links/{}/qr?size=300", BASE_URL, link_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "image/png");
+    let png = response.bytes().await.unwrap();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    // IHDR width and height
+    assert_eq!(&png[16..20], &300u32.to_be_bytes());
+    assert_eq!(&png[20..24], &300u32.to_be_bytes());
+
+    // Out-of-range sizes are clamped
+    let png = client
+        .get(format!("{}/api/links/{}/qr?size=5000", BASE_URL, link_id))
+        .send()
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap();
+    assert_eq!(&png[16..20], &1024u32.to_be_bytes());
+
+    let response = client
+        .get(format!("{}/api/links/{}/qr?format=svg", BASE_URL, link_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "image/svg+xml");
+    let svg = response.text().await.unwrap();
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.contains("width=\"256\""));
+
+    for query in ["format=gif", "size=big"] {
+        let response = client
+            .get(format!("{}/api/links/{}/qr?{}", BASE_URL, link_id, query))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", query);
+    }
+
+    let response = client
+        .get(format!("{}/api/links/does-not-exist/qr", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = test_client()
+        .get(format!("{}/api/links/{}/qr", BASE_URL, link_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_bulk_create_links_reports_each_item() {
+    let client = authenticated_client();
+    let taken_code = create_link_and_get_code("https://example.com/bulk-taken").await;
+
+    let response = client
+        .post(format!("{}/api/links/bulk", BASE_URL))
+        .json(&json!({
+            "links": [
+                { "destination_url": "https://example.com/bulk-1", "title": "Bulk 1" },
+                { "destination_url": "not-a-url" },
+                { "destination_url": "https://example.com/bulk-3", "short_code": taken_code },
+                { "destination_url": "https://example.com/bulk-4", "tags": ["bulk"] }
+            ]
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["created"], 2);
+    assert_eq!(body["failed"], 2);
+    let results = body["results"].as_array().unwrap();
+    assert_eq!(results.len(), 4);
+    for (i, result) in results.iter().enumerate() {
+        assert_eq!(result["index"], i);
+    }
+    assert_eq!(results[0]["status"], "created");
+    assert!(results[0]["short_code"].is_string());
+    assert_eq!(results[1]["status"], "failed");
+    assert!(results[1]["error"].is_string());
+    // Whether the tier allows custom codes or not, the taken code is refused
+    assert_eq!(results[2]["status"], "failed");
+    assert_eq!(results[3]["status"], "created");
+
+    let created_code = results[0]["short_code"].as_str().unwrap();
+    let res = test_client()
+        .get(format!("{}/{}", BASE_URL, created_code))
+        .send()
+        .await
+        .unwrap();
+    assert!(res.status().is_redirection());
+    assert_eq!(
+        res.headers().get("location").and_then(|v| v.to_str().ok()),
+        Some("https://example.com/bulk-1")
+    );
+}
+
+#[tokio::test]
+async fn test_bulk_create_links_rejects_oversized_batch() {
+    let client = authenticated_client();
+    let links: Vec<_> = (0..101)
+        .map(|i| json!({ "destination_url": format!("https://example.com/bulk-cap-{}", i) }))
+        .collect();
+
+    let response = client
+        .post(format!("{}/api/links/bulk", BASE_URL))
+        .json(&json!({ "links": links }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = client
+        .post(format!("{}/api/links/bulk", BASE_URL))
+        .json(&json!({ "links": [] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_bulk_create_links_atomic_creates_nothing_on_bad_item() {
+    let client = authenticated_client();
+    let marker = unique_short_code("bulk-atomic");
+
+    let response = client
+        .post(format!("{}/api/links/bulk?mode=atomic", BASE_URL))
+        .json(&json!({
+            "links": [
+                { "destination_url": "https://example.com/bulk-atomic-1", "title": marker },
+                { "destination_url": "not-a-url", "title": marker },
+                { "destination_url": "https://example.com/bulk-atomic-3", "title": marker }
+            ]
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["message"].as_str().unwrap().starts_with("links[1]"));
+
+    let response = client
+        .get(format!("{}/api/links?search={}", BASE_URL, marker))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(
+        body["data"].as_array().unwrap().is_empty(),
+        "no link should be created when one item is invalid"
+    );
+
+    // The same batch without the bad item is created in full
+    let response = client
+        .post(format!("{}/api/links/bulk?mode=atomic", BASE_URL))
+        .json(&json!({
+            "links": [
+                { "destination_url": "https://example.com/bulk-atomic-1", "title": marker },
+                { "destination_url": "https://example.com/bulk-atomic-3", "title": marker }
+            ]
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["created"], 2);
+    assert_eq!(body["failed"], 0);
+}
+
+#[tokio::test]
+async fn test_bulk_delete_links() {
+    let client = authenticated_client();
+    let mut ids = Vec::new();
+    let mut codes = Vec::new();
+    for i in 0..3 {
+        let link: serde_json::Value =
+            create_test_link(&format!("https://example.com/bulk-delete-{}", i), None)
+                .await
+                .json()
+                .await
+                .unwrap();
+        ids.push(link["id"].as_str().unwrap().to_string());
+        codes.push(link["short_code"].as_str().unwrap().to_string());
+    }
+
+    // Enough unknown ids that the ownership lookup spans several chunks
+    let missing: Vec<String> = (0..150).map(|i| format!("no-such-link-{}", i)).collect();
+    let mut request_ids = vec![ids[0].clone()];
+    request_ids.extend(missing.iter().cloned());
+    request_ids.extend([ids[1].clone(), ids[0].clone(), ids[2].clone()]);
+
+    let response = client
+        .post(format!("{}/api/links/bulk-delete", BASE_URL))
+        .json(&json!({ "ids": request_ids }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["deleted"], 3);
+    assert_eq!(body["not_found"], json!(missing));
+    assert_eq!(body["errors"], json!([]));
+
+    for (id, code) in ids.iter().zip(&codes) {
+        let response = client
+            .get(format!("{}/api/links/{}", BASE_URL, id))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // The KV mapping is gone too
+        let response = test_client()
+            .get(format!("{}/{}", BASE_URL, code))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FOUND);
+    }
+
+    let too_many: Vec<String> = (0..201).map(|i| format!("link-{}", i)).collect();
+    let response = client
+        .post(format!("{}/api/links/bulk-delete", BASE_URL))
+        .json(&json!({ "ids": too_many }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = client
+        .post(format!("{}/api/links/bulk-delete", BASE_URL))
+        .json(&json!({ "ids": [] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_create_link_dedupe_returns_existing_link() {
+    let client = authenticated_client();
+    let path = unique_short_code("dedupe");
+    let original: serde_json::Value =
+        create_test_link(&format!("https://example.com/{}/", path), None)
+            .await
+            .json()
+            .await
+            .unwrap();
+
+    // Scheme/host case, www. and the trailing slash do not matter
+    let response = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": format!("HTTPS://www.Example.com/{}", path),
+            "dedupe": true
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("x-rushomon-deduped").unwrap(),
+        "true"
+    );
+    let deduped: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(deduped["id"], original["id"]);
+    assert_eq!(deduped["short_code"], original["short_code"]);
+
+    // Without the flag a new link is created as before
+    let response = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({ "destination_url": format!("https://example.com/{}", path) }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("x-rushomon-deduped").is_none());
+    let created: serde_json::Value = response.json().await.unwrap();
+    assert_ne!(created["id"], original["id"]);
+}
+
+/// Links created before `normalized_destination` existed have the column
+/// NULL and must still be found by dedupe.
+///
+/// The pre-migration row is simulated with `wrangler d1 execute` against the
+/// local dev database.
+#[tokio::test]
+async fn test_create_link_dedupe_matches_pre_migration_link() {
+    let client = authenticated_client();
+    let path = unique_short_code("dedupelegacy");
+    let original: serde_json::Value =
+        create_test_link(&format!("https://example.com/{}/", path), None)
+            .await
+            .json()
+            .await
+            .unwrap();
+
+    let sql = format!(
+        "UPDATE links SET normalized_destination = NULL WHERE id = '{}'",
+        original["id"].as_str().unwrap()
+    );
+    let cleared = std::process::Command::new("wrangler")
+        .args(["d1", "execute", "rushomon", "--local", "--command", &sql])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false);
+    if !cleared {
+        println!("SKIP: wrangler d1 execute unavailable — cannot simulate pre-migration link");
+        return;
+    }
+
+    let response = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": format!("https://www.example.com/{}", path),
+            "dedupe": true
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("x-rushomon-deduped").unwrap(),
+        "true"
+    );
+    let deduped: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(deduped["id"], original["id"]);
+}
+
+#[tokio::test]
+async fn test_get_links_by_codes_accepts_max_codes() {
+    let client = authenticated_client();
+    let code = unique_short_code("bcmax");
+
+    let response = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": "https://example.com/by-codes-max",
+            "short_code": code
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let link: serde_json::Value = response.json().await.unwrap();
+    let link_id = link["id"].as_str().unwrap().to_string();
+
+    // Exactly at the cap: the lookup binds org_id on top of every code
+    let mut codes = vec![code.clone()];
+    codes.extend((1..100).map(|i| format!("{}m{}", code, i)));
+    let response = client
+        .post(format!("{}/api/links/by-codes", BASE_URL))
+        .json(&json!({ "codes": codes }))
+        .send()
+        .await
+        .unwrap();
+    let status = response.status();
+    let body: serde_json::Value = response.json().await.unwrap();
+
+    let _ = client
+        .delete(format!("{}/api/links/{}", BASE_URL, link_id))
+        .send()
+        .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["links"].as_array().unwrap().len(), 1);
+    assert_eq!(body["links"][0]["short_code"], code.as_str());
+    assert_eq!(body["not_found"].as_array().unwrap().len(), 99);
+}
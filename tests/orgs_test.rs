@@ -38,6 +38,73 @@ async fn test_list_user_orgs_returns_current_org() {
     assert!(!orgs.is_empty(), "User should have at least one org");
 }
 
+#[tokio::test]
+async fn test_list_user_orgs_stats_only_when_requested() {
+    let client = authenticated_client();
+    let org_id = get_primary_test_org_id().await;
+    create_link_and_get_code("https://example.com/org-stats").await;
+
+    let response = client
+        .get(format!("{}/api/orgs", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    for org in body["orgs"].as_array().unwrap() {
+        assert!(org.get("stats").is_none(), "stats should be opt-in");
+    }
+
+    let response = client
+        .get(format!("{}/api/orgs?include=stats", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    let orgs = body["orgs"].as_array().unwrap();
+    for org in orgs {
+        assert!(org["stats"]["link_count"].is_i64());
+        assert!(org["stats"]["clicks_this_month"].is_i64());
+    }
+    let primary = orgs
+        .iter()
+        .find(|o| o["id"].as_str() == Some(org_id.as_str()))
+        .expect("primary org should be listed");
+    assert!(primary["stats"]["link_count"].as_i64().unwrap() >= 1);
+}
+
+#[tokio::test]
+async fn test_list_user_orgs_stats_for_new_org_are_zero() {
+    let client = authenticated_client();
+    let create_response = client
+        .post(format!("{}/api/orgs", BASE_URL))
+        .json(&json!({"name": format!("Stats Org {}", unique_short_code("st"))}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(create_response.status(), StatusCode::OK);
+    let new_org_id = create_response.json::<Value>().await.unwrap()["org"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let response = client
+        .get(format!("{}/api/orgs?include=stats", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    let body: Value = response.json().await.unwrap();
+    let new_org = body["orgs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|o| o["id"].as_str() == Some(new_org_id.as_str()))
+        .expect("new org should be listed");
+    assert_eq!(new_org["stats"]["link_count"].as_i64(), Some(0));
+    assert_eq!(new_org["stats"]["clicks_this_month"].as_i64(), Some(0));
+}
+
 // ─── Create Org ──────────────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -143,6 +210,66 @@ async fn test_get_org_returns_members_and_invitations() {
     assert!(!members.is_empty(), "Should have at least one member");
 }
 
+#[tokio::test]
+async fn test_get_org_include_age_marks_primary_org() {
+    let client = authenticated_client();
+    let primary_org_id = get_primary_test_org_id().await;
+
+    let primary: Value = client
+        .get(format!(
+            "{}/api/orgs/{}?include=age",
+            BASE_URL, primary_org_id
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let created: Value = client
+        .post(format!("{}/api/orgs", BASE_URL))
+        .json(&json!({"name": format!("Secondary Org {}", unique_short_code("p"))}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let secondary_org_id = created["org"]["id"].as_str().unwrap().to_string();
+    let secondary: Value = client
+        .get(format!(
+            "{}/api/orgs/{}?include=age",
+            BASE_URL, secondary_org_id
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let plain: Value = client
+        .get(format!("{}/api/orgs/{}", BASE_URL, secondary_org_id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let _ = client
+        .delete(format!("{}/api/orgs/{}", BASE_URL, secondary_org_id))
+        .json(&json!({"action": "delete"}))
+        .send()
+        .await;
+
+    assert_eq!(primary["org"]["is_primary"], true);
+    assert!(primary["org"]["age_days"].as_i64().unwrap() >= 0);
+    assert_eq!(secondary["org"]["is_primary"], false);
+    assert_eq!(secondary["org"]["age_days"], 0);
+    assert!(plain["org"].get("is_primary").is_none());
+}
+
 #[tokio::test]
 async fn test_get_org_not_member_returns_404() {
     let client = authenticated_client();
@@ -774,6 +901,57 @@ async fn test_resend_invitation_not_found() {
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
+#[tokio::test]
+async fn test_immediate_second_resend_is_rate_limited() {
+    let client = authenticated_client();
+    let org_id = get_primary_test_org_id().await;
+
+    let email = format!("resend-test-{}@example.com", unique_short_code("r"));
+    let response = client
+        .post(format!("{}/api/orgs/{}/invitations", BASE_URL, org_id))
+        .json(&json!({"email": email}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    let invitation_id = body["invitation"]["id"].as_str().unwrap().to_string();
+    let resend_url = format!(
+        "{}/api/orgs/{}/invitations/{}/resend",
+        BASE_URL, org_id, invitation_id
+    );
+
+    // The first resend is allowed; it may still fail with 500 when no mail
+    // provider is configured, but the attempt counts towards the interval
+    let first = client.post(&resend_url).send().await.unwrap();
+    let first_status = first.status();
+
+    let second = client.post(&resend_url).send().await.unwrap();
+    let second_status = second.status();
+    let retry_after = second
+        .headers()
+        .get("retry-after")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<i64>().ok());
+
+    client
+        .delete(format!(
+            "{}/api/orgs/{}/invitations/{}",
+            BASE_URL, org_id, invitation_id
+        ))
+        .send()
+        .await
+        .unwrap();
+
+    assert!(
+        first_status == StatusCode::OK || first_status == StatusCode::INTERNAL_SERVER_ERROR,
+        "First resend should not be rate limited, got {}",
+        first_status
+    );
+    assert_eq!(second_status, StatusCode::TOO_MANY_REQUESTS);
+    assert!(retry_after.is_some_and(|secs| secs > 0));
+}
+
 // ─── Billing Account Level Tier Limits ───────────────────────────────────────
 
 #[tokio::test]
@@ -1216,3 +1394,443 @@ async fn test_exclude_ambiguous_chars_affects_generated_codes() {
         assert_eq!(response.status(), StatusCode::OK);
     }
 }
+
+#[tokio::test]
+async fn test_default_link_sort_applies_when_sort_omitted() {
+    let client = authenticated_client();
+    let org_id = get_primary_test_org_id().await;
+
+    let response = client
+        .patch(format!("{}/api/orgs/{}/settings", BASE_URL, org_id))
+        .json(&json!({"default_link_sort": "newest"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // The links list uses the session's current org; set the default on every
+    // owned org so the assertion holds regardless of which one is active.
+    let orgs_body: Value = client
+        .get(format!("{}/api/orgs", BASE_URL))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let owned_org_ids: Vec<String> = orgs_body["orgs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|o| o["role"].as_str() == Some("owner"))
+        .filter_map(|o| o["id"].as_str().map(|s| s.to_string()))
+        .collect();
+
+    for org_id in &owned_org_ids {
+        let response = client
+            .patch(format!("{}/api/orgs/{}/settings", BASE_URL, org_id))
+            .json(&json!({"default_link_sort": "code"}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["default_link_sort"], "code");
+    }
+
+    // Code order and title order are opposite for these two links
+    let marker = unique_short_code("sortdef");
+    let late_code = format!("zz{}", marker);
+    let early_code = format!("aa{}", marker);
+    for (code, title) in [(&late_code, "A first"), (&early_code, "B second")] {
+        let response = client
+            .post(format!("{}/api/links", BASE_URL))
+            .json(&json!({
+                "destination_url": "https://example.com/default-sort",
+                "short_code": code,
+                "title": format!("{} {}", title, marker)
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let list_codes = |sort: Option<&str>| {
+        let client = client.clone();
+        let url = match sort {
+            Some(sort) => format!("{}/api/links?search={}&sort={}", BASE_URL, marker, sort),
+            None => format!("{}/api/links?search={}", BASE_URL, marker),
+        };
+        async move {
+            let body: Value = client.get(url).send().await.unwrap().json().await.unwrap();
+            body["data"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|l| l["short_code"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        }
+    };
+
+    let default_order = list_codes(None).await;
+    let title_order = list_codes(Some("title")).await;
+
+    // Restore
+    for org_id in &owned_org_ids {
+        let response = client
+            .patch(format!("{}/api/orgs/{}/settings", BASE_URL, org_id))
+            .json(&json!({"default_link_sort": "created"}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    assert_eq!(default_order, vec![early_code.clone(), late_code.clone()]);
+    assert_eq!(title_order, vec![late_code, early_code]);
+}
+
+#[tokio::test]
+async fn test_default_redirect_type_applies_when_omitted() {
+    let client = authenticated_client();
+    let org_id = get_primary_test_org_id().await;
+
+    let response = client
+        .patch(format!("{}/api/orgs/{}/settings", BASE_URL, org_id))
+        .json(&json!({"default_redirect_type": "404"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Use a dedicated org, so other tests creating links in the shared ones
+    // never see the changed default. Links are created through an API key
+    // scoped to it, since switching the session's org would also be shared.
+    let response = client
+        .post(format!("{}/api/orgs", BASE_URL))
+        .json(&json!({"name": format!("Redirect Default Org {}", unique_short_code("rd"))}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let org_id = response.json::<Value>().await.unwrap()["org"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let response = client
+        .patch(format!("{}/api/orgs/{}/settings", BASE_URL, org_id))
+        .json(&json!({"default_redirect_type": "302"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["default_redirect_type"], "302");
+
+    let response = client
+        .post(format!("{}/api/settings/api-keys", BASE_URL))
+        .json(&json!({
+            "name": "Redirect Default Key",
+            "expires_in_days": 1,
+            "org_ids": [org_id],
+            "scopes": ["links:write"]
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let key: Value = response.json().await.unwrap();
+    let key_id = key["id"].as_str().unwrap().to_string();
+    let raw_token = key["raw_token"].as_str().unwrap().to_string();
+
+    let create = |body: Value| {
+        let raw_token = raw_token.clone();
+        async move {
+            let response = test_client()
+                .post(format!("{}/api/links", BASE_URL))
+                .header("Authorization", format!("Bearer {}", raw_token))
+                .json(&body)
+                .send()
+                .await
+                .unwrap();
+            let status = response.status();
+            let link: Value = response.json().await.unwrap();
+            (status, link)
+        }
+    };
+    let (inherited_status, inherited) =
+        create(json!({"destination_url": "https://example.com/default-redirect"})).await;
+    let (explicit_status, explicit) = create(json!({
+        "destination_url": "https://example.com/default-redirect-explicit",
+        "redirect_type": "301"
+    }))
+    .await;
+
+    let redirect_status = test_client()
+        .get(format!(
+            "{}/{}",
+            BASE_URL,
+            inherited["short_code"].as_str().unwrap_or_default()
+        ))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    client
+        .delete(format!("{}/api/settings/api-keys/{}", BASE_URL, key_id))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(inherited_status, StatusCode::OK);
+    assert_eq!(inherited["redirect_type"], "302");
+    assert_eq!(redirect_status, StatusCode::FOUND);
+    assert_eq!(explicit_status, StatusCode::OK);
+    assert_eq!(explicit["redirect_type"], "301");
+}
+
+// ─── Org name uniqueness per billing account ─────────────────────────────────
+
+// Enforcement is an instance-wide setting, so the conflict rules are unit
+// tested (Organization::is_name_taken) rather than toggled here. This covers
+// the default: the check is off and duplicate names are allowed.
+#[tokio::test]
+async fn test_duplicate_org_names_allowed_by_default() {
+    let client = authenticated_client();
+    let name = format!("Duplicate Name Org {}", unique_short_code("dn"));
+
+    let settings: Value = client
+        .get(format!("{}/api/admin/settings", BASE_URL))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    if settings["unique_org_names_per_billing_account"] == "true" {
+        println!("Org name uniqueness is enforced on this instance - skipping test");
+        return;
+    }
+
+    for candidate in [name.clone(), name.to_uppercase()] {
+        let response = client
+            .post(format!("{}/api/orgs", BASE_URL))
+            .json(&json!({ "name": candidate }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
+
+#[tokio::test]
+async fn test_delete_org_with_links_requires_confirm_link_count() {
+    let client = authenticated_client();
+    let original_org_id = get_primary_test_org_id().await;
+
+    let create_response = client
+        .post(format!("{}/api/orgs", BASE_URL))
+        .json(&json!({"name": format!("Delete Confirm Org {}", unique_short_code("dc"))}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(create_response.status(), StatusCode::OK);
+    let org_id = create_response.json::<Value>().await.unwrap()["org"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    // Switch to the new org and use its access token to create links in it
+    let switch_response = client
+        .post(format!("{}/api/auth/switch-org", BASE_URL))
+        .json(&json!({"org_id": org_id}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(switch_response.status(), StatusCode::OK);
+    let access_cookie = switch_response
+        .headers()
+        .get("set-cookie")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .expect("switch-org should set the access cookie")
+        .to_string();
+    let org_client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .default_headers(reqwest::header::HeaderMap::from_iter([(
+            reqwest::header::COOKIE,
+            access_cookie.parse().unwrap(),
+        )]))
+        .build()
+        .unwrap();
+    for i in 0..2 {
+        let response = org_client
+            .post(format!("{}/api/links", BASE_URL))
+            .json(&json!({"destination_url": format!("https://example.com/delete-confirm-{}", i)}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let link: Value = response.json().await.unwrap();
+        assert_eq!(link["org_id"].as_str(), Some(org_id.as_str()));
+    }
+
+    let delete_with = |body: Value| {
+        let client = client.clone();
+        let url = format!("{}/api/orgs/{}", BASE_URL, org_id);
+        async move { client.delete(url).json(&body).send().await.unwrap() }
+    };
+
+    // Absent and stale confirmations are rejected with the real count
+    for body in [
+        json!({"action": "delete"}),
+        json!({"action": "delete", "confirm_link_count": 1}),
+    ] {
+        let response = delete_with(body).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["link_count"], 2);
+    }
+
+    let org_response = client
+        .get(format!("{}/api/orgs/{}", BASE_URL, org_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        org_response.status(),
+        StatusCode::OK,
+        "Org should survive a rejected delete"
+    );
+
+    let response = delete_with(json!({"action": "delete", "confirm_link_count": 2})).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Leave the session pointing at the primary org for later tests
+    let _ = client
+        .post(format!("{}/api/auth/switch-org", BASE_URL))
+        .json(&json!({"org_id": original_org_id}))
+        .send()
+        .await;
+}
+
+#[tokio::test]
+async fn test_update_invitation_branding() {
+    let client = authenticated_client();
+    let org_id = get_primary_test_org_id().await;
+    let settings_url = format!("{}/api/orgs/{}/settings", BASE_URL, org_id);
+
+    let response = client
+        .patch(&settings_url)
+        .json(&json!({
+            "invite_subject": "  Come build with us  ",
+            "invite_message": "Welcome aboard!\nSee you Monday."
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["invite_subject"], "Come build with us");
+    assert_eq!(body["invite_message"], "Welcome aboard!\nSee you Monday.");
+
+    // Subjects end up in an email header: line breaks are rejected
+    let response = client
+        .patch(&settings_url)
+        .json(&json!({ "invite_subject": "Hi\r\nBcc: someone@example.com" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = client
+        .patch(&settings_url)
+        .json(&json!({ "invite_message": "x".repeat(1001) }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // null restores the defaults
+    let response = client
+        .patch(&settings_url)
+        .json(&json!({ "invite_subject": null, "invite_message": "" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert!(body["invite_subject"].is_null());
+    assert!(body["invite_message"].is_null());
+}
+
+// ─── Org Limits ──────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_get_org_limits_matches_tier_limits() {
+    let client = authenticated_client();
+    let org_id = get_primary_test_org_id().await;
+
+    let orgs: Value = client
+        .get(format!("{}/api/orgs", BASE_URL))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let tier = orgs["orgs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|o| o["id"] == org_id.as_str())
+        .and_then(|o| o["tier"].as_str())
+        .unwrap()
+        .to_string();
+
+    let response = client
+        .get(format!("{}/api/orgs/{}/limits", BASE_URL, org_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+
+    assert_eq!(body["tier"], tier.as_str());
+
+    // The primary test org is the user's current org, so every limit
+    // /api/usage reports must match
+    let usage: Value = client
+        .get(format!("{}/api/usage", BASE_URL))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    for (key, value) in usage["limits"].as_object().unwrap() {
+        assert_eq!(&body["limits"][key], value, "limit {} differs", key);
+    }
+    assert!(body["limits"]["max_members"].is_number() || body["limits"]["max_members"].is_null());
+}
+
+#[tokio::test]
+async fn test_get_org_limits_requires_membership() {
+    let client = authenticated_client();
+    let response = client
+        .get(format!("{}/api/orgs/nonexistent-org/limits", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = test_client()
+        .get(format!("{}/api/orgs/nonexistent-org/limits", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
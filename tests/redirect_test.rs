@@ -3,6 +3,7 @@ use serde_json::json;
 
 mod common;
 use common::*;
+use rushomon::utils::utm_token::sign_utm_token;
 
 #[tokio::test]
 async fn test_redirect_with_301() {
@@ -239,6 +240,68 @@ async fn test_root_redirects_to_frontend() {
     );
 }
 
+#[tokio::test]
+async fn test_redirect_domain_root_does_not_redirect_to_itself() {
+    let response = test_client()
+        .get(format!("{}/", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+    let location = response
+        .headers()
+        .get("location")
+        .unwrap()
+        .to_str()
+        .unwrap();
+    assert!(
+        location.starts_with("http") && !location.starts_with(BASE_URL),
+        "Expected the redirect domain root to point at another domain, got: {}",
+        location
+    );
+}
+
+#[tokio::test]
+async fn test_spa_fallback_excluded_path_returns_real_404() {
+    let client = test_client();
+
+    let response = client
+        .get(format!("{}/.well-known/rushomon-missing-file", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body = response.text().await.unwrap().to_lowercase();
+    assert!(
+        !body.contains("<html"),
+        "Excluded path must not be answered with the SPA shell"
+    );
+}
+
+#[tokio::test]
+async fn test_robots_txt_is_served_not_redirected() {
+    let client = test_client();
+
+    let response = client
+        .get(format!("{}/robots.txt", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    assert!(content_type.starts_with("text/plain"));
+    let body = response.text().await.unwrap();
+    assert!(body.contains("User-agent:"), "Unexpected body: {}", body);
+}
+
 #[tokio::test]
 async fn test_admin_update_link_status_updates_kv() {
     let auth_client = authenticated_client();
@@ -358,6 +421,75 @@ async fn test_admin_reactivate_blocked_link_restores_redirect() {
     );
 }
 
+// The status chosen for each setting value is unit-tested in
+// models::blocked_link; this reads the instance setting instead of
+// changing it, so concurrent blacklist tests are unaffected.
+#[tokio::test]
+async fn test_blocked_link_serves_configured_response() {
+    let auth_client = authenticated_client();
+    let public_client = test_client();
+
+    let settings_response = auth_client
+        .get(format!("{}/api/admin/settings", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    if settings_response.status() == StatusCode::FORBIDDEN {
+        println!("Test user is not an admin - skipping test");
+        return;
+    }
+    let settings: serde_json::Value = settings_response.json().await.unwrap();
+    let expected_status = match settings["blocked_link_response"].as_str() {
+        Some("gone") => StatusCode::GONE,
+        Some("unavailable") => StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS,
+        _ => StatusCode::FOUND,
+    };
+
+    let create_response = create_test_link("https://example.com/policy-blocked", None).await;
+    let link: serde_json::Value = create_response.json().await.unwrap();
+    let link_id = link["id"].as_str().unwrap();
+    let short_code = link["short_code"].as_str().unwrap();
+
+    let block_response = auth_client
+        .put(format!("{}/api/admin/links/{}", BASE_URL, link_id))
+        .json(&json!({"status": "blocked"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(block_response.status(), StatusCode::OK);
+
+    let blocked_response = public_client
+        .get(format!("{}/{}", BASE_URL, short_code))
+        .send()
+        .await
+        .unwrap();
+    let missing_response = public_client
+        .get(format!("{}/{}", BASE_URL, unique_short_code("nolink")))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(blocked_response.status(), expected_status);
+    if expected_status == StatusCode::FOUND {
+        let location = blocked_response.headers().get("location").unwrap();
+        assert!(location.to_str().unwrap().ends_with("/404"));
+    } else {
+        assert!(blocked_response.headers().get("location").is_none());
+        let body = blocked_response.text().await.unwrap();
+        assert!(body.contains("removed for policy reasons"));
+    }
+    // Missing codes always go to /404
+    assert_eq!(missing_response.status(), StatusCode::FOUND);
+
+    let invalid = auth_client
+        .put(format!("{}/api/admin/settings", BASE_URL))
+        .json(&json!({ "key": "blocked_link_response", "value": "418" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(invalid.status(), StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn test_blacklist_block_retroactively_disables_normalized_exact_match_link() {
     let auth_client = authenticated_client();
@@ -595,6 +727,104 @@ async fn test_redirect_appends_utm_params() {
         .await;
 }
 
+#[tokio::test]
+async fn test_redirect_applies_signed_utm_override() {
+    let auth_client = authenticated_client();
+    let public_client = test_client();
+    let org_id = get_primary_test_org_id().await;
+
+    let secret_response = auth_client
+        .post(format!(
+            "{}/api/orgs/{}/utm-signing-secret",
+            BASE_URL, org_id
+        ))
+        .send()
+        .await
+        .unwrap();
+    if secret_response.status() == StatusCode::FORBIDDEN {
+        println!("Org not on Pro tier, skipping signed UTM test");
+        return;
+    }
+    assert_eq!(secret_response.status(), StatusCode::OK);
+    let body: serde_json::Value = secret_response.json().await.unwrap();
+    let secret = body["utm_signing_secret"].as_str().unwrap().to_string();
+
+    let create_response = auth_client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": "https://example.com/signed",
+            "utm_params": {
+                "utm_source": "newsletter",
+                "utm_medium": "email"
+            }
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(create_response.status(), StatusCode::OK);
+    let link: serde_json::Value = create_response.json().await.unwrap();
+    let link_id = link["id"].as_str().unwrap();
+    let short_code = link["short_code"].as_str().unwrap();
+
+    let location_for = |token: String| {
+        let public_client = public_client.clone();
+        let url = format!("{}/{}?u={}", BASE_URL, short_code, token);
+        async move {
+            let response = public_client.get(url).send().await.unwrap();
+            assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+            response
+                .headers()
+                .get("location")
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_string()
+        }
+    };
+
+    // Valid token: overrides utm_source, adds utm_campaign, keeps utm_medium
+    let token = sign_utm_token(
+        &secret,
+        short_code,
+        &[("utm_source", "partner"), ("utm_campaign", "fall")],
+    );
+    let location = location_for(token.clone()).await;
+    assert!(
+        location.contains("utm_source=partner") && !location.contains("utm_source=newsletter"),
+        "Expected overridden utm_source, got: {}",
+        location
+    );
+    assert!(
+        location.contains("utm_campaign=fall"),
+        "Expected utm_campaign from token, got: {}",
+        location
+    );
+    assert!(
+        location.contains("utm_medium=email"),
+        "Expected stored utm_medium to be kept, got: {}",
+        location
+    );
+
+    // Tampered payload and wrong secret: token ignored, stored UTMs apply
+    let (_, signature) = token.split_once('.').unwrap();
+    let forged = sign_utm_token(&secret, short_code, &[("utm_source", "forged")]);
+    let (forged_payload, _) = forged.split_once('.').unwrap();
+    let wrong_secret = sign_utm_token("not-the-secret", short_code, &[("utm_source", "x")]);
+    for bad_token in [format!("{}.{}", forged_payload, signature), wrong_secret] {
+        let location = location_for(bad_token).await;
+        assert!(
+            location.contains("utm_source=newsletter") && !location.contains("utm_campaign"),
+            "Expected stored UTMs only for an invalid token, got: {}",
+            location
+        );
+    }
+
+    let _ = auth_client
+        .delete(format!("{}/api/links/{}", BASE_URL, link_id))
+        .send()
+        .await;
+}
+
 #[tokio::test]
 async fn test_redirect_forwards_visitor_query_params() {
     let auth_client = authenticated_client();
@@ -784,3 +1014,880 @@ async fn test_redirect_no_forwarding_strips_visitor_params() {
         .send()
         .await;
 }
+
+// ─── GET /api/links/exists/:code ──────────────────────────────────────────────
+
+async fn probe_link(short_code: &str) -> serde_json::Value {
+    let response = test_client()
+        .get(format!("{}/api/links/exists/{}", BASE_URL, short_code))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn test_link_exists_probe_active_link() {
+    let auth_client = authenticated_client();
+    let create_response = create_test_link("https://example.com/probe", None).await;
+    let link: serde_json::Value = create_response.json().await.unwrap();
+    let link_id = link["id"].as_str().unwrap();
+    let short_code = link["short_code"].as_str().unwrap();
+
+    let body = probe_link(short_code).await;
+    assert_eq!(body["exists"], true);
+    assert_eq!(body["active"], true);
+
+    // Probing does not record a click
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    let link: serde_json::Value = auth_client
+        .get(format!("{}/api/links/{}", BASE_URL, link_id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(link["click_count"].as_i64(), Some(0));
+}
+
+#[tokio::test]
+async fn test_link_exists_probe_expired_link() {
+    let response = authenticated_client()
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": "https://example.com/probe-expired",
+            "expires_at": 1
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let link: serde_json::Value = response.json().await.unwrap();
+    let short_code = link["short_code"].as_str().unwrap();
+
+    let body = probe_link(short_code).await;
+    assert_eq!(body["exists"], true);
+    assert_eq!(body["active"], false);
+}
+
+#[tokio::test]
+async fn test_link_exists_probe_disabled_link() {
+    let auth_client = authenticated_client();
+    let create_response = create_test_link("https://example.com/probe-disabled", None).await;
+    let link: serde_json::Value = create_response.json().await.unwrap();
+    let link_id = link["id"].as_str().unwrap();
+    let short_code = link["short_code"].as_str().unwrap();
+
+    let disable_response = auth_client
+        .put(format!("{}/api/links/{}", BASE_URL, link_id))
+        .json(&serde_json::json!({"status": "disabled"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(disable_response.status(), StatusCode::OK);
+
+    // Disabled links are removed from the edge mapping, so they no longer resolve
+    let body = probe_link(short_code).await;
+    assert_eq!(body["exists"], false);
+    assert_eq!(body["active"], false);
+}
+
+#[tokio::test]
+async fn test_link_exists_probe_missing_code() {
+    let body = probe_link(&unique_short_code("nope")).await;
+    assert_eq!(body["exists"], false);
+    assert_eq!(body["active"], false);
+}
+
+// ─── GET /api/public/links/:code ──────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_public_link_metadata_for_active_link() {
+    let create_response = create_test_link(
+        "https://example.com/preview?token=secret",
+        Some("Preview Card Title"),
+    )
+    .await;
+    let link: serde_json::Value = create_response.json().await.unwrap();
+    let short_code = link["short_code"].as_str().unwrap();
+
+    let response = test_client()
+        .get(format!("{}/api/public/links/{}", BASE_URL, short_code))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let cache_control = response
+        .headers()
+        .get("cache-control")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert!(cache_control.starts_with("public"));
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["short_code"], short_code);
+    assert_eq!(body["title"], "Preview Card Title");
+    assert_eq!(body["destination_host"], "example.com");
+    assert_eq!(body["created_at"], link["created_at"]);
+    // Nothing beyond the preview fields, in particular not the full destination
+    assert_eq!(body.as_object().unwrap().len(), 4);
+    assert!(!body.to_string().contains("secret"));
+}
+
+#[tokio::test]
+async fn test_public_link_metadata_404_for_disabled_link() {
+    let create_response = create_test_link("https://example.com/preview-disabled", None).await;
+    let link: serde_json::Value = create_response.json().await.unwrap();
+    let link_id = link["id"].as_str().unwrap();
+    let short_code = link["short_code"].as_str().unwrap();
+
+    let disable_response = authenticated_client()
+        .put(format!("{}/api/links/{}", BASE_URL, link_id))
+        .json(&json!({"status": "disabled"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(disable_response.status(), StatusCode::OK);
+
+    let response = test_client()
+        .get(format!("{}/api/public/links/{}", BASE_URL, short_code))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let missing = test_client()
+        .get(format!(
+            "{}/api/public/links/{}",
+            BASE_URL,
+            unique_short_code("nope")
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_alias_code_redirects_and_shares_analytics() {
+    let auth_client = authenticated_client();
+    let public_client = test_client();
+
+    let create_response = create_test_link("https://example.com/aliased", None).await;
+    assert_eq!(create_response.status(), StatusCode::OK);
+    let link: serde_json::Value = create_response.json().await.unwrap();
+    let link_id = link["id"].as_str().unwrap();
+    let short_code = link["short_code"].as_str().unwrap();
+
+    let alias_code = unique_short_code("alias");
+    let response = auth_client
+        .post(format!("{}/api/links/{}/aliases", BASE_URL, link_id))
+        .json(&json!({ "alias_code": alias_code }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let alias: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(alias["alias_code"], alias_code.as_str());
+    assert_eq!(alias["link_id"], link_id);
+
+    // The alias code is taken now, and reserved words are never allowed
+    let duplicate = auth_client
+        .post(format!("{}/api/links/{}/aliases", BASE_URL, link_id))
+        .json(&json!({ "alias_code": alias_code }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(duplicate.status(), StatusCode::CONFLICT);
+    let reserved = auth_client
+        .post(format!("{}/api/links/{}/aliases", BASE_URL, link_id))
+        .json(&json!({ "alias_code": "dashboard" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(reserved.status(), StatusCode::BAD_REQUEST);
+
+    for code in [short_code, alias_code.as_str()] {
+        let response = public_client
+            .get(format!("{}/{}", BASE_URL, code))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+        assert_eq!(
+            response.headers().get("location").unwrap(),
+            "https://example.com/aliased"
+        );
+    }
+
+    // Wait for deferred analytics to complete
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let link_response = auth_client
+        .get(format!("{}/api/links/{}", BASE_URL, link_id))
+        .send()
+        .await
+        .unwrap();
+    let updated: serde_json::Value = link_response.json().await.unwrap();
+    assert_eq!(updated["click_count"], 2);
+
+    let analytics: serde_json::Value = auth_client
+        .get(format!(
+            "{}/api/links/{}/analytics?days=1",
+            BASE_URL, link_id
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(analytics["total_clicks_in_range"], 2);
+
+    // Removing the alias frees it; the primary code keeps working
+    let response = auth_client
+        .delete(format!(
+            "{}/api/links/{}/aliases/{}",
+            BASE_URL, link_id, alias_code
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = public_client
+        .get(format!("{}/{}", BASE_URL, alias_code))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FOUND);
+    let response = public_client
+        .get(format!("{}/{}", BASE_URL, short_code))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+
+    let _ = auth_client
+        .delete(format!("{}/api/links/{}", BASE_URL, link_id))
+        .send()
+        .await;
+}
+
+#[tokio::test]
+async fn test_redirect_fills_destination_template() {
+    let auth_client = authenticated_client();
+    let public_client = test_client();
+
+    let link: serde_json::Value = create_test_link(
+        "https://example.com/shop?country={{country}}&city={{city}}&day={{date}}",
+        None,
+    )
+    .await
+    .json()
+    .await
+    .unwrap();
+    let link_id = link["id"].as_str().unwrap().to_string();
+    let short_code = link["short_code"].as_str().unwrap().to_string();
+
+    let response = public_client
+        .get(format!("{}/{}", BASE_URL, short_code))
+        .header("CF-IPCity", "New York & Co/?#")
+        .send()
+        .await
+        .unwrap();
+    let status = response.status();
+    let location = response
+        .headers()
+        .get("location")
+        .map(|v| v.to_str().unwrap().to_string())
+        .unwrap_or_default();
+
+    let _ = auth_client
+        .delete(format!("{}/api/links/{}", BASE_URL, link_id))
+        .send()
+        .await;
+
+    assert_eq!(status, StatusCode::MOVED_PERMANENTLY);
+    assert!(
+        location.contains("city=New%20York%20%26%20Co%2F%3F%23"),
+        "City should be percent-encoded into the destination, got: {}",
+        location
+    );
+    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    assert!(
+        location.contains(&format!("day={}", today)),
+        "Date should be filled in, got: {}",
+        location
+    );
+    assert!(
+        !location.contains("{{") && !location.contains("%7B%7B"),
+        "No placeholder should survive, got: {}",
+        location
+    );
+}
+
+#[tokio::test]
+async fn test_unknown_destination_template_variable_rejected() {
+    let client = authenticated_client();
+
+    let response = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": "https://example.com/?ip={{ip_address}}",
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response.text().await.unwrap();
+    assert!(
+        body.contains("{{ip_address}}"),
+        "Error should name the unknown variable, got: {}",
+        body
+    );
+}
+
+#[tokio::test]
+async fn test_crawler_preview_serves_og_page_to_crawlers_only() {
+    let auth_client = authenticated_client();
+    let public_client = test_client();
+
+    let link: serde_json::Value = create_test_link("https://example.com/unfurl", Some("Unfurl me"))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let short_url = format!("{}/{}", BASE_URL, link["short_code"].as_str().unwrap());
+    let settings_url = format!(
+        "{}/api/orgs/{}/settings",
+        BASE_URL,
+        link["org_id"].as_str().unwrap()
+    );
+
+    let enable_response = auth_client
+        .patch(&settings_url)
+        .json(&json!({ "crawler_preview": true }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(enable_response.status(), StatusCode::OK);
+    let settings: serde_json::Value = enable_response.json().await.unwrap();
+    assert_eq!(settings["crawler_preview"], true);
+
+    let crawler_response = public_client
+        .get(&short_url)
+        .header(
+            "User-Agent",
+            "Slackbot-LinkExpanding 1.0 (+https://api.slack.com/robots)",
+        )
+        .send()
+        .await
+        .unwrap();
+    let crawler_status = crawler_response.status();
+    let content_type = crawler_response
+        .headers()
+        .get("content-type")
+        .map(|v| v.to_str().unwrap().to_string())
+        .unwrap_or_default();
+    let crawler_body = crawler_response.text().await.unwrap();
+
+    let browser_response = public_client
+        .get(&short_url)
+        .header(
+            "User-Agent",
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
+        )
+        .send()
+        .await
+        .unwrap();
+    let browser_status = browser_response.status();
+
+    // Restore the default before asserting so a failure doesn't leak the setting
+    let _ = auth_client
+        .patch(&settings_url)
+        .json(&json!({ "crawler_preview": false }))
+        .send()
+        .await;
+    let _ = auth_client
+        .delete(format!(
+            "{}/api/links/{}",
+            BASE_URL,
+            link["id"].as_str().unwrap()
+        ))
+        .send()
+        .await;
+
+    assert_eq!(crawler_status, StatusCode::OK);
+    assert!(
+        content_type.starts_with("text/html"),
+        "got {}",
+        content_type
+    );
+    assert!(
+        crawler_body.contains("<meta property=\"og:url\" content=\"https://example.com/unfurl\">")
+    );
+    assert!(crawler_body.contains("<meta property=\"og:title\" content=\"Unfurl me\">"));
+
+    assert_eq!(browser_status, StatusCode::MOVED_PERMANENTLY);
+}
+
+#[tokio::test]
+async fn test_untrusted_destination_requires_interstitial() {
+    let auth_client = authenticated_client();
+    let public_client = test_client();
+
+    let trusted: serde_json::Value = create_test_link("https://docs.example.com/trusted", None)
+        .await
+        .json()
+        .await
+        .unwrap();
+    let untrusted: serde_json::Value =
+        create_test_link("https://untrusted-destination.test/page", None)
+            .await
+            .json()
+            .await
+            .unwrap();
+    let settings_url = format!(
+        "{}/api/orgs/{}/settings",
+        BASE_URL,
+        trusted["org_id"].as_str().unwrap()
+    );
+
+    // example.com stays trusted so other tests' links keep redirecting directly
+    let enable_response = auth_client
+        .patch(&settings_url)
+        .json(&json!({
+            "untrusted_interstitial": true,
+            "trusted_domains": ["Example.com"]
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(enable_response.status(), StatusCode::OK);
+    let settings: serde_json::Value = enable_response.json().await.unwrap();
+    assert_eq!(settings["untrusted_interstitial"], true);
+    assert_eq!(settings["trusted_domains"], json!(["example.com"]));
+
+    let trusted_response = public_client
+        .get(format!(
+            "{}/{}",
+            BASE_URL,
+            trusted["short_code"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap();
+    let trusted_status = trusted_response.status();
+
+    let untrusted_response = public_client
+        .get(format!(
+            "{}/{}",
+            BASE_URL,
+            untrusted["short_code"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap();
+    let untrusted_status = untrusted_response.status();
+    let untrusted_body = untrusted_response.text().await.unwrap();
+
+    // Restore the default before asserting so a failure doesn't leak the setting
+    let _ = auth_client
+        .patch(&settings_url)
+        .json(&json!({ "untrusted_interstitial": false, "trusted_domains": [] }))
+        .send()
+        .await;
+    for link in [&trusted, &untrusted] {
+        let _ = auth_client
+            .delete(format!(
+                "{}/api/links/{}",
+                BASE_URL,
+                link["id"].as_str().unwrap()
+            ))
+            .send()
+            .await;
+    }
+
+    assert_eq!(trusted_status, StatusCode::MOVED_PERMANENTLY);
+    assert_eq!(untrusted_status, StatusCode::OK);
+    assert!(untrusted_body.contains("external site"));
+    assert!(untrusted_body.contains("url=https://untrusted-destination.test/page"));
+}
+
+#[tokio::test]
+async fn test_trusted_domains_validation() {
+    let client = authenticated_client();
+    let link: serde_json::Value = create_test_link("https://example.com/validate", None)
+        .await
+        .json()
+        .await
+        .unwrap();
+    let settings_url = format!(
+        "{}/api/orgs/{}/settings",
+        BASE_URL,
+        link["org_id"].as_str().unwrap()
+    );
+
+    for body in [
+        json!({ "trusted_domains": "example.com" }),
+        json!({ "trusted_domains": ["https://example.com/path"] }),
+        json!({ "untrusted_interstitial": "yes" }),
+    ] {
+        let response = client
+            .patch(&settings_url)
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", body);
+    }
+
+    let _ = client
+        .delete(format!(
+            "{}/api/links/{}",
+            BASE_URL,
+            link["id"].as_str().unwrap()
+        ))
+        .send()
+        .await;
+}
+
+#[tokio::test]
+async fn test_redirect_reflects_destination_edit_immediately() {
+    let auth_client = authenticated_client();
+    let public_client = test_client();
+
+    let create_response = create_test_link("https://example.com/before-edit", None).await;
+    let link: serde_json::Value = create_response.json().await.unwrap();
+    let link_id = link["id"].as_str().unwrap();
+    let short_code = link["short_code"].as_str().unwrap();
+
+    let response = public_client
+        .get(format!("{}/{}", BASE_URL, short_code))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.headers().get("location").unwrap(),
+        "https://example.com/before-edit"
+    );
+
+    let update_response = auth_client
+        .put(format!("{}/api/links/{}", BASE_URL, link_id))
+        .json(&json!({"destination_url": "https://example.com/after-edit"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(update_response.status(), StatusCode::OK);
+
+    let response = public_client
+        .get(format!("{}/{}", BASE_URL, short_code))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+    assert_eq!(
+        response.headers().get("location").unwrap(),
+        "https://example.com/after-edit"
+    );
+    let cache_control = response
+        .headers()
+        .get("cache-control")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    assert!(
+        cache_control.contains("no-store"),
+        "Recently edited link should not be cacheable, got: {:?}",
+        cache_control
+    );
+}
+
+#[tokio::test]
+async fn test_redirect_routes_by_device() {
+    let client = authenticated_client();
+    let response = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": "https://example.com/device-fallback",
+            "ios_url": "https://apps.apple.com/app/id000000",
+            "android_url": "https://play.google.com/store/apps/details?id=cc.example"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let link: serde_json::Value = response.json().await.unwrap();
+    let short_code = link["short_code"].as_str().unwrap();
+
+    let redirect_client = test_client();
+    let location = |user_agent: &'static str| {
+        let client = redirect_client.clone();
+        let url = format!("{}/{}", BASE_URL, short_code);
+        async move {
+            let response = client
+                .get(url)
+                .header("User-Agent", user_agent)
+                .send()
+                .await
+                .unwrap();
+            response.headers()["location"].to_str().unwrap().to_string()
+        }
+    };
+
+    assert_eq!(
+        location("Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X)").await,
+        "https://apps.apple.com/app/id000000"
+    );
+    assert_eq!(
+        location("Mozilla/5.0 (Linux; Android 14; Pixel 8)").await,
+        "https://play.google.com/store/apps/details?id=cc.example"
+    );
+    assert_eq!(
+        location("Mozilla/5.0 (Windows NT 10.0; Win64; x64)").await,
+        "https://example.com/device-fallback"
+    );
+}
+
+#[tokio::test]
+async fn test_redirect_stops_after_max_clicks() {
+    let client = authenticated_client();
+    let response = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": "https://example.com/giveaway",
+            "max_clicks": 2
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let link: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(link["max_clicks"], 2);
+    let short_code = link["short_code"].as_str().unwrap();
+
+    // The link's own redirect status, which follows the org default
+    let served =
+        StatusCode::from_u16(link["redirect_type"].as_str().unwrap().parse().unwrap()).unwrap();
+
+    // Each click comes from a different visitor, so click dedup cannot skip it
+    let redirect_client = test_client();
+    let mut statuses = Vec::new();
+    for visitor in 0..3 {
+        let response = redirect_client
+            .get(format!("{}/{}", BASE_URL, short_code))
+            .header("User-Agent", format!("max-clicks-visitor-{}", visitor))
+            .send()
+            .await
+            .unwrap();
+        let location = response
+            .headers()
+            .get("location")
+            .unwrap()
+            .to_str()
+            .unwrap();
+        if location == "https://example.com/giveaway" {
+            // A cached redirect would outlive the cap
+            assert_eq!(response.headers().get("cache-control").unwrap(), "no-store");
+        }
+        statuses.push((
+            response.status(),
+            location == "https://example.com/giveaway",
+        ));
+        // Let the deferred click increment land before the next click
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+    assert_eq!(
+        statuses,
+        vec![(served, true), (served, true), (StatusCode::FOUND, false)]
+    );
+
+    let response = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": "https://example.com/giveaway",
+            "max_clicks": 0
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_unavailable_link_redirects_to_fallback_url() {
+    let client = authenticated_client();
+    let response = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": "https://example.com/flash-sale",
+            "max_clicks": 1,
+            "fallback_url": "https://example.com/sale-over"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let link: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(link["fallback_url"], "https://example.com/sale-over");
+    let link_id = link["id"].as_str().unwrap();
+    let short_code = link["short_code"].as_str().unwrap();
+
+    let redirect_client = test_client();
+    let response = redirect_client
+        .get(format!("{}/{}", BASE_URL, short_code))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.headers().get("location").unwrap(),
+        "https://example.com/flash-sale"
+    );
+    // Let the deferred click increment land before the next click
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let response = redirect_client
+        .get(format!("{}/{}", BASE_URL, short_code))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FOUND);
+    assert_eq!(
+        response.headers().get("location").unwrap(),
+        "https://example.com/sale-over"
+    );
+
+    // A disabled link with a fallback also lands there instead of the 404 page
+    let response = client
+        .put(format!("{}/api/links/{}", BASE_URL, link_id))
+        .json(&json!({ "status": "disabled" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = redirect_client
+        .get(format!("{}/{}", BASE_URL, short_code))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FOUND);
+    assert_eq!(
+        response.headers().get("location").unwrap(),
+        "https://example.com/sale-over"
+    );
+
+    // Clearing it brings back the 404 page
+    let response = client
+        .put(format!("{}/api/links/{}", BASE_URL, link_id))
+        .json(&json!({ "clear_fallback_url": true }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let link: serde_json::Value = response.json().await.unwrap();
+    assert!(link.get("fallback_url").is_none());
+    let response = redirect_client
+        .get(format!("{}/{}", BASE_URL, short_code))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FOUND);
+    assert!(
+        response
+            .headers()
+            .get("location")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .ends_with("/404")
+    );
+
+    let response = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": "https://example.com/flash-sale",
+            "fallback_url": "not a url"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_admin_disabled_link_does_not_redirect_to_fallback_url() {
+    let client = authenticated_client();
+    let response = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": "https://example.com/admin-disabled",
+            "fallback_url": "https://example.com/admin-disabled-fallback"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let link: serde_json::Value = response.json().await.unwrap();
+    let link_id = link["id"].as_str().unwrap();
+    let short_code = link["short_code"].as_str().unwrap();
+
+    let response = client
+        .put(format!("{}/api/admin/links/{}", BASE_URL, link_id))
+        .json(&json!({"status": "disabled"}))
+        .send()
+        .await
+        .unwrap();
+    if response.status() == StatusCode::FORBIDDEN {
+        println!("Test user is not an admin - skipping test");
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // An admin disable sends visitors to the 404 page, also after a re-sync
+    // or after the owner edits the fallback
+    let redirect_client = test_client();
+    for step in ["disable", "resync", "owner_edit"] {
+        match step {
+            "resync" => {
+                let response = client
+                    .post(format!("{}/api/admin/links/{}/sync-kv", BASE_URL, link_id))
+                    .send()
+                    .await
+                    .unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+            }
+            "owner_edit" => {
+                let response = client
+                    .put(format!("{}/api/links/{}", BASE_URL, link_id))
+                    .json(&json!({"fallback_url": "https://example.com/admin-disabled-other"}))
+                    .send()
+                    .await
+                    .unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+            }
+            _ => {}
+        }
+        let response = redirect_client
+            .get(format!("{}/{}", BASE_URL, short_code))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FOUND);
+        let location = response
+            .headers()
+            .get("location")
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert!(
+            location.ends_with("/404"),
+            "Expected admin-disabled link to redirect to /404 after {}, got: {}",
+            step,
+            location
+        );
+    }
+}
@@ -350,3 +350,73 @@ async fn test_list_links_case_insensitive_search() {
     let links = body["data"].as_array().unwrap();
     assert!(!links.is_empty(), "Search should be case-insensitive");
 }
+
+#[tokio::test]
+async fn test_list_links_created_date_range() {
+    let client = authenticated_client();
+
+    let unique_title = format!("DateRange-{}", unique_short_code("dr"));
+    let create_response = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": "https://example.com/date-range",
+            "title": unique_title,
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(create_response.status(), StatusCode::OK);
+    let created_link: serde_json::Value = create_response.json().await.unwrap();
+    let created_at = created_link["created_at"].as_i64().unwrap();
+
+    let count_in_window = |after: Option<i64>, before: Option<i64>| {
+        let client = client.clone();
+        let title = unique_title.clone();
+        async move {
+            let mut url = format!("{}/api/links?search={}", BASE_URL, title);
+            if let Some(after) = after {
+                url.push_str(&format!("&created_after={}", after));
+            }
+            if let Some(before) = before {
+                url.push_str(&format!("&created_before={}", before));
+            }
+            let response = client.get(url).send().await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(
+                body["pagination"]["total"].as_i64().unwrap() as usize,
+                body["data"].as_array().unwrap().len()
+            );
+            body["data"].as_array().unwrap().len()
+        }
+    };
+
+    // Inside the window (after is inclusive, before is exclusive)
+    assert_eq!(
+        count_in_window(Some(created_at), Some(created_at + 1)).await,
+        1
+    );
+    assert_eq!(count_in_window(Some(created_at - 3600), None).await, 1);
+    // Outside the window
+    assert_eq!(count_in_window(Some(created_at + 1), None).await, 0);
+    assert_eq!(count_in_window(None, Some(created_at)).await, 0);
+}
+
+#[tokio::test]
+async fn test_list_links_invalid_date_range() {
+    let client = authenticated_client();
+
+    for query in [
+        "created_after=yesterday",
+        "created_before=-5",
+        "created_after=2000&created_before=1000",
+        "created_after=1000&created_before=1000",
+    ] {
+        let response = client
+            .get(format!("{}/api/links?{}", BASE_URL, query))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "query {query}");
+    }
+}
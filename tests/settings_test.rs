@@ -88,6 +88,13 @@ async fn test_update_setting_signups_enabled() {
     let body: serde_json::Value = response.json().await.unwrap();
     assert_eq!(body["signups_enabled"].as_str().unwrap(), new_value);
 
+    // The public auth config reflects the setting (the instance has users,
+    // so the first-user exception does not apply)
+    assert_eq!(
+        fetch_auth_config_signups_enabled().await,
+        new_value == "true"
+    );
+
     // Restore original value
     let restore_response = client
         .put(format!("{}/api/admin/settings", BASE_URL))
@@ -99,6 +106,41 @@ async fn test_update_setting_signups_enabled() {
     assert_eq!(restore_response.status(), StatusCode::OK);
 }
 
+async fn fetch_auth_config_signups_enabled() -> bool {
+    let response = test_client()
+        .get(format!("{}/api/auth/config", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    body["signups_enabled"].as_bool().unwrap()
+}
+
+#[tokio::test]
+async fn test_public_auth_config_shape() {
+    let response = test_client()
+        .get(format!("{}/api/auth/config", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["signups_enabled"].is_boolean());
+    let providers: Vec<&str> = body["providers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p.as_str().unwrap())
+        .collect();
+    assert!(
+        providers.contains(&"github"),
+        "GitHub should be listed, got: {:?}",
+        providers
+    );
+}
+
 #[tokio::test]
 async fn test_update_setting_invalid_key() {
     let client = authenticated_client();
@@ -378,3 +420,439 @@ async fn test_update_min_random_code_length_validation() {
         .unwrap();
     assert_eq!(res.status(), StatusCode::OK);
 }
+
+/// Maintenance state is cached per isolate and at the edge, so a toggle can
+/// take up to a minute or so to reach the isolate serving the next redirect.
+/// Poll until the redirect answers with `expected` or the window passes.
+async fn wait_for_redirect_status(short_code: &str, expected: StatusCode) -> reqwest::Response {
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(90);
+    loop {
+        let response = test_client()
+            .get(format!("{}/{}", BASE_URL, short_code))
+            .send()
+            .await
+            .unwrap();
+        if response.status() == expected || std::time::Instant::now() >= deadline {
+            return response;
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_maintenance_mode_pauses_redirects() {
+    let client = authenticated_client();
+
+    let short_code = create_link_and_get_code("https://example.com/maintenance").await;
+
+    let res = client
+        .put(format!("{}/api/admin/settings", BASE_URL))
+        .json(&json!({ "key": "maintenance_mode", "value": "true" }))
+        .send()
+        .await
+        .unwrap();
+    if res.status() == StatusCode::FORBIDDEN {
+        println!("Test user is not an admin - skipping test");
+        return;
+    }
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let response = wait_for_redirect_status(&short_code, StatusCode::SERVICE_UNAVAILABLE).await;
+    let status = response.status();
+    let retry_after = response.headers().get("retry-after").cloned();
+    let body = response.text().await.unwrap();
+
+    // Turn maintenance off before asserting so a failure can't leave it on
+    let res = client
+        .put(format!("{}/api/admin/settings", BASE_URL))
+        .json(&json!({ "key": "maintenance_mode", "value": "false" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert!(retry_after.is_some(), "503 must carry Retry-After");
+    assert!(body.contains("maintenance"));
+
+    // Redirects resume once maintenance is off
+    let response = wait_for_redirect_status(&short_code, StatusCode::MOVED_PERMANENTLY).await;
+    assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+    assert_eq!(
+        response.headers().get("location").unwrap(),
+        "https://example.com/maintenance"
+    );
+}
+
+#[tokio::test]
+async fn test_maintenance_mode_rejects_invalid_value() {
+    let client = authenticated_client();
+
+    let res = client
+        .put(format!("{}/api/admin/settings", BASE_URL))
+        .json(&json!({ "key": "maintenance_mode", "value": "yes" }))
+        .send()
+        .await
+        .unwrap();
+    if res.status() == StatusCode::FORBIDDEN {
+        println!("Test user is not an admin - skipping test");
+        return;
+    }
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_allowed_destination_schemes_setting() {
+    let client = authenticated_client();
+
+    let create = |url: &'static str| {
+        let client = client.clone();
+        async move {
+            client
+                .post(format!("{}/api/links", BASE_URL))
+                .json(&json!({ "destination_url": url }))
+                .send()
+                .await
+                .unwrap()
+                .status()
+        }
+    };
+
+    // Default: web URLs only
+    assert_eq!(
+        create("mailto:team@example.com").await,
+        StatusCode::BAD_REQUEST
+    );
+
+    let res = client
+        .put(format!("{}/api/admin/settings", BASE_URL))
+        .json(&json!({ "key": "allowed_destination_schemes", "value": "http,https,mailto" }))
+        .send()
+        .await
+        .unwrap();
+    if res.status() == StatusCode::FORBIDDEN {
+        println!("Test user is not an admin - skipping test");
+        return;
+    }
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let mailto_status = create("mailto:team@example.com?subject=Hello").await;
+    let tel_status = create("tel:+15551234567").await;
+    let https_status = create("https://example.com/schemes").await;
+
+    // Restore the default before asserting so a failure can't leave it widened
+    let res = client
+        .put(format!("{}/api/admin/settings", BASE_URL))
+        .json(&json!({ "key": "allowed_destination_schemes", "value": "http,https" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    assert_eq!(mailto_status, StatusCode::OK);
+    assert_eq!(tel_status, StatusCode::BAD_REQUEST);
+    assert_eq!(https_status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_custom_short_codes_rejected_when_disabled_instance_wide() {
+    let client = authenticated_client();
+
+    let res = client
+        .put(format!("{}/api/admin/settings", BASE_URL))
+        .json(&json!({ "key": "allow_custom_short_codes", "value": "false" }))
+        .send()
+        .await
+        .unwrap();
+    if res.status() == StatusCode::FORBIDDEN {
+        println!("Test user is not an admin - skipping test");
+        return;
+    }
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let custom_status = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": "https://example.com/no-custom-codes",
+            "short_code": unique_short_code("nocustom"),
+        }))
+        .send()
+        .await
+        .unwrap()
+        .status();
+    let random_status = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({ "destination_url": "https://example.com/no-custom-codes" }))
+        .send()
+        .await
+        .unwrap()
+        .status();
+
+    // Restore the default before asserting so a failure can't leave it off
+    let res = client
+        .put(format!("{}/api/admin/settings", BASE_URL))
+        .json(&json!({ "key": "allow_custom_short_codes", "value": "true" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    assert_eq!(custom_status, StatusCode::FORBIDDEN);
+    assert_eq!(random_status, StatusCode::OK);
+
+    let res = client
+        .put(format!("{}/api/admin/settings", BASE_URL))
+        .json(&json!({ "key": "allow_custom_short_codes", "value": "sometimes" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_allowed_destination_schemes_rejects_unsupported() {
+    let client = authenticated_client();
+
+    for value in ["", "https,javascript", "ftp"] {
+        let res = client
+            .put(format!("{}/api/admin/settings", BASE_URL))
+            .json(&json!({ "key": "allowed_destination_schemes", "value": value }))
+            .send()
+            .await
+            .unwrap();
+        if res.status() == StatusCode::FORBIDDEN {
+            println!("Test user is not an admin - skipping test");
+            return;
+        }
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST, "value {value:?}");
+    }
+}
+
+#[tokio::test]
+async fn test_disabled_link_purge_settings_validation() {
+    let client = authenticated_client();
+
+    for (key, value) in [
+        ("disabled_link_purge_days", "-1"),
+        ("disabled_link_purge_days", "thirty"),
+        ("disabled_link_purge_days", "100000"),
+        ("disabled_link_purge_require_zero_clicks", "sometimes"),
+    ] {
+        let response = client
+            .put(format!("{}/api/admin/settings", BASE_URL))
+            .json(&json!({ "key": key, "value": value }))
+            .send()
+            .await
+            .unwrap();
+
+        if response.status() == StatusCode::FORBIDDEN {
+            println!("Test user is not an admin - skipping test");
+            return;
+        }
+
+        assert_eq!(
+            response.status(),
+            StatusCode::BAD_REQUEST,
+            "{}={} should be rejected",
+            key,
+            value
+        );
+    }
+
+    // Purging stays off by default
+    let settings: serde_json::Value = client
+        .get(format!("{}/api/admin/settings", BASE_URL))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(settings["disabled_link_purge_days"], "0");
+    assert_eq!(settings["disabled_link_purge_require_zero_clicks"], "true");
+}
+
+#[tokio::test]
+async fn test_api_key_inactive_revoke_days_validation() {
+    let client = authenticated_client();
+
+    for value in ["-1", "ninety", "100000"] {
+        let response = client
+            .put(format!("{}/api/admin/settings", BASE_URL))
+            .json(&json!({ "key": "api_key_inactive_revoke_days", "value": value }))
+            .send()
+            .await
+            .unwrap();
+
+        if response.status() == StatusCode::FORBIDDEN {
+            println!("Test user is not an admin - skipping test");
+            return;
+        }
+
+        assert_eq!(
+            response.status(),
+            StatusCode::BAD_REQUEST,
+            "api_key_inactive_revoke_days={} should be rejected",
+            value
+        );
+    }
+
+    // Revocation stays off by default
+    let settings: serde_json::Value = client
+        .get(format!("{}/api/admin/settings", BASE_URL))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(settings["api_key_inactive_revoke_days"], "0");
+}
+
+#[tokio::test]
+async fn test_click_dedup_window_validation() {
+    let client = authenticated_client();
+
+    for value in ["-1", "3601", "soon"] {
+        let response = client
+            .put(format!("{}/api/admin/settings", BASE_URL))
+            .json(&json!({ "key": "click_dedup_window_seconds", "value": value }))
+            .send()
+            .await
+            .unwrap();
+
+        if response.status() == StatusCode::FORBIDDEN {
+            println!("Test user is not an admin - skipping test");
+            return;
+        }
+
+        assert_eq!(
+            response.status(),
+            StatusCode::BAD_REQUEST,
+            "click_dedup_window_seconds={} should be rejected",
+            value
+        );
+    }
+
+    // Deduplication stays off by default: every click is counted
+    let settings: serde_json::Value = client
+        .get(format!("{}/api/admin/settings", BASE_URL))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(settings["click_dedup_window_seconds"], "0");
+}
+
+#[tokio::test]
+async fn test_invitation_resend_interval_validation() {
+    let client = authenticated_client();
+
+    for value in ["-1", "86401", "later"] {
+        let response = client
+            .put(format!("{}/api/admin/settings", BASE_URL))
+            .json(&json!({ "key": "invitation_resend_interval_seconds", "value": value }))
+            .send()
+            .await
+            .unwrap();
+
+        if response.status() == StatusCode::FORBIDDEN {
+            println!("Test user is not an admin - skipping test");
+            return;
+        }
+
+        assert_eq!(
+            response.status(),
+            StatusCode::BAD_REQUEST,
+            "invitation_resend_interval_seconds={} should be rejected",
+            value
+        );
+    }
+
+    let settings: serde_json::Value = client
+        .get(format!("{}/api/admin/settings", BASE_URL))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(settings["invitation_resend_interval_seconds"], "300");
+}
+
+#[tokio::test]
+async fn test_short_code_preview_matches_alphabet_and_length() {
+    let client = authenticated_client();
+
+    let response = client
+        .get(format!(
+            "{}/api/admin/short-code/preview?alphabet=base58&length=12",
+            BASE_URL
+        ))
+        .send()
+        .await
+        .unwrap();
+    if response.status() == StatusCode::FORBIDDEN {
+        println!("Test user is not an admin - skipping test");
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["alphabet"], "base58");
+    assert_eq!(body["length"], 12);
+    let samples = body["samples"].as_array().unwrap();
+    assert!(!samples.is_empty());
+    for sample in samples {
+        let code = sample.as_str().unwrap();
+        assert_eq!(code.len(), 12);
+        assert!(
+            code.chars()
+                .all(|c| c.is_ascii_alphanumeric() && !"0OIl".contains(c)),
+            "{} is not base58",
+            code
+        );
+    }
+
+    // Defaults: base62 at the configured random code length
+    let body: serde_json::Value = client
+        .get(format!("{}/api/admin/short-code/preview", BASE_URL))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["alphabet"], "base62");
+    assert_eq!(body["characters"].as_str().unwrap().len(), 62);
+
+    for query in [
+        "alphabet=hex",
+        "length=0",
+        &format!("length={}", MAX_SHORT_CODE_LENGTH + 1),
+        "length=abc",
+    ] {
+        let response = client
+            .get(format!(
+                "{}/api/admin/short-code/preview?{}",
+                BASE_URL, query
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(
+            response.status(),
+            StatusCode::BAD_REQUEST,
+            "{} should be rejected",
+            query
+        );
+    }
+
+    let response = test_client()
+        .get(format!("{}/api/admin/short-code/preview", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
@@ -1180,3 +1180,311 @@ async fn test_pro_tier_can_create_307_redirect() {
         "Failed to reset billing account to unlimited tier"
     );
 }
+
+#[tokio::test]
+async fn test_monthly_limit_error_is_machine_readable() {
+    use chrono::Datelike;
+
+    let client = authenticated_client();
+
+    // Find the billing account of the user's primary org
+    let orgs: serde_json::Value = client
+        .get(format!("{}/api/orgs", BASE_URL))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let Some(billing_account_id) = orgs["orgs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|o| o["role"].as_str() == Some("owner"))
+        .and_then(|o| o["billing_account_id"].as_str())
+        .map(|id| id.to_string())
+    else {
+        println!("Primary org has no billing account - skipping test");
+        return;
+    };
+
+    let tier_response = client
+        .put(format!(
+            "{}/api/admin/billing-accounts/{}/tier",
+            BASE_URL, billing_account_id
+        ))
+        .json(&json!({"tier": "free"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(tier_response.status(), 200);
+
+    let reset_response = client
+        .post(format!(
+            "{}/api/admin/billing-accounts/{}/reset-counter",
+            BASE_URL, billing_account_id
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(reset_response.status(), 200);
+
+    // Use up the free tier's 15 links, then try one more
+    let mut created_links = Vec::new();
+    let mut limit_response = None;
+    for i in 0..16 {
+        let response = client
+            .post(format!("{}/api/links", BASE_URL))
+            .json(&json!({
+                "destination_url": format!("https://example.com/monthly-limit-{}", i),
+            }))
+            .send()
+            .await
+            .unwrap();
+        if response.status() == StatusCode::FORBIDDEN {
+            limit_response = Some(response);
+            break;
+        }
+        assert_eq!(response.status(), StatusCode::OK);
+        let link: serde_json::Value = response.json().await.unwrap();
+        created_links.push(link["id"].as_str().unwrap().to_string());
+    }
+    let body: Option<serde_json::Value> = match limit_response {
+        Some(response) => Some(response.json().await.unwrap()),
+        None => None,
+    };
+
+    // Restore state before asserting so a failure can't leave the account on free
+    for link_id in &created_links {
+        let _ = client
+            .delete(format!("{}/api/links/{}", BASE_URL, link_id))
+            .send()
+            .await;
+    }
+    let _ = client
+        .post(format!(
+            "{}/api/admin/billing-accounts/{}/reset-counter",
+            BASE_URL, billing_account_id
+        ))
+        .send()
+        .await;
+    let _ = client
+        .put(format!(
+            "{}/api/admin/billing-accounts/{}/tier",
+            BASE_URL, billing_account_id
+        ))
+        .json(&json!({"tier": "unlimited"}))
+        .send()
+        .await;
+
+    let body = body.expect("Free tier should reject the 16th link of the month");
+    assert_eq!(created_links.len(), 15);
+    assert_eq!(body["code"], "monthly_limit_reached");
+    assert_eq!(body["limit"], 15);
+    assert_eq!(body["used"], 15);
+    assert!(body["message"].is_string());
+
+    let now = chrono::Utc::now();
+    let (year, month) = if now.month() == 12 {
+        (now.year() + 1, 1)
+    } else {
+        (now.year(), now.month() + 1)
+    };
+    let expected_reset = chrono::NaiveDate::from_ymd_opt(year, month, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc()
+        .timestamp();
+    assert_eq!(body["resets_at"].as_i64(), Some(expected_reset));
+}
+
+#[tokio::test]
+async fn test_custom_domain_limit_enforced_per_tier() {
+    let client = authenticated_client();
+    let org_id = get_primary_test_org_id().await;
+
+    let orgs: serde_json::Value = client
+        .get(format!("{}/api/orgs", BASE_URL))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let Some(billing_account_id) = orgs["orgs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|o| o["id"].as_str() == Some(org_id.as_str()))
+        .and_then(|o| o["billing_account_id"].as_str())
+        .map(|id| id.to_string())
+    else {
+        println!("Primary org has no billing account - skipping test");
+        return;
+    };
+
+    let set_tier = |tier: &'static str| {
+        let client = client.clone();
+        let url = format!(
+            "{}/api/admin/billing-accounts/{}/tier",
+            BASE_URL, billing_account_id
+        );
+        async move {
+            client
+                .put(url)
+                .json(&json!({ "tier": tier }))
+                .send()
+                .await
+                .unwrap()
+                .status()
+        }
+    };
+    let add_domain = |hostname: String| {
+        let client = client.clone();
+        let url = format!("{}/api/orgs/{}/domains", BASE_URL, org_id);
+        async move {
+            client
+                .post(url)
+                .json(&json!({ "hostname": hostname }))
+                .send()
+                .await
+                .unwrap()
+                .status()
+        }
+    };
+    let suffix = unique_short_code("dl");
+
+    // Free tier allows no custom domains at all
+    assert_eq!(set_tier("free").await, StatusCode::OK);
+    let free_hostname = format!("{}-free.example.com", suffix);
+    let free_status = add_domain(free_hostname.clone()).await;
+
+    // Pro allows one: keep adding until the limit kicks in
+    assert_eq!(set_tier("pro").await, StatusCode::OK);
+    let mut added = Vec::new();
+    let mut pro_rejected = None;
+    for i in 0..2 {
+        let hostname = format!("{}-pro{}.example.com", suffix, i);
+        let status = add_domain(hostname.clone()).await;
+        if status == StatusCode::OK {
+            added.push(hostname);
+        } else {
+            pro_rejected = Some(status);
+            break;
+        }
+    }
+    let usage: serde_json::Value = client
+        .get(format!("{}/api/usage", BASE_URL))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // Restore state before asserting
+    for hostname in added.iter().chain(std::iter::once(&free_hostname)) {
+        let _ = client
+            .delete(format!(
+                "{}/api/orgs/{}/domains/{}",
+                BASE_URL, org_id, hostname
+            ))
+            .send()
+            .await;
+    }
+    let _ = set_tier("unlimited").await;
+
+    assert_eq!(
+        free_status,
+        StatusCode::FORBIDDEN,
+        "Free tier should not be able to add a custom domain"
+    );
+    assert_eq!(
+        pro_rejected,
+        Some(StatusCode::FORBIDDEN),
+        "Pro tier should reject domains beyond its limit"
+    );
+    assert!(added.len() <= 1);
+    assert_eq!(usage["limits"]["max_custom_domains"], 1);
+    assert_eq!(usage["usage"]["custom_domains_count"], 1);
+}
+
+#[tokio::test]
+async fn test_org_link_quota_blocks_creation_before_billing_limit() {
+    let client = authenticated_client();
+    let original_org_id = get_primary_test_org_id().await;
+
+    let response = client
+        .post(format!("{}/api/orgs", BASE_URL))
+        .json(&json!({ "name": format!("Quota Org {}", unique_short_code("oq")) }))
+        .send()
+        .await
+        .unwrap();
+    if response.status() == StatusCode::FORBIDDEN {
+        println!("Org limit reached for current tier - skipping test");
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let quota_org_id = response.json::<serde_json::Value>().await.unwrap()["org"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let settings_url = format!("{}/api/orgs/{}/settings", BASE_URL, quota_org_id);
+
+    let response = client
+        .patch(&settings_url)
+        .json(&json!({ "org_link_quota": -1 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = client
+        .patch(&settings_url)
+        .json(&json!({ "org_link_quota": 1 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let settings: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(settings["org_link_quota"], 1);
+
+    let switch_to = |org_id: String| {
+        let client = client.clone();
+        async move {
+            let response = client
+                .post(format!("{}/api/auth/switch-org", BASE_URL))
+                .json(&json!({ "org_id": org_id }))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+    };
+    switch_to(quota_org_id.clone()).await;
+
+    let first = create_test_link("https://example.com/org-quota-1", None).await;
+    let first_status = first.status();
+    let first_id = first.json::<serde_json::Value>().await.unwrap()["id"]
+        .as_str()
+        .map(String::from);
+    let second = create_test_link("https://example.com/org-quota-2", None).await;
+    let second_status = second.status();
+    let second_body: serde_json::Value = second.json().await.unwrap();
+
+    if let Some(id) = first_id {
+        let _ = client
+            .delete(format!("{}/api/links/{}", BASE_URL, id))
+            .send()
+            .await;
+    }
+    switch_to(original_org_id).await;
+
+    assert_eq!(first_status, StatusCode::OK);
+    assert_eq!(second_status, StatusCode::FORBIDDEN);
+    assert_eq!(second_body["code"], "monthly_limit_reached");
+    assert_eq!(second_body["scope"], "organization");
+    assert_eq!(second_body["limit"], 1);
+    assert_eq!(second_body["used"], 1);
+}
@@ -325,3 +325,131 @@ async fn test_accept_valid_alphanumeric_code() {
 
     assert_eq!(response.status(), StatusCode::OK);
 }
+
+// ─── Request body field types ────────────────────────────────────────────────
+
+#[tokio::test]
+async fn test_wrong_type_expires_at_names_field() {
+    let client = authenticated_client();
+
+    let response = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": "https://example.com",
+            "expires_at": "next week"
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response.text().await.unwrap();
+    assert!(
+        body.contains("Field 'expires_at' must be an integer, got a string"),
+        "Unexpected error message: {}",
+        body
+    );
+}
+
+#[tokio::test]
+async fn test_non_string_destination_url_names_field() {
+    let client = authenticated_client();
+
+    let response = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": ["https://example.com"]
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response.text().await.unwrap();
+    assert!(
+        body.contains("Field 'destination_url' must be a string, got an array"),
+        "Unexpected error message: {}",
+        body
+    );
+}
+
+#[tokio::test]
+async fn test_missing_destination_url_names_field() {
+    let client = authenticated_client();
+
+    let response = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({ "title": "No destination" }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response.text().await.unwrap();
+    assert!(
+        body.contains("Missing required field 'destination_url'"),
+        "Unexpected error message: {}",
+        body
+    );
+}
+
+#[tokio::test]
+async fn test_unknown_field_still_rejected() {
+    let client = authenticated_client();
+
+    let response = client
+        .post(format!("{}/api/links", BASE_URL))
+        .json(&json!({
+            "destination_url": "https://example.com",
+            "destination": "https://example.com"
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response.text().await.unwrap();
+    assert!(body.contains("Unknown field 'destination'"));
+}
+
+#[tokio::test]
+async fn test_unsupported_method_returns_405_with_allow() {
+    let client = authenticated_client();
+
+    let response = client
+        .patch(format!("{}/api/links", BASE_URL))
+        .json(&json!({}))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    let allow = response
+        .headers()
+        .get("allow")
+        .expect("405 should carry an Allow header")
+        .to_str()
+        .unwrap()
+        .to_string();
+    let methods: Vec<&str> = allow.split(", ").collect();
+    assert!(methods.contains(&"GET"), "Allow: {}", allow);
+    assert!(methods.contains(&"POST"), "Allow: {}", allow);
+    assert!(!methods.contains(&"PATCH"), "Allow: {}", allow);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["message"].as_str().unwrap().contains("GET"));
+}
+
+#[tokio::test]
+async fn test_unknown_api_path_still_returns_404() {
+    let client = authenticated_client();
+
+    let response = client
+        .patch(format!("{}/api/no-such-endpoint", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(response.headers().get("allow").is_none());
+}